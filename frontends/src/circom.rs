@@ -0,0 +1,464 @@
+use crate::r1cs::R1CS;
+use ark_ff::{BigInteger, PrimeField};
+use std::fmt;
+
+//=========================================================================================
+// Importer for circom's binary outputs, the .r1cs a circuit compiles to and the .wtns its
+// witness generator writes, so a circom circuit can be folded by nova like any other R1CS
+// both files are "magic, u32 version, u32 section count" then sections of (u32 type, u64 size),
+// every integer little endian and every field element n8 little endian bytes of its value
+// .r1cs sections
+//   1  header: n8, prime, nWires, nPubOut, nPubIn, nPrvIn, nLabels (u64), nConstraints
+//   2  constraints: A, B and C of each one as a u32 count of (u32 wire, value) terms
+//   3  wire to label map, only for debugging and skipped here
+//   4, 5  plonk custom gates, refused since they have no R1CS form
+// .wtns sections
+//   1  header: n8, prime, nWitness
+//   2  the nWitness values
+// circom numbers the wires 1, public outputs, public inputs, private inputs then the internal
+// signals, which is already z = (u, x, w) with the outputs and inputs together as x
+// the sections may come in any order, everything is checked before it is allocated so a bad
+// file is an error and never a panic or a huge allocation
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircomError {
+    BadMagic,
+    UnsupportedVersion(u32),
+    Truncated,
+    MissingSection(u32),
+    DuplicateSection(u32),
+    CustomGates,
+    WrongField,   // the file is over another prime than F
+    NonCanonical, // a value at or above the modulus
+    BadHeader,    // the wire counts don't add up
+    WireOutOfRange { wire: u32, num_wires: u32 },
+    WrongLength { expected: usize, found: usize },
+    ConstantNotOne, // the first witness value is the constant wire
+}
+
+impl fmt::Display for CircomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircomError::BadMagic => write!(f, "not a circom file of this kind"),
+            CircomError::UnsupportedVersion(version) => {
+                write!(f, "unsupported file version {}", version)
+            }
+            CircomError::Truncated => write!(f, "the file ends in the middle of a section"),
+            CircomError::MissingSection(section) => write!(f, "section {} is missing", section),
+            CircomError::DuplicateSection(section) => {
+                write!(f, "section {} appears more than once", section)
+            }
+            CircomError::CustomGates => write!(f, "custom gates have no R1CS form"),
+            CircomError::WrongField => write!(f, "the file is over another field"),
+            CircomError::NonCanonical => {
+                write!(f, "not the canonical encoding of a field element")
+            }
+            CircomError::BadHeader => write!(f, "the header's wire counts are inconsistent"),
+            CircomError::WireOutOfRange { wire, num_wires } => write!(
+                f,
+                "a constraint refers to wire {} but there are {}",
+                wire, num_wires
+            ),
+            CircomError::WrongLength { expected, found } => write!(
+                f,
+                "expected {} witness values but found {}",
+                expected, found
+            ),
+            CircomError::ConstantNotOne => write!(f, "the constant wire is not 1"),
+        }
+    }
+}
+
+impl std::error::Error for CircomError {}
+
+pub fn read_r1cs<F: PrimeField>(bytes: &[u8]) -> Result<R1CS<F>, CircomError> {
+    let sections = read_sections(bytes, b"r1cs")?;
+    if sections.iter().any(|(kind, _)| *kind == 4 || *kind == 5) {
+        return Err(CircomError::CustomGates);
+    }
+
+    let mut header = Reader::new(section(&sections, 1)?);
+    let n8 = read_prime::<F>(&mut header)?;
+    let num_wires = header.u32()?;
+    let num_pub_out = header.u32()?;
+    let num_pub_in = header.u32()?;
+    let num_prv_in = header.u32()?;
+    let _num_labels = header.u64()?;
+    let num_constraints = header.u32()? as usize;
+
+    let num_public = num_pub_out as usize + num_pub_in as usize;
+    if num_wires == 0 || 1 + num_public + num_prv_in as usize > num_wires as usize {
+        return Err(CircomError::BadHeader);
+    }
+
+    // a constraint is at least three empty term counts
+    let mut constraints = Reader::new(section(&sections, 2)?);
+    if num_constraints > constraints.remaining() / 12 {
+        return Err(CircomError::Truncated);
+    }
+    let mut a = Vec::with_capacity(num_constraints);
+    let mut b = Vec::with_capacity(num_constraints);
+    let mut c = Vec::with_capacity(num_constraints);
+    for _ in 0..num_constraints {
+        a.push(read_row(&mut constraints, n8, num_wires)?);
+        b.push(read_row(&mut constraints, n8, num_wires)?);
+        c.push(read_row(&mut constraints, n8, num_wires)?);
+    }
+
+    Ok(R1CS::new(
+        a,
+        b,
+        c,
+        num_public,
+        num_wires as usize - 1 - num_public,
+    ))
+}
+
+// every wire value, the constant 1 first
+pub fn read_witness<F: PrimeField>(bytes: &[u8]) -> Result<Vec<F>, CircomError> {
+    let sections = read_sections(bytes, b"wtns")?;
+
+    let mut header = Reader::new(section(&sections, 1)?);
+    let n8 = read_prime::<F>(&mut header)?;
+    let num_witness = header.u32()? as usize;
+
+    let mut values = Reader::new(section(&sections, 2)?);
+    if num_witness > values.remaining() / n8 {
+        return Err(CircomError::Truncated);
+    }
+
+    (0..num_witness).map(|_| values.field(n8)).collect()
+}
+
+// (x, w) for R1CS::is_satisfied and nova out of a witness from read_witness
+pub fn split_witness<F: PrimeField>(
+    r1cs: &R1CS<F>,
+    witness: &[F],
+) -> Result<(Vec<F>, Vec<F>), CircomError> {
+    let expected = 1 + r1cs.num_public + r1cs.num_witness;
+    if witness.len() != expected {
+        return Err(CircomError::WrongLength {
+            expected,
+            found: witness.len(),
+        });
+    }
+    if witness[0] != F::one() {
+        return Err(CircomError::ConstantNotOne);
+    }
+
+    let (x, w) = witness[1..].split_at(r1cs.num_public);
+
+    Ok((x.to_vec(), w.to_vec()))
+}
+
+// (type, contents) of every section, after checking the magic and version
+fn read_sections<'a>(
+    bytes: &'a [u8],
+    magic: &[u8; 4],
+) -> Result<Vec<(u32, &'a [u8])>, CircomError> {
+    let mut reader = Reader::new(bytes);
+    if reader.take(4).map_err(|_| CircomError::BadMagic)? != magic {
+        return Err(CircomError::BadMagic);
+    }
+    let version = reader.u32()?;
+    if version == 0 || version > 2 {
+        return Err(CircomError::UnsupportedVersion(version));
+    }
+
+    let num_sections = reader.u32()?;
+    let mut sections: Vec<(u32, &[u8])> = Vec::new();
+    for _ in 0..num_sections {
+        let kind = reader.u32()?;
+        let size = usize::try_from(reader.u64()?).map_err(|_| CircomError::Truncated)?;
+        if sections.iter().any(|(seen, _)| *seen == kind) {
+            return Err(CircomError::DuplicateSection(kind));
+        }
+        sections.push((kind, reader.take(size)?));
+    }
+
+    Ok(sections)
+}
+
+fn section<'a>(sections: &[(u32, &'a [u8])], kind: u32) -> Result<&'a [u8], CircomError> {
+    match sections.iter().find(|(seen, _)| *seen == kind) {
+        Some((_, contents)) => Ok(contents),
+        None => Err(CircomError::MissingSection(kind)),
+    }
+}
+
+// n8 then the prime, which has to be F's modulus in the same number of bytes
+fn read_prime<F: PrimeField>(header: &mut Reader) -> Result<usize, CircomError> {
+    let n8 = header.u32()? as usize;
+    let modulus = F::MODULUS.to_bytes_le();
+    if n8 != modulus.len() || header.take(n8)? != modulus.as_slice() {
+        return Err(CircomError::WrongField);
+    }
+
+    Ok(n8)
+}
+
+fn read_row<F: PrimeField>(
+    reader: &mut Reader,
+    n8: usize,
+    num_wires: u32,
+) -> Result<Vec<(usize, F)>, CircomError> {
+    let num_terms = reader.u32()? as usize;
+    if num_terms > reader.remaining() / (4 + n8) {
+        return Err(CircomError::Truncated);
+    }
+
+    let mut row = Vec::with_capacity(num_terms);
+    for _ in 0..num_terms {
+        let wire = reader.u32()?;
+        if wire >= num_wires {
+            return Err(CircomError::WireOutOfRange { wire, num_wires });
+        }
+        row.push((wire as usize, reader.field(n8)?));
+    }
+
+    Ok(row)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], CircomError> {
+        if len > self.bytes.len() {
+            return Err(CircomError::Truncated);
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, CircomError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, CircomError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn field<F: PrimeField>(&mut self, n8: usize) -> Result<F, CircomError> {
+        let bytes = self.take(n8)?;
+        let value = F::from_le_bytes_mod_order(bytes);
+        // a reduced value encodes back to other bytes
+        if value.into_bigint().to_bytes_le() != bytes {
+            return Err(CircomError::NonCanonical);
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fr;
+
+    fn field_bytes(value: &Fr) -> Vec<u8> {
+        value.into_bigint().to_bytes_le()
+    }
+
+    fn file(magic: &[u8; 4], sections: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let mut bytes = magic.to_vec();
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend((sections.len() as u32).to_le_bytes());
+        for (kind, contents) in sections {
+            bytes.extend(kind.to_le_bytes());
+            bytes.extend((contents.len() as u64).to_le_bytes());
+            bytes.extend(contents);
+        }
+
+        bytes
+    }
+
+    fn prime() -> Vec<u8> {
+        let modulus = Fr::MODULUS.to_bytes_le();
+        let mut bytes = (modulus.len() as u32).to_le_bytes().to_vec();
+        bytes.extend(modulus);
+
+        bytes
+    }
+
+    // the sections circom would write for r1cs, with all the public inputs counted as outputs
+    fn r1cs_sections(r1cs: &R1CS<Fr>) -> Vec<(u32, Vec<u8>)> {
+        let num_wires = (1 + r1cs.num_public + r1cs.num_witness) as u32;
+
+        let mut header = prime();
+        for count in [num_wires, r1cs.num_public as u32, 0, 0] {
+            header.extend(count.to_le_bytes());
+        }
+        header.extend((num_wires as u64).to_le_bytes());
+        header.extend((r1cs.num_constraints() as u32).to_le_bytes());
+
+        let mut constraints = Vec::new();
+        for i in 0..r1cs.num_constraints() {
+            for row in [&r1cs.a[i], &r1cs.b[i], &r1cs.c[i]] {
+                constraints.extend((row.len() as u32).to_le_bytes());
+                for (wire, value) in row {
+                    constraints.extend((*wire as u32).to_le_bytes());
+                    constraints.extend(field_bytes(value));
+                }
+            }
+        }
+
+        let labels = (0..num_wires as u64).flat_map(u64::to_le_bytes).collect();
+
+        vec![(1, header), (2, constraints), (3, labels)]
+    }
+
+    fn wtns(witness: &[Fr]) -> Vec<u8> {
+        let mut header = prime();
+        header.extend((witness.len() as u32).to_le_bytes());
+
+        file(
+            b"wtns",
+            &[
+                (1, header),
+                (2, witness.iter().flat_map(field_bytes).collect()),
+            ],
+        )
+    }
+
+    // x³ + x + 5 = y with z = (1, y, x, x², x³)
+    fn cubic_r1cs() -> R1CS<Fr> {
+        let one = Fr::from(1);
+
+        R1CS::new(
+            vec![
+                vec![(2, one)],
+                vec![(3, one)],
+                vec![(4, one), (2, one), (0, Fr::from(5))],
+            ],
+            vec![vec![(2, one)], vec![(2, one)], vec![(0, one)]],
+            vec![vec![(3, one)], vec![(4, one)], vec![(1, one)]],
+            1,
+            3,
+        )
+    }
+
+    fn cubic_witness(x: u64) -> Vec<Fr> {
+        let x = Fr::from(x);
+
+        vec![
+            Fr::from(1),
+            x * x * x + x + Fr::from(5),
+            x,
+            x * x,
+            x * x * x,
+        ]
+    }
+
+    #[test]
+    fn test_circom_files_import() {
+        let r1cs = cubic_r1cs();
+        let imported = read_r1cs::<Fr>(&file(b"r1cs", &r1cs_sections(&r1cs))).unwrap();
+        assert_eq!(imported, r1cs);
+
+        let witness = read_witness::<Fr>(&wtns(&cubic_witness(3))).unwrap();
+        let (x, w) = split_witness(&imported, &witness).unwrap();
+        assert_eq!(x, vec![Fr::from(35)]);
+        assert!(imported.is_satisfied(&x, &w));
+
+        // the sections can come in any order
+        let mut sections = r1cs_sections(&r1cs);
+        sections.reverse();
+        assert_eq!(read_r1cs::<Fr>(&file(b"r1cs", &sections)), Ok(r1cs));
+    }
+
+    #[test]
+    fn test_circom_import_rejects_bad_files() {
+        let r1cs = cubic_r1cs();
+        let bytes = file(b"r1cs", &r1cs_sections(&r1cs));
+
+        assert_eq!(
+            read_r1cs::<Fr>(&wtns(&cubic_witness(3))),
+            Err(CircomError::BadMagic)
+        );
+        assert_eq!(
+            read_r1cs::<Fr>(&bytes[..bytes.len() - 1]),
+            Err(CircomError::Truncated)
+        );
+
+        // a prime off by one is another field
+        let mut sections = r1cs_sections(&r1cs);
+        sections[0].1[4] ^= 1;
+        assert_eq!(
+            read_r1cs::<Fr>(&file(b"r1cs", &sections)),
+            Err(CircomError::WrongField)
+        );
+
+        let mut sections = r1cs_sections(&r1cs);
+        sections.remove(1);
+        assert_eq!(
+            read_r1cs::<Fr>(&file(b"r1cs", &sections)),
+            Err(CircomError::MissingSection(2))
+        );
+
+        let mut sections = r1cs_sections(&r1cs);
+        sections.push((4, Vec::new()));
+        assert_eq!(
+            read_r1cs::<Fr>(&file(b"r1cs", &sections)),
+            Err(CircomError::CustomGates)
+        );
+
+        // the first term of A is wire 2, point it past the five wires
+        let mut sections = r1cs_sections(&r1cs);
+        sections[1].1[4..8].copy_from_slice(&5u32.to_le_bytes());
+        assert_eq!(
+            read_r1cs::<Fr>(&file(b"r1cs", &sections)),
+            Err(CircomError::WireOutOfRange {
+                wire: 5,
+                num_wires: 5
+            })
+        );
+
+        // and its value to the modulus, which would reduce to 0
+        let mut sections = r1cs_sections(&r1cs);
+        sections[1].1[8..40].copy_from_slice(&Fr::MODULUS.to_bytes_le());
+        assert_eq!(
+            read_r1cs::<Fr>(&file(b"r1cs", &sections)),
+            Err(CircomError::NonCanonical)
+        );
+
+        // a constraint count no section could hold is refused before allocating
+        let mut sections = r1cs_sections(&r1cs);
+        let at = sections[0].1.len() - 4;
+        sections[0].1[at..].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            read_r1cs::<Fr>(&file(b"r1cs", &sections)),
+            Err(CircomError::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_circom_witness_checks() {
+        let r1cs = cubic_r1cs();
+        let mut witness = cubic_witness(3);
+
+        assert_eq!(
+            split_witness(&r1cs, &witness[1..]),
+            Err(CircomError::WrongLength {
+                expected: 5,
+                found: 4
+            })
+        );
+        witness[0] = Fr::from(2);
+        assert_eq!(
+            split_witness(&r1cs, &witness),
+            Err(CircomError::ConstantNotOne)
+        );
+    }
+}
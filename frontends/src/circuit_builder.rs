@@ -0,0 +1,420 @@
+use ark_ff::PrimeField;
+use std::collections::HashMap;
+use zk_protocols::gkr::{
+    circuit_limits::{CircuitLimits, LimitError},
+    gkr_circuit::{Circuit, Gate, GateOp, Layer, WireId},
+    padded_layer::PaddedLayer,
+};
+
+pub use zk_protocols::gkr::gkr_circuit::Wire;
+
+//=========================================================================================
+// CircuitBuilder lets gadgets be written as straight-line code over wires
+// e.g. let t = builder.add(x, k); let t3 = builder.mul(builder.mul(t, t), t);
+// a gate is placed one layer above its deepest input and any shallower input is carried up
+// with copy gates (w + 0), so every layer keeps the constant zero wire at index 0
+// values are tracked as gates are added so gadgets can compute advice from earlier wires
+// build pads every layer of gates to a power of 2 with zero gates (0 + 0), the provers read a
+// layer as an MLE and Circuit::layer_bits addresses the padded width of the layer below
+//=========================================================================================
+pub struct CircuitBuilder<F: PrimeField> {
+    gate_layers: Vec<Vec<Gate>>, // gate_layers[i] builds layer i + 1
+    values: Vec<Vec<F>>,         // values[0] are the circuit inputs
+    copies: HashMap<(Wire, usize), Wire>,
+    constants: HashMap<F, Wire>,
+    input_names: Vec<(String, usize)>,
+}
+
+impl<F: PrimeField> Default for CircuitBuilder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: PrimeField> CircuitBuilder<F> {
+    pub fn new() -> Self {
+        CircuitBuilder {
+            gate_layers: Vec::new(),
+            values: vec![vec![F::zero()]], // the zero wire
+            copies: HashMap::new(),
+            constants: HashMap::new(),
+            input_names: Vec::new(),
+        }
+    }
+
+    pub fn zero(&self) -> Wire {
+        Wire { layer: 0, index: 0 }
+    }
+
+    // public input or prover supplied advice, both live in the input layer
+    pub fn input(&mut self, value: F) -> Wire {
+        self.values[0].push(value);
+
+        Wire {
+            layer: 0,
+            index: self.values[0].len() - 1,
+        }
+    }
+
+    // an input the built circuit can assign by name, see Circuit::assign
+    pub fn named_input(&mut self, name: &str, value: F) -> Wire {
+        let wire = self.input(value);
+        self.input_names.push((name.to_string(), wire.index));
+
+        wire
+    }
+
+    pub fn constant(&mut self, value: F) -> Wire {
+        if value.is_zero() {
+            return self.zero();
+        }
+        if let Some(wire) = self.constants.get(&value) {
+            return *wire;
+        }
+
+        let wire = self.input(value);
+        self.constants.insert(value, wire);
+
+        wire
+    }
+
+    pub fn value(&self, wire: Wire) -> F {
+        self.values[wire.layer][wire.index]
+    }
+
+    pub fn depth(&self) -> usize {
+        self.gate_layers.len()
+    }
+
+    pub fn add(&mut self, left: Wire, right: Wire) -> Wire {
+        self.gate(GateOp::Add, left, right)
+    }
+
+    pub fn mul(&mut self, left: Wire, right: Wire) -> Wire {
+        self.gate(GateOp::Mul, left, right)
+    }
+
+    // a single wire squared, the right wire of the gate is unused
+    pub fn square(&mut self, wire: Wire) -> Wire {
+        self.gate(GateOp::Square, wire, wire)
+    }
+
+    pub fn sub(&mut self, left: Wire, right: Wire) -> Wire {
+        let minus_one = self.constant(-F::one());
+        let neg_right = self.mul(right, minus_one);
+
+        self.add(left, neg_right)
+    }
+
+    pub fn add_constant(&mut self, wire: Wire, value: F) -> Wire {
+        let constant = self.constant(value);
+        self.add(wire, constant)
+    }
+
+    pub fn mul_constant(&mut self, wire: Wire, value: F) -> Wire {
+        let constant = self.constant(value);
+        self.mul(wire, constant)
+    }
+
+    // sums the wires pairwise so the depth only grows by log2(len)
+    pub fn sum(&mut self, wires: &[Wire]) -> Wire {
+        if wires.is_empty() {
+            return self.zero();
+        }
+
+        let mut current = wires.to_vec();
+        while current.len() > 1 {
+            current = current
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => self.add(*left, *right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+
+        current[0]
+    }
+
+    // wire^exponent by square and multiply, exponent is expected to be small (S-box degrees)
+    pub fn pow(&mut self, wire: Wire, exponent: u64) -> Wire {
+        if exponent == 0 {
+            return self.constant(F::one());
+        }
+
+        let mut result: Option<Wire> = None;
+        let mut base = wire;
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = Some(match result {
+                    Some(acc) => self.mul(acc, base),
+                    None => base,
+                });
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = self.square(base);
+            }
+        }
+
+        result.unwrap()
+    }
+
+    fn gate(&mut self, op: GateOp, left: Wire, right: Wire) -> Wire {
+        let layer = left.layer.max(right.layer) + 1;
+        let left = self.lift(left, layer - 1);
+        let right = self.lift(right, layer - 1);
+
+        let value = match op {
+            GateOp::Add => self.value(left) + self.value(right),
+            GateOp::Mul => self.value(left) * self.value(right),
+            GateOp::Square => self.value(left) * self.value(left),
+        };
+
+        self.push_gate(layer, op, left.index, right.index, value)
+    }
+
+    // carries a wire up to the given layer with copy gates, reusing copies already made
+    fn lift(&mut self, wire: Wire, layer: usize) -> Wire {
+        if wire.layer >= layer {
+            return wire;
+        }
+        if let Some(copy) = self.copies.get(&(wire, layer)) {
+            return *copy;
+        }
+
+        let below = self.lift(wire, layer - 1);
+        let value = self.value(below);
+        let copy = self.push_gate(layer, GateOp::Add, below.index, 0, value);
+        self.copies.insert((wire, layer), copy);
+
+        copy
+    }
+
+    fn push_gate(&mut self, layer: usize, op: GateOp, left: usize, right: usize, value: F) -> Wire {
+        self.ensure_layer(layer);
+
+        let gates = &mut self.gate_layers[layer - 1];
+        let output = gates.len();
+        gates.push(Gate {
+            left: WireId(left),
+            right: WireId(right),
+            op,
+            output: WireId(output),
+        });
+        self.values[layer].push(value);
+
+        Wire {
+            layer,
+            index: output,
+        }
+    }
+
+    fn ensure_layer(&mut self, layer: usize) {
+        while self.gate_layers.len() < layer {
+            // index 0 of every layer is the zero wire (0 + 0)
+            self.gate_layers.push(vec![Gate {
+                left: WireId(0),
+                right: WireId(0),
+                op: GateOp::Add,
+                output: WireId(0),
+            }]);
+            self.values.push(vec![F::zero()]);
+        }
+    }
+
+    // the output layer holds exactly the given wires, in order
+    pub fn build(mut self, outputs: &[Wire]) -> Circuit<F> {
+        let depth = outputs
+            .iter()
+            .map(|wire| wire.layer)
+            .max()
+            .unwrap_or(0)
+            .max(self.depth());
+        let lifted: Vec<Wire> = outputs.iter().map(|wire| self.lift(*wire, depth)).collect();
+
+        // the output layer is padded by the provers and the inputs by InputPadding
+        for layer in 1..=depth {
+            let padded_len = PaddedLayer::<F>::padded_len(self.gate_layers[layer - 1].len());
+            while self.gate_layers[layer - 1].len() < padded_len {
+                self.push_gate(layer, GateOp::Add, 0, 0, F::zero());
+            }
+        }
+
+        let mut circuit = Circuit::new(self.values[0].clone());
+        for gates in self.gate_layers {
+            circuit.add_layer(Layer { gates });
+        }
+
+        let output_gates = lifted
+            .iter()
+            .enumerate()
+            .map(|(output, wire)| Gate {
+                left: WireId(wire.index),
+                right: WireId(0),
+                op: GateOp::Add,
+                output: WireId(output),
+            })
+            .collect();
+        circuit.add_layer(Layer {
+            gates: output_gates,
+        });
+
+        for (name, index) in self.input_names.iter() {
+            if let Err(err) = circuit.name_input(name, WireId(*index)) {
+                panic!("{}", err);
+            }
+        }
+
+        circuit
+    }
+
+    // same as build with the outputs named in order
+    pub fn build_named(self, outputs: &[(&str, Wire)]) -> Circuit<F> {
+        let wires: Vec<Wire> = outputs.iter().map(|(_, wire)| *wire).collect();
+        let mut circuit = self.build(&wires);
+
+        for (output, (name, _)) in outputs.iter().enumerate() {
+            if let Err(err) = circuit.name_output(name, WireId(output)) {
+                panic!("{}", err);
+            }
+        }
+
+        circuit
+    }
+
+    // build, then check the circuit against limits
+    pub fn build_with_limits(
+        self,
+        outputs: &[Wire],
+        limits: &CircuitLimits,
+    ) -> Result<Circuit<F>, LimitError> {
+        let circuit = self.build(outputs);
+        limits.check(&circuit)?;
+
+        Ok(circuit)
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+    #[cfg(feature = "prover")]
+    use {zk_primitives::transcript::test::domain, zk_protocols::gkr::public_inputs::PublicInputs};
+
+    #[test]
+    fn test_builder_evaluates_like_values() {
+        let mut builder = CircuitBuilder::<Fq>::new();
+        let x = builder.input(Fq::from(3));
+        let y = builder.input(Fq::from(4));
+
+        // (x + y) * x - 5 = 16 and y^5 = 1024
+        let sum = builder.add(x, y);
+        let prod = builder.mul(sum, x);
+        let five = builder.constant(Fq::from(5));
+        let result = builder.sub(prod, five);
+        let y_5 = builder.pow(y, 5);
+        let total = builder.sum(&[x, y, five]);
+
+        assert_eq!(builder.value(result), Fq::from(16));
+        assert_eq!(builder.value(total), Fq::from(12));
+        assert_eq!(builder.value(y_5), Fq::from(1024));
+
+        let circuit = builder.build(&[result, y_5, x]);
+        let output = circuit.evaluate().pop().unwrap();
+        assert_eq!(output, vec![Fq::from(16), Fq::from(1024), Fq::from(3)]);
+    }
+
+    #[test]
+    fn test_builder_names_inputs_and_outputs() {
+        let mut builder = CircuitBuilder::<Fq>::new();
+        let amount = builder.named_input("amount", Fq::from(0));
+        let price = builder.named_input("price", Fq::from(0));
+        let total = builder.mul(amount, price);
+
+        let mut circuit = builder.build_named(&[("total", total)]);
+        circuit
+            .assign(&[("amount", Fq::from(6)), ("price", Fq::from(7))])
+            .unwrap();
+
+        assert_eq!(circuit.output_value("total"), Ok(Fq::from(42)));
+    }
+
+    #[test]
+    #[should_panic(expected = "`x` is named twice")]
+    fn test_builder_rejects_duplicate_names() {
+        let mut builder = CircuitBuilder::<Fq>::new();
+        let x = builder.named_input("x", Fq::from(1));
+        builder.named_input("x", Fq::from(2));
+
+        builder.build(&[x]);
+    }
+
+    #[test]
+    fn test_builder_reuses_copies_and_constants() {
+        let mut builder = CircuitBuilder::<Fq>::new();
+        let x = builder.input(Fq::from(2));
+
+        let two = builder.constant(Fq::from(2));
+        assert_eq!(builder.constant(Fq::from(2)), two);
+
+        let x_2 = builder.mul(x, x);
+        let x_4 = builder.mul(x_2, x_2);
+        let a = builder.add(x_4, x);
+        let b = builder.mul(x_4, x);
+
+        // layer 2 holds zero, x_4 and a single copy of x shared by a and b
+        assert_eq!(builder.gate_layers[1].len(), 3);
+        assert_eq!(builder.value(a), Fq::from(18));
+        assert_eq!(builder.value(b), Fq::from(32));
+    }
+
+    #[test]
+    fn test_build_with_limits() {
+        let mut builder = CircuitBuilder::<Fq>::new();
+        let inputs: Vec<Wire> = (1..=6).map(|i| builder.input(Fq::from(i))).collect();
+        let sum = builder.sum(&inputs);
+
+        let result =
+            builder.build_with_limits(&[sum], &CircuitLimits::default().with_max_layers(2));
+        assert!(matches!(
+            result,
+            Err(LimitError::TooManyLayers { max: 2, .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "prover")]
+    fn test_builder_circuit_proves_and_verifies() {
+        let mut builder = CircuitBuilder::<Fq>::new();
+        let x = builder.input(Fq::from(3));
+        let y = builder.input(Fq::from(4));
+
+        let sum = builder.add(x, y);
+        let prod = builder.mul(sum, x);
+        let result = builder.sub(prod, y);
+        let y_5 = builder.pow(y, 5);
+
+        let circuit = builder.build(&[result, y_5, x]);
+        for layer in circuit.layers[..circuit.layers.len() - 1].iter() {
+            assert!(layer.gates.len().is_power_of_two());
+        }
+
+        let proof = circuit.proof(&domain());
+        let public = circuit.public_inputs();
+        assert_eq!(
+            public.outputs,
+            vec![Fq::from(17), Fq::from(1024), Fq::from(3)]
+        );
+        assert!(circuit.verify(&proof, &public, &domain()));
+
+        let wrong = PublicInputs::new(
+            circuit.inputs.clone(),
+            vec![Fq::from(18), Fq::from(1024), Fq::from(3)],
+        );
+        assert!(!circuit.verify(&proof, &wrong, &domain()));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod hash_helper_functions;
+pub mod mimc;
+pub mod rescue_prime;
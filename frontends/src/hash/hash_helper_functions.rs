@@ -0,0 +1,111 @@
+use ark_ff::{BigInteger, PrimeField};
+use zk_primitives::transcript::Transcript;
+
+// p - 1 as little endian u64 limbs (p is odd so there is no borrow)
+fn modulus_minus_one<F: PrimeField>() -> Vec<u64> {
+    let mut limbs = F::MODULUS.as_ref().to_vec();
+    limbs[0] -= 1;
+
+    limbs
+}
+
+fn rem_small(limbs: &[u64], divisor: u64) -> u64 {
+    limbs.iter().rev().fold(0u128, |rem, limb| {
+        ((rem << 64) + *limb as u128) % divisor as u128
+    }) as u64
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// smallest odd d >= 3 with gcd(d, p - 1) = 1 i.e. x -> x^d is a permutation of F
+// this is the S-box degree used by both MiMC and Rescue-Prime
+pub fn smallest_sbox_exponent<F: PrimeField>() -> u64 {
+    let p_minus_one = modulus_minus_one::<F>();
+
+    (3u64..)
+        .step_by(2)
+        .find(|d| gcd(*d, rem_small(&p_minus_one, *d)) == 1)
+        .unwrap()
+}
+
+// 1/alpha mod (p - 1) so that (x^alpha)^(1/alpha) = x
+// found as (k * (p - 1) + 1) / alpha for the k in [1, alpha) that makes the division exact
+pub fn inverse_exponent<F: PrimeField>(alpha: u64) -> Vec<u64> {
+    let p_minus_one = modulus_minus_one::<F>();
+    let rem = rem_small(&p_minus_one, alpha);
+
+    let k = (1..alpha)
+        .find(|k| (k * rem + 1).is_multiple_of(alpha))
+        .expect("alpha must be coprime to p - 1");
+
+    // k * (p - 1) + 1
+    let mut numerator = Vec::with_capacity(p_minus_one.len() + 1);
+    let mut carry = 1u128;
+    for limb in p_minus_one.iter() {
+        let value = (*limb as u128) * (k as u128) + carry;
+        numerator.push(value as u64);
+        carry = value >> 64;
+    }
+    numerator.push(carry as u64);
+
+    // long division by alpha, most significant limb first
+    let mut quotient = vec![0u64; numerator.len()];
+    let mut rem = 0u128;
+    for (i, limb) in numerator.iter().enumerate().rev() {
+        let value = (rem << 64) + *limb as u128;
+        quotient[i] = (value / alpha as u128) as u64;
+        rem = value % alpha as u128;
+    }
+
+    quotient
+}
+
+// nothing-up-my-sleeve constants squeezed from a keccak transcript seeded with a label
+pub fn round_constants<F: PrimeField>(seed: &[u8], count: usize) -> Vec<F> {
+    let mut transcript = Transcript::new();
+    transcript.absorb(seed);
+
+    (0..count)
+        .map(|_| transcript.squeeze_field::<F>())
+        .collect()
+}
+
+// bit size of the field, used to pick round counts
+pub fn field_bits<F: PrimeField>() -> u64 {
+    F::MODULUS.num_bits() as u64
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+    use ark_ff::Field;
+
+    #[test]
+    fn test_sbox_exponent_is_invertible() {
+        let alpha = smallest_sbox_exponent::<Fq>();
+        let alpha_inv = inverse_exponent::<Fq>(alpha);
+
+        for x in [Fq::from(2), Fq::from(12345), -Fq::from(7)] {
+            assert_eq!(x.pow([alpha]).pow(&alpha_inv), x);
+            assert_eq!(x.pow(&alpha_inv).pow([alpha]), x);
+        }
+    }
+
+    #[test]
+    fn test_round_constants_are_deterministic() {
+        let a = round_constants::<Fq>(b"seed", 4);
+        let b = round_constants::<Fq>(b"seed", 4);
+        let c = round_constants::<Fq>(b"other", 4);
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a[0], a[1]);
+    }
+}
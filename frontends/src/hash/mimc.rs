@@ -0,0 +1,135 @@
+use crate::{
+    circuit_builder::{CircuitBuilder, Wire},
+    hash::hash_helper_functions::{field_bits, round_constants, smallest_sbox_exponent},
+};
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// MiMC block cipher E_k(x): x = (x + k + cᵢ)^d for every round then x + k
+// d is the smallest exponent that is a permutation of F (3 when gcd(3, p - 1) = 1, else 5, 7, ...)
+// rounds = ⌈log_d(p)⌉ and c₀ = 0 as in the original paper
+// hashing uses Miyaguchi-Preneel: h = E_h(m) + h + m for every message element
+//=========================================================================================
+#[derive(Debug, Clone)]
+pub struct MiMC<F: PrimeField> {
+    pub exponent: u64,
+    pub round_constants: Vec<F>,
+}
+
+impl<F: PrimeField> Default for MiMC<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: PrimeField> MiMC<F> {
+    pub fn new() -> Self {
+        let exponent = smallest_sbox_exponent::<F>();
+        let rounds = (field_bits::<F>() as f64 / (exponent as f64).log2()).ceil() as usize;
+
+        let mut constants = vec![F::zero()];
+        constants.extend(round_constants::<F>(b"MiMC", rounds - 1));
+
+        MiMC {
+            exponent,
+            round_constants: constants,
+        }
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.round_constants.len()
+    }
+
+    pub fn encrypt(&self, x: F, key: F) -> F {
+        let mut x = x;
+        for c in self.round_constants.iter() {
+            x = (x + key + c).pow([self.exponent]);
+        }
+
+        x + key
+    }
+
+    pub fn hash(&self, inputs: &[F]) -> F {
+        inputs
+            .iter()
+            .fold(F::zero(), |h, m| self.encrypt(*m, h) + h + m)
+    }
+
+    //=====================================================================================
+    // Gadgets: same computation expressed as circuit gates
+    //=====================================================================================
+    pub fn encrypt_gadget(&self, builder: &mut CircuitBuilder<F>, x: Wire, key: Wire) -> Wire {
+        let mut x = x;
+        for c in self.round_constants.iter() {
+            let t = builder.add(x, key);
+            let t = if c.is_zero() {
+                t
+            } else {
+                builder.add_constant(t, *c)
+            };
+            x = builder.pow(t, self.exponent);
+        }
+
+        builder.add(x, key)
+    }
+
+    pub fn hash_gadget(&self, builder: &mut CircuitBuilder<F>, inputs: &[Wire]) -> Wire {
+        let mut h = builder.zero();
+        for m in inputs.iter() {
+            let e = self.encrypt_gadget(builder, *m, h);
+            h = builder.sum(&[e, h, *m]);
+        }
+
+        h
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_mimc_parameters() {
+        let mimc = MiMC::<Fq>::new();
+
+        assert!(mimc.exponent >= 3);
+        assert_eq!(mimc.round_constants[0], Fq::from(0));
+        assert!(mimc.rounds() as f64 * (mimc.exponent as f64).log2() >= field_bits::<Fq>() as f64);
+    }
+
+    #[test]
+    fn test_mimc_hash() {
+        let mimc = MiMC::<Fq>::new();
+        let inputs = [Fq::from(1), Fq::from(2)];
+
+        assert_eq!(mimc.hash(&inputs), mimc.hash(&inputs));
+        assert_ne!(mimc.hash(&inputs), mimc.hash(&[Fq::from(2), Fq::from(1)]));
+        assert_ne!(
+            mimc.encrypt(Fq::from(1), Fq::from(2)),
+            mimc.encrypt(Fq::from(1), Fq::from(3))
+        );
+    }
+
+    #[test]
+    fn test_mimc_gadget_matches_native() {
+        let mimc = MiMC::<Fq>::new();
+        let inputs = [Fq::from(3), Fq::from(5)];
+
+        let mut builder = CircuitBuilder::new();
+        let input_wires: Vec<Wire> = inputs.iter().map(|m| builder.input(*m)).collect();
+        let x = builder.input(Fq::from(7));
+        let key = builder.input(Fq::from(11));
+
+        let hash = mimc.hash_gadget(&mut builder, &input_wires);
+        let cipher = mimc.encrypt_gadget(&mut builder, x, key);
+        assert_eq!(builder.value(hash), mimc.hash(&inputs));
+
+        let circuit = builder.build(&[hash, cipher]);
+        let output = circuit.evaluate().pop().unwrap();
+        assert_eq!(
+            output,
+            vec![mimc.hash(&inputs), mimc.encrypt(Fq::from(7), Fq::from(11))]
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    circuit_builder::{CircuitBuilder, Wire},
+    hash::hash_helper_functions::{inverse_exponent, round_constants, smallest_sbox_exponent},
+};
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// Rescue-Prime permutation over a state of `width` elements, every round is
+// state^α -> MDS -> + constants -> state^(1/α) -> MDS -> + constants
+// the MDS matrix is the Cauchy matrix 1 / (i + (width + j)) which is always MDS
+// the sponge absorbs `width - capacity` elements at a time and outputs state[capacity]
+//=========================================================================================
+#[derive(Debug, Clone)]
+pub struct RescuePrime<F: PrimeField> {
+    pub width: usize,
+    pub capacity: usize,
+    pub rounds: usize,
+    pub alpha: u64,
+    pub alpha_inv: Vec<u64>,
+    pub mds: Vec<Vec<F>>,
+    pub round_constants: Vec<F>, // 2 * width per round
+}
+
+impl<F: PrimeField> Default for RescuePrime<F> {
+    fn default() -> Self {
+        // width 3 / capacity 1 / 8 rounds is the usual ~128 bit instance for 255 bit fields
+        Self::new(3, 1, 8)
+    }
+}
+
+impl<F: PrimeField> RescuePrime<F> {
+    pub fn new(width: usize, capacity: usize, rounds: usize) -> Self {
+        if capacity >= width {
+            panic!("Capacity must leave room for at least one rate element");
+        }
+
+        let alpha = smallest_sbox_exponent::<F>();
+        let alpha_inv = inverse_exponent::<F>(alpha);
+
+        let mds = (0..width)
+            .map(|i| {
+                (0..width)
+                    .map(|j| F::from((i + width + j) as u64).inverse().unwrap())
+                    .collect()
+            })
+            .collect();
+
+        RescuePrime {
+            width,
+            capacity,
+            rounds,
+            alpha,
+            alpha_inv,
+            mds,
+            round_constants: round_constants::<F>(b"Rescue-Prime", 2 * width * rounds),
+        }
+    }
+
+    pub fn rate(&self) -> usize {
+        self.width - self.capacity
+    }
+
+    fn mds_mul(&self, state: &[F]) -> Vec<F> {
+        self.mds
+            .iter()
+            .map(|row| row.iter().zip(state.iter()).map(|(m, s)| *m * s).sum())
+            .collect()
+    }
+
+    fn constants(&self, round: usize, half: usize) -> &[F] {
+        let start = (2 * round + half) * self.width;
+        &self.round_constants[start..start + self.width]
+    }
+
+    pub fn permute(&self, state: &mut [F]) {
+        if state.len() != self.width {
+            panic!("State must have exactly width elements");
+        }
+
+        for round in 0..self.rounds {
+            let sboxed: Vec<F> = state.iter().map(|s| s.pow([self.alpha])).collect();
+            let mixed = self.mds_mul(&sboxed);
+            for (s, (m, c)) in state
+                .iter_mut()
+                .zip(mixed.iter().zip(self.constants(round, 0)))
+            {
+                *s = *m + c;
+            }
+
+            let sboxed: Vec<F> = state.iter().map(|s| s.pow(&self.alpha_inv)).collect();
+            let mixed = self.mds_mul(&sboxed);
+            for (s, (m, c)) in state
+                .iter_mut()
+                .zip(mixed.iter().zip(self.constants(round, 1)))
+            {
+                *s = *m + c;
+            }
+        }
+    }
+
+    // pads with a single 1 followed by zeros up to a multiple of the rate
+    fn pad(&self, inputs: &[F]) -> Vec<F> {
+        let mut padded = inputs.to_vec();
+        padded.push(F::one());
+        while !padded.len().is_multiple_of(self.rate()) {
+            padded.push(F::zero());
+        }
+
+        padded
+    }
+
+    pub fn hash(&self, inputs: &[F]) -> F {
+        let mut state = vec![F::zero(); self.width];
+
+        for block in self.pad(inputs).chunks(self.rate()) {
+            for (j, m) in block.iter().enumerate() {
+                state[self.capacity + j] += m;
+            }
+            self.permute(&mut state);
+        }
+
+        state[self.capacity]
+    }
+
+    //=====================================================================================
+    // Gadgets
+    // x^(1/α) has a huge degree, so the prover supplies y = x^(1/α) as advice (an input wire)
+    // and the circuit outputs y^α - x as a check wire; every check wire must evaluate to 0
+    //=====================================================================================
+    fn mds_gadget(
+        &self,
+        builder: &mut CircuitBuilder<F>,
+        state: &[Wire],
+        round: usize,
+        half: usize,
+    ) -> Vec<Wire> {
+        let constants = self.constants(round, half).to_vec();
+
+        self.mds
+            .iter()
+            .zip(constants)
+            .map(|(row, c)| {
+                let mut terms: Vec<Wire> = row
+                    .iter()
+                    .zip(state.iter())
+                    .map(|(m, s)| builder.mul_constant(*s, *m))
+                    .collect();
+                terms.push(builder.constant(c));
+
+                builder.sum(&terms)
+            })
+            .collect()
+    }
+
+    // returns the permuted state and the check wires
+    pub fn permutation_gadget(
+        &self,
+        builder: &mut CircuitBuilder<F>,
+        state: &[Wire],
+    ) -> (Vec<Wire>, Vec<Wire>) {
+        if state.len() != self.width {
+            panic!("State must have exactly width elements");
+        }
+
+        let mut state = state.to_vec();
+        let mut checks = Vec::new();
+
+        for round in 0..self.rounds {
+            let sboxed: Vec<Wire> = state.iter().map(|s| builder.pow(*s, self.alpha)).collect();
+            state = self.mds_gadget(builder, &sboxed, round, 0);
+
+            let sboxed: Vec<Wire> = state
+                .iter()
+                .map(|s| {
+                    let advice = builder.input(builder.value(*s).pow(&self.alpha_inv));
+                    let advice_pow = builder.pow(advice, self.alpha);
+                    checks.push(builder.sub(advice_pow, *s));
+
+                    advice
+                })
+                .collect();
+            state = self.mds_gadget(builder, &sboxed, round, 1);
+        }
+
+        (state, checks)
+    }
+
+    pub fn hash_gadget(
+        &self,
+        builder: &mut CircuitBuilder<F>,
+        inputs: &[Wire],
+    ) -> (Wire, Vec<Wire>) {
+        let mut inputs = inputs.to_vec();
+        let one = builder.constant(F::one());
+        inputs.push(one);
+        while !inputs.len().is_multiple_of(self.rate()) {
+            inputs.push(builder.zero());
+        }
+
+        let mut state = vec![builder.zero(); self.width];
+        let mut checks = Vec::new();
+
+        for block in inputs.chunks(self.rate()) {
+            for (j, m) in block.iter().enumerate() {
+                state[self.capacity + j] = builder.add(state[self.capacity + j], *m);
+            }
+
+            let (new_state, new_checks) = self.permutation_gadget(builder, &state);
+            state = new_state;
+            checks.extend(new_checks);
+        }
+
+        (state[self.capacity], checks)
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_rescue_prime_hash() {
+        let rescue = RescuePrime::<Fq>::default();
+
+        let a = rescue.hash(&[Fq::from(1), Fq::from(2)]);
+        assert_eq!(a, rescue.hash(&[Fq::from(1), Fq::from(2)]));
+        assert_ne!(a, rescue.hash(&[Fq::from(2), Fq::from(1)]));
+        // padding keeps [1] and [1, 0] apart
+        assert_ne!(
+            rescue.hash(&[Fq::from(1)]),
+            rescue.hash(&[Fq::from(1), Fq::from(0)])
+        );
+    }
+
+    #[test]
+    fn test_rescue_prime_permutation_changes_state() {
+        let rescue = RescuePrime::<Fq>::new(3, 1, 2);
+        let mut state = vec![Fq::from(0); 3];
+
+        rescue.permute(&mut state);
+        assert_ne!(state, vec![Fq::from(0); 3]);
+    }
+
+    #[test]
+    fn test_rescue_prime_gadget_matches_native() {
+        let rescue = RescuePrime::<Fq>::new(3, 1, 2);
+        let inputs = [Fq::from(3), Fq::from(5), Fq::from(8)];
+
+        let mut builder = CircuitBuilder::new();
+        let input_wires: Vec<Wire> = inputs.iter().map(|m| builder.input(*m)).collect();
+        let (hash, checks) = rescue.hash_gadget(&mut builder, &input_wires);
+
+        let mut outputs = vec![hash];
+        outputs.extend(checks.iter());
+        let circuit = builder.build(&outputs);
+        let output = circuit.evaluate().pop().unwrap();
+
+        assert_eq!(output[0], rescue.hash(&inputs));
+        assert!(output[1..].iter().all(|check| *check == Fq::from(0)));
+    }
+}
@@ -0,0 +1,16 @@
+//=========================================================================================
+// Frontends, the ways a statement becomes something the protocols can prove
+//   circuit_builder  straight-line gadget code compiled into a layered GKR circuit
+//   circom           importer for the .r1cs and .wtns files circom writes, into the R1CS below
+//   hash, signature  the MiMC and Rescue-Prime gadgets and the Schnorr verification circuit
+//                    (with the native hashes and signatures they are checked against)
+//   r1cs             the R1CS that nova folds, re-exported from zk-protocols since the folding
+//                    scheme is built on it
+// zk-frontends -> zk-protocols -> zk-primitives, nothing below depends on this crate
+//=========================================================================================
+pub mod circom;
+pub mod circuit_builder;
+pub mod hash;
+pub mod signature;
+
+pub use zk_protocols::folding::r1cs;
@@ -0,0 +1,361 @@
+use crate::circuit_builder::{CircuitBuilder, Wire};
+use ark_ff::{BigInteger, PrimeField};
+
+//=========================================================================================
+// Elliptic curve gadgets for short Weierstrass curves y² = x³ + b (a = 0, e.g. bn254 / bls12 G1)
+// the circuit field is the curve's base field and points live in projective coordinates
+// (X : Y : Z) with the identity at (0 : 1 : 0)
+// addition uses the complete formulas of Renes-Costello-Batina (Algorithm 7) so doubling,
+// adding the identity and adding P + (-P) need no special cases and no inversions
+//=========================================================================================
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointWires {
+    pub x: Wire,
+    pub y: Wire,
+    pub z: Wire,
+}
+
+pub fn identity<F: PrimeField>(builder: &mut CircuitBuilder<F>) -> PointWires {
+    PointWires {
+        x: builder.zero(),
+        y: builder.constant(F::one()),
+        z: builder.zero(),
+    }
+}
+
+// affine (x, y) as a circuit input i.e. (x : y : 1)
+pub fn input_point<F: PrimeField>(builder: &mut CircuitBuilder<F>, point: (F, F)) -> PointWires {
+    PointWires {
+        x: builder.input(point.0),
+        y: builder.input(point.1),
+        z: builder.constant(F::one()),
+    }
+}
+
+pub fn constant_point<F: PrimeField>(builder: &mut CircuitBuilder<F>, point: (F, F)) -> PointWires {
+    PointWires {
+        x: builder.constant(point.0),
+        y: builder.constant(point.1),
+        z: builder.constant(F::one()),
+    }
+}
+
+// y² - x³ - b, zero iff the affine point is on the curve
+pub fn on_curve_check<F: PrimeField>(
+    builder: &mut CircuitBuilder<F>,
+    point: PointWires,
+    b: F,
+) -> Wire {
+    let y_2 = builder.mul(point.y, point.y);
+    let x_3 = builder.pow(point.x, 3);
+    let rhs = builder.add_constant(x_3, b);
+
+    builder.sub(y_2, rhs)
+}
+
+// X3 = (X1Y2 + X2Y1)(Y1Y2 - 3bZ1Z2) - 3b(Y1Z2 + Y2Z1)(X1Z2 + X2Z1)
+// Y3 = (Y1Y2 + 3bZ1Z2)(Y1Y2 - 3bZ1Z2) + 9bX1X2(X1Z2 + X2Z1)
+// Z3 = (Y1Z2 + Y2Z1)(Y1Y2 + 3bZ1Z2) + 3X1X2(X1Y2 + X2Y1)
+pub fn add_points<F: PrimeField>(
+    builder: &mut CircuitBuilder<F>,
+    p: PointWires,
+    q: PointWires,
+    b: F,
+) -> PointWires {
+    let b3 = F::from(3u64) * b;
+
+    let x1x2 = builder.mul(p.x, q.x);
+    let y1y2 = builder.mul(p.y, q.y);
+    let z1z2 = builder.mul(p.z, q.z);
+    let b3z1z2 = builder.mul_constant(z1z2, b3);
+
+    let x1y2 = builder.mul(p.x, q.y);
+    let x2y1 = builder.mul(q.x, p.y);
+    let xy = builder.add(x1y2, x2y1);
+    let y1z2 = builder.mul(p.y, q.z);
+    let y2z1 = builder.mul(q.y, p.z);
+    let yz = builder.add(y1z2, y2z1);
+    let x1z2 = builder.mul(p.x, q.z);
+    let x2z1 = builder.mul(q.x, p.z);
+    let xz = builder.add(x1z2, x2z1);
+
+    let y_minus = builder.sub(y1y2, b3z1z2);
+    let y_plus = builder.add(y1y2, b3z1z2);
+
+    let x_left = builder.mul(xy, y_minus);
+    let yz_xz = builder.mul(yz, xz);
+    let x_right = builder.mul_constant(yz_xz, b3);
+    let x = builder.sub(x_left, x_right);
+
+    let y_left = builder.mul(y_plus, y_minus);
+    let x1x2_xz = builder.mul(x1x2, xz);
+    let y_right = builder.mul_constant(x1x2_xz, F::from(3u64) * b3);
+    let y = builder.add(y_left, y_right);
+
+    let z_left = builder.mul(yz, y_plus);
+    let x1x2_xy = builder.mul(x1x2, xy);
+    let z_right = builder.mul_constant(x1x2_xy, F::from(3u64));
+    let z = builder.add(z_left, z_right);
+
+    PointWires { x, y, z }
+}
+
+// bit ? point : identity, the bit must be constrained to {0, 1} separately
+pub fn select_point<F: PrimeField>(
+    builder: &mut CircuitBuilder<F>,
+    bit: Wire,
+    point: PointWires,
+) -> PointWires {
+    // y = bit * (Y - 1) + 1
+    let y_minus_one = builder.add_constant(point.y, -F::one());
+    let y = builder.mul(bit, y_minus_one);
+
+    PointWires {
+        x: builder.mul(bit, point.x),
+        y: builder.add_constant(y, F::one()),
+        z: builder.mul(bit, point.z),
+    }
+}
+
+// zero iff p and q are the same projective point (both checks)
+pub fn points_equal_checks<F: PrimeField>(
+    builder: &mut CircuitBuilder<F>,
+    p: PointWires,
+    q: PointWires,
+) -> Vec<Wire> {
+    let xp_zq = builder.mul(p.x, q.z);
+    let xq_zp = builder.mul(q.x, p.z);
+    let yp_zq = builder.mul(p.y, q.z);
+    let yq_zp = builder.mul(q.y, p.z);
+
+    vec![builder.sub(xp_zq, xq_zp), builder.sub(yp_zq, yq_zp)]
+}
+
+//=====================================================================================
+// Scalars enter the circuit as little endian bits supplied by the prover
+//=====================================================================================
+pub fn input_bits<F: PrimeField>(builder: &mut CircuitBuilder<F>, bits: &[bool]) -> Vec<Wire> {
+    bits.iter()
+        .map(|bit| builder.input(F::from(*bit)))
+        .collect()
+}
+
+// bit² - bit, zero iff bit ∈ {0, 1}
+pub fn boolean_check<F: PrimeField>(builder: &mut CircuitBuilder<F>, bit: Wire) -> Wire {
+    let bit_2 = builder.mul(bit, bit);
+    builder.sub(bit_2, bit)
+}
+
+// Σ bitᵢ * 2ⁱ
+pub fn recompose<F: PrimeField>(builder: &mut CircuitBuilder<F>, bits: &[Wire]) -> Wire {
+    let mut power = F::one();
+    let mut terms = Vec::with_capacity(bits.len());
+    for bit in bits.iter() {
+        terms.push(builder.mul_constant(*bit, power));
+        power.double_in_place();
+    }
+
+    builder.sum(&terms)
+}
+
+// zero iff the little endian bits are below the field modulus p, i.e. at most p - 1
+// going down from the top bit, the bits exceed p - 1 exactly when one of them is 1 where p - 1
+// has a 0 and every bit above matched, each such position adds prefix_equal * bit and for
+// boolean bits at most one of them is 1, so the sum can't wrap around to 0
+pub fn less_than_modulus_check<F: PrimeField>(
+    builder: &mut CircuitBuilder<F>,
+    bits: &[Wire],
+) -> Wire {
+    let max = (-F::one()).into_bigint().to_bits_le();
+    if max.iter().skip(bits.len()).any(|bit| *bit) {
+        return builder.zero(); // fewer bits than p - 1, every value is below p
+    }
+
+    let mut prefix_equal = builder.constant(F::one());
+    let mut over = Vec::new();
+    for (i, bit) in bits.iter().enumerate().rev() {
+        if max.get(i).copied().unwrap_or(false) {
+            prefix_equal = builder.mul(prefix_equal, *bit);
+        } else {
+            let above = builder.mul(prefix_equal, *bit);
+            over.push(above);
+            prefix_equal = builder.sub(prefix_equal, above);
+        }
+    }
+
+    builder.sum(&over)
+}
+
+// Σ bitᵢ * (2ⁱ * P) for a fixed P, the multiples are computed outside the circuit
+// the selected terms are summed as a tree so the depth is only log2(bits) additions
+pub fn scalar_mul_fixed_base<F: PrimeField>(
+    builder: &mut CircuitBuilder<F>,
+    bits: &[Wire],
+    base_multiples: &[(F, F)],
+    b: F,
+) -> PointWires {
+    if bits.len() > base_multiples.len() {
+        panic!("Not enough multiples of the base for the scalar bits");
+    }
+
+    let mut terms: Vec<PointWires> = bits
+        .iter()
+        .zip(base_multiples.iter())
+        .map(|(bit, multiple)| {
+            let point = constant_point(builder, *multiple);
+            select_point(builder, *bit, point)
+        })
+        .collect();
+
+    if terms.is_empty() {
+        return identity(builder);
+    }
+
+    while terms.len() > 1 {
+        terms = terms
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => add_points(builder, *left, *right, b),
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    terms[0]
+}
+
+// double-and-add for a point only known as wires, the doubling chain and the
+// accumulator advance side by side so they stay at the same depth
+pub fn scalar_mul<F: PrimeField>(
+    builder: &mut CircuitBuilder<F>,
+    bits: &[Wire],
+    base: PointWires,
+    b: F,
+) -> PointWires {
+    let mut acc = identity(builder);
+    let mut doubled = base;
+
+    for (i, bit) in bits.iter().enumerate() {
+        let term = select_point(builder, *bit, doubled);
+        acc = add_points(builder, acc, term, b);
+
+        if i + 1 < bits.len() {
+            doubled = add_points(builder, doubled, doubled, b);
+        }
+    }
+
+    acc
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+    use ark_ff::Field;
+
+    // (1, 2) is on y² = x³ + 3 (the bn254 G1 generator)
+    const B: u64 = 3;
+
+    fn affine_add(p: (Fq, Fq), q: (Fq, Fq)) -> (Fq, Fq) {
+        let lambda = if p == q {
+            (Fq::from(3) * p.0 * p.0) / (Fq::from(2) * p.1)
+        } else {
+            (q.1 - p.1) / (q.0 - p.0)
+        };
+        let x = lambda * lambda - p.0 - q.0;
+
+        (x, lambda * (p.0 - x) - p.1)
+    }
+
+    fn to_affine(builder: &CircuitBuilder<Fq>, point: PointWires) -> (Fq, Fq) {
+        let z_inv = builder.value(point.z).inverse().unwrap();
+
+        (
+            builder.value(point.x) * z_inv,
+            builder.value(point.y) * z_inv,
+        )
+    }
+
+    #[test]
+    fn test_add_points_matches_affine_formulas() {
+        let g = (Fq::from(1), Fq::from(2));
+        let g_2 = affine_add(g, g);
+        let g_3 = affine_add(g_2, g);
+
+        let mut builder = CircuitBuilder::new();
+        let p = input_point(&mut builder, g);
+        let on_curve = on_curve_check(&mut builder, p, Fq::from(B));
+        let doubled = add_points(&mut builder, p, p, Fq::from(B));
+        let tripled = add_points(&mut builder, doubled, p, Fq::from(B));
+        let zero = identity(&mut builder);
+        let same = add_points(&mut builder, p, zero, Fq::from(B));
+
+        assert_eq!(builder.value(on_curve), Fq::from(0));
+        assert_eq!(to_affine(&builder, doubled), g_2);
+        assert_eq!(to_affine(&builder, tripled), g_3);
+        assert_eq!(to_affine(&builder, same), g);
+
+        let checks = points_equal_checks(&mut builder, same, p);
+        assert!(checks
+            .iter()
+            .all(|check| builder.value(*check) == Fq::from(0)));
+    }
+
+    #[test]
+    fn test_scalar_mul() {
+        let g = (Fq::from(1), Fq::from(2));
+        let mut multiples = vec![g];
+        for _ in 0..3 {
+            let last = *multiples.last().unwrap();
+            multiples.push(affine_add(last, last));
+        }
+        // 11 = 0b1011
+        let g_11 = affine_add(affine_add(multiples[0], multiples[1]), multiples[3]);
+        let bits = [true, true, false, true];
+
+        let mut builder = CircuitBuilder::new();
+        let bit_wires = input_bits(&mut builder, &bits);
+        let p = input_point(&mut builder, g);
+        let fixed = scalar_mul_fixed_base(&mut builder, &bit_wires, &multiples, Fq::from(B));
+        let variable = scalar_mul(&mut builder, &bit_wires, p, Fq::from(B));
+        let scalar = recompose(&mut builder, &bit_wires);
+
+        assert_eq!(to_affine(&builder, fixed), g_11);
+        assert_eq!(to_affine(&builder, variable), g_11);
+        assert_eq!(builder.value(scalar), Fq::from(11));
+    }
+
+    #[test]
+    fn test_boolean_check() {
+        let mut builder = CircuitBuilder::<Fq>::new();
+        let bit = builder.input(Fq::from(1));
+        let not_bit = builder.input(Fq::from(2));
+
+        let bit_check = boolean_check(&mut builder, bit);
+        let not_bit_check = boolean_check(&mut builder, not_bit);
+        assert_eq!(builder.value(bit_check), Fq::from(0));
+        assert_ne!(builder.value(not_bit_check), Fq::from(0));
+    }
+
+    #[test]
+    fn test_less_than_modulus_check() {
+        let bits_of = |value: <Fq as PrimeField>::BigInt| {
+            value.to_bits_le()[..Fq::MODULUS_BIT_SIZE as usize].to_vec()
+        };
+        let mut p_plus_one = Fq::MODULUS;
+        p_plus_one.add_with_carry(&Fq::from(1).into_bigint());
+
+        let mut builder = CircuitBuilder::<Fq>::new();
+        let mut check = |value| {
+            let bits = input_bits(&mut builder, &bits_of(value));
+            let check = less_than_modulus_check(&mut builder, &bits);
+            builder.value(check)
+        };
+
+        assert_eq!(check((-Fq::from(1)).into_bigint()), Fq::from(0));
+        assert_eq!(check(Fq::from(5).into_bigint()), Fq::from(0));
+        assert_ne!(check(Fq::MODULUS), Fq::from(0));
+        assert_ne!(check(p_plus_one), Fq::from(0));
+    }
+}
@@ -0,0 +1,406 @@
+use crate::{
+    circuit_builder::{CircuitBuilder, Wire},
+    hash::mimc::MiMC,
+    signature::curve_gadgets::{
+        add_points, boolean_check, input_bits, input_point, less_than_modulus_check,
+        on_curve_check, points_equal_checks, recompose, scalar_mul, scalar_mul_fixed_base,
+    },
+};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use zk_protocols::gkr::gkr_circuit::Circuit;
+#[cfg(feature = "prover")]
+use {ark_ff::UniformRand, rand::Rng};
+
+//=========================================================================================
+// Schnorr signatures over a curve y² = x³ + b whose base field is the circuit field
+// e = MiMC(R.x, R.y, A.x, A.y, m)  (a zk-friendly hash so the circuit can recompute it)
+// sign: R = k·G, s = k + e·sk      verify: s·G == R + e·A
+// the verification circuit outputs a list of checks which are all 0 iff the signature is valid
+// NOTE: on bn254 the circuit has thousands of layers with hundreds of gates each and a layer
+// sum-check is quadratic in the width, so the tests prove it over a 16 bit curve
+//=========================================================================================
+pub struct SchnorrSignature<G: CurveGroup> {
+    pub r: G::Affine,
+    pub s: G::ScalarField,
+}
+
+#[cfg(feature = "prover")]
+pub fn keygen<G: CurveGroup, R: Rng>(rng: &mut R) -> (G::ScalarField, G::Affine) {
+    let secret_key = G::ScalarField::rand(rng);
+    let public_key = G::generator().mul_bigint(secret_key.into_bigint());
+
+    (secret_key, public_key.into_affine())
+}
+
+pub fn challenge<G: CurveGroup>(
+    r: &G::Affine,
+    public_key: &G::Affine,
+    message: &[G::BaseField],
+) -> G::BaseField
+where
+    G::BaseField: PrimeField,
+{
+    let (r_x, r_y) = coordinates::<G>(r);
+    let (a_x, a_y) = coordinates::<G>(public_key);
+
+    let mut inputs = vec![r_x, r_y, a_x, a_y];
+    inputs.extend_from_slice(message);
+
+    MiMC::<G::BaseField>::new().hash(&inputs)
+}
+
+// e lives in the base field, multiplying a point by its integer value is the same as by e mod r
+fn challenge_scalar<G: CurveGroup>(e: G::BaseField) -> G::ScalarField
+where
+    G::BaseField: PrimeField,
+{
+    G::ScalarField::from_le_bytes_mod_order(&e.into_bigint().to_bytes_le())
+}
+
+#[cfg(feature = "prover")]
+pub fn sign<G: CurveGroup, R: Rng>(
+    secret_key: G::ScalarField,
+    message: &[G::BaseField],
+    rng: &mut R,
+) -> SchnorrSignature<G>
+where
+    G::BaseField: PrimeField,
+{
+    let public_key = G::generator()
+        .mul_bigint(secret_key.into_bigint())
+        .into_affine();
+
+    let k = G::ScalarField::rand(rng);
+    let r = G::generator().mul_bigint(k.into_bigint()).into_affine();
+    let e = challenge_scalar::<G>(challenge::<G>(&r, &public_key, message));
+
+    SchnorrSignature {
+        r,
+        s: k + (e * secret_key),
+    }
+}
+
+pub fn verify<G: CurveGroup>(
+    public_key: &G::Affine,
+    message: &[G::BaseField],
+    signature: &SchnorrSignature<G>,
+) -> bool
+where
+    G::BaseField: PrimeField,
+{
+    if signature.r.is_zero() || public_key.is_zero() {
+        return false;
+    }
+
+    let e = challenge_scalar::<G>(challenge::<G>(&signature.r, public_key, message));
+
+    let lhs = G::generator().mul_bigint(signature.s.into_bigint());
+    let rhs = signature.r.into_group() + public_key.mul_bigint(e.into_bigint());
+
+    lhs == rhs
+}
+
+//=====================================================================================
+// Verification circuit
+// inputs: R, A, m, the bits of s and the bits of e (advice)
+// checks: R and A on the curve, every bit boolean, bits of e below p, bits of e recompose to
+// MiMC(R, A, m) and Σ sᵢ(2ⁱG) == R + e·A
+// the recomposition is mod p but scalar_mul uses the integer the bits spell, so without e < p
+// the bits of e + p (when < 2^bits) would pass with a signature made for e + p
+//=====================================================================================
+pub fn verification_circuit<G: CurveGroup>(
+    public_key: &G::Affine,
+    message: &[G::BaseField],
+    signature: &SchnorrSignature<G>,
+) -> Circuit<G::BaseField>
+where
+    G::BaseField: PrimeField,
+{
+    let e = challenge::<G>(&signature.r, public_key, message);
+    let e_bits = e.into_bigint().to_bits_le();
+
+    circuit_with_challenge_bits(
+        public_key,
+        message,
+        signature,
+        &e_bits[..G::BaseField::MODULUS_BIT_SIZE as usize],
+    )
+}
+
+// the circuit for any advice bits of e, verification_circuit passes the canonical ones
+fn circuit_with_challenge_bits<G: CurveGroup>(
+    public_key: &G::Affine,
+    message: &[G::BaseField],
+    signature: &SchnorrSignature<G>,
+    e_bits: &[bool],
+) -> Circuit<G::BaseField>
+where
+    G::BaseField: PrimeField,
+{
+    let b = curve_b::<G>();
+    let mut builder = CircuitBuilder::new();
+
+    let r = input_point(&mut builder, coordinates::<G>(&signature.r));
+    let a = input_point(&mut builder, coordinates::<G>(public_key));
+    let message_wires: Vec<Wire> = message.iter().map(|m| builder.input(*m)).collect();
+
+    let s_bits = signature.s.into_bigint().to_bits_le();
+    let s_bits = input_bits(
+        &mut builder,
+        &s_bits[..G::ScalarField::MODULUS_BIT_SIZE as usize],
+    );
+    let e_bits = input_bits(&mut builder, e_bits);
+
+    let mut checks = vec![
+        on_curve_check(&mut builder, r, b),
+        on_curve_check(&mut builder, a, b),
+    ];
+    for bit in s_bits.iter().chain(e_bits.iter()) {
+        checks.push(boolean_check(&mut builder, *bit));
+    }
+    checks.push(less_than_modulus_check(&mut builder, &e_bits));
+
+    let mut hash_inputs = vec![r.x, r.y, a.x, a.y];
+    hash_inputs.extend(message_wires);
+    let e_wire = MiMC::new().hash_gadget(&mut builder, &hash_inputs);
+    let e_recomposed = recompose(&mut builder, &e_bits);
+    checks.push(builder.sub(e_recomposed, e_wire));
+
+    let lhs = scalar_mul_fixed_base(
+        &mut builder,
+        &s_bits,
+        &generator_multiples::<G>(s_bits.len()),
+        b,
+    );
+    let e_a = scalar_mul(&mut builder, &e_bits, a, b);
+    let rhs = add_points(&mut builder, r, e_a, b);
+    checks.extend(points_equal_checks(&mut builder, lhs, rhs));
+
+    builder.build(&checks)
+}
+
+// the signature is valid iff every output of the verification circuit is 0
+pub fn circuit_accepts<F: PrimeField>(circuit: &Circuit<F>) -> bool {
+    circuit
+        .evaluate()
+        .last()
+        .is_some_and(|outputs| outputs.iter().all(|output| output.is_zero()))
+}
+
+fn coordinates<G: CurveGroup>(point: &G::Affine) -> (G::BaseField, G::BaseField) {
+    point
+        .xy()
+        .expect("The point at infinity has no affine coordinates")
+}
+
+// b = y² - x³ from the generator, only valid for a = 0 curves
+fn curve_b<G: CurveGroup>() -> G::BaseField
+where
+    G::BaseField: PrimeField,
+{
+    let (x, y) = coordinates::<G>(&G::generator().into_affine());
+
+    (y * y) - (x * x * x)
+}
+
+// [G, 2G, 4G, ...] as affine coordinates
+fn generator_multiples<G: CurveGroup>(count: usize) -> Vec<(G::BaseField, G::BaseField)> {
+    let mut point = G::generator();
+    let mut multiples = Vec::with_capacity(count);
+    for _ in 0..count {
+        multiples.push(coordinates::<G>(&point.into_affine()));
+        point = point + point;
+    }
+
+    multiples
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::{Fq, Fr, G1Projective as G1};
+    use ark_ff::Zero;
+    use zk_primitives::transcript::test::domain;
+
+    fn message() -> Vec<Fq> {
+        vec![Fq::from(1337), Fq::from(42)]
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let mut rng = rand::thread_rng();
+        let (secret_key, public_key) = keygen::<G1, _>(&mut rng);
+
+        let signature = sign::<G1, _>(secret_key, &message(), &mut rng);
+        assert!(verify::<G1>(&public_key, &message(), &signature));
+    }
+
+    // the same signature on every enabled curve, like the matrix in zk-protocols' curves.rs
+    fn sign_and_verify<G: CurveGroup>()
+    where
+        G::BaseField: PrimeField,
+    {
+        let mut rng = rand::thread_rng();
+        let (secret_key, public_key) = keygen::<G, _>(&mut rng);
+        let message = [G::BaseField::from(7u64), G::BaseField::from(11u64)];
+
+        let signature = sign::<G, _>(secret_key, &message, &mut rng);
+        assert!(verify::<G>(&public_key, &message, &signature));
+        assert!(!verify::<G>(
+            &public_key,
+            &[G::BaseField::from(8u64)],
+            &signature
+        ));
+    }
+
+    #[test]
+    fn test_sign_verify_on_every_curve() {
+        sign_and_verify::<G1>();
+        #[cfg(feature = "bls12-381")]
+        sign_and_verify::<ark_bls12_381::G1Projective>();
+        #[cfg(feature = "bls12-377")]
+        sign_and_verify::<ark_bls12_377::G1Projective>();
+        #[cfg(feature = "pallas")]
+        sign_and_verify::<ark_pallas::Projective>();
+        #[cfg(feature = "vesta")]
+        sign_and_verify::<ark_vesta::Projective>();
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_message_or_key() {
+        let mut rng = rand::thread_rng();
+        let (secret_key, public_key) = keygen::<G1, _>(&mut rng);
+        let (_, other_key) = keygen::<G1, _>(&mut rng);
+
+        let signature = sign::<G1, _>(secret_key, &message(), &mut rng);
+        assert!(!verify::<G1>(&public_key, &[Fq::from(1338)], &signature));
+        assert!(!verify::<G1>(&other_key, &message(), &signature));
+    }
+
+    #[test]
+    fn test_verification_circuit() {
+        let mut rng = rand::thread_rng();
+        let (secret_key, public_key) = keygen::<G1, _>(&mut rng);
+        let mut signature = sign::<G1, _>(secret_key, &message(), &mut rng);
+
+        let circuit = verification_circuit::<G1>(&public_key, &message(), &signature);
+        assert!(circuit_accepts(&circuit));
+
+        signature.s += Fr::from(1);
+        let circuit = verification_circuit::<G1>(&public_key, &message(), &signature);
+        assert!(!circuit_accepts(&circuit));
+    }
+
+    //=====================================================================================
+    // y² = x³ + 2 over F_32803 with 32497 points (a prime) and G = (2, 405)
+    // the verification circuit over a 16 bit curve has 16 bit scalars and a 7 round MiMC,
+    // small enough for the layer sum-checks of a test, on bn254 it is ~16x as deep and as wide
+    //=====================================================================================
+    mod toy_curve {
+        use ark_ec::{
+            short_weierstrass::{self, SWCurveConfig},
+            CurveConfig,
+        };
+        use ark_ff::{
+            fields::{Fp64, MontBackend, MontConfig},
+            MontFp,
+        };
+
+        #[derive(MontConfig)]
+        #[modulus = "32803"]
+        #[generator = "5"]
+        pub struct FqConfig;
+        pub type Fq = Fp64<MontBackend<FqConfig, 1>>;
+
+        #[derive(MontConfig)]
+        #[modulus = "32497"]
+        #[generator = "7"]
+        pub struct FrConfig;
+        pub type Fr = Fp64<MontBackend<FrConfig, 1>>;
+
+        #[derive(Clone, Copy, Default, PartialEq, Eq)]
+        pub struct Config;
+
+        impl CurveConfig for Config {
+            type BaseField = Fq;
+            type ScalarField = Fr;
+            const COFACTOR: &'static [u64] = &[1];
+            const COFACTOR_INV: Fr = MontFp!("1");
+        }
+
+        impl SWCurveConfig for Config {
+            const COEFF_A: Fq = MontFp!("0");
+            const COEFF_B: Fq = MontFp!("2");
+            const GENERATOR: short_weierstrass::Affine<Self> =
+                short_weierstrass::Affine::new_unchecked(MontFp!("2"), MontFp!("405"));
+        }
+
+        pub type G = short_weierstrass::Projective<Config>;
+    }
+
+    fn prove_verification_circuit<G: CurveGroup>()
+    where
+        G::BaseField: PrimeField,
+    {
+        let mut rng = rand::thread_rng();
+        let (secret_key, public_key) = keygen::<G, _>(&mut rng);
+        let message = [G::BaseField::from(7u64), G::BaseField::from(11u64)];
+        let signature = sign::<G, _>(secret_key, &message, &mut rng);
+
+        let circuit = verification_circuit::<G>(&public_key, &message, &signature);
+        let proof = circuit.proof(&domain());
+        let public = circuit.public_inputs();
+        assert!(circuit.verify(&proof, &public, &domain()));
+        assert!(public.outputs.iter().all(|output| output.is_zero()));
+    }
+
+    #[test]
+    fn test_verification_circuit_proves_and_verifies() {
+        prove_verification_circuit::<toy_curve::G>();
+    }
+
+    // a signature made for the integer e + p passes every check but e < p
+    fn reject_e_plus_p<G: CurveGroup>()
+    where
+        G::BaseField: PrimeField,
+    {
+        let mut rng = rand::thread_rng();
+        let (secret_key, public_key) = keygen::<G, _>(&mut rng);
+        let message = [G::BaseField::from(7u64), G::BaseField::from(11u64)];
+        let e_len = G::BaseField::MODULUS_BIT_SIZE;
+
+        // a fresh R until e + p still fits in the bits of e, then s = k + (e + p) * sk
+        let (signature, e_plus_p) = loop {
+            let k = G::ScalarField::rand(&mut rng);
+            let r = G::generator().mul_bigint(k.into_bigint()).into_affine();
+            let mut e_plus_p = challenge::<G>(&r, &public_key, &message).into_bigint();
+            let carry = e_plus_p.add_with_carry(&G::BaseField::MODULUS);
+            if !carry && e_plus_p.num_bits() <= e_len {
+                let e = G::ScalarField::from_le_bytes_mod_order(&e_plus_p.to_bytes_le());
+                let s = k + (e * secret_key);
+                break (SchnorrSignature::<G> { r, s }, e_plus_p);
+            }
+        };
+
+        let e_bits = &e_plus_p.to_bits_le()[..e_len as usize];
+        let circuit = circuit_with_challenge_bits::<G>(&public_key, &message, &signature, e_bits);
+
+        // the proof holds, it is the outputs it proves that reject the signature
+        let proof = circuit.proof(&domain());
+        let public = circuit.public_inputs();
+        assert!(circuit.verify(&proof, &public, &domain()));
+        assert!(!circuit_accepts(&circuit));
+
+        // R, A on the curve, the boolean checks of s and e, then e < p is the only one failing
+        let canonical = 2 + (G::ScalarField::MODULUS_BIT_SIZE + e_len) as usize;
+        for (i, output) in public.outputs.iter().enumerate() {
+            assert_eq!(output.is_zero(), i != canonical);
+        }
+    }
+
+    #[test]
+    fn test_verification_circuit_rejects_e_plus_p() {
+        reject_e_plus_p::<toy_curve::G>();
+    }
+}
@@ -0,0 +1,249 @@
+use crate::{
+    multi_linear::SumCheckField,
+    transcript::{Transcript, TranscriptHasher},
+};
+#[cfg(feature = "prover")]
+use rand::Rng;
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, MulAssign, Sub},
+};
+
+//=========================================================================================
+// Binary tower fields (Wiedemann / Binius): T₀ = GF(2), Tₖ₊₁ = Tₖ[Xₖ] / (Xₖ² + Xₖ₋₁·Xₖ + 1)
+// with X₋₁ = 1, so T₁ = GF(4), T₂ = GF(16), ... T₇ = GF(2¹²⁸)
+// an element of Tₖ is 2ᵏ bits, the low half is the constant term and the high half the Xₖ₋₁
+// coefficient, so every smaller tower sits inside the bigger ones as its low bits
+// addition is xor (characteristic 2: a + a = 0 and subtraction is addition)
+// multiplication splits both sides in halves, Karatsuba on the three half products and reduces
+// with X² = Xₖ₋₂·X + 1, inversion is a^(2^(2ᵏ) - 2)
+// sum-check works unchanged since it only ever evaluates the round polys at 0 and 1, the
+// challenge is the low 2ᵏ bits of the squeeze, so over a small tower it is only as sound as
+// 1 / 2^(2ᵏ) allows per round (B128 for challenges, the small ones for the data)
+//=========================================================================================
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BinaryTower<const LEVEL: u32>(u128);
+
+pub type B1 = BinaryTower<0>;
+pub type B8 = BinaryTower<3>;
+pub type B16 = BinaryTower<4>;
+pub type B32 = BinaryTower<5>;
+pub type B64 = BinaryTower<6>;
+pub type B128 = BinaryTower<7>;
+
+impl<const LEVEL: u32> BinaryTower<LEVEL> {
+    pub const BITS: u32 = 1 << LEVEL;
+    pub const ZERO: Self = BinaryTower(0);
+    pub const ONE: Self = BinaryTower(1);
+
+    const fn mask() -> u128 {
+        if Self::BITS == 128 {
+            u128::MAX
+        } else {
+            (1 << Self::BITS) - 1
+        }
+    }
+
+    // the bits above the tower's width are dropped
+    pub fn new(bits: u128) -> Self {
+        BinaryTower(bits & Self::mask())
+    }
+
+    pub fn bits(&self) -> u128 {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn rand<R: Rng>(rng: &mut R) -> Self {
+        Self::new(((rng.gen::<u64>() as u128) << 64) | rng.gen::<u64>() as u128)
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    // the multiplicative group has order 2^(2ᵏ) - 1, so a⁻¹ = a^(2^(2ᵏ) - 2) = Π a^(2ⁱ) for i ≥ 1
+    pub fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        let mut power = *self;
+        let mut result = Self::ONE;
+        for _ in 1..Self::BITS {
+            power = power.square();
+            result *= power;
+        }
+
+        Some(result)
+    }
+
+    // the same element seen from a bigger tower
+    pub fn lift<const TO: u32>(self) -> BinaryTower<TO> {
+        if TO < LEVEL {
+            panic!("A tower element only lifts into a bigger tower");
+        }
+
+        BinaryTower(self.0)
+    }
+}
+
+fn mul_at(a: u128, b: u128, level: u32) -> u128 {
+    if level == 0 {
+        return a & b & 1;
+    }
+
+    let half = 1u32 << (level - 1);
+    let mask = if half == 64 {
+        u64::MAX as u128
+    } else {
+        (1 << half) - 1
+    };
+    let (a_0, a_1) = (a & mask, a >> half);
+    let (b_0, b_1) = (b & mask, b >> half);
+
+    let lo = mul_at(a_0, b_0, level - 1);
+    let hi = mul_at(a_1, b_1, level - 1);
+    let cross = mul_at(a_0 ^ a_1, b_0 ^ b_1, level - 1) ^ lo ^ hi; // a₀b₁ + a₁b₀
+
+    // a₁b₁·X² = a₁b₁·(Xₖ₋₂·X + 1), where Xₖ₋₂ is the top half bit of the level below
+    let x_below = if level == 1 { 1 } else { 1 << (half / 2) };
+    let new_lo = lo ^ hi;
+    let new_hi = cross ^ mul_at(hi, x_below, level - 1);
+
+    new_lo | (new_hi << half)
+}
+
+impl<const LEVEL: u32> Add for BinaryTower<LEVEL> {
+    type Output = Self;
+
+    // characteristic 2, adding is xor
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self {
+        BinaryTower(self.0 ^ rhs.0)
+    }
+}
+
+impl<const LEVEL: u32> Sub for BinaryTower<LEVEL> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self {
+        BinaryTower(self.0 ^ rhs.0)
+    }
+}
+
+impl<const LEVEL: u32> Mul for BinaryTower<LEVEL> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        BinaryTower(mul_at(self.0, rhs.0, LEVEL))
+    }
+}
+
+impl<const LEVEL: u32> AddAssign for BinaryTower<LEVEL> {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
+    }
+}
+
+impl<const LEVEL: u32> MulAssign for BinaryTower<LEVEL> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const LEVEL: u32> Sum for BinaryTower<LEVEL> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a, const LEVEL: u32> Sum<&'a Self> for BinaryTower<LEVEL> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + *x)
+    }
+}
+
+impl<const LEVEL: u32> fmt::Debug for BinaryTower<LEVEL> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B{}({:#x})", Self::BITS, self.0)
+    }
+}
+
+impl<const LEVEL: u32> SumCheckField for BinaryTower<LEVEL> {
+    // big-endian, whole bytes (B1 to B8 take one byte each)
+    fn transcript_bytes(values: &[Self]) -> Vec<u8> {
+        let num_bytes = (Self::BITS as usize).div_ceil(8);
+        values
+            .iter()
+            .flat_map(|x| x.0.to_be_bytes()[16 - num_bytes..].to_vec())
+            .collect()
+    }
+
+    fn squeeze_challenge<H: TranscriptHasher>(transcript: &mut Transcript<H>) -> Self {
+        let squeeze = transcript.squeeze();
+        let low_bytes: [u8; 16] = squeeze[squeeze.len() - 16..].try_into().unwrap();
+
+        Self::new(u128::from_be_bytes(low_bytes))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gf4_table() {
+        // T₁ = GF(2)[X] / (X² + X + 1), with 2 = X and 3 = X + 1
+        let x = BinaryTower::<1>::new(2);
+        assert_eq!(x * x, BinaryTower::new(3));
+        assert_eq!(x * BinaryTower::new(3), BinaryTower::ONE);
+        assert_eq!(BinaryTower::<1>::new(3).square(), x);
+    }
+
+    fn check_field_axioms<const LEVEL: u32>() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let (a, b, c) = (
+                BinaryTower::<LEVEL>::rand(&mut rng),
+                BinaryTower::<LEVEL>::rand(&mut rng),
+                BinaryTower::<LEVEL>::rand(&mut rng),
+            );
+
+            assert_eq!(a * b, b * a);
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a * (b + c), a * b + a * c);
+            assert_eq!(a + a, BinaryTower::ZERO);
+            assert_eq!(a * BinaryTower::ONE, a);
+            if !a.is_zero() {
+                assert_eq!(a * a.inverse().unwrap(), BinaryTower::ONE);
+            }
+        }
+        assert_eq!(BinaryTower::<LEVEL>::ZERO.inverse(), None);
+    }
+
+    #[test]
+    fn test_field_axioms() {
+        check_field_axioms::<2>();
+        check_field_axioms::<3>();
+        check_field_axioms::<5>();
+        check_field_axioms::<7>();
+    }
+
+    #[test]
+    fn test_subfield_embeds() {
+        // products of B8 elements are the same when computed in B128
+        let mut rng = rand::thread_rng();
+        let (a, b) = (B8::rand(&mut rng), B8::rand(&mut rng));
+        assert_eq!((a * b).lift::<7>(), a.lift::<7>() * b.lift::<7>());
+        assert_eq!(B128::new(u128::MAX).bits(), u128::MAX);
+        assert_eq!(B8::new(0x1ff).bits(), 0xff);
+    }
+}
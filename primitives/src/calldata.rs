@@ -0,0 +1,222 @@
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField, Zero};
+
+use crate::field_hex::byte_len;
+
+//=========================================================================================
+// Proofs as EVM calldata, the Solidity ABI encoding of the arguments of a verify function
+// everything is a 32 byte big endian word:
+//   a field element is its value (not its Montgomery form) left padded to whole words, one word
+//   up to 256 bits and two for the 381 bit base field of BLS12-381 (the 64 bytes of EIP-2537)
+//   a G1 point is x then y in its base field, the point at infinity is (0, 0) as the
+//   precompiles (EIP-196, EIP-2537) expect it
+//   a digest is a bytes32
+// a static value sits in the head, an array is a dynamic T[k][] (T[] for single words): its
+// offset in the head, then its length in elements and its words in the tail, so the bytes are
+// exactly what abi.encode(...) gives for the same arguments in a contract
+// the proofs' to_calldata list their arguments, the order there is the verify signature
+// NOTE: there is no Solidity verifier in the crate, the layouts are the ABI a contract verifier
+// would declare, the transcript it replays is still the Keccak transcript of the proof
+//=========================================================================================
+pub const WORD_LEN: usize = 32;
+
+pub type Word = [u8; WORD_LEN];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiValue {
+    Static(Vec<Word>),
+    Array {
+        element_words: usize, // k in T[k][], the words per element
+        words: Vec<Word>,
+    },
+}
+
+pub fn uint_word(value: u64) -> Word {
+    let mut word = [0u8; WORD_LEN];
+    word[WORD_LEN - 8..].copy_from_slice(&value.to_be_bytes());
+
+    word
+}
+
+// words per element of F, from the modulus so it is the same for every element
+pub fn field_word_count<F: PrimeField>() -> usize {
+    byte_len::<F>().div_ceil(WORD_LEN)
+}
+
+pub fn field_words<F: PrimeField>(value: &F) -> Vec<Word> {
+    let bytes = value.into_bigint().to_bytes_be();
+    let mut padded = vec![0u8; field_word_count::<F>() * WORD_LEN];
+    // above byte_len the bigint is only zero limbs
+    let len = bytes.len().min(padded.len());
+    let start = padded.len() - len;
+    padded[start..].copy_from_slice(&bytes[bytes.len() - len..]);
+
+    padded
+        .chunks(WORD_LEN)
+        .map(|chunk| chunk.try_into().unwrap())
+        .collect()
+}
+
+pub fn point_word_count<G: CurveGroup>() -> usize
+where
+    G::BaseField: PrimeField,
+{
+    2 * field_word_count::<G::BaseField>()
+}
+
+pub fn point_words<G: CurveGroup>(point: &G) -> Vec<Word>
+where
+    G::BaseField: PrimeField,
+{
+    let (x, y) = point
+        .into_affine()
+        .xy()
+        .unwrap_or((G::BaseField::zero(), G::BaseField::zero()));
+
+    [field_words(&x), field_words(&y)].concat()
+}
+
+impl AbiValue {
+    pub fn uint(value: u64) -> Self {
+        AbiValue::Static(vec![uint_word(value)])
+    }
+
+    pub fn digest(digest: &[u8; WORD_LEN]) -> Self {
+        AbiValue::Static(vec![*digest])
+    }
+
+    pub fn field<F: PrimeField>(value: &F) -> Self {
+        AbiValue::Static(field_words(value))
+    }
+
+    pub fn point<G: CurveGroup>(point: &G) -> Self
+    where
+        G::BaseField: PrimeField,
+    {
+        AbiValue::Static(point_words(point))
+    }
+
+    pub fn uints(values: impl IntoIterator<Item = u64>) -> Self {
+        AbiValue::Array {
+            element_words: 1,
+            words: values.into_iter().map(uint_word).collect(),
+        }
+    }
+
+    // elements of `width` field elements each, e.g. the (Wᵢ(r_b), Wᵢ(r_c)) pairs as width 2
+    pub fn fields<'a, F: PrimeField>(
+        values: impl IntoIterator<Item = &'a F>,
+        width: usize,
+    ) -> Self {
+        AbiValue::Array {
+            element_words: width * field_word_count::<F>(),
+            words: values.into_iter().flat_map(field_words).collect(),
+        }
+    }
+
+    pub fn points<'a, G: CurveGroup>(points: impl IntoIterator<Item = &'a G>) -> Self
+    where
+        G::BaseField: PrimeField,
+    {
+        AbiValue::Array {
+            element_words: point_word_count::<G>(),
+            words: points.into_iter().flat_map(point_words).collect(),
+        }
+    }
+
+    fn head_words(&self) -> usize {
+        match self {
+            AbiValue::Static(words) => words.len(),
+            AbiValue::Array { .. } => 1,
+        }
+    }
+}
+
+// abi.encode(values...), offsets are from the start of the encoding
+pub fn abi_encode(values: &[AbiValue]) -> Vec<u8> {
+    let mut head: Vec<Word> = Vec::new();
+    let mut tail: Vec<Word> = Vec::new();
+    let head_len = values.iter().map(AbiValue::head_words).sum::<usize>();
+
+    for value in values {
+        match value {
+            AbiValue::Static(words) => head.extend_from_slice(words),
+            AbiValue::Array {
+                element_words,
+                words,
+            } => {
+                let offset = (head_len + tail.len()) * WORD_LEN;
+                head.push(uint_word(offset as u64));
+                let len = match *element_words {
+                    0 => 0,
+                    element_words => words.len() / element_words,
+                };
+                tail.push(uint_word(len as u64));
+                tail.extend_from_slice(words);
+            }
+        }
+    }
+
+    head.into_iter().chain(tail).flatten().collect()
+}
+
+#[cfg(all(test, feature = "bn254", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use ark_ec::PrimeGroup;
+
+    fn words(bytes: &[u8]) -> Vec<Word> {
+        bytes
+            .chunks(WORD_LEN)
+            .map(|chunk| chunk.try_into().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_abi_encode_matches_solidity() {
+        // abi.encode(uint256(1), uint256[](2, 3), uint256(4))
+        let encoded = abi_encode(&[
+            AbiValue::uint(1),
+            AbiValue::uints([2, 3]),
+            AbiValue::uint(4),
+        ]);
+        let expected: Vec<Word> = [1, 0x60, 4, 2, 2, 3].map(uint_word).to_vec();
+        assert_eq!(words(&encoded), expected);
+
+        // two arrays, the second offset is past the first's tail, an empty one is just its length
+        let encoded = abi_encode(&[AbiValue::uints([7]), AbiValue::uints([])]);
+        let expected: Vec<Word> = [0x40, 0x80, 1, 7, 0].map(uint_word).to_vec();
+        assert_eq!(words(&encoded), expected);
+    }
+
+    #[test]
+    fn test_field_and_point_widths() {
+        use ark_bls12_381::{Fr as BlsFr, G1Projective as BlsG1};
+        use ark_bn254::{Fr, G1Projective};
+
+        assert_eq!(field_word_count::<Fr>(), 1);
+        assert_eq!(field_word_count::<BlsFr>(), 1);
+        assert_eq!(field_words(&Fr::from(258)), vec![uint_word(258)]);
+        // -1 is the modulus minus one, not the Montgomery form
+        assert_eq!(
+            field_words(&-Fr::from(1))[0].to_vec(),
+            (-Fr::from(1)).into_bigint().to_bytes_be()
+        );
+
+        let g = point_words(&G1Projective::generator());
+        assert_eq!(g.len(), point_word_count::<G1Projective>());
+        assert_eq!(
+            point_words(&G1Projective::zero()),
+            vec![[0u8; WORD_LEN]; point_word_count::<G1Projective>()]
+        );
+
+        // an array of points counts points, not words
+        let points = [BlsG1::generator(), BlsG1::generator() + BlsG1::generator()];
+        let encoded = abi_encode(&[AbiValue::points(points.iter())]);
+        assert_eq!(words(&encoded)[1], uint_word(2));
+        assert_eq!(
+            encoded.len(),
+            (2 + 2 * point_word_count::<BlsG1>()) * WORD_LEN
+        );
+    }
+}
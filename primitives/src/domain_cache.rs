@@ -0,0 +1,180 @@
+use ark_ff::FftField;
+use std::{collections::HashMap, sync::Arc};
+
+//=========================================================================================
+// A multiplicative domain {1, ω, ω², ..., ωⁿ⁻¹} with everything the FFT and FRI keep asking for
+// powers[i] = ωⁱ so ω⁻ⁱ = powers[n - i] and -ωⁱ = powers[i + n/2]
+// bit_reversal[i] is i with its log2(n) bits reversed (the input order of the iterative FFT)
+// DomainCache builds each size once, callers hold on to the cache across FFTs / FRI rounds
+// a CosetDomain is shift·{1, ω, ..., ωⁿ⁻¹} on top of a cached Domain, for codewords that must
+// stay off the subgroup (zero-knowledge FRI), squaring it gives the coset shift²·{ω²ʲ} of half the size
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct Domain<F: FftField> {
+    pub size: usize,
+    pub generator: F,
+    pub powers: Vec<F>,
+    pub bit_reversal: Vec<usize>,
+}
+
+impl<F: FftField> Domain<F> {
+    pub fn new(size: usize) -> Self {
+        if !size.is_power_of_two() {
+            panic!("The domain size must be a power of 2");
+        }
+
+        let generator = F::get_root_of_unity(size as u64).unwrap();
+        let mut powers = Vec::with_capacity(size);
+        let mut power = F::one();
+        for _ in 0..size {
+            powers.push(power);
+            power *= generator;
+        }
+
+        let num_bits = size.ilog2();
+        let bit_reversal = (0..size)
+            .map(|i| {
+                if num_bits == 0 {
+                    0
+                } else {
+                    i.reverse_bits() >> (usize::BITS - num_bits)
+                }
+            })
+            .collect();
+
+        Domain {
+            size,
+            generator,
+            powers,
+            bit_reversal,
+        }
+    }
+
+    // ωⁱ
+    pub fn element(&self, i: usize) -> F {
+        self.powers[i % self.size]
+    }
+
+    // ω⁻ⁱ
+    pub fn inverse_element(&self, i: usize) -> F {
+        self.powers[(self.size - i % self.size) % self.size]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CosetDomain<F: FftField> {
+    pub shift: F,
+    pub shift_inv: F,
+    pub domain: Arc<Domain<F>>,
+}
+
+impl<F: FftField> CosetDomain<F> {
+    pub fn new(shift: F, domain: Arc<Domain<F>>) -> Self {
+        CosetDomain {
+            shift,
+            shift_inv: shift.inverse().expect("The coset shift must be nonzero"),
+            domain,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.domain.size
+    }
+
+    // shift * ωⁱ
+    pub fn element(&self, i: usize) -> F {
+        self.shift * self.domain.element(i)
+    }
+
+    // (shift * ωⁱ)⁻¹
+    pub fn inverse_element(&self, i: usize) -> F {
+        self.shift_inv * self.domain.inverse_element(i)
+    }
+
+    // x is on the coset iff (x / shift)ⁿ = 1
+    pub fn contains(&self, x: F) -> bool {
+        (x * self.shift_inv).pow([self.size() as u64]) == F::one()
+    }
+
+    // the domain of the next FRI layer, x ↦ x² over the half size subgroup
+    pub fn square(&self, cache: &mut DomainCache<F>) -> Self {
+        CosetDomain::new(self.shift.square(), cache.get(self.size() / 2))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DomainCache<F: FftField> {
+    domains: HashMap<usize, Arc<Domain<F>>>,
+}
+
+impl<F: FftField> DomainCache<F> {
+    pub fn new() -> Self {
+        DomainCache {
+            domains: HashMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, size: usize) -> Arc<Domain<F>> {
+        self.domains
+            .entry(size)
+            .or_insert_with(|| Arc::new(Domain::new(size)))
+            .clone()
+    }
+
+    pub fn coset(&mut self, size: usize, shift: F) -> CosetDomain<F> {
+        CosetDomain::new(shift, self.get(size))
+    }
+
+    pub fn len(&self) -> usize {
+        self.domains.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.domains.is_empty()
+    }
+}
+
+#[cfg(all(test, feature = "bls12-377"))]
+mod test {
+    use super::*;
+    use ark_bls12_377::Fr;
+    use ark_ff::Field;
+
+    #[test]
+    fn test_domain_tables() {
+        let domain = Domain::<Fr>::new(8);
+
+        assert_eq!(domain.element(3), domain.generator.pow([3]));
+        assert_eq!(domain.element(3) * domain.inverse_element(3), Fr::from(1));
+        assert_eq!(domain.element(4), -Fr::from(1));
+        assert_eq!(domain.bit_reversal, vec![0, 4, 2, 6, 1, 5, 3, 7]);
+    }
+
+    #[test]
+    fn test_coset_domain() {
+        let mut cache = DomainCache::<Fr>::new();
+        let coset = cache.coset(8, Fr::from(7));
+
+        assert_eq!(coset.element(3), Fr::from(7) * coset.domain.element(3));
+        assert_eq!(coset.element(3) * coset.inverse_element(3), Fr::from(1));
+        assert!(coset.contains(coset.element(5)));
+        assert!(!coset.contains(Fr::from(1)));
+
+        let squared = coset.square(&mut cache);
+        assert_eq!(squared.size(), 4);
+        assert_eq!(squared.element(1), coset.element(1).square());
+        assert!(Arc::ptr_eq(&squared.domain, &cache.get(4)));
+    }
+
+    #[test]
+    fn test_cache_reuses_domains() {
+        let mut cache = DomainCache::<Fr>::new();
+
+        let first = cache.get(16);
+        let second = cache.get(16);
+        cache.get(8);
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.len(), 2);
+    }
+}
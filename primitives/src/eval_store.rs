@@ -0,0 +1,192 @@
+use ark_ff::PrimeField;
+use std::{
+    borrow::Cow,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+//=========================================================================================
+// Evaluation vectors that can live in memory or in a file on disk
+// the FRI and GKR provers keep every layer around until the queries / sum-checks need them,
+// with EvalStorage::Disk those layers are spilled to files and read back on demand
+// so only the layer being worked on has to fit in RAM (paid for with disk reads)
+// every element is written uncompressed so element i sits at i * element size in the file
+//=========================================================================================
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum EvalStorage {
+    #[default]
+    Memory,
+    Disk(PathBuf), // directory the spill files are created in, they are removed on drop
+}
+
+#[derive(Debug)]
+pub enum EvalStore<F: PrimeField> {
+    Memory(Vec<F>),
+    Disk(DiskEvals<F>),
+}
+
+#[derive(Debug)]
+pub struct DiskEvals<F: PrimeField> {
+    file: File,
+    path: PathBuf,
+    len: usize,
+    _field: PhantomData<F>,
+}
+
+// spill files of one process never share a name
+static SPILL_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+impl EvalStorage {
+    pub fn store<F: PrimeField>(&self, values: Vec<F>) -> io::Result<EvalStore<F>> {
+        match self {
+            EvalStorage::Memory => Ok(EvalStore::Memory(values)),
+            EvalStorage::Disk(dir) => Ok(EvalStore::Disk(DiskEvals::write(dir, &values)?)),
+        }
+    }
+}
+
+impl<F: PrimeField> EvalStore<F> {
+    pub fn len(&self) -> usize {
+        match self {
+            EvalStore::Memory(values) => values.len(),
+            EvalStore::Disk(disk) => disk.len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, i: usize) -> F {
+        match self {
+            EvalStore::Memory(values) => values[i],
+            EvalStore::Disk(disk) => disk.read_range(i, i + 1)[0],
+        }
+    }
+
+    // a slice of the values, only a disk store allocates
+    pub fn read_range(&self, start: usize, end: usize) -> Cow<'_, [F]> {
+        match self {
+            EvalStore::Memory(values) => Cow::Borrowed(&values[start..end]),
+            EvalStore::Disk(disk) => Cow::Owned(disk.read_range(start, end)),
+        }
+    }
+
+    pub fn values(&self) -> Cow<'_, [F]> {
+        self.read_range(0, self.len())
+    }
+
+    pub fn into_vec(self) -> Vec<F> {
+        match self {
+            EvalStore::Memory(values) => values,
+            EvalStore::Disk(disk) => disk.read_range(0, disk.len),
+        }
+    }
+}
+
+impl<F: PrimeField> DiskEvals<F> {
+    fn element_size() -> usize {
+        F::zero().uncompressed_size()
+    }
+
+    fn write(dir: &Path, values: &[F]) -> io::Result<Self> {
+        let path = dir.join(format!(
+            "evals-{}-{}.bin",
+            std::process::id(),
+            SPILL_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+
+        let mut writer = BufWriter::new(&file);
+        for value in values {
+            value
+                .serialize_uncompressed(&mut writer)
+                .map_err(io::Error::other)?;
+        }
+        writer.flush()?;
+        drop(writer);
+
+        Ok(DiskEvals {
+            file,
+            path,
+            len: values.len(),
+            _field: PhantomData,
+        })
+    }
+
+    // the file was written by this process so a failed read is not something to recover from
+    fn read_range(&self, start: usize, end: usize) -> Vec<F> {
+        if start > end || end > self.len {
+            panic!(
+                "Range {}..{} is out of bounds for {} evaluations",
+                start, end, self.len
+            );
+        }
+
+        let size = Self::element_size();
+        let mut bytes = vec![0u8; (end - start) * size];
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start((start * size) as u64))
+            .and_then(|_| file.read_exact(&mut bytes))
+            .expect("could not read the spilled evaluations back");
+
+        bytes
+            .chunks(size)
+            .map(|chunk| F::deserialize_uncompressed_unchecked(chunk).unwrap())
+            .collect()
+    }
+}
+
+impl<F: PrimeField> Drop for DiskEvals<F> {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test {
+    use super::*;
+    #[cfg(all(test, feature = "bn254"))]
+    use ark_bn254::Fr;
+
+    pub fn disk_storage() -> EvalStorage {
+        EvalStorage::Disk(std::env::temp_dir())
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn test_disk_store_reads_back() {
+        let values: Vec<Fr> = (0..37u64).map(|i| Fr::from(i * i + 3)).collect();
+
+        for storage in [EvalStorage::Memory, disk_storage()] {
+            let store = storage.store(values.clone()).unwrap();
+            assert_eq!(store.len(), values.len());
+            assert_eq!(store.get(11), values[11]);
+            assert_eq!(store.read_range(5, 20).as_ref(), &values[5..20]);
+            assert_eq!(store.into_vec(), values);
+        }
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn test_disk_store_removes_its_file() {
+        let store = disk_storage()
+            .store(vec![Fr::from(1), Fr::from(2)])
+            .unwrap();
+        let path = match &store {
+            EvalStore::Disk(disk) => disk.path.clone(),
+            EvalStore::Memory(_) => unreachable!(),
+        };
+
+        assert!(path.exists());
+        drop(store);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,225 @@
+use crate::multi_linear::SumCheckField;
+
+//=========================================================================================
+// Explain mode: a step by step account of a verifier run, for reading along with the protocol
+// the *_explained verifiers record into an Explain what they are handed and what they derive:
+//   Section:   the part of the protocol that follows (the statement, a GKR layer, the input layer)
+//   Claim:     a value the next checks are about (a claimed sum, W₀(r_a), Wᵢ(r_b))
+//   RoundPoly: the prover's round message as evaluations at 0..=d
+//   Challenge: a verifier challenge squeezed from the transcript
+//   Check:     an equation the verifier tested and whether it held
+// the run stops at the first failed check, so the last step of a rejected proof says why
+// the plain verifiers run with Explain::off(), which records nothing
+// values are SumCheckField::hex_string (field_hex for prime fields), to_text() is for reading, to_json() for tooling
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExplainStep {
+    Section(String),
+    Claim { label: String, value: String },
+    RoundPoly { round: usize, evals: Vec<String> },
+    Challenge { label: String, value: String },
+    Check { label: String, passed: bool },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Explain {
+    enabled: bool,
+    protocol: String,
+    steps: Vec<ExplainStep>,
+}
+
+impl Explain {
+    pub fn off() -> Self {
+        Self::default()
+    }
+
+    pub fn new(protocol: &str) -> Self {
+        Explain {
+            enabled: true,
+            protocol: protocol.to_string(),
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    pub fn steps(&self) -> &[ExplainStep] {
+        &self.steps
+    }
+
+    fn push(&mut self, step: impl FnOnce() -> ExplainStep) {
+        if self.enabled {
+            self.steps.push(step());
+        }
+    }
+
+    pub fn section(&mut self, title: &str) {
+        self.push(|| ExplainStep::Section(title.to_string()));
+    }
+
+    pub fn claim<F: SumCheckField>(&mut self, label: &str, value: F) {
+        self.push(|| ExplainStep::Claim {
+            label: label.to_string(),
+            value: value.hex_string(),
+        });
+    }
+
+    pub fn round_poly<F: SumCheckField>(&mut self, round: usize, evals: &[F]) {
+        self.push(|| ExplainStep::RoundPoly {
+            round,
+            evals: evals.iter().map(SumCheckField::hex_string).collect(),
+        });
+    }
+
+    pub fn challenge<F: SumCheckField>(&mut self, label: &str, value: F) {
+        self.push(|| ExplainStep::Challenge {
+            label: label.to_string(),
+            value: value.hex_string(),
+        });
+    }
+
+    // records the check and hands back the outcome, if !explain.check(..) { return false; }
+    pub fn check(&mut self, label: &str, passed: bool) -> bool {
+        self.push(|| ExplainStep::Check {
+            label: label.to_string(),
+            passed,
+        });
+        passed
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\n", self.protocol);
+        for step in self.steps.iter() {
+            let line = match step {
+                ExplainStep::Section(title) => format!("== {} ==", title),
+                ExplainStep::Claim { label, value } => {
+                    format!("  claim      {} = {}", label, value)
+                }
+                ExplainStep::RoundPoly { round, evals } => {
+                    format!("  round {:<4} g = [{}]", round, evals.join(", "))
+                }
+                ExplainStep::Challenge { label, value } => {
+                    format!("  challenge  {} = {}", label, value)
+                }
+                ExplainStep::Check { label, passed } => format!(
+                    "  check      {}: {}",
+                    label,
+                    if *passed { "ok" } else { "FAILED" }
+                ),
+            };
+            text.push_str(&line);
+            text.push('\n');
+        }
+
+        text
+    }
+
+    // {"protocol": .., "steps": [{"step": "claim", "label": .., "value": ..}, ..]}
+    pub fn to_json(&self) -> String {
+        let steps: Vec<String> = self
+            .steps
+            .iter()
+            .map(|step| match step {
+                ExplainStep::Section(title) => {
+                    format!(
+                        "{{\"step\": \"section\", \"title\": {}}}",
+                        json_string(title)
+                    )
+                }
+                ExplainStep::Claim { label, value } => format!(
+                    "{{\"step\": \"claim\", \"label\": {}, \"value\": \"{}\"}}",
+                    json_string(label),
+                    value
+                ),
+                ExplainStep::RoundPoly { round, evals } => format!(
+                    "{{\"step\": \"round_poly\", \"round\": {}, \"evals\": [{}]}}",
+                    round,
+                    evals
+                        .iter()
+                        .map(|eval| format!("\"{}\"", eval))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                ExplainStep::Challenge { label, value } => format!(
+                    "{{\"step\": \"challenge\", \"label\": {}, \"value\": \"{}\"}}",
+                    json_string(label),
+                    value
+                ),
+                ExplainStep::Check { label, passed } => format!(
+                    "{{\"step\": \"check\", \"label\": {}, \"passed\": {}}}",
+                    json_string(label),
+                    passed
+                ),
+            })
+            .map(|step| format!("    {}", step))
+            .collect();
+
+        format!(
+            "{{\n  \"protocol\": {},\n  \"steps\": [\n{}\n  ]\n}}\n",
+            json_string(&self.protocol),
+            steps.join(",\n")
+        )
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::field_hex::to_hex;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn test_explain_off_records_nothing() {
+        let mut explain = Explain::off();
+        explain.claim("claimed sum", Fr::from(1));
+        assert!(!explain.check("g(0) + g(1) = claim", false));
+        assert!(explain.steps().is_empty());
+    }
+
+    #[test]
+    fn test_explain_renders_text_and_json() {
+        let mut explain = Explain::new("sum-check");
+        explain.section("statement \"s\"");
+        explain.claim("claimed sum", Fr::from(1));
+        explain.round_poly(0, &[Fr::from(0), Fr::from(1)]);
+        explain.check("g(0) + g(1) = claim", true);
+
+        let one = to_hex(&Fr::from(1));
+        let text = explain.to_text();
+        assert!(text.starts_with("sum-check\n== statement \"s\" ==\n"));
+        assert!(text.contains(&format!("claimed sum = {}", one)));
+        assert!(text.ends_with("g(0) + g(1) = claim: ok\n"));
+
+        let json = explain.to_json();
+        assert!(json.contains("\"title\": \"statement \\\"s\\\"\""));
+        assert!(json.contains(&format!(
+            "\"evals\": [\"{}\", \"{}\"]",
+            to_hex(&Fr::from(0)),
+            one
+        )));
+        assert!(json.contains("\"passed\": true"));
+    }
+}
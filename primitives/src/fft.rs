@@ -0,0 +1,208 @@
+use ark_ff::FftField;
+
+use crate::{
+    domain_cache::{Domain, DomainCache},
+    parallel::{Job, Parallelism},
+};
+
+#[derive(Debug)]
+pub struct FastFourierTransform<F: FftField> {
+    pub coefficients: Vec<F>,
+}
+
+impl<F: FftField> FastFourierTransform<F> {
+    pub fn new(coefficients: Vec<F>) -> Self {
+        FastFourierTransform { coefficients }
+    }
+
+    fn is_power_of_two(&self) -> bool {
+        self.coefficients.len().is_power_of_two()
+    }
+
+    // this is the FFT function i.e. converting Coeff => Values
+    pub fn evaluate(&self) -> Self {
+        self.evaluate_with(&mut DomainCache::new())
+    }
+
+    // same as evaluate but the roots of unity come from (and stay in) the cache
+    pub fn evaluate_with(&self, cache: &mut DomainCache<F>) -> Self {
+        if !self.is_power_of_two() {
+            panic!("The computation array must be in the power of 2");
+        }
+
+        let domain = cache.get(self.coefficients.len());
+
+        let mut y = self.coefficients.clone();
+        butterfly(&mut y, &domain, false);
+
+        FastFourierTransform { coefficients: y }
+    }
+
+    // FFTs every column in place (Coeff => Values), all columns must share the same power of 2 length
+    pub fn evaluate_many(columns: &mut [Vec<F>]) {
+        Self::evaluate_many_with(columns, &mut DomainCache::new(), 1);
+    }
+
+    // the twiddles are looked up once for the whole batch and the columns are split over num_threads
+    pub fn evaluate_many_with(
+        columns: &mut [Vec<F>],
+        cache: &mut DomainCache<F>,
+        num_threads: usize,
+    ) {
+        Self::evaluate_many_in(columns, cache, &Parallelism::Threads(num_threads));
+    }
+
+    // same, with the columns split over whatever parallelism says (e.g. the caller's own pool)
+    pub fn evaluate_many_in(
+        columns: &mut [Vec<F>],
+        cache: &mut DomainCache<F>,
+        parallelism: &Parallelism,
+    ) {
+        if columns.is_empty() {
+            return;
+        }
+
+        let n = columns[0].len();
+        if !n.is_power_of_two() || columns.iter().any(|column| column.len() != n) {
+            panic!("Every column must have the same power of 2 length");
+        }
+
+        let domain = cache.get(n);
+        let num_threads = parallelism.threads().clamp(1, columns.len());
+        let chunk_len = columns.len().div_ceil(num_threads);
+        let domain = &domain;
+        let jobs: Vec<Job<'_>> = columns
+            .chunks_mut(chunk_len)
+            .map(|chunk| {
+                Box::new(move || {
+                    for column in chunk.iter_mut() {
+                        butterfly(column, domain, false);
+                    }
+                }) as Job<'_>
+            })
+            .collect();
+        parallelism.run_all(jobs);
+    }
+
+    // This function is the inverse IFFT i.e. converting Values => Coeff, it divides the values by n
+    pub fn interpolate(&self) -> Self {
+        self.interpolate_with(&mut DomainCache::new())
+    }
+
+    pub fn interpolate_with(&self, cache: &mut DomainCache<F>) -> Self {
+        if !self.is_power_of_two() {
+            panic!("The computation array must be in the power of 2");
+        }
+
+        let domain = cache.get(self.coefficients.len());
+        let n_inv = F::from(self.coefficients.len() as u64).inverse().unwrap();
+
+        let mut y = self.coefficients.clone();
+        butterfly(&mut y, &domain, true);
+        let y_divided: Vec<F> = y.iter().map(|elem| *elem * n_inv).collect();
+
+        FastFourierTransform {
+            coefficients: y_divided,
+        }
+    }
+}
+
+//=========================================================================================
+// Iterative Cooley-Tukey: the input is put in bit reversed order, then every level combines
+// pairs of half sized transforms with y[k] = e[k] + ωᵏo[k] and y[k + n/2] = e[k] - ωᵏo[k]
+// (the same even/odd split as the recursive version, without the allocations)
+// inverse uses ω⁻¹ which is just the powers table read backwards
+//=========================================================================================
+fn butterfly<F: FftField>(y: &mut [F], domain: &Domain<F>, inverse: bool) {
+    let n = y.len();
+    for (i, j) in domain.bit_reversal.iter().enumerate() {
+        if i < *j {
+            y.swap(i, *j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let step = n / len;
+        let half = len / 2;
+
+        for start in (0..n).step_by(len) {
+            for k in 0..half {
+                let w = if inverse {
+                    domain.inverse_element(k * step)
+                } else {
+                    domain.element(k * step)
+                };
+
+                let even = y[start + k];
+                let odd = w * y[start + k + half];
+                y[start + k] = even + odd;
+                y[start + k + half] = even - odd;
+            }
+        }
+
+        len *= 2;
+    }
+}
+
+#[cfg(all(test, feature = "bls12-377"))]
+mod tests {
+    use super::*;
+    use ark_bls12_377::Fr;
+
+    #[test]
+    fn test_fft() {
+        let coefficients = vec![Fr::from(5), Fr::from(0), Fr::from(0), Fr::from(2)];
+
+        let fft = FastFourierTransform::new(coefficients.clone());
+        let values = fft.evaluate();
+        dbg!(&values);
+        let interpolated = values.interpolate();
+
+        assert_eq!(interpolated.coefficients, coefficients);
+    }
+
+    #[test]
+    fn test_fft_matches_direct_evaluation() {
+        let coefficients: Vec<Fr> = [3, 1, 4, 1, 5, 9, 2, 6].map(Fr::from).to_vec();
+        let mut cache = DomainCache::new();
+
+        let values = FastFourierTransform::new(coefficients.clone()).evaluate_with(&mut cache);
+        let domain = cache.get(8);
+        for (j, value) in values.coefficients.iter().enumerate() {
+            let x = domain.element(j);
+            let expected = coefficients
+                .iter()
+                .rev()
+                .fold(Fr::from(0), |acc, coeff| acc * x + coeff);
+            assert_eq!(*value, expected);
+        }
+
+        let interpolated = values.interpolate_with(&mut cache);
+        assert_eq!(interpolated.coefficients, coefficients);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_evaluate_many() {
+        let columns: Vec<Vec<Fr>> = (0..5u64)
+            .map(|c| (0..8u64).map(|i| Fr::from(c * 10 + i)).collect())
+            .collect();
+        let expected: Vec<Vec<Fr>> = columns
+            .iter()
+            .map(|column| {
+                FastFourierTransform::new(column.clone())
+                    .evaluate()
+                    .coefficients
+            })
+            .collect();
+
+        let mut sequential = columns.clone();
+        FastFourierTransform::evaluate_many(&mut sequential);
+        assert_eq!(sequential, expected);
+
+        let mut parallel = columns;
+        FastFourierTransform::evaluate_many_with(&mut parallel, &mut DomainCache::new(), 3);
+        assert_eq!(parallel, expected);
+    }
+}
@@ -0,0 +1,137 @@
+use ark_ff::{BigInteger, PrimeField};
+use std::{fmt, str::FromStr};
+
+//=========================================================================================
+// Canonical text form of a field element, for the conformance vectors and the CLI
+// "0x" then the value (not its Montgomery form) big endian in lowercase hex, zero padded to the
+// byte length of the modulus, e.g. 5 in bn254 Fr is "0x" followed by 62 zeros and "05"
+// every element has exactly one string: from_hex refuses a missing prefix, another width,
+// uppercase digits and anything at or above the modulus instead of reducing it
+// the width also says which size of field a vector is over, where decimal to_string() of a small
+// value reads the same in every field
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldHexError {
+    MissingPrefix,
+    WrongLength { expected: usize, found: usize }, // in hex digits, without the prefix
+    InvalidDigit,
+    NotCanonical, // uppercase digits or a value not below the modulus
+}
+
+impl fmt::Display for FieldHexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldHexError::MissingPrefix => write!(f, "a field element must start with 0x"),
+            FieldHexError::WrongLength { expected, found } => write!(
+                f,
+                "expected {} hex digits for this field but found {}",
+                expected, found
+            ),
+            FieldHexError::InvalidDigit => write!(f, "invalid hex digit"),
+            FieldHexError::NotCanonical => {
+                write!(f, "not the canonical encoding of a field element")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FieldHexError {}
+
+// bytes of the modulus, the width of every encoded element
+pub fn byte_len<F: PrimeField>() -> usize {
+    (F::MODULUS_BIT_SIZE as usize).div_ceil(8)
+}
+
+pub fn to_hex<F: PrimeField>(value: &F) -> String {
+    let bytes = value.into_bigint().to_bytes_be();
+    format!("0x{}", hex::encode(&bytes[bytes.len() - byte_len::<F>()..]))
+}
+
+pub fn from_hex<F: PrimeField>(text: &str) -> Result<F, FieldHexError> {
+    let digits = text
+        .strip_prefix("0x")
+        .ok_or(FieldHexError::MissingPrefix)?;
+    if digits.len() != 2 * byte_len::<F>() {
+        return Err(FieldHexError::WrongLength {
+            expected: 2 * byte_len::<F>(),
+            found: digits.len(),
+        });
+    }
+
+    let bytes = hex::decode(digits).map_err(|_| FieldHexError::InvalidDigit)?;
+    let value = F::from_be_bytes_mod_order(&bytes);
+    // a reduced value or an uppercase digit encodes back to another string
+    if to_hex(&value) != text {
+        return Err(FieldHexError::NotCanonical);
+    }
+
+    Ok(value)
+}
+
+// println!("{}", Hex(value)) and "0x..".parse::<Hex<F>>()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hex<F>(pub F);
+
+impl<F: PrimeField> fmt::Display for Hex<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", to_hex(&self.0))
+    }
+}
+
+impl<F: PrimeField> FromStr for Hex<F> {
+    type Err = FieldHexError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        from_hex(text).map(Hex)
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fr;
+
+    fn digits() -> usize {
+        2 * byte_len::<Fr>()
+    }
+
+    #[test]
+    fn test_hex_roundtrip_and_width() {
+        let five = to_hex(&Fr::from(5));
+        assert_eq!(five, format!("0x{}05", "0".repeat(digits() - 2)));
+        assert_eq!(from_hex::<Fr>(&five), Ok(Fr::from(5)));
+        assert_eq!(Hex(-Fr::from(1)).to_string().parse(), Ok(Hex(-Fr::from(1))));
+    }
+
+    #[test]
+    fn test_hex_is_strict() {
+        let five = to_hex(&Fr::from(5));
+        assert_eq!(
+            from_hex::<Fr>(&five[2..]),
+            Err(FieldHexError::MissingPrefix)
+        );
+        assert_eq!(
+            from_hex::<Fr>("0x05"),
+            Err(FieldHexError::WrongLength {
+                expected: digits(),
+                found: 2
+            })
+        );
+        assert_eq!(
+            from_hex::<Fr>(&five.replace("05", "0g")),
+            Err(FieldHexError::InvalidDigit)
+        );
+        assert_eq!(
+            from_hex::<Fr>(&to_hex(&Fr::from(10)).to_uppercase().replace("0X", "0x")),
+            Err(FieldHexError::NotCanonical)
+        );
+
+        // the modulus would reduce to 0, 0 only has the all zeros string
+        let modulus = Fr::MODULUS.to_bytes_be();
+        let modulus = format!(
+            "0x{}",
+            hex::encode(&modulus[modulus.len() - byte_len::<Fr>()..])
+        );
+        assert_eq!(from_hex::<Fr>(&modulus), Err(FieldHexError::NotCanonical));
+    }
+}
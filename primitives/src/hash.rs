@@ -0,0 +1,3 @@
+pub mod generators;
+pub mod hash_to_curve;
+pub mod hash_to_field;
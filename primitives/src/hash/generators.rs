@@ -0,0 +1,78 @@
+use crate::{
+    hash::hash_to_curve::HashToCurveGroup,
+    transcript::{Transcript, DIGEST_LEN},
+};
+use ark_ec::CurveGroup;
+
+//=========================================================================================
+// Commitment generators from a domain string, nothing to sample and nothing to ship
+//   Gᵢ = hash_to_curve(domain || "/" || name || I2OSP(i, 8)) with GENERATORS_DST
+// nobody knows a discrete log relation between hash_to_curve outputs, which is all Pedersen and
+// the inner product argument need, and the verifier rebuilds the same points from the same string
+// every named vector (g, h, ...) is its own sequence, so a larger key from the same domain starts
+// with the points of a smaller one
+// GeneratorSetId is a digest of the points themselves (however they were made), keys carry it and
+// proofs repeat it, so a proof checked against other generators fails on the id instead of on
+// some equation further down
+//=========================================================================================
+const GENERATORS_DST: &[u8] = b"ZK-GENERATORS-V01-CS01-with-XMD:SHA-256_SVDW_RO_";
+const ID_LABEL: &[u8] = b"generator-set-id";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GeneratorSetId(pub [u8; DIGEST_LEN]);
+
+impl GeneratorSetId {
+    pub fn of<'a, G: CurveGroup>(points: impl IntoIterator<Item = &'a G>) -> Self {
+        let mut transcript = Transcript::new();
+        transcript.absorb(ID_LABEL);
+        points
+            .into_iter()
+            .for_each(|point| transcript.absorb_commitment(&point.into_affine()));
+
+        let mut id = [0u8; DIGEST_LEN];
+        id.copy_from_slice(&transcript.squeeze());
+        GeneratorSetId(id)
+    }
+}
+
+pub fn derive_generators<G: HashToCurveGroup>(domain: &[u8], name: &str, count: usize) -> Vec<G> {
+    let prefix = [domain, b"/", name.as_bytes()].concat();
+
+    (0..count as u64)
+        .map(|i| {
+            G::hash_to_curve(
+                &[prefix.as_slice(), &i.to_be_bytes()].concat(),
+                GENERATORS_DST,
+            )
+        })
+        .map(G::from)
+        .collect()
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::G1Projective;
+    use ark_ff::Zero;
+
+    #[test]
+    fn test_derive_generators() {
+        let g = derive_generators::<G1Projective>(b"app", "g", 4);
+        assert_eq!(g.len(), 4);
+        assert!(g.iter().all(|point| !point.is_zero()));
+        assert!((1..4).all(|i| g[i] != g[i - 1]));
+
+        // prefix stable, and separated by name and domain
+        assert_eq!(derive_generators::<G1Projective>(b"app", "g", 2), g[..2]);
+        assert_ne!(derive_generators::<G1Projective>(b"app", "h", 1)[0], g[0]);
+        assert_ne!(derive_generators::<G1Projective>(b"other", "g", 1)[0], g[0]);
+    }
+
+    #[test]
+    fn test_generator_set_id() {
+        let g = derive_generators::<G1Projective>(b"app", "g", 4);
+        assert_eq!(GeneratorSetId::of(&g), GeneratorSetId::of(&g.clone()));
+        assert_ne!(GeneratorSetId::of(&g), GeneratorSetId::of(&g[..3]));
+        assert_ne!(GeneratorSetId::of(&g), GeneratorSetId::of(g.iter().rev()));
+    }
+}
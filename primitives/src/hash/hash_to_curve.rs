@@ -0,0 +1,223 @@
+use crate::hash::hash_to_field::hash_to_field;
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::{BigInteger, Field, LegendreSymbol, PrimeField};
+
+//=========================================================================================
+// hash_to_curve from RFC 9380 (section 3, the random oracle encoding) with the
+// Shallue-van de Woestijne map
+//   u₀, u₁ = hash_to_field(msg, DST, 2) over the base field, P = clear_cofactor(map(u₀) + map(u₁))
+// map is the straight-line SvdW of section 6.6.1, it works on any y² = x³ + Ax + B including
+// A = 0 (which simplified SWU can only do through an isogeny), so one map covers every G1 here
+// Z is the first candidate of find_z_svdw (appendix H.1) in the order 1, -1, 2, -2, ...,
+// Z = 1 for BN254, and the sign of y follows the sign of u (sgn0 is the parity of the integer)
+// the suite in a DST is <curve>_XMD:SHA-256_SVDW_RO_, e.g. BN254G1_XMD:SHA-256_SVDW_RO_
+// NOTE: the RFC gives BLS12-381 its own suite (SSWU over an 11-isogeny), points from here are
+// just as uniform but will not match that suite's vectors
+// NOTE: the candidate selection branches, don't feed it secret messages
+//=========================================================================================
+pub trait HashToCurveGroup: CurveGroup {
+    fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Self::Affine;
+}
+
+impl<P: SWCurveConfig> HashToCurveGroup for Projective<P>
+where
+    P::BaseField: PrimeField,
+{
+    fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Affine<P> {
+        hash_to_curve::<P>(msg, dst)
+    }
+}
+
+pub fn hash_to_curve<P: SWCurveConfig>(msg: &[u8], dst: &[u8]) -> Affine<P>
+where
+    P::BaseField: PrimeField,
+{
+    let map = SvdwMap::new(P::COEFF_A, P::COEFF_B);
+    let u = hash_to_field::<P::BaseField>(msg, dst, 2);
+    let (x_0, y_0) = map.map(u[0]);
+    let (x_1, y_1) = map.map(u[1]);
+
+    (Affine::<P>::new_unchecked(x_0, y_0) + Affine::<P>::new_unchecked(x_1, y_1))
+        .into_affine()
+        .clear_cofactor()
+}
+
+#[derive(Debug, Clone)]
+pub struct SvdwMap<F: PrimeField> {
+    a: F,
+    b: F,
+    z: F,
+    c1: F, // g(Z)
+    c2: F, // -Z / 2
+    c3: F, // sqrt(-g(Z) * (3Z² + 4A)) with sgn0 = 0
+    c4: F, // -4g(Z) / (3Z² + 4A)
+}
+
+impl<F: PrimeField> SvdwMap<F> {
+    pub fn new(a: F, b: F) -> Self {
+        let g = |x: F| (x.square() + a) * x + b;
+        let three_z2_4a = |z: F| F::from(3u64) * z.square() + F::from(4u64) * a;
+        let h = |z: F| -three_z2_4a(z) / (F::from(4u64) * g(z));
+
+        let z = (1u64..)
+            .flat_map(|ctr| [F::from(ctr), -F::from(ctr)])
+            .find(|z| {
+                !g(*z).is_zero()
+                    && !h(*z).is_zero()
+                    && is_square(h(*z))
+                    && (is_square(g(*z)) || is_square(g(-*z / F::from(2u64))))
+            })
+            .unwrap();
+
+        let c1 = g(z);
+        let c3 = (-c1 * three_z2_4a(z))
+            .sqrt()
+            .expect("The SvdW constant -g(Z)(3Z² + 4A) must be a square");
+
+        SvdwMap {
+            a,
+            b,
+            z,
+            c1,
+            c2: -z / F::from(2u64),
+            c3: if sgn0(&c3) { -c3 } else { c3 },
+            c4: -F::from(4u64) * c1 / three_z2_4a(z),
+        }
+    }
+
+    pub fn z(&self) -> F {
+        self.z
+    }
+
+    fn g(&self, x: F) -> F {
+        (x.square() + self.a) * x + self.b
+    }
+
+    // (x, y) with y² = x³ + Ax + B for any u
+    pub fn map(&self, u: F) -> (F, F) {
+        let tv1 = u.square() * self.c1;
+        let tv2 = F::one() + tv1;
+        let tv1 = F::one() - tv1;
+        let tv3 = (tv1 * tv2).inverse().unwrap_or(F::zero()); // inv0
+        let tv4 = u * tv1 * tv3 * self.c3;
+
+        // at least one of the three is on the curve, the first that is wins
+        let x_1 = self.c2 - tv4;
+        let x_2 = self.c2 + tv4;
+        let x = if is_square(self.g(x_1)) {
+            x_1
+        } else if is_square(self.g(x_2)) {
+            x_2
+        } else {
+            (tv2.square() * tv3).square() * self.c4 + self.z
+        };
+
+        let y = self
+            .g(x)
+            .sqrt()
+            .expect("The SvdW map always lands on the curve");
+        let y = if sgn0(&u) == sgn0(&y) { y } else { -y };
+
+        (x, y)
+    }
+}
+
+fn is_square<F: Field>(x: F) -> bool {
+    x.legendre() != LegendreSymbol::QuadraticNonResidue
+}
+
+fn sgn0<F: PrimeField>(x: &F) -> bool {
+    x.into_bigint().is_odd()
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::hash::hash_to_field::field_element_len;
+    use ark_bn254::{Fq, G1Projective};
+
+    const DST: &[u8] = b"QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+
+    #[test]
+    fn test_svdw_lands_on_the_curve() {
+        // y² = x³ + 3 as on BN254, whose suite uses Z = 1
+        let map = SvdwMap::new(Fq::from(0), Fq::from(3));
+        assert_eq!(map.z(), Fq::from(1));
+
+        for u in hash_to_field::<Fq>(b"points", DST, 16)
+            .into_iter()
+            .chain([0, 1].map(Fq::from))
+        {
+            let (x, y) = map.map(u);
+            assert_eq!(y.square(), x.square() * x + Fq::from(3));
+            assert_eq!(sgn0(&y), sgn0(&u));
+        }
+    }
+
+    #[test]
+    fn test_hash_to_field_lengths() {
+        assert_eq!(
+            field_element_len::<Fq>(),
+            (Fq::MODULUS_BIT_SIZE as usize + 135) / 8
+        );
+        let elements = hash_to_field::<Fq>(b"msg", DST, 3);
+        assert_eq!(elements.len(), 3);
+        // the length goes into b₀, asking for fewer elements is not a prefix of asking for more
+        assert_ne!(hash_to_field::<Fq>(b"msg", DST, 1)[0], elements[0]);
+        assert_ne!(elements[0], elements[1]);
+    }
+
+    #[test]
+    fn test_hash_to_curve() {
+        let point = G1Projective::hash_to_curve(b"abc", DST);
+        assert!(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve());
+        assert!(!point.is_zero());
+        assert_eq!(point, G1Projective::hash_to_curve(b"abc", DST));
+        assert_ne!(point, G1Projective::hash_to_curve(b"abd", DST));
+        assert_ne!(point, G1Projective::hash_to_curve(b"abc", b"another DST"));
+    }
+
+    #[test]
+    fn test_hash_to_curve_vectors() {
+        // BN254G1_XMD:SHA-256_SVDW_RO_ run through the RFC 9380 procedure, the RFC has no BN254
+        // suite of its own, the vectors are gnark-crypto's (ecc/bn254/hash_vectors_test.go)
+        let q128 = [b"q128_".as_slice(), &[b'q'; 128]].concat();
+        let a512 = [b"a512_".as_slice(), &[b'a'; 512]].concat();
+        let vectors: [(&[u8], &str, &str); 5] = [
+            (
+                b"",
+                "0a976ab906170db1f9638d376514dbf8c42aef256a54bbd48521f20749e59e86",
+                "02925ead66b9e68bfc309b014398640ab55f6619ab59bc1fab2210ad4c4d53d5",
+            ),
+            (
+                b"abc",
+                "23f717bee89b1003957139f193e6be7da1df5f1374b26a4643b0378b5baf53d1",
+                "04142f826b71ee574452dbc47e05bc3e1a647478403a7ba38b7b93948f4e151d",
+            ),
+            (
+                b"abcdef0123456789",
+                "187dbf1c3c89aceceef254d6548d7163fdfa43084145f92c4c91c85c21442d4a",
+                "0abd99d5b0000910b56058f9cc3b0ab0a22d47cf27615f588924fac1e5c63b4d",
+            ),
+            (
+                &q128,
+                "00fe2b0743575324fc452d590d217390ad48e5a16cf051bee5c40a2eba233f5c",
+                "0794211e0cc72d3cbbdf8e4e5cd6e7d7e78d101ff94862caae8acbe63e9fdc78",
+            ),
+            (
+                &a512,
+                "01b05dc540bd79fd0fea4fbb07de08e94fc2e7bd171fe025c479dc212a2173ce",
+                "1bf028afc00c0f843d113758968f580640541728cfc6d32ced9779aa613cd9b0",
+            ),
+        ];
+
+        let coordinate = |hex: &str| Fq::from_be_bytes_mod_order(&hex::decode(hex).unwrap());
+        for (msg, x, y) in vectors {
+            let point = G1Projective::hash_to_curve(msg, DST);
+            assert_eq!(point.xy(), Some((coordinate(x), coordinate(y))));
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use ark_ff::PrimeField;
+use sha2::{Digest, Sha256};
+
+//=========================================================================================
+// hash_to_field from RFC 9380 (section 5) with expand_message_xmd over SHA-256
+//   expand_message_xmd(msg, DST, n): b₀ = H(Z_pad || msg || I2OSP(n, 2) || 0 || DST'),
+//   b₁ = H(b₀ || 1 || DST') and bᵢ = H((b₀ ⊕ bᵢ₋₁) || i || DST'), the output is the first n
+//   bytes of b₁ || b₂ || ... with DST' = DST || len(DST) and Z_pad the 64 zero bytes of a
+//   SHA-256 block, a DST longer than 255 bytes is replaced by H("H2C-OVERSIZE-DST-" || DST)
+//   hash_to_field(msg, count): count * L uniform bytes, every L byte chunk read big-endian and
+//   reduced mod p with L = ceil((ceil(log2 p) + 128) / 8), so each element is within 2⁻¹²⁸ of
+//   uniform (a single 32 byte digest mod a 254 bit p is not, values below 2²⁵⁶ mod p come up
+//   noticeably more often)
+// the DST names the protocol and the suite, uses with different DSTs never share outputs
+//=========================================================================================
+pub const SECURITY_BITS: usize = 128;
+
+const B_IN_BYTES: usize = 32; // SHA-256 output
+const S_IN_BYTES: usize = 64; // SHA-256 block
+const OVERSIZE_DST_PREFIX: &[u8] = b"H2C-OVERSIZE-DST-";
+
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    let ell = len_in_bytes.div_ceil(B_IN_BYTES);
+    if ell > 255 || len_in_bytes > u16::MAX as usize {
+        panic!("The requested length is too long for expand_message_xmd");
+    }
+
+    let dst = if dst.len() > 255 {
+        Sha256::new()
+            .chain_update(OVERSIZE_DST_PREFIX)
+            .chain_update(dst)
+            .finalize()
+            .to_vec()
+    } else {
+        dst.to_vec()
+    };
+    let dst_prime = [dst.as_slice(), &[dst.len() as u8]].concat();
+
+    let b_0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut uniform_bytes = b_i.to_vec();
+    for i in 2..=ell {
+        let mixed: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+        b_i = Sha256::new()
+            .chain_update(&mixed)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+
+    uniform_bytes
+}
+
+// L, the bytes behind every field element
+pub fn field_element_len<F: PrimeField>() -> usize {
+    (F::MODULUS_BIT_SIZE as usize + SECURITY_BITS).div_ceil(8)
+}
+
+pub fn hash_to_field<F: PrimeField>(msg: &[u8], dst: &[u8], count: usize) -> Vec<F> {
+    let len = field_element_len::<F>();
+
+    expand_message_xmd(msg, dst, count * len)
+        .chunks(len)
+        .map(F::from_be_bytes_mod_order)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DST: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+
+    #[test]
+    fn test_expand_message_xmd_vectors() {
+        // RFC 9380 appendix K.1
+        assert_eq!(
+            hex::encode(expand_message_xmd(b"", DST, 0x20)),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+        assert_eq!(
+            hex::encode(expand_message_xmd(b"abc", DST, 0x20)),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+        assert_eq!(
+            hex::encode(expand_message_xmd(b"", DST, 0x80)),
+            "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbe\
+             e0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18\
+             eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dc\
+             c541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced"
+        );
+    }
+
+    #[test]
+    fn test_long_dst_is_hashed() {
+        let long = vec![b'x'; 300];
+        let hashed = Sha256::new()
+            .chain_update(OVERSIZE_DST_PREFIX)
+            .chain_update(&long)
+            .finalize();
+        assert_eq!(
+            expand_message_xmd(b"msg", &long, 48),
+            expand_message_xmd(b"msg", &hashed, 48)
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//=========================================================================================
+// The boolean hypercube {0, 1}ⁿ and the one place index <-> point conversions live
+// the crate's variable order is MSB-first: variable 0 is the most significant bit of the
+// index, so index 6 over 3 variables is (1, 1, 0), the order of MultiLinearPoly tables,
+// the Lagrange basis of the trusted setup and eq_at
+// LsbFirst is there for data that comes in the other order (bit 0 is variable 0), which is
+// what most other multilinear libraries use: reorder converts tables, the points themselves
+// (x₀, ..., xₙ₋₁) don't change, only which index each one is stored at
+//=========================================================================================
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BitOrder {
+    #[default]
+    MsbFirst,
+    LsbFirst,
+}
+
+// the mask of variable var in an index over num_vars variables
+pub fn var_mask(var: usize, num_vars: usize, order: BitOrder) -> usize {
+    if var >= num_vars {
+        panic!("The variable must be one of the hypercube's variables");
+    }
+
+    match order {
+        BitOrder::MsbFirst => 1 << (num_vars - 1 - var),
+        BitOrder::LsbFirst => 1 << var,
+    }
+}
+
+pub fn index_to_point(index: usize, num_vars: usize, order: BitOrder) -> Vec<bool> {
+    if num_vars < usize::BITS as usize && index >> num_vars != 0 {
+        panic!("The index is not a point of the hypercube");
+    }
+
+    (0..num_vars)
+        .map(|var| index & var_mask(var, num_vars, order) != 0)
+        .collect()
+}
+
+pub fn point_to_index(point: &[bool], order: BitOrder) -> usize {
+    point
+        .iter()
+        .enumerate()
+        .filter(|(_, bit)| **bit)
+        .map(|(var, _)| var_mask(var, point.len(), order))
+        .sum()
+}
+
+// the same point of an n variable hypercube in the other order, i.e. its bits reversed
+pub fn reorder_index(index: usize, num_vars: usize, from: BitOrder, to: BitOrder) -> usize {
+    if from == to {
+        return index;
+    }
+
+    point_to_index(&index_to_point(index, num_vars, from), to)
+}
+
+// a table indexed in order `from` re-indexed in order `to`, e.g. the evaluations of an MLE
+// from a little-endian library: reorder(&evals, BitOrder::LsbFirst, BitOrder::MsbFirst)
+pub fn reorder<T: Clone>(values: &[T], from: BitOrder, to: BitOrder) -> Vec<T> {
+    if from == to {
+        return values.to_vec();
+    }
+    if !values.len().is_power_of_two() {
+        panic!("Only a power of 2 table can change bit order");
+    }
+
+    let num_vars = values.len().ilog2() as usize;
+    (0..values.len())
+        .map(|index| values[reorder_index(index, num_vars, to, from)].clone())
+        .collect()
+}
+
+// every point of {0, 1}ⁿ in index order 0, 1, ..., 2ⁿ - 1
+#[derive(Debug, Clone)]
+pub struct BooleanHypercube {
+    num_vars: usize,
+    order: BitOrder,
+    next: usize,
+}
+
+impl BooleanHypercube {
+    pub fn new(num_vars: usize) -> Self {
+        BooleanHypercube::with_order(num_vars, BitOrder::MsbFirst)
+    }
+
+    pub fn with_order(num_vars: usize, order: BitOrder) -> Self {
+        if num_vars >= usize::BITS as usize {
+            panic!("The hypercube has more points than an index can hold");
+        }
+
+        BooleanHypercube {
+            num_vars,
+            order,
+            next: 0,
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn size(&self) -> usize {
+        1 << self.num_vars
+    }
+}
+
+impl Iterator for BooleanHypercube {
+    type Item = Vec<bool>;
+
+    fn next(&mut self) -> Option<Vec<bool>> {
+        if self.next == self.size() {
+            return None;
+        }
+
+        let point = index_to_point(self.next, self.num_vars, self.order);
+        self.next += 1;
+
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.size() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for BooleanHypercube {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hypercube_order() {
+        let msb: Vec<Vec<bool>> = BooleanHypercube::new(3).collect();
+        assert_eq!(msb.len(), 8);
+        assert_eq!(msb[1], [false, false, true]);
+        assert_eq!(msb[6], [true, true, false]);
+
+        let lsb: Vec<Vec<bool>> = BooleanHypercube::with_order(3, BitOrder::LsbFirst).collect();
+        assert_eq!(lsb[1], [true, false, false]);
+        assert_eq!(lsb[6], [false, true, true]);
+
+        assert_eq!(BooleanHypercube::new(0).collect::<Vec<_>>(), [vec![]]);
+        assert_eq!(BooleanHypercube::new(4).len(), 16);
+    }
+
+    #[test]
+    fn test_index_point_round_trip() {
+        for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+            for (index, point) in BooleanHypercube::with_order(4, order).enumerate() {
+                assert_eq!(point, index_to_point(index, 4, order));
+                assert_eq!(point_to_index(&point, order), index);
+            }
+        }
+
+        assert_eq!(var_mask(0, 3, BitOrder::MsbFirst), 0b100);
+        assert_eq!(var_mask(0, 3, BitOrder::LsbFirst), 0b001);
+    }
+
+    #[test]
+    fn test_reorder() {
+        // 3 variables, LSB-first index 1 is x₀ = 1, which is MSB-first index 4
+        assert_eq!(
+            reorder_index(1, 3, BitOrder::LsbFirst, BitOrder::MsbFirst),
+            4
+        );
+        assert_eq!(
+            reorder_index(6, 3, BitOrder::MsbFirst, BitOrder::MsbFirst),
+            6
+        );
+
+        let values: Vec<usize> = (0..8).collect();
+        let msb = reorder(&values, BitOrder::LsbFirst, BitOrder::MsbFirst);
+        assert_eq!(msb, [0, 4, 2, 6, 1, 5, 3, 7]);
+        assert_eq!(
+            reorder(&msb, BitOrder::MsbFirst, BitOrder::LsbFirst),
+            values
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The index is not a point")]
+    fn test_index_out_of_range() {
+        index_to_point(8, 3, BitOrder::MsbFirst);
+    }
+}
@@ -0,0 +1,184 @@
+use ark_ff::{batch_inversion, PrimeField};
+
+//=========================================================================================
+// Barycentric Lagrange evaluation
+// f(x) = l(x) * Σ wⱼ * yⱼ / (x - xⱼ) with l(x) = Π(x - xⱼ) and wⱼ = 1 / Π_(k≠j)(xⱼ - xₖ)
+// the weights only depend on the points so they are computed once (O(d²)) and reused,
+// every evaluation after that is O(d) with a single inversion
+// the sum-check verifiers get their round polys as [g(0), ..., g(d)] every round over the same
+// points, so one LagrangeWeights::for_degree(d) per proof replaces an interpolate per round
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct LagrangeWeights<F: PrimeField> {
+    xs: Vec<F>,
+    weights: Vec<F>,
+}
+
+impl<F: PrimeField> LagrangeWeights<F> {
+    // the points have to be distinct
+    pub fn new(xs: &[F]) -> Self {
+        let mut weights: Vec<F> = xs
+            .iter()
+            .enumerate()
+            .map(|(j, x_j)| {
+                xs.iter()
+                    .enumerate()
+                    .filter(|(k, _)| *k != j)
+                    .map(|(_, x_k)| *x_j - x_k)
+                    .product()
+            })
+            .collect();
+
+        if weights.iter().any(|w| w.is_zero()) {
+            panic!("Interpolation points must be distinct");
+        }
+        batch_inversion(&mut weights);
+
+        LagrangeWeights {
+            xs: xs.to_vec(),
+            weights,
+        }
+    }
+
+    // points 0, 1, ..., degree i.e. the domain of a round poly in evaluation form
+    pub fn for_degree(degree: usize) -> Self {
+        let xs: Vec<F> = (0..=degree).map(|i| F::from(i as u64)).collect();
+
+        Self::new(&xs)
+    }
+
+    pub fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty()
+    }
+
+    // the poly through (xⱼ, evals[j]) evaluated at x
+    pub fn evaluate(&self, evals: &[F], x: F) -> F {
+        if evals.len() != self.xs.len() {
+            panic!(
+                "Expected {} evaluations, found {}",
+                self.xs.len(),
+                evals.len()
+            );
+        }
+
+        let diffs: Vec<F> = self.xs.iter().map(|x_j| x - x_j).collect();
+        // x is one of the points, l(x) is 0 there so the formula does not apply
+        if let Some(j) = diffs.iter().position(|diff| diff.is_zero()) {
+            return evals[j];
+        }
+
+        let l_x: F = diffs.iter().product();
+        let mut inverses = diffs;
+        batch_inversion(&mut inverses);
+
+        let sum: F = self
+            .weights
+            .iter()
+            .zip(evals.iter())
+            .zip(inverses.iter())
+            .map(|((w_j, y_j), inverse)| *w_j * y_j * inverse)
+            .sum();
+
+        l_x * sum
+    }
+}
+
+// λⱼ such that f(0) = Σ λⱼ * f(xⱼ) for every f of degree < xs.len(), what Shamir reconstruction
+// (and combining anything linear in the shares) needs instead of the whole interpolated poly
+// λⱼ = Π_(k≠j) xₖ / (xₖ - xⱼ) = (Π xₖ) / (xⱼ * Π_(k≠j)(xₖ - xⱼ)), one batch inversion for all of them
+// the points have to be distinct and non zero (0 is where the secret sits)
+pub fn lagrange_coefficients_at_zero<F: PrimeField>(xs: &[F]) -> Vec<F> {
+    let mut denominators: Vec<F> = xs
+        .iter()
+        .enumerate()
+        .map(|(j, x_j)| {
+            let diffs: F = xs
+                .iter()
+                .enumerate()
+                .filter(|(k, _)| *k != j)
+                .map(|(_, x_k)| *x_k - x_j)
+                .product();
+            *x_j * diffs
+        })
+        .collect();
+
+    if denominators.iter().any(|d| d.is_zero()) {
+        panic!("Interpolation points must be distinct and non zero");
+    }
+    batch_inversion(&mut denominators);
+
+    let numerator: F = xs.iter().product();
+    denominators
+        .into_iter()
+        .map(|inverse| numerator * inverse)
+        .collect()
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::UnivariatePoly;
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_matches_interpolate() {
+        // 3 + 2x + 5x^2 + x^3 at 0, 1, 2, 3
+        let evals = [3, 11, 35, 81].map(Fq::from);
+        let weights = LagrangeWeights::for_degree(3);
+        let xs = [0, 1, 2, 3].map(Fq::from);
+        let poly = UnivariatePoly::interpolate(&xs, &evals);
+
+        for x in [Fq::from(7), Fq::from(2), -Fq::from(9)] {
+            assert_eq!(weights.evaluate(&evals, x), poly.evaluate(x));
+        }
+        assert_eq!(weights.evaluate(&evals, Fq::from(7)), Fq::from(605));
+    }
+
+    #[test]
+    fn test_arbitrary_points() {
+        // 2x + 1 through x = 4, 9
+        let weights = LagrangeWeights::new(&[Fq::from(4), Fq::from(9)]);
+
+        assert_eq!(
+            weights.evaluate(&[Fq::from(9), Fq::from(19)], Fq::from(0)),
+            Fq::from(1)
+        );
+    }
+
+    #[test]
+    fn test_coefficients_at_zero() {
+        // 7 + 3x + 2x^2 through x = 2, 5, 6
+        let xs = [2, 5, 6].map(Fq::from);
+        let ys = xs.map(|x| Fq::from(7) + Fq::from(3) * x + Fq::from(2) * x * x);
+        let coefficients = lagrange_coefficients_at_zero(&xs);
+
+        let at_zero: Fq = coefficients
+            .iter()
+            .zip(ys.iter())
+            .map(|(l, y)| *l * y)
+            .sum();
+        assert_eq!(at_zero, Fq::from(7));
+        assert_eq!(
+            at_zero,
+            UnivariatePoly::interpolate(&xs, &ys).evaluate(Fq::from(0))
+        );
+        // a constant poly is its own value at 0
+        assert_eq!(coefficients.iter().sum::<Fq>(), Fq::from(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Interpolation points must be distinct and non zero")]
+    fn test_coefficients_at_zero_rejects_zero() {
+        lagrange_coefficients_at_zero(&[Fq::from(0), Fq::from(1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Interpolation points must be distinct")]
+    fn test_repeated_points() {
+        LagrangeWeights::new(&[Fq::from(1), Fq::from(1)]);
+    }
+}
@@ -0,0 +1,87 @@
+use ark_ff::FftField;
+
+use crate::{domain_cache::DomainCache, fft::FastFourierTransform};
+
+//=========================================================================================
+// Low-degree extension: evaluate f over a domain blowup times larger than its coefficients
+// the coefficients are padded to a power of 2 first, so the domain size is
+// coefficients.len().next_power_of_two() * blowup and evals[j] = f(shift * ωʲ)
+// f(shift * x) = Σ cᵢ * shiftⁱ * xⁱ, so a coset is just an FFT of the scaled coefficients
+// shift = 1 gives the plain subgroup, any shift outside it keeps the LDE off the trace domain
+//=========================================================================================
+pub fn lde<F: FftField>(coefficients: &[F], blowup: usize, coset_shift: F) -> Vec<F> {
+    lde_with(coefficients, blowup, coset_shift, &mut DomainCache::new())
+}
+
+pub fn lde_with<F: FftField>(
+    coefficients: &[F],
+    blowup: usize,
+    coset_shift: F,
+    cache: &mut DomainCache<F>,
+) -> Vec<F> {
+    if coefficients.is_empty() {
+        panic!("Cannot extend an empty polynomial");
+    }
+    if !blowup.is_power_of_two() {
+        panic!("The blowup factor must be a power of 2");
+    }
+
+    let domain_size = lde_domain_size(coefficients.len(), blowup);
+
+    let mut shift_pow = F::one();
+    let mut padded_poly = Vec::with_capacity(domain_size);
+    for coeff in coefficients.iter() {
+        padded_poly.push(*coeff * shift_pow);
+        shift_pow *= coset_shift;
+    }
+    padded_poly.resize(domain_size, F::zero());
+
+    FastFourierTransform::new(padded_poly)
+        .evaluate_with(cache)
+        .coefficients
+}
+
+pub fn lde_domain_size(num_coefficients: usize, blowup: usize) -> usize {
+    num_coefficients.next_power_of_two() * blowup
+}
+
+#[cfg(all(test, feature = "bls12-377"))]
+mod test {
+    use super::*;
+    use ark_bls12_377::Fr;
+
+    // 5 + 3x + 2x^3 + x^4 + 7x^5
+    fn poly_1() -> Vec<Fr> {
+        [5, 3, 0, 2, 1, 7].map(Fr::from).to_vec()
+    }
+
+    fn evaluate(coefficients: &[Fr], x: Fr) -> Fr {
+        coefficients
+            .iter()
+            .rev()
+            .fold(Fr::from(0), |acc, coeff| acc * x + coeff)
+    }
+
+    #[test]
+    fn test_lde_on_subgroup() {
+        let evals = lde(&poly_1(), 4, Fr::from(1));
+        assert_eq!(evals.len(), 32);
+
+        let domain = DomainCache::<Fr>::new().get(32);
+        for (j, eval) in evals.iter().enumerate() {
+            assert_eq!(*eval, evaluate(&poly_1(), domain.element(j)));
+        }
+    }
+
+    #[test]
+    fn test_lde_on_coset() {
+        let shift = Fr::from(7);
+        let evals = lde(&poly_1(), 2, shift);
+        assert_eq!(evals.len(), 16);
+
+        let domain = DomainCache::<Fr>::new().get(16);
+        for (j, eval) in evals.iter().enumerate() {
+            assert_eq!(*eval, evaluate(&poly_1(), shift * domain.element(j)));
+        }
+    }
+}
@@ -0,0 +1,448 @@
+//=========================================================================================
+// The building blocks every protocol shares: univariate and multilinear polys, FFTs and LDEs,
+// Merkle trees, the transcript and its schedules, hashing to fields and curves, and the glue
+// around fields (hex, binary towers, calldata, proof bytes)
+// nothing here knows about a protocol, zk-protocols builds the provers and verifiers on top
+// and re-exports these modules under the same paths
+// the prover feature gates the same things it does there: lde, Merkle tree construction and
+// everything that samples randomness
+//=========================================================================================
+pub mod binary_tower;
+pub mod calldata;
+pub mod domain_cache;
+pub mod eval_store;
+pub mod explain;
+pub mod fft;
+pub mod field_hex;
+pub mod hash;
+pub mod hypercube;
+pub mod lagrange;
+#[cfg(feature = "prover")]
+pub mod lde;
+pub mod merkle_tree;
+pub mod multi_linear;
+pub mod parallel;
+pub mod product_poly;
+pub mod progress;
+pub mod proof_format;
+pub mod security;
+pub mod subproduct_tree;
+pub mod transcript;
+pub mod transcript_schedule;
+pub mod virtual_polynomial;
+
+use ark_ff::PrimeField;
+use std::iter::{Product, Sum};
+use std::ops::{Add, Mul};
+use subproduct_tree::SubproductTree;
+
+// how UnivariatePoly::interpolate_with gets from points to coefficients, both give the same poly
+// Lagrange multiplies out every basis poly, O(n²) and the quickest for a few dozen points
+// SubproductTree is O(n log² n) (see subproduct_tree), for hundreds of Shamir shares and up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    #[default]
+    Lagrange,
+    SubproductTree,
+}
+
+impl Interpolation {
+    // from about this many points on the subproduct tree is the faster one
+    pub const SUBPRODUCT_TREE_FROM: usize = 64;
+
+    pub fn for_len(num_points: usize) -> Self {
+        if num_points >= Self::SUBPRODUCT_TREE_FROM {
+            Interpolation::SubproductTree
+        } else {
+            Interpolation::Lagrange
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnivariatePoly<F: PrimeField> {
+    // 1 coefficient for each power of x
+    pub coefficient: Vec<F>,
+}
+
+impl<F: PrimeField> UnivariatePoly<F> {
+    pub fn new(coefficient: Vec<F>) -> Self {
+        UnivariatePoly { coefficient }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.coefficient.len() - 1
+    }
+
+    pub fn evaluate(&self, x: F) -> F {
+        // self.coefficient
+        //     .iter()
+        //     .enumerate()
+        //     .map(|(i, coeff)| coeff * x.powf(i as f64))
+        //     .sum()
+
+        /*
+            rev() is used here cos the lowest power of x is the first element in the inputted vector e.g. 2x^2 + 3x + 1 => [1, 3, 2]
+            This is then reversed to [2, 3, 1] so that the highest power of x is the first element in the vector
+            We can then run 2 * 2 + 3 = 7; 7 * 2 + 1 = 15 evaluating at x = 2;
+
+            Broader explanation:
+            with 5x^4 + 3x^2 + 7x + 11 = 5x^4 + 0x^3 + 3x^2 + 7x + 11
+            (((5x + 0)x + 3)x + 7)x + 11
+
+            in the program [11, 7, 3, 0, 5] will be inputted but this is then reversed to [5, 0, 3, 7, 11]
+            (((5x + 0)x + 3)x + 7)x + 11 = 5*2 + 0 = 10; 10*2 + 3 = 23; 23*2 + 7 = 53; 53*2 + 11 = 117 evaluating at x = 2
+        */
+        self.coefficient
+            .iter()
+            .rev()
+            .cloned()
+            .reduce(|acc, curr| acc * x + curr)
+            .unwrap()
+    }
+
+    // coefficient of x^degree(), i.e. f evaluated "at infinity" (lim f(x) / x^degree)
+    pub fn leading_coefficient(&self) -> F {
+        self.coefficient[self.degree()]
+    }
+
+    // x^k * f(x)
+    pub fn shift(&self, k: usize) -> Self {
+        UnivariatePoly::new([vec![F::zero(); k], self.coefficient.clone()].concat())
+    }
+
+    // f(x) mod x^k, the terms of degree < k (the zero poly for k = 0)
+    pub fn truncate(&self, k: usize) -> Self {
+        let len = k.min(self.coefficient.len()).max(1);
+        let mut coefficient = self.coefficient[..len].to_vec();
+        if k == 0 {
+            coefficient[0] = F::zero();
+        }
+
+        UnivariatePoly::new(coefficient)
+    }
+
+    // (low, high) with f(x) = low(x) + x^k * high(x) and deg(low) < k
+    // e.g. t(x) = t_lo(x) + x^n * t_mid(x) + x^2n * t_hi(x) for the PLONK quotient,
+    // or the even/odd halves of FRI folding after regrouping
+    pub fn split_at_degree(&self, k: usize) -> (Self, Self) {
+        let high = if k < self.coefficient.len() {
+            self.coefficient[k..].to_vec()
+        } else {
+            vec![F::zero()]
+        };
+
+        (self.truncate(k), UnivariatePoly::new(high))
+    }
+
+    pub fn interpolate(xs: &[F], ys: &[F]) -> Self {
+        Self::interpolate_with(xs, ys, Interpolation::Lagrange)
+    }
+
+    // same poly with the algorithm picked by the caller, Interpolation::for_len(xs.len()) picks by size
+    pub fn interpolate_with(xs: &[F], ys: &[F], method: Interpolation) -> Self {
+        match method {
+            Interpolation::Lagrange => Self::lagrange_interpolate(xs, ys),
+            Interpolation::SubproductTree => SubproductTree::new(xs).interpolate(ys),
+        }
+    }
+
+    fn lagrange_interpolate(xs: &[F], ys: &[F]) -> Self {
+        xs.iter()
+            .zip(ys.iter())
+            .map(|(x, y)| Self::basis(x, xs).scalar_mul(y))
+            .sum()
+    }
+
+    // Multiplies an array with an integer
+    fn scalar_mul(&self, scalar: &F) -> Self {
+        UnivariatePoly {
+            coefficient: self.coefficient.iter().map(|x| *x * scalar).collect(),
+        }
+    }
+
+    /*
+        [1, 2, 3]
+        L_2(x) = (x - 1)(x - 3)
+                 --------------
+                 (2 - 1)(2 - 3)
+        x - 1
+        [-x, 1]
+
+        [1, 2, 3] -> [1, 3] -> [(x - 1), (x - 3)]
+    */
+    fn basis(x: &F, interpolating_set: &[F]) -> Self {
+        //numerator
+        let numerator: UnivariatePoly<F> = interpolating_set
+            .iter()
+            .filter(|val| *val != x)
+            .map(|x_i| UnivariatePoly::new(vec![-*x_i, F::one()]))
+            .product();
+
+        // denominator
+        let denominator = F::one() / numerator.evaluate(*x);
+
+        numerator.scalar_mul(&denominator)
+    }
+}
+
+impl<F: PrimeField> Mul for &UnivariatePoly<F> {
+    type Output = UnivariatePoly<F>;
+
+    // Multiplying two polynomials or arrays
+    fn mul(self, rhs: Self) -> Self::Output {
+        // mul for dense
+        let new_degree = self.degree() + rhs.degree();
+        let mut result = vec![F::zero(); new_degree + 1];
+        for i in 0..self.coefficient.len() {
+            for j in 0..rhs.coefficient.len() {
+                result[i + j] += self.coefficient[i] * rhs.coefficient[j];
+            }
+        }
+        UnivariatePoly {
+            coefficient: result,
+        }
+    }
+}
+
+// This is why it is important to input the array in order of power from smallest to biggest to make it easier to perform actions on them
+impl<F: PrimeField> Add for &UnivariatePoly<F> {
+    type Output = UnivariatePoly<F>;
+
+    // adding two polynomials
+    fn add(self, rhs: Self) -> Self::Output {
+        let (mut bigger, smaller) = if self.degree() < rhs.degree() {
+            (rhs.clone(), self)
+        } else {
+            (self.clone(), rhs)
+        };
+
+        bigger
+            .coefficient
+            .iter_mut()
+            .zip(smaller.coefficient.iter())
+            .for_each(|(b_coeff, s_coeff)| *b_coeff += s_coeff);
+
+        UnivariatePoly::new(bigger.coefficient)
+    }
+}
+
+impl<F: PrimeField> Sum for UnivariatePoly<F> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        let mut result = UnivariatePoly::new(vec![F::zero()]);
+        for item in iter {
+            result = &result + &item;
+        }
+        result
+    }
+}
+
+impl<F: PrimeField> Product for UnivariatePoly<F> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        let mut result = UnivariatePoly::new(vec![F::one()]);
+        for item in iter {
+            result = &result * &item;
+        }
+        result
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use crate::UnivariatePoly;
+    use ark_bn254::Fq;
+    use ark_ff::Field;
+
+    fn poly_1() -> UnivariatePoly<Fq> {
+        // f(x) = 1 + 2x + 3x^2
+        UnivariatePoly {
+            coefficient: vec![Fq::from(1), Fq::from(2), Fq::from(3)],
+        }
+    }
+
+    fn poly_2() -> UnivariatePoly<Fq> {
+        // f(x) = 4x + 3 + 5x^11
+        UnivariatePoly {
+            coefficient: [
+                vec![Fq::from(3), Fq::from(4)],
+                vec![Fq::from(0); 9],
+                vec![Fq::from(5)],
+            ]
+            .concat(),
+        }
+    }
+
+    #[test]
+    fn test_degree() {
+        assert_eq!(poly_1().degree(), 2);
+    }
+
+    #[test]
+    fn test_evaluation() {
+        assert_eq!(poly_1().evaluate(Fq::from(2)), Fq::from(17));
+    }
+
+    #[test]
+    fn test_addition() {
+        // f(x) = 1 + 2x + 3x^2
+        // f(x) = 4x + 3 + 5x^11
+
+        // r(x) = 4 + 6x + 3x^2 + 5x^11
+        assert_eq!(
+            (&poly_1() + &poly_2()).coefficient,
+            [
+                vec![Fq::from(4), Fq::from(6), Fq::from(3)],
+                vec![Fq::from(0); 8],
+                vec![Fq::from(5)]
+            ]
+            .concat()
+        )
+    }
+
+    #[test]
+    fn test_leading_coefficient() {
+        assert_eq!(poly_1().leading_coefficient(), Fq::from(3));
+        assert_eq!(poly_2().leading_coefficient(), Fq::from(5));
+    }
+
+    #[test]
+    fn test_shift_truncate_split() {
+        // x^2 * (1 + 2x + 3x^2)
+        let shifted = poly_1().shift(2);
+        assert_eq!(shifted.coefficient, [0, 0, 1, 2, 3].map(Fq::from).to_vec());
+        assert_eq!(
+            shifted.evaluate(Fq::from(2)),
+            Fq::from(4) * poly_1().evaluate(Fq::from(2))
+        );
+
+        assert_eq!(
+            poly_1().truncate(2).coefficient,
+            [1, 2].map(Fq::from).to_vec()
+        );
+        assert_eq!(poly_1().truncate(7), poly_1());
+        assert_eq!(poly_1().truncate(0).coefficient, vec![Fq::from(0)]);
+
+        // f = low + x^k * high for every k
+        let x = Fq::from(3);
+        for k in 0..14 {
+            let (low, high) = poly_2().split_at_degree(k);
+            assert!(k == 0 || low.degree() < k);
+            assert_eq!(
+                low.evaluate(x) + x.pow([k as u64]) * high.evaluate(x),
+                poly_2().evaluate(x)
+            );
+        }
+    }
+
+    #[test]
+    fn test_mul() {
+        // f(x) = 5 + 2x^2
+        let poly_1 = UnivariatePoly {
+            coefficient: vec![Fq::from(5), Fq::from(0), Fq::from(2)],
+        };
+        // f(x) = 2x + 6
+        let poly_2 = UnivariatePoly {
+            coefficient: vec![Fq::from(6), Fq::from(2)],
+        };
+
+        // r(x) = 30 + 10x + 12x^2 + 4x^3
+        assert_eq!(
+            (&poly_1 * &poly_2).coefficient,
+            vec![Fq::from(30), Fq::from(10), Fq::from(12), Fq::from(4)]
+        );
+    }
+
+    #[test]
+    fn test_interpolate() {
+        // f(x) = 2x
+        // [(2, 4), (4, 8)]
+        let maybe_2x =
+            UnivariatePoly::interpolate(&[Fq::from(2), Fq::from(4)], &[Fq::from(4), Fq::from(8)]);
+        assert_eq!(maybe_2x.coefficient, vec![Fq::from(0), Fq::from(2)]);
+
+        // let new_check = UnivariatePoly::interpolate(vec![0.0, 1.0, 2.0, 3.0, 5.0, 10.0], vec![5.0, 7.0, 21.0, 59.0, 255.0, 2005.0]);
+        // assert_eq!(new_check.coefficient, vec![5.0, 0.0, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_fibonacci() {
+        // f(x) = 1 + x
+        // [(0, 0), (1, 1), (2, 1), (3, 2), (4, 3), (5, 5), (6, 8), (7, 13)]
+
+        let fib = UnivariatePoly::interpolate(
+            &[
+                Fq::from(0),
+                Fq::from(1),
+                Fq::from(2),
+                Fq::from(3),
+                Fq::from(4),
+                Fq::from(5),
+                Fq::from(6),
+                Fq::from(7),
+            ],
+            &[
+                Fq::from(0),
+                Fq::from(1),
+                Fq::from(1),
+                Fq::from(2),
+                Fq::from(3),
+                Fq::from(5),
+                Fq::from(8),
+                Fq::from(13),
+            ],
+        );
+
+        let check_1 = fib.evaluate(Fq::from(4));
+        let check_2 = fib.evaluate(Fq::from(5));
+        let check_3 = fib.evaluate(Fq::from(6));
+        let check_sum = check_1 + check_2;
+
+        assert_eq!(check_3, check_sum);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion `left == right` failed\n  left: 189\n right: 55")]
+    fn test_unequal_output() {
+        let fib = UnivariatePoly::interpolate(
+            &[
+                Fq::from(0),
+                Fq::from(1),
+                Fq::from(2),
+                Fq::from(3),
+                Fq::from(4),
+                Fq::from(5),
+                Fq::from(6),
+                Fq::from(7),
+            ],
+            &[
+                Fq::from(0),
+                Fq::from(1),
+                Fq::from(1),
+                Fq::from(2),
+                Fq::from(3),
+                Fq::from(5),
+                Fq::from(8),
+                Fq::from(13),
+            ],
+        );
+
+        let check_1 = fib.evaluate(Fq::from(7));
+        let check_2 = fib.evaluate(Fq::from(8));
+        let check_3 = fib.evaluate(Fq::from(9));
+        let check_sum = check_1 + check_2;
+
+        assert_eq!(check_3, check_sum);
+    }
+
+    #[test]
+    fn test_gkr_interpolate() {
+        let interpolate = UnivariatePoly::interpolate(
+            &[Fq::from(0), Fq::from(1), Fq::from(2)],
+            &[Fq::from(0), Fq::from(12), Fq::from(48)],
+        );
+        dbg!(&interpolate);
+
+        let check_1 = interpolate.evaluate(Fq::from(4));
+        dbg!(check_1);
+    }
+}
@@ -0,0 +1,635 @@
+use crate::proof_format::{ensure_consumed, read_varint, write_varint, ProofFormatError};
+use sha2::{Digest, Sha256};
+
+pub const HASH_LEN: usize = 32; // Sha256
+
+// domain bytes in front of every hash of the tree, a leaf is H(0x00 || salt || data) and a node
+// is H(0x01 || left || right), so a node's two children can't be handed in as a leaf of the layer
+// below (the second preimage trick on a plain H(data) / H(left || right) tree)
+pub const LEAF_PREFIX: u8 = 0x00;
+pub const NODE_PREFIX: u8 = 0x01;
+// a leaf made of several parts (a row of trace columns, a value per batched polynomial) is
+// H(0x02 || salt || count || len || part || len || part ...) with count and lens as u64 BE
+// the lengths keep ("ab", "c") and ("a", "bc") apart, the own prefix keeps the tuple encoding
+// from colliding with a plain leaf whose bytes happen to look like one
+// the parts are fed to the hasher one by one, a wide row is never copied into one buffer
+pub const TUPLE_LEAF_PREFIX: u8 = 0x02;
+
+//=========================================================================================
+// For Input: [1, 2, 3, 4, 5, 6, 7, 8]
+// layers: vec![
+// Layer 0 (Leaves)
+// vec![L(1), L(2), L(3), L(4), L(5), L(6), L(7), L(8)],
+// Layer 1
+// vec![N(L(1), L(2)), N(L(3), L(4)), N(L(5), L(6)), N(L(7), L(8))],
+// Layer 2
+// vec![N(N(L(1), L(2)), N(L(3), L(4))), N(N(L(5), L(6)), N(L(7), L(8)))],
+// Layer 3 (Root)
+// vec![N(N(N(L(1), L(2)), N(L(3), L(4))), N(N(L(5), L(6)), N(L(7), L(8))))],
+// ],
+// with L(x) = H(LEAF_PREFIX || salt || x) and N(l, r) = H(NODE_PREFIX || l || r)
+// the salt is empty for MerkleTree::new, with_salt takes one and the verifier needs the same
+
+// layers is a Vec<Vec<Vec<u8>>> where
+// the outer Vec represents the layers of the Merkle tree,
+// the middle Vec represents the nodes in a layer, and
+// the inner Vec represents the hash of a node. i.e. hash fn returns Vec<u8>
+//=========================================================================================
+#[derive(Debug)]
+pub struct MerkleTree {
+    pub layers: Vec<Vec<Vec<u8>>>,
+    pub salt: Vec<u8>,
+}
+
+//=========================================================================================
+// siblings refers to the sibling nodes that are required to reconstruct the path
+// from a specific leaf (input) to the root of the tree
+// on the wire a proof is leaf_index as a varint then the siblings back to back, HASH_LEN bytes
+// each, the depth is not sent: the reader knows it from the tree size and takes exactly that
+// many siblings, and an index with bits above the depth is refused (the path never reads them,
+// so they would give the same proof a second encoding)
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub siblings: Vec<Vec<u8>>,
+    pub leaf_index: usize,
+}
+
+//=========================================================================================
+// Several leaves of one tree opened together: the paths are walked up layer by layer and a
+// sibling is only sent when no other opened path already gives it, so two leaves whose paths
+// meet share everything above the meeting point (x and -x in FRI meet at the root's children)
+// siblings are in the order the verifier asks for them, bottom layer first and left to right,
+// the verifier brings its own leaf indices and the depth, neither is taken from the proof
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleMultiProof {
+    pub siblings: Vec<Vec<u8>>,
+}
+
+impl MerkleProof {
+    pub fn depth(&self) -> usize {
+        self.siblings.len()
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofFormatError> {
+        let mut bytes = Vec::with_capacity(self.encoded_len());
+        write_varint(self.leaf_index as u64, &mut bytes);
+        for sibling in self.siblings.iter() {
+            if sibling.len() != HASH_LEN {
+                return Err(ProofFormatError::InconsistentLengths {
+                    expected: HASH_LEN,
+                    found: sibling.len(),
+                });
+            }
+            bytes.extend_from_slice(sibling);
+        }
+
+        Ok(bytes)
+    }
+
+    // reads one proof of the given depth and advances bytes past it
+    pub fn read_from(bytes: &mut &[u8], depth: usize) -> Result<Self, ProofFormatError> {
+        let index = read_varint(bytes)?;
+        if depth < 64 && index >> depth != 0 {
+            return Err(ProofFormatError::LeafIndexOutOfRange { index, depth });
+        }
+
+        let len = depth.saturating_mul(HASH_LEN);
+        if bytes.len() < len {
+            return Err(ProofFormatError::LengthMismatch {
+                len: len as u64,
+                remaining: bytes.len(),
+            });
+        }
+        let (siblings, rest) = bytes.split_at(len);
+        *bytes = rest;
+
+        Ok(MerkleProof {
+            siblings: siblings.chunks(HASH_LEN).map(<[u8]>::to_vec).collect(),
+            leaf_index: index as usize,
+        })
+    }
+
+    pub fn from_bytes(mut bytes: &[u8], depth: usize) -> Result<Self, ProofFormatError> {
+        let proof = Self::read_from(&mut bytes, depth)?;
+        ensure_consumed(bytes)?;
+
+        Ok(proof)
+    }
+
+    pub fn encoded_len(&self) -> usize {
+        let index_bits = usize::BITS - self.leaf_index.leading_zeros();
+        (index_bits as usize).div_ceil(7).max(1) + self.siblings.len() * HASH_LEN
+    }
+}
+
+impl MerkleTree {
+    #[cfg(feature = "prover")]
+    pub fn new(data: &[&[u8]]) -> Self {
+        MerkleTree::with_salt(data, &[])
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn with_salt(data: &[&[u8]], salt: &[u8]) -> Self {
+        let leaves = data
+            .iter()
+            .map(|x| MerkleTree::hash_leaf(salt, x))
+            .collect::<Vec<Vec<u8>>>();
+
+        MerkleTree::from_leaf_hashes(leaves, salt)
+    }
+
+    // one leaf per tuple, see TUPLE_LEAF_PREFIX
+    #[cfg(feature = "prover")]
+    pub fn with_tuples<P: AsRef<[u8]>>(tuples: &[Vec<P>], salt: &[u8]) -> Self {
+        let leaves = tuples
+            .iter()
+            .map(|parts| MerkleTree::hash_leaf_tuple(salt, parts))
+            .collect::<Vec<Vec<u8>>>();
+
+        MerkleTree::from_leaf_hashes(leaves, salt)
+    }
+
+    #[cfg(feature = "prover")]
+    fn from_leaf_hashes(leaves: Vec<Vec<u8>>, salt: &[u8]) -> Self {
+        if leaves.is_empty() {
+            return MerkleTree {
+                layers: vec![],
+                salt: salt.to_vec(),
+            };
+        }
+
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let current_layer = layers.last().unwrap();
+            let mut next_layer = Vec::new();
+
+            let mut i = 0;
+            while i < current_layer.len() {
+                if i + 1 < current_layer.len() {
+                    next_layer.push(MerkleTree::hash_node(
+                        &current_layer[i],
+                        &current_layer[i + 1],
+                    ));
+                    i += 2;
+                } else {
+                    next_layer.push(MerkleTree::hash_node(&current_layer[i], &current_layer[i]));
+                    i += 1;
+                }
+            }
+            layers.push(next_layer);
+        }
+
+        MerkleTree {
+            layers,
+            salt: salt.to_vec(),
+        }
+    }
+
+    pub fn root(&self) -> Option<Vec<u8>> {
+        self.layers.last().and_then(|layer| layer.first().cloned())
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn generate_proof(&self, leaf: &[u8]) -> Option<MerkleProof> {
+        let leaf_hash = MerkleTree::hash_leaf(&self.salt, leaf);
+        let index = self.layers.first()?.iter().position(|x| x == &leaf_hash)?;
+
+        self.generate_proof_at(index)
+    }
+
+    // same as generate_proof but by position, for when the leaves repeat or the caller
+    // only knows where the value sits
+    #[cfg(feature = "prover")]
+    pub fn generate_proof_at(&self, index: usize) -> Option<MerkleProof> {
+        if self.layers.is_empty() || index >= self.layers[0].len() {
+            return None;
+        }
+
+        let mut siblings = Vec::new();
+        let mut current_index = index;
+
+        for layer in self.layers.iter().take(self.layers.len() - 1) {
+            let sibling_index = if current_index.is_multiple_of(2) {
+                current_index + 1
+            } else {
+                current_index - 1
+            };
+
+            let sibling_hash = if sibling_index >= layer.len() {
+                layer[current_index].clone()
+            } else {
+                layer[sibling_index].clone()
+            };
+
+            siblings.push(sibling_hash);
+            current_index /= 2;
+        }
+
+        Some(MerkleProof {
+            siblings,
+            leaf_index: index,
+        })
+    }
+
+    pub fn hash(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+        hasher.finalize().to_vec()
+    }
+
+    pub fn hash_leaf(salt: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update([LEAF_PREFIX]);
+        hasher.update(salt);
+        hasher.update(data);
+        hasher.finalize().to_vec()
+    }
+
+    pub fn hash_leaf_tuple<P: AsRef<[u8]>>(salt: &[u8], parts: &[P]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update([TUPLE_LEAF_PREFIX]);
+        hasher.update(salt);
+        hasher.update((parts.len() as u64).to_be_bytes());
+        for part in parts {
+            let part = part.as_ref();
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part);
+        }
+        hasher.finalize().to_vec()
+    }
+
+    pub fn hash_node(left: &[u8], right: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update([NODE_PREFIX]);
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().to_vec()
+    }
+
+    pub fn verify_proof(&self, leaf_data: &[u8], proof: &MerkleProof, root: &[u8]) -> bool {
+        MerkleTree::verify_salted_path(&self.salt, leaf_data, proof, root)
+    }
+
+    // the check needs nothing but the root, so a verifier without the tree can call it too
+    pub fn verify_path(leaf_data: &[u8], proof: &MerkleProof, root: &[u8]) -> bool {
+        MerkleTree::verify_salted_path(&[], leaf_data, proof, root)
+    }
+
+    pub fn verify_salted_path(
+        salt: &[u8],
+        leaf_data: &[u8],
+        proof: &MerkleProof,
+        root: &[u8],
+    ) -> bool {
+        MerkleTree::verify_leaf_hash(MerkleTree::hash_leaf(salt, leaf_data), proof, root)
+    }
+
+    pub fn verify_tuple_path<P: AsRef<[u8]>>(
+        salt: &[u8],
+        parts: &[P],
+        proof: &MerkleProof,
+        root: &[u8],
+    ) -> bool {
+        MerkleTree::verify_leaf_hash(MerkleTree::hash_leaf_tuple(salt, parts), proof, root)
+    }
+
+    fn verify_leaf_hash(leaf_hash: Vec<u8>, proof: &MerkleProof, root: &[u8]) -> bool {
+        // the path only reads depth bits of the index, the rest must be 0 (see MerkleProof)
+        if proof
+            .leaf_index
+            .checked_shr(proof.depth() as u32)
+            .is_some_and(|high| high != 0)
+        {
+            return false;
+        }
+
+        let mut current_hash = leaf_hash;
+        let mut idx = proof.leaf_index;
+
+        for sibling_hash in &proof.siblings {
+            current_hash = if idx.is_multiple_of(2) {
+                MerkleTree::hash_node(&current_hash, sibling_hash)
+            } else {
+                MerkleTree::hash_node(sibling_hash, &current_hash)
+            };
+            idx /= 2;
+        }
+
+        current_hash == root
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn generate_multiproof(&self, indices: &[usize]) -> Option<MerkleMultiProof> {
+        let mut known: Vec<usize> = indices.to_vec();
+        known.sort_unstable();
+        known.dedup();
+        if known.is_empty() || self.layers.is_empty() || *known.last()? >= self.layers[0].len() {
+            return None;
+        }
+
+        let mut siblings = Vec::new();
+        for layer in self.layers.iter().take(self.layers.len() - 1) {
+            let mut i = 0;
+            while i < known.len() {
+                let index = known[i];
+                if index.is_multiple_of(2) && known.get(i + 1) == Some(&(index + 1)) {
+                    i += 2;
+                    continue;
+                }
+                // an odd sized layer pairs its last node with itself, as in generate_proof_at
+                let sibling_index = index ^ 1;
+                siblings.push(layer.get(sibling_index).unwrap_or(&layer[index]).clone());
+                i += 1;
+            }
+            known = known.iter().map(|index| index / 2).collect();
+            known.dedup();
+        }
+
+        Some(MerkleMultiProof { siblings })
+    }
+
+    // leaves are (index, data) at depth layers below root, every sibling must be used up
+    pub fn verify_multiproof(
+        salt: &[u8],
+        leaves: &[(usize, &[u8])],
+        proof: &MerkleMultiProof,
+        depth: usize,
+        root: &[u8],
+    ) -> bool {
+        let mut known: Vec<(usize, Vec<u8>)> = leaves
+            .iter()
+            .map(|(index, data)| (*index, MerkleTree::hash_leaf(salt, data)))
+            .collect();
+        known.sort_by_key(|(index, _)| *index);
+        // the same index twice must be the same leaf
+        if known
+            .windows(2)
+            .any(|pair| pair[0].0 == pair[1].0 && pair[0].1 != pair[1].1)
+        {
+            return false;
+        }
+        known.dedup_by_key(|(index, _)| *index);
+        if known.is_empty()
+            || known.iter().any(|(index, _)| {
+                index
+                    .checked_shr(depth as u32)
+                    .is_some_and(|high| high != 0)
+            })
+        {
+            return false;
+        }
+
+        let mut siblings = proof.siblings.iter();
+        for _ in 0..depth {
+            let mut parents = Vec::with_capacity(known.len());
+            let mut i = 0;
+            while i < known.len() {
+                let (index, hash) = &known[i];
+                let parent = if index.is_multiple_of(2)
+                    && known.get(i + 1).is_some_and(|(next, _)| *next == index + 1)
+                {
+                    i += 1;
+                    MerkleTree::hash_node(hash, &known[i].1)
+                } else {
+                    let sibling = match siblings.next() {
+                        Some(sibling) => sibling,
+                        None => return false,
+                    };
+                    if index.is_multiple_of(2) {
+                        MerkleTree::hash_node(hash, sibling)
+                    } else {
+                        MerkleTree::hash_node(sibling, hash)
+                    }
+                };
+                parents.push((index / 2, parent));
+                i += 1;
+            }
+            known = parents;
+        }
+
+        siblings.next().is_none() && known.len() == 1 && known[0].1 == root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merkle_tree() {
+        let data: Vec<&[u8]> = vec![
+            b"hello", b"world", b"foo", b"bar", b"baz", b"qux", b"quux", b"corge",
+        ];
+
+        let tree = MerkleTree::new(&data);
+        let root = tree.root().unwrap();
+
+        for leaf in data.iter() {
+            let proof = tree.generate_proof(leaf).unwrap();
+            assert!(tree.verify_proof(leaf, &proof, &root));
+        }
+    }
+
+    #[test]
+    fn test_verify_proof() {
+        // Original inputs
+        let data: Vec<&[u8]> = vec![
+            b"hello", b"world", b"foo", b"bar", b"baz", b"qux", b"quux", b"corge",
+        ];
+
+        // Build the Merkle tree
+        let tree = MerkleTree::new(&data);
+        let input_to_prove = b"foo";
+
+        let root = tree.root().unwrap();
+
+        // Generate the proof
+        let proof = tree.generate_proof(input_to_prove).unwrap();
+        println!(
+            "Proof for 'foo': {:?}",
+            proof.siblings.iter().map(hex::encode).collect::<Vec<_>>()
+        );
+
+        // Verify the proof
+        let is_valid = tree.verify_proof(input_to_prove, &proof, &root);
+        assert!(is_valid);
+    }
+
+    #[test]
+    fn test_verify_proof_num() {
+        let input = [1, 2, 3, 4, 5, 6, 7, 8];
+
+        // Store the String values in a vector to ensure they outlive `data`
+        let string_data: Vec<String> = input.iter().map(|num| num.to_string()).collect();
+
+        // Create the `data` vector from references to the String values
+        let data: Vec<&[u8]> = string_data.iter().map(|s| s.as_bytes()).collect();
+
+        // Build the Merkle tree
+        let tree = MerkleTree::new(&data);
+        let input_to_prove = b"6";
+
+        let root = tree.root().unwrap();
+
+        // Generate the proof
+        let proof = tree.generate_proof(input_to_prove).unwrap();
+        println!(
+            "Proof for '6': {:?}",
+            proof.siblings.iter().map(hex::encode).collect::<Vec<_>>()
+        );
+
+        // Verify the proof
+        let is_valid = tree.verify_proof(input_to_prove, &proof, &root);
+        assert!(is_valid);
+    }
+
+    #[test]
+    fn test_proof_bytes_are_strict() {
+        let data: Vec<&[u8]> = vec![b"hello", b"world", b"foo", b"bar", b"baz"];
+        let tree = MerkleTree::new(&data);
+        let root = tree.root().unwrap();
+        let proof = tree.generate_proof_at(4).unwrap();
+
+        let bytes = proof.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 1 + 3 * HASH_LEN);
+        assert_eq!(bytes.len(), proof.encoded_len());
+        let decoded = MerkleProof::from_bytes(&bytes, 3).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(tree.verify_proof(b"baz", &decoded, &root));
+
+        // one sibling short, one byte too many, a path of another depth
+        assert!(MerkleProof::from_bytes(&bytes[..bytes.len() - 1], 3).is_err());
+        assert!(MerkleProof::from_bytes(&[bytes.clone(), vec![0]].concat(), 3).is_err());
+        assert!(MerkleProof::from_bytes(&bytes, 2).is_err());
+
+        // leaf 4 and leaf 4 + 8 follow the same path, only the first is accepted
+        let mut high = proof.clone();
+        high.leaf_index += 8;
+        assert!(!tree.verify_proof(b"baz", &high, &root));
+        assert!(matches!(
+            MerkleProof::from_bytes(&high.to_bytes().unwrap(), 3),
+            Err(ProofFormatError::LeafIndexOutOfRange {
+                index: 12,
+                depth: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_leaves_and_nodes_are_domain_separated() {
+        let data: Vec<&[u8]> = vec![b"hello", b"world", b"foo", b"bar"];
+        let tree = MerkleTree::new(&data);
+        let root = tree.root().unwrap();
+
+        // a layer 1 node's children presented as a leaf of a one layer shorter path
+        let forged_leaf = [tree.layers[0][0].as_slice(), tree.layers[0][1].as_slice()].concat();
+        let forged = MerkleProof {
+            siblings: vec![tree.layers[1][1].clone()],
+            leaf_index: 0,
+        };
+        assert!(!MerkleTree::verify_path(&forged_leaf, &forged, &root));
+        assert_ne!(MerkleTree::hash_leaf(&[], &forged_leaf), tree.layers[1][0]);
+
+        // a salted tree commits to the same data under another root, and only opens with its salt
+        let salted = MerkleTree::with_salt(&data, b"salt");
+        let salted_root = salted.root().unwrap();
+        assert_ne!(salted_root, root);
+        let proof = salted.generate_proof(b"foo").unwrap();
+        assert!(salted.verify_proof(b"foo", &proof, &salted_root));
+        assert!(MerkleTree::verify_salted_path(
+            b"salt",
+            b"foo",
+            &proof,
+            &salted_root
+        ));
+        assert!(!MerkleTree::verify_path(b"foo", &proof, &salted_root));
+    }
+
+    #[test]
+    fn test_tuple_leaves_are_canonical() {
+        let rows: Vec<Vec<&[u8]>> = vec![
+            vec![b"ab", b"c"],
+            vec![b"a", b"bc"],
+            vec![b"abc"],
+            vec![b"", b"abc"],
+        ];
+        let tree = MerkleTree::with_tuples(&rows, &[]);
+        let root = tree.root().unwrap();
+
+        // the same bytes split differently are different leaves
+        let leaves = &tree.layers[0];
+        for i in 0..leaves.len() {
+            for j in i + 1..leaves.len() {
+                assert_ne!(leaves[i], leaves[j]);
+            }
+        }
+        assert_ne!(leaves[2], MerkleTree::hash_leaf(&[], b"abc"));
+
+        for (i, row) in rows.iter().enumerate() {
+            let proof = tree.generate_proof_at(i).unwrap();
+            assert!(MerkleTree::verify_tuple_path(&[], row, &proof, &root));
+        }
+        let proof = tree.generate_proof_at(0).unwrap();
+        assert!(!MerkleTree::verify_tuple_path(&[], &rows[1], &proof, &root));
+        assert!(!MerkleTree::verify_path(b"abc", &proof, &root));
+    }
+
+    #[test]
+    fn test_multiproof() {
+        let data: Vec<&[u8]> = vec![
+            b"hello", b"world", b"foo", b"bar", b"baz", b"qux", b"quux", b"corge",
+        ];
+        let tree = MerkleTree::new(&data);
+        let root = tree.root().unwrap();
+
+        // 1 and 5 share nothing below the root, 2 and 3 share their parent
+        let proof = tree.generate_multiproof(&[5, 1, 2, 3]).unwrap();
+        let leaves: Vec<(usize, &[u8])> = [1, 2, 3, 5].iter().map(|i| (*i, data[*i])).collect();
+        assert!(MerkleTree::verify_multiproof(
+            &[],
+            &leaves,
+            &proof,
+            3,
+            &root
+        ));
+        assert!(proof.siblings.len() < 4 * 3);
+
+        // a wrong leaf, a moved index, a wrong depth or a leftover sibling all fail
+        let mut wrong = leaves.clone();
+        wrong[0].1 = b"foo";
+        assert!(!MerkleTree::verify_multiproof(
+            &[],
+            &wrong,
+            &proof,
+            3,
+            &root
+        ));
+        let mut moved = leaves.clone();
+        moved[3].0 = 4;
+        assert!(!MerkleTree::verify_multiproof(
+            &[],
+            &moved,
+            &proof,
+            3,
+            &root
+        ));
+        assert!(!MerkleTree::verify_multiproof(
+            &[],
+            &leaves,
+            &proof,
+            2,
+            &root
+        ));
+        let mut extra = proof.clone();
+        extra.siblings.push(root.clone());
+        assert!(!MerkleTree::verify_multiproof(
+            &[],
+            &leaves,
+            &extra,
+            3,
+            &root
+        ));
+    }
+}
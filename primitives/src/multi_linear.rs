@@ -0,0 +1,653 @@
+use crate::{
+    field_hex::to_hex,
+    hypercube::{reorder, var_mask, BitOrder},
+    transcript::{Transcript, TranscriptHasher},
+    UnivariatePoly,
+};
+use ark_ff::{BigInteger, PrimeField};
+use std::{
+    fmt::Debug,
+    iter::Sum,
+    ops::{Add, Mul, Sub},
+};
+
+//=========================================================================================
+// What the multilinear / sum-check layer needs from a field: the ring ops, a way to put
+// elements in the transcript and a way to get a challenge out of it
+// every PrimeField is one (same bytes and challenges as before), binary_tower adds the
+// GF(2^k) towers, where the challenge is the low bits of the squeeze instead of a mod p reduction
+//=========================================================================================
+pub trait SumCheckField:
+    Copy
+    + Debug
+    + PartialEq
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Sum
+    + for<'a> Sum<&'a Self>
+{
+    fn transcript_bytes(values: &[Self]) -> Vec<u8>;
+
+    fn squeeze_challenge<H: TranscriptHasher>(transcript: &mut Transcript<H>) -> Self;
+
+    // how explain and other readable output show one element, 0x and the transcript bytes
+    fn hex_string(&self) -> String {
+        format!("0x{}", hex::encode(Self::transcript_bytes(&[*self])))
+    }
+}
+
+impl<F: PrimeField> SumCheckField for F {
+    fn transcript_bytes(values: &[F]) -> Vec<u8> {
+        MultiLinearPoly::to_bytes(values)
+    }
+
+    fn squeeze_challenge<H: TranscriptHasher>(transcript: &mut Transcript<H>) -> F {
+        transcript.squeeze_field::<F>()
+    }
+
+    fn hex_string(&self) -> String {
+        to_hex(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MultiLinearPoly<F: SumCheckField> {
+    // 2a + 3b
+    // computation is simply passing in 00, 01, 10, 11 and getting the result in an array
+    // 00 -> 0, 01 -> 3, 10 -> 2, 11 -> 5 => [0, 3, 2, 5]
+    pub computation: Vec<F>,
+}
+
+impl<F: SumCheckField> MultiLinearPoly<F> {
+    pub fn new(computation: &[F]) -> Self {
+        if !computation.len().is_power_of_two() {
+            panic!("The computation array must be in the power of 2");
+        }
+        MultiLinearPoly {
+            computation: computation.to_vec(),
+        }
+    }
+
+    // evaluations over the hypercube indexed in `order`, stored MSB-first like every other table
+    pub fn from_evaluations(evaluations: &[F], order: BitOrder) -> Self {
+        MultiLinearPoly::new(&reorder(evaluations, order, BitOrder::MsbFirst))
+    }
+
+    pub fn evaluations(&self, order: BitOrder) -> Vec<F> {
+        reorder(&self.computation, BitOrder::MsbFirst, order)
+    }
+
+    fn variable_count(&self) -> u32 {
+        // if the variable is e.g. a, b = 2
+        // the computation array will have a size of 2^2 = 4
+        // thus the variable count is log2 of the length of the computation array
+        self.computation.len().ilog2()
+    }
+
+    fn get_power(&self, eval_point_index: usize) -> usize {
+        self.variable_count() as usize - eval_point_index - 1
+    }
+
+    pub fn partial_evaluate(&mut self, eval_value: F, eval_value_position: usize) -> Self {
+        let new_length = self.computation.len() / 2;
+        let mut new_computation = Vec::with_capacity(new_length);
+
+        let step = 2u32.pow(self.get_power(eval_value_position) as u32);
+
+        let mut y_1_i = 0;
+
+        while y_1_i < self.computation.len() {
+            let y_1 = self.computation[y_1_i];
+            let y_2 = self.computation[y_1_i + step as usize];
+
+            new_computation.push(y_1 + (y_2 - y_1) * eval_value);
+
+            if (y_1_i + 1) % step as usize == 0 {
+                y_1_i = y_1_i + step as usize + 1;
+            } else {
+                y_1_i += 1;
+            }
+        }
+
+        MultiLinearPoly::new(&new_computation)
+    }
+
+    pub fn evaluate(&mut self, eval_points: &[F]) -> Self {
+        if eval_points.len() != self.variable_count() as usize {
+            panic!("The number of eval points must be equal to the number of variables");
+        }
+
+        let mut this_computation = MultiLinearPoly::new(&self.computation);
+        let mut i = 0;
+
+        while i < eval_points.len() {
+            this_computation = this_computation.partial_evaluate(eval_points[i], 0);
+            i += 1;
+        }
+
+        this_computation
+    }
+
+    // fixes any subset of the variables at once, positions are in the original numbering
+    // (0 is the most significant bit) so nothing shifts as variables are removed
+    // every output entry reads its 2ᵏ inputs (one per assignment of the fixed bits) and folds
+    // them, so the whole table is read once whatever the positions are
+    pub fn evaluate_at(&self, assignments: &[(usize, F)]) -> Self {
+        let num_vars = self.variable_count() as usize;
+        let mut fixed = assignments.to_vec();
+        fixed.sort_by_key(|(position, _)| *position);
+        if fixed.iter().any(|(position, _)| *position >= num_vars)
+            || fixed.windows(2).any(|pair| pair[0].0 == pair[1].0)
+        {
+            panic!("The positions must be distinct variables of the polynomial");
+        }
+
+        let bit = |position: usize| var_mask(position, num_vars, BitOrder::MsbFirst);
+        let free: Vec<usize> = (0..num_vars)
+            .filter(|position| {
+                fixed
+                    .iter()
+                    .all(|(fixed_position, _)| fixed_position != position)
+            })
+            .collect();
+
+        // offset of every assignment of the fixed bits, the first fixed variable is the top bit
+        let offsets: Vec<usize> = (0..1usize << fixed.len())
+            .map(|c| {
+                fixed
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| c >> (fixed.len() - i - 1) & 1 == 1)
+                    .map(|(_, (position, _))| bit(*position))
+                    .sum()
+            })
+            .collect();
+
+        let mut values = Vec::with_capacity(offsets.len());
+        let new_computation = (0..1usize << free.len())
+            .map(|j| {
+                let base: usize = free
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| j >> (free.len() - i - 1) & 1 == 1)
+                    .map(|(_, position)| bit(*position))
+                    .sum();
+
+                values.clear();
+                values.extend(offsets.iter().map(|offset| self.computation[base + offset]));
+                for (_, value) in fixed.iter() {
+                    let half = values.len() / 2;
+                    for t in 0..half {
+                        values[t] = values[t] + (values[t + half] - values[t]) * *value;
+                    }
+                    values.truncate(half);
+                }
+
+                values[0]
+            })
+            .collect::<Vec<F>>();
+
+        MultiLinearPoly::new(&new_computation)
+    }
+
+    // h(x, y) = f(x) * g(y) over the variables of self followed by those of other
+    // index x || y, so every value of self is multiplied by the whole table of other
+    pub fn tensor(&self, other: &Self) -> Self {
+        let computation: Vec<F> = self
+            .computation
+            .iter()
+            .flat_map(|f_x| other.computation.iter().map(move |g_y| *f_x * *g_y))
+            .collect();
+
+        MultiLinearPoly::new(&computation)
+    }
+
+    // the same polynomial over front + n + back variables, it ignores the new ones
+    // front variables are the most significant so the table repeats 2^front times,
+    // back variables repeat every value 2^back times
+    pub fn extend_vars(&self, front: usize, back: usize) -> Self {
+        let computation: Vec<F> = (0..1usize << front)
+            .flat_map(|_| {
+                self.computation
+                    .iter()
+                    .flat_map(move |value| std::iter::repeat_n(*value, 1 << back))
+            })
+            .collect();
+
+        MultiLinearPoly::new(&computation)
+    }
+}
+
+impl<F: PrimeField> MultiLinearPoly<F> {
+    // W restricted to the line ℓ(t) = a + t * b, i.e. q(t) = W(ℓ(t))
+    // every variable is linear in t so deg q <= num_vars, num_vars + 1 evaluations pin it down
+    pub fn restrict_to_line(&self, a: &[F], b: &[F]) -> UnivariatePoly<F> {
+        let num_vars = self.variable_count() as usize;
+        if a.len() != num_vars || b.len() != num_vars {
+            panic!("The line must have one coordinate per variable");
+        }
+
+        let ts: Vec<F> = (0..=num_vars).map(|t| F::from(t as u64)).collect();
+        let evals: Vec<F> = ts
+            .iter()
+            .map(|t| self.clone().evaluate(&line_point(a, b, *t)).computation[0])
+            .collect();
+
+        UnivariatePoly::interpolate(&ts, &evals)
+    }
+
+    pub fn to_bytes(computation: &[F]) -> Vec<u8> {
+        computation
+            .iter()
+            .flat_map(|x| F::into_bigint(*x).to_bytes_be())
+            .collect()
+    }
+}
+
+//=========================================================================================
+// Evaluating many MLEs at the same point
+// f(r) = Σₓ f(x) * χ_x(r), so the 2ⁿ weights χ_x(r) are built once for r (n doublings, 2ⁿ muls)
+// and every MLE at r is then a dot product with its table instead of n folds of the table
+// x is read most significant bit first, the same variable order as MultiLinearPoly
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluationTable<F: PrimeField> {
+    weights: Vec<F>, // χ_x(r) for x in 0..2ⁿ
+}
+
+impl<F: PrimeField> EvaluationTable<F> {
+    pub fn new(point: &[F]) -> Self {
+        let mut weights = vec![F::zero(); 1 << point.len()];
+        weights[0] = F::one();
+
+        // each variable appends one bit to every x, going backwards so nothing is overwritten
+        // before it is read
+        for (j, r) in point.iter().enumerate() {
+            for i in (0..1 << j).rev() {
+                let high = weights[i] * r;
+                weights[2 * i + 1] = high;
+                weights[2 * i] = weights[i] - high;
+            }
+        }
+
+        EvaluationTable { weights }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.weights.len().ilog2() as usize
+    }
+
+    pub fn weights(&self) -> &[F] {
+        &self.weights
+    }
+
+    pub fn evaluate(&self, poly: &MultiLinearPoly<F>) -> F {
+        self.evaluate_slice(&poly.computation)
+    }
+
+    pub fn evaluate_slice(&self, computation: &[F]) -> F {
+        if computation.len() != self.weights.len() {
+            panic!("The number of eval points must be equal to the number of variables");
+        }
+
+        computation
+            .iter()
+            .zip(self.weights.iter())
+            .map(|(value, weight)| *value * weight)
+            .sum()
+    }
+}
+
+// ℓ(t) = a + t * b
+pub fn line_point<F: PrimeField>(a: &[F], b: &[F], t: F) -> Vec<F> {
+    a.iter()
+        .zip(b.iter())
+        .map(|(a_i, b_i)| *a_i + t * b_i)
+        .collect()
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+
+    pub fn setup_mle_poly() -> MultiLinearPoly<Fq> {
+        let computation = vec![
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(4),
+            Fq::from(0),
+            Fq::from(4),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(3),
+            Fq::from(3),
+            Fq::from(5),
+            Fq::from(9),
+            Fq::from(8),
+            Fq::from(12),
+        ];
+
+        MultiLinearPoly::new(&computation)
+    }
+
+    #[test]
+    fn test_partial_evaluate() {
+        // 2a + 3b
+        let computation = vec![Fq::from(0), Fq::from(3), Fq::from(2), Fq::from(5)];
+        let mut multi_linear_poly = MultiLinearPoly::new(&computation);
+
+        let eval_value = Fq::from(1);
+        let eval_value_position = 0;
+        let result = multi_linear_poly.partial_evaluate(eval_value, eval_value_position);
+
+        assert_eq!(result.computation, vec![Fq::from(2), Fq::from(5)]);
+
+        let eval_value_b = Fq::from(4);
+        let eval_value_b_position = 1;
+        let result_b = multi_linear_poly.partial_evaluate(eval_value_b, eval_value_b_position);
+
+        assert_eq!(result_b.computation, vec![Fq::from(12), Fq::from(14)]);
+    }
+
+    #[test]
+    fn test_partial_evaluate_at_last_index() {
+        // 00030025 0 9 0 11 at c = 3
+        let computation = vec![
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(3),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(2),
+            Fq::from(5),
+        ];
+        let mut multi_linear_poly = MultiLinearPoly::new(&computation);
+
+        let eval_value = Fq::from(3);
+        let eval_value_position = 2;
+        let result = multi_linear_poly.partial_evaluate(eval_value, eval_value_position);
+
+        assert_eq!(
+            result.computation,
+            vec![Fq::from(0), Fq::from(9), Fq::from(0), Fq::from(11)]
+        );
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let computation = vec![Fq::from(0), Fq::from(3), Fq::from(2), Fq::from(5)];
+        let mut multi_linear_poly = MultiLinearPoly::new(&computation);
+
+        let eval_points = vec![Fq::from(1), Fq::from(1)];
+        let result = multi_linear_poly.evaluate(&eval_points);
+
+        assert_eq!(result.computation, vec![Fq::from(5)]);
+    }
+
+    #[test]
+    fn test_partial_evaluate_2() {
+        let mut multi_linear_poly = setup_mle_poly();
+
+        let eval_value = Fq::from(4);
+        let eval_value_position = 0;
+        let result = multi_linear_poly.partial_evaluate(eval_value, eval_value_position);
+
+        assert_eq!(
+            result.computation,
+            vec![
+                Fq::from(0),
+                Fq::from(0),
+                Fq::from(12),
+                Fq::from(12),
+                Fq::from(20),
+                Fq::from(24),
+                Fq::from(32),
+                Fq::from(36)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partial_evaluate_2_at_2_points() {
+        let mut multi_linear_poly = setup_mle_poly();
+        let eval_value_position = 0;
+
+        let eval_value_a = Fq::from(4);
+
+        let eval_value_b = Fq::from(2);
+        let result = multi_linear_poly
+            .partial_evaluate(eval_value_a, eval_value_position)
+            .partial_evaluate(eval_value_b, eval_value_position);
+
+        assert_eq!(
+            result.computation,
+            vec![Fq::from(40), Fq::from(48), Fq::from(52), Fq::from(60)]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_2() {
+        let mut multi_linear_poly = setup_mle_poly();
+
+        let eval_points = vec![Fq::from(4), Fq::from(2), Fq::from(6), Fq::from(1)];
+        let result = multi_linear_poly.evaluate(&eval_points);
+
+        assert_eq!(result.computation, vec![Fq::from(120)]);
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        let computation = vec![Fq::from(5)];
+        let bytes = MultiLinearPoly::to_bytes(&computation);
+
+        assert_eq!(
+            bytes,
+            vec![
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 5
+            ]
+        );
+    }
+
+    // Test for understanding GKR
+    #[test]
+    fn test_mul_add_evaluate() {
+        let computation = vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(3)];
+        let mut poly = MultiLinearPoly::new(&computation);
+
+        // using a random challenge r = 2 for a
+        let eval_value = Fq::from(0);
+        let eval_position = 0;
+        let result = poly.partial_evaluate(eval_value, eval_position);
+
+        dbg!(result);
+        // 21888242871839275222246405745257275088696311157297823662689037894645226208582
+    }
+
+    #[test]
+    fn test_w_evaluate() {
+        let computation = vec![Fq::from(3), Fq::from(7), Fq::from(11), Fq::from(56)];
+        let mut poly = MultiLinearPoly::new(&computation);
+
+        let eval_points = vec![Fq::from(1), Fq::from(1)];
+        let result = poly.evaluate(&eval_points);
+
+        assert_eq!(result.computation, vec![Fq::from(56)]);
+    }
+
+    #[test]
+    fn test_w_evaluate2() {
+        let computation = vec![Fq::from(0), Fq::from(12)];
+        let mut poly = MultiLinearPoly::new(&computation);
+
+        let eval_points = vec![Fq::from(2)];
+        let result = poly.evaluate(&eval_points);
+
+        dbg!(result);
+    }
+
+    #[test]
+    fn test_evaluate_kzg() {
+        let computation = vec![
+            Fq::from(-72),
+            Fq::from(-68),
+            Fq::from(-72),
+            Fq::from(-68),
+            Fq::from(-72),
+            Fq::from(-68),
+            Fq::from(-69),
+            Fq::from(-65),
+        ];
+        let mut poly = MultiLinearPoly::new(&computation);
+
+        let eval_point = Fq::from(6);
+        let result = poly.partial_evaluate(eval_point, 0);
+        dbg!(&result);
+    }
+
+    #[test]
+    fn test_evaluate_at() {
+        let poly = setup_mle_poly();
+        let (r_1, r_3) = (Fq::from(5), Fq::from(-3));
+
+        // same as folding position 3 first then position 1, which does not move
+        let expected = poly
+            .clone()
+            .partial_evaluate(r_3, 3)
+            .partial_evaluate(r_1, 1);
+        assert_eq!(poly.evaluate_at(&[(1, r_1), (3, r_3)]), expected);
+        assert_eq!(poly.evaluate_at(&[(3, r_3), (1, r_1)]), expected);
+
+        // fixing everything is evaluate, fixing nothing is the identity
+        let point = vec![Fq::from(2), Fq::from(7), Fq::from(-1), Fq::from(4)];
+        let all: Vec<(usize, Fq)> = point.iter().copied().enumerate().collect();
+        assert_eq!(poly.evaluate_at(&all), poly.clone().evaluate(&point));
+        assert_eq!(poly.evaluate_at(&[]), poly);
+
+        // r_b on the first half of the variables leaves c free
+        let (r_b, c) = point.split_at(2);
+        let restricted = poly.evaluate_at(&[(0, r_b[0]), (1, r_b[1])]);
+        assert_eq!(
+            restricted.clone().evaluate(c),
+            poly.clone().evaluate(&point)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The positions must be distinct")]
+    fn test_evaluate_at_repeated_position() {
+        setup_mle_poly().evaluate_at(&[(2, Fq::from(1)), (2, Fq::from(3))]);
+    }
+
+    #[test]
+    fn test_evaluations_in_order() {
+        // 2a + 3b + 5c, a little-endian library lists f(a, b, c) at index a + 2b + 4c
+        let f = |a: u64, b: u64, c: u64| Fq::from(2 * a + 3 * b + 5 * c);
+        let lsb: Vec<Fq> = (0..8u64)
+            .map(|i| f(i & 1, (i >> 1) & 1, (i >> 2) & 1))
+            .collect();
+
+        let poly = MultiLinearPoly::from_evaluations(&lsb, BitOrder::LsbFirst);
+        assert_eq!(poly.computation[1], f(0, 0, 1));
+        assert_eq!(poly.evaluations(BitOrder::LsbFirst), lsb);
+
+        // only the table moved, the point is still (a, b, c)
+        let (a, b, c) = (Fq::from(7), Fq::from(-2), Fq::from(10));
+        assert_eq!(
+            poly.clone().evaluate(&[a, b, c]).computation[0],
+            Fq::from(2) * a + Fq::from(3) * b + Fq::from(5) * c
+        );
+    }
+
+    #[test]
+    fn test_tensor() {
+        // (2a + 3b) * (1 + c)
+        let f = MultiLinearPoly::new(&[0, 3, 2, 5].map(Fq::from));
+        let g = MultiLinearPoly::new(&[1, 2].map(Fq::from));
+        let h = f.tensor(&g);
+        assert_eq!(h.computation, [0, 0, 3, 6, 2, 4, 5, 10].map(Fq::from));
+
+        let (x, y) = ([Fq::from(4), Fq::from(-7)], [Fq::from(9)]);
+        assert_eq!(
+            h.clone().evaluate(&[x[0], x[1], y[0]]),
+            MultiLinearPoly::new(&[
+                f.clone().evaluate(&x).computation[0] * g.clone().evaluate(&y).computation[0]
+            ])
+        );
+    }
+
+    #[test]
+    fn test_extend_vars() {
+        let f = MultiLinearPoly::new(&[0, 3, 2, 5].map(Fq::from));
+
+        let extended = f.extend_vars(1, 2);
+        assert_eq!(extended.computation.len(), 32);
+
+        // the new variables can be anything, the value is f at the middle ones
+        let point = [
+            Fq::from(11),
+            Fq::from(2),
+            Fq::from(-3),
+            Fq::from(8),
+            Fq::from(6),
+        ];
+        assert_eq!(
+            extended.clone().evaluate(&point),
+            f.clone().evaluate(&point[1..3])
+        );
+
+        // the tensor with the constant 1 over k variables
+        let ones = MultiLinearPoly::new(&[Fq::from(1); 4]);
+        assert_eq!(f.extend_vars(0, 2), f.tensor(&ones));
+        assert_eq!(f.extend_vars(2, 0), ones.tensor(&f));
+        assert_eq!(f.extend_vars(0, 0), f);
+    }
+
+    #[test]
+    fn test_restrict_to_line() {
+        let poly = setup_mle_poly();
+        let a = vec![Fq::from(2), Fq::from(5), Fq::from(1), Fq::from(3)];
+        let b = vec![Fq::from(1), Fq::from(-4), Fq::from(7), Fq::from(0)];
+
+        let restricted = poly.restrict_to_line(&a, &b);
+        assert!(restricted.degree() <= 4);
+
+        for t in [0, 1, 9, 23] {
+            let t = Fq::from(t);
+            let expected = poly.clone().evaluate(&line_point(&a, &b, t)).computation[0];
+            assert_eq!(restricted.evaluate(t), expected);
+        }
+    }
+
+    #[test]
+    fn test_evaluation_table() {
+        let point = vec![Fq::from(3), Fq::from(-2), Fq::from(7), Fq::from(11)];
+        let table = EvaluationTable::new(&point);
+        assert_eq!(table.num_vars(), 4);
+
+        let mut poly = setup_mle_poly();
+        assert_eq!(table.evaluate(&poly), poly.evaluate(&point).computation[0]);
+
+        // the weights are χ_x(r), summing to 1
+        assert_eq!(table.weights().iter().sum::<Fq>(), Fq::from(1));
+    }
+
+    #[test]
+    fn test_evaluate_kzg_2() {
+        let computation = vec![Fq::from(-72), Fq::from(-68), Fq::from(-54), Fq::from(-50)];
+        let mut poly = MultiLinearPoly::new(&computation);
+
+        let eval_point = Fq::from(4);
+        let result = poly.partial_evaluate(eval_point, 0);
+        dbg!(&result);
+    }
+}
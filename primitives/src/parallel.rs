@@ -0,0 +1,147 @@
+use std::{fmt, ops::Range, sync::Arc};
+
+//=========================================================================================
+// Parallelism: how many threads a prover may use and who runs them
+//   Sequential (the default): everything on the calling thread
+//   Threads(n):               at most n scoped std threads per batch of work, joined before
+//                             the batch returns so nothing outlives the call
+//   Pool(executor):           the jobs go to the caller's own pool (e.g. a rayon pool wrapped in
+//                             an Executor) and the library never spawns a thread itself
+// work is only ever split into at most threads() contiguous ranges, each a single job, and the
+// results are combined in range order so the output does not depend on the level
+//=========================================================================================
+pub type Job<'a> = Box<dyn FnOnce() + Send + 'a>;
+
+// a rayon pool is pool.scope(|s| for job in jobs { s.spawn(move |_| job()) })
+pub trait Executor: Send + Sync {
+    // how many jobs are worth handing over at once
+    fn num_threads(&self) -> usize;
+
+    // must have run every job to completion when it returns
+    fn run_all<'a>(&self, jobs: Vec<Job<'a>>);
+}
+
+#[derive(Clone, Default)]
+pub enum Parallelism {
+    #[default]
+    Sequential,
+    Threads(usize),
+    Pool(Arc<dyn Executor>),
+}
+
+impl fmt::Debug for Parallelism {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Parallelism::Sequential => write!(f, "Sequential"),
+            Parallelism::Threads(n) => write!(f, "Threads({})", n),
+            Parallelism::Pool(pool) => write!(f, "Pool({} threads)", pool.num_threads()),
+        }
+    }
+}
+
+impl Parallelism {
+    pub fn threads(&self) -> usize {
+        match self {
+            Parallelism::Sequential => 1,
+            Parallelism::Threads(n) => (*n).max(1),
+            Parallelism::Pool(pool) => pool.num_threads().max(1),
+        }
+    }
+
+    pub fn run_all(&self, jobs: Vec<Job<'_>>) {
+        match self {
+            Parallelism::Sequential => jobs.into_iter().for_each(|job| job()),
+            Parallelism::Threads(_) => {
+                if jobs.len() <= 1 {
+                    return jobs.into_iter().for_each(|job| job());
+                }
+                std::thread::scope(|scope| {
+                    for job in jobs {
+                        scope.spawn(job);
+                    }
+                });
+            }
+            Parallelism::Pool(pool) => pool.run_all(jobs),
+        }
+    }
+
+    // 0..len cut into at most threads() ranges of at least min_len (the last may be shorter),
+    // f over every range, the results in range order
+    pub fn map_ranges<T: Send>(
+        &self,
+        len: usize,
+        min_len: usize,
+        f: impl Fn(Range<usize>) -> T + Sync,
+    ) -> Vec<T> {
+        let num_jobs = self.threads().min(len / min_len.max(1)).max(1);
+        let range_len = len.div_ceil(num_jobs).max(1);
+        let ranges: Vec<Range<usize>> = (0..len)
+            .step_by(range_len)
+            .map(|start| start..(start + range_len).min(len))
+            .collect();
+        if ranges.len() <= 1 {
+            return ranges.into_iter().map(&f).collect();
+        }
+
+        let mut results: Vec<Option<T>> = ranges.iter().map(|_| None).collect();
+        let f = &f;
+        let jobs: Vec<Job<'_>> = results
+            .iter_mut()
+            .zip(ranges)
+            .map(|(result, range)| Box::new(move || *result = Some(f(range))) as Job<'_>)
+            .collect();
+        self.run_all(jobs);
+
+        results
+            .into_iter()
+            .map(|result| result.expect("The executor must run every job"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // runs the jobs itself and counts them, standing in for a caller's pool
+    struct CountingPool(AtomicUsize);
+
+    impl Executor for CountingPool {
+        fn num_threads(&self) -> usize {
+            3
+        }
+
+        fn run_all<'a>(&self, jobs: Vec<Job<'a>>) {
+            self.0.fetch_add(jobs.len(), Ordering::SeqCst);
+            jobs.into_iter().for_each(|job| job());
+        }
+    }
+
+    fn sum_of_squares(parallelism: &Parallelism) -> u64 {
+        parallelism
+            .map_ranges(1000, 16, |range| range.map(|i| (i * i) as u64).sum::<u64>())
+            .iter()
+            .sum()
+    }
+
+    #[test]
+    fn test_levels_agree() {
+        let expected = sum_of_squares(&Parallelism::Sequential);
+        assert_eq!(sum_of_squares(&Parallelism::Threads(4)), expected);
+        assert_eq!(sum_of_squares(&Parallelism::Threads(0)), expected);
+
+        let pool = Arc::new(CountingPool(AtomicUsize::new(0)));
+        assert_eq!(sum_of_squares(&Parallelism::Pool(pool.clone())), expected);
+        assert_eq!(pool.0.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_ranges_respect_the_minimum() {
+        let ranges = Parallelism::Threads(8).map_ranges(10, 4, |range| range);
+        assert_eq!(ranges, vec![0..5, 5..10]);
+
+        let ranges = Parallelism::Threads(8).map_ranges(3, 4, |range| range);
+        assert_eq!(ranges, vec![0..3]);
+    }
+}
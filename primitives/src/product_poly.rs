@@ -23,15 +23,9 @@ pub struct ProductPoly<F: PrimeField> {
 impl<F: PrimeField> ProductPoly<F> {
     pub fn new(poly_array: Vec<MultiLinearPoly<F>>) -> Self {
         let expected_len = poly_array[0].computation.len();
-        assert!(
-            poly_array
-                .iter()
-                .map(|p| p.computation.len())
-                .filter(|len| *len != expected_len)
-                .collect::<Vec<_>>()
-                .len()
-                == 0
-        );
+        assert!(poly_array
+            .iter()
+            .all(|p| p.computation.len() == expected_len));
 
         ProductPoly { poly_array }
     }
@@ -94,17 +88,15 @@ impl<F: PrimeField> ProductPoly<F> {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "bn254"))]
 mod test {
     use super::*;
     use ark_bn254::Fq;
 
     #[test]
     fn test_product_poly() {
-        let poly_1 =
-            MultiLinearPoly::new(&vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(2)]);
-        let poly_2 =
-            MultiLinearPoly::new(&vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(3)]);
+        let poly_1 = MultiLinearPoly::new(&[Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(2)]);
+        let poly_2 = MultiLinearPoly::new(&[Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(3)]);
         let product_poly = ProductPoly::new(vec![poly_1, poly_2]);
         let result = product_poly.univariate_to_evaluation();
         assert_eq!(result, vec![Fq::from(0), Fq::from(6), Fq::from(24)]);
@@ -112,8 +104,8 @@ mod test {
 
     #[test]
     fn test_product_poly2() {
-        let poly_1 = MultiLinearPoly::new(&vec![Fq::from(0), Fq::from(8)]);
-        let poly_2 = MultiLinearPoly::new(&vec![Fq::from(0), Fq::from(12)]);
+        let poly_1 = MultiLinearPoly::new(&[Fq::from(0), Fq::from(8)]);
+        let poly_2 = MultiLinearPoly::new(&[Fq::from(0), Fq::from(12)]);
         let product_poly = ProductPoly::new(vec![poly_1, poly_2]);
         let result = product_poly.univariate_to_evaluation();
         assert_eq!(result, vec![Fq::from(0), Fq::from(96), Fq::from(384)]);
@@ -0,0 +1,126 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+//=========================================================================================
+// Progress reporting and cooperative cancellation for the long running provers
+// the *_with_progress provers call Progress::report after every unit of work:
+//   GKR:       a Round per layer sum-check round and a Layer when the layer's proof is done
+//   sum-check: a Round per round
+//   FRI:       a Fold per committed and folded layer
+// a CancelToken is cloned into another thread (a UI, a signal handler) and checked at every
+// report, the prover then returns Err(Cancelled) and nothing of the partial proof is kept
+// the plain provers run with Progress::none(), same proof, no callback and never cancelled
+//=========================================================================================
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressEvent {
+    Layer {
+        done: usize,
+        total: usize,
+    },
+    Round {
+        layer: Option<usize>, // index of the layer proof in a GKR proof, None for a plain sum-check
+        done: usize,
+        total: usize,
+    },
+    Fold {
+        done: usize,
+        total: usize,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the prover was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+#[derive(Default)]
+pub struct Progress<'a> {
+    callback: Option<Box<dyn FnMut(ProgressEvent) + 'a>>,
+    cancel: Option<CancelToken>,
+}
+
+impl<'a> Progress<'a> {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn new(callback: impl FnMut(ProgressEvent) + 'a) -> Self {
+        Progress {
+            callback: Some(Box::new(callback)),
+            cancel: None,
+        }
+    }
+
+    pub fn with_cancel(self, token: &CancelToken) -> Self {
+        Progress {
+            cancel: Some(token.clone()),
+            ..self
+        }
+    }
+
+    // the event goes to the callback first, so a cancelled prover still reports its last step
+    pub fn report(&mut self, event: ProgressEvent) -> Result<(), Cancelled> {
+        if let Some(callback) = self.callback.as_mut() {
+            callback(event);
+        }
+
+        match &self.cancel {
+            Some(token) if token.is_cancelled() => Err(Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+// for the provers that keep their infallible signature and run with Progress::none()
+pub fn never_cancelled<T>(result: Result<T, Cancelled>) -> T {
+    result.expect("The prover can't be cancelled without a CancelToken")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_progress_reports_then_cancels() {
+        let token = CancelToken::new();
+        let mut events = Vec::new();
+        let mut progress = Progress::new(|event| events.push(event)).with_cancel(&token);
+
+        let fold = |done| ProgressEvent::Fold { done, total: 2 };
+        assert_eq!(progress.report(fold(1)), Ok(()));
+        token.clone().cancel();
+        assert_eq!(progress.report(fold(2)), Err(Cancelled));
+        drop(progress);
+
+        assert_eq!(events, [fold(1), fold(2)]);
+        assert_eq!(Progress::none().report(fold(1)), Ok(()));
+    }
+}
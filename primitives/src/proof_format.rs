@@ -0,0 +1,582 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use std::fmt;
+
+//=========================================================================================
+// Every serialized proof starts with a 2 byte header: [version, protocol id]
+// bump PROOF_FORMAT_VERSION whenever the byte layout of any proof changes
+// so that an older verifier rejects the bytes instead of misreading them,
+// and whenever a transcript changes so old proofs fail on the header instead of a challenge
+//=========================================================================================
+pub const PROOF_FORMAT_VERSION: u8 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolId {
+    Kzg = 1,
+    BatchKzg = 2,
+    Gkr = 3,
+    Compressed = 4,    // a zstd container around another proof, see proof_size
+    GkrCheckpoint = 5, // a partial GKR proof to resume from, see proving_session
+    Ipa = 6,
+    RangeProof = 7,
+    Session = 8, // a proof bound to a session id, see bind_session
+}
+
+impl ProtocolId {
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            1 => Some(ProtocolId::Kzg),
+            2 => Some(ProtocolId::BatchKzg),
+            3 => Some(ProtocolId::Gkr),
+            4 => Some(ProtocolId::Compressed),
+            5 => Some(ProtocolId::GkrCheckpoint),
+            6 => Some(ProtocolId::Ipa),
+            7 => Some(ProtocolId::RangeProof),
+            8 => Some(ProtocolId::Session),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ProofFormatError {
+    MissingHeader,
+    UnsupportedVersion {
+        found: u8,
+        supported: u8,
+    },
+    UnknownProtocol(u8),
+    ProtocolMismatch {
+        expected: ProtocolId,
+        found: ProtocolId,
+    },
+    TrailingBytes(usize),
+    TooLarge {
+        len: usize,
+        max: usize,
+    },
+    LengthMismatch {
+        len: u64,
+        remaining: usize,
+    }, // a length prefix promising more than the bytes left
+    InconsistentLengths {
+        expected: usize,
+        found: usize,
+    }, // vectors that must line up don't
+    UnknownGateOp(u8),
+    UnknownReduction(u8),
+    InvalidVarint, // overlong, not minimal or past u64
+    LeafIndexOutOfRange {
+        index: u64,
+        depth: usize,
+    }, // a Merkle path index with bits above the path's depth
+    WireOutOfRange {
+        wire: u64,
+        len: usize,
+    }, // a serialized gate reading or writing past its layer
+    EmptyCircuit,
+    NonCanonicalEncoding, // bytes that decode but are not the encoding of what they decode to
+    Decompression(std::io::Error),
+    Serialization(SerializationError),
+}
+
+impl fmt::Display for ProofFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofFormatError::MissingHeader => write!(f, "proof is missing its version header"),
+            ProofFormatError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "unsupported proof format version {} (this verifier supports {})",
+                found, supported
+            ),
+            ProofFormatError::UnknownProtocol(id) => write!(f, "unknown protocol id {}", id),
+            ProofFormatError::ProtocolMismatch { expected, found } => {
+                write!(
+                    f,
+                    "expected a {:?} proof but found a {:?} proof",
+                    expected, found
+                )
+            }
+            ProofFormatError::TrailingBytes(count) => {
+                write!(f, "{} unexpected bytes after the proof", count)
+            }
+            ProofFormatError::TooLarge { len, max } => {
+                write!(f, "{} exceeds the decoding limit of {}", len, max)
+            }
+            ProofFormatError::LengthMismatch { len, remaining } => write!(
+                f,
+                "length prefix {} is more than the {} bytes left",
+                len, remaining
+            ),
+            ProofFormatError::InconsistentLengths { expected, found } => write!(
+                f,
+                "expected {} entries to match the rest of the proof but found {}",
+                expected, found
+            ),
+            ProofFormatError::UnknownGateOp(op) => write!(f, "unknown gate op {}", op),
+            ProofFormatError::UnknownReduction(id) => write!(f, "unknown layer reduction {}", id),
+            ProofFormatError::InvalidVarint => write!(f, "invalid varint"),
+            ProofFormatError::LeafIndexOutOfRange { index, depth } => write!(
+                f,
+                "leaf index {} doesn't fit a Merkle path of depth {}",
+                index, depth
+            ),
+            ProofFormatError::WireOutOfRange { wire, len } => {
+                write!(f, "wire {} is outside of a layer of {} wires", wire, len)
+            }
+            ProofFormatError::EmptyCircuit => write!(f, "the circuit has an empty layer"),
+            ProofFormatError::NonCanonicalEncoding => {
+                write!(f, "a point is not in its canonical compressed encoding")
+            }
+            ProofFormatError::Decompression(err) => {
+                write!(f, "the compressed proof doesn't inflate: {}", err)
+            }
+            ProofFormatError::Serialization(err) => write!(f, "serialization error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ProofFormatError {}
+
+impl From<SerializationError> for ProofFormatError {
+    fn from(err: SerializationError) -> Self {
+        ProofFormatError::Serialization(err)
+    }
+}
+
+pub fn write_header(protocol: ProtocolId, bytes: &mut Vec<u8>) {
+    bytes.push(PROOF_FORMAT_VERSION);
+    bytes.push(protocol as u8);
+}
+
+// checks the header and advances bytes past it
+pub fn read_header(bytes: &mut &[u8], expected: ProtocolId) -> Result<(), ProofFormatError> {
+    if bytes.len() < 2 {
+        return Err(ProofFormatError::MissingHeader);
+    }
+
+    let (version, protocol) = (bytes[0], bytes[1]);
+    if version != PROOF_FORMAT_VERSION {
+        return Err(ProofFormatError::UnsupportedVersion {
+            found: version,
+            supported: PROOF_FORMAT_VERSION,
+        });
+    }
+
+    let found =
+        ProtocolId::from_byte(protocol).ok_or(ProofFormatError::UnknownProtocol(protocol))?;
+    if found != expected {
+        return Err(ProofFormatError::ProtocolMismatch { expected, found });
+    }
+
+    *bytes = &bytes[2..];
+    Ok(())
+}
+
+pub fn ensure_consumed(bytes: &[u8]) -> Result<(), ProofFormatError> {
+    if !bytes.is_empty() {
+        return Err(ProofFormatError::TrailingBytes(bytes.len()));
+    }
+
+    Ok(())
+}
+
+// LEB128: 7 bits per byte, low bits first, the high bit says another byte follows
+pub fn write_varint(mut value: u64, bytes: &mut Vec<u8>) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+// only the minimal encoding of a value is accepted, so every value has exactly one encoding
+pub fn read_varint(bytes: &mut &[u8]) -> Result<u64, ProofFormatError> {
+    let mut value = 0u64;
+
+    for (i, byte) in bytes.iter().enumerate() {
+        let bits = (*byte & 0x7f) as u64;
+        // the 10th byte only has room for the top bit of a u64
+        if i == 9 && bits > 1 {
+            return Err(ProofFormatError::InvalidVarint);
+        }
+        value |= bits << (7 * i);
+
+        if byte & 0x80 == 0 {
+            if i > 0 && *byte == 0 {
+                return Err(ProofFormatError::InvalidVarint);
+            }
+            *bytes = &bytes[i + 1..];
+            return Ok(value);
+        }
+        if i == 9 {
+            return Err(ProofFormatError::InvalidVarint);
+        }
+    }
+
+    Err(ProofFormatError::InvalidVarint)
+}
+
+//=========================================================================================
+// Limits for decoding untrusted bytes, checked before anything is allocated
+// every vector goes through read_vec which compares its length prefix with max_len and with the
+// bytes that are actually left (every element takes at least one byte)
+// so a forged length can never make the decoder reserve more than the input it was handed
+//=========================================================================================
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    pub max_bytes: usize, // size of the whole encoding
+    pub max_len: usize,   // entries in any one vector
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits {
+            max_bytes: 1 << 26,
+            max_len: 1 << 22,
+        }
+    }
+}
+
+impl DecodeLimits {
+    pub fn check_size(&self, bytes: &[u8]) -> Result<(), ProofFormatError> {
+        if bytes.len() > self.max_bytes {
+            return Err(ProofFormatError::TooLarge {
+                len: bytes.len(),
+                max: self.max_bytes,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// the u64 length prefix ark writes in front of a Vec, advances bytes past it
+pub fn read_len(bytes: &mut &[u8], limits: &DecodeLimits) -> Result<usize, ProofFormatError> {
+    let len = u64::deserialize_compressed(&mut *bytes)?;
+    if len > bytes.len() as u64 {
+        return Err(ProofFormatError::LengthMismatch {
+            len,
+            remaining: bytes.len(),
+        });
+    }
+    if len > limits.max_len as u64 {
+        return Err(ProofFormatError::TooLarge {
+            len: len as usize,
+            max: limits.max_len,
+        });
+    }
+
+    Ok(len as usize)
+}
+
+// same bytes as Vec::<T>::deserialize_compressed, read_item decodes one entry
+pub fn read_vec_with<T>(
+    bytes: &mut &[u8],
+    limits: &DecodeLimits,
+    mut read_item: impl FnMut(&mut &[u8]) -> Result<T, ProofFormatError>,
+) -> Result<Vec<T>, ProofFormatError> {
+    let len = read_len(bytes, limits)?;
+
+    let mut values = Vec::with_capacity(len);
+    for _ in 0..len {
+        values.push(read_item(bytes)?);
+    }
+
+    Ok(values)
+}
+
+pub fn read_vec<T: CanonicalDeserialize>(
+    bytes: &mut &[u8],
+    limits: &DecodeLimits,
+) -> Result<Vec<T>, ProofFormatError> {
+    read_vec_with(bytes, limits, |bytes| {
+        Ok(T::deserialize_compressed(&mut *bytes)?)
+    })
+}
+
+//=========================================================================================
+// Curve points in proofs and setups
+// a point is ark's compressed encoding, its byte order is the one the curve's encoding fixes
+// (little endian x with the flags in the top bits for the arkworks curves, the big endian zcash
+// format for BLS12-381) and never the machine's, and it is decoded with Validate::Yes so it has
+// to be on the curve and in the prime order subgroup
+// that still lets through bytes ark accepts but never writes, e.g. the point at infinity with
+// junk in its x bytes, so read_point encodes the point again and only takes exactly those bytes:
+// one encoding per point, a proof can't be changed without changing what it says
+//=========================================================================================
+pub fn read_point<G: CanonicalSerialize + CanonicalDeserialize>(
+    bytes: &mut &[u8],
+) -> Result<G, ProofFormatError> {
+    let start = *bytes;
+    let point = G::deserialize_compressed(&mut *bytes)?;
+
+    let mut canonical = Vec::with_capacity(start.len() - bytes.len());
+    point.serialize_compressed(&mut canonical)?;
+    if canonical != start[..start.len() - bytes.len()] {
+        return Err(ProofFormatError::NonCanonicalEncoding);
+    }
+
+    Ok(point)
+}
+
+pub fn read_points<G: CanonicalSerialize + CanonicalDeserialize>(
+    bytes: &mut &[u8],
+    limits: &DecodeLimits,
+) -> Result<Vec<G>, ProofFormatError> {
+    read_vec_with(bytes, limits, read_point)
+}
+
+// Vec<Vec<T>>, both levels are checked against the same limits
+pub fn read_nested_vec<T: CanonicalDeserialize>(
+    bytes: &mut &[u8],
+    limits: &DecodeLimits,
+) -> Result<Vec<Vec<T>>, ProofFormatError> {
+    read_vec_with(bytes, limits, |bytes| read_vec(bytes, limits))
+}
+
+//=========================================================================================
+// Proofs bound to a session id, for verifier services that accept each proof once
+// the prover proves under TranscriptDomain::with_session(id), so every challenge depends on the
+// id, and sends bind_session(id, proof bytes): header (version, Session) || id as a length
+// prefixed byte vector || the inner proof with its own header
+// the verifier takes the id from open_session, rejects it if it has seen it before and verifies
+// the inner proof under the same with_session(id), a proof replayed with another id (or with
+// the container stripped) gets other challenges and fails, so the id in hand is the only thing
+// the service has to remember
+// the id is the caller's (a random nonce, a request id), nothing here checks it is fresh
+//=========================================================================================
+pub fn bind_session(session_id: &[u8], proof_bytes: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_header(ProtocolId::Session, &mut bytes);
+    session_id
+        .to_vec()
+        .serialize_compressed(&mut bytes)
+        .expect("Serializing into a Vec cannot fail");
+    bytes.extend_from_slice(proof_bytes);
+
+    bytes
+}
+
+// (session id, inner proof bytes), the inner proof is decoded by its own from_bytes
+pub fn open_session<'a>(
+    mut bytes: &'a [u8],
+    limits: &DecodeLimits,
+) -> Result<(Vec<u8>, &'a [u8]), ProofFormatError> {
+    limits.check_size(bytes)?;
+    read_header(&mut bytes, ProtocolId::Session)?;
+    let session_id = read_vec::<u8>(&mut bytes, limits)?;
+
+    Ok((session_id, bytes))
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_session_roundtrip() {
+        let mut inner = Vec::new();
+        write_header(ProtocolId::Gkr, &mut inner);
+        inner.extend_from_slice(&[1, 2, 3]);
+
+        let bytes = bind_session(b"request-17", &inner);
+        let (session_id, proof_bytes) = open_session(&bytes, &DecodeLimits::default()).unwrap();
+        assert_eq!(session_id, b"request-17");
+        assert_eq!(proof_bytes, inner);
+
+        // the bare proof is not a session container
+        assert!(matches!(
+            open_session(&inner, &DecodeLimits::default()),
+            Err(ProofFormatError::ProtocolMismatch {
+                expected: ProtocolId::Session,
+                found: ProtocolId::Gkr
+            })
+        ));
+
+        let limits = DecodeLimits {
+            max_len: 4,
+            ..DecodeLimits::default()
+        };
+        assert!(matches!(
+            open_session(&bytes, &limits),
+            Err(ProofFormatError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_header_roundtrip() {
+        let mut bytes = Vec::new();
+        write_header(ProtocolId::Gkr, &mut bytes);
+        bytes.push(42);
+
+        let mut reader = bytes.as_slice();
+        read_header(&mut reader, ProtocolId::Gkr).unwrap();
+        assert_eq!(reader, &[42]);
+    }
+
+    #[test]
+    fn test_read_vec_limits() {
+        use ark_bn254::Fr;
+        use ark_serialize::CanonicalSerialize;
+
+        let values: Vec<Vec<Fr>> = vec![vec![Fr::from(1), Fr::from(2)], vec![Fr::from(3)]];
+        let mut bytes = Vec::new();
+        values.serialize_compressed(&mut bytes).unwrap();
+
+        let limits = DecodeLimits::default();
+        let mut reader = bytes.as_slice();
+        assert_eq!(read_nested_vec::<Fr>(&mut reader, &limits).unwrap(), values);
+        assert!(reader.is_empty());
+
+        let small = DecodeLimits {
+            max_len: 1,
+            ..limits
+        };
+        assert!(matches!(
+            read_nested_vec::<Fr>(&mut bytes.as_slice(), &small),
+            Err(ProofFormatError::TooLarge { len: 2, max: 1 })
+        ));
+
+        // a length prefix of 2^40 with nothing behind it is refused before allocating
+        let forged = (1u64 << 40).to_le_bytes();
+        assert!(matches!(
+            read_vec::<Fr>(&mut forged.as_slice(), &limits),
+            Err(ProofFormatError::LengthMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_point() {
+        use ark_bn254::G1Projective;
+        use ark_ec::PrimeGroup;
+        use ark_ff::Zero;
+        use ark_serialize::CanonicalSerialize;
+
+        let encode = |point: &G1Projective| {
+            let mut bytes = Vec::new();
+            point.serialize_compressed(&mut bytes).unwrap();
+            bytes
+        };
+        let points = vec![G1Projective::generator(), G1Projective::zero()];
+        let mut bytes = Vec::new();
+        points.serialize_compressed(&mut bytes).unwrap();
+        let mut reader = bytes.as_slice();
+        assert_eq!(
+            read_points::<G1Projective>(&mut reader, &DecodeLimits::default()).unwrap(),
+            points
+        );
+        assert!(reader.is_empty());
+
+        // the point at infinity with a bit set in its x, decodes to the same point in ark
+        let mut junk = encode(&G1Projective::zero());
+        junk[16] |= 1;
+        assert!(read_point::<G1Projective>(&mut junk.as_slice()).is_err());
+
+        // an x past the modulus (all ones below the two flag bits)
+        let mut unreduced = vec![0xff; 32];
+        unreduced[31] = 0x3f;
+        assert!(read_point::<G1Projective>(&mut unreduced.as_slice()).is_err());
+    }
+
+    // reads any odd byte as a 1, only 1 itself is what it writes
+    #[derive(Debug, PartialEq)]
+    struct Parity(u8);
+
+    impl ark_serialize::Valid for Parity {
+        fn check(&self) -> Result<(), SerializationError> {
+            Ok(())
+        }
+    }
+
+    impl CanonicalSerialize for Parity {
+        fn serialize_with_mode<W: std::io::Write>(
+            &self,
+            writer: W,
+            compress: ark_serialize::Compress,
+        ) -> Result<(), SerializationError> {
+            self.0.serialize_with_mode(writer, compress)
+        }
+
+        fn serialized_size(&self, _compress: ark_serialize::Compress) -> usize {
+            1
+        }
+    }
+
+    impl CanonicalDeserialize for Parity {
+        fn deserialize_with_mode<R: std::io::Read>(
+            reader: R,
+            compress: ark_serialize::Compress,
+            validate: ark_serialize::Validate,
+        ) -> Result<Self, SerializationError> {
+            Ok(Parity(
+                u8::deserialize_with_mode(reader, compress, validate)? % 2,
+            ))
+        }
+    }
+
+    #[test]
+    fn test_read_point_takes_only_the_canonical_bytes() {
+        assert_eq!(
+            read_point::<Parity>(&mut [1u8].as_slice()).unwrap(),
+            Parity(1)
+        );
+        assert!(matches!(
+            read_point::<Parity>(&mut [3u8].as_slice()),
+            Err(ProofFormatError::NonCanonicalEncoding)
+        ));
+    }
+
+    #[test]
+    fn test_varint_roundtrip_and_canonical() {
+        for value in [0, 1, 127, 128, 300, 1 << 35, u64::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(value, &mut bytes);
+            bytes.push(42);
+
+            let mut reader = bytes.as_slice();
+            assert_eq!(read_varint(&mut reader).unwrap(), value);
+            assert_eq!(reader, &[42]);
+        }
+
+        // 1 padded with a zero group, a u64 overflow, and an unterminated varint
+        for bytes in [
+            &[0x81, 0x00][..],
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02],
+            &[0x80],
+        ] {
+            assert!(matches!(
+                read_varint(&mut &bytes[..]),
+                Err(ProofFormatError::InvalidVarint)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_header_rejects_unknown_version() {
+        let bytes = [PROOF_FORMAT_VERSION + 1, ProtocolId::Kzg as u8];
+
+        let result = read_header(&mut bytes.as_slice(), ProtocolId::Kzg);
+        assert!(matches!(
+            result,
+            Err(ProofFormatError::UnsupportedVersion { found, .. }) if found == PROOF_FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn test_header_rejects_other_protocol() {
+        let mut bytes = Vec::new();
+        write_header(ProtocolId::BatchKzg, &mut bytes);
+
+        let result = read_header(&mut bytes.as_slice(), ProtocolId::Kzg);
+        assert!(matches!(
+            result,
+            Err(ProofFormatError::ProtocolMismatch {
+                expected: ProtocolId::Kzg,
+                found: ProtocolId::BatchKzg
+            })
+        ));
+
+        let result = read_header(&mut [PROOF_FORMAT_VERSION, 99].as_slice(), ProtocolId::Kzg);
+        assert!(matches!(result, Err(ProofFormatError::UnknownProtocol(99))));
+    }
+}
@@ -0,0 +1,115 @@
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// One place for the soundness knobs every protocol used to pick on its own
+// FRI: each query catches a far-from-low-degree oracle w.p. about 1 - 1/blowup, so it is worth
+//   log2(blowup) bits, grinding (proof of work before the queries) adds grinding_bits on top
+//   soundness ≈ query_count * log2(blowup) + grinding_bits
+// sum-check: a cheating prover survives a round w.p. degree / |F|, so one run over num_vars rounds
+//   is worth field_bits - log2(degree * num_vars) bits, a small field needs k independent
+//   repetitions to get there: soundness ≈ k * (field_bits - log2(degree * num_vars))
+// the loss term is fixed at SUM_CHECK_LOSS_BITS (degree * num_vars <= 256) for the defaults
+//=========================================================================================
+pub const DEFAULT_TARGET_BITS: usize = 100;
+pub const SUM_CHECK_LOSS_BITS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecurityConfig {
+    pub target_bits: usize,
+    pub field_bits: usize,
+    pub query_count: usize,     // FRI queries
+    pub grinding_bits: usize,   // leading zero bits of the proof of work before the FRI queries
+    pub num_repetitions: usize, // independent sum-check runs
+}
+
+impl SecurityConfig {
+    // the cheapest parameters reaching target_bits without grinding
+    pub fn for_target(target_bits: usize, field_bits: usize, blowup_factor: usize) -> Self {
+        if !blowup_factor.is_power_of_two() || blowup_factor < 2 {
+            panic!("The blowup factor must be a power of 2 greater than 1");
+        }
+        if field_bits <= SUM_CHECK_LOSS_BITS {
+            panic!("The field is too small for sum-check to be sound");
+        }
+
+        SecurityConfig {
+            target_bits,
+            field_bits,
+            query_count: target_bits.div_ceil(blowup_factor.ilog2() as usize),
+            grinding_bits: 0,
+            num_repetitions: target_bits.div_ceil(field_bits - SUM_CHECK_LOSS_BITS),
+        }
+    }
+
+    pub fn for_field<F: PrimeField>(target_bits: usize, blowup_factor: usize) -> Self {
+        Self::for_target(target_bits, F::MODULUS_BIT_SIZE as usize, blowup_factor)
+    }
+
+    // trades queries for prover work, the verifier only checks one hash
+    pub fn with_grinding(self, grinding_bits: usize, blowup_factor: usize) -> Self {
+        let remaining_bits = self.target_bits.saturating_sub(grinding_bits);
+
+        SecurityConfig {
+            grinding_bits,
+            query_count: remaining_bits.div_ceil(blowup_factor.ilog2() as usize),
+            ..self
+        }
+    }
+
+    pub fn fri_soundness_bits(&self, blowup_factor: usize) -> usize {
+        self.query_count * blowup_factor.ilog2() as usize + self.grinding_bits
+    }
+
+    pub fn sum_check_soundness_bits(&self, degree: usize, num_vars: usize) -> usize {
+        let loss_bits = (degree * num_vars).max(1).next_power_of_two().ilog2() as usize;
+
+        self.num_repetitions * self.field_bits.saturating_sub(loss_bits)
+    }
+
+    // true if both FRI and a sum-check of this shape reach the target
+    pub fn meets_target(&self, blowup_factor: usize, degree: usize, num_vars: usize) -> bool {
+        self.fri_soundness_bits(blowup_factor) >= self.target_bits
+            && self.sum_check_soundness_bits(degree, num_vars) >= self.target_bits
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_defaults_for_large_field() {
+        let config = SecurityConfig::for_target(DEFAULT_TARGET_BITS, 254, 4);
+
+        assert_eq!(config.query_count, 50);
+        assert_eq!(config.num_repetitions, 1);
+        assert!(config.meets_target(4, 3, 20));
+
+        let config = SecurityConfig::for_field::<Fq>(DEFAULT_TARGET_BITS, 4);
+        assert_eq!(config.field_bits, Fq::MODULUS_BIT_SIZE as usize);
+    }
+
+    #[test]
+    fn test_small_field_needs_repetitions() {
+        // a 64 bit field (Goldilocks sized)
+        let config = SecurityConfig::for_target(DEFAULT_TARGET_BITS, 64, 2);
+
+        assert_eq!(config.num_repetitions, 2);
+        assert!(config.sum_check_soundness_bits(3, 20) >= DEFAULT_TARGET_BITS);
+
+        let single_run = SecurityConfig {
+            num_repetitions: 1,
+            ..config
+        };
+        assert!(!single_run.meets_target(2, 3, 20));
+    }
+
+    #[test]
+    fn test_grinding_replaces_queries() {
+        let config = SecurityConfig::for_target(DEFAULT_TARGET_BITS, 254, 2).with_grinding(20, 2);
+
+        assert_eq!(config.query_count, 80);
+        assert_eq!(config.fri_soundness_bits(2), DEFAULT_TARGET_BITS);
+    }
+}
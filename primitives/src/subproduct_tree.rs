@@ -0,0 +1,276 @@
+use crate::{domain_cache::DomainCache, fft::FastFourierTransform, UnivariatePoly};
+use ark_ff::{batch_inversion, PrimeField};
+
+//=========================================================================================
+// Subproduct tree: interpolation and multipoint evaluation in O(n log² n) instead of O(n²)
+// the leaves are (x - xᵢ), every node is the product of its two children and the root is
+// M(x) = ∏(x - xᵢ)
+//   evaluate:    f mod M at the root, then mod each child on the way down, the leaf remainder
+//                f mod (x - xᵢ) is f(xᵢ)
+//   interpolate: f(x) = Σ cᵢ·M(x)/(x - xᵢ) with cᵢ = yᵢ / M'(xᵢ), the M'(xᵢ) come from evaluate
+//                and the sum is built bottom up, a node is f_left·M_right + f_right·M_left
+// products of large polys go through the FFT and a remainder is one Newton inversion of the
+// reversed divisor, both fall back to the schoolbook versions below FFT_THRESHOLD
+// the Lagrange basis product in UnivariatePoly::interpolate is still faster for a few dozen
+// points, Interpolation::for_len picks between the two
+//=========================================================================================
+
+// below this many coefficients in the shorter factor schoolbook multiplication wins
+const FFT_THRESHOLD: usize = 32;
+
+#[derive(Debug, Clone)]
+struct Node<F: PrimeField> {
+    poly: Vec<F>, // ∏(x - xᵢ) over the points under this node
+    children: Option<Box<(Node<F>, Node<F>)>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubproductTree<F: PrimeField> {
+    points: Vec<F>,
+    root: Node<F>,
+}
+
+impl<F: PrimeField> SubproductTree<F> {
+    pub fn new(points: &[F]) -> Self {
+        if points.is_empty() {
+            panic!("The subproduct tree needs at least one point");
+        }
+
+        SubproductTree {
+            points: points.to_vec(),
+            root: Node::build(points, &mut DomainCache::new()),
+        }
+    }
+
+    // M(x) = ∏(x - xᵢ), the vanishing polynomial of the points
+    pub fn vanishing_poly(&self) -> UnivariatePoly<F> {
+        UnivariatePoly::new(self.root.poly.clone())
+    }
+
+    // f(xᵢ) for every point, in the order the points were given
+    pub fn evaluate(&self, poly: &UnivariatePoly<F>) -> Vec<F> {
+        let mut cache = DomainCache::new();
+        let mut evals = Vec::with_capacity(self.points.len());
+        let remainder = rem(&poly.coefficient, &self.root.poly, &mut cache);
+        self.root.evaluate(remainder, &mut cache, &mut evals);
+
+        evals
+    }
+
+    // the poly of degree < n through (xᵢ, yᵢ), same coefficients as UnivariatePoly::interpolate
+    pub fn interpolate(&self, ys: &[F]) -> UnivariatePoly<F> {
+        if ys.len() != self.points.len() {
+            panic!("The number of values must match the number of points");
+        }
+
+        let mut cache = DomainCache::new();
+        let derivative = UnivariatePoly::new(derivative(&self.root.poly));
+        let mut weights = self.evaluate(&derivative);
+        if weights.iter().any(|weight| weight.is_zero()) {
+            panic!("The interpolation points must be distinct");
+        }
+        batch_inversion(&mut weights);
+        let weights: Vec<F> = weights.iter().zip(ys).map(|(w, y)| *w * y).collect();
+
+        let mut coefficient = self.root.combine(&weights, &mut cache);
+        coefficient.resize(self.points.len(), F::zero());
+
+        UnivariatePoly::new(coefficient)
+    }
+}
+
+impl<F: PrimeField> Node<F> {
+    fn build(points: &[F], cache: &mut DomainCache<F>) -> Self {
+        if points.len() == 1 {
+            return Node {
+                poly: vec![-points[0], F::one()],
+                children: None,
+            };
+        }
+
+        let (left, right) = points.split_at(points.len() / 2);
+        let (left, right) = (Node::build(left, cache), Node::build(right, cache));
+
+        Node {
+            poly: mul(&left.poly, &right.poly, cache),
+            children: Some(Box::new((left, right))),
+        }
+    }
+
+    // remainder is f mod self.poly, pushes the values at this node's points left to right
+    fn evaluate(&self, remainder: Vec<F>, cache: &mut DomainCache<F>, evals: &mut Vec<F>) {
+        match &self.children {
+            None => evals.push(remainder[0]),
+            Some(children) => {
+                let (left, right) = children.as_ref();
+                let left_remainder = rem(&remainder, &left.poly, cache);
+                let right_remainder = rem(&remainder, &right.poly, cache);
+                left.evaluate(left_remainder, cache, evals);
+                right.evaluate(right_remainder, cache, evals);
+            }
+        }
+    }
+
+    // Σ cᵢ·self.poly/(x - xᵢ) over this node's points, weights are the cᵢ in the same order
+    fn combine(&self, weights: &[F], cache: &mut DomainCache<F>) -> Vec<F> {
+        match &self.children {
+            None => vec![weights[0]],
+            Some(children) => {
+                let (left, right) = children.as_ref();
+                let (left_weights, right_weights) = weights.split_at(weights.len() / 2);
+                let left_sum = left.combine(left_weights, cache);
+                let right_sum = right.combine(right_weights, cache);
+
+                add(
+                    &mul(&left_sum, &right.poly, cache),
+                    &mul(&right_sum, &left.poly, cache),
+                )
+            }
+        }
+    }
+}
+
+fn add<F: PrimeField>(a: &[F], b: &[F]) -> Vec<F> {
+    let mut sum = if a.len() >= b.len() {
+        a.to_vec()
+    } else {
+        b.to_vec()
+    };
+    let shorter = if a.len() >= b.len() { b } else { a };
+    for (s, c) in sum.iter_mut().zip(shorter) {
+        *s += c;
+    }
+
+    sum
+}
+
+fn mul<F: PrimeField>(a: &[F], b: &[F], cache: &mut DomainCache<F>) -> Vec<F> {
+    let len = a.len() + b.len() - 1;
+    let size = len.next_power_of_two();
+    if a.len().min(b.len()) < FFT_THRESHOLD || size.trailing_zeros() > F::TWO_ADICITY {
+        return (&UnivariatePoly::new(a.to_vec()) * &UnivariatePoly::new(b.to_vec())).coefficient;
+    }
+
+    let evals = |poly: &[F], cache: &mut DomainCache<F>| {
+        let mut padded = poly.to_vec();
+        padded.resize(size, F::zero());
+        FastFourierTransform::new(padded)
+            .evaluate_with(cache)
+            .coefficients
+    };
+    let (a_evals, b_evals) = (evals(a, cache), evals(b, cache));
+    let product: Vec<F> = a_evals.iter().zip(b_evals).map(|(a, b)| *a * b).collect();
+
+    let mut coefficients = FastFourierTransform::new(product)
+        .interpolate_with(cache)
+        .coefficients;
+    coefficients.truncate(len);
+
+    coefficients
+}
+
+// g with f·g = 1 mod x^k, by Newton iteration g ← g·(2 - f·g) doubling the precision each step
+fn inverse_series<F: PrimeField>(f: &[F], k: usize, cache: &mut DomainCache<F>) -> Vec<F> {
+    let mut g = vec![f[0]
+        .inverse()
+        .expect("The constant term of a series must be invertible")];
+    let mut precision = 1;
+    while precision < k {
+        precision = (2 * precision).min(k);
+        let mut fg = mul(&f[..f.len().min(precision)], &g, cache);
+        fg.truncate(precision);
+        let mut correction: Vec<F> = fg.iter().map(|c| -*c).collect();
+        correction[0] += F::from(2u64);
+        g = mul(&g, &correction, cache);
+        g.truncate(precision);
+    }
+
+    g
+}
+
+// a mod b with b monic (every node poly is), through the reversed quotient
+// rev(q) = rev(a) / rev(b) mod x^(deg a - deg b + 1), then a - q·b
+fn rem<F: PrimeField>(a: &[F], b: &[F], cache: &mut DomainCache<F>) -> Vec<F> {
+    let b_degree = b.len() - 1;
+    if a.len() <= b_degree {
+        let mut remainder = a.to_vec();
+        remainder.resize(b_degree.max(1), F::zero());
+        return remainder;
+    }
+
+    let quotient_len = a.len() - b_degree;
+    let a_rev: Vec<F> = a.iter().rev().take(quotient_len).cloned().collect();
+    let b_rev: Vec<F> = b.iter().rev().cloned().collect();
+    let mut quotient = mul(&a_rev, &inverse_series(&b_rev, quotient_len, cache), cache);
+    quotient.truncate(quotient_len);
+    quotient.reverse();
+
+    let product = mul(&quotient, b, cache);
+    let mut remainder: Vec<F> = a
+        .iter()
+        .zip(product)
+        .take(b_degree.max(1))
+        .map(|(a, p)| *a - p)
+        .collect();
+    remainder.resize(b_degree.max(1), F::zero());
+
+    remainder
+}
+
+fn derivative<F: PrimeField>(poly: &[F]) -> Vec<F> {
+    if poly.len() == 1 {
+        return vec![F::zero()];
+    }
+
+    poly.iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| F::from(i as u64) * c)
+        .collect()
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::SubproductTree;
+    use crate::{Interpolation, UnivariatePoly};
+    use ark_bn254::Fr;
+    use ark_ff::UniformRand;
+
+    fn random(len: usize) -> Vec<Fr> {
+        let mut rng = rand::thread_rng();
+        (0..len).map(|_| Fr::rand(&mut rng)).collect()
+    }
+
+    #[test]
+    fn test_fast_interpolate_matches_lagrange() {
+        // 70 goes through the FFT products and the Newton remainders
+        for n in [1, 2, 3, 8, 13, 70] {
+            let (xs, ys) = (random(n), random(n));
+            let fast = UnivariatePoly::interpolate_with(&xs, &ys, Interpolation::SubproductTree);
+            assert_eq!(fast, UnivariatePoly::interpolate(&xs, &ys));
+        }
+    }
+
+    #[test]
+    fn test_multipoint_evaluation() {
+        let xs = random(100);
+        let poly = UnivariatePoly {
+            coefficient: random(150),
+        };
+        let tree = SubproductTree::new(&xs);
+
+        let expected: Vec<Fr> = xs.iter().map(|x| poly.evaluate(*x)).collect();
+        assert_eq!(tree.evaluate(&poly), expected);
+        assert!(tree
+            .evaluate(&tree.vanishing_poly())
+            .iter()
+            .all(|v| *v == Fr::from(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "The interpolation points must be distinct")]
+    fn test_repeated_point() {
+        let xs = [1, 2, 1].map(Fr::from);
+        SubproductTree::new(&xs).interpolate(&random(3));
+    }
+}
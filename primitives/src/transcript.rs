@@ -0,0 +1,709 @@
+use crate::{
+    hash::hash_to_field::hash_to_field,
+    transcript_schedule::{Schedule, ScheduleCursor, Step},
+};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+//=========================================================================================
+// The hash behind a transcript, anything with a 32 byte output that can be cloned mid-stream
+// Keccak256 is the default and what every proof in the crate is made with, Sha256 is there for
+// verifiers that only have a SHA-256 precompile, e.g. Transcript::<Sha256>::with_hasher()
+// the hasher only changes the squeezes, the order and framing of what is absorbed stay the same
+//=========================================================================================
+pub trait TranscriptHasher: Clone + Default {
+    fn update(&mut self, data: &[u8]);
+
+    fn finalize(self) -> [u8; DIGEST_LEN];
+}
+
+impl TranscriptHasher for Keccak256 {
+    fn update(&mut self, data: &[u8]) {
+        Digest::update(self, data);
+    }
+
+    fn finalize(self) -> [u8; DIGEST_LEN] {
+        Digest::finalize(self).into()
+    }
+}
+
+impl TranscriptHasher for Sha256 {
+    fn update(&mut self, data: &[u8]) {
+        sha2::Digest::update(self, data);
+    }
+
+    fn finalize(self) -> [u8; DIGEST_LEN] {
+        sha2::Digest::finalize(self).into()
+    }
+}
+
+#[derive(Clone)]
+pub struct Transcript<H: TranscriptHasher = Keccak256> {
+    hasher: H,                        // Keep the hasher as part of the state
+    schedule: Option<ScheduleCursor>, // see transcript_schedule
+    log: Option<EventLog>,            // see TranscriptRecorder
+    label: &'static str,              // the step of the last follow(), for the log
+}
+
+impl Default for Transcript {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transcript {
+    pub fn new() -> Self {
+        Transcript::with_hasher()
+    }
+
+    // the transcript every GKR / FRI / KZG entry point starts from, see TranscriptDomain
+    pub fn new_with_domain(context: &[u8], public_digest: &[u8; DIGEST_LEN]) -> Self {
+        Transcript::with_hasher_and_domain(context, public_digest)
+    }
+}
+
+impl<H: TranscriptHasher> Transcript<H> {
+    pub fn with_hasher() -> Self {
+        Transcript {
+            hasher: H::default(),
+            schedule: None,
+            log: None,
+            label: "",
+        }
+    }
+
+    // every absorb and squeeze from here on is also logged in recorder, labelled with its step
+    pub fn with_recorder(self, recorder: &TranscriptRecorder) -> Self {
+        Transcript {
+            log: Some(recorder.events.clone()),
+            ..self
+        }
+    }
+
+    // the label is used up by the event it was given for
+    fn record(&mut self, event: impl FnOnce(String) -> TranscriptEvent) {
+        let label = std::mem::take(&mut self.label);
+        if let Some(log) = &self.log {
+            log.lock().unwrap().push(event(label.to_string()));
+        }
+    }
+
+    // every follow() from here on is checked against schedule, from its first step
+    pub fn with_schedule(self, schedule: &'static Schedule) -> Self {
+        Transcript {
+            schedule: Some(ScheduleCursor::new(schedule)),
+            ..self
+        }
+    }
+
+    // the next absorb or squeeze is step, a transcript without a schedule takes any step
+    // e.g. transcript.follow(Step::Squeeze("r")).squeeze_field::<F>()
+    pub fn follow(&mut self, step: Step) -> &mut Self {
+        if let Step::Absorb(label) | Step::Squeeze(label) = step {
+            self.label = label;
+        }
+        if let Some(cursor) = self.schedule.as_mut() {
+            if !cursor.advance(step) {
+                panic!(
+                    "The transcript left the {} schedule at {}",
+                    cursor.schedule().protocol,
+                    step
+                );
+            }
+        }
+
+        self
+    }
+
+    // the transcript of a sub-protocol run inside this one (e.g. a GKR layer sum-check): it starts
+    // from everything absorbed here so far and the label, so its challenges are bound to the
+    // domain, the session and every earlier message, and nothing it absorbs comes back in here
+    // the fork has no schedule and logs to the same recorder
+    pub fn fork(&self, label: &[u8]) -> Self {
+        let mut fork = Transcript {
+            hasher: self.hasher.clone(),
+            schedule: None,
+            log: self.log.clone(),
+            label: "",
+        };
+        fork.absorb_labeled(b"fork", label);
+
+        fork
+    }
+
+    // false while the schedule still expects a step
+    pub fn schedule_finished(&self) -> bool {
+        self.schedule
+            .as_ref()
+            .is_none_or(|cursor| cursor.finished())
+    }
+
+    pub fn with_hasher_and_domain(context: &[u8], public_digest: &[u8; DIGEST_LEN]) -> Self {
+        let mut transcript = Transcript::<H>::with_hasher();
+        transcript.hasher.update(&[DigestTag::Context as u8]);
+        transcript
+            .hasher
+            .update(&(context.len() as u32).to_be_bytes());
+        transcript.hasher.update(context);
+        transcript.absorb_digest(DigestTag::PublicInput, public_digest);
+
+        transcript
+    }
+
+    pub fn absorb(&mut self, byte_array: &[u8]) {
+        self.hasher.update(byte_array);
+        self.record(|label| TranscriptEvent::Absorb {
+            label,
+            data: byte_array.to_vec(),
+        });
+    }
+
+    pub fn squeeze(&mut self) -> Vec<u8> {
+        // Clone the current hasher state to not affect future updates
+        let hasher_clone = self.hasher.clone();
+        let challenge_hash = hasher_clone.finalize().to_vec();
+        self.hasher.update(&challenge_hash);
+        self.record(|label| TranscriptEvent::Squeeze {
+            label,
+            output: challenge_hash.clone(),
+        });
+
+        challenge_hash
+    }
+
+    // the only way a field challenge leaves a transcript, see challenge_from_digest
+    pub fn squeeze_field<F: PrimeField>(&mut self) -> F {
+        challenge_from_digest(&self.squeeze())
+    }
+
+    // within 2⁻¹²⁸ of uniform (the squeeze goes through hash_to_field), for nonces and anything
+    // else where the bias of squeeze_field's single digest reduction matters
+    pub fn squeeze_uniform_field<F: PrimeField>(&mut self) -> F {
+        hash_to_field(&self.squeeze(), UNIFORM_DST, 1)[0]
+    }
+
+    // a query index in 0..bound, taken from a field challenge so it follows the same derivation
+    pub fn squeeze_index<F: PrimeField>(&mut self, bound: usize) -> usize {
+        index_from_challenge(self.squeeze_field::<F>(), bound)
+    }
+
+    // proof of work: the first nonce whose hash with the current state starts with `bits` zero bits
+    // the nonce is absorbed so the challenges after it depend on the work
+    pub fn grind(&mut self, bits: usize) -> u64 {
+        let nonce = (0u64..)
+            .find(|nonce| leading_zero_bits(&self.pow_hash(*nonce)) >= bits)
+            .unwrap();
+        self.absorb(&nonce.to_be_bytes());
+
+        nonce
+    }
+
+    pub fn check_grinding(&mut self, bits: usize, nonce: u64) -> bool {
+        let valid = leading_zero_bits(&self.pow_hash(nonce)) >= bits;
+        self.absorb(&nonce.to_be_bytes());
+
+        valid
+    }
+
+    fn pow_hash(&self, nonce: u64) -> Vec<u8> {
+        let mut hasher = self.hasher.clone();
+        hasher.update(b"grinding");
+        hasher.update(&nonce.to_be_bytes());
+
+        hasher.finalize().to_vec()
+    }
+
+    // tag || length || digest, the only way a Merkle root or a commitment enters a transcript
+    pub fn absorb_digest(&mut self, tag: DigestTag, digest: &[u8; DIGEST_LEN]) {
+        self.hasher.update(&[tag as u8]);
+        self.hasher.update(&(DIGEST_LEN as u32).to_be_bytes());
+        self.hasher.update(digest);
+        self.record(|label| TranscriptEvent::Absorb {
+            label,
+            data: [&[tag as u8][..], digest].concat(),
+        });
+    }
+
+    // [label length as u32 BE][label][data length as u64 BE][data]
+    // both lengths are written so neither side of a message can bleed into the next one
+    pub fn absorb_labeled(&mut self, label: &[u8], byte_array: &[u8]) {
+        self.hasher.update(&(label.len() as u32).to_be_bytes());
+        self.hasher.update(label);
+        self.hasher.update(&(byte_array.len() as u64).to_be_bytes());
+        self.hasher.update(byte_array);
+        self.record(|_| TranscriptEvent::Absorb {
+            label: String::from_utf8_lossy(label).into_owned(),
+            data: byte_array.to_vec(),
+        });
+    }
+
+    // Merkle roots are SHA-256 outputs, anything else is a malformed root
+    pub fn absorb_merkle_root(&mut self, root: &[u8]) {
+        let digest: &[u8; DIGEST_LEN] = root
+            .try_into()
+            .expect("A Merkle root must be a 32 byte digest");
+
+        self.absorb_digest(DigestTag::MerkleRoot, digest);
+    }
+
+    pub fn absorb_commitment<C: CanonicalSerialize>(&mut self, commitment: &C) {
+        self.absorb_digest(DigestTag::Commitment, &commitment_digest(commitment));
+    }
+}
+
+//=========================================================================================
+// Fixed-width digests for everything that commits to something
+// raw roots / point strings of varying length next to each other can be re-split into a
+// different sequence of messages, so every root and commitment is absorbed as
+// [tag][32 as u32 BE][32 byte digest]
+// a Merkle root already is the 32 byte digest, a curve point is Keccak256 of its compressed encoding
+// the tag keeps the same 32 bytes from meaning a root in one protocol and a point in another
+//=========================================================================================
+pub const DIGEST_LEN: usize = 32;
+
+// the hash_to_field DST of squeeze_uniform_field
+const UNIFORM_DST: &[u8] = b"ZK-TRANSCRIPT-V01-UNIFORM_XMD:SHA-256_RO_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestTag {
+    MerkleRoot = 1,
+    Commitment = 2,
+    Context = 3,
+    PublicInput = 4,
+    Circuit = 5,
+    Setup = 6,
+    Session = 7,
+}
+
+pub fn commitment_digest<C: CanonicalSerialize>(commitment: &C) -> [u8; DIGEST_LEN] {
+    let mut bytes = Vec::new();
+    commitment
+        .serialize_compressed(&mut bytes)
+        .expect("Serializing into a Vec cannot fail");
+
+    Keccak256::digest(&bytes).into()
+}
+
+//=========================================================================================
+// Application domain separation
+// a transcript made by new_with_domain absorbs an application context string (e.g. b"my-app-v1")
+// and a digest of the public inputs before any protocol message, so a proof made for one
+// application or one statement gets different challenges anywhere else and fails to verify there
+// prove and verify entry points take the domain instead of starting from an empty transcript
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptDomain {
+    pub context: Vec<u8>,
+    pub public_digest: [u8; DIGEST_LEN],
+    pub session_id: Option<Vec<u8>>, // see proof_format::bind_session
+    pub recorder: Option<TranscriptRecorder>, // logs every transcript made under the domain
+}
+
+impl TranscriptDomain {
+    pub fn new(context: &[u8], public_digest: [u8; DIGEST_LEN]) -> Self {
+        TranscriptDomain {
+            context: context.to_vec(),
+            public_digest,
+            session_id: None,
+            recorder: None,
+        }
+    }
+
+    // binds every proof made or checked under this domain to session_id, a domain without one
+    // gives the same transcripts as before
+    pub fn with_session(self, session_id: &[u8]) -> Self {
+        TranscriptDomain {
+            session_id: Some(session_id.to_vec()),
+            ..self
+        }
+    }
+
+    // the transcripts of a prove or verify call under this domain go to recorder, see
+    // TranscriptRecorder
+    pub fn with_recorder(self, recorder: &TranscriptRecorder) -> Self {
+        TranscriptDomain {
+            recorder: Some(recorder.clone()),
+            ..self
+        }
+    }
+
+    pub fn transcript(&self) -> Transcript {
+        let mut transcript = Transcript::new_with_domain(&self.context, &self.public_digest);
+        if let Some(recorder) = &self.recorder {
+            transcript = transcript.with_recorder(recorder);
+        }
+        if let Some(session_id) = &self.session_id {
+            transcript.absorb_digest(DigestTag::Session, &public_digest(session_id));
+        }
+
+        transcript
+    }
+}
+
+//=========================================================================================
+// Challenge derivation, kept in one place so every module (and every version) agrees on it
+// a challenge is the 32 byte Keccak squeeze read as a big-endian integer and reduced mod p
+// an index is the lowest 64 bit limb of that challenge reduced mod bound, the reduction is done
+// on the u64 before the cast so a 32 bit target picks the same index as a 64 bit one
+//=========================================================================================
+pub fn challenge_from_digest<F: PrimeField>(digest: &[u8]) -> F {
+    if digest.len() != DIGEST_LEN {
+        panic!("A challenge is derived from a {} byte squeeze", DIGEST_LEN);
+    }
+
+    F::from_be_bytes_mod_order(digest)
+}
+
+pub fn index_from_challenge<F: PrimeField>(challenge: F, bound: usize) -> usize {
+    let low_limb = challenge.into_bigint().as_ref()[0];
+
+    (low_limb % bound as u64) as usize
+}
+
+// digest of whatever public data the application binds the proof to
+pub fn public_digest(public_data: &[u8]) -> [u8; DIGEST_LEN] {
+    Keccak256::digest(public_data).into()
+}
+
+fn leading_zero_bits(bytes: &[u8]) -> usize {
+    let mut bits = 0;
+    for byte in bytes.iter() {
+        bits += byte.leading_zeros() as usize;
+        if *byte != 0 {
+            break;
+        }
+    }
+
+    bits
+}
+
+// verifiers check this before absorbing roots that came in with a proof
+pub fn is_digest(root: &[u8]) -> bool {
+    root.len() == DIGEST_LEN
+}
+
+//=========================================================================================
+// TranscriptRecorder keeps a labelled log of every absorb/squeeze
+// run the prover and the verifier with a recorder each, then diff the two logs
+// first_divergence points at the first step where they stopped agreeing
+// a transcript logs into one with Transcript::with_recorder, and every transcript a prove or
+// verify entry point makes does under TranscriptDomain::with_recorder, the label of an event is
+// the schedule step it followed (empty if there was none)
+// the recorder can also be used as a transcript itself through its own absorb/squeeze
+// clones share the log, two recorders are equal when they are the same log
+//=========================================================================================
+type EventLog = Arc<Mutex<Vec<TranscriptEvent>>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptEvent {
+    Absorb { label: String, data: Vec<u8> },
+    Squeeze { label: String, output: Vec<u8> },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Divergence {
+    pub index: usize,
+    pub left: Option<TranscriptEvent>, // None if this log ended first
+    pub right: Option<TranscriptEvent>,
+}
+
+#[derive(Clone)]
+pub struct TranscriptRecorder {
+    transcript: Transcript,
+    events: EventLog,
+}
+
+impl PartialEq for TranscriptRecorder {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.events, &other.events)
+    }
+}
+
+impl Eq for TranscriptRecorder {}
+
+impl fmt::Debug for TranscriptRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TranscriptRecorder")
+            .field("events", &self.events().len())
+            .finish()
+    }
+}
+
+impl Default for TranscriptRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TranscriptRecorder {
+    pub fn new() -> Self {
+        TranscriptRecorder {
+            transcript: Transcript::new(),
+            events: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn absorb(&mut self, label: &str, byte_array: &[u8]) {
+        self.transcript.absorb(byte_array);
+        self.events.lock().unwrap().push(TranscriptEvent::Absorb {
+            label: label.to_string(),
+            data: byte_array.to_vec(),
+        });
+    }
+
+    pub fn squeeze(&mut self, label: &str) -> Vec<u8> {
+        let output = self.transcript.squeeze();
+        self.events.lock().unwrap().push(TranscriptEvent::Squeeze {
+            label: label.to_string(),
+            output: output.clone(),
+        });
+
+        output
+    }
+
+    pub fn events(&self) -> Vec<TranscriptEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    pub fn first_divergence(&self, other: &TranscriptRecorder) -> Option<Divergence> {
+        let (events, other_events) = (self.events(), other.events());
+        let max_len = events.len().max(other_events.len());
+
+        for index in 0..max_len {
+            let left = events.get(index);
+            let right = other_events.get(index);
+
+            if left != right {
+                return Some(Divergence {
+                    index,
+                    left: left.cloned(),
+                    right: right.cloned(),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+// the test fixtures are shared with the crates above through the test-utils feature
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test {
+    use super::*;
+
+    // the domain every test proof is made under
+    pub fn domain() -> TranscriptDomain {
+        TranscriptDomain::new(b"zero-knowledge-tests", public_digest(b""))
+    }
+
+    #[cfg(feature = "bn254")]
+    #[test]
+    fn test_challenge_derivation() {
+        use ark_bn254::Fr;
+
+        let mut transcript = domain().transcript();
+        let mut same = domain().transcript();
+
+        let challenge = transcript.squeeze_field::<Fr>();
+        assert_eq!(challenge, Fr::from_be_bytes_mod_order(&same.squeeze()));
+
+        // the index is the low limb reduced as a u64, whatever the width of usize
+        let value = (1u64 << 40) + 5;
+        assert_eq!(
+            index_from_challenge(Fr::from(value), 1000),
+            (value % 1000) as usize
+        );
+        assert_eq!(
+            transcript.squeeze_index::<Fr>(1000),
+            index_from_challenge(same.squeeze_field::<Fr>(), 1000)
+        );
+    }
+
+    #[test]
+    fn test_domains_separate_transcripts() {
+        let mut first = domain().transcript();
+        let mut other_app = TranscriptDomain::new(b"other-app", public_digest(b"")).transcript();
+        let mut other_inputs =
+            TranscriptDomain::new(b"zero-knowledge-tests", public_digest(b"1695")).transcript();
+
+        let challenge = first.squeeze();
+        assert_ne!(challenge, other_app.squeeze());
+        assert_ne!(challenge, other_inputs.squeeze());
+        assert_eq!(challenge, domain().transcript().squeeze());
+
+        // the context is length prefixed, moving bytes into the digest does not collide
+        let mut shifted =
+            TranscriptDomain::new(b"zero-knowledge-test", public_digest(b"")).transcript();
+        assert_ne!(challenge, shifted.squeeze());
+
+        // every session gets its own challenges, the empty id is still a session
+        let session = |id: &[u8]| domain().with_session(id).transcript().squeeze();
+        assert_ne!(challenge, session(b"1"));
+        assert_ne!(challenge, session(b""));
+        assert_ne!(session(b"1"), session(b"2"));
+        assert_eq!(session(b"1"), session(b"1"));
+    }
+
+    #[test]
+    fn test_grinding() {
+        let mut prover = Transcript::new();
+        prover.absorb(b"statement");
+        let nonce = prover.grind(8);
+
+        let mut verifier = Transcript::new();
+        verifier.absorb(b"statement");
+        assert!(verifier.check_grinding(8, nonce));
+        assert_eq!(prover.squeeze(), verifier.squeeze());
+
+        assert_eq!(leading_zero_bits(&[0, 0b0001_0000, 0xff]), 11);
+        assert_eq!(leading_zero_bits(&[0b1000_0000]), 0);
+    }
+
+    #[test]
+    fn test_digest_tags_separate_roots_and_commitments() {
+        let digest = [7u8; DIGEST_LEN];
+
+        let mut root_transcript = Transcript::new();
+        root_transcript.absorb_digest(DigestTag::MerkleRoot, &digest);
+        let mut commitment_transcript = Transcript::new();
+        commitment_transcript.absorb_digest(DigestTag::Commitment, &digest);
+        let mut raw_transcript = Transcript::new();
+        raw_transcript.absorb(&digest);
+
+        let root_challenge = root_transcript.squeeze();
+        assert_ne!(root_challenge, commitment_transcript.squeeze());
+        assert_ne!(root_challenge, raw_transcript.squeeze());
+    }
+
+    #[cfg(feature = "bls12-381")]
+    #[test]
+    fn test_commitment_digest_is_fixed_width() {
+        use ark_bls12_381::G1Projective;
+        use ark_ec::PrimeGroup;
+
+        let g = G1Projective::generator();
+        assert_eq!(commitment_digest(&g).len(), DIGEST_LEN);
+        assert_ne!(commitment_digest(&g), commitment_digest(&(g + g)));
+        assert!(is_digest(&crate::merkle_tree::MerkleTree::hash(b"leaf")));
+    }
+
+    #[test]
+    fn test_labeled_absorption() {
+        let mut first = Transcript::new();
+        first.absorb_labeled(b"poly", b"ab");
+        first.absorb_labeled(b"sum", b"c");
+
+        // moving a byte across a label boundary or relabelling changes the challenge
+        let mut shifted = Transcript::new();
+        shifted.absorb_labeled(b"poly", b"a");
+        shifted.absorb_labeled(b"sum", b"bc");
+        let mut relabelled = Transcript::new();
+        relabelled.absorb_labeled(b"pol", b"yab");
+        relabelled.absorb_labeled(b"sum", b"c");
+
+        let challenge = first.squeeze();
+        assert_ne!(challenge, shifted.squeeze());
+        assert_ne!(challenge, relabelled.squeeze());
+    }
+
+    #[test]
+    fn test_forks() {
+        let mut parent = domain().transcript();
+        parent.absorb(b"statement");
+
+        // the fork depends on its parent and its label
+        let challenge = parent.fork(b"sub").squeeze();
+        assert_eq!(challenge, parent.fork(b"sub").squeeze());
+        assert_ne!(challenge, parent.fork(b"other").squeeze());
+        assert_ne!(challenge, Transcript::new().fork(b"sub").squeeze());
+
+        let mut session = domain().with_session(b"1").transcript();
+        session.absorb(b"statement");
+        assert_ne!(challenge, session.fork(b"sub").squeeze());
+
+        // and the parent doesn't see it
+        let mut same = domain().transcript();
+        same.absorb(b"statement");
+        parent.fork(b"sub").absorb(b"round poly");
+        assert_eq!(parent.squeeze(), same.squeeze());
+    }
+
+    #[test]
+    fn test_hashers() {
+        let mut keccak = Transcript::new();
+        let mut sha = Transcript::<Sha256>::with_hasher();
+        keccak.absorb(b"statement");
+        sha.absorb(b"statement");
+
+        // the default hasher is Keccak256, Sha256 is the plain SHA-256 of the same stream
+        let mut expected = <Sha256 as sha2::Digest>::new();
+        sha2::Digest::update(&mut expected, b"statement");
+        assert_eq!(sha.squeeze(), sha2::Digest::finalize(expected).to_vec());
+        assert_eq!(keccak.squeeze(), Keccak256::digest(b"statement").to_vec());
+
+        let nonce = sha.grind(4);
+        let mut verifier = Transcript::<Sha256>::with_hasher();
+        verifier.absorb(b"statement");
+        verifier.squeeze();
+        assert!(verifier.check_grinding(4, nonce));
+    }
+
+    #[test]
+    fn test_recorder_matches_transcript() {
+        let mut transcript = Transcript::new();
+        let mut recorder = TranscriptRecorder::new();
+
+        transcript.absorb(b"commitment");
+        recorder.absorb("commitment", b"commitment");
+
+        assert_eq!(transcript.squeeze(), recorder.squeeze("r_0"));
+        assert_eq!(recorder.events().len(), 2);
+    }
+
+    #[test]
+    fn test_first_divergence() {
+        let mut prover = TranscriptRecorder::new();
+        let mut verifier = TranscriptRecorder::new();
+
+        prover.absorb("w_0", &[1, 2, 3]);
+        verifier.absorb("w_0", &[1, 2, 3]);
+        prover.squeeze("r_a");
+        verifier.squeeze("r_a");
+
+        prover.absorb("round_poly_0", &[4, 5]);
+        verifier.absorb("round_poly_0", &[4, 6]);
+        prover.squeeze("r_0");
+        verifier.squeeze("r_0");
+
+        let divergence = prover.first_divergence(&verifier).unwrap();
+        assert_eq!(divergence.index, 2);
+        assert_eq!(
+            divergence.left,
+            Some(TranscriptEvent::Absorb {
+                label: "round_poly_0".to_string(),
+                data: vec![4, 5],
+            })
+        );
+    }
+
+    #[test]
+    fn test_no_divergence_and_early_end() {
+        let mut prover = TranscriptRecorder::new();
+        let mut verifier = TranscriptRecorder::new();
+
+        prover.absorb("w_0", &[1]);
+        verifier.absorb("w_0", &[1]);
+        assert_eq!(prover.first_divergence(&verifier), None);
+
+        prover.squeeze("r_a");
+        let divergence = prover.first_divergence(&verifier).unwrap();
+        assert_eq!(divergence.index, 1);
+        assert_eq!(divergence.right, None);
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+
+//=========================================================================================
+// Transcript schedules: the absorption order of a protocol written down as data
+// a Schedule is a list of labelled steps, absorb (a prover message or part of the statement),
+// squeeze (a challenge) and repeat (the inner steps any number of times in a row, once per
+// round, layer, commitment, ...), e.g. a sum-check is
+//   absorb poly, absorb claimed sum, repeat rounds { absorb round poly, squeeze r }
+// the prover and the verifier each attach the protocol's schedule to their transcript
+// (Transcript::with_schedule) and go through it step by step (transcript.follow(step)), a step
+// that is not next in the schedule panics on whichever side took it, so the two sides cannot
+// drift apart without every test of the protocol failing on the spot
+// the labels are never hashed, a schedule changes nothing in the proofs, and Display prints the
+// schedule as the wire format reference, see the tests for the schedules of record
+// a Repeat has to start with an absorb or a squeeze and its first step must not also be the step
+// after it, so the next step always decides whether to go round again
+//=========================================================================================
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Absorb(&'static str),
+    Squeeze(&'static str),
+    Repeat(&'static str, &'static [Step]),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Schedule {
+    pub protocol: &'static str,
+    pub steps: &'static [Step],
+}
+
+// where a transcript is in its schedule, every frame is a list of steps and the next one in it,
+// a frame under a Repeat points at that Repeat until its steps are left
+#[derive(Debug, Clone)]
+pub struct ScheduleCursor {
+    schedule: &'static Schedule,
+    frames: Vec<(&'static [Step], usize)>,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Absorb(label) => write!(f, "absorb {}", label),
+            Step::Squeeze(label) => write!(f, "squeeze {}", label),
+            Step::Repeat(label, _) => write!(f, "repeat {}", label),
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_steps(f: &mut fmt::Formatter<'_>, steps: &[Step], depth: usize) -> fmt::Result {
+            for step in steps {
+                writeln!(f, "{:indent$}{}", "", step, indent = 2 * depth)?;
+                if let Step::Repeat(_, inner) = step {
+                    write_steps(f, inner, depth + 1)?;
+                }
+            }
+            Ok(())
+        }
+
+        writeln!(f, "{}", self.protocol)?;
+        write_steps(f, self.steps, 1)
+    }
+}
+
+fn first_step(steps: &[Step]) -> Option<Step> {
+    match steps.first() {
+        Some(Step::Repeat(_, inner)) => first_step(inner),
+        step => step.copied(),
+    }
+}
+
+impl ScheduleCursor {
+    pub fn new(schedule: &'static Schedule) -> Self {
+        ScheduleCursor {
+            schedule,
+            frames: vec![(schedule.steps, 0)],
+        }
+    }
+
+    pub fn schedule(&self) -> &'static Schedule {
+        self.schedule
+    }
+
+    // moves past step, false (and the cursor stays where it was) if step can't come next
+    pub fn advance(&mut self, step: Step) -> bool {
+        if matches!(step, Step::Repeat(..)) {
+            panic!("The transcript takes absorb and squeeze steps, a repeat is only in a schedule");
+        }
+
+        let mut frames = self.frames.clone();
+        loop {
+            let depth = frames.len() - 1;
+            let (steps, index) = frames[depth];
+            match steps.get(index) {
+                Some(Step::Repeat(_, inner)) => {
+                    if first_step(inner) == Some(step) {
+                        frames.push((inner, 0));
+                    } else {
+                        frames[depth].1 += 1;
+                    }
+                }
+                Some(next) => {
+                    if *next != step {
+                        return false;
+                    }
+                    frames[depth].1 += 1;
+                    self.frames = frames;
+                    return true;
+                }
+                // the end of a repeat: round again or carry on after it
+                None if depth > 0 => {
+                    if first_step(steps) == Some(step) {
+                        frames[depth].1 = 0;
+                    } else {
+                        frames.pop();
+                        frames[depth - 1].1 += 1;
+                    }
+                }
+                None => return false,
+            }
+        }
+    }
+
+    // true once everything left can be skipped, i.e. only repeats remain
+    pub fn finished(&self) -> bool {
+        let innermost = self.frames.len() - 1;
+        self.frames
+            .iter()
+            .enumerate()
+            .all(|(depth, (steps, index))| {
+                let rest = if depth < innermost {
+                    &steps[index + 1..]
+                } else {
+                    &steps[*index..]
+                };
+                rest.iter().all(|step| matches!(step, Step::Repeat(..)))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    static ROUNDS: Schedule = Schedule {
+        protocol: "rounds",
+        steps: &[
+            Step::Absorb("statement"),
+            Step::Repeat(
+                "rounds",
+                &[Step::Absorb("round poly"), Step::Squeeze("challenge")],
+            ),
+            Step::Squeeze("final"),
+        ],
+    };
+
+    #[test]
+    fn test_cursor() {
+        let mut cursor = ScheduleCursor::new(&ROUNDS);
+        assert!(!cursor.advance(Step::Absorb("round poly")));
+        assert!(cursor.advance(Step::Absorb("statement")));
+        for _ in 0..3 {
+            assert!(cursor.advance(Step::Absorb("round poly")));
+            assert!(!cursor.finished());
+            assert!(!cursor.advance(Step::Absorb("round poly")));
+            assert!(cursor.advance(Step::Squeeze("challenge")));
+        }
+        assert!(cursor.advance(Step::Squeeze("final")));
+        assert!(cursor.finished());
+        assert!(!cursor.advance(Step::Squeeze("final")));
+
+        // no rounds at all is a repeat of 0
+        let mut cursor = ScheduleCursor::new(&ROUNDS);
+        assert!(cursor.advance(Step::Absorb("statement")));
+        assert!(cursor.advance(Step::Squeeze("final")));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{
+    multi_linear::{EvaluationTable, MultiLinearPoly},
+    product_poly::ProductPoly,
+};
+use ark_ff::PrimeField;
+use std::sync::Arc;
+
+//=========================================================================================
+// VirtualPolynomial (as in HyperPlonk): f(x) = Σⱼ cⱼ · ∏_{i ∈ Sⱼ} gᵢ(x) over shared MLEs gᵢ
+// the products are only lists of indices into the MLEs, so an MLE that appears in several
+// products (W(b) in both GKR terms, eq in every HyperPlonk gate term) is stored once, and neither
+// a product table nor a sum table is ever built
+//   round_evaluations: [h(0), ..., h(d)] for the first free variable with d the largest product,
+//                      every entry of the half tables is read once and gᵢ(t, x') is stepped from
+//                      gᵢ(0, x') by gᵢ(1, x') - gᵢ(0, x'), so a round needs no copies at all
+//   fix_variable:      binds the first free variable (the most significant bit, the same order
+//                      as MultiLinearPoly::partial_evaluate(r, 0)) of every MLE once
+// an MLE added through an Arc that is already there (Arc::ptr_eq) is not added again
+// partial_sum_check::proof runs on this, a Vec<ProductPoly> converts with from_products
+//=========================================================================================
+#[derive(Debug, Clone)]
+pub struct VirtualPolynomial<F: PrimeField> {
+    num_vars: usize,
+    max_degree: usize,
+    products: Vec<(F, Vec<usize>)>, // (cⱼ, indices into mles)
+    mles: Vec<Arc<MultiLinearPoly<F>>>,
+}
+
+impl<F: PrimeField> VirtualPolynomial<F> {
+    // the zero polynomial in num_vars variables
+    pub fn new(num_vars: usize) -> Self {
+        VirtualPolynomial {
+            num_vars,
+            max_degree: 0,
+            products: Vec::new(),
+            mles: Vec::new(),
+        }
+    }
+
+    pub fn new_from_mle(mle: Arc<MultiLinearPoly<F>>, coefficient: F) -> Self {
+        let mut poly = VirtualPolynomial::new(mle.computation.len().ilog2() as usize);
+        poly.add_product([mle], coefficient);
+
+        poly
+    }
+
+    // Σ over the products of every ProductPoly with coefficient 1, the same polynomial and
+    // (through partial_sum_check) the same proof
+    pub fn from_products(sum_poly: Vec<ProductPoly<F>>) -> Self {
+        let num_vars = sum_poly[0].poly_array[0].computation.len().ilog2() as usize;
+        let mut poly = VirtualPolynomial::new(num_vars);
+        for product in sum_poly {
+            poly.add_product(product.poly_array.into_iter().map(Arc::new), F::one());
+        }
+
+        poly
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    // the degree of every round polynomial
+    pub fn max_degree(&self) -> usize {
+        self.max_degree
+    }
+
+    pub fn num_products(&self) -> usize {
+        self.products.len()
+    }
+
+    pub fn mles(&self) -> &[Arc<MultiLinearPoly<F>>] {
+        &self.mles
+    }
+
+    // self += coefficient · ∏ mles
+    pub fn add_product(
+        &mut self,
+        mles: impl IntoIterator<Item = Arc<MultiLinearPoly<F>>>,
+        coefficient: F,
+    ) {
+        let indices: Vec<usize> = mles.into_iter().map(|mle| self.index_of(mle)).collect();
+        if indices.is_empty() {
+            panic!("A product needs at least one MLE");
+        }
+
+        self.max_degree = self.max_degree.max(indices.len());
+        self.products.push((coefficient, indices));
+    }
+
+    // self *= coefficient · mle, every product gains a factor
+    pub fn mul_by_mle(&mut self, mle: Arc<MultiLinearPoly<F>>, coefficient: F) {
+        let index = self.index_of(mle);
+        for (c, indices) in self.products.iter_mut() {
+            *c *= coefficient;
+            indices.push(index);
+        }
+        self.max_degree += 1;
+    }
+
+    fn index_of(&mut self, mle: Arc<MultiLinearPoly<F>>) -> usize {
+        if mle.computation.len() != 1 << self.num_vars {
+            panic!("The MLE must have as many variables as the virtual polynomial");
+        }
+
+        match self.mles.iter().position(|other| Arc::ptr_eq(other, &mle)) {
+            Some(index) => index,
+            None => {
+                self.mles.push(mle);
+                self.mles.len() - 1
+            }
+        }
+    }
+
+    pub fn evaluate(&self, point: &[F]) -> F {
+        if point.len() != self.num_vars {
+            panic!("The number of eval points must be equal to the number of variables");
+        }
+
+        let table = EvaluationTable::new(point);
+        let values: Vec<F> = self.mles.iter().map(|mle| table.evaluate(mle)).collect();
+
+        self.combine(&values)
+    }
+
+    // Σ over the hypercube, the claim a sum-check over self starts from
+    pub fn sum(&self) -> F {
+        (0..1 << self.num_vars)
+            .map(|x| {
+                let values: Vec<F> = self.mles.iter().map(|mle| mle.computation[x]).collect();
+                self.combine(&values)
+            })
+            .sum()
+    }
+
+    // Σⱼ cⱼ · ∏ values[i] with values one per MLE
+    fn combine(&self, values: &[F]) -> F {
+        self.products
+            .iter()
+            .map(|(c, indices)| indices.iter().fold(*c, |acc, i| acc * values[*i]))
+            .sum()
+    }
+
+    // [h(0), ..., h(max_degree)] with h(t) = Σ_{x'} f(t, x')
+    pub fn round_evaluations(&self) -> Vec<F> {
+        if self.num_vars == 0 {
+            panic!("The virtual polynomial has no variable left to sum over");
+        }
+
+        let half = 1 << (self.num_vars - 1);
+        let mut evals = vec![F::zero(); self.max_degree + 1];
+        let mut values = vec![F::zero(); self.mles.len()];
+        let mut steps = vec![F::zero(); self.mles.len()];
+
+        for x in 0..half {
+            for (i, mle) in self.mles.iter().enumerate() {
+                values[i] = mle.computation[x];
+                steps[i] = mle.computation[half + x] - values[i];
+            }
+            for eval in evals.iter_mut() {
+                *eval += self.combine(&values);
+                values
+                    .iter_mut()
+                    .zip(steps.iter())
+                    .for_each(|(value, step)| *value += step);
+            }
+        }
+
+        evals
+    }
+
+    pub fn fix_variable(&mut self, challenge: F) {
+        if self.num_vars == 0 {
+            panic!("The virtual polynomial has no variable left to fix");
+        }
+
+        let half = 1 << (self.num_vars - 1);
+        for mle in self.mles.iter_mut() {
+            let computation = &mle.computation;
+            let folded: Vec<F> = (0..half)
+                .map(|x| computation[x] + (computation[half + x] - computation[x]) * challenge)
+                .collect();
+            *mle = Arc::new(MultiLinearPoly {
+                computation: folded,
+            });
+        }
+        self.num_vars -= 1;
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::UniformRand;
+
+    fn random_mle(num_vars: usize) -> Arc<MultiLinearPoly<Fr>> {
+        let mut rng = rand::thread_rng();
+        let computation: Vec<Fr> = (0..1 << num_vars).map(|_| Fr::rand(&mut rng)).collect();
+        Arc::new(MultiLinearPoly::new(&computation))
+    }
+
+    #[test]
+    fn test_shared_mles_and_mixed_degrees() {
+        // f = 2·a·b·c + 3·b - a·b, with b stored once
+        let (a, b, c) = (random_mle(2), random_mle(2), random_mle(2));
+        let mut poly = VirtualPolynomial::new(2);
+        poly.add_product([a.clone(), b.clone(), c.clone()], Fr::from(2));
+        poly.add_product([b.clone()], Fr::from(3));
+        poly.add_product([a.clone(), b.clone()], -Fr::from(1));
+        assert_eq!(poly.mles().len(), 3);
+        assert_eq!(poly.max_degree(), 3);
+
+        let f = |x: usize| {
+            let (a, b, c) = (a.computation[x], b.computation[x], c.computation[x]);
+            Fr::from(2) * a * b * c + Fr::from(3) * b - a * b
+        };
+        assert_eq!(poly.sum(), (0..4).map(f).sum::<Fr>());
+
+        // h(0) + h(1) is the sum, and h(t) is the sum with the first variable at t
+        let evals = poly.round_evaluations();
+        assert_eq!(evals.len(), 4);
+        assert_eq!(evals[0] + evals[1], poly.sum());
+        let mut fixed = poly.clone();
+        fixed.fix_variable(Fr::from(3));
+        assert_eq!(evals[3], fixed.sum());
+
+        let point = [Fr::from(4), Fr::from(9)];
+        let mut folded = poly.clone();
+        folded.fix_variable(point[0]);
+        folded.fix_variable(point[1]);
+        assert_eq!(poly.evaluate(&point), folded.evaluate(&[]));
+
+        // g·f multiplies every product
+        let mut scaled = poly.clone();
+        scaled.mul_by_mle(c.clone(), Fr::from(5));
+        assert_eq!(scaled.mles().len(), 3);
+        assert_eq!(
+            scaled.evaluate(&point),
+            Fr::from(5) * EvaluationTable::new(&point).evaluate(&c) * poly.evaluate(&point)
+        );
+    }
+}
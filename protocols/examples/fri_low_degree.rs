@@ -0,0 +1,66 @@
+use ark_bn254::Fr;
+use ark_ff::UniformRand;
+use std::time::Instant;
+use zk_protocols::{
+    fri::{
+        lde::lde,
+        low_degree::{prove_low_degree, verify_low_degree, LowDegreeConfig},
+    },
+    security::SecurityConfig,
+    transcript::{public_digest, TranscriptDomain},
+};
+
+//=========================================================================================
+// FRI as a degree-bound check on the low degree extension of a random polynomial
+// the query count and grinding come from a 100 bit SecurityConfig at blowup 4, then a polynomial
+// of twice the degree over the same domain is shown to fail
+// cargo run --release --example fri_low_degree
+//=========================================================================================
+const DEGREE_BOUND: usize = 1 << 14;
+const BLOWUP: usize = 4;
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let coefficients: Vec<Fr> = (0..DEGREE_BOUND).map(|_| Fr::rand(&mut rng)).collect();
+
+    let security = SecurityConfig::for_field::<Fr>(100, BLOWUP);
+    let config = LowDegreeConfig::from_security(&security, BLOWUP);
+    let domain = TranscriptDomain::new(b"fri-low-degree-example", public_digest(b""));
+
+    let start = Instant::now();
+    let evals = lde(&coefficients, BLOWUP, Fr::from(1));
+    println!(
+        "degree < {}, {} evaluations, {} queries: lde {:?}",
+        DEGREE_BOUND,
+        evals.len(),
+        config.num_queries,
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    let proof = prove_low_degree(&evals, DEGREE_BOUND, &config, &domain);
+    println!("prove:  {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let valid = verify_low_degree(&proof, DEGREE_BOUND, &config, &domain);
+    println!("verify: {:?}", start.elapsed());
+    assert!(valid, "the proof must verify");
+
+    println!("{}", proof.size_report());
+
+    // twice the degree at half the blowup fills the same domain, the bound no longer holds
+    let coefficients: Vec<Fr> = (0..2 * DEGREE_BOUND).map(|_| Fr::rand(&mut rng)).collect();
+    let evals = lde(&coefficients, BLOWUP / 2, Fr::from(1));
+    let proof = prove_low_degree(&evals, DEGREE_BOUND, &config, &domain);
+    let valid = verify_low_degree(&proof, DEGREE_BOUND, &config, &domain);
+    println!(
+        "degree {} against the bound {}: {}",
+        2 * DEGREE_BOUND - 1,
+        DEGREE_BOUND,
+        valid
+    );
+    assert!(
+        !valid,
+        "a polynomial over the degree bound must be rejected"
+    );
+}
@@ -0,0 +1,134 @@
+use ark_bn254::Fr;
+use ark_ff::Field;
+use std::time::Instant;
+use zk_protocols::{
+    gkr::{
+        gkr_circuit::{Circuit, Gate, GateOp, Layer, WireId},
+        gkr_protocol::GKRProof,
+    },
+    transcript::{public_digest, TranscriptDomain},
+};
+
+//=========================================================================================
+// GKR over the S-box layer of a Poseidon-style permutation: (x + c)⁵ for every lane
+// the crate has no Poseidon instance and the GKR provers only take layers that halve, so the
+// MDS mix (which keeps the state width) is left out and the constants are illustrative,
+// what is proven is the nonlinear part, which is where the gates of a Poseidon round go
+// every lane has 16 input wires [x, c, 0, ...] and four layers:
+//   8 gates: t = x + c (twice), then zeros
+//   4 gates: t², t
+//   2 gates: t⁴, t
+//   1 gate:  t⁵
+// the provers build addᵢ / mulᵢ as dense tables over (a, b, c), which grows as the cube of the
+// layer width, 16 lanes (one width 16 state) keeps the first layer's table at 2²³ entries
+// cargo run --release --example gkr_poseidon
+//=========================================================================================
+const LANES: usize = 16;
+const LANE_INPUTS: usize = 16;
+
+fn gate(left: usize, right: usize, op: GateOp, output: usize) -> Gate {
+    Gate {
+        left: WireId(left),
+        right: WireId(right),
+        op,
+        output: WireId(output),
+    }
+}
+
+fn sbox_circuit(state: &[Fr], constants: &[Fr]) -> Circuit<Fr> {
+    let mut inputs = vec![Fr::from(0); LANES * LANE_INPUTS];
+    for lane in 0..LANES {
+        inputs[lane * LANE_INPUTS] = state[lane];
+        inputs[lane * LANE_INPUTS + 1] = constants[lane];
+    }
+    let mut circuit = Circuit::new(inputs);
+
+    // every layer is the same lane pattern repeated, lane offsets are per layer width
+    let layer = |width: usize, lane_gates: &[(usize, usize, GateOp)]| {
+        let below = 2 * width;
+        let gates = (0..LANES)
+            .flat_map(|lane| {
+                lane_gates
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, (left, right, op))| {
+                        gate(
+                            lane * below + left,
+                            lane * below + right,
+                            *op,
+                            lane * width + i,
+                        )
+                    })
+            })
+            .collect();
+
+        Layer { gates }
+    };
+
+    let zero = (2, 3, GateOp::Add);
+    circuit.add_layer(layer(
+        8,
+        &[
+            (0, 1, GateOp::Add),
+            (0, 1, GateOp::Add),
+            zero,
+            zero,
+            zero,
+            zero,
+            zero,
+            zero,
+        ],
+    ));
+    circuit.add_layer(layer(
+        4,
+        &[
+            (0, 1, GateOp::Mul),
+            (0, 2, GateOp::Add),
+            (2, 2, GateOp::Add),
+            (2, 2, GateOp::Add),
+        ],
+    ));
+    circuit.add_layer(layer(2, &[(0, 0, GateOp::Mul), (1, 2, GateOp::Add)]));
+    circuit.add_layer(layer(1, &[(0, 1, GateOp::Mul)]));
+
+    circuit
+}
+
+fn main() {
+    let state: Vec<Fr> = (0..LANES as u64).map(|i| Fr::from(i * i + 1)).collect();
+    let constants: Vec<Fr> = (0..LANES as u64).map(|i| Fr::from(7 * i + 3)).collect();
+
+    let circuit = sbox_circuit(&state, &constants);
+    let public = circuit.public_inputs();
+    let expected: Vec<Fr> = state
+        .iter()
+        .zip(constants.iter())
+        .map(|(x, c)| (*x + c).pow([5]))
+        .collect();
+    assert_eq!(
+        public.outputs, expected,
+        "the circuit must compute (x + c)^5"
+    );
+
+    let domain = TranscriptDomain::new(b"gkr-poseidon-example", public_digest(b""));
+    println!(
+        "{} lanes, {} inputs, {} layers",
+        LANES,
+        circuit.inputs.len(),
+        circuit.layers.len()
+    );
+
+    let start = Instant::now();
+    let proof = circuit.proof(&domain);
+    println!("prove:  {:?}", start.elapsed());
+
+    let bytes = proof.to_bytes().expect("the proof serializes");
+    let decoded = GKRProof::<Fr>::from_bytes(&bytes).expect("the proof decodes");
+
+    let start = Instant::now();
+    let valid = circuit.verify(&decoded, &public, &domain);
+    println!("verify: {:?}", start.elapsed());
+    assert!(valid, "the proof must verify");
+
+    println!("{}", proof.size_report());
+}
@@ -0,0 +1,65 @@
+use ark_bn254::{Fr, G1Projective};
+use ark_ff::UniformRand;
+use std::time::Instant;
+use zk_protocols::shamir_secret_sharing::{
+    generate_verifiable_shares, reconstruct_from_bytes, ShamirShare, ShareFormatError,
+    ShareMetadata,
+};
+
+//=========================================================================================
+// A verifiable 67-of-100 dealing, sent over the wire and put back together
+// every share is checked against the Feldman commitments, encoded with the dealing's
+// metadata, and any threshold of the encoded shares reconstructs the secret
+// too few shares, or a share from another dealing, is an error instead of a wrong secret
+// cargo run --release --example threshold_shamir
+//=========================================================================================
+const THRESHOLD: u8 = 67;
+const NUM_SHARES: u8 = 100;
+
+fn main() {
+    let secret = Fr::rand(&mut rand::thread_rng());
+
+    let start = Instant::now();
+    let (shares, commitments) =
+        generate_verifiable_shares::<Fr, G1Projective>(secret, THRESHOLD, NUM_SHARES);
+    println!(
+        "deal {}-of-{}: {:?}",
+        THRESHOLD,
+        NUM_SHARES,
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    assert!(shares.iter().all(|share| share.verify(&commitments)));
+    println!("verify every share: {:?}", start.elapsed());
+
+    let metadata = ShareMetadata::new(THRESHOLD, NUM_SHARES).with_commitments(&commitments);
+    let encoded: Vec<Vec<u8>> = shares
+        .iter()
+        .map(|share| share.to_bytes(&metadata))
+        .collect();
+    println!("{} bytes per share", encoded[0].len());
+
+    let start = Instant::now();
+    let reconstructed =
+        reconstruct_from_bytes::<Fr>(&encoded[NUM_SHARES as usize - THRESHOLD as usize..])
+            .expect("a threshold of shares reconstructs");
+    println!("reconstruct: {:?}", start.elapsed());
+    assert_eq!(reconstructed, secret);
+
+    let too_few = reconstruct_from_bytes::<Fr>(&encoded[..THRESHOLD as usize - 1]);
+    assert!(matches!(
+        too_few,
+        Err(ShareFormatError::NotEnoughShares { .. })
+    ));
+
+    let other = ShamirShare::new(Fr::from(1), Fr::from(2))
+        .to_bytes(&ShareMetadata::new(THRESHOLD, NUM_SHARES));
+    let mut mixed = encoded[..THRESHOLD as usize - 1].to_vec();
+    mixed.push(other);
+    assert!(matches!(
+        reconstruct_from_bytes::<Fr>(&mixed),
+        Err(ShareFormatError::MixedShares)
+    ));
+    println!("too few and mixed shares are rejected");
+}
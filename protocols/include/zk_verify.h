@@ -0,0 +1,37 @@
+/*
+ * C declarations for the verifiers in src/ffi.rs, build the crate with the ffi feature
+ * (cargo build --release --features ffi) and link against libzk_protocols.a or
+ * libzk_protocols.so.
+ *
+ * A proof is the output of GKRProof::to_bytes and a verifying key the output of
+ * VerifyingKey::to_bytes, both over the bn254 scalar field. The buffers are only read during
+ * the call and are never freed or kept by the library.
+ *
+ * Keep in sync with src/ffi.rs, test_header_matches checks the two against each other.
+ */
+#ifndef ZK_VERIFY_H
+#define ZK_VERIFY_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ZK_VALID (1)
+#define ZK_INVALID (0)
+#define ZK_ERR_NULL (-1)  /* a null pointer with a non zero length */
+#define ZK_ERR_PROOF (-2) /* the proof bytes don't decode */
+#define ZK_ERR_KEY (-3)   /* the verifying key bytes don't decode */
+#define ZK_ERR_PANIC (-4)
+
+/* returns ZK_VALID, ZK_INVALID or one of the negative ZK_ERR_ codes */
+int32_t zk_verify_gkr(const uint8_t *proof_ptr, size_t proof_len,
+                       const uint8_t *vk_ptr, size_t vk_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ZK_VERIFY_H */
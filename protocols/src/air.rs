@@ -0,0 +1,7 @@
+pub mod air_error;
+pub mod constraints;
+pub mod trace;
+
+pub use air_error::AirError;
+pub use constraints::{Air, Frame};
+pub use trace::{ColumnType, TraceBuilder, TraceTable};
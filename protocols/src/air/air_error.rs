@@ -0,0 +1,55 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AirError {
+    DuplicateColumn(String),
+    UnknownColumn(String),
+    // a row closure left a cell empty
+    Unassigned { row: usize, column: String },
+    // the value does not fit the declared column type
+    TypeMismatch { row: usize, column: String },
+    EmptyTrace,
+    // the AIR was written for other columns than the trace has
+    ColumnMismatch,
+    // a periodic column must repeat a power of two number of values that fits in the trace
+    BadPeriod { name: String, len: usize },
+    // transition constraint `constraint` is not zero between `row` and `row + 1`
+    TransitionFailed { row: usize, constraint: usize },
+    BoundaryFailed { row: usize, column: String },
+}
+
+impl fmt::Display for AirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AirError::DuplicateColumn(name) => write!(f, "column `{}` is declared twice", name),
+            AirError::UnknownColumn(name) => write!(f, "the trace has no column `{}`", name),
+            AirError::Unassigned { row, column } => {
+                write!(f, "row {} never assigns column `{}`", row, column)
+            }
+            AirError::TypeMismatch { row, column } => write!(
+                f,
+                "row {} puts a value in column `{}` that does not fit its type",
+                row, column
+            ),
+            AirError::EmptyTrace => write!(f, "the trace has no rows or no columns"),
+            AirError::ColumnMismatch => write!(f, "the AIR and the trace have different columns"),
+            AirError::BadPeriod { name, len } => write!(
+                f,
+                "periodic column `{}` has {} values, not a power of two within the trace",
+                name, len
+            ),
+            AirError::TransitionFailed { row, constraint } => write!(
+                f,
+                "transition constraint {} does not hold from row {} to {}",
+                constraint,
+                row,
+                row + 1
+            ),
+            AirError::BoundaryFailed { row, column } => {
+                write!(f, "column `{}` has the wrong value in row {}", column, row)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AirError {}
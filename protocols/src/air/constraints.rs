@@ -0,0 +1,363 @@
+use ark_ff::{FftField, PrimeField};
+
+use crate::{
+    air::{AirError, TraceTable},
+    fri::{domain_cache::Domain, fft::FastFourierTransform},
+    UnivariatePoly,
+};
+
+//=========================================================================================
+// Constraints of an AIR over a TraceTable with n rows (n a power of two, row i sits at ωⁱ)
+//   transition: C(current row, next row, periodic values) == 0 for every step i -> i + 1
+//               except the wrap-around from n - 1 to 0, so the zerofier is (xⁿ - 1) / (x - ωⁿ⁻¹)
+//               (padding repeats the last row, gate with a selector column if that breaks one)
+//   boundary:   the cell (row, column) equals a public value, quotient (T(x) - v) / (x - ωʳᵒʷ)
+//   periodic:   a column of k public constants repeated every k rows (round constants),
+//               P(x) interpolates them over the k-th roots of unity and the value for row i is
+//               P(x^(n/k)) at x = ωⁱ, so the verifier evaluates it at z^(n/k) without the trace
+// check() runs the constraints on a concrete trace (the prover's sanity check), the *_at
+// functions are what a verifier evaluates out of the domain from the openings T(z), T(ωz)
+//=========================================================================================
+pub type TransitionFn<F> = Box<dyn Fn(&Frame<F>) -> F + Send + Sync>;
+
+pub struct Air<F: PrimeField + FftField> {
+    names: Vec<String>,
+    transitions: Vec<TransitionFn<F>>,
+    boundaries: Vec<(usize, String, F)>, // (row, column, value)
+    periodic: Vec<(String, Vec<F>)>,
+}
+
+// what a transition constraint sees, by column name
+pub struct Frame<'a, F: PrimeField> {
+    names: &'a [String],
+    current: &'a [F],
+    next: &'a [F],
+    periodic_names: Vec<&'a str>,
+    periodic: &'a [F],
+}
+
+impl<F: PrimeField> Frame<'_, F> {
+    pub fn current(&self, name: &str) -> F {
+        self.current[Self::index_of(self.names.iter().map(|n| n.as_str()), name)]
+    }
+
+    pub fn next(&self, name: &str) -> F {
+        self.next[Self::index_of(self.names.iter().map(|n| n.as_str()), name)]
+    }
+
+    pub fn periodic(&self, name: &str) -> F {
+        self.periodic[Self::index_of(self.periodic_names.iter().copied(), name)]
+    }
+
+    // the constraint closures are written against the AIR's own columns, a wrong name is a bug
+    fn index_of<'b>(mut names: impl Iterator<Item = &'b str>, name: &str) -> usize {
+        names
+            .position(|n| n == name)
+            .unwrap_or_else(|| panic!("The AIR has no column `{}`", name))
+    }
+}
+
+impl<F: PrimeField + FftField> Air<F> {
+    pub fn new(columns: &[&str]) -> Self {
+        Air {
+            names: columns.iter().map(|name| name.to_string()).collect(),
+            transitions: vec![],
+            boundaries: vec![],
+            periodic: vec![],
+        }
+    }
+
+    pub fn transition<C: Fn(&Frame<F>) -> F + Send + Sync + 'static>(
+        mut self,
+        constraint: C,
+    ) -> Self {
+        self.transitions.push(Box::new(constraint));
+        self
+    }
+
+    pub fn boundary(mut self, column: &str, row: usize, value: F) -> Self {
+        self.boundaries.push((row, column.to_string(), value));
+        self
+    }
+
+    pub fn periodic(mut self, name: &str, values: Vec<F>) -> Self {
+        self.periodic.push((name.to_string(), values));
+        self
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.transitions.len() + self.boundaries.len()
+    }
+
+    fn frame<'a>(&'a self, current: &'a [F], next: &'a [F], periodic: &'a [F]) -> Frame<'a, F> {
+        Frame {
+            names: &self.names,
+            current,
+            next,
+            periodic_names: self
+                .periodic
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect(),
+            periodic,
+        }
+    }
+
+    fn column_index(&self, column: &str) -> Option<usize> {
+        self.names.iter().position(|name| name == column)
+    }
+
+    fn check_shape(&self, num_rows: usize) -> Result<(), AirError> {
+        for (name, values) in self.periodic.iter() {
+            if !values.len().is_power_of_two() || values.len() > num_rows {
+                return Err(AirError::BadPeriod {
+                    name: name.clone(),
+                    len: values.len(),
+                });
+            }
+        }
+        for (row, column, _) in self.boundaries.iter() {
+            if self.column_index(column).is_none() {
+                return Err(AirError::UnknownColumn(column.clone()));
+            }
+            if *row >= num_rows {
+                return Err(AirError::BoundaryFailed {
+                    row: *row,
+                    column: column.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    // every constraint on every row of the (padded) trace
+    pub fn check(&self, trace: &TraceTable<F>) -> Result<(), AirError> {
+        if trace.names() != self.names.as_slice() {
+            return Err(AirError::ColumnMismatch);
+        }
+        let num_rows = trace.num_rows();
+        self.check_shape(num_rows)?;
+
+        for (row, column, value) in self.boundaries.iter() {
+            if trace.get(*row, column) != Some(*value) {
+                return Err(AirError::BoundaryFailed {
+                    row: *row,
+                    column: column.clone(),
+                });
+            }
+        }
+
+        let mut current = trace.row(0);
+        for row in 0..num_rows - 1 {
+            let next = trace.row(row + 1);
+            let periodic: Vec<F> = self
+                .periodic
+                .iter()
+                .map(|(_, values)| values[row % values.len()])
+                .collect();
+
+            let frame = self.frame(&current, &next, &periodic);
+            if let Some(constraint) = self.transitions.iter().position(|c| !c(&frame).is_zero()) {
+                return Err(AirError::TransitionFailed { row, constraint });
+            }
+            current = next;
+        }
+
+        Ok(())
+    }
+
+    // every periodic column evaluated at z for a trace of trace_len rows
+    pub fn periodic_at(&self, z: F, trace_len: usize) -> Vec<F> {
+        self.periodic
+            .iter()
+            .map(|(_, values)| {
+                let coefficient = FastFourierTransform::new(values.clone())
+                    .interpolate()
+                    .coefficients;
+                let z_k = z.pow([(trace_len / values.len()) as u64]);
+
+                UnivariatePoly { coefficient }.evaluate(z_k)
+            })
+            .collect()
+    }
+
+    // the transition numerators C(T(z), T(ωz), P(z)), in the order they were added
+    pub fn transitions_at(&self, z: F, current: &[F], next: &[F], trace_len: usize) -> Vec<F> {
+        let periodic = self.periodic_at(z, trace_len);
+        let frame = self.frame(current, next, &periodic);
+
+        self.transitions.iter().map(|c| c(&frame)).collect()
+    }
+
+    //=========================================================================================
+    // The composition polynomial at an out of domain z, from the trace openings at z and ωz:
+    // Σ αᵢ·Cᵢ(z)·(z - ωⁿ⁻¹) / (zⁿ - 1) + Σ βⱼ·(T_colⱼ(z) - vⱼ) / (z - ω^rowⱼ)
+    // with the α then β in challenges, one per constraint
+    // None when z is in the trace domain (the quotients are undefined there), a challenge is
+    // missing, or the AIR does not fit the trace length
+    //=========================================================================================
+    pub fn composition_at(
+        &self,
+        z: F,
+        current: &[F],
+        next: &[F],
+        trace_len: usize,
+        challenges: &[F],
+    ) -> Option<F> {
+        if challenges.len() != self.num_constraints()
+            || current.len() != self.names.len()
+            || next.len() != self.names.len()
+            || !trace_len.is_power_of_two()
+            || self.check_shape(trace_len).is_err()
+        {
+            return None;
+        }
+
+        let domain = Domain::<F>::new(trace_len);
+        let vanishing_inv = (z.pow([trace_len as u64]) - F::ONE).inverse()?;
+        let transition_zerofier_inv = (z - domain.element(trace_len - 1)) * vanishing_inv;
+
+        let (alphas, betas) = challenges.split_at(self.transitions.len());
+        let transitions: F = self
+            .transitions_at(z, current, next, trace_len)
+            .iter()
+            .zip(alphas.iter())
+            .map(|(c, alpha)| *c * alpha)
+            .sum();
+
+        let mut boundaries = F::ZERO;
+        for ((row, column, value), beta) in self.boundaries.iter().zip(betas.iter()) {
+            let column = self.column_index(column)?;
+            boundaries += (current[column] - value) * (z - domain.element(*row)).inverse()? * beta;
+        }
+
+        Some(transitions * transition_zerofier_inv + boundaries)
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::air::{ColumnType, TraceBuilder};
+    use ark_bn254::Fr;
+    use ark_ff::UniformRand;
+
+    fn round_constants() -> Vec<Fr> {
+        [7, 11, 13, 17].map(Fr::from).to_vec()
+    }
+
+    // x_{i+1} = x_i³ + k_i with k repeating every 4 rows, a toy MiMC
+    fn mimc_trace(seed: u64) -> TraceTable<Fr> {
+        let constants = round_constants();
+        TraceBuilder::new()
+            .column("x", ColumnType::Field)
+            .fill(8, |i, row| match row.prev("x") {
+                None => row.set("x", Fr::from(seed)),
+                Some(x) => row.set("x", x * x * x + constants[(i - 1) % 4]),
+            })
+            .unwrap()
+    }
+
+    fn mimc_air(seed: u64, output: Fr) -> Air<Fr> {
+        Air::new(&["x"])
+            .periodic("k", round_constants())
+            .transition(|frame| {
+                let x = frame.current("x");
+                frame.next("x") - (x * x * x + frame.periodic("k"))
+            })
+            .boundary("x", 0, Fr::from(seed))
+            .boundary("x", 7, output)
+    }
+
+    #[test]
+    fn test_check_trace() {
+        let trace = mimc_trace(3);
+        let output = trace.get(7, "x").unwrap();
+        assert_eq!(mimc_air(3, output).check(&trace), Ok(()));
+
+        assert_eq!(
+            mimc_air(4, output).check(&trace),
+            Err(AirError::BoundaryFailed {
+                row: 0,
+                column: "x".to_string()
+            })
+        );
+
+        // the wrong round constants break the first transition
+        let air = Air::new(&["x"])
+            .periodic("k", vec![Fr::from(1), Fr::from(2)])
+            .transition(|frame| {
+                let x = frame.current("x");
+                frame.next("x") - (x * x * x + frame.periodic("k"))
+            });
+        assert_eq!(
+            air.check(&trace),
+            Err(AirError::TransitionFailed {
+                row: 0,
+                constraint: 0
+            })
+        );
+
+        let air = mimc_air(3, output).periodic("bad", vec![Fr::from(1); 3]);
+        assert!(matches!(
+            air.check(&trace),
+            Err(AirError::BadPeriod { len: 3, .. })
+        ));
+        let air = mimc_air(3, output).boundary("y", 0, Fr::from(0));
+        assert_eq!(air.check(&trace), Err(AirError::UnknownColumn("y".into())));
+    }
+
+    #[test]
+    fn test_periodic_and_transitions_at_domain_points() {
+        let trace = mimc_trace(3);
+        let air = mimc_air(3, trace.get(7, "x").unwrap());
+        let poly = UnivariatePoly {
+            coefficient: trace.to_coefficients().remove(0),
+        };
+        let domain = Domain::<Fr>::new(8);
+
+        for i in 0..8 {
+            let z = domain.element(i);
+            assert_eq!(air.periodic_at(z, 8), vec![round_constants()[i % 4]]);
+
+            let current = [poly.evaluate(z)];
+            let next = [poly.evaluate(z * domain.generator)];
+            let transitions = air.transitions_at(z, &current, &next, 8);
+            // only the wrap-around from the last row back to the first is exempt
+            assert_eq!(transitions[0] == Fr::from(0), i < 7);
+        }
+    }
+
+    #[test]
+    fn test_composition_at() {
+        let trace = mimc_trace(3);
+        let air = mimc_air(3, trace.get(7, "x").unwrap());
+        let poly = UnivariatePoly {
+            coefficient: trace.to_coefficients().remove(0),
+        };
+        let domain = Domain::<Fr>::new(8);
+        let mut rng = rand::thread_rng();
+        let challenges: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+
+        let z = Fr::rand(&mut rng);
+        let (current, next) = ([poly.evaluate(z)], [poly.evaluate(z * domain.generator)]);
+        assert!(air
+            .composition_at(z, &current, &next, 8, &challenges)
+            .is_some());
+
+        // undefined on the trace domain and for a challenge short
+        let (current, next) = (
+            [poly.evaluate(Fr::from(1))],
+            [poly.evaluate(domain.generator)],
+        );
+        assert_eq!(
+            air.composition_at(Fr::from(1), &current, &next, 8, &challenges),
+            None
+        );
+        assert_eq!(
+            air.composition_at(z, &current, &next, 8, &challenges[..2]),
+            None
+        );
+    }
+}
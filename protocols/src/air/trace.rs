@@ -0,0 +1,325 @@
+use ark_ff::{BigInteger, FftField, PrimeField};
+
+use crate::{
+    air::AirError,
+    fri::{domain_cache::DomainCache, fft::FastFourierTransform},
+};
+
+//=========================================================================================
+// Execution trace of an AIR: one row per step, one named column per register
+// every column has a type that each assigned value is checked against
+//   Field: anything, Bit: 0 or 1, U32: below 2³²
+// rows are filled by a closure that sees the row index and can read the previous row,
+// so a Fibonacci trace is fill(n, |i, row| { if i == 0 { seed } else { row.prev("b") ... } })
+// the table is padded to a power of two by repeating the last row (the FFT domain needs
+// 2^k points, and a repeated row keeps every transition that holds on the last step)
+// stored column-major, which is what the FFT / FRI layer consumes
+//=========================================================================================
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Field,
+    Bit,
+    U32,
+}
+
+impl ColumnType {
+    fn accepts<F: PrimeField>(&self, value: &F) -> bool {
+        match self {
+            ColumnType::Field => true,
+            ColumnType::Bit => value.is_zero() || value.is_one(),
+            ColumnType::U32 => value.into_bigint().num_bits() <= 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TraceBuilder {
+    names: Vec<String>,
+    types: Vec<ColumnType>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceTable<F: PrimeField> {
+    names: Vec<String>,
+    types: Vec<ColumnType>,
+    columns: Vec<Vec<F>>,
+    num_assigned_rows: usize, // rows before the padding
+}
+
+// the row a fill closure is writing, plus read access to the one before it
+pub struct Row<'a, F: PrimeField> {
+    index: usize,
+    names: &'a [String],
+    columns: &'a [Vec<F>],
+    cells: Vec<Option<F>>,
+    error: Option<AirError>,
+}
+
+impl TraceBuilder {
+    pub fn new() -> Self {
+        TraceBuilder::default()
+    }
+
+    pub fn column(mut self, name: &str, column_type: ColumnType) -> Self {
+        self.names.push(name.to_string());
+        self.types.push(column_type);
+        self
+    }
+
+    pub fn fill<F: PrimeField, A: FnMut(usize, &mut Row<F>)>(
+        self,
+        num_rows: usize,
+        mut assign: A,
+    ) -> Result<TraceTable<F>, AirError> {
+        if num_rows == 0 || self.names.is_empty() {
+            return Err(AirError::EmptyTrace);
+        }
+        for (i, name) in self.names.iter().enumerate() {
+            if self.names[..i].contains(name) {
+                return Err(AirError::DuplicateColumn(name.clone()));
+            }
+        }
+
+        let mut columns = vec![Vec::with_capacity(num_rows.next_power_of_two()); self.names.len()];
+        for index in 0..num_rows {
+            let mut row = Row {
+                index,
+                names: &self.names,
+                columns: &columns,
+                cells: vec![None; self.names.len()],
+                error: None,
+            };
+            assign(index, &mut row);
+
+            if let Some(error) = row.error {
+                return Err(error);
+            }
+            let cells = row.cells;
+
+            for (c, cell) in cells.into_iter().enumerate() {
+                let value = cell.ok_or_else(|| AirError::Unassigned {
+                    row: index,
+                    column: self.names[c].clone(),
+                })?;
+                if !self.types[c].accepts(&value) {
+                    return Err(AirError::TypeMismatch {
+                        row: index,
+                        column: self.names[c].clone(),
+                    });
+                }
+                columns[c].push(value);
+            }
+        }
+
+        for column in columns.iter_mut() {
+            let last = *column.last().unwrap();
+            column.resize(num_rows.next_power_of_two(), last);
+        }
+
+        Ok(TraceTable {
+            names: self.names,
+            types: self.types,
+            columns,
+            num_assigned_rows: num_rows,
+        })
+    }
+}
+
+impl<F: PrimeField> Row<'_, F> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn set(&mut self, name: &str, value: F) {
+        match self.names.iter().position(|n| n == name) {
+            Some(c) => self.cells[c] = Some(value),
+            None => self.fail(AirError::UnknownColumn(name.to_string())),
+        }
+    }
+
+    // the value the column got in the row before, None on the first row
+    pub fn prev(&mut self, name: &str) -> Option<F> {
+        match self.names.iter().position(|n| n == name) {
+            Some(c) => self.columns[c].last().copied(),
+            None => {
+                self.fail(AirError::UnknownColumn(name.to_string()));
+                None
+            }
+        }
+    }
+
+    // keeps the first error, it is reported once the closure returns
+    fn fail(&mut self, error: AirError) {
+        self.error.get_or_insert(error);
+    }
+}
+
+impl<F: PrimeField> TraceTable<F> {
+    // rows after padding, always a power of two
+    pub fn num_rows(&self) -> usize {
+        self.columns[0].len()
+    }
+
+    pub fn num_assigned_rows(&self) -> usize {
+        self.num_assigned_rows
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn column_type(&self, name: &str) -> Option<ColumnType> {
+        self.position(name).map(|c| self.types[c])
+    }
+
+    pub fn column(&self, name: &str) -> Option<&[F]> {
+        self.position(name).map(|c| self.columns[c].as_slice())
+    }
+
+    pub fn get(&self, row: usize, name: &str) -> Option<F> {
+        self.column(name)?.get(row).copied()
+    }
+
+    // one row across every column, in declaration order
+    pub fn row(&self, row: usize) -> Vec<F> {
+        self.columns.iter().map(|column| column[row]).collect()
+    }
+
+    pub fn columns(&self) -> &[Vec<F>] {
+        &self.columns
+    }
+
+    pub fn into_columns(self) -> Vec<Vec<F>> {
+        self.columns
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+}
+
+impl<F: PrimeField + FftField> TraceTable<F> {
+    // every column interpolated over the 2^k roots of unity (column i ↦ coefficients of Tᵢ(x)
+    // with Tᵢ(ωʲ) = trace[j][i]), ready for lde / FRI
+    pub fn to_coefficients(&self) -> Vec<Vec<F>> {
+        let mut cache = DomainCache::new();
+
+        self.columns
+            .iter()
+            .map(|column| {
+                FastFourierTransform::new(column.clone())
+                    .interpolate_with(&mut cache)
+                    .coefficients
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fr;
+
+    fn fibonacci(num_rows: usize) -> Result<TraceTable<Fr>, AirError> {
+        TraceBuilder::new()
+            .column("a", ColumnType::Field)
+            .column("b", ColumnType::Field)
+            .fill(num_rows, |i, row| {
+                if i == 0 {
+                    row.set("a", Fr::from(1));
+                    row.set("b", Fr::from(1));
+                } else {
+                    let (a, b) = (row.prev("a").unwrap(), row.prev("b").unwrap());
+                    row.set("a", b);
+                    row.set("b", a + b);
+                }
+            })
+    }
+
+    #[test]
+    fn test_fill_and_pad() {
+        let trace = fibonacci(6).unwrap();
+        assert_eq!(trace.num_rows(), 8);
+        assert_eq!(trace.num_assigned_rows(), 6);
+        assert_eq!(trace.num_columns(), 2);
+        assert_eq!(
+            trace.column("b").unwrap(),
+            [1, 2, 3, 5, 8, 13, 13, 13].map(Fr::from)
+        );
+        assert_eq!(trace.row(5), vec![Fr::from(8), Fr::from(13)]);
+        assert_eq!(trace.get(2, "a"), Some(Fr::from(2)));
+        assert_eq!(trace.get(2, "c"), None);
+
+        // already a power of two, nothing is added
+        assert_eq!(fibonacci(4).unwrap().num_rows(), 4);
+    }
+
+    #[test]
+    fn test_column_types_are_checked() {
+        let result = TraceBuilder::new()
+            .column("flag", ColumnType::Bit)
+            .fill(3, |i, row| row.set("flag", Fr::from(i as u64)));
+        assert_eq!(
+            result,
+            Err(AirError::TypeMismatch {
+                row: 2,
+                column: "flag".to_string()
+            })
+        );
+
+        let result = TraceBuilder::new()
+            .column("word", ColumnType::U32)
+            .fill(2, |i, row| {
+                row.set("word", Fr::from(u32::MAX as u64 + i as u64))
+            });
+        assert!(matches!(result, Err(AirError::TypeMismatch { row: 1, .. })));
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        let unassigned = TraceBuilder::new()
+            .column("a", ColumnType::Field)
+            .column("b", ColumnType::Field)
+            .fill(1, |_, row| row.set("a", Fr::from(1)));
+        assert_eq!(
+            unassigned,
+            Err(AirError::Unassigned {
+                row: 0,
+                column: "b".to_string()
+            })
+        );
+
+        let unknown = TraceBuilder::new()
+            .column("a", ColumnType::Field)
+            .fill(1, |_, row| row.set("z", Fr::from(1)));
+        assert_eq!(unknown, Err(AirError::UnknownColumn("z".to_string())));
+
+        let duplicate = TraceBuilder::new()
+            .column("a", ColumnType::Field)
+            .column("a", ColumnType::Bit)
+            .fill(1, |_, row: &mut Row<Fr>| row.set("a", Fr::from(1)));
+        assert_eq!(duplicate, Err(AirError::DuplicateColumn("a".to_string())));
+
+        let empty = TraceBuilder::new()
+            .column("a", ColumnType::Field)
+            .fill(0, |_, row: &mut Row<Fr>| row.set("a", Fr::from(1)));
+        assert_eq!(empty, Err(AirError::EmptyTrace));
+    }
+
+    #[test]
+    fn test_to_coefficients_round_trips() {
+        let trace = fibonacci(8).unwrap();
+        let coefficients = trace.to_coefficients();
+
+        for (column, coefficients) in trace.columns().iter().zip(coefficients) {
+            let evaluations = FastFourierTransform::new(coefficients)
+                .evaluate()
+                .coefficients;
+            assert_eq!(&evaluations, column);
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod inner_product;
+pub mod range_proof;
@@ -0,0 +1,214 @@
+use crate::transcript::Transcript;
+use ark_ec::CurveGroup;
+use ark_ff::{Field, PrimeField};
+
+//=========================================================================================
+// Inner product argument (Bulletproofs, section 3)
+// proves knowledge of a, b with P = <a, G> + <b, H> + <a, b>·U in log₂(n) rounds
+// each round the prover sends L, R (the cross terms of the two halves), the verifier answers x
+// and both sides fold: a' = x·a_lo + x⁻¹·a_hi, b' = x⁻¹·b_lo + x·b_hi,
+// G' = x⁻¹·G_lo + x·G_hi, H' = x·H_lo + x⁻¹·H_hi, P' = x²·L + P + x⁻²·R
+// until a and b are single scalars that are sent in the clear
+// the caller has absorbed P (or everything P is built from) into the transcript already
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct InnerProductProof<G: CurveGroup> {
+    pub l_vec: Vec<G>,
+    pub r_vec: Vec<G>,
+    pub a: G::ScalarField,
+    pub b: G::ScalarField,
+}
+
+// Σ scalarsᵢ·pointsᵢ
+pub fn msm<G: CurveGroup>(points: &[G], scalars: &[G::ScalarField]) -> G {
+    points
+        .iter()
+        .zip(scalars.iter())
+        .fold(G::zero(), |acc, (point, scalar)| {
+            acc + point.mul_bigint(scalar.into_bigint())
+        })
+}
+
+pub fn inner_product<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b.iter()).map(|(a_i, b_i)| *a_i * b_i).sum()
+}
+
+fn fold_scalars<F: PrimeField>(lo: &[F], hi: &[F], x_lo: F, x_hi: F) -> Vec<F> {
+    lo.iter()
+        .zip(hi.iter())
+        .map(|(lo, hi)| *lo * x_lo + *hi * x_hi)
+        .collect()
+}
+
+fn fold_points<G: CurveGroup>(
+    lo: &[G],
+    hi: &[G],
+    x_lo: G::ScalarField,
+    x_hi: G::ScalarField,
+) -> Vec<G> {
+    lo.iter()
+        .zip(hi.iter())
+        .map(|(lo, hi)| lo.mul_bigint(x_lo.into_bigint()) + hi.mul_bigint(x_hi.into_bigint()))
+        .collect()
+}
+
+// the round challenge and its inverse, None only for the (negligible) zero challenge
+fn round_challenge<G: CurveGroup>(
+    transcript: &mut Transcript,
+    l: &G,
+    r: &G,
+) -> Option<(G::ScalarField, G::ScalarField)> {
+    transcript.absorb_commitment(l);
+    transcript.absorb_commitment(r);
+    let x = transcript.squeeze_field::<G::ScalarField>();
+
+    x.inverse().map(|x_inv| (x, x_inv))
+}
+
+pub fn prove<G: CurveGroup>(
+    transcript: &mut Transcript,
+    g_vec: &[G],
+    h_vec: &[G],
+    u: &G,
+    a: &[G::ScalarField],
+    b: &[G::ScalarField],
+) -> InnerProductProof<G> {
+    let n = a.len();
+    if !n.is_power_of_two() || b.len() != n || g_vec.len() != n || h_vec.len() != n {
+        panic!("The inner product argument needs vectors of the same power of two length");
+    }
+
+    let (mut a, mut b) = (a.to_vec(), b.to_vec());
+    let (mut g_vec, mut h_vec) = (g_vec.to_vec(), h_vec.to_vec());
+    let rounds = n.ilog2() as usize;
+    let mut l_vec = Vec::with_capacity(rounds);
+    let mut r_vec = Vec::with_capacity(rounds);
+
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let (a_lo, a_hi) = a.split_at(half);
+        let (b_lo, b_hi) = b.split_at(half);
+        let (g_lo, g_hi) = g_vec.split_at(half);
+        let (h_lo, h_hi) = h_vec.split_at(half);
+
+        let c_l = inner_product(a_lo, b_hi);
+        let c_r = inner_product(a_hi, b_lo);
+        let l = msm(g_hi, a_lo) + msm(h_lo, b_hi) + u.mul_bigint(c_l.into_bigint());
+        let r = msm(g_lo, a_hi) + msm(h_hi, b_lo) + u.mul_bigint(c_r.into_bigint());
+
+        let (x, x_inv) =
+            round_challenge(transcript, &l, &r).expect("The round challenge is never zero");
+        l_vec.push(l);
+        r_vec.push(r);
+
+        a = fold_scalars(a_lo, a_hi, x, x_inv);
+        b = fold_scalars(b_lo, b_hi, x_inv, x);
+        g_vec = fold_points(g_lo, g_hi, x_inv, x);
+        h_vec = fold_points(h_lo, h_hi, x, x_inv);
+    }
+
+    InnerProductProof {
+        l_vec,
+        r_vec,
+        a: a[0],
+        b: b[0],
+    }
+}
+
+pub fn verify<G: CurveGroup>(
+    transcript: &mut Transcript,
+    g_vec: &[G],
+    h_vec: &[G],
+    u: &G,
+    p: &G,
+    proof: &InnerProductProof<G>,
+) -> bool {
+    let n = g_vec.len();
+    if !n.is_power_of_two()
+        || h_vec.len() != n
+        || proof.l_vec.len() != n.ilog2() as usize
+        || proof.r_vec.len() != proof.l_vec.len()
+    {
+        return false;
+    }
+
+    let (mut g_vec, mut h_vec) = (g_vec.to_vec(), h_vec.to_vec());
+    let mut p = *p;
+
+    for (l, r) in proof.l_vec.iter().zip(proof.r_vec.iter()) {
+        let (x, x_inv) = match round_challenge(transcript, l, r) {
+            Some(challenge) => challenge,
+            None => return false,
+        };
+
+        let half = g_vec.len() / 2;
+        g_vec = fold_points(&g_vec[..half], &g_vec[half..], x_inv, x);
+        h_vec = fold_points(&h_vec[..half], &h_vec[half..], x, x_inv);
+        p += l.mul_bigint(x.square().into_bigint()) + r.mul_bigint(x_inv.square().into_bigint());
+    }
+
+    let expected = g_vec[0].mul_bigint(proof.a.into_bigint())
+        + h_vec[0].mul_bigint(proof.b.into_bigint())
+        + u.mul_bigint((proof.a * proof.b).into_bigint());
+
+    (p - expected).is_zero()
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::transcript::test::domain;
+    use ark_bn254::{Fr, G1Projective};
+    use ark_ec::PrimeGroup;
+    use ark_ff::UniformRand;
+
+    fn generators(n: usize) -> Vec<G1Projective> {
+        let mut rng = rand::thread_rng();
+        (0..n)
+            .map(|_| G1Projective::generator().mul_bigint(Fr::rand(&mut rng).into_bigint()))
+            .collect()
+    }
+
+    #[test]
+    fn test_inner_product_argument() {
+        let n = 8;
+        let (g_vec, h_vec, u) = (generators(n), generators(n), generators(1)[0]);
+        let a: Vec<Fr> = (1..=n as u64).map(Fr::from).collect();
+        let b: Vec<Fr> = (0..n as u64).map(|i| Fr::from(i * i + 3)).collect();
+
+        let p =
+            msm(&g_vec, &a) + msm(&h_vec, &b) + u.mul_bigint(inner_product(&a, &b).into_bigint());
+        let proof = prove(&mut domain().transcript(), &g_vec, &h_vec, &u, &a, &b);
+        assert_eq!(proof.l_vec.len(), 3);
+        assert!(verify(
+            &mut domain().transcript(),
+            &g_vec,
+            &h_vec,
+            &u,
+            &p,
+            &proof
+        ));
+
+        // a P that claims another inner product
+        let wrong_p = p + u;
+        assert!(!verify(
+            &mut domain().transcript(),
+            &g_vec,
+            &h_vec,
+            &u,
+            &wrong_p,
+            &proof
+        ));
+
+        let mut short = proof.clone();
+        short.l_vec.pop();
+        assert!(!verify(
+            &mut domain().transcript(),
+            &g_vec,
+            &h_vec,
+            &u,
+            &p,
+            &short
+        ));
+    }
+}
@@ -0,0 +1,429 @@
+use crate::{
+    bulletproofs::inner_product::{self, msm, InnerProductProof},
+    hash::{
+        generators::{derive_generators, GeneratorSetId},
+        hash_to_curve::HashToCurveGroup,
+    },
+    proof_format::{
+        ensure_consumed, read_header, read_point, read_points, write_header, DecodeLimits,
+        ProofFormatError, ProtocolId,
+    },
+    transcript::{Transcript, TranscriptDomain, DIGEST_LEN},
+};
+use ark_ec::CurveGroup;
+use ark_ff::{Field, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+#[cfg(feature = "prover")]
+use {crate::bulletproofs::inner_product::inner_product, ark_ff::UniformRand, rand::Rng};
+
+//=========================================================================================
+// Bulletproofs range proof (section 4.2): V = v·g + γ·h commits to v and the proof shows
+// v ∈ [0, 2ⁿ) without opening it
+// aₗ are the bits of v, aᵣ = aₗ - 1, and with challenges y, z the three conditions
+// <aₗ, 2ⁿ> = v, aₗ ∘ aᵣ = 0, aₗ - 1 - aᵣ = 0 collapse into one inner product
+// t(x) = <l(x), r(x)>, l(x) = aₗ - z + sₗ·x, r(x) = yⁿ ∘ (aᵣ + z + sᵣ·x) + z²·2ⁿ
+// the prover commits to t₁, t₂ (T₁, T₂), opens t̂ = t(x) and proves l, r with the inner
+// product argument against H'ᵢ = y⁻ⁱ·Hᵢ instead of sending the two n long vectors
+// n is a power of two up to 64 so the argument halves cleanly and v fits a u64
+// nobody may know relations between the generators, they are either sampled at random (setup) or
+// hashed to the curve from a domain string (derive), and the proof names the set it was made with
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeProofGens<G: CurveGroup> {
+    pub g: G, // value generator of V
+    pub h: G, // blinding generator
+    pub u: G, // inner product generator
+    pub g_vec: Vec<G>,
+    pub h_vec: Vec<G>,
+    pub id: GeneratorSetId,
+}
+
+impl<G: CurveGroup> RangeProofGens<G> {
+    pub fn new(g: G, h: G, u: G, g_vec: Vec<G>, h_vec: Vec<G>) -> Self {
+        let id = GeneratorSetId::of([&g, &h, &u].into_iter().chain(&g_vec).chain(&h_vec));
+
+        RangeProofGens {
+            g,
+            h,
+            u,
+            g_vec,
+            h_vec,
+            id,
+        }
+    }
+
+    // enough generators for ranges of up to max_bits bits
+    #[cfg(feature = "prover")]
+    pub fn setup<R: Rng>(max_bits: usize, rng: &mut R) -> Self {
+        let mut random_point =
+            || G::generator().mul_bigint(G::ScalarField::rand(rng).into_bigint());
+
+        let (h, u) = (random_point(), random_point());
+        let g_vec = (0..max_bits).map(|_| random_point()).collect();
+        let h_vec = (0..max_bits).map(|_| random_point()).collect();
+        RangeProofGens::new(G::generator(), h, u, g_vec, h_vec)
+    }
+
+    // the value generator stays the group generator, everything else comes from the domain
+    pub fn derive(domain: &[u8], max_bits: usize) -> Self
+    where
+        G: HashToCurveGroup,
+    {
+        let blinding = derive_generators(domain, "blinding", 2);
+
+        RangeProofGens::new(
+            G::generator(),
+            blinding[0],
+            blinding[1],
+            derive_generators(domain, "g", max_bits),
+            derive_generators(domain, "h", max_bits),
+        )
+    }
+
+    pub fn commit(&self, value: u64, blinding: G::ScalarField) -> G {
+        self.g.mul_bigint(G::ScalarField::from(value).into_bigint())
+            + self.h.mul_bigint(blinding.into_bigint())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeProof<G: CurveGroup> {
+    pub generators: GeneratorSetId,
+    pub a: G, // commits to aₗ, aᵣ
+    pub s: G, // commits to sₗ, sᵣ
+    pub t_1: G,
+    pub t_2: G,
+    pub tau_x: G::ScalarField, // blinding of t̂
+    pub mu: G::ScalarField,    // blinding of A + x·S
+    pub t_hat: G::ScalarField,
+    pub ipa: InnerProductProof<G>,
+}
+
+impl<G: CurveGroup> RangeProof<G> {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofFormatError> {
+        let mut bytes = Vec::new();
+        write_header(ProtocolId::RangeProof, &mut bytes);
+
+        bytes.extend_from_slice(&self.generators.0);
+        [self.a, self.s, self.t_1, self.t_2].serialize_compressed(&mut bytes)?;
+        [self.tau_x, self.mu, self.t_hat].serialize_compressed(&mut bytes)?;
+        self.ipa.l_vec.serialize_compressed(&mut bytes)?;
+        self.ipa.r_vec.serialize_compressed(&mut bytes)?;
+        [self.ipa.a, self.ipa.b].serialize_compressed(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    pub fn from_bytes_with_limits(
+        mut bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        limits.check_size(bytes)?;
+        read_header(&mut bytes, ProtocolId::RangeProof)?;
+
+        let generators = GeneratorSetId(<[u8; DIGEST_LEN]>::deserialize_compressed(&mut bytes)?);
+        let a = read_point::<G>(&mut bytes)?;
+        let s = read_point::<G>(&mut bytes)?;
+        let t_1 = read_point::<G>(&mut bytes)?;
+        let t_2 = read_point::<G>(&mut bytes)?;
+        let [tau_x, mu, t_hat] = <[G::ScalarField; 3]>::deserialize_compressed(&mut bytes)?;
+        let l_vec = read_points::<G>(&mut bytes, limits)?;
+        let r_vec = read_points::<G>(&mut bytes, limits)?;
+        if l_vec.len() != r_vec.len() {
+            return Err(ProofFormatError::InconsistentLengths {
+                expected: l_vec.len(),
+                found: r_vec.len(),
+            });
+        }
+        let [ipa_a, ipa_b] = <[G::ScalarField; 2]>::deserialize_compressed(&mut bytes)?;
+        ensure_consumed(bytes)?;
+
+        Ok(RangeProof {
+            generators,
+            a,
+            s,
+            t_1,
+            t_2,
+            tau_x,
+            mu,
+            t_hat,
+            ipa: InnerProductProof {
+                l_vec,
+                r_vec,
+                a: ipa_a,
+                b: ipa_b,
+            },
+        })
+    }
+}
+
+fn powers<F: PrimeField>(base: F, n: usize) -> Vec<F> {
+    std::iter::successors(Some(F::one()), |power| Some(*power * base))
+        .take(n)
+        .collect()
+}
+
+fn check_bits<G: CurveGroup>(gens: &RangeProofGens<G>, bits: usize) -> bool {
+    bits.is_power_of_two() && bits <= 64 && bits <= gens.g_vec.len() && bits <= gens.h_vec.len()
+}
+
+// V, A, S go in before y and z, T₁, T₂ before x, and the opened scalars before w
+fn absorb_points<G: CurveGroup>(transcript: &mut Transcript, points: &[&G]) {
+    points
+        .iter()
+        .for_each(|point| transcript.absorb_commitment(*point));
+}
+
+// Hᵢ scaled by y⁻ⁱ, the generators r(x) is committed against
+fn scaled_h<G: CurveGroup>(h_vec: &[G], y_inv: G::ScalarField) -> Vec<G> {
+    h_vec
+        .iter()
+        .zip(powers(y_inv, h_vec.len()))
+        .map(|(h, y_inv_i)| h.mul_bigint(y_inv_i.into_bigint()))
+        .collect()
+}
+
+// δ(y, z) = (z - z²)·<1, yⁿ> - z³·<1, 2ⁿ>
+fn delta<F: PrimeField>(y: F, z: F, bits: usize) -> F {
+    let sum_y: F = powers(y, bits).into_iter().sum();
+    let sum_2: F = powers(F::from(2), bits).into_iter().sum();
+    let z_2 = z.square();
+
+    (z - z_2) * sum_y - z_2 * z * sum_2
+}
+
+// value must fit in bits, the returned proof is for gens.commit(value, blinding)
+#[cfg(feature = "prover")]
+pub fn prove<G: CurveGroup, R: Rng>(
+    gens: &RangeProofGens<G>,
+    value: u64,
+    blinding: G::ScalarField,
+    bits: usize,
+    transcript_domain: &TranscriptDomain,
+    rng: &mut R,
+) -> RangeProof<G> {
+    if !check_bits(gens, bits) {
+        panic!("The range must be a power of two number of bits covered by the generators");
+    }
+    if bits < 64 && value >> bits != 0 {
+        panic!("The value does not fit in {} bits", bits);
+    }
+
+    let one = G::ScalarField::ONE;
+    let g_vec = &gens.g_vec[..bits];
+    let h_vec = &gens.h_vec[..bits];
+    let commitment = gens.commit(value, blinding);
+
+    let a_l: Vec<G::ScalarField> = (0..bits)
+        .map(|i| G::ScalarField::from((value >> i) & 1))
+        .collect();
+    let a_r: Vec<G::ScalarField> = a_l.iter().map(|bit| *bit - one).collect();
+    let alpha = G::ScalarField::rand(rng);
+    let a = gens.h.mul_bigint(alpha.into_bigint()) + msm(g_vec, &a_l) + msm(h_vec, &a_r);
+
+    let s_l: Vec<G::ScalarField> = (0..bits).map(|_| G::ScalarField::rand(rng)).collect();
+    let s_r: Vec<G::ScalarField> = (0..bits).map(|_| G::ScalarField::rand(rng)).collect();
+    let rho = G::ScalarField::rand(rng);
+    let s = gens.h.mul_bigint(rho.into_bigint()) + msm(g_vec, &s_l) + msm(h_vec, &s_r);
+
+    let mut transcript = transcript_domain.transcript();
+    absorb_points(&mut transcript, &[&commitment, &a, &s]);
+    let y = transcript.squeeze_field::<G::ScalarField>();
+    let z = transcript.squeeze_field::<G::ScalarField>();
+    let z_2 = z.square();
+
+    let y_n = powers(y, bits);
+    let two_n = powers(G::ScalarField::from(2), bits);
+
+    // l(x) = l₀ + l₁·x, r(x) = r₀ + r₁·x
+    let l_0: Vec<G::ScalarField> = a_l.iter().map(|a| *a - z).collect();
+    let r_0: Vec<G::ScalarField> = (0..bits)
+        .map(|i| y_n[i] * (a_r[i] + z) + z_2 * two_n[i])
+        .collect();
+    let r_1: Vec<G::ScalarField> = (0..bits).map(|i| y_n[i] * s_r[i]).collect();
+
+    let t_1 = inner_product(&l_0, &r_1) + inner_product(&s_l, &r_0);
+    let t_2 = inner_product(&s_l, &r_1);
+    let (tau_1, tau_2) = (G::ScalarField::rand(rng), G::ScalarField::rand(rng));
+    let t_1_point = gens.g.mul_bigint(t_1.into_bigint()) + gens.h.mul_bigint(tau_1.into_bigint());
+    let t_2_point = gens.g.mul_bigint(t_2.into_bigint()) + gens.h.mul_bigint(tau_2.into_bigint());
+
+    absorb_points(&mut transcript, &[&t_1_point, &t_2_point]);
+    let x = transcript.squeeze_field::<G::ScalarField>();
+
+    let l: Vec<G::ScalarField> = (0..bits).map(|i| l_0[i] + s_l[i] * x).collect();
+    let r: Vec<G::ScalarField> = (0..bits).map(|i| r_0[i] + r_1[i] * x).collect();
+    let t_hat = inner_product(&l, &r);
+    let tau_x = tau_2 * x.square() + tau_1 * x + z_2 * blinding;
+    let mu = alpha + rho * x;
+
+    transcript.absorb_commitment(&vec![tau_x, mu, t_hat]);
+    let w = transcript.squeeze_field::<G::ScalarField>();
+    let u = gens.u.mul_bigint(w.into_bigint());
+
+    let y_inv = y.inverse().expect("The challenge y is never zero");
+    let ipa = inner_product::prove(&mut transcript, g_vec, &scaled_h(h_vec, y_inv), &u, &l, &r);
+
+    RangeProof {
+        generators: gens.id,
+        a,
+        s,
+        t_1: t_1_point,
+        t_2: t_2_point,
+        tau_x,
+        mu,
+        t_hat,
+        ipa,
+    }
+}
+
+pub fn verify<G: CurveGroup>(
+    gens: &RangeProofGens<G>,
+    commitment: &G,
+    bits: usize,
+    proof: &RangeProof<G>,
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    if proof.generators != gens.id || !check_bits(gens, bits) {
+        return false;
+    }
+    let g_vec = &gens.g_vec[..bits];
+    let h_vec = &gens.h_vec[..bits];
+
+    let mut transcript = transcript_domain.transcript();
+    absorb_points(&mut transcript, &[commitment, &proof.a, &proof.s]);
+    let y = transcript.squeeze_field::<G::ScalarField>();
+    let z = transcript.squeeze_field::<G::ScalarField>();
+    absorb_points(&mut transcript, &[&proof.t_1, &proof.t_2]);
+    let x = transcript.squeeze_field::<G::ScalarField>();
+    transcript.absorb_commitment(&vec![proof.tau_x, proof.mu, proof.t_hat]);
+    let w = transcript.squeeze_field::<G::ScalarField>();
+
+    let y_inv = match y.inverse() {
+        Some(y_inv) => y_inv,
+        None => return false,
+    };
+    let z_2 = z.square();
+
+    // t̂·g + τₓ·h == z²·V + δ(y, z)·g + x·T₁ + x²·T₂
+    let lhs =
+        gens.g.mul_bigint(proof.t_hat.into_bigint()) + gens.h.mul_bigint(proof.tau_x.into_bigint());
+    let rhs = commitment.mul_bigint(z_2.into_bigint())
+        + gens.g.mul_bigint(delta(y, z, bits).into_bigint())
+        + proof.t_1.mul_bigint(x.into_bigint())
+        + proof.t_2.mul_bigint(x.square().into_bigint());
+    if !(lhs - rhs).is_zero() {
+        return false;
+    }
+
+    // P = A + x·S - z·<1, G> + <z·yⁿ + z²·2ⁿ, H'> - μ·h + t̂·u is <l, G> + <r, H'> + <l, r>·u
+    let h_prime = scaled_h(h_vec, y_inv);
+    let y_n = powers(y, bits);
+    let two_n = powers(G::ScalarField::from(2), bits);
+    let h_scalars: Vec<G::ScalarField> = (0..bits).map(|i| z * y_n[i] + z_2 * two_n[i]).collect();
+    let u = gens.u.mul_bigint(w.into_bigint());
+
+    let p = proof.a + proof.s.mul_bigint(x.into_bigint()) - msm(g_vec, &vec![z; bits])
+        + msm(&h_prime, &h_scalars)
+        - gens.h.mul_bigint(proof.mu.into_bigint())
+        + u.mul_bigint(proof.t_hat.into_bigint());
+
+    inner_product::verify(&mut transcript, g_vec, &h_prime, &u, &p, &proof.ipa)
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::transcript::{public_digest, test::domain};
+    use ark_bn254::{Fr, G1Projective};
+
+    fn gens() -> RangeProofGens<G1Projective> {
+        RangeProofGens::setup(64, &mut rand::thread_rng())
+    }
+
+    #[test]
+    fn test_range_proof() {
+        let gens = gens();
+        let mut rng = rand::thread_rng();
+
+        for (value, bits) in [(0, 8), (255, 8), (1 << 20, 32), (u64::MAX, 64), (5, 4)] {
+            let blinding = Fr::rand(&mut rng);
+            let commitment = gens.commit(value, blinding);
+
+            let proof = prove(&gens, value, blinding, bits, &domain(), &mut rng);
+            assert_eq!(proof.ipa.l_vec.len(), bits.ilog2() as usize);
+            assert!(verify(&gens, &commitment, bits, &proof, &domain()));
+        }
+    }
+
+    #[test]
+    fn test_range_proof_rejects() {
+        let gens = gens();
+        let mut rng = rand::thread_rng();
+        let blinding = Fr::rand(&mut rng);
+        let commitment = gens.commit(200, blinding);
+        let proof = prove(&gens, 200, blinding, 8, &domain(), &mut rng);
+
+        // another value, another range, another domain
+        assert!(!verify(
+            &gens,
+            &gens.commit(201, blinding),
+            8,
+            &proof,
+            &domain()
+        ));
+        assert!(!verify(&gens, &commitment, 16, &proof, &domain()));
+        let other = TranscriptDomain::new(b"other-app", public_digest(b""));
+        assert!(!verify(&gens, &commitment, 8, &proof, &other));
+
+        let mut tampered = proof.clone();
+        tampered.t_hat += Fr::from(1);
+        assert!(!verify(&gens, &commitment, 8, &tampered, &domain()));
+
+        let mut tampered = proof;
+        tampered.ipa.a += Fr::from(1);
+        assert!(!verify(&gens, &commitment, 8, &tampered, &domain()));
+    }
+
+    #[test]
+    #[should_panic(expected = "The value does not fit in 8 bits")]
+    fn test_range_proof_value_too_large() {
+        let gens = gens();
+        prove(
+            &gens,
+            256,
+            Fr::from(1),
+            8,
+            &domain(),
+            &mut rand::thread_rng(),
+        );
+    }
+
+    #[test]
+    fn test_derived_gens() {
+        let gens = RangeProofGens::<G1Projective>::derive(b"range-test", 16);
+        assert_eq!(gens, RangeProofGens::derive(b"range-test", 16));
+
+        let blinding = Fr::from(9);
+        let commitment = gens.commit(1000, blinding);
+        let proof = prove(
+            &gens,
+            1000,
+            blinding,
+            16,
+            &domain(),
+            &mut rand::thread_rng(),
+        );
+        let proof = RangeProof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+        assert!(verify(&gens, &commitment, 16, &proof, &domain()));
+
+        // same commitment, but a verifier holding other generators
+        let other = RangeProofGens::<G1Projective>::derive(b"range-other", 16);
+        assert_ne!(other.id, gens.id);
+        assert!(!verify(&other, &commitment, 16, &proof, &domain()));
+    }
+}
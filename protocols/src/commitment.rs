@@ -0,0 +1,238 @@
+use ark_ec::pairing::Pairing;
+
+#[cfg(feature = "prover")]
+use crate::kzg::kzg_helper_functions::compute_commitment;
+use crate::{
+    folding::pedersen::PedersenKey,
+    fri::merkle_tree::{MerkleProof, MerkleTree},
+    kzg::{
+        kzg_protocol::{self, KZGProof},
+        trusted_setup::TrustedSetup,
+    },
+    multi_linear::MultiLinearPoly,
+};
+
+//=========================================================================================
+// One API over the vector commitments in the crate, so a protocol that only needs
+// "commit to a vector, later open one position of it" can be written once
+// Self is whatever the scheme needs to commit and check (a key, a setup, nothing)
+//   Merkle:   SHA-256 root over byte leaves, opened at an index with an authentication path
+//   Pedersen: Σ vᵢ·Gᵢ opened at an index by revealing the whole vector (O(n), the succinct
+//             opening is bulletproofs::inner_product)
+//   KZG:      multilinear commitment to the evaluations over the hypercube, opened at any point
+// ProverData is what the committer keeps around to open later, commit and open are prover
+// features so the verifier-only build is left with verify
+//=========================================================================================
+pub trait VectorCommitment {
+    type Value;
+    type Position;
+    type Commitment;
+    type Opening;
+    type ProverData;
+
+    #[cfg(feature = "prover")]
+    fn commit(&self, values: &[Self::Value]) -> (Self::Commitment, Self::ProverData);
+
+    // None when the position is outside of what was committed
+    #[cfg(feature = "prover")]
+    fn open(
+        &self,
+        data: &Self::ProverData,
+        position: &Self::Position,
+    ) -> Option<(Self::Value, Self::Opening)>;
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        position: &Self::Position,
+        value: &Self::Value,
+        opening: &Self::Opening,
+    ) -> bool;
+}
+
+// the Merkle tree has no parameters, the hash is fixed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MerkleCommitment;
+
+impl VectorCommitment for MerkleCommitment {
+    type Value = Vec<u8>;
+    type Position = usize;
+    type Commitment = Vec<u8>;
+    type Opening = MerkleProof;
+    type ProverData = (Vec<Vec<u8>>, MerkleTree);
+
+    #[cfg(feature = "prover")]
+    fn commit(&self, values: &[Vec<u8>]) -> (Vec<u8>, Self::ProverData) {
+        let leaves: Vec<&[u8]> = values.iter().map(|value| value.as_slice()).collect();
+        let tree = MerkleTree::new(&leaves);
+
+        // an empty tree has no root, and nothing can be opened against the empty commitment
+        (tree.root().unwrap_or_default(), (values.to_vec(), tree))
+    }
+
+    #[cfg(feature = "prover")]
+    fn open(
+        &self,
+        (values, tree): &Self::ProverData,
+        index: &usize,
+    ) -> Option<(Vec<u8>, MerkleProof)> {
+        let proof = tree.generate_proof_at(*index)?;
+
+        Some((values[*index].clone(), proof))
+    }
+
+    fn verify(&self, root: &Vec<u8>, index: &usize, value: &Vec<u8>, proof: &MerkleProof) -> bool {
+        // the path alone would accept a proof for another leaf
+        proof.leaf_index == *index && MerkleTree::verify_path(value, proof, root)
+    }
+}
+
+impl<P: Pairing> VectorCommitment for PedersenKey<P> {
+    type Value = P::ScalarField;
+    type Position = usize;
+    type Commitment = P::G1;
+    type Opening = Vec<P::ScalarField>;
+    type ProverData = Vec<P::ScalarField>;
+
+    #[cfg(feature = "prover")]
+    fn commit(&self, values: &[P::ScalarField]) -> (P::G1, Self::ProverData) {
+        (PedersenKey::commit(self, values), values.to_vec())
+    }
+
+    #[cfg(feature = "prover")]
+    fn open(
+        &self,
+        values: &Self::ProverData,
+        index: &usize,
+    ) -> Option<(P::ScalarField, Vec<P::ScalarField>)> {
+        values.get(*index).map(|value| (*value, values.clone()))
+    }
+
+    fn verify(
+        &self,
+        commitment: &P::G1,
+        index: &usize,
+        value: &P::ScalarField,
+        values: &Vec<P::ScalarField>,
+    ) -> bool {
+        values.len() <= self.generators.len()
+            && values.get(*index) == Some(value)
+            && PedersenKey::commit(self, values) == *commitment
+    }
+}
+
+impl<P: Pairing> VectorCommitment for TrustedSetup<P> {
+    type Value = P::ScalarField;
+    type Position = Vec<P::ScalarField>;
+    type Commitment = P::G1;
+    type Opening = Vec<P::G1>; // the quotient commitments
+    type ProverData = MultiLinearPoly<P::ScalarField>;
+
+    #[cfg(feature = "prover")]
+    fn commit(&self, values: &[P::ScalarField]) -> (P::G1, Self::ProverData) {
+        let poly = MultiLinearPoly::new(values);
+        let commitment = compute_commitment::<P::ScalarField, P>(&poly, &self.g1_arr)
+            .expect("Polynomial size does not match the trusted setup");
+
+        (commitment, poly)
+    }
+
+    #[cfg(feature = "prover")]
+    fn open(
+        &self,
+        poly: &Self::ProverData,
+        point: &Vec<P::ScalarField>,
+    ) -> Option<(P::ScalarField, Vec<P::G1>)> {
+        if point.len() != self.verifier_setup.max_num_vars() {
+            return None;
+        }
+
+        let proof = kzg_protocol::proof::<P::ScalarField, P>(poly.clone(), &self.g1_arr, point);
+
+        Some((proof.poly_opened, proof.quotient_evals))
+    }
+
+    fn verify(
+        &self,
+        commitment: &P::G1,
+        point: &Vec<P::ScalarField>,
+        value: &P::ScalarField,
+        quotients: &Vec<P::G1>,
+    ) -> bool {
+        // kzg_protocol::verify indexes both by the setup size
+        let num_vars = self.verifier_setup.max_num_vars();
+        if point.len() != num_vars || quotients.len() != num_vars {
+            return false;
+        }
+
+        let proof = KZGProof {
+            commitment: *commitment,
+            quotient_evals: quotients.clone(),
+            poly_opened: *value,
+        };
+
+        kzg_protocol::verify(&proof, &self.verifier_setup, point)
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::kzg::trusted_setup::tests::setup;
+    use ark_bls12_381::{Bls12_381, Fr};
+
+    // written once against the trait, run for every scheme
+    fn check_scheme<C: VectorCommitment>(
+        scheme: &C,
+        values: &[C::Value],
+        position: C::Position,
+        wrong_position: C::Position,
+        wrong_value: C::Value,
+    ) where
+        C::Value: PartialEq + std::fmt::Debug,
+    {
+        let (commitment, data) = scheme.commit(values);
+        let (value, opening) = scheme.open(&data, &position).unwrap();
+
+        assert!(scheme.verify(&commitment, &position, &value, &opening));
+        assert!(!scheme.verify(&commitment, &position, &wrong_value, &opening));
+        assert!(!scheme.verify(&commitment, &wrong_position, &value, &opening));
+    }
+
+    #[test]
+    fn test_merkle_commitment() {
+        let values: Vec<Vec<u8>> = [b"a", b"b", b"c", b"a"]
+            .iter()
+            .map(|v| v.to_vec())
+            .collect();
+        check_scheme(&MerkleCommitment, &values, 3, 1, b"b".to_vec());
+
+        // the repeated leaf opens at either position
+        let (root, data) = MerkleCommitment.commit(&values);
+        let (value, proof) = MerkleCommitment.open(&data, &0).unwrap();
+        assert!(MerkleCommitment.verify(&root, &0, &value, &proof));
+        assert!(MerkleCommitment.open(&data, &4).is_none());
+    }
+
+    #[test]
+    fn test_pedersen_commitment() {
+        let key = PedersenKey::<Bls12_381>::setup(4, &mut rand::thread_rng());
+        let values = [3, 1, 4, 1].map(Fr::from);
+        check_scheme(&key, &values, 2, 0, Fr::from(5));
+        assert!(VectorCommitment::open(&key, &values.to_vec(), &4).is_none());
+    }
+
+    #[test]
+    fn test_kzg_commitment() {
+        let setup = setup();
+        let values: Vec<Fr> = (0..8).map(|i| Fr::from(i * i + 1)).collect();
+        let point = [2, 5, 7].map(Fr::from).to_vec();
+        let wrong_point = [2, 5, 8].map(Fr::from).to_vec();
+        check_scheme(&setup, &values, point, wrong_point, Fr::from(0));
+
+        // a point off the hypercube size opens to nothing and verifies to false
+        let (commitment, poly) = setup.commit(&values);
+        assert!(setup.open(&poly, &vec![Fr::from(1)]).is_none());
+        assert!(!setup.verify(&commitment, &vec![Fr::from(1)], &Fr::from(0), &vec![]));
+    }
+}
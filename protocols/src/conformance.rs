@@ -0,0 +1,258 @@
+//=========================================================================================
+// Conformance vectors
+// fixed small instances of every protocol are proved and everything that crosses the wire
+// (transcript squeezes, challenges, proof bytes) is written as hex into vectors/<name>.json
+// the tests regenerate the JSON and compare it byte for byte with the checked in file, so a
+// refactor that changes a single challenge or serialized byte fails here, and an implementation
+// in another language can check itself against the same files
+// a missing file is written on the first run (commit it), BLESS_VECTORS=1 rewrites all of them,
+// only do that for an intended format change and bump PROOF_FORMAT_VERSION with it
+// field elements are field_hex strings (0x, big endian, the modulus' width), points are ark
+// compressed, lists are JSON arrays
+//=========================================================================================
+use crate::{
+    field_hex::to_hex,
+    fri::{
+        lde::lde,
+        low_degree::{prove_low_degree, LowDegreeConfig},
+    },
+    gkr::test_circuits::circuit8,
+    kzg::{
+        kzg_helper_functions::test::poly_1,
+        kzg_protocol,
+        trusted_setup::{tests::TEST_SEED, TrustedSetup},
+    },
+    multi_linear::MultiLinearPoly,
+    proof_format::PROOF_FORMAT_VERSION,
+    sum_check,
+    transcript::test::domain,
+};
+use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+use ark_bn254::Fr;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use std::{env, fs, path::Path};
+
+enum Value {
+    Number(u64),
+    Hex(Vec<u8>),
+    Field(String),
+    List(Vec<Value>),
+}
+
+impl Value {
+    fn fields<F: PrimeField>(values: &[F]) -> Self {
+        Value::List(
+            values
+                .iter()
+                .map(|value| Value::Field(to_hex(value)))
+                .collect(),
+        )
+    }
+
+    // lists inside a list stay on one line
+    fn to_json(&self, top_level: bool) -> String {
+        match self {
+            Value::Number(number) => number.to_string(),
+            Value::Hex(bytes) => format!("\"{}\"", hex::encode(bytes)),
+            Value::Field(text) => format!("\"{}\"", text),
+            Value::List(items) if top_level => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|item| format!("    {}", item.to_json(false)))
+                    .collect();
+                format!("[\n{}\n  ]", items.join(",\n"))
+            }
+            Value::List(items) => {
+                let items: Vec<String> = items.iter().map(|item| item.to_json(false)).collect();
+                format!("[{}]", items.join(", "))
+            }
+        }
+    }
+}
+
+struct Vector {
+    name: &'static str,
+    entries: Vec<(&'static str, Value)>,
+}
+
+impl Vector {
+    fn new(name: &'static str) -> Self {
+        Vector {
+            name,
+            entries: vec![(
+                "proof_format_version",
+                Value::Number(PROOF_FORMAT_VERSION as u64),
+            )],
+        }
+    }
+
+    fn number(mut self, key: &'static str, value: u64) -> Self {
+        self.entries.push((key, Value::Number(value)));
+        self
+    }
+
+    fn hex(mut self, key: &'static str, bytes: Vec<u8>) -> Self {
+        self.entries.push((key, Value::Hex(bytes)));
+        self
+    }
+
+    fn list(mut self, key: &'static str, items: Vec<Vec<u8>>) -> Self {
+        self.entries.push((
+            key,
+            Value::List(items.into_iter().map(Value::Hex).collect()),
+        ));
+        self
+    }
+
+    fn field<F: PrimeField>(mut self, key: &'static str, value: F) -> Self {
+        self.entries.push((key, Value::Field(to_hex(&value))));
+        self
+    }
+
+    fn fields<F: PrimeField>(mut self, key: &'static str, values: &[F]) -> Self {
+        self.entries.push((key, Value::fields(values)));
+        self
+    }
+
+    // one inner list per round, layer...
+    fn field_lists<F: PrimeField>(mut self, key: &'static str, lists: &[&[F]]) -> Self {
+        self.entries.push((
+            key,
+            Value::List(lists.iter().map(|values| Value::fields(values)).collect()),
+        ));
+        self
+    }
+
+    fn to_json(&self) -> String {
+        let entries: Vec<String> = self
+            .entries
+            .iter()
+            .map(|(key, value)| format!("  \"{}\": {}", key, value.to_json(true)))
+            .collect();
+
+        format!("{{\n{}\n}}\n", entries.join(",\n"))
+    }
+
+    fn check(&self) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("vectors")
+            .join(format!("{}.json", self.name));
+        let json = self.to_json();
+
+        if env::var_os("BLESS_VECTORS").is_some() || !path.exists() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, json).unwrap();
+            return;
+        }
+
+        let expected = fs::read_to_string(&path).unwrap();
+        assert!(
+            json == expected,
+            "{} no longer matches vectors/{}.json, rerun with BLESS_VECTORS=1 only if the format \
+             change is intended (and bump PROOF_FORMAT_VERSION)",
+            self.name,
+            self.name
+        );
+    }
+}
+
+fn compressed<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value
+        .serialize_compressed(&mut bytes)
+        .expect("Serializing into a Vec can't fail");
+    bytes
+}
+
+#[test]
+fn test_transcript_vector() {
+    let mut transcript = domain().transcript();
+    transcript.absorb(b"conformance");
+
+    let first = transcript.squeeze();
+    let second = transcript.squeeze();
+    let challenge = transcript.squeeze_field::<Fr>();
+    let index = transcript.squeeze_index::<Fr>(1000);
+
+    Vector::new("transcript")
+        .list("squeezes", vec![first, second])
+        .field("bn254_challenge", challenge)
+        .number("index_below_1000", index as u64)
+        .check();
+}
+
+#[test]
+fn test_sum_check_vector() {
+    // 3ab + 4c over bn254, sums to 22
+    let poly = MultiLinearPoly::new(&[0, 4, 0, 4, 0, 4, 3, 7].map(Fr::from));
+    let proof = sum_check::proof(poly, Fr::from(22));
+
+    let round_polys: Vec<&[Fr]> = proof
+        .sum_polys
+        .iter()
+        .map(|sum_poly| sum_poly.computation.as_slice())
+        .collect();
+
+    Vector::new("sum_check_bn254")
+        .field("init_claimed_sum", proof.init_claimed_sum)
+        .field_lists("round_polys", &round_polys)
+        .check();
+}
+
+#[test]
+fn test_gkr_vector() {
+    let circuit = circuit8::<Fr>();
+    let proof = circuit.proof(&domain());
+
+    let layer_challenges: Vec<&[Fr]> = proof
+        .p_proofs
+        .iter()
+        .map(|p_proof| p_proof.challenges.as_slice())
+        .collect();
+
+    Vector::new("gkr_circuit8_bn254")
+        .field_lists("layer_challenges", &layer_challenges)
+        .hex("proof", proof.to_bytes().unwrap())
+        .check();
+}
+
+#[test]
+fn test_kzg_vector() {
+    let setup = TrustedSetup::<Bls12_381>::insecure_from_seed(TEST_SEED, 3);
+    let point = [6, 4, 0].map(BlsFr::from);
+    let proof = kzg_protocol::proof::<BlsFr, Bls12_381>(poly_1(), &setup.g1_arr, &point);
+
+    Vector::new("kzg_bls12_381")
+        .number("setup_seed", TEST_SEED)
+        .hex("commitment", compressed(&proof.commitment))
+        .hex("proof", proof.to_bytes().unwrap())
+        .check();
+}
+
+#[test]
+fn test_fri_vector() {
+    // 5 + 3x + 2x^3 + x^4 + 7x^5 over a domain of size 16
+    let evals = lde(&[5, 3, 0, 2, 1, 7].map(Fr::from), 2, Fr::from(1));
+    let proof = prove_low_degree(&evals, 6, &LowDegreeConfig::default(), &domain());
+
+    let openings: Vec<Vec<u8>> = proof
+        .query_openings
+        .iter()
+        .flatten()
+        .map(|opening| {
+            let mut bytes = compressed(&(opening.value, opening.neg_value));
+            for path in [&opening.proof, &opening.neg_proof] {
+                bytes.extend(path.to_bytes().unwrap());
+            }
+            bytes
+        })
+        .collect();
+
+    Vector::new("fri_low_degree_bn254")
+        .list("root_hashes", proof.root_hashes.clone())
+        .fields("final_layer", &proof.final_layer)
+        .number("pow_nonce", proof.pow_nonce)
+        .list("query_openings", openings)
+        .check();
+}
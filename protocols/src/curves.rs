@@ -0,0 +1,239 @@
+//=========================================================================================
+// Curve backends
+// every protocol in this crate is generic over F: PrimeField, G: CurveGroup or P: Pairing,
+// the curve crates are only pulled in through cargo features so a user can compile just theirs
+// bn254, bls12-381 and bls12-377 are pairing friendly (KZG, G2 KZG and everything below)
+// pallas and vesta are not, they cover the field and group paths (GKR, sum-check, FRI, Feldman
+// shares, and Schnorr in zk-frontends)
+// the test matrix at the bottom runs the same protocol bodies on every curve that is enabled
+//=========================================================================================
+#[cfg(feature = "bls12-377")]
+pub use ark_bls12_377 as bls12_377;
+#[cfg(feature = "bls12-381")]
+pub use ark_bls12_381 as bls12_381;
+#[cfg(feature = "bn254")]
+pub use ark_bn254 as bn254;
+#[cfg(feature = "pallas")]
+pub use ark_pallas as pallas;
+#[cfg(feature = "vesta")]
+pub use ark_vesta as vesta;
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use crate::{
+        fri::{
+            lde::lde,
+            low_degree::{prove_low_degree, verify_low_degree, LowDegreeConfig},
+        },
+        gkr::test_circuits::circuit8,
+        multi_linear::MultiLinearPoly,
+        shamir_secret_sharing::{generate_verifiable_shares, reconstruct_secret, ShamirShare},
+        sum_check,
+        transcript::test::domain,
+    };
+    use ark_ec::CurveGroup;
+    use ark_ff::{FftField, PrimeField};
+
+    // one #[test] per enabled curve, each calling body::<scalar field>()
+    macro_rules! field_tests {
+        ($name:ident, $body:ident) => {
+            mod $name {
+                #[cfg(feature = "bn254")]
+                #[test]
+                fn bn254() {
+                    super::$body::<ark_bn254::Fr>();
+                }
+
+                #[cfg(feature = "bls12-381")]
+                #[test]
+                fn bls12_381() {
+                    super::$body::<ark_bls12_381::Fr>();
+                }
+
+                #[cfg(feature = "bls12-377")]
+                #[test]
+                fn bls12_377() {
+                    super::$body::<ark_bls12_377::Fr>();
+                }
+
+                #[cfg(feature = "pallas")]
+                #[test]
+                fn pallas() {
+                    super::$body::<ark_pallas::Fr>();
+                }
+
+                #[cfg(feature = "vesta")]
+                #[test]
+                fn vesta() {
+                    super::$body::<ark_vesta::Fr>();
+                }
+            }
+        };
+    }
+
+    // one #[test] per enabled curve, each calling body::<G1 / curve group>()
+    macro_rules! group_tests {
+        ($name:ident, $body:ident) => {
+            mod $name {
+                #[cfg(feature = "bn254")]
+                #[test]
+                fn bn254() {
+                    super::$body::<ark_bn254::G1Projective>();
+                }
+
+                #[cfg(feature = "bls12-381")]
+                #[test]
+                fn bls12_381() {
+                    super::$body::<ark_bls12_381::G1Projective>();
+                }
+
+                #[cfg(feature = "bls12-377")]
+                #[test]
+                fn bls12_377() {
+                    super::$body::<ark_bls12_377::G1Projective>();
+                }
+
+                #[cfg(feature = "pallas")]
+                #[test]
+                fn pallas() {
+                    super::$body::<ark_pallas::Projective>();
+                }
+
+                #[cfg(feature = "vesta")]
+                #[test]
+                fn vesta() {
+                    super::$body::<ark_vesta::Projective>();
+                }
+            }
+        };
+    }
+
+    // 3ab + 4c
+    fn poly_1<F: PrimeField>() -> MultiLinearPoly<F> {
+        MultiLinearPoly::new(&[0, 4, 0, 4, 0, 4, 3, 7].map(F::from))
+    }
+
+    fn gkr_prove_and_verify<F: PrimeField>() {
+        let circuit = circuit8::<F>();
+        let proof = circuit.proof(&domain());
+
+        assert!(circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+
+        let mut public = circuit.public_inputs();
+        public.outputs[0] += F::one();
+        assert!(!circuit.verify(&proof, &public, &domain()));
+    }
+
+    fn sum_check_prove_and_verify<F: PrimeField>() {
+        let poly = poly_1::<F>();
+        let claimed_sum = poly.computation.iter().sum();
+
+        assert!(sum_check::verify(&sum_check::proof(
+            poly.clone(),
+            claimed_sum
+        )));
+        assert!(!sum_check::verify(&sum_check::proof(
+            poly,
+            claimed_sum + F::one()
+        )));
+    }
+
+    fn fri_low_degree<F: FftField + PrimeField>() {
+        // 5 + 3x + 2x^3 + x^4 + 7x^5 over a domain of size 16
+        let evals = lde(&[5u64, 3, 0, 2, 1, 7].map(F::from), 2, F::one());
+        let config = LowDegreeConfig::default();
+
+        let proof = prove_low_degree(&evals, 6, &config, &domain());
+        assert!(verify_low_degree(&proof, 6, &config, &domain()));
+    }
+
+    fn feldman_shares<G: CurveGroup>() {
+        let secret = G::ScalarField::from(42u64);
+        let (shares, commitments) = generate_verifiable_shares::<G::ScalarField, G>(secret, 3, 5);
+
+        assert!(shares.iter().all(|share| share.verify(&commitments)));
+        assert_eq!(reconstruct_secret(&shares[1..4], 3), secret);
+
+        let forged = ShamirShare::new(shares[0].x, shares[0].y + G::ScalarField::from(1u64));
+        assert!(!forged.verify(&commitments));
+    }
+
+    field_tests!(test_gkr_prove_and_verify, gkr_prove_and_verify);
+    field_tests!(test_sum_check_prove_and_verify, sum_check_prove_and_verify);
+    field_tests!(test_fri_low_degree, fri_low_degree);
+
+    group_tests!(test_feldman_shares, feldman_shares);
+
+    // the KZG bodies only exist when at least one pairing curve is enabled
+    #[cfg(any(feature = "bn254", feature = "bls12-381", feature = "bls12-377"))]
+    mod pairing {
+        use super::poly_1;
+        use crate::kzg::{
+            g2_kzg::{commit_g2, commitments_match, initialize_g2},
+            kzg_helper_functions::compute_commitment,
+            kzg_protocol,
+            trusted_setup::{initialize, insecure_taus, TrustedSetup},
+        };
+        use ark_ec::pairing::Pairing;
+
+        // one #[test] per enabled pairing curve, each calling body::<Curve>()
+        macro_rules! pairing_curve_tests {
+            ($name:ident, $body:ident) => {
+                mod $name {
+                    #[cfg(feature = "bn254")]
+                    #[test]
+                    fn bn254() {
+                        super::$body::<ark_bn254::Bn254>();
+                    }
+
+                    #[cfg(feature = "bls12-381")]
+                    #[test]
+                    fn bls12_381() {
+                        super::$body::<ark_bls12_381::Bls12_381>();
+                    }
+
+                    #[cfg(feature = "bls12-377")]
+                    #[test]
+                    fn bls12_377() {
+                        super::$body::<ark_bls12_377::Bls12_377>();
+                    }
+                }
+            };
+        }
+
+        fn kzg_open_and_verify<P: Pairing>() {
+            let setup = TrustedSetup::<P>::insecure_from_seed(5, 3);
+            let point = [6u64, 4, 0].map(P::ScalarField::from);
+
+            let proof = kzg_protocol::proof::<P::ScalarField, P>(poly_1(), &setup.g1_arr, &point);
+            assert_eq!(proof.poly_opened, P::ScalarField::from(72u64));
+            assert!(kzg_protocol::verify(&proof, &setup.verifier_setup, &point));
+
+            let mut wrong_value =
+                kzg_protocol::proof::<P::ScalarField, P>(poly_1(), &setup.g1_arr, &point);
+            wrong_value.poly_opened += P::ScalarField::from(1u64);
+            assert!(!kzg_protocol::verify(
+                &wrong_value,
+                &setup.verifier_setup,
+                &point
+            ));
+        }
+
+        fn g2_commitment_matches_g1<P: Pairing>() {
+            let taus = insecure_taus::<P::ScalarField>(5, 3);
+            let setup = initialize::<P::ScalarField, P>(&taus);
+            let g2_setup = initialize_g2::<P::ScalarField, P>(&taus);
+
+            let g1_commitment = compute_commitment::<P::ScalarField, P>(&poly_1(), &setup.g1_arr);
+            let g2_commitment = commit_g2::<P::ScalarField, P>(&poly_1(), &g2_setup.g2_arr);
+            assert!(commitments_match(
+                &g1_commitment.unwrap(),
+                &g2_commitment.unwrap(),
+                &g2_setup.verifier_setup
+            ));
+        }
+
+        pairing_curve_tests!(test_kzg_open_and_verify, kzg_open_and_verify);
+        pairing_curve_tests!(test_g2_commitment_matches_g1, g2_commitment_matches_g1);
+    }
+}
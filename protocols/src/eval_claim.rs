@@ -0,0 +1,116 @@
+use crate::{
+    multi_linear::MultiLinearPoly,
+    pcs::{PcsError, PolynomialCommitment},
+};
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// Evaluation claims, the hand-off between a sum-check / GKR verifier and whoever checks openings
+// a verifier that ends on "Wᵢ(r) = v" returns EvalClaim { poly_id: Layer(i), point: r, value: v }
+// instead of a bare value, and the claim is discharged either by evaluating the polynomial
+// directly (holds_on) or by a PCS opening of its commitment (check_with)
+// a GKR layer reduction ends on two of them, one at r_b and one at r_c: the sum-check point is
+// r_b ‖ r_c and the proofs carry the two values as the (Wᵢ(r_b), Wᵢ(r_c)) pairs in w_i_evals
+//=========================================================================================
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PolyId {
+    Layer(usize), // Wᵢ of a GKR circuit, layer 0 is the output layer
+    Input,        // the input layer, the one a commitment is opened on
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalClaim<F: PrimeField> {
+    pub poly_id: PolyId,
+    pub point: Vec<F>,
+    pub value: F,
+}
+
+impl<F: PrimeField> EvalClaim<F> {
+    pub fn new(poly_id: PolyId, point: Vec<F>, value: F) -> Self {
+        EvalClaim {
+            poly_id,
+            point,
+            value,
+        }
+    }
+
+    // the claims at r_b and r_c a GKR sum-check over (b, c) ends on, point is r_b ‖ r_c
+    // None if the point doesn't split into two halves, the point comes from an untrusted proof
+    pub fn pair(poly_id: PolyId, point: &[F], (value_b, value_c): (F, F)) -> Option<[Self; 2]> {
+        if !point.len().is_multiple_of(2) {
+            return None;
+        }
+
+        let (r_b, r_c) = point.split_at(point.len() / 2);
+        Some([
+            EvalClaim::new(poly_id, r_b.to_vec(), value_b),
+            EvalClaim::new(poly_id, r_c.to_vec(), value_c),
+        ])
+    }
+
+    // checked against the polynomial itself, for a verifier that has it (e.g. public inputs)
+    pub fn holds_on(&self, poly: &MultiLinearPoly<F>) -> bool {
+        if poly.computation.len() != 1 << self.point.len() {
+            return false;
+        }
+
+        poly.clone().evaluate(&self.point).computation[0] == self.value
+    }
+
+    // checked against a commitment to the polynomial with any backend of pcs
+    pub fn check_with<K: PolynomialCommitment<Point = Vec<F>, Value = F>>(
+        &self,
+        vk: &K,
+        commitment: &K::Commitment,
+        proof: &K::Proof,
+    ) -> Result<(), PcsError> {
+        vk.check_evaluation(commitment, &self.point, &self.value, proof)
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::{
+        commitment::VectorCommitment,
+        kzg::{kzg_helper_functions::test::poly_1, trusted_setup::tests::setup},
+    };
+    use ark_bls12_381::Fr;
+
+    #[test]
+    fn test_pair_splits_the_point() {
+        let point = [1, 2, 3, 4].map(Fr::from);
+        let [claim_b, claim_c] =
+            EvalClaim::pair(PolyId::Layer(2), &point, (Fr::from(5), Fr::from(6))).unwrap();
+
+        assert_eq!(claim_b.point, [Fr::from(1), Fr::from(2)]);
+        assert_eq!(claim_c.point, [Fr::from(3), Fr::from(4)]);
+        assert_eq!((claim_b.value, claim_c.value), (Fr::from(5), Fr::from(6)));
+        assert_eq!(claim_c.poly_id, PolyId::Layer(2));
+        assert!(EvalClaim::pair(PolyId::Input, &point[..3], (Fr::from(5), Fr::from(6))).is_none());
+    }
+
+    #[test]
+    fn test_claim_discharged_by_poly_and_commitment() {
+        let setup = setup();
+        let poly = poly_1();
+        let (commitment, committed) = setup.commit(&poly.computation);
+        let point = vec![Fr::from(2), Fr::from(3), Fr::from(4)];
+        let (value, quotients) = setup.open(&committed, &point).unwrap();
+        let verifier_setup = &setup.verifier_setup;
+
+        let claim = EvalClaim::new(PolyId::Input, point.clone(), value);
+        assert!(claim.holds_on(&poly));
+        assert_eq!(
+            claim.check_with(verifier_setup, &commitment, &quotients),
+            Ok(())
+        );
+
+        let wrong = EvalClaim::new(PolyId::Input, point, value + Fr::from(1));
+        assert!(!wrong.holds_on(&poly));
+        assert_eq!(
+            wrong.check_with(verifier_setup, &commitment, &quotients),
+            Err(PcsError::Rejected)
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::gkr::{gkr_protocol::GKRProof, verifying_key::VerifyingKey};
+use ark_bn254::Fr;
+use std::{panic, slice};
+
+//=========================================================================================
+// C ABI for the verifiers, so they can be linked into C / C++ / Go applications
+// the proof is GKRProof::to_bytes and the key VerifyingKey::to_bytes, both over the bn254
+// scalar field, the declarations for C are in include/zk_verify.h and a test below keeps the
+// two in sync
+// nothing here allocates on behalf of the caller and nothing is kept after a call returns
+// a panic never crosses the boundary, it is reported as ZK_ERR_PANIC
+//=========================================================================================
+pub const ZK_VALID: i32 = 1;
+pub const ZK_INVALID: i32 = 0;
+pub const ZK_ERR_NULL: i32 = -1; // a null pointer with a non zero length
+pub const ZK_ERR_PROOF: i32 = -2; // the proof bytes don't decode
+pub const ZK_ERR_KEY: i32 = -3; // the verifying key bytes don't decode
+pub const ZK_ERR_PANIC: i32 = -4;
+
+unsafe fn bytes_from<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    if len == 0 {
+        return Some(&[]);
+    }
+    if ptr.is_null() {
+        return None;
+    }
+
+    Some(slice::from_raw_parts(ptr, len))
+}
+
+fn verify_gkr(proof: &[u8], key: &[u8]) -> i32 {
+    let proof = match GKRProof::<Fr>::from_bytes(proof) {
+        Ok(proof) => proof,
+        Err(_) => return ZK_ERR_PROOF,
+    };
+    let key = match VerifyingKey::<Fr>::from_bytes(key) {
+        Ok(key) => key,
+        Err(_) => return ZK_ERR_KEY,
+    };
+
+    if key.verify(&proof) {
+        ZK_VALID
+    } else {
+        ZK_INVALID
+    }
+}
+
+/// Verifies a serialized GKR proof against a serialized verifying key, returns ZK_VALID,
+/// ZK_INVALID or one of the negative ZK_ERR_ codes.
+///
+/// # Safety
+///
+/// `proof_ptr` must point to `proof_len` readable bytes and `vk_ptr` to `vk_len` readable bytes
+/// (either may be null when its length is 0), and neither may be written to during the call.
+#[no_mangle]
+pub unsafe extern "C" fn zk_verify_gkr(
+    proof_ptr: *const u8,
+    proof_len: usize,
+    vk_ptr: *const u8,
+    vk_len: usize,
+) -> i32 {
+    let (proof, key) = match (bytes_from(proof_ptr, proof_len), bytes_from(vk_ptr, vk_len)) {
+        (Some(proof), Some(key)) => (proof, key),
+        _ => return ZK_ERR_NULL,
+    };
+
+    panic::catch_unwind(|| verify_gkr(proof, key)).unwrap_or(ZK_ERR_PANIC)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        gkr::{gkr_circuit::Circuit, test_circuits::circuit8},
+        transcript::test::domain,
+    };
+    use std::ptr;
+
+    fn proof_and_key(circuit: &Circuit<Fr>) -> (Vec<u8>, Vec<u8>) {
+        let proof = circuit.proof(&domain()).to_bytes().unwrap();
+        let key = VerifyingKey::new(circuit, circuit.public_inputs(), domain()).to_bytes();
+
+        (proof, key)
+    }
+
+    fn call(proof: &[u8], key: &[u8]) -> i32 {
+        unsafe { zk_verify_gkr(proof.as_ptr(), proof.len(), key.as_ptr(), key.len()) }
+    }
+
+    #[test]
+    fn test_zk_verify_gkr() {
+        let circuit = circuit8::<Fr>();
+        let (proof, key) = proof_and_key(&circuit);
+        assert_eq!(call(&proof, &key), ZK_VALID);
+
+        // the same proof against another claimed output
+        let mut other = circuit8::<Fr>();
+        other.inputs[0] += Fr::from(1);
+        let (_, other_key) = proof_and_key(&other);
+        assert_eq!(call(&proof, &other_key), ZK_INVALID);
+    }
+
+    #[test]
+    fn test_zk_verify_gkr_errors() {
+        let (proof, key) = proof_and_key(&circuit8::<Fr>());
+
+        assert_eq!(call(&proof[..proof.len() - 1], &key), ZK_ERR_PROOF);
+        assert_eq!(call(&proof, &key[1..]), ZK_ERR_KEY);
+        assert_eq!(call(&[], &key), ZK_ERR_PROOF);
+        let null = unsafe { zk_verify_gkr(ptr::null(), 4, key.as_ptr(), key.len()) };
+        assert_eq!(null, ZK_ERR_NULL);
+    }
+
+    #[test]
+    fn test_header_matches() {
+        let header = include_str!("../include/zk_verify.h");
+
+        for (name, value) in [
+            ("ZK_VALID", ZK_VALID),
+            ("ZK_INVALID", ZK_INVALID),
+            ("ZK_ERR_NULL", ZK_ERR_NULL),
+            ("ZK_ERR_PROOF", ZK_ERR_PROOF),
+            ("ZK_ERR_KEY", ZK_ERR_KEY),
+            ("ZK_ERR_PANIC", ZK_ERR_PANIC),
+        ] {
+            let define = format!("#define {} ({})", name, value);
+            assert!(header.contains(&define), "missing `{}`", define);
+        }
+        assert!(header.contains(
+            "int32_t zk_verify_gkr(const uint8_t *proof_ptr, size_t proof_len,\n                       const uint8_t *vk_ptr, size_t vk_len);"
+        ));
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    iop::iop_trait::InteractiveOracleProof,
+    transcript::{Transcript, TranscriptHasher},
+};
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// Fiat-Shamir for any public coin IOP in the crate
+// every prover message is absorbed and each coin the verifier would have flipped is squeezed
+// the prover runs the verifier's side too, only to learn what the next verifier message is
+// the statement (claimed sum, circuit inputs, ...) should be absorbed by the caller first
+// prover messages go in with absorb_labeled under PROVER_MSG_LABEL
+//=========================================================================================
+pub const PROVER_MSG_LABEL: &[u8] = b"prover-msg";
+
+pub fn compile<F: PrimeField, I: InteractiveOracleProof<F>, H: TranscriptHasher>(
+    prover: &mut I::Prover,
+    verifier: &mut I::Verifier,
+    transcript: &mut Transcript<H>,
+) -> Vec<I::ProverMsg> {
+    let mut prover_msgs = Vec::new();
+    let mut verifier_msg: Option<I::VerifierMsg> = None;
+
+    while !I::is_finished(verifier) {
+        let prover_msg = I::prover_round(prover, verifier_msg.as_ref());
+        transcript.absorb_labeled(PROVER_MSG_LABEL, &I::prover_msg_bytes(&prover_msg));
+
+        let mut coins = || transcript.squeeze_field::<F>();
+        verifier_msg = I::verifier_round(verifier, &prover_msg, &mut coins);
+        prover_msgs.push(prover_msg);
+
+        // a dishonest or broken prover, the messages so far will not verify anyway
+        if verifier_msg.is_none() {
+            break;
+        }
+    }
+
+    prover_msgs
+}
+
+pub fn verify<F: PrimeField, I: InteractiveOracleProof<F>, H: TranscriptHasher>(
+    verifier: &mut I::Verifier,
+    proof: &[I::ProverMsg],
+    transcript: &mut Transcript<H>,
+) -> bool {
+    for prover_msg in proof.iter() {
+        if I::is_finished(verifier) {
+            return false; // more messages than the protocol has rounds
+        }
+
+        transcript.absorb_labeled(PROVER_MSG_LABEL, &I::prover_msg_bytes(prover_msg));
+        let mut coins = || transcript.squeeze_field::<F>();
+        if I::verifier_round(verifier, prover_msg, &mut coins).is_none() {
+            return false;
+        }
+    }
+
+    let mut coins = || transcript.squeeze_field::<F>();
+    I::is_finished(verifier) && I::verify_final(verifier, &mut coins)
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-377"))]
+mod test {
+    use super::*;
+    use crate::{
+        gkr::product_poly::ProductPoly,
+        iop::{
+            fri_iop::{FRIFoldingIOP, FRIFoldingProver, FRIFoldingVerifier},
+            sum_check_iop::{SumCheckIOP, SumCheckProver, SumCheckVerifier},
+        },
+        multi_linear::MultiLinearPoly,
+    };
+    use ark_bls12_377::Fr;
+
+    fn product_polys() -> Vec<ProductPoly<Fr>> {
+        let poly_1 = MultiLinearPoly::new(&[1, 2, 3, 4, 5, 6, 7, 8].map(Fr::from));
+        let poly_2 = MultiLinearPoly::new(&[0, 0, 0, 2, 0, 3, 1, 1].map(Fr::from));
+
+        vec![ProductPoly::new(vec![poly_1, poly_2])]
+    }
+
+    #[test]
+    fn test_compile_sum_check() {
+        let mut prover = SumCheckProver::new(product_polys());
+        let claimed_sum = prover.claimed_sum();
+
+        let mut prover_verifier = SumCheckVerifier::new(product_polys(), claimed_sum);
+        let proof = compile::<Fr, SumCheckIOP, _>(
+            &mut prover,
+            &mut prover_verifier,
+            &mut Transcript::new(),
+        );
+        assert_eq!(proof.len(), 3);
+
+        let mut verifier = SumCheckVerifier::new(product_polys(), claimed_sum);
+        assert!(verify::<Fr, SumCheckIOP, _>(
+            &mut verifier,
+            &proof,
+            &mut Transcript::new()
+        ));
+        // both sides squeezed the same challenges
+        assert_eq!(verifier.challenges, prover_verifier.challenges);
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_length() {
+        let mut prover = SumCheckProver::new(product_polys());
+        let claimed_sum = prover.claimed_sum();
+
+        let mut prover_verifier = SumCheckVerifier::new(product_polys(), claimed_sum);
+        let mut proof = compile::<Fr, SumCheckIOP, _>(
+            &mut prover,
+            &mut prover_verifier,
+            &mut Transcript::new(),
+        );
+
+        proof.push(proof[0].clone());
+        let mut verifier = SumCheckVerifier::new(product_polys(), claimed_sum);
+        assert!(!verify::<Fr, SumCheckIOP, _>(
+            &mut verifier,
+            &proof,
+            &mut Transcript::new()
+        ));
+
+        proof.truncate(2);
+        let mut verifier = SumCheckVerifier::new(product_polys(), claimed_sum);
+        assert!(!verify::<Fr, SumCheckIOP, _>(
+            &mut verifier,
+            &proof,
+            &mut Transcript::new()
+        ));
+    }
+
+    #[test]
+    fn test_compile_fri_folding() {
+        let coeffs = [5, 3, 0, 2, 1, 7].map(Fr::from).to_vec();
+        let mut prover = FRIFoldingProver::new(coeffs, 2);
+
+        let proof = compile::<Fr, FRIFoldingIOP, _>(
+            &mut prover,
+            &mut FRIFoldingVerifier::new(6, 2, 4),
+            &mut Transcript::new(),
+        );
+
+        let mut verifier = FRIFoldingVerifier::new(6, 2, 4);
+        assert!(verify::<Fr, FRIFoldingIOP, _>(
+            &mut verifier,
+            &proof,
+            &mut Transcript::new()
+        ));
+
+        // a different transcript prefix gives different folding challenges
+        let mut transcript = Transcript::new();
+        transcript.absorb(b"other statement");
+        let mut verifier = FRIFoldingVerifier::new(6, 2, 4);
+        assert!(!verify::<Fr, FRIFoldingIOP, _>(
+            &mut verifier,
+            &proof,
+            &mut transcript
+        ));
+    }
+}
@@ -1,3 +1,2 @@
 pub mod prover;
-pub mod transcript;
 pub mod verifier;
@@ -1,10 +1,18 @@
-use crate::{fiat_shamir_non_interactive::transcript::Transcript, multi_linear::MultiLinearPoly};
+use crate::{
+    fiat_shamir,
+    gkr::product_poly::ProductPoly,
+    iop::sum_check_iop::{SumCheckIOP, SumCheckProver, SumCheckVerifier},
+    multi_linear::MultiLinearPoly,
+    sum_check,
+    transcript::{Transcript, TranscriptHasher},
+};
 use ark_ff::PrimeField;
+use sha3::Keccak256;
 
 #[derive(Debug, Clone)]
 pub struct Proof<F: PrimeField> {
     pub claimed_sums: Vec<F>,
-    pub sum_polys: Vec<MultiLinearPoly<F>>,
+    pub sum_polys: Vec<MultiLinearPoly<F>>, // round polys in evaluation form [g(0), g(1), ..., g(d)]
 }
 
 pub struct FinalState<F: PrimeField> {
@@ -16,14 +24,24 @@ pub struct ProverStruct<F: PrimeField> {
     // boolean hypercube computation
     // added the challenges array and final_univariate_poly to enable final testing
     pub bh_computation: MultiLinearPoly<F>,
+    pub sum_poly: Vec<ProductPoly<F>>, // Σ ∏ of multilinear polys, a single poly for new()
     pub proof: Proof<F>,
     pub final_state: FinalState<F>,
 }
 
 impl<F: PrimeField> ProverStruct<F> {
     pub fn new(bh_computation: Vec<F>) -> Self {
+        let poly = MultiLinearPoly::new(&bh_computation);
+
+        ProverStruct::from_product_polys(vec![ProductPoly::new(vec![poly])])
+    }
+
+    // same round polynomials as the GKR sum-check (partial_sum_check) so products are sound
+    // bh_computation holds the values of Σ ∏ over the hypercube
+    pub fn from_product_polys(sum_poly: Vec<ProductPoly<F>>) -> Self {
         ProverStruct {
-            bh_computation: MultiLinearPoly::new(&bh_computation),
+            bh_computation: MultiLinearPoly::new(&hypercube_values(&sum_poly)),
+            sum_poly,
             proof: Proof {
                 claimed_sums: Vec::new(),
                 sum_polys: Vec::new(),
@@ -39,65 +57,107 @@ impl<F: PrimeField> ProverStruct<F> {
         MultiLinearPoly::to_bytes(&computation)
     }
 
+    // the rounds come from fiat_shamir::compile over the sum-check IOP
     pub fn generate_proof(&mut self) -> Vec<F> {
-        let mut current_poly_ml = MultiLinearPoly::new(&self.bh_computation.computation);
-        let mut transcript = Transcript::new();
-        transcript.append(&ProverStruct::convert_to_bytes(
-            current_poly_ml.computation.clone(),
-        ));
-
-        while current_poly_ml.computation.len() > 1 {
-            // println!("Starting computation is {:?}", current_poly_ml.computation);
-            let claimed_sum: F = current_poly_ml.computation.iter().sum();
-
-            let half_len = current_poly_ml.computation.len() / 2;
-            let (left, right) = current_poly_ml.computation.split_at(half_len);
-            let left_sum: F = left.iter().sum();
-            let right_sum = right.iter().sum();
-
-            let sum_poly = MultiLinearPoly::new(&vec![left_sum, right_sum]);
-            // println!("Sum poly is {:?}", sum_poly);
-            self.proof.claimed_sums.push(claimed_sum);
-            self.proof.sum_polys.push(MultiLinearPoly {
-                computation: sum_poly.computation.clone(),
-            });
+        self.generate_proof_with_hasher::<Keccak256>()
+    }
+
+    // the verifier has to be given the same hasher, see VerifierStruct::verify_proof_with_hasher
+    pub fn generate_proof_with_hasher<H: TranscriptHasher>(&mut self) -> Vec<F> {
+        let claimed_sum: F = self.bh_computation.computation.iter().sum();
+        let mut transcript =
+            statement_transcript::<F, H>(&self.bh_computation.computation, claimed_sum);
 
-            transcript.append(&ProverStruct::convert_to_bytes(vec![claimed_sum]));
-            transcript.append(&ProverStruct::convert_to_bytes(
-                sum_poly.computation.clone(),
-            ));
-            let challenge_bytes = transcript.challenge();
-            let challenge = F::from_be_bytes_mod_order(&challenge_bytes);
-            self.final_state.challenges.push(challenge);
+        let mut prover = SumCheckProver::new(self.sum_poly.clone());
+        let mut verifier = SumCheckVerifier::new(self.sum_poly.clone(), claimed_sum);
+        let round_polys =
+            fiat_shamir::compile::<F, SumCheckIOP, _>(&mut prover, &mut verifier, &mut transcript);
 
-            current_poly_ml = current_poly_ml.partial_evaluate(challenge, 0);
+        for round_poly in round_polys {
+            // the claim of each round is g(0) + g(1) of its round poly
+            self.proof.claimed_sums.push(round_poly[0] + round_poly[1]);
+            self.proof.sum_polys.push(MultiLinearPoly {
+                computation: round_poly,
+            });
         }
 
-        self.final_state.final_univariate_poly = current_poly_ml.computation.clone();
-        current_poly_ml.computation
+        self.final_state.challenges = verifier.challenges;
+        self.final_state.final_univariate_poly =
+            vec![oracle_eval(&self.sum_poly, &self.final_state.challenges)];
+        self.final_state.final_univariate_poly.clone()
     }
 
     pub fn get_proof(&self) -> Proof<F> {
-        // println!("Proof is {:?}", self.proof.clone());
         self.proof.clone()
     }
 
     pub fn verify_proof(&self) -> bool {
-        let mut current_poly_ml = MultiLinearPoly::new(&self.bh_computation.computation);
-
-        let final_output = current_poly_ml
-            .evaluate(&self.final_state.challenges)
-            .computation[0];
+        let final_output = oracle_eval(&self.sum_poly, &self.final_state.challenges);
 
         final_output == self.final_state.final_univariate_poly[0]
     }
 }
 
-#[cfg(test)]
+// the statement both sides absorb before any round: the hypercube values and the claimed sum
+// the same labelled statement as sum_check, so both frontends draw the same challenges
+pub fn statement_transcript<F: PrimeField, H: TranscriptHasher>(
+    bh_computation: &[F],
+    claimed_sum: F,
+) -> Transcript<H> {
+    sum_check::statement_transcript(bh_computation, claimed_sum)
+}
+
+// Σ ∏ evaluated point-wise over the boolean hypercube
+pub fn hypercube_values<F: PrimeField>(sum_poly: &[ProductPoly<F>]) -> Vec<F> {
+    let len = sum_poly[0].poly_array[0].computation.len();
+
+    (0..len)
+        .map(|i| {
+            sum_poly
+                .iter()
+                .map(|p_poly| {
+                    p_poly
+                        .poly_array
+                        .iter()
+                        .map(|m_poly| m_poly.computation[i])
+                        .product::<F>()
+                })
+                .sum()
+        })
+        .collect()
+}
+
+// Σ ∏ evaluated at the challenges, the oracle query at the end of the sum-check
+pub fn oracle_eval<F: PrimeField>(sum_poly: &[ProductPoly<F>], challenges: &[F]) -> F {
+    sum_poly
+        .iter()
+        .map(|p_poly| {
+            p_poly
+                .poly_array
+                .iter()
+                .map(|m_poly| m_poly.clone().evaluate(challenges).computation[0])
+                .product::<F>()
+        })
+        .sum()
+}
+
+#[cfg(all(test, feature = "bn254"))]
 mod test {
     use super::*;
+    use crate::fiat_shamir_non_interactive::verifier::VerifierStruct;
+    use crate::gkr::partial_sum_check::{self, fold_sum_poly, round_poly};
     use ark_bn254::Fq;
 
+    fn product_polys() -> Vec<ProductPoly<Fq>> {
+        let poly_1 = MultiLinearPoly::new(&[1, 2, 3, 4, 5, 6, 7, 8].map(Fq::from));
+        let poly_2 = MultiLinearPoly::new(&[0, 0, 0, 2, 0, 3, 1, 1].map(Fq::from));
+
+        vec![
+            ProductPoly::new(vec![poly_1, poly_2.clone()]),
+            ProductPoly::new(vec![poly_2.clone(), poly_2]),
+        ]
+    }
+
     #[test]
     fn test_prover() {
         // 2a + 3b
@@ -134,4 +194,79 @@ mod test {
 
         assert!(prover.verify_proof(), "Proof verification failed");
     }
+
+    #[test]
+    fn test_round_count_is_the_number_of_variables() {
+        // shapes where the number of products, multilinears per product and variables all differ
+        let mle = |num_vars: usize, seed: u64| {
+            MultiLinearPoly::new(
+                &(0..1u64 << num_vars)
+                    .map(|i| Fq::from(seed * i + 1))
+                    .collect::<Vec<_>>(),
+            )
+        };
+        let shapes: [(usize, usize, usize); 4] = [(1, 1, 3), (3, 1, 2), (1, 4, 1), (2, 3, 4)];
+
+        for (num_products, degree, num_vars) in shapes {
+            let sum_poly: Vec<ProductPoly<Fq>> = (0..num_products)
+                .map(|p| {
+                    ProductPoly::new(
+                        (0..degree)
+                            .map(|m| mle(num_vars, (p * degree + m) as u64 + 2))
+                            .collect(),
+                    )
+                })
+                .collect();
+
+            let mut prover = ProverStruct::from_product_polys(sum_poly.clone());
+            prover.generate_proof();
+            let proof = prover.get_proof();
+            assert_eq!(proof.sum_polys.len(), num_vars);
+            assert!(proof
+                .sum_polys
+                .iter()
+                .all(|round_poly| round_poly.computation.len() == degree + 1));
+            assert!(prover.verify_proof());
+            assert!(VerifierStruct::from_product_polys(sum_poly.clone()).verify_proof(&proof));
+
+            let claimed_sum = hypercube_values(&sum_poly).iter().sum();
+            let gkr_proof = partial_sum_check::proof(sum_poly, claimed_sum);
+            assert_eq!(gkr_proof.round_polys.len(), num_vars);
+            assert!(partial_sum_check::try_verify(&gkr_proof).is_some());
+        }
+    }
+
+    #[test]
+    fn test_prover_over_products() {
+        let mut prover = ProverStruct::from_product_polys(product_polys());
+        prover.generate_proof();
+
+        // (4*2 + 6*3 + 7*1 + 8*1) + (2*2 + 3*3 + 1*1 + 1*1) = 56
+        assert_eq!(prover.get_proof().claimed_sums[0], Fq::from(56));
+        assert_eq!(prover.get_proof().sum_polys[0].computation.len(), 3);
+        assert!(prover.verify_proof());
+    }
+
+    #[test]
+    fn test_round_polys_match_partial_sum_check() {
+        let mut prover = ProverStruct::from_product_polys(product_polys());
+        prover.generate_proof();
+        let proof = prover.get_proof();
+
+        // the transcripts differ so only the first round (before any challenge) is comparable
+        let gkr_proof = partial_sum_check::proof(product_polys(), Fq::from(56));
+        assert_eq!(proof.sum_polys[0].computation, gkr_proof.round_polys[0]);
+        assert_eq!(proof.sum_polys.len(), gkr_proof.round_polys.len());
+
+        // and every later round agrees with partial_sum_check folded at the same challenges
+        let mut sum_poly = product_polys();
+        for (round, challenge) in proof
+            .sum_polys
+            .iter()
+            .zip(prover.final_state.challenges.iter())
+        {
+            assert_eq!(round.computation, round_poly(&sum_poly));
+            sum_poly = fold_sum_poly(&sum_poly, *challenge);
+        }
+    }
 }
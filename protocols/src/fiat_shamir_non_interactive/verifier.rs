@@ -1,18 +1,32 @@
-use crate::{fiat_shamir_non_interactive::transcript::Transcript, multi_linear::MultiLinearPoly};
+use crate::{
+    fiat_shamir,
+    fiat_shamir_non_interactive::prover::{hypercube_values, statement_transcript, Proof},
+    gkr::product_poly::ProductPoly,
+    iop::sum_check_iop::{SumCheckIOP, SumCheckVerifier},
+    multi_linear::MultiLinearPoly,
+    transcript::TranscriptHasher,
+};
 use ark_ff::PrimeField;
-
-use super::prover::Proof;
+use sha3::Keccak256;
 
 pub struct VerifierStruct<F: PrimeField> {
     pub bh_computation: MultiLinearPoly<F>,
+    pub sum_poly: Vec<ProductPoly<F>>, // oracle access for the final check
     pub challenges: Vec<F>,
     pub final_eval_poly: Vec<F>,
 }
 
 impl<F: PrimeField> VerifierStruct<F> {
     pub fn new(bh_computation: Vec<F>) -> Self {
+        let poly = MultiLinearPoly::new(&bh_computation);
+
+        VerifierStruct::from_product_polys(vec![ProductPoly::new(vec![poly])])
+    }
+
+    pub fn from_product_polys(sum_poly: Vec<ProductPoly<F>>) -> Self {
         VerifierStruct {
-            bh_computation: MultiLinearPoly::new(&bh_computation),
+            bh_computation: MultiLinearPoly::new(&hypercube_values(&sum_poly)),
+            sum_poly,
             challenges: Vec::new(),
             final_eval_poly: Vec::with_capacity(1),
         }
@@ -22,60 +36,60 @@ impl<F: PrimeField> VerifierStruct<F> {
         MultiLinearPoly::to_bytes(&computation)
     }
 
-    fn check_proof(&mut self, proof: Proof<F>) {
-        let mut transcript = Transcript::new();
-        transcript.append(&VerifierStruct::convert_to_bytes(
-            self.bh_computation.computation.clone(),
-        ));
-
-        // get the claimed_sums and sum_polys from the proof
-        let claimed_sums = proof.claimed_sums;
-        let sum_polys = proof.sum_polys;
-
-        // add the sum_polys at an index to give the claimed_sum at that index
-        for i in 0..sum_polys.len() {
-            let sum_poly_i = &sum_polys[i];
-            if sum_poly_i.computation.iter().sum::<F>() != claimed_sums[i] {
-                return;
-            }
-
-            transcript.append(&VerifierStruct::convert_to_bytes(vec![claimed_sums[i]]));
-            transcript.append(&VerifierStruct::convert_to_bytes(
-                sum_poly_i.computation.clone(),
-            ));
-            let challenge_bytes = transcript.challenge();
-            let challenge = F::from_be_bytes_mod_order(&challenge_bytes);
-            self.challenges.push(challenge);
-
-            self.final_eval_poly = sum_poly_i.computation.clone();
-        }
+    // the rounds are checked by fiat_shamir::verify over the sum-check IOP
+    // claimed_sums only repeat g(0) + g(1) of each round so they must agree with the round polys
+    pub fn verify_proof(&mut self, proof: &Proof<F>) -> bool {
+        self.verify_proof_with_hasher::<Keccak256>(proof)
     }
 
-    // perform a final check to see if the final_eval_poly is equal to the final_eval_poly from the prover
-    /*
-       Note that for the final evaluation, the verifier will not send the last challenge point to the prover
-       He would instead use the last challenge to evaluate the final polynomial
-    */
-    pub fn verify_proof(&mut self, proof: Proof<F>) -> bool {
-        self.check_proof(proof);
-
-        // convert the final_eval_poly to a univariate polynomial and evaluate it at the first challenge
-        let mut final_eval: MultiLinearPoly<F> = MultiLinearPoly::new(&self.final_eval_poly);
-        let final_eval_at_challenge =
-            final_eval.partial_evaluate(self.challenges[self.challenges.len() - 1], 0);
-
-        let mut this_computation = self.bh_computation.clone();
-        let full_evaluation = MultiLinearPoly::new(&vec![
-            this_computation.evaluate(&self.challenges).computation[0],
-        ]);
-
-        final_eval_at_challenge == full_evaluation
+    pub fn verify_proof_with_hasher<H: TranscriptHasher>(&mut self, proof: &Proof<F>) -> bool {
+        let num_vars = self.bh_computation.computation.len().ilog2() as usize;
+        if proof.sum_polys.len() != num_vars || proof.claimed_sums.len() != num_vars {
+            return false;
+        }
+
+        let round_polys: Vec<Vec<F>> = proof
+            .sum_polys
+            .iter()
+            .map(|sum_poly| sum_poly.computation.clone())
+            .collect();
+        let consistent =
+            proof
+                .claimed_sums
+                .iter()
+                .zip(round_polys.iter())
+                .all(|(claimed_sum, round_poly)| {
+                    round_poly.len() >= 2 && *claimed_sum == round_poly[0] + round_poly[1]
+                });
+        let claimed_sum = match proof.claimed_sums.first() {
+            Some(claimed_sum) if consistent => *claimed_sum,
+            _ => return false,
+        };
+
+        let mut transcript =
+            statement_transcript::<F, H>(&self.bh_computation.computation, claimed_sum);
+        let mut verifier = SumCheckVerifier::new(self.sum_poly.clone(), claimed_sum);
+        let result =
+            fiat_shamir::verify::<F, SumCheckIOP, _>(&mut verifier, &round_polys, &mut transcript);
+
+        self.challenges = verifier.challenges;
+        self.final_eval_poly = round_polys.last().cloned().unwrap_or_default();
+
+        result
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "bn254"))]
 mod test {
-    use crate::fiat_shamir_non_interactive::{prover::ProverStruct, verifier::VerifierStruct};
+    use crate::{
+        fiat_shamir_non_interactive::{prover::ProverStruct, verifier::VerifierStruct},
+        gkr::{
+            partial_sum_check::{self, verify_round},
+            product_poly::ProductPoly,
+        },
+        lagrange::LagrangeWeights,
+        multi_linear::MultiLinearPoly,
+    };
     use ark_bn254::Fq;
 
     fn bh_computation() -> Vec<Fq> {
@@ -108,7 +122,57 @@ mod test {
         let _ = prover.generate_proof();
         let proof = prover.get_proof();
 
-        let verify = verifier.verify_proof(proof);
-        assert_eq!(verify, true);
+        let verify = verifier.verify_proof(&proof);
+        assert!(verify);
+    }
+
+    fn product_polys() -> Vec<ProductPoly<Fq>> {
+        let poly_1 = MultiLinearPoly::new(&bh_computation());
+        let poly_2 =
+            MultiLinearPoly::new(&bh_computation().iter().rev().copied().collect::<Vec<_>>());
+
+        vec![ProductPoly::new(vec![poly_1, poly_2])]
+    }
+
+    #[test]
+    fn test_verify_product_proof() {
+        let mut prover = ProverStruct::from_product_polys(product_polys());
+        prover.generate_proof();
+
+        let mut verifier = VerifierStruct::from_product_polys(product_polys());
+        assert!(verifier.verify_proof(&prover.get_proof()));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_proof() {
+        let mut prover = ProverStruct::from_product_polys(product_polys());
+        prover.generate_proof();
+
+        let mut proof = prover.get_proof();
+        proof.claimed_sums[0] += Fq::from(1);
+        assert!(!VerifierStruct::from_product_polys(product_polys()).verify_proof(&proof));
+
+        // a round poly that still sums to the claim but is wrong elsewhere is caught later
+        let mut proof = prover.get_proof();
+        proof.sum_polys[1].computation[2] += Fq::from(1);
+        assert!(!VerifierStruct::from_product_polys(product_polys()).verify_proof(&proof));
+    }
+
+    #[test]
+    fn test_verify_partial_sum_check_rounds() {
+        // rounds produced by the GKR sum-check prover are accepted round by round here too
+        let gkr_proof = partial_sum_check::proof(product_polys(), Fq::from(0));
+        let mut prover = ProverStruct::from_product_polys(product_polys());
+        prover.generate_proof();
+
+        let claimed_sum = prover.get_proof().claimed_sums[0];
+        let weights = LagrangeWeights::for_degree(2);
+        assert!(verify_round(
+            claimed_sum,
+            &gkr_proof.round_polys[0],
+            &weights,
+            Fq::from(7)
+        )
+        .is_some());
     }
 }
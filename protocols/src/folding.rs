@@ -0,0 +1,4 @@
+pub mod nova;
+pub mod pedersen;
+pub mod r1cs;
+pub mod step_batch;
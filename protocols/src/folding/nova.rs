@@ -0,0 +1,278 @@
+use ark_ec::{pairing::Pairing, PrimeGroup};
+use ark_ff::{PrimeField, Zero};
+
+use crate::{
+    folding::{pedersen::PedersenKey, r1cs::R1CS},
+    multi_linear::MultiLinearPoly,
+    transcript::{Transcript, TranscriptDomain},
+};
+
+//=========================================================================================
+// Nova folding of relaxed R1CS instances
+// instance U = (com(W), com(E), u, x), witness (W, E) with (A·z) ∘ (B·z) = u * (C·z) + E, z = (u, x, W)
+// folding z = z₁ + r * z₂ leaves the cross term
+//   T = (A·z₁) ∘ (B·z₂) + (A·z₂) ∘ (B·z₁) - u₁ * (C·z₂) - u₂ * (C·z₁)
+// so with E = E₁ + r * T + r² * E₂ and u = u₁ + r * u₂ the folded pair is satisfied iff both were
+// the prover commits T before r is drawn, the verifier folds the instances from commitments alone:
+//   com(W) = com(W₁) + r * com(W₂), com(E) = com(E₁) + r * com(T) + r² * com(E₂)
+// a sum-check claim is folded the same way once it is arithmetized as R1CS
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelaxedInstance<P: Pairing> {
+    pub comm_w: P::G1,
+    pub comm_e: P::G1,
+    pub u: P::ScalarField,
+    pub x: Vec<P::ScalarField>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelaxedWitness<F: PrimeField> {
+    pub w: Vec<F>,
+    pub e: Vec<F>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoldingProof<P: Pairing> {
+    pub comm_t: P::G1, // commitment to the cross term
+}
+
+impl<P: Pairing> RelaxedInstance<P> {
+    // a plain R1CS instance is relaxed with u = 1 and E = 0
+    pub fn from_r1cs(key: &PedersenKey<P>, x: &[P::ScalarField], w: &[P::ScalarField]) -> Self {
+        RelaxedInstance {
+            comm_w: key.commit(w),
+            comm_e: P::G1::zero(),
+            u: P::ScalarField::from(1u64),
+            x: x.to_vec(),
+        }
+    }
+
+    pub fn absorb_into(&self, transcript: &mut Transcript) {
+        transcript.absorb_commitment(&self.comm_w);
+        transcript.absorb_commitment(&self.comm_e);
+        transcript.absorb(&MultiLinearPoly::to_bytes(&[self.u]));
+        transcript.absorb(&MultiLinearPoly::to_bytes(&self.x));
+    }
+}
+
+impl<F: PrimeField> RelaxedWitness<F> {
+    pub fn from_r1cs(r1cs: &R1CS<F>, w: &[F]) -> Self {
+        RelaxedWitness {
+            w: w.to_vec(),
+            e: vec![F::zero(); r1cs.num_constraints()],
+        }
+    }
+}
+
+// the witness matches the commitments and satisfies the relaxed relation
+pub fn is_satisfied<P: Pairing>(
+    key: &PedersenKey<P>,
+    r1cs: &R1CS<P::ScalarField>,
+    instance: &RelaxedInstance<P>,
+    witness: &RelaxedWitness<P::ScalarField>,
+) -> bool {
+    r1cs.is_relaxed_satisfied(instance.u, &instance.x, &witness.w, &witness.e)
+        && key.commit(&witness.w) == instance.comm_w
+        && key.commit(&witness.e) == instance.comm_e
+}
+
+pub fn cross_term<P: Pairing>(
+    r1cs: &R1CS<P::ScalarField>,
+    instance_1: &RelaxedInstance<P>,
+    witness_1: &RelaxedWitness<P::ScalarField>,
+    instance_2: &RelaxedInstance<P>,
+    witness_2: &RelaxedWitness<P::ScalarField>,
+) -> Vec<P::ScalarField> {
+    let (az_1, bz_1, cz_1) = r1cs.products(&r1cs.z(instance_1.u, &instance_1.x, &witness_1.w));
+    let (az_2, bz_2, cz_2) = r1cs.products(&r1cs.z(instance_2.u, &instance_2.x, &witness_2.w));
+
+    (0..r1cs.num_constraints())
+        .map(|i| {
+            az_1[i] * bz_2[i] + az_2[i] * bz_1[i] - instance_1.u * cz_2[i] - instance_2.u * cz_1[i]
+        })
+        .collect()
+}
+
+pub fn fold_prove<P: Pairing>(
+    key: &PedersenKey<P>,
+    r1cs: &R1CS<P::ScalarField>,
+    (instance_1, witness_1): (&RelaxedInstance<P>, &RelaxedWitness<P::ScalarField>),
+    (instance_2, witness_2): (&RelaxedInstance<P>, &RelaxedWitness<P::ScalarField>),
+    transcript_domain: &TranscriptDomain,
+) -> (
+    RelaxedInstance<P>,
+    RelaxedWitness<P::ScalarField>,
+    FoldingProof<P>,
+) {
+    let t = cross_term(r1cs, instance_1, witness_1, instance_2, witness_2);
+    let proof = FoldingProof {
+        comm_t: key.commit(&t),
+    };
+
+    let r = fold_challenge(instance_1, instance_2, &proof, transcript_domain);
+    let instance = fold_instances(instance_1, instance_2, &proof, r);
+
+    let r_squared = r * r;
+    let witness = RelaxedWitness {
+        w: witness_1
+            .w
+            .iter()
+            .zip(witness_2.w.iter())
+            .map(|(w_1, w_2)| *w_1 + r * w_2)
+            .collect(),
+        e: (0..r1cs.num_constraints())
+            .map(|i| witness_1.e[i] + r * t[i] + r_squared * witness_2.e[i])
+            .collect(),
+    };
+
+    (instance, witness, proof)
+}
+
+// the verifier's side only touches the instances, the cost does not depend on the witness size
+pub fn fold_verify<P: Pairing>(
+    instance_1: &RelaxedInstance<P>,
+    instance_2: &RelaxedInstance<P>,
+    proof: &FoldingProof<P>,
+    transcript_domain: &TranscriptDomain,
+) -> Option<RelaxedInstance<P>> {
+    if instance_1.x.len() != instance_2.x.len() {
+        return None;
+    }
+
+    let r = fold_challenge(instance_1, instance_2, proof, transcript_domain);
+
+    Some(fold_instances(instance_1, instance_2, proof, r))
+}
+
+fn fold_challenge<P: Pairing>(
+    instance_1: &RelaxedInstance<P>,
+    instance_2: &RelaxedInstance<P>,
+    proof: &FoldingProof<P>,
+    transcript_domain: &TranscriptDomain,
+) -> P::ScalarField {
+    let mut transcript = transcript_domain.transcript();
+    instance_1.absorb_into(&mut transcript);
+    instance_2.absorb_into(&mut transcript);
+    transcript.absorb_commitment(&proof.comm_t);
+
+    transcript.squeeze_field::<P::ScalarField>()
+}
+
+fn fold_instances<P: Pairing>(
+    instance_1: &RelaxedInstance<P>,
+    instance_2: &RelaxedInstance<P>,
+    proof: &FoldingProof<P>,
+    r: P::ScalarField,
+) -> RelaxedInstance<P> {
+    let r_squared = r * r;
+
+    RelaxedInstance {
+        comm_w: instance_1.comm_w + instance_2.comm_w.mul_bigint(r.into_bigint()),
+        comm_e: instance_1.comm_e
+            + proof.comm_t.mul_bigint(r.into_bigint())
+            + instance_2.comm_e.mul_bigint(r_squared.into_bigint()),
+        u: instance_1.u + r * instance_2.u,
+        x: instance_1
+            .x
+            .iter()
+            .zip(instance_2.x.iter())
+            .map(|(x_1, x_2)| *x_1 + r * x_2)
+            .collect(),
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{
+        folding::r1cs::test::{cubic_assignment, cubic_r1cs},
+        transcript::test::domain,
+    };
+    use ark_bn254::{Bn254, Fr};
+
+    fn relaxed_pair(
+        key: &PedersenKey<Bn254>,
+        r1cs: &R1CS<Fr>,
+        input: u64,
+    ) -> (RelaxedInstance<Bn254>, RelaxedWitness<Fr>) {
+        let (x, w) = cubic_assignment(input);
+
+        (
+            RelaxedInstance::from_r1cs(key, &x, &w),
+            RelaxedWitness::from_r1cs(r1cs, &w),
+        )
+    }
+
+    #[test]
+    fn test_fold_two_instances() {
+        let r1cs = cubic_r1cs();
+        let key = PedersenKey::<Bn254>::setup(r1cs.num_constraints(), &mut rand::thread_rng());
+
+        let (instance_1, witness_1) = relaxed_pair(&key, &r1cs, 3);
+        let (instance_2, witness_2) = relaxed_pair(&key, &r1cs, 2);
+        assert!(is_satisfied(&key, &r1cs, &instance_1, &witness_1));
+
+        let (instance, witness, proof) = fold_prove(
+            &key,
+            &r1cs,
+            (&instance_1, &witness_1),
+            (&instance_2, &witness_2),
+            &domain(),
+        );
+        assert!(is_satisfied(&key, &r1cs, &instance, &witness));
+        assert_eq!(
+            fold_verify(&instance_1, &instance_2, &proof, &domain()),
+            Some(instance.clone())
+        );
+
+        // folding the running instance again keeps it satisfied
+        let (instance_3, witness_3) = relaxed_pair(&key, &r1cs, 5);
+        let (instance, witness, _) = fold_prove(
+            &key,
+            &r1cs,
+            (&instance, &witness),
+            (&instance_3, &witness_3),
+            &domain(),
+        );
+        assert!(is_satisfied(&key, &r1cs, &instance, &witness));
+    }
+
+    #[test]
+    fn test_fold_rejects_unsatisfied_instance() {
+        let r1cs = cubic_r1cs();
+        let key = PedersenKey::<Bn254>::setup(r1cs.num_constraints(), &mut rand::thread_rng());
+
+        let (instance_1, witness_1) = relaxed_pair(&key, &r1cs, 3);
+        let (mut instance_2, witness_2) = relaxed_pair(&key, &r1cs, 2);
+        instance_2.x[0] += Fr::from(1); // claims x³ + x + 5 = 16 for x = 2
+
+        let (instance, witness, _) = fold_prove(
+            &key,
+            &r1cs,
+            (&instance_1, &witness_1),
+            (&instance_2, &witness_2),
+            &domain(),
+        );
+        assert!(!is_satisfied(&key, &r1cs, &instance, &witness));
+    }
+
+    #[test]
+    fn test_fold_verify_rejects_tampered_cross_term() {
+        let r1cs = cubic_r1cs();
+        let key = PedersenKey::<Bn254>::setup(r1cs.num_constraints(), &mut rand::thread_rng());
+
+        let (instance_1, witness_1) = relaxed_pair(&key, &r1cs, 3);
+        let (instance_2, witness_2) = relaxed_pair(&key, &r1cs, 2);
+        let (_, witness, mut proof) = fold_prove(
+            &key,
+            &r1cs,
+            (&instance_1, &witness_1),
+            (&instance_2, &witness_2),
+            &domain(),
+        );
+
+        proof.comm_t += key.generators[0];
+        let instance = fold_verify(&instance_1, &instance_2, &proof, &domain()).unwrap();
+        assert!(!is_satisfied(&key, &r1cs, &instance, &witness));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::hash::{
+    generators::{derive_generators, GeneratorSetId},
+    hash_to_curve::HashToCurveGroup,
+};
+use ark_ec::{pairing::Pairing, PrimeGroup};
+use ark_ff::{PrimeField, Zero};
+#[cfg(feature = "prover")]
+use {ark_ff::UniformRand, rand::Rng};
+
+//=========================================================================================
+// Pedersen vector commitment over G1: com(v) = Σ vᵢ * Gᵢ
+// binding as long as nobody knows a relation between the generators, so they are sampled at random
+// (setup) or hashed to the curve from a domain string (derive, see hash::generators)
+// additively homomorphic: com(v₁) + r * com(v₂) = com(v₁ + r * v₂), which is all folding needs
+// not hiding, there is no blinding generator
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct PedersenKey<P: Pairing> {
+    pub generators: Vec<P::G1>,
+    pub id: GeneratorSetId,
+}
+
+impl<P: Pairing> PedersenKey<P> {
+    pub fn new(generators: Vec<P::G1>) -> Self {
+        let id = GeneratorSetId::of(&generators);
+
+        PedersenKey { generators, id }
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn setup<R: Rng>(max_len: usize, rng: &mut R) -> Self {
+        PedersenKey::new(
+            (0..max_len)
+                .map(|_| P::G1::generator().mul_bigint(P::ScalarField::rand(rng).into_bigint()))
+                .collect(),
+        )
+    }
+
+    pub fn derive(domain: &[u8], max_len: usize) -> Self
+    where
+        P::G1: HashToCurveGroup,
+    {
+        PedersenKey::new(derive_generators(domain, "g", max_len))
+    }
+
+    pub fn commit(&self, values: &[P::ScalarField]) -> P::G1 {
+        if values.len() > self.generators.len() {
+            panic!("The vector is longer than the commitment key");
+        }
+
+        values
+            .iter()
+            .zip(self.generators.iter())
+            .fold(P::G1::zero(), |acc, (value, generator)| {
+                acc + generator.mul_bigint(value.into_bigint())
+            })
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::{Bn254, Fr};
+
+    #[test]
+    fn test_pedersen_is_homomorphic() {
+        let key = PedersenKey::<Bn254>::setup(3, &mut rand::thread_rng());
+        let v_1 = [1, 2, 3].map(Fr::from);
+        let v_2 = [4, 5, 6].map(Fr::from);
+        let r = Fr::from(7);
+
+        let combined: Vec<Fr> = v_1
+            .iter()
+            .zip(v_2.iter())
+            .map(|(a, b)| *a + r * b)
+            .collect();
+        assert_eq!(
+            key.commit(&v_1) + key.commit(&v_2).mul_bigint(r.into_bigint()),
+            key.commit(&combined)
+        );
+        assert_ne!(key.commit(&v_1), key.commit(&v_2));
+    }
+
+    #[test]
+    fn test_derived_key() {
+        let key = PedersenKey::<Bn254>::derive(b"pedersen-test", 3);
+        assert_eq!(key, PedersenKey::derive(b"pedersen-test", 3));
+        assert_eq!(
+            key.generators[..2],
+            PedersenKey::<Bn254>::derive(b"pedersen-test", 2).generators
+        );
+        assert_ne!(key.id, PedersenKey::<Bn254>::derive(b"pedersen-test", 2).id);
+        assert_ne!(key.id, PedersenKey::<Bn254>::derive(b"other", 3).id);
+    }
+}
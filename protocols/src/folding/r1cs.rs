@@ -0,0 +1,142 @@
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// Rank-1 constraint system: (A·z) ∘ (B·z) = C·z
+// z = (u, x, w) where u is the constant slot (1 for a plain instance), x the public inputs
+// and w the witness, the matrices are stored row by row as (column, value) pairs
+// the relaxed form (A·z) ∘ (B·z) = u * (C·z) + E is what folding keeps closed under random combinations
+//=========================================================================================
+pub type SparseMatrix<F> = Vec<Vec<(usize, F)>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct R1CS<F: PrimeField> {
+    pub a: SparseMatrix<F>,
+    pub b: SparseMatrix<F>,
+    pub c: SparseMatrix<F>,
+    pub num_public: usize,
+    pub num_witness: usize,
+}
+
+impl<F: PrimeField> R1CS<F> {
+    pub fn new(
+        a: SparseMatrix<F>,
+        b: SparseMatrix<F>,
+        c: SparseMatrix<F>,
+        num_public: usize,
+        num_witness: usize,
+    ) -> Self {
+        if a.len() != b.len() || b.len() != c.len() {
+            panic!("A, B and C must have one row per constraint");
+        }
+        let z_len = 1 + num_public + num_witness;
+        if [&a, &b, &c]
+            .iter()
+            .any(|matrix| matrix.iter().flatten().any(|(col, _)| *col >= z_len))
+        {
+            panic!("A constraint refers to a variable outside z");
+        }
+
+        R1CS {
+            a,
+            b,
+            c,
+            num_public,
+            num_witness,
+        }
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.a.len()
+    }
+
+    // z = (u, x, w)
+    pub fn z(&self, u: F, x: &[F], w: &[F]) -> Vec<F> {
+        if x.len() != self.num_public || w.len() != self.num_witness {
+            panic!("Wrong number of public inputs or witness values");
+        }
+
+        let mut z = Vec::with_capacity(1 + x.len() + w.len());
+        z.push(u);
+        z.extend_from_slice(x);
+        z.extend_from_slice(w);
+
+        z
+    }
+
+    // (A·z, B·z, C·z)
+    pub fn products(&self, z: &[F]) -> (Vec<F>, Vec<F>, Vec<F>) {
+        (
+            mul_vec(&self.a, z),
+            mul_vec(&self.b, z),
+            mul_vec(&self.c, z),
+        )
+    }
+
+    // (A·z) ∘ (B·z) = u * (C·z) + E
+    pub fn is_relaxed_satisfied(&self, u: F, x: &[F], w: &[F], e: &[F]) -> bool {
+        if x.len() != self.num_public
+            || w.len() != self.num_witness
+            || e.len() != self.num_constraints()
+        {
+            return false;
+        }
+
+        let (az, bz, cz) = self.products(&self.z(u, x, w));
+
+        (0..self.num_constraints()).all(|i| az[i] * bz[i] == u * cz[i] + e[i])
+    }
+
+    pub fn is_satisfied(&self, x: &[F], w: &[F]) -> bool {
+        self.is_relaxed_satisfied(F::one(), x, w, &vec![F::zero(); self.num_constraints()])
+    }
+}
+
+pub fn mul_vec<F: PrimeField>(matrix: &SparseMatrix<F>, z: &[F]) -> Vec<F> {
+    matrix
+        .iter()
+        .map(|row| row.iter().map(|(col, value)| *value * z[*col]).sum())
+        .collect()
+}
+
+#[cfg(all(test, feature = "bn254"))]
+pub mod test {
+    use super::*;
+    use ark_bn254::Fr;
+
+    // x³ + x + 5 = y with z = (u, y, x, x², x³)
+    //   x  * x = x²
+    //   x² * x = x³
+    //   (x³ + x + 5u) * u = y
+    pub fn cubic_r1cs() -> R1CS<Fr> {
+        let one = Fr::from(1);
+
+        R1CS::new(
+            vec![
+                vec![(2, one)],
+                vec![(3, one)],
+                vec![(4, one), (2, one), (0, Fr::from(5))],
+            ],
+            vec![vec![(2, one)], vec![(2, one)], vec![(0, one)]],
+            vec![vec![(3, one)], vec![(4, one)], vec![(1, one)]],
+            1,
+            3,
+        )
+    }
+
+    // (x = [y], w = [x, x², x³])
+    pub fn cubic_assignment(x: u64) -> (Vec<Fr>, Vec<Fr>) {
+        let x = Fr::from(x);
+
+        (vec![x * x * x + x + Fr::from(5)], vec![x, x * x, x * x * x])
+    }
+
+    #[test]
+    fn test_r1cs_satisfied() {
+        let r1cs = cubic_r1cs();
+        let (x, w) = cubic_assignment(3);
+
+        assert_eq!(x, vec![Fr::from(35)]);
+        assert!(r1cs.is_satisfied(&x, &w));
+        assert!(!r1cs.is_satisfied(&[Fr::from(36)], &w));
+    }
+}
@@ -0,0 +1,267 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::{PrimeField, Zero};
+
+use crate::{
+    folding::{
+        nova::{
+            fold_prove, fold_verify, is_satisfied, FoldingProof, RelaxedInstance, RelaxedWitness,
+        },
+        pedersen::PedersenKey,
+        r1cs::R1CS,
+    },
+    hash::generators::GeneratorSetId,
+    transcript::TranscriptDomain,
+};
+
+//=========================================================================================
+// Folding-based batch verification of repeated step circuit applications
+// z₀ -> F(z₀) = z₁ -> ... -> zₙ with every step folded into one running instance, a step circuit
+// is an R1CS whose public inputs are x = (zᵢ, zᵢ₊₁) so consecutive steps chain through them
+// the verifier reads every step instance (commitments and states, no witnesses), checks the chain
+// and redoes the folds, then decides the running instance with a single relaxed R1CS check
+// that is O(steps) group operations instead of O(steps) R1CS checks, and NOT IVC: nothing folds
+// inside the step circuit (no augmented circuit), so the proof grows with the number of steps
+//=========================================================================================
+pub trait StepCircuit<F: PrimeField> {
+    fn state_len(&self) -> usize;
+
+    // x = (zᵢ, zᵢ₊₁), w is whatever else the step needs
+    fn r1cs(&self) -> R1CS<F>;
+
+    // (zᵢ₊₁, w)
+    fn step(&self, state: &[F]) -> (Vec<F>, Vec<F>);
+}
+
+#[derive(Debug, Clone)]
+pub struct StepBatchProof<P: Pairing> {
+    pub generators: GeneratorSetId, // of the Pedersen key behind every commitment
+    pub final_state: Vec<P::ScalarField>,
+    pub step_instances: Vec<RelaxedInstance<P>>,
+    pub fold_proofs: Vec<FoldingProof<P>>, // one per step after the first
+    pub accumulator: RelaxedInstance<P>,
+    pub accumulator_witness: RelaxedWitness<P::ScalarField>,
+}
+
+pub fn prove_steps<P: Pairing, C: StepCircuit<P::ScalarField>>(
+    key: &PedersenKey<P>,
+    step_circuit: &C,
+    initial_state: &[P::ScalarField],
+    steps: usize,
+    transcript_domain: &TranscriptDomain,
+) -> StepBatchProof<P> {
+    if steps == 0 {
+        panic!("The batch needs at least one step");
+    }
+    if initial_state.len() != step_circuit.state_len() {
+        panic!("The initial state does not match the step circuit");
+    }
+
+    let r1cs = step_circuit.r1cs();
+    let mut state = initial_state.to_vec();
+    let mut step_instances = Vec::new();
+    let mut fold_proofs = Vec::new();
+    let mut running: Option<(RelaxedInstance<P>, RelaxedWitness<P::ScalarField>)> = None;
+
+    for _ in 0..steps {
+        let (next_state, w) = step_circuit.step(&state);
+        let x = [state.as_slice(), next_state.as_slice()].concat();
+        let instance = RelaxedInstance::from_r1cs(key, &x, &w);
+        let witness = RelaxedWitness::from_r1cs(&r1cs, &w);
+
+        running = Some(match running {
+            None => (instance.clone(), witness),
+            Some((acc_instance, acc_witness)) => {
+                let (folded_instance, folded_witness, proof) = fold_prove(
+                    key,
+                    &r1cs,
+                    (&acc_instance, &acc_witness),
+                    (&instance, &witness),
+                    transcript_domain,
+                );
+                fold_proofs.push(proof);
+
+                (folded_instance, folded_witness)
+            }
+        });
+        step_instances.push(instance);
+        state = next_state;
+    }
+
+    let (accumulator, accumulator_witness) = running.unwrap();
+
+    StepBatchProof {
+        generators: key.id,
+        final_state: state,
+        step_instances,
+        fold_proofs,
+        accumulator,
+        accumulator_witness,
+    }
+}
+
+// true if applying the step circuit `steps` times to initial_state gives proof.final_state
+pub fn verify_steps<P: Pairing, C: StepCircuit<P::ScalarField>>(
+    key: &PedersenKey<P>,
+    step_circuit: &C,
+    initial_state: &[P::ScalarField],
+    steps: usize,
+    proof: &StepBatchProof<P>,
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    let state_len = step_circuit.state_len();
+    if steps == 0
+        || proof.generators != key.id
+        || proof.step_instances.len() != steps
+        || proof.fold_proofs.len() != steps - 1
+        || proof.final_state.len() != state_len
+    {
+        return false;
+    }
+
+    // every step is a fresh instance (u = 1, E = 0) whose input state is the last output state
+    let one = P::ScalarField::from(1u64);
+    let mut state = initial_state;
+    for instance in proof.step_instances.iter() {
+        if instance.u != one
+            || !instance.comm_e.is_zero()
+            || instance.x.len() != 2 * state_len
+            || instance.x[..state_len] != *state
+        {
+            return false;
+        }
+        state = &instance.x[state_len..];
+    }
+    if state != proof.final_state {
+        return false;
+    }
+
+    let mut accumulator = proof.step_instances[0].clone();
+    for (instance, fold_proof) in proof.step_instances[1..]
+        .iter()
+        .zip(proof.fold_proofs.iter())
+    {
+        accumulator = match fold_verify(&accumulator, instance, fold_proof, transcript_domain) {
+            Some(folded) => folded,
+            None => return false,
+        };
+    }
+
+    // decider
+    accumulator == proof.accumulator
+        && is_satisfied(
+            key,
+            &step_circuit.r1cs(),
+            &proof.accumulator,
+            &proof.accumulator_witness,
+        )
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::transcript::test::domain;
+    use ark_bn254::{Bn254, Fr};
+
+    // zᵢ₊₁ = zᵢ² + 1 with z = (u, zᵢ, zᵢ₊₁, t)
+    //   zᵢ * zᵢ = t
+    //   (t + u) * u = zᵢ₊₁
+    struct SquarePlusOne;
+
+    impl StepCircuit<Fr> for SquarePlusOne {
+        fn state_len(&self) -> usize {
+            1
+        }
+
+        fn r1cs(&self) -> R1CS<Fr> {
+            let one = Fr::from(1);
+
+            R1CS::new(
+                vec![vec![(1, one)], vec![(3, one), (0, one)]],
+                vec![vec![(1, one)], vec![(0, one)]],
+                vec![vec![(3, one)], vec![(2, one)]],
+                2,
+                1,
+            )
+        }
+
+        fn step(&self, state: &[Fr]) -> (Vec<Fr>, Vec<Fr>) {
+            let t = state[0] * state[0];
+
+            (vec![t + Fr::from(1)], vec![t])
+        }
+    }
+
+    #[test]
+    fn test_step_batch_verifies() {
+        let key = PedersenKey::<Bn254>::setup(2, &mut rand::thread_rng());
+        let initial_state = [Fr::from(1)];
+
+        let proof = prove_steps(&key, &SquarePlusOne, &initial_state, 4, &domain());
+        // 1 -> 2 -> 5 -> 26 -> 677
+        assert_eq!(proof.final_state, vec![Fr::from(677)]);
+        assert!(verify_steps(
+            &key,
+            &SquarePlusOne,
+            &initial_state,
+            4,
+            &proof,
+            &domain()
+        ));
+
+        assert!(!verify_steps(
+            &key,
+            &SquarePlusOne,
+            &initial_state,
+            3,
+            &proof,
+            &domain()
+        ));
+        assert!(!verify_steps(
+            &key,
+            &SquarePlusOne,
+            &[Fr::from(2)],
+            4,
+            &proof,
+            &domain()
+        ));
+        assert!(!verify_steps(
+            &PedersenKey::derive(b"step-batch-other", 2),
+            &SquarePlusOne,
+            &initial_state,
+            4,
+            &proof,
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_step_batch_rejects_wrong_final_state() {
+        let key = PedersenKey::<Bn254>::setup(2, &mut rand::thread_rng());
+        let initial_state = [Fr::from(1)];
+
+        let mut proof = prove_steps(&key, &SquarePlusOne, &initial_state, 3, &domain());
+        proof.final_state[0] += Fr::from(1);
+        assert!(!verify_steps(
+            &key,
+            &SquarePlusOne,
+            &initial_state,
+            3,
+            &proof,
+            &domain()
+        ));
+
+        // a lying step shows up in the decider even when the states chain
+        let mut proof = prove_steps(&key, &SquarePlusOne, &initial_state, 3, &domain());
+        let last = proof.step_instances.len() - 1;
+        proof.step_instances[last].x[1] += Fr::from(1);
+        proof.final_state[0] += Fr::from(1);
+        assert!(!verify_steps(
+            &key,
+            &SquarePlusOne,
+            &initial_state,
+            3,
+            &proof,
+            &domain()
+        ));
+    }
+}
@@ -1,5 +1,15 @@
-pub mod fft;
 pub mod fri_helper_functions;
 pub mod fri_protocol;
-pub mod merkle_tree;
+pub mod low_degree;
 pub mod optimized_fri_protocol;
+
+// the FFT, LDE and Merkle tree FRI runs on are zk-primitives'
+#[cfg(feature = "prover")]
+pub use zk_primitives::lde;
+pub use zk_primitives::{domain_cache, fft, merkle_tree};
+
+#[cfg(feature = "prover")]
+pub use lde::lde;
+#[cfg(feature = "prover")]
+pub use low_degree::prove_low_degree;
+pub use low_degree::verify_low_degree;
@@ -25,6 +25,20 @@ impl<F: FftField> FRIProtocol<F> {
 
         size
     }
+
+    // one fold per halving of the domain, fixed by max_degree and the blowup factor alone
+    pub fn num_rounds(&self) -> usize {
+        self.domain_size().ilog2() as usize
+    }
+
+    // every per round vector of a proof has num_rounds entries and the claimed sums one less,
+    // the verifier checks this before indexing into anything the prover sent
+    pub fn has_round_lengths(&self, per_round: &[usize], claimed_sums: usize) -> bool {
+        let num_rounds = self.num_rounds();
+        num_rounds > 0
+            && per_round.iter().all(|len| *len == num_rounds)
+            && claimed_sums == num_rounds - 1
+    }
 }
 
 pub fn fold_poly<F: FftField>(poly: &[F], r_challenge: F) -> Vec<F> {
@@ -68,7 +82,7 @@ pub fn pad_poly_to_power_of_two<F: FftField>(poly: &[F]) -> Vec<F> {
     padded_poly
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "bn254"))]
 mod test {
     use super::*;
     use crate::fri::fri_protocol::FRIProtocol;
@@ -1,10 +1,14 @@
 use ark_ff::{FftField, PrimeField};
 
+#[cfg(feature = "prover")]
+use crate::fri::{
+    domain_cache::DomainCache,
+    fft::FastFourierTransform,
+    fri_helper_functions::{fold_poly, pad_poly_to_power_of_two},
+};
 use crate::{
-    fri::fft::FastFourierTransform,
-    fri::fri_helper_functions::{fold_poly, pad_poly_to_power_of_two},
     fri::merkle_tree::{MerkleProof, MerkleTree},
-    transcript::Transcript,
+    transcript::{is_digest, TranscriptDomain},
 };
 
 pub struct FRIProtocol<F: FftField> {
@@ -14,6 +18,7 @@ pub struct FRIProtocol<F: FftField> {
 }
 
 pub struct FRIProof<F: FftField> {
+    pub max_degree: usize, // the degree bound the proof was made for
     pub root_hashes: Vec<Vec<u8>>,
     pub final_poly: Vec<F>,
     pub values_at_index: Vec<F>,
@@ -27,6 +32,21 @@ pub struct FRIProof<F: FftField> {
 impl<F: FftField + PrimeField> FRIProtocol<F> {
     pub fn new(poly: Vec<F>, blowup_factor: usize) -> Self {
         let max_degree = poly.len() - 1;
+        Self::with_max_degree(poly, blowup_factor, max_degree)
+    }
+
+    // a declared degree bound instead of the one read off the length, the verifier derives the
+    // number of folding rounds from it so both sides have to agree on it up front
+    // the prover's poly must really be below it, padding would otherwise cut off its top terms
+    pub fn with_max_degree(poly: Vec<F>, blowup_factor: usize, max_degree: usize) -> Self {
+        if poly
+            .iter()
+            .skip(max_degree + 1)
+            .any(|coeff| !coeff.is_zero())
+        {
+            panic!("The polynomial has a degree above the declared max degree");
+        }
+
         FRIProtocol {
             poly,
             blowup_factor,
@@ -35,28 +55,37 @@ impl<F: FftField + PrimeField> FRIProtocol<F> {
     }
 
     // This fn can be made to take in num_rounds in future impl
-    pub fn generate_proof(&self) -> FRIProof<F> {
-        let mut transcript = Transcript::new();
-        let mut m_hashes = vec![];
-        let mut m_trees = vec![];
-        let mut c_sums = vec![];
-        let mut v_at_index = vec![];
-        let mut v_at_neg_index = vec![];
-        let mut p_at_index = vec![];
-        let mut p_at_neg_index = vec![];
-        let mut all_evals = vec![];
+    #[cfg(feature = "prover")]
+    pub fn generate_proof(&self, transcript_domain: &TranscriptDomain) -> FRIProof<F> {
+        let mut transcript = transcript_domain.transcript();
 
+        // folded as a poly of the declared degree, so the rounds match what the verifier expects
         let mut f_poly = self.poly.clone();
+        f_poly.resize((self.max_degree + 1).next_power_of_two(), F::zero());
+
+        // every round FFTs over a domain half the size of the last one, the tables are built once
+        let mut cache = DomainCache::new();
 
         let padded_poly = self.pad_to_power_of_two();
         let domain_size = padded_poly.len();
+        let num_rounds = domain_size.ilog2();
+
+        // one commitment and one pair of openings per round, the claimed sums skip the first round
+        let rounds = num_rounds as usize;
+        let mut m_hashes = Vec::with_capacity(rounds);
+        let mut m_trees = Vec::with_capacity(rounds);
+        let mut c_sums = Vec::with_capacity(rounds.saturating_sub(1));
+        let mut v_at_index = Vec::with_capacity(rounds);
+        let mut v_at_neg_index = Vec::with_capacity(rounds);
+        let mut p_at_index = Vec::with_capacity(rounds);
+        let mut p_at_neg_index = Vec::with_capacity(rounds);
+        let mut all_evals = Vec::with_capacity(rounds + 1);
+
         let fft = FastFourierTransform::new(padded_poly);
-        let mut eval_poly = fft.evaluate().coefficients;
+        let mut eval_poly = fft.evaluate_with(&mut cache).coefficients;
 
         all_evals.push(eval_poly.clone());
 
-        let num_rounds = eval_poly.len().ilog2();
-
         for _i in 0..num_rounds {
             let poly_string: Vec<String> = eval_poly.iter().map(|d| d.to_string()).collect();
             let poly_bytes: Vec<&[u8]> = poly_string.iter().map(|s| s.as_bytes()).collect();
@@ -64,12 +93,12 @@ impl<F: FftField + PrimeField> FRIProtocol<F> {
             let m_tree = MerkleTree::new(&poly_bytes);
             let m_root = m_tree.root().unwrap();
 
-            transcript.absorb(&m_root);
+            transcript.absorb_merkle_root(&m_root);
             m_hashes.push(m_root);
 
             m_trees.push(m_tree);
 
-            let r = F::from_be_bytes_mod_order(&transcript.squeeze());
+            let r = transcript.squeeze_field::<F>();
 
             if f_poly.len() == 1 {
                 eval_poly = fold_poly(&f_poly, r);
@@ -82,7 +111,7 @@ impl<F: FftField + PrimeField> FRIProtocol<F> {
                 let padded_poly = pad_poly_to_power_of_two(&f_poly);
 
                 let fft = FastFourierTransform::new(padded_poly);
-                eval_poly = fft.evaluate().coefficients;
+                eval_poly = fft.evaluate_with(&mut cache).coefficients;
 
                 all_evals.push(eval_poly.clone());
             }
@@ -91,43 +120,33 @@ impl<F: FftField + PrimeField> FRIProtocol<F> {
         let final_poly = eval_poly;
 
         //=========================================================================================
-        // Sample a random index and get the evaluations at that index
+        // Sample a random index over the whole domain and get the evaluations at that index
+        // x = ωⁱ squares to ωⁱ of the half sized domain, so round k opens index mod its domain
+        // size and the -x next to it half a domain further on
         //=========================================================================================
-        let verifier_field = F::from_be_bytes_mod_order(&transcript.squeeze());
-        let field_integer_repr = verifier_field.into_bigint().as_ref()[0];
-        let mut v_index = (field_integer_repr as usize) % self.poly.len();
-        let verifier_index = v_index;
+        let verifier_index = transcript.squeeze_index::<F>(domain_size);
 
-        for round in 0..num_rounds {
+        for round in 0..num_rounds as usize {
             let round_domain_size = domain_size >> round;
-            let half_domain_size = round_domain_size / 2;
+            let (index, neg_index) = query_positions(verifier_index, round_domain_size);
 
-            let value_at_index = all_evals[round as usize][v_index % round_domain_size];
-            let proof_at_index =
-                m_trees[round as usize].generate_proof(&value_at_index.to_string().as_bytes());
-            v_at_index.push(value_at_index);
-            p_at_index.push(proof_at_index.unwrap());
-
-            let value_at_neg_index =
-                all_evals[round as usize][(v_index + half_domain_size) % round_domain_size];
-            let proof_at_neg_index =
-                m_trees[round as usize].generate_proof(&value_at_neg_index.to_string().as_bytes());
-            v_at_neg_index.push(value_at_neg_index);
-            p_at_neg_index.push(proof_at_neg_index.unwrap());
+            // opened by position, a value that repeats in the codeword would find the wrong leaf
+            v_at_index.push(all_evals[round][index]);
+            p_at_index.push(m_trees[round].generate_proof_at(index).unwrap());
+            v_at_neg_index.push(all_evals[round][neg_index]);
+            p_at_neg_index.push(m_trees[round].generate_proof_at(neg_index).unwrap());
 
             //=========================================================================================
             // We skip the first round since there is no claimed sum in it
             // The claimed_sum is computed so the verifier can have a direct comparison
             //=========================================================================================
             if round != 0 {
-                let claimed_sum = all_evals[round as usize][verifier_index % round_domain_size];
-                c_sums.push(claimed_sum);
+                c_sums.push(all_evals[round][index]);
             }
-
-            v_index /= 2;
         }
 
         FRIProof {
+            max_degree: self.max_degree,
             root_hashes: m_hashes,
             final_poly,
             values_at_index: v_at_index,
@@ -139,56 +158,95 @@ impl<F: FftField + PrimeField> FRIProtocol<F> {
         }
     }
 
-    pub fn verify(&self, proof: FRIProof<F>) -> bool {
-        let mut transcript = Transcript::new();
+    // the query index is squeezed again here and every opening must sit where it says, the
+    // leaf_index in a Merkle proof is only compared against it and never used on its own
+    pub fn verify(&self, proof: &FRIProof<F>, transcript_domain: &TranscriptDomain) -> bool {
+        let mut transcript = transcript_domain.transcript();
+
+        let root_hashes = &proof.root_hashes;
+        let values_at_index = &proof.values_at_index;
+        let values_at_neg_index = &proof.values_at_neg_index;
+        let proofs_at_index = &proof.proofs_at_index;
+        let proofs_at_neg_index = &proof.proofs_at_neg_index;
+        let claimed_sums = &proof.claimed_sums;
+
+        // the number of folds comes from the declared degree bound, never from the proof
+        if proof.max_degree != self.max_degree
+            || !self.has_round_lengths(
+                &[
+                    root_hashes.len(),
+                    values_at_index.len(),
+                    values_at_neg_index.len(),
+                    proofs_at_index.len(),
+                    proofs_at_neg_index.len(),
+                ],
+                claimed_sums.len(),
+            )
+            || proof.final_poly.is_empty()
+        {
+            return false;
+        }
+        if !root_hashes.iter().all(|root| is_digest(root)) {
+            return false;
+        }
 
-        let root_hashes = proof.root_hashes;
-        let values_at_index = proof.values_at_index;
-        let values_at_neg_index = proof.values_at_neg_index;
-        let merkle_trees = proof.merkle_trees;
-        let proofs_at_index = proof.proofs_at_index;
-        let proofs_at_neg_index = proof.proofs_at_neg_index;
-        let claimed_sums = proof.claimed_sums;
+        let domain_size = self.domain_size();
+        let num_rounds = self.num_rounds();
 
-        let domain_size = 2u64.pow(root_hashes.len() as u32);
+        //=========================================================================================
+        // Same transcript as the prover: a root then its folding challenge per round, then the index
+        //=========================================================================================
+        let challenges: Vec<F> = root_hashes
+            .iter()
+            .map(|root| {
+                transcript.absorb_merkle_root(root);
+                transcript.squeeze_field::<F>()
+            })
+            .collect();
+        let verifier_index = transcript.squeeze_index::<F>(domain_size);
 
         //=========================================================================================
         // Get primitive root of unity for the domain
         //=========================================================================================
         let mut primitive_root = F::get_root_of_unity(domain_size as u64).unwrap();
 
-        let num_rounds = root_hashes.len();
+        for round in 0..num_rounds {
+            let (index, neg_index) = query_positions(verifier_index, domain_size >> round);
 
-        for index in 0..(num_rounds - 1) {
-            let check_proof_i = merkle_trees[index].verify_proof(
-                &values_at_index[index].to_string().as_bytes(),
-                &proofs_at_index[index],
-                &root_hashes[index],
+            //=========================================================================================
+            // Both openings must be at the positions the transcript picked, and in the tree
+            //=========================================================================================
+            if proofs_at_index[round].leaf_index != index
+                || proofs_at_neg_index[round].leaf_index != neg_index
+            {
+                return false;
+            }
+            let check_proof_i = MerkleTree::verify_path(
+                values_at_index[round].to_string().as_bytes(),
+                &proofs_at_index[round],
+                &root_hashes[round],
             );
-            let check_proof_neg_i = merkle_trees[index].verify_proof(
-                &values_at_neg_index[index].to_string().as_bytes(),
-                &proofs_at_neg_index[index],
-                &root_hashes[index],
+            let check_proof_neg_i = MerkleTree::verify_path(
+                values_at_neg_index[round].to_string().as_bytes(),
+                &proofs_at_neg_index[round],
+                &root_hashes[round],
             );
 
-            if !check_proof_i && !check_proof_neg_i {
+            if !check_proof_i || !check_proof_neg_i {
                 return false;
             }
 
-            transcript.absorb(&root_hashes[index]);
-            let r = F::from_be_bytes_mod_order(&transcript.squeeze());
-
             //=========================================================================================
             // Get the values at x and -x
             //=========================================================================================
-            let f_x = values_at_index[index];
-            let f_neg_x = values_at_neg_index[index];
+            let f_x = values_at_index[round];
+            let f_neg_x = values_at_neg_index[round];
 
             //=========================================================================================
             // Get the actual domain element (ω^i)
             // i.e. root of unity raised to the power of i
             //=========================================================================================
-            let omega_i = primitive_root.pow(&[proofs_at_index[index].leaf_index as u64]);
+            let omega_i = primitive_root.pow([index as u64]);
 
             //=========================================================================================
             // Calculate the next round value using the formula:
@@ -208,58 +266,39 @@ impl<F: FftField + PrimeField> FRIProtocol<F> {
             let diff_term = diff * omega_i_doubled.inverse().unwrap();
 
             //=========================================================================================
-            // Final calculation
+            // Final calculation, the claimed sum is the value opened at x² in the next round
+            // and the last fold is the constant the poly folded down to
             //=========================================================================================
-            let expected_next_eval = sum_term + (r * diff_term);
-
-            if claimed_sums[index] != expected_next_eval {
+            let expected_next_eval = sum_term + (challenges[round] * diff_term);
+
+            if round + 1 < num_rounds {
+                if claimed_sums[round] != expected_next_eval
+                    || claimed_sums[round] != values_at_index[round + 1]
+                {
+                    return false;
+                }
+            } else if proof.final_poly[0] != expected_next_eval {
                 return false;
             }
 
             primitive_root = primitive_root.square();
         }
 
-        //=========================================================================================
-        // Oracle check for the last round
-        //=========================================================================================
-        let check_proof_last_i = merkle_trees[num_rounds - 1].verify_proof(
-            &values_at_index[num_rounds - 1].to_string().as_bytes(),
-            &proofs_at_index[num_rounds - 1],
-            &root_hashes[num_rounds - 1],
-        );
-        let check_proof_neg_last_i = merkle_trees[num_rounds - 1].verify_proof(
-            &values_at_neg_index[num_rounds - 1].to_string().as_bytes(),
-            &proofs_at_neg_index[num_rounds - 1],
-            &root_hashes[num_rounds - 1],
-        );
-
-        if !check_proof_last_i && !check_proof_neg_last_i {
-            return false;
-        }
-
-        transcript.absorb(&root_hashes[num_rounds - 1]);
-        let r = F::from_be_bytes_mod_order(&transcript.squeeze());
-
-        let f_x = values_at_index[num_rounds - 1];
-        let f_neg_x = values_at_neg_index[num_rounds - 1];
-
-        let omega_i = primitive_root.pow(&[proofs_at_index[num_rounds - 1].leaf_index as u64]);
-
-        let sum_term = (f_x + f_neg_x) * F::from(2).inverse().unwrap();
-
-        let diff = f_x - f_neg_x;
-        let omega_i_doubled = omega_i.double();
-        let diff_term = diff * omega_i_doubled.inverse().unwrap();
+        true
+    }
+}
 
-        let expected_last_eval = sum_term + (r * diff_term);
+// (x, -x) of the query in a round's domain: the index mod the domain size and half a domain on
+pub(crate) fn query_positions(verifier_index: usize, round_domain_size: usize) -> (usize, usize) {
+    let index = verifier_index % round_domain_size;
 
-        proof.final_poly[0] == expected_last_eval
-    }
+    (index, (index + round_domain_size / 2) % round_domain_size)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
 mod tests {
     use super::*;
+    use crate::transcript::test::domain;
     use ark_bn254::Fr;
 
     #[test]
@@ -267,7 +306,7 @@ mod tests {
         let poly: Vec<ark_ff::Fp<ark_ff::MontBackend<ark_bn254::FrConfig, 4>, 4>> =
             vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
         let fri = FRIProtocol::new(poly, 2);
-        fri.generate_proof();
+        fri.generate_proof(&domain());
     }
 
     #[test]
@@ -275,7 +314,61 @@ mod tests {
         let poly: Vec<ark_ff::Fp<ark_ff::MontBackend<ark_bn254::FrConfig, 4>, 4>> =
             vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
         let fri = FRIProtocol::new(poly, 2);
-        let proof = fri.generate_proof();
-        assert!(fri.verify(proof));
+        let proof = fri.generate_proof(&domain());
+        assert!(fri.verify(&proof, &domain()));
+    }
+
+    #[test]
+    fn test_fri_rounds_follow_declared_degree() {
+        let poly = vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
+        let fri = FRIProtocol::with_max_degree(poly.clone(), 2, 7);
+        let proof = fri.generate_proof(&domain());
+        assert_eq!(proof.root_hashes.len(), fri.num_rounds());
+        assert!(fri.verify(&proof, &domain()));
+
+        // a verifier expecting degree 3 folds one round less and refuses the proof
+        assert!(!FRIProtocol::new(poly, 2).verify(&proof, &domain()));
+
+        // dropping the last round doesn't make the proof one for a smaller degree
+        let mut proof = fri.generate_proof(&domain());
+        proof.root_hashes.pop();
+        proof.merkle_trees.pop();
+        proof.values_at_index.pop();
+        proof.values_at_neg_index.pop();
+        proof.proofs_at_index.pop();
+        proof.proofs_at_neg_index.pop();
+        proof.claimed_sums.pop();
+        assert!(!fri.verify(&proof, &domain()));
+    }
+
+    #[test]
+    #[should_panic(expected = "The polynomial has a degree above the declared max degree")]
+    fn test_fri_poly_above_declared_degree() {
+        let poly = vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
+        FRIProtocol::with_max_degree(poly, 2, 2);
+    }
+
+    #[test]
+    fn test_fri_query_positions_come_from_the_transcript() {
+        let poly: Vec<Fr> = (1..=8).map(Fr::from).collect();
+        let fri = FRIProtocol::new(poly, 2);
+        for context in 0..16u8 {
+            let domain = TranscriptDomain::new(&[context], [0; 32]);
+            assert!(fri.verify(&fri.generate_proof(&domain), &domain));
+        }
+
+        // a valid opening of another leaf of the same tree is not an opening of the query
+        let mut proof = fri.generate_proof(&domain());
+        let moved = proof.proofs_at_index[0].leaf_index ^ 1;
+        proof.proofs_at_index[0] = proof.merkle_trees[0].generate_proof_at(moved).unwrap();
+        proof.values_at_index[0] = FastFourierTransform::new(fri.pad_to_power_of_two())
+            .evaluate()
+            .coefficients[moved];
+        assert!(MerkleTree::verify_path(
+            proof.values_at_index[0].to_string().as_bytes(),
+            &proof.proofs_at_index[0],
+            &proof.root_hashes[0]
+        ));
+        assert!(!fri.verify(&proof, &domain()));
     }
 }
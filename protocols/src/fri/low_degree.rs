@@ -0,0 +1,624 @@
+use ark_ff::{FftField, PrimeField};
+
+use crate::{
+    eval_store::EvalStorage,
+    fri::domain_cache::{CosetDomain, Domain, DomainCache},
+    fri::merkle_tree::{MerkleProof, MerkleTree},
+    multi_linear::MultiLinearPoly,
+    security::SecurityConfig,
+    transcript::{is_digest, Transcript, TranscriptDomain},
+};
+#[cfg(feature = "prover")]
+use {
+    crate::{
+        fri::{fft::FastFourierTransform, lde::lde_with},
+        progress::{never_cancelled, Cancelled, Progress, ProgressEvent},
+    },
+    rand::Rng,
+};
+
+//=========================================================================================
+// FRI as a standalone degree-bound check on an evaluation vector
+// evals[j] = f(ωʲ) over a domain of size degree_bound * blowup_factor (both rounded up to a power of 2)
+// every round commits to the current layer and folds it on r in evaluation form
+// fᵢ₊₁(x²) = (fᵢ(x) + fᵢ(-x)) / 2 + r * (fᵢ(x) - fᵢ(-x)) / 2x
+// after log2(degree_bound) rounds an honest layer is constant, it is sent in the clear
+// each query opens x and -x in every layer so the verifier can redo the fold
+// with grinding_bits > 0 the prover grinds a proof of work nonce before the queries are drawn
+// storage decides where the committed layers wait for the queries (the Merkle trees stay in memory)
+//
+// zero knowledge (blinding > 0): the codeword lives on the coset g·ωʲ (g = F::GENERATOR) and
+// blinded_extension appends blinding random values to the trace before interpolating it, so no
+// query lands on a trace point and the openings are masked by the random rows
+// layer i is then over g^(2ⁱ)·ωʲ and the fold divides by that x instead of ωʲ
+// the PCS and the univariate sum-check only take the coset from the config, they commit to
+// polynomials that are already fixed
+//=========================================================================================
+#[derive(Debug, Clone)]
+pub struct LowDegreeConfig {
+    pub blowup_factor: usize,
+    pub num_queries: usize,
+    pub grinding_bits: usize,
+    pub storage: EvalStorage,
+    pub blinding: usize, // random values blinded_extension appends to the trace, 0 keeps the plain subgroup
+}
+
+impl Default for LowDegreeConfig {
+    fn default() -> Self {
+        LowDegreeConfig {
+            blowup_factor: 2,
+            num_queries: 8,
+            grinding_bits: 0,
+            storage: EvalStorage::Memory,
+            blinding: 0,
+        }
+    }
+}
+
+impl LowDegreeConfig {
+    pub fn from_security(security: &SecurityConfig, blowup_factor: usize) -> Self {
+        LowDegreeConfig {
+            blowup_factor,
+            num_queries: security.query_count,
+            grinding_bits: security.grinding_bits,
+            storage: EvalStorage::Memory,
+            blinding: 0,
+        }
+    }
+
+    pub fn with_blinding(self, blinding: usize) -> Self {
+        LowDegreeConfig { blinding, ..self }
+    }
+
+    // g with blinding on, 1 (the subgroup itself) without
+    pub fn coset_shift<F: FftField>(&self) -> F {
+        if self.blinding > 0 {
+            F::GENERATOR
+        } else {
+            F::one()
+        }
+    }
+
+    // the domain the first layer is committed over
+    pub fn coset_domain<F: FftField>(
+        &self,
+        degree_bound: usize,
+        cache: &mut DomainCache<F>,
+    ) -> CosetDomain<F> {
+        cache.coset(self.domain_size(degree_bound), self.coset_shift())
+    }
+
+    pub fn domain_size(&self, degree_bound: usize) -> usize {
+        degree_bound.next_power_of_two() * self.blowup_factor
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LayerOpening<F: PrimeField> {
+    pub value: F,
+    pub neg_value: F,
+    pub proof: MerkleProof,
+    pub neg_proof: MerkleProof,
+}
+
+#[derive(Debug, Clone)]
+pub struct LowDegreeProof<F: PrimeField> {
+    pub root_hashes: Vec<Vec<u8>>, // one per folded layer, the first commits to evals
+    pub final_layer: Vec<F>,       // constant for an honest prover
+    pub query_openings: Vec<Vec<LayerOpening<F>>>, // [query][round]
+    pub pow_nonce: u64,
+}
+
+impl<F: PrimeField> LowDegreeProof<F> {
+    // Merkle root of the evaluation vector the proof is about
+    pub fn commitment(&self) -> &[u8] {
+        &self.root_hashes[0]
+    }
+}
+
+#[cfg(feature = "prover")]
+pub fn prove_low_degree<F: FftField + PrimeField>(
+    evals: &[F],
+    degree_bound: usize,
+    config: &LowDegreeConfig,
+    transcript_domain: &TranscriptDomain,
+) -> LowDegreeProof<F> {
+    never_cancelled(prove_low_degree_with_progress(
+        evals,
+        degree_bound,
+        config,
+        transcript_domain,
+        &mut Progress::none(),
+    ))
+}
+
+// same proof with a Fold reported after every committed layer, see progress
+#[cfg(feature = "prover")]
+pub fn prove_low_degree_with_progress<F: FftField + PrimeField>(
+    evals: &[F],
+    degree_bound: usize,
+    config: &LowDegreeConfig,
+    transcript_domain: &TranscriptDomain,
+    progress: &mut Progress,
+) -> Result<LowDegreeProof<F>, Cancelled> {
+    let domain_size = config.domain_size(degree_bound);
+    if evals.len() != domain_size {
+        panic!("The evaluations must cover the whole domain (degree bound * blowup factor)");
+    }
+
+    let mut transcript = transcript_domain.transcript();
+    let mut cache = DomainCache::new();
+    let rounds = num_rounds(degree_bound);
+    let mut layers = Vec::with_capacity(rounds);
+    let mut m_trees = Vec::with_capacity(rounds);
+    let mut root_hashes = Vec::with_capacity(rounds);
+
+    // only the layer being committed and folded is held in memory here
+    let mut layer = evals.to_vec();
+    let mut domain = config.coset_domain(degree_bound, &mut cache);
+    for round in 0..rounds {
+        let m_tree = commit_layer(&layer);
+        let m_root = m_tree.root().unwrap();
+
+        transcript.absorb_merkle_root(&m_root);
+        let r = transcript.squeeze_field::<F>();
+
+        let next_layer = fold_coset_evals(&layer, r, &domain);
+        domain = domain.square(&mut cache);
+        m_trees.push(m_tree);
+        root_hashes.push(m_root);
+        layers.push(
+            config
+                .storage
+                .store(layer)
+                .expect("could not store a FRI layer"),
+        );
+        layer = next_layer;
+        progress.report(ProgressEvent::Fold {
+            done: round + 1,
+            total: rounds,
+        })?;
+    }
+
+    let final_layer = layer;
+    transcript.absorb(&MultiLinearPoly::to_bytes(&final_layer));
+    let pow_nonce = transcript.grind(config.grinding_bits);
+
+    let query_openings = query_indices::<F>(&mut transcript, domain_size, config.num_queries)
+        .into_iter()
+        .map(|query| {
+            layers
+                .iter()
+                .zip(m_trees.iter())
+                .map(|(layer, m_tree)| {
+                    let n = layer.len();
+                    let (j, neg_j) = (query % n, (query + n / 2) % n);
+                    let (value, neg_value) = (layer.get(j), layer.get(neg_j));
+
+                    LayerOpening {
+                        value,
+                        neg_value,
+                        proof: m_tree.generate_proof(&leaf_bytes(j, value)).unwrap(),
+                        neg_proof: m_tree
+                            .generate_proof(&leaf_bytes(neg_j, neg_value))
+                            .unwrap(),
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(LowDegreeProof {
+        root_hashes,
+        final_layer,
+        query_openings,
+        pow_nonce,
+    })
+}
+
+// true if the committed evaluations are (close to) a poly of degree < degree_bound
+pub fn verify_low_degree<F: FftField + PrimeField>(
+    proof: &LowDegreeProof<F>,
+    degree_bound: usize,
+    config: &LowDegreeConfig,
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    let domain_size = config.domain_size(degree_bound);
+    let num_rounds = num_rounds(degree_bound);
+
+    if proof.root_hashes.len() != num_rounds
+        || !proof.root_hashes.iter().all(|root| is_digest(root))
+        || proof.final_layer.len() != domain_size >> num_rounds
+        || proof.query_openings.len() != config.num_queries
+    {
+        return false;
+    }
+    if proof
+        .final_layer
+        .iter()
+        .any(|eval| *eval != proof.final_layer[0])
+    {
+        return false;
+    }
+
+    let mut transcript = transcript_domain.transcript();
+    let challenges: Vec<F> = proof
+        .root_hashes
+        .iter()
+        .map(|m_root| {
+            transcript.absorb_merkle_root(m_root);
+            transcript.squeeze_field::<F>()
+        })
+        .collect();
+    transcript.absorb(&MultiLinearPoly::to_bytes(&proof.final_layer));
+    if !transcript.check_grinding(config.grinding_bits, proof.pow_nonce) {
+        return false;
+    }
+
+    let queries = query_indices::<F>(&mut transcript, domain_size, config.num_queries);
+    let domains: Vec<_> = {
+        let mut cache = DomainCache::<F>::new();
+        let first = config.coset_domain(degree_bound, &mut cache);
+        std::iter::successors(Some(first), |domain| Some(domain.square(&mut cache)))
+            .take(num_rounds)
+            .collect()
+    };
+    let two_inv = F::from(2u64).inverse().unwrap();
+
+    queries
+        .iter()
+        .zip(proof.query_openings.iter())
+        .all(|(query, openings)| {
+            if openings.len() != num_rounds {
+                return false;
+            }
+
+            let mut n = domain_size;
+            let mut expected = None;
+            for (((opening, m_root), r), domain) in openings
+                .iter()
+                .zip(proof.root_hashes.iter())
+                .zip(challenges.iter())
+                .zip(domains.iter())
+            {
+                let (j, neg_j) = (query % n, (query + n / 2) % n);
+                if !check_opening(j, opening.value, &opening.proof, m_root)
+                    || !check_opening(neg_j, opening.neg_value, &opening.neg_proof, m_root)
+                {
+                    return false;
+                }
+                // the value opened here must be the fold computed from the previous layer
+                if expected.is_some_and(|expected| expected != opening.value) {
+                    return false;
+                }
+
+                let (f_x, f_neg_x) = (opening.value, opening.neg_value);
+                let even = (f_x + f_neg_x) * two_inv;
+                let odd = (f_x - f_neg_x) * two_inv * domain.inverse_element(j);
+                expected = Some(even + (*r * odd));
+
+                n /= 2;
+            }
+
+            match expected {
+                Some(expected) => proof.final_layer[query % n] == expected,
+                None => true, // degree_bound 1, the constant final layer is all there is
+            }
+        })
+}
+
+// fᵢ₊₁(ω²ʲ) from fᵢ(ωʲ) and fᵢ(-ωʲ) = fᵢ(ωʲ⁺ⁿᐟ²), domain is the one evals live on
+pub fn fold_evals<F: FftField + PrimeField>(evals: &[F], r: F, domain: &Domain<F>) -> Vec<F> {
+    fold_with(evals, r, |i| domain.inverse_element(i))
+}
+
+// the same fold with x = shift·ωʲ, the result lives on domain.square()
+pub fn fold_coset_evals<F: FftField + PrimeField>(
+    evals: &[F],
+    r: F,
+    domain: &CosetDomain<F>,
+) -> Vec<F> {
+    fold_with(evals, r, |i| domain.inverse_element(i))
+}
+
+fn fold_with<F: FftField + PrimeField>(evals: &[F], r: F, x_inv: impl Fn(usize) -> F) -> Vec<F> {
+    let half = evals.len() / 2;
+    let two_inv = F::from(2u64).inverse().unwrap();
+
+    (0..half)
+        .map(|i| {
+            let (f_x, f_neg_x) = (evals[i], evals[i + half]);
+            let even = (f_x + f_neg_x) * two_inv;
+            let odd = (f_x - f_neg_x) * two_inv * x_inv(i);
+
+            even + (r * odd)
+        })
+        .collect()
+}
+
+// the trace followed by config.blinding random values, interpolated over the subgroup of size
+// degree_bound = (trace.len() + blinding).next_power_of_two() and extended over the coset
+// trace[i] is the value at ωⁱ of that subgroup, which the codeword never touches
+// (evals, degree_bound) go straight into prove_low_degree with the same config
+#[cfg(feature = "prover")]
+pub fn blinded_extension<F: FftField + PrimeField, R: Rng>(
+    trace: &[F],
+    config: &LowDegreeConfig,
+    rng: &mut R,
+) -> (Vec<F>, usize) {
+    if trace.is_empty() {
+        panic!("The trace must not be empty");
+    }
+    if config.blinding == 0 {
+        panic!("The config has no blinding values to append");
+    }
+
+    let mut rows = trace.to_vec();
+    rows.extend((0..config.blinding).map(|_| F::rand(rng)));
+    let degree_bound = rows.len().next_power_of_two();
+    rows.resize(degree_bound, F::zero());
+
+    let mut cache = DomainCache::new();
+    let coefficients = FastFourierTransform::new(rows)
+        .interpolate_with(&mut cache)
+        .coefficients;
+    let evals = lde_with(
+        &coefficients,
+        config.blowup_factor,
+        config.coset_shift(),
+        &mut cache,
+    );
+
+    (evals, degree_bound)
+}
+
+fn num_rounds(degree_bound: usize) -> usize {
+    degree_bound.next_power_of_two().ilog2() as usize
+}
+
+// the index is part of the leaf so repeated values still get their own Merkle path
+pub(crate) fn leaf_bytes<F: PrimeField>(index: usize, value: F) -> Vec<u8> {
+    [
+        (index as u64).to_be_bytes().to_vec(),
+        MultiLinearPoly::to_bytes(&[value]),
+    ]
+    .concat()
+}
+
+#[cfg(feature = "prover")]
+pub(crate) fn commit_layer<F: PrimeField>(layer: &[F]) -> MerkleTree {
+    let leaves: Vec<Vec<u8>> = layer
+        .iter()
+        .enumerate()
+        .map(|(i, value)| leaf_bytes(i, *value))
+        .collect();
+    let leaf_refs: Vec<&[u8]> = leaves.iter().map(|leaf| leaf.as_slice()).collect();
+
+    MerkleTree::new(&leaf_refs)
+}
+
+pub(crate) fn check_opening<F: PrimeField>(
+    index: usize,
+    value: F,
+    proof: &MerkleProof,
+    root: &[u8],
+) -> bool {
+    proof.leaf_index == index && MerkleTree::verify_path(&leaf_bytes(index, value), proof, root)
+}
+
+// one leaf per domain point covering every column, leaf j is the tuple (j, c₀[j], c₁[j], ...)
+// so a batch of polynomials over the same domain shares one tree and one path per query
+pub(crate) fn row_parts<F: PrimeField>(index: usize, row: &[F]) -> Vec<Vec<u8>> {
+    std::iter::once((index as u64).to_be_bytes().to_vec())
+        .chain(row.iter().map(|value| MultiLinearPoly::to_bytes(&[*value])))
+        .collect()
+}
+
+#[cfg(feature = "prover")]
+pub(crate) fn commit_rows<F: PrimeField>(columns: &[Vec<F>]) -> MerkleTree {
+    let len = columns.first().map_or(0, Vec::len);
+    if columns.iter().any(|column| column.len() != len) {
+        panic!("The columns must all have the same length");
+    }
+
+    let rows: Vec<Vec<Vec<u8>>> = (0..len)
+        .map(|i| {
+            let row: Vec<F> = columns.iter().map(|column| column[i]).collect();
+            row_parts(i, &row)
+        })
+        .collect();
+
+    MerkleTree::with_tuples(&rows, &[])
+}
+
+pub(crate) fn check_row_opening<F: PrimeField>(
+    index: usize,
+    row: &[F],
+    proof: &MerkleProof,
+    root: &[u8],
+) -> bool {
+    proof.leaf_index == index
+        && MerkleTree::verify_tuple_path(&[], &row_parts(index, row), proof, root)
+}
+
+pub(crate) fn query_indices<F: PrimeField>(
+    transcript: &mut Transcript,
+    domain_size: usize,
+    num_queries: usize,
+) -> Vec<usize> {
+    (0..num_queries)
+        .map(|_| transcript.squeeze_index::<F>(domain_size))
+        .collect()
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-377"))]
+mod test {
+    use super::*;
+    use crate::{
+        eval_store::test::disk_storage, fri::lde::lde, progress::CancelToken,
+        transcript::test::domain,
+    };
+    use ark_bls12_377::Fr;
+
+    // evaluations of 5 + 3x + 2x^3 + x^4 + 7x^5 (degree 5) over a domain of size 8 * blowup
+    fn evals(blowup_factor: usize) -> Vec<Fr> {
+        lde(
+            &[5, 3, 0, 2, 1, 7].map(Fr::from),
+            blowup_factor,
+            Fr::from(1),
+        )
+    }
+
+    #[test]
+    fn test_low_degree_accepts() {
+        let config = LowDegreeConfig::default();
+        let proof = prove_low_degree(&evals(2), 6, &config, &domain());
+
+        assert_eq!(proof.root_hashes.len(), 3);
+        assert_eq!(proof.final_layer.len(), 2);
+        assert!(verify_low_degree(&proof, 6, &config, &domain()));
+    }
+
+    #[test]
+    fn test_low_degree_progress() {
+        let config = LowDegreeConfig::default();
+        let token = CancelToken::new();
+        let mut folds = Vec::new();
+        let mut progress = Progress::new(|event| folds.push(event)).with_cancel(&token);
+
+        let proof = prove_low_degree_with_progress(&evals(2), 6, &config, &domain(), &mut progress)
+            .unwrap();
+        assert!(verify_low_degree(&proof, 6, &config, &domain()));
+
+        token.cancel();
+        let result =
+            prove_low_degree_with_progress(&evals(2), 6, &config, &domain(), &mut progress);
+        assert!(result.is_err());
+        drop(progress);
+
+        let fold = |done| ProgressEvent::Fold { done, total: 3 };
+        assert_eq!(folds, [fold(1), fold(2), fold(3), fold(1)]);
+    }
+
+    #[test]
+    fn test_low_degree_rejects_higher_degree() {
+        // same 32 point domain, but claiming degree < 4 for a degree 5 poly
+        let config = LowDegreeConfig {
+            blowup_factor: 8,
+            num_queries: 8,
+            ..Default::default()
+        };
+        let proof = prove_low_degree(&evals(4), 4, &config, &domain());
+
+        assert!(!verify_low_degree(&proof, 4, &config, &domain()));
+    }
+
+    #[test]
+    fn test_low_degree_layers_on_disk() {
+        let config = LowDegreeConfig {
+            storage: disk_storage(),
+            ..Default::default()
+        };
+        let proof = prove_low_degree(&evals(2), 6, &config, &domain());
+        let in_memory = prove_low_degree(&evals(2), 6, &LowDegreeConfig::default(), &domain());
+
+        assert_eq!(proof.root_hashes, in_memory.root_hashes);
+        assert_eq!(
+            proof.query_openings[0][1].value,
+            in_memory.query_openings[0][1].value
+        );
+        assert!(verify_low_degree(&proof, 6, &config, &domain()));
+    }
+
+    #[test]
+    fn test_low_degree_rejects_tampered_opening() {
+        let config = LowDegreeConfig::default();
+        let mut proof = prove_low_degree(&evals(2), 6, &config, &domain());
+        proof.query_openings[0][1].value += Fr::from(1);
+
+        assert!(!verify_low_degree(&proof, 6, &config, &domain()));
+    }
+
+    #[test]
+    fn test_blinded_low_degree() {
+        let config = LowDegreeConfig::default().with_blinding(3);
+        let trace = [4, 8, 15, 16, 23].map(Fr::from);
+        let (evals, degree_bound) = blinded_extension(&trace, &config, &mut rand::thread_rng());
+        assert_eq!(degree_bound, 8);
+        assert_eq!(evals.len(), 16);
+
+        let proof = prove_low_degree(&evals, degree_bound, &config, &domain());
+        assert!(verify_low_degree(&proof, degree_bound, &config, &domain()));
+        // the folds only line up on the coset the config names
+        assert!(!verify_low_degree(
+            &proof,
+            degree_bound,
+            &LowDegreeConfig::default(),
+            &domain()
+        ));
+
+        // fresh random rows, a fresh codeword for the same trace
+        let (again, _) = blinded_extension(&trace, &config, &mut rand::thread_rng());
+        assert_ne!(
+            prove_low_degree(&again, degree_bound, &config, &domain()).commitment(),
+            proof.commitment()
+        );
+    }
+
+    #[test]
+    fn test_blinded_extension_keeps_the_trace() {
+        let config = LowDegreeConfig::default().with_blinding(2);
+        let trace = [1, 2, 3].map(Fr::from);
+        let (evals, degree_bound) = blinded_extension(&trace, &config, &mut rand::thread_rng());
+
+        // back to coefficients over the coset, then evaluate on the trace subgroup
+        let mut cache = DomainCache::new();
+        let coset = config.coset_domain::<Fr>(degree_bound, &mut cache);
+        let mut coefficients = FastFourierTransform::new(evals)
+            .interpolate_with(&mut cache)
+            .coefficients;
+        let mut shift_inv_pow = Fr::from(1);
+        for coeff in coefficients.iter_mut() {
+            *coeff *= shift_inv_pow;
+            shift_inv_pow *= coset.shift_inv;
+        }
+        assert!(coefficients[degree_bound..]
+            .iter()
+            .all(|c| *c == Fr::from(0)));
+
+        let subgroup = cache.get(degree_bound);
+        for (i, value) in trace.iter().enumerate() {
+            let x = subgroup.element(i);
+            let at_x = coefficients
+                .iter()
+                .rev()
+                .fold(Fr::from(0), |acc, coeff| acc * x + coeff);
+            assert_eq!(at_x, *value);
+            assert!(!coset.contains(x));
+        }
+    }
+
+    #[test]
+    fn test_low_degree_with_grinding() {
+        let security = SecurityConfig::for_target(24, 254, 2).with_grinding(8, 2);
+        let config = LowDegreeConfig::from_security(&security, 2);
+        assert_eq!(config.num_queries, 16);
+
+        let mut proof = prove_low_degree(&evals(2), 6, &config, &domain());
+        assert!(verify_low_degree(&proof, 6, &config, &domain()));
+
+        // a nonce that does not do the work fails before any query is looked at
+        proof.pow_nonce = (0..)
+            .find(|nonce| {
+                let mut transcript = domain().transcript();
+                for m_root in proof.root_hashes.iter() {
+                    transcript.absorb_merkle_root(m_root);
+                    transcript.squeeze();
+                }
+                transcript.absorb(&MultiLinearPoly::to_bytes(&proof.final_layer));
+                !transcript.check_grinding(8, *nonce)
+            })
+            .unwrap();
+        assert!(!verify_low_degree(&proof, 6, &config, &domain()));
+    }
+}
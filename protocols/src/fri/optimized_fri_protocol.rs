@@ -1,40 +1,43 @@
-use std::vec;
-
 use ark_ff::{FftField, PrimeField};
 
+#[cfg(feature = "prover")]
+use crate::fri::fft::FastFourierTransform;
 use crate::{
-    fri::fft::FastFourierTransform,
-    fri::fri_protocol::FRIProtocol,
-    fri::merkle_tree::{MerkleProof, MerkleTree},
-    transcript::Transcript,
+    fri::fri_protocol::{query_positions, FRIProtocol},
+    fri::merkle_tree::{MerkleMultiProof, MerkleTree},
+    transcript::{is_digest, TranscriptDomain},
 };
 
 pub struct OptimizedFRIProof<F: FftField> {
+    pub max_degree: usize, // the degree bound the proof was made for
     pub root_hashes: Vec<Vec<u8>>,
     pub final_poly: Vec<F>,
     pub values_at_index: Vec<F>,
     pub values_at_neg_index: Vec<F>,
     pub merkle_trees: Vec<MerkleTree>,
-    pub proofs_at_index: Vec<MerkleProof>,
-    pub proofs_at_neg_index: Vec<MerkleProof>,
+    pub openings: Vec<MerkleMultiProof>, // x and -x of each round in one multiproof
     pub claimed_sums: Vec<F>,
 }
 
 impl<F: FftField + PrimeField> FRIProtocol<F> {
     // This fn can be made to take in num_rounds in future impl
-    pub fn optimized_proof(&self) -> OptimizedFRIProof<F> {
-        let mut transcript = Transcript::new();
-        let mut m_hashes = vec![];
-        let mut m_trees = vec![];
-        let mut c_sums = vec![];
-        let mut v_at_index = vec![];
-        let mut v_at_neg_index = vec![];
-        let mut p_at_index = vec![];
-        let mut p_at_neg_index = vec![];
-        let mut all_evals = vec![];
+    #[cfg(feature = "prover")]
+    pub fn optimized_proof(&self, transcript_domain: &TranscriptDomain) -> OptimizedFRIProof<F> {
+        let mut transcript = transcript_domain.transcript();
 
         let padded_poly = self.pad_to_power_of_two();
         let domain_size = padded_poly.len();
+        let num_rounds = domain_size.ilog2();
+
+        // one commitment and one pair of openings per round, the claimed sums skip the first round
+        let rounds = num_rounds as usize;
+        let mut m_hashes = Vec::with_capacity(rounds);
+        let mut m_trees = Vec::with_capacity(rounds);
+        let mut c_sums = Vec::with_capacity(rounds.saturating_sub(1));
+        let mut v_at_index = Vec::with_capacity(rounds);
+        let mut v_at_neg_index = Vec::with_capacity(rounds);
+        let mut openings = Vec::with_capacity(rounds);
+        let mut all_evals = Vec::with_capacity(rounds + 1);
 
         //=========================================================================================
         // Get primitive root of unity for the domain
@@ -52,8 +55,6 @@ impl<F: FftField + PrimeField> FRIProtocol<F> {
         let mut current_domain_size = domain_size;
         let mut current_primitive_root = primitive_root;
 
-        let num_rounds = domain_size.ilog2();
-
         for _round in 0..num_rounds {
             let poly_string: Vec<String> = current_evals.iter().map(|d| d.to_string()).collect();
             let poly_bytes: Vec<&[u8]> = poly_string.iter().map(|s| s.as_bytes()).collect();
@@ -63,10 +64,10 @@ impl<F: FftField + PrimeField> FRIProtocol<F> {
 
             m_trees.push(m_tree);
 
-            transcript.absorb(&m_root);
+            transcript.absorb_merkle_root(&m_root);
             m_hashes.push(m_root);
 
-            let r = F::from_be_bytes_mod_order(&transcript.squeeze());
+            let r = transcript.squeeze_field::<F>();
 
             let next_domain_size = current_domain_size / 2;
             let mut next_evals = Vec::with_capacity(next_domain_size);
@@ -82,7 +83,7 @@ impl<F: FftField + PrimeField> FRIProtocol<F> {
                 // Get the actual domain element (ω^i)
                 // i.e. root of unity raised to the power of i
                 //=========================================================================================
-                let omega_i = current_primitive_root.pow(&[i as u64]);
+                let omega_i = current_primitive_root.pow([i as u64]);
 
                 //=========================================================================================
                 // Calculate the next round value using the formula:
@@ -126,175 +127,172 @@ impl<F: FftField + PrimeField> FRIProtocol<F> {
 
         //=========================================================================================
         // Sample a random index and get the evaluations at that index
-        // This is the verifier's challenge
+        // This is the verifier's challenge, over the whole domain
+        // next_evals[i] folds current_evals[i] and current_evals[i + n/2] so the point follows
+        // as index mod the round's domain size (x² of ωⁱ is ωⁱ of the half sized domain)
         //=========================================================================================
-        let verifier_field = F::from_be_bytes_mod_order(&transcript.squeeze());
-        let field_integer_repr = verifier_field.into_bigint().as_ref()[0];
-        let mut v_index = (field_integer_repr as usize) % self.poly.len();
-        let verifier_index = v_index;
+        let verifier_index = transcript.squeeze_index::<F>(domain_size);
 
-        for round in 0..num_rounds {
-            let round_domain_size = domain_size >> round;
-            let half_domain_size = round_domain_size / 2;
-
-            let value_at_index = all_evals[round as usize][v_index % round_domain_size];
-            let proof_at_index =
-                m_trees[round as usize].generate_proof(&value_at_index.to_string().as_bytes());
-            v_at_index.push(value_at_index);
-            p_at_index.push(proof_at_index.unwrap());
-
-            let value_at_neg_index =
-                all_evals[round as usize][(v_index + half_domain_size) % round_domain_size];
-            let proof_at_neg_index =
-                m_trees[round as usize].generate_proof(&value_at_neg_index.to_string().as_bytes());
-            v_at_neg_index.push(value_at_neg_index);
-            p_at_neg_index.push(proof_at_neg_index.unwrap());
+        for round in 0..rounds {
+            let (index, neg_index) = query_positions(verifier_index, domain_size >> round);
+
+            v_at_index.push(all_evals[round][index]);
+            v_at_neg_index.push(all_evals[round][neg_index]);
+            openings.push(
+                m_trees[round]
+                    .generate_multiproof(&[index, neg_index])
+                    .unwrap(),
+            );
 
             //=========================================================================================
             // We skip the first round since there is no claimed sum in it
             // The claimed_sum is computed so the verifier can have a direct comparison
             //=========================================================================================
             if round != 0 {
-                let claimed_sum = all_evals[round as usize][verifier_index % round_domain_size];
-                c_sums.push(claimed_sum);
+                c_sums.push(all_evals[round][index]);
             }
-
-            v_index /= 2;
         }
 
         OptimizedFRIProof {
+            max_degree: self.max_degree,
             root_hashes: m_hashes,
             final_poly,
             values_at_index: v_at_index,
             values_at_neg_index: v_at_neg_index,
             merkle_trees: m_trees,
-            proofs_at_index: p_at_index,
-            proofs_at_neg_index: p_at_neg_index,
+            openings,
             claimed_sums: c_sums,
         }
     }
 
-    pub fn optimized_verify(&self, proof: OptimizedFRIProof<F>) -> bool {
-        let mut transcript = Transcript::new();
+    //=========================================================================================
+    // The verifier runs in three passes and stops at the first that fails:
+    //   1. the shape of the proof against the declared degree, no hashing and no field work
+    //   2. the fold of every round from the point the transcript picked: f₁(x), f₁(-x) of round k
+    //      must fold to the value opened at x² in round k + 1 (and to the final constant after
+    //      the last round), the point comes from the squeezed index and never from the proof
+    //   3. the openings, collected while folding and checked together at the end with one
+    //      multiproof per round tree, so a proof that is already wrong costs no hashing
+    //=========================================================================================
+    pub fn optimized_verify(
+        &self,
+        proof: &OptimizedFRIProof<F>,
+        transcript_domain: &TranscriptDomain,
+    ) -> bool {
+        let mut transcript = transcript_domain.transcript();
+
+        let root_hashes = &proof.root_hashes;
+        let values_at_index = &proof.values_at_index;
+        let values_at_neg_index = &proof.values_at_neg_index;
+        let claimed_sums = &proof.claimed_sums;
+
+        // the number of folds comes from the declared degree bound, never from the proof
+        if proof.max_degree != self.max_degree
+            || !self.has_round_lengths(
+                &[
+                    root_hashes.len(),
+                    values_at_index.len(),
+                    values_at_neg_index.len(),
+                    proof.openings.len(),
+                ],
+                claimed_sums.len(),
+            )
+            || proof.final_poly.len() != 1
+        {
+            return false;
+        }
+        if !root_hashes.iter().all(|root| is_digest(root)) {
+            return false;
+        }
 
-        let root_hashes = proof.root_hashes;
-        let values_at_index = proof.values_at_index;
-        let values_at_neg_index = proof.values_at_neg_index;
-        let merkle_trees = proof.merkle_trees;
-        let proofs_at_index = proof.proofs_at_index;
-        let proofs_at_neg_index = proof.proofs_at_neg_index;
-        let claimed_sums = proof.claimed_sums;
+        let domain_size = self.domain_size();
+        let num_rounds = self.num_rounds();
 
-        let domain_size = 2u64.pow(root_hashes.len() as u32);
+        //=========================================================================================
+        // Same transcript as the prover: a root then its folding challenge per round, then the index
+        //=========================================================================================
+        let challenges: Vec<F> = root_hashes
+            .iter()
+            .map(|root| {
+                transcript.absorb_merkle_root(root);
+                transcript.squeeze_field::<F>()
+            })
+            .collect();
+        let verifier_index = transcript.squeeze_index::<F>(domain_size);
 
         //=========================================================================================
         // Get primitive root of unity for the domain
         //=========================================================================================
         let mut primitive_root = F::get_root_of_unity(domain_size as u64).unwrap();
+        let two_inv = F::from(2).inverse().unwrap();
+        let mut merkle_checks = Vec::with_capacity(num_rounds);
 
-        let num_rounds = root_hashes.len();
-
-        for index in 0..(num_rounds - 1) {
-            let check_proof_i = merkle_trees[index].verify_proof(
-                &values_at_index[index].to_string().as_bytes(),
-                &proofs_at_index[index],
-                &root_hashes[index],
-            );
-            let check_proof_neg_i = merkle_trees[index].verify_proof(
-                &values_at_neg_index[index].to_string().as_bytes(),
-                &proofs_at_neg_index[index],
-                &root_hashes[index],
-            );
-
-            if !check_proof_i && !check_proof_neg_i {
-                return false;
-            }
-
-            transcript.absorb(&root_hashes[index]);
-            let r = F::from_be_bytes_mod_order(&transcript.squeeze());
+        for round in 0..num_rounds {
+            let (index, neg_index) = query_positions(verifier_index, domain_size >> round);
 
             //=========================================================================================
             // Get the values at x and -x
             //=========================================================================================
-            let f_x = values_at_index[index];
-            let f_neg_x = values_at_neg_index[index];
+            let f_x = values_at_index[round];
+            let f_neg_x = values_at_neg_index[round];
 
             //=========================================================================================
             // Get the actual domain element (ω^i)
-            // i.e. root of unity raised to the power of i
+            // i.e. root of unity raised to the power of the index the transcript picked
             //=========================================================================================
-            let omega_i = primitive_root.pow(&[proofs_at_index[index].leaf_index as u64]);
+            let omega_i = primitive_root.pow([index as u64]);
 
             //=========================================================================================
             // Calculate the next round value using the formula:
             // f₂(x²) = (f₁(x) + f₁(-x))/2 + r * ((f₁(x) - f₁(-x))/(2x))
             //=========================================================================================
+            let sum_term = (f_x + f_neg_x) * two_inv;
+            let diff_term = (f_x - f_neg_x) * omega_i.double().inverse().unwrap();
+            let expected_next_eval = sum_term + (challenges[round] * diff_term);
 
             //=========================================================================================
-            // First part: (f₁(x) + f₁(-x))/2
-            //=========================================================================================
-            let sum_term = (f_x + f_neg_x) * F::from(2).inverse().unwrap();
-
-            //=========================================================================================
-            // Second part: (f₁(x) - f₁(-x))/(2x)
-            //=========================================================================================
-            let diff = f_x - f_neg_x;
-            let omega_i_doubled = omega_i.double();
-            let diff_term = diff * omega_i_doubled.inverse().unwrap();
-
-            //=========================================================================================
-            // Final calculation
+            // x² is the index of the next round, so the fold must be what was opened there
             //=========================================================================================
-            let expected_next_eval = sum_term + (r * diff_term);
-
-            if claimed_sums[index] != expected_next_eval {
+            let next_eval = if round + 1 < num_rounds {
+                if claimed_sums[round] != values_at_index[round + 1] {
+                    return false;
+                }
+                values_at_index[round + 1]
+            } else {
+                proof.final_poly[0]
+            };
+            if expected_next_eval != next_eval {
                 return false;
             }
 
+            merkle_checks.push((index, neg_index));
             primitive_root = primitive_root.square();
         }
 
         //=========================================================================================
-        // Oracle check for the last round
+        // Every fold holds, now the openings: round k's tree has depth num_rounds - k
         //=========================================================================================
-        let check_proof_last_i = merkle_trees[num_rounds - 1].verify_proof(
-            &values_at_index[num_rounds - 1].to_string().as_bytes(),
-            &proofs_at_index[num_rounds - 1],
-            &root_hashes[num_rounds - 1],
-        );
-        let check_proof_neg_last_i = merkle_trees[num_rounds - 1].verify_proof(
-            &values_at_neg_index[num_rounds - 1].to_string().as_bytes(),
-            &proofs_at_neg_index[num_rounds - 1],
-            &root_hashes[num_rounds - 1],
-        );
-
-        if !check_proof_last_i && !check_proof_neg_last_i {
-            return false;
-        }
-
-        transcript.absorb(&root_hashes[num_rounds - 1]);
-        let r = F::from_be_bytes_mod_order(&transcript.squeeze());
-
-        let f_x = values_at_index[num_rounds - 1];
-        let f_neg_x = values_at_neg_index[num_rounds - 1];
-
-        let omega_i = primitive_root.pow(&[proofs_at_index[num_rounds - 1].leaf_index as u64]);
-
-        let sum_term = (f_x + f_neg_x) * F::from(2).inverse().unwrap();
-
-        let diff = f_x - f_neg_x;
-        let omega_i_doubled = omega_i.double();
-        let diff_term = diff * omega_i_doubled.inverse().unwrap();
-
-        let expected_last_eval = sum_term + (r * diff_term);
-
-        proof.final_poly[0] == expected_last_eval
+        merkle_checks
+            .iter()
+            .enumerate()
+            .all(|(round, (index, neg_index))| {
+                let f_x = values_at_index[round].to_string();
+                let f_neg_x = values_at_neg_index[round].to_string();
+                MerkleTree::verify_multiproof(
+                    &[],
+                    &[(*index, f_x.as_bytes()), (*neg_index, f_neg_x.as_bytes())],
+                    &proof.openings[round],
+                    num_rounds - round,
+                    &root_hashes[round],
+                )
+            })
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
 mod tests {
     use super::*;
+    use crate::transcript::test::domain;
     use ark_bn254::Fr;
 
     #[test]
@@ -302,7 +300,7 @@ mod tests {
         let poly: Vec<ark_ff::Fp<ark_ff::MontBackend<ark_bn254::FrConfig, 4>, 4>> =
             vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
         let fri = FRIProtocol::new(poly, 2);
-        fri.optimized_proof();
+        fri.optimized_proof(&domain());
     }
 
     #[test]
@@ -310,8 +308,36 @@ mod tests {
         let poly: Vec<ark_ff::Fp<ark_ff::MontBackend<ark_bn254::FrConfig, 4>, 4>> =
             vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
         let fri = FRIProtocol::new(poly, 2);
-        let proof = fri.optimized_proof();
+        let proof = fri.optimized_proof(&domain());
+
+        assert!(fri.optimized_verify(&proof, &domain()));
+    }
+
+    #[test]
+    fn test_optimized_verify_every_index() {
+        // each context squeezes another index, the fold has to line up for all of them
+        let poly: Vec<Fr> = (1..=8).map(Fr::from).collect();
+        let fri = FRIProtocol::new(poly, 2);
+        for context in 0..16u8 {
+            let domain = TranscriptDomain::new(&[context], [0; 32]);
+            let proof = fri.optimized_proof(&domain);
+            assert!(fri.optimized_verify(&proof, &domain));
+        }
+    }
+
+    #[test]
+    fn test_optimized_verify_rejects_tampering() {
+        let poly = vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
+        let fri = FRIProtocol::new(poly, 2);
+
+        // a fold that doesn't reach the next round's opening
+        let mut proof = fri.optimized_proof(&domain());
+        proof.values_at_neg_index[1] += Fr::from(1);
+        assert!(!fri.optimized_verify(&proof, &domain()));
 
-        assert!(fri.optimized_verify(proof));
+        // consistent folds over values that aren't in the committed tree
+        let mut proof = fri.optimized_proof(&domain());
+        proof.openings[0].siblings[0] = proof.root_hashes[1].clone();
+        assert!(!fri.optimized_verify(&proof, &domain()));
     }
 }
@@ -1,6 +1,30 @@
+pub mod chunked_sum_check;
+pub mod circuit_limits;
+pub mod committed_output_gkr;
+pub mod core;
+pub mod densify;
+pub mod epilogue;
 pub mod gkr_2_to_1_trick;
 pub mod gkr_circuit;
 pub mod gkr_protocol;
+pub mod holographic_gkr;
+pub mod interactive_gkr;
+pub mod lazy_inputs;
+pub mod lookup;
+pub mod named_wires;
+pub mod padded_layer;
 pub mod partial_sum_check;
-pub mod product_poly;
+pub mod prepared_verifier;
+pub mod proof_view;
+pub mod prover_state;
+pub mod proving_session;
+pub mod public_inputs;
+pub mod streaming_gkr;
 pub mod succinct_gkr;
+#[cfg(feature = "prover")]
+pub mod test_circuits;
+pub mod trace;
+pub mod verifying_key;
+
+// the sum of products the GKR sum-checks run on is a zk-primitives poly
+pub use zk_primitives::product_poly;
@@ -0,0 +1,338 @@
+use crate::{
+    gkr::{
+        gkr_2_to_1_trick::LayerReduction,
+        partial_sum_check::{start_transcript, Proof},
+    },
+    multi_linear::MultiLinearPoly,
+    parallel::{Executor, Parallelism},
+    progress::{never_cancelled, Cancelled, Progress, ProgressEvent},
+    transcript::Transcript,
+    transcript_schedule::Step,
+};
+use ark_ff::PrimeField;
+use std::{ops::Range, sync::Arc};
+
+//=========================================================================================
+// Memory bounded layer sum-check
+// the exploded tables Wᵢ₊₁(b) and Wᵢ₊₁(c) (and their sum/product) have n² entries but only
+// n distinct values: Wᵢ₊₁(b) at index b * n + c is W[b] and Wᵢ₊₁(c) is W[c]
+// ExplodedW keeps the two n sized halves, folding a b variable only touches the b half,
+// and the round evaluations are accumulated over chunks of chunk_len indices
+// so no n² table of W values is ever allocated
+// the output is the same partial_sum_check::Proof (same round polys, same challenges)
+// the chunks of a round are split over config.parallelism (see parallel), each thread sums its
+// own range and the partial round polys are added in order, so the proof does not change
+// NOTE: addᵢ/mulᵢ with the output variables fixed are still dense n² tables
+//=========================================================================================
+#[derive(Debug, Clone, Default)]
+pub struct ProverConfig {
+    pub chunk_len: Option<usize>, // None materializes the exploded tables (the default prover)
+    pub reduction: LayerReduction, // how two claims on a layer become one
+    pub debug_checks: bool, // recompute every layer's final claim from the trace, see ProverState::check_layer_proof
+    pub parallelism: Parallelism, // only the chunked prover splits its rounds, Sequential by default
+}
+
+impl ProverConfig {
+    pub fn chunked(chunk_len: usize) -> Self {
+        if chunk_len == 0 {
+            panic!("The chunk length must be greater than 0");
+        }
+
+        ProverConfig {
+            chunk_len: Some(chunk_len),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_reduction(self, reduction: LayerReduction) -> Self {
+        ProverConfig { reduction, ..self }
+    }
+
+    // at most threads scoped threads per round, the library's own
+    pub fn with_threads(self, threads: usize) -> Self {
+        self.with_parallelism(Parallelism::Threads(threads))
+    }
+
+    // the rounds run on the caller's pool instead
+    pub fn with_pool(self, pool: Arc<dyn Executor>) -> Self {
+        self.with_parallelism(Parallelism::Pool(pool))
+    }
+
+    pub fn with_parallelism(self, parallelism: Parallelism) -> Self {
+        ProverConfig {
+            parallelism,
+            ..self
+        }
+    }
+
+    pub fn with_debug_checks(self) -> Self {
+        ProverConfig {
+            debug_checks: true,
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExplodedW<F: PrimeField> {
+    w_b: Vec<F>, // b variables still free
+    w_c: Vec<F>, // c variables still free
+}
+
+impl<F: PrimeField> ExplodedW<F> {
+    pub fn new(layer: &[F]) -> Self {
+        ExplodedW {
+            w_b: layer.to_vec(),
+            w_c: layer.to_vec(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.w_b.len() * self.w_c.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // (Wᵢ₊₁(b), Wᵢ₊₁(c)) at index b * n + c of the exploded tables
+    pub fn get(&self, index: usize) -> (F, F) {
+        let c_len = self.w_c.len();
+
+        (self.w_b[index / c_len], self.w_c[index % c_len])
+    }
+
+    // binds the first free variable, the b variables come first
+    pub fn fold(&mut self, challenge: F) {
+        if self.w_b.len() > 1 {
+            self.w_b = fold_first_var(&self.w_b, challenge);
+        } else {
+            self.w_c = fold_first_var(&self.w_c, challenge);
+        }
+    }
+}
+
+fn fold_first_var<F: PrimeField>(values: &[F], challenge: F) -> Vec<F> {
+    let half = values.len() / 2;
+
+    (0..half)
+        .map(|i| values[i] + (values[i + half] - values[i]) * challenge)
+        .collect()
+}
+
+// both products are a wiring poly times a W term, so every round poly has degree 2
+pub(crate) const LAYER_DEGREE: usize = 2;
+
+// addᵢ(b, c)(Wᵢ₊₁(b) + Wᵢ₊₁(c)) + mulᵢ(b, c)(Wᵢ₊₁(b) * Wᵢ₊₁(c)) without the exploded tables
+pub struct ChunkedLayerPoly<F: PrimeField> {
+    add_i: Vec<F>,
+    mul_i: Vec<F>,
+    w: ExplodedW<F>,
+    chunk_len: usize,
+    parallelism: Parallelism,
+}
+
+impl<F: PrimeField> ChunkedLayerPoly<F> {
+    pub fn new(
+        add_i: MultiLinearPoly<F>,
+        mul_i: MultiLinearPoly<F>,
+        next_layer: &[F],
+        chunk_len: usize,
+    ) -> Self {
+        let w = ExplodedW::new(next_layer);
+        if add_i.computation.len() != w.len() || mul_i.computation.len() != w.len() {
+            panic!("addᵢ and mulᵢ must have one entry per (b, c) pair");
+        }
+
+        ChunkedLayerPoly {
+            add_i: add_i.computation,
+            mul_i: mul_i.computation,
+            w,
+            chunk_len,
+            parallelism: Parallelism::Sequential,
+        }
+    }
+
+    pub fn with_parallelism(self, parallelism: Parallelism) -> Self {
+        ChunkedLayerPoly {
+            parallelism,
+            ..self
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.add_i.len().ilog2() as usize
+    }
+
+    // [g(0), g(1), g(2)] for the first free variable, same values as partial_sum_check::round_poly
+    pub fn round_poly(&self) -> Vec<F> {
+        let half = self.add_i.len() / 2;
+
+        self.parallelism
+            .map_ranges(half, self.chunk_len, |range| {
+                self.round_poly_over(range, half)
+            })
+            .into_iter()
+            .fold(vec![F::zero(); 3], |mut evals, partial| {
+                for (eval, part) in evals.iter_mut().zip(partial) {
+                    *eval += part;
+                }
+                evals
+            })
+    }
+
+    // the round poly restricted to the pairs (i, i + half) with i in range, chunk by chunk
+    fn round_poly_over(&self, range: Range<usize>, half: usize) -> Vec<F> {
+        let mut evals = vec![F::zero(); 3];
+
+        let mut chunk_start = range.start;
+        while chunk_start < range.end {
+            let chunk_end = (chunk_start + self.chunk_len).min(range.end);
+
+            for (t, eval) in evals.iter_mut().enumerate() {
+                let t = F::from(t as u64);
+                let at_t = |lo: F, hi: F| lo + (hi - lo) * t;
+
+                for i in chunk_start..chunk_end {
+                    let (w_b_lo, w_c_lo) = self.w.get(i);
+                    let (w_b_hi, w_c_hi) = self.w.get(i + half);
+
+                    let sum_term = at_t(w_b_lo + w_c_lo, w_b_hi + w_c_hi);
+                    let mul_term = at_t(w_b_lo * w_c_lo, w_b_hi * w_c_hi);
+
+                    *eval += (at_t(self.add_i[i], self.add_i[i + half]) * sum_term)
+                        + (at_t(self.mul_i[i], self.mul_i[i + half]) * mul_term);
+                }
+            }
+
+            chunk_start = chunk_end;
+        }
+
+        evals
+    }
+
+    pub fn fold(&mut self, challenge: F) {
+        self.add_i = fold_first_var(&self.add_i, challenge);
+        self.mul_i = fold_first_var(&self.mul_i, challenge);
+        self.w.fold(challenge);
+    }
+}
+
+// same transcript as partial_sum_check::proof so the proofs are interchangeable
+pub fn proof<F: PrimeField>(layer_poly: ChunkedLayerPoly<F>, init_claimed_sum: F) -> Proof<F> {
+    never_cancelled(proof_with_progress(
+        &Transcript::new(),
+        layer_poly,
+        init_claimed_sum,
+        None,
+        &mut Progress::none(),
+    ))
+}
+
+// same proof with a Round reported after every round, as in partial_sum_check
+pub fn proof_with_progress<F: PrimeField>(
+    transcript: &Transcript,
+    mut layer_poly: ChunkedLayerPoly<F>,
+    init_claimed_sum: F,
+    layer: Option<usize>,
+    progress: &mut Progress,
+) -> Result<Proof<F>, Cancelled> {
+    let num_rounds = layer_poly.num_vars();
+    let mut transcript =
+        start_transcript(transcript, num_rounds, LAYER_DEGREE, 2, init_claimed_sum);
+    let mut challenges = Vec::with_capacity(num_rounds);
+    let mut round_polys = Vec::with_capacity(num_rounds);
+
+    for round in 0..num_rounds {
+        let round_poly = layer_poly.round_poly();
+        transcript
+            .follow(Step::Absorb("round poly"))
+            .absorb(&MultiLinearPoly::to_bytes(&round_poly));
+
+        let challenge = transcript.follow(Step::Squeeze("r")).squeeze_field::<F>();
+        layer_poly.fold(challenge);
+
+        challenges.push(challenge);
+        round_polys.push(round_poly);
+        progress.report(ProgressEvent::Round {
+            layer,
+            done: round + 1,
+            total: num_rounds,
+        })?;
+    }
+
+    Ok(Proof {
+        degree: LAYER_DEGREE,
+        num_products: 2, // add * (W(b) + W(c)) and mul * (W(b) * W(c))
+        init_claimed_sum,
+        challenges,
+        round_polys,
+    })
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::gkr::{
+        gkr_circuit::{test::setup_test_circuit8, Circuit},
+        partial_sum_check,
+        prover_state::ProverState,
+    };
+    use crate::transcript::test::domain;
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_exploded_w_matches_explode_layer() {
+        let layer = [3, 5, 7, 11].map(Fq::from);
+        let (w_i_b, w_i_c) = Circuit::explode_layer(&layer);
+        let exploded = ExplodedW::new(&layer);
+
+        assert_eq!(exploded.len(), w_i_b.len());
+        for i in 0..exploded.len() {
+            assert_eq!(exploded.get(i), (w_i_b[i], w_i_c[i]));
+        }
+
+        // folding all b variables leaves W(r_b) next to the untouched W(c)
+        let mut exploded = ExplodedW::new(&layer);
+        exploded.fold(Fq::from(2));
+        exploded.fold(Fq::from(3));
+        let w_r = MultiLinearPoly::new(&layer).evaluate(&[Fq::from(2), Fq::from(3)]);
+        assert_eq!(exploded.get(1), (w_r.computation[0], layer[1]));
+    }
+
+    #[test]
+    fn test_chunked_proof_matches_partial_sum_check() {
+        let circuit = setup_test_circuit8();
+        let state = ProverState::new(&circuit);
+        let r_a = [Fq::from(9)];
+
+        let (add_i, mul_i) = circuit.layer_i_add_mul_at(3, &r_a);
+        let expected = partial_sum_check::proof(
+            state.layer_sum_poly(2, add_i.clone(), mul_i.clone()),
+            Fq::from(0),
+        );
+
+        for chunk_len in [1, 3, 64] {
+            let layer_poly =
+                ChunkedLayerPoly::new(add_i.clone(), mul_i.clone(), &state.layer(2), chunk_len);
+            let result = proof(layer_poly, Fq::from(0));
+
+            assert_eq!(result.round_polys, expected.round_polys);
+            assert_eq!(result.challenges, expected.challenges);
+        }
+    }
+
+    #[test]
+    fn test_threads_do_not_change_the_proof() {
+        let circuit = setup_test_circuit8();
+        let sequential = circuit.proof_with_config(&ProverConfig::chunked(2), &domain());
+
+        for config in [
+            ProverConfig::chunked(2).with_threads(3),
+            ProverConfig::chunked(1).with_threads(64),
+        ] {
+            let proof = circuit.proof_with_config(&config, &domain());
+            assert_eq!(proof.to_bytes().unwrap(), sequential.to_bytes().unwrap());
+        }
+    }
+}
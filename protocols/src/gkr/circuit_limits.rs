@@ -0,0 +1,257 @@
+use crate::{
+    gkr::{
+        committed_output_gkr::{CommittedOutputGKRProof, CommittedStatement},
+        gkr_circuit::Circuit,
+    },
+    kzg::trusted_setup::{TrustedSetup, VerifierSetup},
+    transcript::TranscriptDomain,
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use std::fmt;
+
+//=========================================================================================
+// Limits on the shape of a circuit, checked before anything is allocated for it
+// a circuit that is too deep, has a layer too wide for the prover's memory (the wiring tables
+// of a layer are n² entries) or inputs bigger than the SRS otherwise fails somewhere inside a
+// prover, an index out of bounds in an MSM or a trim that panics, with nothing that says why
+// check() gives a LimitError instead, CircuitBuilder::build_with_limits (in zk-frontends) runs
+// it at build time and try_committed_output_proof at prove time, with the input limit taken
+// from the setup
+//=========================================================================================
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitLimits {
+    pub max_layers: usize,          // gate layers, the input layer not counted
+    pub max_gates_per_layer: usize, // in any one layer
+    pub max_input_vars: usize,      // the inputs padded to a power of two, 2^max_input_vars
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitError {
+    TooManyLayers {
+        layers: usize,
+        max: usize,
+    },
+    TooManyGates {
+        layer: usize,
+        gates: usize,
+        max: usize,
+    },
+    InputsExceedSetup {
+        num_vars: usize,
+        max: usize,
+    },
+    SetupNotTrimmed {
+        num_vars: usize,
+        setup_num_vars: usize,
+    }, // see TrustedSetup::trim_for_circuit
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitError::TooManyLayers { layers, max } => write!(
+                f,
+                "the circuit has {} layers, more than the limit of {}",
+                layers, max
+            ),
+            LimitError::TooManyGates { layer, gates, max } => write!(
+                f,
+                "layer {} has {} gates, more than the limit of {}",
+                layer, gates, max
+            ),
+            LimitError::InputsExceedSetup { num_vars, max } => write!(
+                f,
+                "circuit exceeds SRS size 2^{}: its inputs need 2^{}",
+                max, num_vars
+            ),
+            LimitError::SetupNotTrimmed {
+                num_vars,
+                setup_num_vars,
+            } => write!(
+                f,
+                "the SRS has size 2^{} but the circuit's inputs are 2^{}, trim it to the circuit",
+                setup_num_vars, num_vars
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LimitError {}
+
+impl Default for CircuitLimits {
+    fn default() -> Self {
+        CircuitLimits {
+            max_layers: 1 << 12,
+            max_gates_per_layer: 1 << 22,
+            max_input_vars: 26,
+        }
+    }
+}
+
+impl CircuitLimits {
+    // the default limits with the inputs bounded by what the setup can commit to
+    pub fn for_setup<P: Pairing>(setup: &VerifierSetup<P>) -> Self {
+        CircuitLimits::default().with_max_input_vars(setup.max_num_vars())
+    }
+
+    pub fn with_max_layers(self, max_layers: usize) -> Self {
+        CircuitLimits { max_layers, ..self }
+    }
+
+    pub fn with_max_gates_per_layer(self, max_gates_per_layer: usize) -> Self {
+        CircuitLimits {
+            max_gates_per_layer,
+            ..self
+        }
+    }
+
+    pub fn with_max_input_vars(self, max_input_vars: usize) -> Self {
+        CircuitLimits {
+            max_input_vars,
+            ..self
+        }
+    }
+
+    // layers first, the input size needs layer 1 to be there
+    pub fn check<F: PrimeField>(&self, circuit: &Circuit<F>) -> Result<(), LimitError> {
+        if circuit.layers.len() > self.max_layers {
+            return Err(LimitError::TooManyLayers {
+                layers: circuit.layers.len(),
+                max: self.max_layers,
+            });
+        }
+        for (layer, gates) in circuit.layers.iter().enumerate() {
+            if gates.gates.len() > self.max_gates_per_layer {
+                return Err(LimitError::TooManyGates {
+                    layer: layer + 1,
+                    gates: gates.gates.len(),
+                    max: self.max_gates_per_layer,
+                });
+            }
+        }
+
+        let num_vars = circuit.input_num_vars();
+        if num_vars > self.max_input_vars {
+            return Err(LimitError::InputsExceedSetup {
+                num_vars,
+                max: self.max_input_vars,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: PrimeField> Circuit<F> {
+    // committed_output_proof once the circuit fits both limits and the setup, which has to be
+    // exactly the size of the inputs (a bigger one is trimmed by the caller, the verifier needs
+    // the same trimmed setup)
+    pub fn try_committed_output_proof<P: Pairing>(
+        &self,
+        setup: &TrustedSetup<P>,
+        limits: &CircuitLimits,
+        transcript_domain: &TranscriptDomain,
+    ) -> Result<(CommittedStatement<P>, CommittedOutputGKRProof<F, P>), LimitError> {
+        let setup_num_vars = setup.max_num_vars();
+        limits
+            .with_max_input_vars(limits.max_input_vars.min(setup_num_vars))
+            .check(self)?;
+        let num_vars = self.input_num_vars();
+        if num_vars != setup_num_vars {
+            return Err(LimitError::SetupNotTrimmed {
+                num_vars,
+                setup_num_vars,
+            });
+        }
+
+        Ok(self.committed_output_proof(setup, transcript_domain))
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::{
+        gkr::{gkr_circuit::GateOp, test_circuits::binary_tree_circuit},
+        kzg::trusted_setup::tests::setup_of_size,
+        transcript::test::domain,
+    };
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    // ((1 + 2) * (3 * 4)) + ((5 * 6) * (7 * 8)), 3 input vars
+    fn setup_test_circuit() -> Circuit<BlsFr> {
+        binary_tree_circuit(
+            (1..=8).map(BlsFr::from).collect(),
+            &[
+                &[GateOp::Add, GateOp::Mul, GateOp::Mul, GateOp::Mul],
+                &[GateOp::Mul, GateOp::Mul],
+                &[GateOp::Add],
+            ],
+        )
+    }
+
+    #[test]
+    fn test_check_limits() {
+        let circuit = setup_test_circuit();
+        assert_eq!(CircuitLimits::default().check(&circuit), Ok(()));
+
+        assert_eq!(
+            CircuitLimits::default().with_max_layers(2).check(&circuit),
+            Err(LimitError::TooManyLayers { layers: 3, max: 2 })
+        );
+        assert_eq!(
+            CircuitLimits::default()
+                .with_max_gates_per_layer(2)
+                .check(&circuit),
+            Err(LimitError::TooManyGates {
+                layer: 1,
+                gates: 4,
+                max: 2
+            })
+        );
+        assert_eq!(
+            CircuitLimits::for_setup(&setup_of_size(2).verifier_setup).check(&circuit),
+            Err(LimitError::InputsExceedSetup {
+                num_vars: 3,
+                max: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_committed_output_proof() {
+        let circuit = setup_test_circuit();
+        let limits = CircuitLimits::default();
+
+        let small = setup_of_size(2);
+        let err = circuit
+            .try_committed_output_proof(&small, &limits, &domain())
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "circuit exceeds SRS size 2^2: its inputs need 2^3"
+        );
+
+        let big = setup_of_size(4);
+        assert!(matches!(
+            circuit.try_committed_output_proof(&big, &limits, &domain()),
+            Err(LimitError::SetupNotTrimmed {
+                num_vars: 3,
+                setup_num_vars: 4
+            })
+        ));
+
+        let trimmed = big.trim_for_circuit(&circuit);
+        let (statement, proof) = circuit
+            .try_committed_output_proof(&trimmed, &limits, &domain())
+            .unwrap();
+        assert!(circuit.committed_output_verify::<Bls12_381>(
+            &statement,
+            &proof,
+            &trimmed.verifier_setup,
+            &domain()
+        ));
+    }
+}
@@ -0,0 +1,442 @@
+use crate::{
+    eval_claim::{EvalClaim, PolyId},
+    gkr::{
+        gkr_2_to_1_trick::squeeze_alpha_beta,
+        gkr_circuit::Circuit,
+        gkr_protocol::layer_f_eval,
+        partial_sum_check::{self, Proof},
+        prover_state::ProverState,
+        succinct_gkr::InputOpening,
+    },
+    kzg::{
+        kzg_helper_functions::compute_commitment,
+        kzg_protocol::{self, KZGProof},
+        trusted_setup::{TrustedSetup, VerifierSetup},
+    },
+    multi_linear::{EvaluationTable, MultiLinearPoly},
+    transcript::{DigestTag, Transcript, TranscriptDomain, DIGEST_LEN},
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// GKR where neither the inputs nor the outputs are revealed, only KZG commitments to them
+// the statement is (circuit digest, input commitment, output commitment), the three values
+// that would be posted on chain, everything else (inputs, outputs, layers) is witness
+// the prover absorbs the statement, squeezes r_a and opens W₀ at r_a, so the first
+// sum-check claim W₀(r_a) is bound to the committed outputs instead of a raw output layer
+// the input layer is opened at r_b and r_c at the end just like the succinct GKR
+// the verifier only reads the circuit's wiring (which the digest pins), never its inputs
+// W₀ has fewer variables than the inputs so it is committed with the trimmed setup
+// NOTE: KZG commitments are not hiding and GKR itself is not zero knowledge (the sum-check
+// messages leak evaluations), so this keeps outputs off the wire but is not full privacy
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommittedStatement<P: Pairing> {
+    pub circuit_digest: [u8; DIGEST_LEN], // Circuit::digest, the wiring being proved
+    pub input_commitment: P::G1,
+    pub output_commitment: P::G1, // to W₀ padded to a power of two, under the trimmed setup
+}
+
+impl<P: Pairing> CommittedStatement<P> {
+    // first thing in the transcript for both the prover and the verifier
+    pub fn absorb_into(&self, transcript: &mut Transcript) {
+        transcript.absorb_digest(DigestTag::Circuit, &self.circuit_digest);
+        transcript.absorb_commitment(&self.input_commitment);
+        transcript.absorb_commitment(&self.output_commitment);
+    }
+}
+
+pub struct CommittedOutputGKRProof<F: PrimeField, P: Pairing> {
+    pub output_value: F,                                  // W₀(r_a)
+    pub output_quotients: Vec<P::G1>,                     // opening of the output commitment at r_a
+    pub w_i_evals: Vec<(F, F)>,                           // array of wᵢ evaluated at r_b and r_c
+    pub p_proofs: Vec<Proof<F>>,                          // array of sum-check proofs
+    pub input_openings: Vec<InputOpening<F, Vec<P::G1>>>, // at r_b, then at r_c
+}
+
+impl<F: PrimeField> Circuit<F> {
+    // the statement this circuit's inputs and outputs commit to under the setup
+    pub fn committed_statement<P: Pairing>(
+        &self,
+        setup: &TrustedSetup<P>,
+    ) -> CommittedStatement<P> {
+        self.statement_from_state(&ProverState::new(self), setup)
+    }
+
+    fn statement_from_state<P: Pairing>(
+        &self,
+        state: &ProverState<F>,
+        setup: &TrustedSetup<P>,
+    ) -> CommittedStatement<P> {
+        let w_0_arr = state.padded_output_layer().into_padded();
+        let output_setup = setup.trim(w_0_arr.len().ilog2() as usize);
+
+        CommittedStatement {
+            circuit_digest: self.digest(),
+            input_commitment: compute_commitment::<F, P>(
+                &self.input_poly(&self.inputs),
+                &setup.g1_arr,
+            )
+            .expect("Circuit inputs do not match the trusted setup size"),
+            output_commitment: compute_commitment::<F, P>(
+                &MultiLinearPoly::new(&w_0_arr),
+                &output_setup.g1_arr,
+            )
+            .expect("Output layer does not match the trimmed setup size"),
+        }
+    }
+
+    pub fn committed_output_proof<P: Pairing>(
+        &self,
+        setup: &TrustedSetup<P>,
+        transcript_domain: &TranscriptDomain,
+    ) -> (CommittedStatement<P>, CommittedOutputGKRProof<F, P>) {
+        let mut transcript = transcript_domain.transcript();
+        let state = ProverState::new(self);
+        let circuit_len = state.circuit_len();
+
+        //=========================================================================================
+        // The statement, then r_a and the opening of W₀(r_a)
+        //=========================================================================================
+        let statement = self.statement_from_state(&state, setup);
+        statement.absorb_into(&mut transcript);
+
+        let input_poly = self.input_poly(&self.inputs);
+        let w_0_arr = state.padded_output_layer().into_padded();
+        let w_0_len = w_0_arr.len().ilog2() as usize;
+        let output_setup = setup.trim(w_0_len);
+        let r_a_challenges: Vec<F> = (0..w_0_len)
+            .map(|_| transcript.squeeze_field::<F>())
+            .collect();
+
+        let output_opening = kzg_protocol::proof::<F, P>(
+            MultiLinearPoly::new(&w_0_arr),
+            &output_setup.g1_arr,
+            &r_a_challenges,
+        );
+
+        //=========================================================================================
+        // GKR layers, starting from the opened claim W₀(r_a)
+        //=========================================================================================
+        let mut claimed_sum = output_opening.poly_opened;
+        let (add_i, mul_i) = self.layer_i_add_mul_at(circuit_len, &r_a_challenges);
+        let mut sum_poly = state.layer_sum_poly(circuit_len - 1, add_i, mul_i);
+
+        let mut w_i_evals = Vec::new();
+        let mut p_proofs = Vec::new();
+
+        for layer_idx in (1..=circuit_len).rev() {
+            let p_proof =
+                partial_sum_check::proof_in::<F>(&transcript, sum_poly.clone(), claimed_sum);
+            let (w_i_b, w_i_c) = state.w_i_evals(layer_idx - 1, &p_proof.challenges);
+            transcript.absorb(&MultiLinearPoly::to_bytes(&[w_i_b, w_i_c]));
+
+            if layer_idx > 1 {
+                let (alpha, beta) = squeeze_alpha_beta(&mut transcript);
+                claimed_sum = (alpha * w_i_b) + (beta * w_i_c);
+                let (new_add, new_mul) =
+                    self.gkr_trick(&p_proof.challenges, layer_idx - 1, (alpha, beta));
+                sum_poly = state.layer_sum_poly(layer_idx - 2, new_add, new_mul);
+            }
+
+            w_i_evals.push((w_i_b, w_i_c));
+            p_proofs.push(p_proof);
+        }
+
+        //=========================================================================================
+        // KZG openings of the input layer at r_b and r_c
+        //=========================================================================================
+        let final_challenges = &p_proofs.last().unwrap().challenges;
+        let mid = final_challenges.len() / 2;
+        let (r_b_challenges, r_c_challenges) = final_challenges.split_at(mid);
+
+        let input_openings = [r_b_challenges, r_c_challenges]
+            .into_iter()
+            .map(|point| {
+                let opening = kzg_protocol::proof::<F, P>(input_poly.clone(), &setup.g1_arr, point);
+                InputOpening {
+                    point: point.to_vec(),
+                    value: opening.poly_opened,
+                    quotients: opening.quotient_evals,
+                }
+            })
+            .collect();
+
+        let proof = CommittedOutputGKRProof {
+            output_value: output_opening.poly_opened,
+            output_quotients: output_opening.quotient_evals,
+            w_i_evals,
+            p_proofs,
+            input_openings,
+        };
+
+        (statement, proof)
+    }
+
+    pub fn committed_output_verify<P: Pairing>(
+        &self,
+        statement: &CommittedStatement<P>,
+        proof: &CommittedOutputGKRProof<F, P>,
+        verifier_setup: &VerifierSetup<P>,
+        transcript_domain: &TranscriptDomain,
+    ) -> bool {
+        // the digest is what the statement names, the wiring used below must be that circuit
+        if self.digest() != statement.circuit_digest {
+            return false;
+        }
+
+        let circuit_len = self.layers.len();
+        if proof.p_proofs.len() != circuit_len || proof.w_i_evals.len() != circuit_len {
+            return false;
+        }
+
+        let w_0_len = proof.output_quotients.len();
+        if w_0_len > verifier_setup.max_num_vars() {
+            return false;
+        }
+
+        //=========================================================================================
+        // Re-derive r_a from the statement and check the opening of W₀(r_a)
+        //=========================================================================================
+        let mut transcript = transcript_domain.transcript();
+        statement.absorb_into(&mut transcript);
+        let r_a_challenges: Vec<F> = (0..w_0_len)
+            .map(|_| transcript.squeeze_field::<F>())
+            .collect();
+
+        let output_opening = KZGProof::<F, P> {
+            commitment: statement.output_commitment,
+            quotient_evals: proof.output_quotients.clone(),
+            poly_opened: proof.output_value,
+        };
+        if !kzg_protocol::verify::<F, P>(
+            &output_opening,
+            &verifier_setup.trim(w_0_len),
+            &r_a_challenges,
+        ) {
+            return false;
+        }
+
+        //=========================================================================================
+        // GKR layers, every sum-check must start from the claim the previous layer reduced to
+        //=========================================================================================
+        let mut claimed_sum = proof.output_value;
+        let (mut add_i, mut mul_i) = self.layer_i_add_mul_at(circuit_len, &r_a_challenges);
+
+        for (i, p_proof) in proof.p_proofs.iter().enumerate() {
+            if p_proof.init_claimed_sum != claimed_sum {
+                return false;
+            }
+
+            let sub_claim = match partial_sum_check::try_verify_in(&transcript, p_proof) {
+                Some(sub_claim) => sub_claim,
+                None => return false,
+            };
+            let table = EvaluationTable::new(&sub_claim.challenges);
+            let add_eval = table.evaluate(&add_i);
+            let mul_eval = table.evaluate(&mul_i);
+
+            let (w_i_b, w_i_c) = proof.w_i_evals[i];
+            transcript.absorb(&MultiLinearPoly::to_bytes(&[w_i_b, w_i_c]));
+
+            if layer_f_eval(add_eval, mul_eval, w_i_b, w_i_c) != sub_claim.last_claimed_sum {
+                return false;
+            }
+
+            let layer_idx = circuit_len - i - 1;
+            if layer_idx == 0 {
+                return self.verify_committed_inputs(
+                    statement,
+                    proof,
+                    verifier_setup,
+                    &sub_claim.challenges,
+                );
+            }
+
+            let (alpha, beta) = squeeze_alpha_beta(&mut transcript);
+            claimed_sum = (alpha * w_i_b) + (beta * w_i_c);
+            (add_i, mul_i) = self.gkr_trick(&sub_claim.challenges, layer_idx, (alpha, beta));
+        }
+
+        false
+    }
+
+    // the last (Wᵢ(r_b), Wᵢ(r_c)) must be openings of the committed inputs at the last sum-check point
+    fn verify_committed_inputs<P: Pairing>(
+        &self,
+        statement: &CommittedStatement<P>,
+        proof: &CommittedOutputGKRProof<F, P>,
+        verifier_setup: &VerifierSetup<P>,
+        challenges: &[F],
+    ) -> bool {
+        let input_claims = match proof.w_i_evals.last() {
+            Some(input_evals) => EvalClaim::pair(PolyId::Input, challenges, *input_evals),
+            None => None,
+        };
+        let input_claims = match input_claims {
+            Some(input_claims) => input_claims,
+            None => return false,
+        };
+        if proof.input_openings.len() != input_claims.len() {
+            return false;
+        }
+
+        let num_vars = verifier_setup.max_num_vars();
+        proof
+            .input_openings
+            .iter()
+            .zip(input_claims.iter())
+            .all(|(opening, claim)| {
+                // kzg_protocol::verify indexes both by the setup's variables
+                if opening.claim(PolyId::Input) != *claim
+                    || claim.point.len() != num_vars
+                    || opening.quotients.len() != num_vars
+                {
+                    return false;
+                }
+
+                let kzg_proof = KZGProof::<F, P> {
+                    commitment: statement.input_commitment,
+                    quotient_evals: opening.quotients.clone(),
+                    poly_opened: claim.value,
+                };
+
+                kzg_protocol::verify::<F, P>(&kzg_proof, verifier_setup, &claim.point)
+            })
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use crate::gkr::{
+        gkr_circuit::{Circuit, GateOp},
+        test_circuits::binary_tree_circuit,
+    };
+    use crate::kzg::trusted_setup::{tests::setup, TrustedSetup};
+    use crate::transcript::test::domain;
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    // ((1 + 2) * (3 * 4)) + ((5 * 6) * (7 * 8)) = 1716
+    fn setup_test_circuit() -> Circuit<BlsFr> {
+        binary_tree_circuit(
+            (1..=8).map(BlsFr::from).collect(),
+            &[
+                &[GateOp::Add, GateOp::Mul, GateOp::Mul, GateOp::Mul],
+                &[GateOp::Mul, GateOp::Mul],
+                &[GateOp::Add],
+            ],
+        )
+    }
+
+    #[test]
+    fn test_committed_output_verify() {
+        let circuit = setup_test_circuit();
+        let setup = TrustedSetup::<Bls12_381>::for_circuit(&circuit, &mut rand::thread_rng());
+
+        let (statement, proof) = circuit.committed_output_proof::<Bls12_381>(&setup, &domain());
+        assert_eq!(statement, circuit.committed_statement(&setup));
+        assert_eq!(proof.output_value, proof.p_proofs[0].init_claimed_sum);
+
+        assert!(circuit.committed_output_verify::<Bls12_381>(
+            &statement,
+            &proof,
+            &setup.verifier_setup,
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_committed_output_rejects_other_outputs() {
+        let circuit = setup_test_circuit();
+        let setup = setup();
+        let (mut statement, proof) = circuit.committed_output_proof::<Bls12_381>(&setup, &domain());
+
+        // a commitment to different outputs changes r_a so the opening no longer verifies
+        let other = TrustedSetup::<Bls12_381>::insecure_from_seed(9, 1);
+        statement.output_commitment = other.g1_arr[0];
+
+        assert!(!circuit.committed_output_verify::<Bls12_381>(
+            &statement,
+            &proof,
+            &setup.verifier_setup,
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_committed_output_rejects_wrong_claim() {
+        let circuit = setup_test_circuit();
+        let setup = setup();
+        let (statement, mut proof) = circuit.committed_output_proof::<Bls12_381>(&setup, &domain());
+
+        proof.output_value += BlsFr::from(1);
+
+        assert!(!circuit.committed_output_verify::<Bls12_381>(
+            &statement,
+            &proof,
+            &setup.verifier_setup,
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_committed_output_rejects_tampered_round_poly() {
+        let circuit = setup_test_circuit();
+        let setup = setup();
+        let (statement, mut proof) = circuit.committed_output_proof::<Bls12_381>(&setup, &domain());
+
+        // a round that doesn't sum to the previous claim is a rejection, not a panic
+        proof.p_proofs[1].round_polys[1][0] += BlsFr::from(1);
+
+        assert!(!circuit.committed_output_verify::<Bls12_381>(
+            &statement,
+            &proof,
+            &setup.verifier_setup,
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_committed_output_verifies_without_inputs() {
+        let circuit = setup_test_circuit();
+        let setup = setup();
+        let (statement, proof) = circuit.committed_output_proof::<Bls12_381>(&setup, &domain());
+
+        // the verifier only has the wiring, the inputs are witness
+        let mut wiring_only = setup_test_circuit();
+        wiring_only.inputs = vec![BlsFr::from(0); wiring_only.inputs.len()];
+        assert!(wiring_only.committed_output_verify::<Bls12_381>(
+            &statement,
+            &proof,
+            &setup.verifier_setup,
+            &domain()
+        ));
+
+        // a statement about other wiring is rejected before anything else
+        let other_circuit = binary_tree_circuit(
+            (1..=8).map(BlsFr::from).collect(),
+            &[
+                &[GateOp::Mul, GateOp::Mul, GateOp::Mul, GateOp::Mul],
+                &[GateOp::Mul, GateOp::Mul],
+                &[GateOp::Add],
+            ],
+        );
+        assert!(!other_circuit.committed_output_verify::<Bls12_381>(
+            &statement,
+            &proof,
+            &setup.verifier_setup,
+            &domain()
+        ));
+
+        // a statement naming other inputs changes r_a and the input openings
+        let mut other_inputs = statement.clone();
+        other_inputs.input_commitment = setup.g1_arr[0];
+        assert!(!circuit.committed_output_verify::<Bls12_381>(
+            &other_inputs,
+            &proof,
+            &setup.verifier_setup,
+            &domain()
+        ));
+    }
+}
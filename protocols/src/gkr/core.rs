@@ -0,0 +1,425 @@
+use crate::{
+    explain::Explain,
+    gkr::gkr_2_to_1_trick::{line_challenge, squeeze_alpha_beta, LayerReduction},
+    gkr::gkr_circuit::Circuit,
+    gkr::gkr_protocol::layer_f_eval,
+    gkr::lookup::LayerLookups,
+    gkr::padded_layer::PaddedLayer,
+    gkr::partial_sum_check,
+    gkr::proof_view::GKRProofSource,
+    lagrange::LagrangeWeights,
+    multi_linear::{EvaluationTable, MultiLinearPoly},
+    transcript::Transcript,
+    transcript_schedule::{Schedule, Step},
+    UnivariatePoly,
+};
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// The layer schedule shared by the plain GKR and the succinct GKR
+// both start from a transcript that already holds their statement (PublicInputs for the plain
+// GKR, the digests, the outputs and the input commitment for the succinct one), from there on:
+//   W₀ if it is sent → r_a → W₀(r_a) is the claim on layer 0
+//   per layer: sum-check → (Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)) → alpha, beta (or q and r*) → the next claim
+//   the sum-check runs in a fork of the transcript at that point, see partial_sum_check
+//   the last pair is the claim on the input layer, checked by whoever knows the inputs
+//   with lookups (see lookup) their claims go in after the statement and a gamma is squeezed
+//   wherever a looked up layer's claim is settled, a plain proof takes none of those steps
+// the prover side is LayerProver, the verifier side is verify_layers and LayerWalk::check_inputs
+// so a change to the order of the transcript happens to both GKR variants at once
+// as schedules (see transcript_schedule) the statement differs and LAYER_STEPS is shared
+//=========================================================================================
+const LAYER_STEPS: [Step; 4] = [
+    Step::Repeat("output layer (more than one output)", &[Step::Absorb("W₀")]),
+    Step::Repeat("output point", &[Step::Squeeze("r_a")]),
+    Step::Repeat("lookup on the output layer", &[Step::Squeeze("gamma")]),
+    Step::Repeat(
+        "layers",
+        &[
+            Step::Absorb("Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)"),
+            Step::Repeat(
+                "alpha, beta (AlphaBeta reduction, not after the input layer)",
+                &[Step::Squeeze("alpha"), Step::Squeeze("beta")],
+            ),
+            Step::Repeat(
+                "line, q, r* (Line reduction, not after the input layer)",
+                &[Step::Absorb("r_b ‖ r_c, q"), Step::Squeeze("r*")],
+            ),
+            Step::Repeat(
+                "lookup on the layer below (not the input layer)",
+                &[Step::Squeeze("gamma")],
+            ),
+        ],
+    ),
+];
+
+pub static GKR_SCHEDULE: Schedule = Schedule {
+    protocol: "gkr",
+    steps: &[
+        Step::Absorb("public inputs"),
+        Step::Repeat(
+            "lookups (each proven in a fork of the transcript)",
+            &[Step::Absorb("lookup claim")],
+        ),
+        LAYER_STEPS[0],
+        LAYER_STEPS[1],
+        LAYER_STEPS[2],
+        LAYER_STEPS[3],
+    ],
+};
+
+pub static SUCCINCT_GKR_SCHEDULE: Schedule = Schedule {
+    protocol: "succinct_gkr",
+    steps: &[
+        Step::Absorb("circuit digest"),
+        Step::Absorb("setup digest"),
+        Step::Absorb("outputs"),
+        Step::Repeat("input commitment", &[Step::Absorb("segment commitment")]),
+        LAYER_STEPS[0],
+        LAYER_STEPS[1],
+        LAYER_STEPS[2],
+        LAYER_STEPS[3],
+    ],
+};
+
+// r_a and the claim W₀(r_a), W₀ goes in first when it is part of the proof
+pub(crate) fn output_claim<F: PrimeField>(
+    transcript: &mut Transcript,
+    w_0: &PaddedLayer<F>,
+    send_w_0: bool,
+) -> (Vec<F>, F) {
+    if send_w_0 {
+        w_0.absorb_into(transcript.follow(Step::Absorb("W₀")));
+    }
+    let r_a: Vec<F> = (0..w_0.num_vars())
+        .map(|_| transcript.follow(Step::Squeeze("r_a")).squeeze_field::<F>())
+        .collect();
+    let w_0_eval = MultiLinearPoly::new(w_0.padded())
+        .evaluate(&r_a)
+        .computation[0];
+
+    (r_a, w_0_eval)
+}
+
+// Wᵢ₊₁(r_b), Wᵢ₊₁(r_c) go in before the challenges of the next reduction come out
+pub(crate) fn absorb_evals<F: PrimeField>(transcript: &mut Transcript, (w_b, w_c): (F, F)) {
+    transcript
+        .follow(Step::Absorb("Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)"))
+        .absorb(&MultiLinearPoly::to_bytes(&[w_b, w_c]));
+}
+
+// the reduction is read off the proof, a line proof has one q per inner layer
+pub(crate) fn reduction_of<F: PrimeField>(proof: &impl GKRProofSource<F>) -> LayerReduction {
+    if proof.line_poly_count() == 0 {
+        LayerReduction::AlphaBeta
+    } else {
+        LayerReduction::Line
+    }
+}
+
+// where the verifier stands after the last layer sum-check: its point r_b ‖ r_c, the claim it
+// ended on and the input layer's wiring at that point
+pub(crate) struct LayerWalk<F: PrimeField> {
+    pub(crate) point: Vec<F>,
+    pub(crate) claimed_sum: F,
+    add: MultiLinearPoly<F>,
+    mul: MultiLinearPoly<F>,
+}
+
+impl<F: PrimeField> LayerWalk<F> {
+    // the oracle check on the input layer
+    // f(b, c) = [add_i(b, c) * (w_i+1(b) + w_i+1(c))] + [mul_i(b,c) * (w_i+1(b) * w_i+1(c))]
+    pub(crate) fn check_inputs(
+        &self,
+        transcript: &mut Transcript,
+        (input_eval_b, input_eval_c): (F, F),
+        explain: &mut Explain,
+    ) -> bool {
+        absorb_evals(transcript, (input_eval_b, input_eval_c));
+        explain.section("inputs");
+        explain.claim("input(r_b)", input_eval_b);
+        explain.claim("input(r_c)", input_eval_c);
+
+        let table = EvaluationTable::new(&self.point);
+        let oracle_check = layer_f_eval(
+            table.evaluate(&self.add),
+            table.evaluate(&self.mul),
+            input_eval_b,
+            input_eval_c,
+        );
+
+        explain.check(
+            "add·(in(r_b) + in(r_c)) + mul·in(r_b)·in(r_c) = last round claim",
+            oracle_check == self.claimed_sum,
+        )
+    }
+}
+
+// absorbs W₀ if it was sent, squeezes r_a and checks every layer sum-check down to the input
+// layer with the lookup claims folded in, None as soon as one check fails
+// the proof shape is the caller's to check, a missing part here is only a rejection
+pub(crate) fn verify_layers<F: PrimeField>(
+    circuit: &Circuit<F>,
+    transcript: &mut Transcript,
+    proof: &impl GKRProofSource<F>,
+    (w_0, send_w_0): (&PaddedLayer<F>, bool),
+    weights: Option<&LagrangeWeights<F>>,
+    lookups: &LayerLookups<F>,
+    explain: &mut Explain,
+) -> Option<LayerWalk<F>> {
+    let circuit_len = circuit.layers.len();
+    let reduction = reduction_of(proof);
+
+    let (r_a_challenges, w_0_eval) = output_claim(transcript, w_0, send_w_0);
+    for (j, r_a) in r_a_challenges.iter().enumerate() {
+        explain.challenge(&format!("r_a[{}]", j), *r_a);
+    }
+
+    // the first sum-check has to start from W₀(r_a), this is what ties the outputs to the proof
+    explain.claim("W₀(r_a)", w_0_eval);
+    let mut wiring = circuit.layer_i_add_mul_at(circuit_len, &r_a_challenges);
+    let mut claim = w_0_eval;
+    if let Some(folded) = lookups.squeeze(transcript, circuit_len) {
+        folded.explain(explain);
+        claim = folded.claim(claim);
+        wiring = folded.wiring(circuit, circuit_len, wiring);
+    }
+    if !explain.check(
+        "layer 0 sum-check starts at W₀(r_a)",
+        proof.init_claimed_sum(0) == Some(claim),
+    ) {
+        return None;
+    }
+
+    let (mut new_add, mut new_mul) = wiring;
+    let mut curr_challenges = Vec::new();
+    let mut current_claimed_sum = F::zero();
+
+    for i in 0..circuit_len {
+        let p_proof = proof.sum_check(i)?;
+        let p_proof = p_proof.as_ref();
+        explain.section(&format!("layer {}", i));
+        let sub_claim = match weights {
+            Some(weights) => {
+                partial_sum_check::try_verify_explained(transcript, p_proof, weights, explain)
+            }
+            None => partial_sum_check::try_verify_explained(
+                transcript,
+                p_proof,
+                &LagrangeWeights::for_degree(p_proof.degree),
+                explain,
+            ),
+        }?;
+        let challenges = sub_claim.challenges.clone();
+
+        curr_challenges = challenges.clone();
+
+        // For all but the last proof, check against w_i_evals
+        if i < circuit_len - 1 {
+            // addᵢ and mulᵢ at the same point share one table of χ weights
+            let table = EvaluationTable::new(&challenges);
+            let new_add_eval = table.evaluate(&new_add);
+            let new_mul_eval = table.evaluate(&new_mul);
+
+            let [claim_b, claim_c] = proof.layer_claims(i, &challenges)?;
+            let (w_i_rb, w_i_rc) = (claim_b.value, claim_c.value);
+            absorb_evals(transcript, (w_i_rb, w_i_rc));
+            explain.claim(&format!("W{}(r_b)", i + 1), w_i_rb);
+            explain.claim(&format!("W{}(r_c)", i + 1), w_i_rc);
+
+            let check = layer_f_eval(new_add_eval, new_mul_eval, w_i_rb, w_i_rc);
+
+            if !explain.check(
+                "add·(W(r_b) + W(r_c)) + mul·W(r_b)·W(r_c) = last round claim",
+                check == sub_claim.last_claimed_sum,
+            ) {
+                return None;
+            }
+
+            let next_layer = circuit_len - i - 1;
+            let (mut claim, mut wiring, label) = match reduction {
+                LayerReduction::AlphaBeta => {
+                    // the next sum-check must start at alpha * Wᵢ(r_b) + beta * Wᵢ(r_c)
+                    let (alpha, beta) = squeeze_alpha_beta(transcript);
+                    explain.challenge("alpha", alpha);
+                    explain.challenge("beta", beta);
+
+                    (
+                        alpha * w_i_rb + beta * w_i_rc,
+                        circuit.gkr_trick(&challenges, next_layer, (alpha, beta)),
+                        "next sum-check starts at alpha·W(r_b) + beta·W(r_c)",
+                    )
+                }
+                LayerReduction::Line => {
+                    // q(0) and q(1) are the two claims, the next sum-check must start at q(r*)
+                    let line_poly = proof.line_poly(i)?;
+                    if line_poly.is_empty() || line_poly.len() > challenges.len() / 2 + 1 {
+                        return None;
+                    }
+                    let q = UnivariatePoly {
+                        coefficient: line_poly.to_vec(),
+                    };
+                    if !explain.check(
+                        "q(0) = W(r_b) and q(1) = W(r_c)",
+                        q.evaluate(F::zero()) == w_i_rb && q.evaluate(F::one()) == w_i_rc,
+                    ) {
+                        return None;
+                    }
+
+                    let r_star = line_challenge(transcript, &challenges, &line_poly);
+                    explain.challenge("r*", r_star);
+
+                    (
+                        q.evaluate(r_star),
+                        circuit.line_add_mul(&challenges, r_star, next_layer),
+                        "next sum-check starts at q(r*)",
+                    )
+                }
+            };
+            if let Some(folded) = lookups.squeeze(transcript, next_layer) {
+                folded.explain(explain);
+                claim = folded.claim(claim);
+                wiring = folded.wiring(circuit, next_layer, wiring);
+            }
+            if !explain.check(label, proof.init_claimed_sum(i + 1) == Some(claim)) {
+                return None;
+            }
+
+            (new_add, new_mul) = wiring;
+        }
+
+        current_claimed_sum = sub_claim.last_claimed_sum;
+    }
+
+    // new_add and new_mul now hold the input layer's reduction from the last loop step
+    Some(LayerWalk {
+        point: curr_challenges,
+        claimed_sum: current_claimed_sum,
+        add: new_add,
+        mul: new_mul,
+    })
+}
+
+// the two GKR variants against each other, layer for layer on the succinct statement
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::{verify_layers, LayerLookups};
+    use crate::{
+        explain::Explain,
+        gkr::{
+            chunked_sum_check::ProverConfig,
+            gkr_circuit::Circuit,
+            gkr_protocol::{GKRProof, LayerProver},
+            prover_state::ProverState,
+            public_inputs::PublicInputs,
+            succinct_gkr::{statement_transcript, SuccinctGKRProof},
+            test_circuits,
+        },
+        kzg::trusted_setup::tests::setup,
+        progress::Progress,
+        transcript::{test::domain, Transcript},
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+
+    fn transcript(circuit: &Circuit<Fr>, proof: &SuccinctGKRProof<Fr, Bls12_381>) -> Transcript {
+        statement_transcript(
+            &domain(),
+            (&proof.circuit_digest, &proof.setup_digest),
+            &PublicInputs::outputs_only(circuit.public_inputs().outputs),
+            &proof.commitment,
+        )
+    }
+
+    // what the plain GKR prover sends after the succinct statement, chunked to not share the
+    // default prover's sum-check code
+    fn plain_layers(
+        circuit: &Circuit<Fr>,
+        proof: &SuccinctGKRProof<Fr, Bls12_381>,
+    ) -> GKRProof<Fr> {
+        let state = ProverState::new(circuit);
+        let mut prover = LayerProver::from_statement(&state, transcript(circuit, proof), true);
+        let (mut w_i_evals, mut p_proofs) = (Vec::new(), Vec::new());
+        while let Some(layer) = prover
+            .prove_layer(&state, &ProverConfig::chunked(2), &mut Progress::none())
+            .unwrap()
+        {
+            w_i_evals.push(layer.w_evals);
+            p_proofs.push(layer.sum_check);
+        }
+
+        GKRProof {
+            output_layer: prover.output_layer,
+            w_i_evals,
+            p_proofs,
+            line_polys: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_plain_prover_makes_the_succinct_layers() {
+        let circuit = test_circuits::circuit8::<Fr>();
+        let setup = setup();
+        let domain = domain();
+        let mut proof = circuit.succinct_proof::<Bls12_381>(&setup, &domain);
+
+        let plain = plain_layers(&circuit, &proof);
+        let succinct = GKRProof {
+            output_layer: proof.output_layer.clone(),
+            w_i_evals: proof.w_i_evals.clone(),
+            p_proofs: proof.p_proofs.clone(),
+            line_polys: Vec::new(),
+        };
+        assert_eq!(plain.to_bytes().unwrap(), succinct.to_bytes().unwrap());
+
+        // and the succinct verifier takes them in place of its own prover's
+        proof.w_i_evals = plain.w_i_evals;
+        proof.p_proofs = plain.p_proofs;
+        assert!(circuit.succinct_verify(
+            &proof,
+            &PublicInputs::outputs_only(circuit.public_inputs().outputs),
+            &setup.verifier_setup,
+            &domain,
+        ));
+    }
+
+    #[test]
+    fn test_plain_layer_checks_accept_succinct_layers() {
+        let circuit = test_circuits::circuit8::<Fr>();
+        let mut proof = circuit.succinct_proof::<Bls12_381>(&setup(), &domain());
+        let w_0 = ProverState::new(&circuit).padded_output_layer();
+
+        // the plain verifier's input check with the inputs in the clear instead of the openings
+        let mut statement = transcript(&circuit, &proof);
+        let walk = verify_layers(
+            &circuit,
+            &mut statement,
+            &proof,
+            (&w_0, true),
+            None,
+            &LayerLookups::none(),
+            &mut Explain::off(),
+        )
+        .unwrap();
+        let (r_b, r_c) = walk.point.split_at(walk.point.len() / 2);
+        let mut input_poly = circuit.input_poly(&circuit.inputs);
+        let input_evals = (
+            input_poly.evaluate(r_b).computation[0],
+            input_poly.evaluate(r_c).computation[0],
+        );
+        assert_eq!(Some(&input_evals), proof.w_i_evals.last());
+        assert!(walk.check_inputs(&mut statement, input_evals, &mut Explain::off()));
+
+        // a claim moved on one layer is caught by the shared checks
+        proof.w_i_evals[0].0 += Fr::from(1);
+        assert!(verify_layers(
+            &circuit,
+            &mut transcript(&circuit, &proof),
+            &proof,
+            (&w_0, true),
+            None,
+            &LayerLookups::none(),
+            &mut Explain::off(),
+        )
+        .is_none());
+    }
+}
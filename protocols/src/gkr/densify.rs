@@ -0,0 +1,221 @@
+use crate::gkr::{
+    gkr_circuit::{Circuit, Gate, GateOp, Layer, WireId},
+    named_wires::CircuitError,
+};
+use ark_ff::PrimeField;
+use std::{collections::BTreeMap, fmt};
+
+//=========================================================================================
+// Renumbering gate outputs densely
+// evaluate_layer writes gate.output into a vector of gates.len() zeros, so a hand written layer
+// with outputs like [0, 4, 9] either panics (9 is past the end) or leaves slots that no gate
+// writes reading as 0, and two gates on the same output silently keep the last one
+// densify_outputs sorts every layer by output and gives its gates the outputs 0..gates.len(),
+// the left/right wires of the layer above and the output names follow the new numbering
+// gates sharing an output each get their own slot, the layer above reads the last of them
+// (the one evaluate used to keep) and the collision is reported as a warning
+// layers are numbered by their position in circuit.layers, 0 is the one above the inputs
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DensifyWarning {
+    OutputCollision {
+        layer: usize,
+        wire: WireId,
+        gates: usize,
+    }, // `gates` gates wrote the same output
+}
+
+impl fmt::Display for DensifyWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DensifyWarning::OutputCollision { layer, wire, gates } => write!(
+                f,
+                "{} gates of layer {} write {}, only the last one is read",
+                gates, layer, wire
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Densified {
+    pub permutation: Vec<BTreeMap<WireId, WireId>>, // per layer, old output -> new output
+    pub warnings: Vec<DensifyWarning>,
+}
+
+impl Densified {
+    // where an output of the last layer ended up, None if no gate wrote it
+    pub fn output(&self, old: WireId) -> Option<WireId> {
+        self.permutation.last()?.get(&old).copied()
+    }
+}
+
+impl<F: PrimeField> Circuit<F> {
+    // true if every layer's outputs are exactly 0..gates.len()
+    pub fn outputs_are_dense(&self) -> bool {
+        self.layers.iter().all(|layer| {
+            let mut seen = vec![false; layer.gates.len()];
+            layer.gates.iter().all(|gate| {
+                let index = gate.output.index();
+                index < seen.len() && !std::mem::replace(&mut seen[index], true)
+            })
+        })
+    }
+
+    // nothing changes if a gate reads a wire of the layer below that no gate writes
+    pub fn densify_outputs(&mut self) -> Result<Densified, CircuitError> {
+        let mut densified = Densified::default();
+        let mut layers = Vec::with_capacity(self.layers.len());
+
+        for (layer_i, layer) in self.layers.iter().enumerate() {
+            let below = if layer_i == 0 {
+                None
+            } else {
+                densified.permutation.last()
+            };
+            let remap = |wire: WireId| match below {
+                None => Ok(wire),
+                Some(map) => map.get(&wire).copied().ok_or(CircuitError::UnwrittenWire {
+                    layer: layer_i - 1,
+                    wire,
+                }),
+            };
+
+            // stable, gates on the same output keep their order
+            let mut gates = layer.gates.clone();
+            gates.sort_by_key(|gate| gate.output);
+
+            let mut permutation = BTreeMap::new();
+            let mut new_gates = Vec::with_capacity(gates.len());
+            for (new_output, gate) in gates.iter().enumerate() {
+                let left = remap(gate.left)?;
+                let right = match gate.op {
+                    GateOp::Square => remap(gate.right).unwrap_or(left),
+                    _ => remap(gate.right)?,
+                };
+
+                permutation.insert(gate.output, WireId(new_output));
+                new_gates.push(Gate {
+                    left,
+                    right,
+                    op: gate.op,
+                    output: WireId(new_output),
+                });
+            }
+
+            for group in gates.chunk_by(|a, b| a.output == b.output) {
+                if group.len() > 1 {
+                    densified.warnings.push(DensifyWarning::OutputCollision {
+                        layer: layer_i,
+                        wire: group[0].output,
+                        gates: group.len(),
+                    });
+                }
+            }
+
+            densified.permutation.push(permutation);
+            layers.push(Layer { gates: new_gates });
+        }
+
+        if let Some(outputs) = densified.permutation.last() {
+            self.names.remap_outputs(self.layers.len() - 1, outputs)?;
+        }
+        self.layers = layers;
+
+        Ok(densified)
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+
+    fn gate(left: usize, right: usize, op: GateOp, output: usize) -> Gate {
+        Gate {
+            left: WireId(left),
+            right: WireId(right),
+            op,
+            output: WireId(output),
+        }
+    }
+
+    // (a + b) * (c * d) with the middle layer written to outputs 3 and 9
+    fn sparse_circuit() -> Circuit<Fq> {
+        let mut circuit = Circuit::new([2, 3, 4, 5].map(Fq::from).to_vec());
+        circuit.add_layer(Layer {
+            gates: vec![gate(2, 3, GateOp::Mul, 9), gate(0, 1, GateOp::Add, 3)],
+        });
+        circuit.add_layer(Layer {
+            gates: vec![gate(3, 9, GateOp::Mul, 5)],
+        });
+
+        circuit
+    }
+
+    #[test]
+    fn test_densify_renumbers_outputs() {
+        let mut circuit = sparse_circuit();
+        assert!(!circuit.outputs_are_dense());
+
+        let densified = circuit.densify_outputs().unwrap();
+        assert!(circuit.outputs_are_dense());
+        assert!(densified.warnings.is_empty());
+        assert_eq!(
+            densified.permutation[0],
+            BTreeMap::from([(WireId(3), WireId(0)), (WireId(9), WireId(1))])
+        );
+        assert_eq!(densified.output(WireId(5)), Some(WireId(0)));
+
+        assert_eq!(circuit.evaluate()[1], vec![Fq::from(5), Fq::from(20)]);
+        assert_eq!(circuit.evaluate()[2], vec![Fq::from(100)]);
+    }
+
+    #[test]
+    fn test_densify_warns_on_collisions() {
+        let mut circuit = Circuit::new([2, 3, 4, 5].map(Fq::from).to_vec());
+        circuit.add_layer(Layer {
+            gates: vec![gate(0, 1, GateOp::Add, 1), gate(2, 3, GateOp::Mul, 1)],
+        });
+        circuit.add_layer(Layer {
+            gates: vec![gate(1, 1, GateOp::Square, 0)],
+        });
+        circuit.name_output("square", WireId(0)).unwrap();
+        let before = circuit.evaluate()[2].clone();
+
+        let densified = circuit.densify_outputs().unwrap();
+        assert_eq!(
+            densified.warnings,
+            [DensifyWarning::OutputCollision {
+                layer: 0,
+                wire: WireId(1),
+                gates: 2
+            }]
+        );
+        assert_eq!(
+            densified.warnings[0].to_string(),
+            "2 gates of layer 0 write wire 1, only the last one is read"
+        );
+
+        // both gates are kept, the layer above still reads the last one
+        assert_eq!(circuit.layers[0].gates.len(), 2);
+        assert_eq!(circuit.evaluate()[2], before);
+        assert_eq!(circuit.output_value("square"), Ok(Fq::from(400)));
+    }
+
+    #[test]
+    fn test_densify_rejects_unwritten_wires() {
+        let mut circuit = sparse_circuit();
+        circuit.layers[1].gates[0].left = WireId(4);
+
+        assert_eq!(
+            circuit.densify_outputs(),
+            Err(CircuitError::UnwrittenWire {
+                layer: 0,
+                wire: WireId(4)
+            })
+        );
+        // untouched
+        assert_eq!(circuit.layers[0].gates[0].output, WireId(9));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::gkr::{
+    gkr_circuit::{Circuit, Gate, GateOp, Layer, Wire, WireId},
+    named_wires::CircuitError,
+    padded_layer::PaddedLayer,
+};
+use ark_ff::PrimeField;
+use std::collections::HashMap;
+
+//=========================================================================================
+// Exposing internal wires as extra outputs
+// a layer only reads the layer below it, so a wire of layer k reaches the outputs by being
+// copied (w + 0) into every layer above it, then an epilogue layer is appended that relays
+// the original outputs first and the exposed wires after them
+// the 0 is a zero input appended to the circuit and carried up as 0 + 0 in every layer, the
+// copies and the zero go after the existing gates so no existing wire moves and output names
+// stay valid, a wire exposed twice or two wires on the same path share their copies
+// wires are addressed like in circuit_builder, layer 0 is the input layer
+// every grown layer is padded back to a power of 2 with more 0 + 0 gates, like the builder does,
+// so the result can still be proven, Circuit::layer_bits handles a layer below that got wider
+//=========================================================================================
+impl<F: PrimeField> Circuit<F> {
+    // returns where each wire ended up in the new output layer
+    pub fn expose_wires(&mut self, wires: &[Wire]) -> Result<Vec<WireId>, CircuitError> {
+        if self.layers.is_empty() {
+            return Err(CircuitError::NoOutputLayer);
+        }
+        if !self.outputs_are_dense() {
+            return Err(CircuitError::SparseOutputs);
+        }
+
+        let depth = self.layers.len();
+        let output_len = self.output_len();
+        for wire in wires.iter() {
+            let len = match wire.layer {
+                0 => self.inputs.len(),
+                layer if layer <= depth => self.layers[layer - 1].gates.len(),
+                _ => 0,
+            };
+            if wire.index >= len {
+                return Err(CircuitError::NoSuchWire {
+                    layer: wire.layer,
+                    wire: WireId(wire.index),
+                });
+            }
+        }
+
+        self.inputs.push(F::zero());
+        let mut zero = WireId(self.inputs.len() - 1);
+        let mut carried: Vec<Option<WireId>> = wires
+            .iter()
+            .map(|wire| (wire.layer == 0).then_some(WireId(wire.index)))
+            .collect();
+
+        for layer_i in 1..=depth {
+            let gates = &mut self.layers[layer_i - 1].gates;
+            let mut push = |left: WireId, right: WireId| {
+                let output = WireId(gates.len());
+                gates.push(Gate {
+                    left,
+                    right,
+                    op: GateOp::Add,
+                    output,
+                });
+                output
+            };
+
+            let below_zero = zero;
+            zero = push(below_zero, below_zero);
+
+            let mut copies: HashMap<WireId, WireId> = HashMap::new();
+            for (wire, carried) in wires.iter().zip(carried.iter_mut()) {
+                *carried = match carried {
+                    _ if wire.layer == layer_i => Some(WireId(wire.index)),
+                    Some(below) => Some(
+                        *copies
+                            .entry(*below)
+                            .or_insert_with(|| push(*below, below_zero)),
+                    ),
+                    None => None,
+                };
+            }
+
+            let padded_len = PaddedLayer::<F>::padded_len(gates.len());
+            let padding = (gates.len()..padded_len).map(|output| Gate {
+                left: below_zero,
+                right: below_zero,
+                op: GateOp::Add,
+                output: WireId(output),
+            });
+            gates.extend(padding);
+        }
+
+        // the original outputs keep their positions, the exposed wires follow
+        let sources = (0..output_len)
+            .map(WireId)
+            .chain(carried.into_iter().flatten());
+        let gates = sources
+            .enumerate()
+            .map(|(output, left)| Gate {
+                left,
+                right: zero,
+                op: GateOp::Add,
+                output: WireId(output),
+            })
+            .collect();
+        self.add_layer(Layer { gates });
+
+        Ok((output_len..output_len + wires.len()).map(WireId).collect())
+    }
+
+    // same as expose_wires with the new outputs named
+    pub fn expose_named(&mut self, wires: &[(&str, Wire)]) -> Result<(), CircuitError> {
+        let exposed: Vec<Wire> = wires.iter().map(|(_, wire)| *wire).collect();
+        let outputs = self.expose_wires(&exposed)?;
+
+        for ((name, _), output) in wires.iter().zip(outputs) {
+            self.name_output(name, output)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{
+        gkr::{public_inputs::PublicInputs, test_circuits::circuit8},
+        transcript::test::domain,
+    };
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_exposed_wires_follow_the_outputs() {
+        let mut circuit = circuit8::<Fq>();
+        let before = circuit.evaluate();
+
+        // an input, a wire two layers down, a wire of the output layer and the first one again
+        let wires = [
+            Wire { layer: 0, index: 5 },
+            Wire { layer: 1, index: 2 },
+            Wire { layer: 3, index: 0 },
+            Wire { layer: 0, index: 5 },
+        ];
+        let outputs = circuit.expose_wires(&wires).unwrap();
+        assert_eq!(outputs, [1, 2, 3, 4].map(WireId));
+
+        let after = circuit.evaluate();
+        let expected = [
+            before[3][0],
+            before[0][5],
+            before[1][2],
+            before[3][0],
+            before[0][5],
+        ];
+        assert_eq!(after.last().unwrap(), &expected);
+        assert_eq!(circuit.layers.len(), 4);
+        assert!(circuit.outputs_are_dense());
+
+        // the input wire is copied once per layer even though it is exposed twice, then the
+        // layer is padded from 4 + 2 gates to 8
+        let copies = circuit.layers[0]
+            .gates
+            .iter()
+            .filter(|gate| gate.left == WireId(5))
+            .count();
+        assert_eq!(copies, 1);
+        assert_eq!(circuit.layers[0].gates.len(), 8);
+    }
+
+    #[test]
+    fn test_exposed_circuit_proves_and_verifies() {
+        let mut circuit = circuit8::<Fq>();
+        let wires = [Wire { layer: 0, index: 5 }, Wire { layer: 1, index: 2 }];
+        circuit.expose_wires(&wires).unwrap();
+        // the new output layer is padded by the provers, every layer below it by expose_wires
+        let (_, below) = circuit.layers.split_last().unwrap();
+        for layer in below.iter() {
+            assert!(layer.gates.len().is_power_of_two());
+        }
+
+        let proof = circuit.proof(&domain());
+        assert!(circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+
+        // a wrong exposed value is rejected
+        let mut outputs = circuit.evaluate().last().unwrap().clone();
+        outputs[2] += Fq::from(1);
+        let public = PublicInputs {
+            inputs: circuit.inputs.clone(),
+            outputs,
+        };
+        assert!(!circuit.verify(&proof, &public, &domain()));
+    }
+
+    #[test]
+    fn test_expose_rejects_unprovable_circuits() {
+        let mut empty = Circuit::new(vec![Fq::from(1), Fq::from(2)]);
+        assert_eq!(
+            empty.expose_wires(&[Wire { layer: 0, index: 0 }]),
+            Err(CircuitError::NoOutputLayer)
+        );
+
+        let mut sparse = circuit8::<Fq>();
+        sparse.layers[2].gates[0].output = WireId(1);
+        assert_eq!(
+            sparse.expose_wires(&[Wire { layer: 0, index: 0 }]),
+            Err(CircuitError::SparseOutputs)
+        );
+        // untouched
+        assert_eq!(sparse.inputs.len(), 8);
+    }
+
+    #[test]
+    fn test_expose_named() {
+        let mut circuit = circuit8::<Fq>();
+        circuit.name_output("root", WireId(0)).unwrap();
+        let layer_2 = circuit.evaluate()[2].clone();
+
+        circuit
+            .expose_named(&[("left", Wire { layer: 2, index: 0 })])
+            .unwrap();
+        assert_eq!(circuit.output_value("root"), Ok(Fq::from(1695)));
+        assert_eq!(circuit.output_value("left"), Ok(layer_2[0]));
+
+        assert_eq!(
+            circuit.expose_wires(&[Wire { layer: 2, index: 7 }]),
+            Err(CircuitError::NoSuchWire {
+                layer: 2,
+                wire: WireId(7)
+            })
+        );
+    }
+}
@@ -1,63 +1,97 @@
-use crate::{gkr::gkr_circuit::Circuit, multi_linear::MultiLinearPoly, transcript::Transcript};
+use crate::{
+    gkr::gkr_circuit::Circuit,
+    multi_linear::{line_point, EvaluationTable, MultiLinearPoly},
+    transcript::Transcript,
+    transcript_schedule::Step,
+    UnivariatePoly,
+};
 use ark_ff::PrimeField;
 
+//=========================================================================================
+// Two ways to turn the two claims Wᵢ(r_b), Wᵢ(r_c) into the one the next sum-check starts from
+// AlphaBeta: claim = α * Wᵢ(r_b) + β * Wᵢ(r_c), addᵢ/mulᵢ get folded the same way
+//   in the Fiat-Shamir provers α, β are squeezed right after (Wᵢ(r_b), Wᵢ(r_c)) are absorbed,
+//   a prover who could pick the two claims after seeing α, β could make them cancel
+// Line: ℓ(t) = r_b + t * (r_c - r_b) so ℓ(0) = r_b and ℓ(1) = r_c
+//   the prover sends q(t) = Wᵢ(ℓ(t)) (deg q <= num_vars), the verifier checks q(0) and q(1)
+//   against the two claims, picks r* and the next claim is q(r*) = Wᵢ(ℓ(r*))
+//   in the Fiat-Shamir provers r* is squeezed from the protocol transcript after ℓ and q went in
+//   addᵢ/mulᵢ are then evaluated at the single point ℓ(r*), no combination needed
+//=========================================================================================
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LayerReduction {
+    #[default]
+    AlphaBeta,
+    Line,
+}
+
+// alpha and beta used to fold Wᵢ(*b) and Wᵢ(*c) into a single claim, drawn from the protocol
+// transcript after the two evaluations went in so they depend on them
+pub fn squeeze_alpha_beta<F: PrimeField>(transcript: &mut Transcript) -> (F, F) {
+    let alpha = transcript
+        .follow(Step::Squeeze("alpha"))
+        .squeeze_field::<F>();
+    let beta = transcript
+        .follow(Step::Squeeze("beta"))
+        .squeeze_field::<F>();
+
+    (alpha, beta)
+}
+
+// (a, b) with ℓ(t) = a + t * b going through r_b at t = 0 and r_c at t = 1
+pub fn line_through<F: PrimeField>(challenges: &[F]) -> (Vec<F>, Vec<F>) {
+    let mid = challenges.len() / 2;
+    let (r_b_challenges, r_c_challenges) = challenges.split_at(mid);
+
+    let direction = r_b_challenges
+        .iter()
+        .zip(r_c_challenges.iter())
+        .map(|(r_b, r_c)| *r_c - r_b)
+        .collect();
+
+    (r_b_challenges.to_vec(), direction)
+}
+
+// r* drawn from the protocol transcript after the line (through the sum-check challenges) and q
+// went in, so it depends on both and on everything the transcript already holds
+pub fn line_challenge<F: PrimeField>(
+    transcript: &mut Transcript,
+    challenges: &[F],
+    line_poly: &[F],
+) -> F {
+    transcript.follow(Step::Absorb("r_b ‖ r_c, q")).absorb(
+        &[
+            MultiLinearPoly::to_bytes(challenges),
+            MultiLinearPoly::to_bytes(line_poly),
+        ]
+        .concat(),
+    );
+
+    transcript.follow(Step::Squeeze("r*")).squeeze_field::<F>()
+}
+
 impl<F: PrimeField> Circuit<F> {
     pub fn gkr_trick(
         &self,
         challenges: &[F],
         index: usize,
+        (alpha, beta): (F, F),
     ) -> (MultiLinearPoly<F>, MultiLinearPoly<F>) {
-        let mut transcript = Transcript::new();
-
-        let alpha = F::from_be_bytes_mod_order(&transcript.squeeze());
-        let beta = F::from_be_bytes_mod_order(&transcript.squeeze());
-
-        let (add_i, mul_i) = self.layer_i_add_mul(index);
-
-        let mut add_rb = MultiLinearPoly::new(&add_i);
-        let mut add_rc = MultiLinearPoly::new(&add_i);
-        let mut mul_rb = MultiLinearPoly::new(&mul_i);
-        let mut mul_rc = MultiLinearPoly::new(&mul_i);
-
         let mid = challenges.len() / 2;
         let (r_b_challenges, r_c_challenges) = challenges.split_at(mid);
-
-        for &r_b in r_b_challenges {
-            add_rb = add_rb.partial_evaluate(r_b, 0);
-            mul_rb = mul_rb.partial_evaluate(r_b, 0);
-        }
-
-        for &r_c in r_c_challenges {
-            add_rc = add_rc.partial_evaluate(r_c, 0);
-            mul_rc = mul_rc.partial_evaluate(r_c, 0);
-        }
-
-        let new_add_values: Vec<F> = add_rb
-            .computation
-            .iter()
-            .zip(add_rc.computation.iter())
-            .map(|(&rb_val, &rc_val)| (alpha * rb_val) + (beta * rc_val))
-            .collect();
-        let new_add = MultiLinearPoly::new(&new_add_values);
-
-        let new_mul_values: Vec<F> = mul_rb
-            .computation
-            .iter()
-            .zip(mul_rc.computation.iter())
-            .map(|(&rb_val, &rc_val)| (alpha * rb_val) + (beta * rc_val))
-            .collect();
-        let new_mul = MultiLinearPoly::new(&new_mul_values);
-
-        (new_add, new_mul)
+        let (rb_table, rc_table) = (
+            EvaluationTable::new(r_b_challenges),
+            EvaluationTable::new(r_c_challenges),
+        );
+
+        // α * addᵢ(r_b, b, c) + β * addᵢ(r_c, b, c), the same for mulᵢ
+        self.layer_i_add_mul_weighted(index, |output| {
+            (alpha * rb_table.weights()[output]) + (beta * rc_table.weights()[output])
+        })
     }
 
-    pub fn new_claimed_sum(&self, w_i_arr: Vec<F>, challenges: &[F]) -> F {
-        let mut transcript = Transcript::new();
-
-        let w_i_eval = MultiLinearPoly::new(&w_i_arr);
-
-        let alpha = F::from_be_bytes_mod_order(&transcript.squeeze());
-        let beta = F::from_be_bytes_mod_order(&transcript.squeeze());
+    pub fn new_claimed_sum(&self, w_i_arr: &[F], challenges: &[F], (alpha, beta): (F, F)) -> F {
+        let w_i_eval = MultiLinearPoly::new(w_i_arr);
 
         let mut w_i_b = w_i_eval.clone();
         let mut w_i_c = w_i_eval;
@@ -83,11 +117,30 @@ impl<F: PrimeField> Circuit<F> {
 
         claimed_sum
     }
+
+    // q(t) = Wᵢ(ℓ(t)), sent by the prover in place of the alpha/beta combination
+    pub fn line_poly(&self, w_i_arr: &[F], challenges: &[F]) -> UnivariatePoly<F> {
+        let (a, b) = line_through(challenges);
+
+        MultiLinearPoly::new(w_i_arr).restrict_to_line(&a, &b)
+    }
+
+    // addᵢ and mulᵢ with the output variables fixed to ℓ(r*)
+    pub fn line_add_mul(
+        &self,
+        challenges: &[F],
+        r_star: F,
+        index: usize,
+    ) -> (MultiLinearPoly<F>, MultiLinearPoly<F>) {
+        let (a, b) = line_through(challenges);
+
+        self.layer_i_add_mul_at(index, &line_point(&a, &b, r_star))
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
 mod test {
-    // use super::*;
+    use super::*;
     use crate::gkr::gkr_circuit::test::setup_test_circuit8;
     use ark_bn254::Fq;
 
@@ -99,12 +152,51 @@ mod test {
         let r_c = Fq::from(3);
         let challenges = vec![r_b, r_c];
 
-        let (new_add, new_mul) = circuit.gkr_trick(&challenges, 2);
+        let (new_add, new_mul) = circuit.gkr_trick(&challenges, 2, (Fq::from(5), Fq::from(7)));
 
         assert_eq!(new_add.computation.len(), 16);
         assert_eq!(new_mul.computation.len(), 16);
     }
 
+    #[test]
+    fn test_line_poly_hits_both_claims() {
+        let circuit = setup_test_circuit8();
+        let evaluated_circuit = circuit.evaluate();
+        let w_i = &evaluated_circuit[1];
+
+        let num_vars = w_i.len().ilog2() as usize;
+        let challenges: Vec<Fq> = (0..2 * num_vars as u64)
+            .map(|i| Fq::from(3 * i + 2))
+            .collect();
+        let (r_b, r_c) = challenges.split_at(num_vars);
+        let q = circuit.line_poly(w_i, &challenges);
+
+        let mut w_i_mle = MultiLinearPoly::new(w_i);
+        assert_eq!(
+            q.evaluate(Fq::from(0)),
+            w_i_mle.evaluate(r_b).computation[0]
+        );
+        assert_eq!(
+            q.evaluate(Fq::from(1)),
+            w_i_mle.evaluate(r_c).computation[0]
+        );
+
+        let r_star = line_challenge(&mut Transcript::new(), &challenges, &q.coefficient);
+        let (a, b) = line_through(&challenges);
+        assert_eq!(
+            q.evaluate(r_star),
+            w_i_mle.evaluate(&line_point(&a, &b, r_star)).computation[0]
+        );
+
+        // r* comes from the caller's transcript, not from the line and q alone
+        let mut transcript = Transcript::new();
+        transcript.absorb(b"an earlier layer");
+        assert_ne!(
+            line_challenge(&mut transcript, &challenges, &q.coefficient),
+            r_star
+        );
+    }
+
     #[test]
     fn test_new_claimed_sum() {
         let circuit = setup_test_circuit8();
@@ -114,9 +206,10 @@ mod test {
         let challenges = vec![r_b, r_c];
 
         let evaluated_circuit = circuit.evaluate();
-        let w_i_eval = evaluated_circuit[1].clone();
+        let w_i_eval = &evaluated_circuit[1];
 
-        let claimed_sum = circuit.new_claimed_sum(w_i_eval, &challenges);
+        let claimed_sum =
+            circuit.new_claimed_sum(w_i_eval, &challenges, (Fq::from(5), Fq::from(7)));
 
         dbg!(claimed_sum);
     }
@@ -1,25 +1,90 @@
+use crate::{
+    gkr::{
+        named_wires::WireNames,
+        padded_layer::{InputPadding, PaddedLayer},
+    },
+    hypercube::{reorder, reorder_index, var_mask, BitOrder},
+    multi_linear::{EvaluationTable, MultiLinearPoly},
+};
 use ark_ff::PrimeField;
+use std::{collections::HashSet, fmt};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum GateOp {
     Add,
     Mul,
+    Square, // left², right is not read
 }
 
+impl GateOp {
+    // the byte a gate is hashed and serialized with
+    pub fn to_byte(self) -> u8 {
+        match self {
+            GateOp::Add => 0,
+            GateOp::Mul => 1,
+            GateOp::Square => 2,
+        }
+    }
+
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(GateOp::Add),
+            1 => Some(GateOp::Mul),
+            2 => Some(GateOp::Square),
+            _ => None,
+        }
+    }
+}
+
+// position of a wire in its layer, a gate reads left/right from the layer below and writes
+// output in its own layer, so the three can't be mixed up with each other or with gate counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WireId(pub usize);
+
+impl WireId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+// a wire anywhere in the circuit, layer 0 is the inputs (WireId is its index within the layer)
+// what CircuitBuilder hands out and what epilogue exposes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Wire {
+    pub layer: usize, // 0 is the input layer
+    pub index: usize,
+}
+
+impl From<usize> for WireId {
+    fn from(index: usize) -> Self {
+        WireId(index)
+    }
+}
+
+impl fmt::Display for WireId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "wire {}", self.0)
+    }
+}
+
+#[derive(Clone)]
 pub struct Gate {
-    pub left: usize,
-    pub right: usize,
+    pub left: WireId,
+    pub right: WireId,
     pub op: GateOp,
-    pub output: usize,
+    pub output: WireId,
 }
 
+#[derive(Clone)]
 pub struct Layer {
     pub gates: Vec<Gate>,
 }
 
+#[derive(Clone)]
 pub struct Circuit<F: PrimeField> {
     pub inputs: Vec<F>,
     pub layers: Vec<Layer>,
+    pub names: WireNames, // optional names for inputs and outputs, see named_wires
 }
 
 impl<F: PrimeField> Circuit<F> {
@@ -27,6 +92,7 @@ impl<F: PrimeField> Circuit<F> {
         Circuit {
             layers: Vec::new(),
             inputs,
+            names: WireNames::default(),
         }
     }
 
@@ -34,24 +100,87 @@ impl<F: PrimeField> Circuit<F> {
         self.layers.push(layer);
     }
 
+    // inputs indexed in another bit order, stored MSB-first like the layers the provers read
+    pub fn new_in_order(inputs: Vec<F>, order: BitOrder) -> Self {
+        Circuit::new(reorder(&inputs, order, BitOrder::MsbFirst))
+    }
+
+    // a layer whose wire indices are in another bit order, the outputs are indexed over the
+    // layer's own gates and left/right over the layer below, both must be powers of 2 to reorder
+    pub fn add_layer_in_order(&mut self, layer: Layer, order: BitOrder) {
+        if order == BitOrder::MsbFirst {
+            return self.add_layer(layer);
+        }
+
+        let below_len = match self.layers.last() {
+            Some(below) => below.gates.len(),
+            None => self.inputs.len(),
+        };
+        let own_len = layer.gates.len();
+        if !below_len.is_power_of_two() || !own_len.is_power_of_two() {
+            panic!("Only power of 2 layers can change bit order");
+        }
+
+        let to_msb = |wire: WireId, len: usize| {
+            WireId(reorder_index(
+                wire.index(),
+                len.ilog2() as usize,
+                order,
+                BitOrder::MsbFirst,
+            ))
+        };
+        let mut gates: Vec<Gate> = layer
+            .gates
+            .into_iter()
+            .map(|gate| Gate {
+                left: to_msb(gate.left, below_len),
+                right: to_msb(gate.right, below_len),
+                op: gate.op,
+                output: to_msb(gate.output, own_len),
+            })
+            .collect();
+        gates.sort_by_key(|gate| gate.output);
+
+        self.add_layer(Layer { gates });
+    }
+
+    // number of output gates before any padding
+    pub fn output_len(&self) -> usize {
+        self.layers.last().unwrap().gates.len()
+    }
+
+    // variables of the input layer MLE, the size of the KZG setup that commits to the inputs
+    pub fn input_num_vars(&self) -> usize {
+        self.input_padding().num_vars()
+    }
+
+    // how the inputs are padded to what layer 1 addresses, see InputPadding
+    pub fn input_padding(&self) -> InputPadding {
+        let input_bits = match self.layers.is_empty() {
+            true => PaddedLayer::<F>::padded_len(self.inputs.len()).ilog2(),
+            false => self.layer_bits(1).1,
+        };
+
+        InputPadding::new(self.inputs.len(), input_bits).unwrap_or_else(|| {
+            panic!(
+                "The first layer addresses {} input wires, the circuit has {} inputs",
+                1usize << input_bits,
+                self.inputs.len()
+            )
+        })
+    }
+
+    // the input layer MLE over inputs (self.inputs or the public ones), padded
+    pub fn input_poly(&self, inputs: &[F]) -> MultiLinearPoly<F> {
+        self.input_padding().mle(inputs)
+    }
+
     pub fn evaluate(&self) -> Vec<Vec<F>> {
         let mut current_layer = self.inputs.clone();
         let mut eval_layers = vec![current_layer.clone()];
 
         for layer in self.layers.iter() {
-            let mut next_layer = vec![F::zero(); layer.gates.len()];
-
-            for gate in layer.gates.iter() {
-                let left = current_layer[gate.left];
-                let right = current_layer[gate.right];
-
-                let result = match gate.op {
-                    GateOp::Add => left + right,
-                    GateOp::Mul => left * right,
-                };
-
-                next_layer[gate.output] = result;
-            }
+            let next_layer = Self::evaluate_layer(layer, &current_layer);
 
             eval_layers.push(next_layer.clone());
             current_layer = next_layer;
@@ -59,60 +188,217 @@ impl<F: PrimeField> Circuit<F> {
         eval_layers
     }
 
-    pub fn layer_i_add_mul(&self, layer_i: usize) -> (Vec<F>, Vec<F>) {
+    // the gates of one layer applied to the values of the layer below it
+    pub fn evaluate_layer(layer: &Layer, current_layer: &[F]) -> Vec<F> {
+        let mut next_layer = vec![F::zero(); layer.gates.len()];
+
+        for gate in layer.gates.iter() {
+            let left = current_layer[gate.left.index()];
+
+            let result = match gate.op {
+                GateOp::Add => left + current_layer[gate.right.index()],
+                GateOp::Mul => left * current_layer[gate.right.index()],
+                GateOp::Square => left * left,
+            };
+
+            next_layer[gate.output.index()] = result;
+        }
+
+        next_layer
+    }
+
+    // (output_bits, input_bits) used to index addᵢ/mulᵢ of a layer
+    pub fn layer_bits(&self, layer_i: usize) -> (u32, u32) {
         let layer = &self.layers[layer_i - 1]; // this is because i added input as first layer
 
         // my discovery is that for a 2-gate with 1-bit each at output and 2-bits each at input
         // for gate counts not in the power of 2, we pad it to the next power of 2
-        let gate_count;
-        let input_bits;
-        let output_bits;
-
-        if layer.gates.len() == 1 {
-            input_bits = 1;
-            output_bits = 1;
-        } else if layer.gates.len().is_power_of_two() {
-            gate_count = layer.gates.len();
-
-            input_bits = (2 * gate_count).ilog2();
-            output_bits = gate_count.ilog2();
+        // a single gate is padded to 2 with its input as a 1-bit layer, same rule as PaddedLayer
+        let gate_count = PaddedLayer::<F>::padded_len(layer.gates.len());
+        let (output_bits, halving_bits) = if layer.gates.len() == 1 {
+            (1, 1)
         } else {
-            gate_count = layer.gates.len().next_power_of_two();
+            (gate_count.ilog2(), (2 * gate_count).ilog2())
+        };
 
-            input_bits = (2 * gate_count).ilog2();
-            output_bits = gate_count.ilog2();
+        // the layer below can be wider than twice this one (a builder circuit carrying wires up),
+        // the inputs are padded to what layer 1 addresses, a layer of gates must already be
+        // a power of 2 so its MLE is the layer itself, for halving circuits nothing changes
+        let below_len = match layer_i {
+            1 => self.inputs.len(),
+            _ => self.layers[layer_i - 2].gates.len(),
+        };
+        let below_bits = PaddedLayer::<F>::padded_len(below_len).ilog2();
+        let input_bits = match layer_i {
+            1 => halving_bits.max(below_bits),
+            _ => below_bits,
+        };
+
+        (output_bits, input_bits)
+    }
+
+    //=========================================================================================
+    // Square gates have their own wiring sqᵢ(a, b) over the output and the left wire only
+    // in f(b, c) a square at (a, b) is a mul with c = b, so as a table the composed polynomial uses
+    // mulᵢ with an extra 1 at (a, b, b), layer_i_add_mul returns that merged table so every prover
+    // and verifier works unchanged, its multilinear extension splits as
+    //   mulᵢ(a, b, c) + Σ_{b'} sqᵢ(a, b') * χ_b'(b) * χ_b'(c)
+    // so layer_i_mul_eval only needs the small sqᵢ table and never the (a, b, b) entries
+    //=========================================================================================
+    pub fn layer_i_add_mul(&self, layer_i: usize) -> (Vec<F>, Vec<F>) {
+        let (add_vec, mut mul_vec, square_vec) = self.layer_i_wiring(layer_i);
+        let (_, input_bits) = self.layer_bits(layer_i);
+
+        // (a, b) -> (a, b, b)
+        for (index, value) in square_vec.iter().enumerate() {
+            if !value.is_zero() {
+                let left = index & ((1 << input_bits) - 1);
+                mul_vec[(index << input_bits) + left] += value;
+            }
         }
 
+        (add_vec, mul_vec)
+    }
+
+    // (addᵢ(a, b, c), mulᵢ(a, b, c), sqᵢ(a, b)) with the square gates kept out of mulᵢ
+    pub fn layer_i_wiring(&self, layer_i: usize) -> (Vec<F>, Vec<F>, Vec<F>) {
+        let layer = &self.layers[layer_i - 1]; // this is because i added input as first layer
+        let (output_bits, input_bits) = self.layer_bits(layer_i);
+
         let n_bits = (2 * input_bits) + output_bits; // (left_bit + right_bit )+ output_bit
         let total_combinations = 2usize.pow(n_bits);
         let mut add_vec = vec![F::zero(); total_combinations];
         let mut mul_vec = vec![F::zero(); total_combinations];
+        let mut square_vec = vec![F::zero(); 2usize.pow(output_bits + input_bits)];
 
         let output_start_index = n_bits - output_bits;
         let left_start_index = output_start_index - input_bits;
-        let right_start_index = 0 as u32;
+        let right_start_index = 0_u32;
 
         for gate in &layer.gates {
-            let index = match gate.op {
-                GateOp::Mul => {
-                    (gate.output << output_start_index)
-                        + (gate.left << left_start_index)
-                        + (gate.right << right_start_index)
-                }
-                GateOp::Add => {
-                    (gate.output << output_start_index)
-                        + (gate.left << left_start_index)
-                        + (gate.right << right_start_index)
-                }
-            };
+            let (output, left, right) =
+                (gate.output.index(), gate.left.index(), gate.right.index());
+            let index = (output << output_start_index)
+                + (left << left_start_index)
+                + (right << right_start_index);
 
             match gate.op {
                 GateOp::Mul => mul_vec[index] = F::one(),
                 GateOp::Add => add_vec[index] = F::one(),
+                GateOp::Square => square_vec[(output << input_bits) + left] = F::one(),
             }
         }
 
-        (add_vec, mul_vec)
+        (add_vec, mul_vec, square_vec)
+    }
+
+    // mulᵢ(r_a, r_b, r_c) of the merged table, from the split wiring
+    pub fn layer_i_mul_eval(&self, layer_i: usize, r_a: &[F], r_b: &[F], r_c: &[F]) -> F {
+        self.mul_i_at(layer_i, &[r_a, r_b, r_c].concat())
+    }
+
+    //=========================================================================================
+    // addᵢ / mulᵢ at any point (r_a, r_b, r_c) straight from the gate list
+    // the MLE of a 0/1 table is Σ over its 1 entries of χ_(a, b, c)(r_a, r_b, r_c) and χ splits as
+    // χ_a(r_a) * χ_b(r_b) * χ_c(r_c), so every gate costs O(bits) and no 2^(3n) table is built
+    // a square gate is the (a, b, b) entry of the merged mulᵢ i.e. χ_a(r_a) * χ_b(r_b) * χ_b(r_c)
+    //=========================================================================================
+    pub fn add_i_at(&self, layer_i: usize, point: &[F]) -> F {
+        self.wiring_at(layer_i, point, |op| op == GateOp::Add)
+    }
+
+    pub fn mul_i_at(&self, layer_i: usize, point: &[F]) -> F {
+        self.wiring_at(layer_i, point, |op| op != GateOp::Add)
+    }
+
+    fn wiring_at(&self, layer_i: usize, point: &[F], selected: impl Fn(GateOp) -> bool) -> F {
+        let (output_bits, input_bits) = self.layer_bits(layer_i);
+        let (output_bits, input_bits) = (output_bits as usize, input_bits as usize);
+        if point.len() != output_bits + 2 * input_bits {
+            panic!(
+                "Layer {} wiring has {} variables, got a point of {}",
+                layer_i,
+                output_bits + 2 * input_bits,
+                point.len()
+            );
+        }
+
+        let (r_a, r_bc) = point.split_at(output_bits);
+        let (r_b, r_c) = r_bc.split_at(input_bits);
+
+        self.layers[layer_i - 1]
+            .gates
+            .iter()
+            .filter(|gate| selected(gate.op))
+            .map(|gate| {
+                let right = match gate.op {
+                    GateOp::Square => gate.left,
+                    _ => gate.right,
+                };
+
+                eq_at(gate.output.index(), r_a)
+                    * eq_at(gate.left.index(), r_b)
+                    * eq_at(right.index(), r_c)
+            })
+            .sum()
+    }
+
+    // addᵢ(a, b, c) and mulᵢ(a, b, c) with the output variables a fixed to r_a
+    pub fn layer_i_add_mul_at(
+        &self,
+        layer_i: usize,
+        r_a_challenges: &[F],
+    ) -> (MultiLinearPoly<F>, MultiLinearPoly<F>) {
+        let (output_bits, _) = self.layer_bits(layer_i);
+        if r_a_challenges.len() != output_bits as usize {
+            panic!(
+                "Layer {} has {} output variables, got {} challenges",
+                layer_i,
+                output_bits,
+                r_a_challenges.len()
+            );
+        }
+
+        let table = EvaluationTable::new(r_a_challenges);
+        self.layer_i_add_mul_weighted(layer_i, |output| table.weights()[output])
+    }
+
+    //=========================================================================================
+    // addᵢ and mulᵢ with the output variables summed out against weights
+    // fixing a to r_a is Σ_a χ_a(r_a) * addᵢ(a, b, c), so every gate adds the weight of its output
+    // at its (b, c) entry and only the 2^(2 * input_bits) table is built, never the one over
+    // (a, b, c) that layer_i_add_mul returns, the gkr trick weighs an output by
+    // alpha * χ_a(r_b) + beta * χ_a(r_c) the same way
+    // a gate repeated with the same wires is one entry of the 0/1 table, so it is only added once
+    //=========================================================================================
+    pub fn layer_i_add_mul_weighted(
+        &self,
+        layer_i: usize,
+        weight: impl Fn(usize) -> F,
+    ) -> (MultiLinearPoly<F>, MultiLinearPoly<F>) {
+        let (_, input_bits) = self.layer_bits(layer_i);
+        let mut add_i = vec![F::zero(); 1 << (2 * input_bits)];
+        let mut mul_i = vec![F::zero(); 1 << (2 * input_bits)];
+
+        let mut seen = HashSet::new();
+        for gate in self.layers[layer_i - 1].gates.iter() {
+            let (output, left) = (gate.output.index(), gate.left.index());
+            let right = match gate.op {
+                GateOp::Square => left,
+                _ => gate.right.index(),
+            };
+            if !seen.insert((gate.op.to_byte(), output, left, right)) {
+                continue;
+            }
+
+            let index = (left << input_bits) + right;
+            match gate.op {
+                GateOp::Add => add_i[index] += weight(output),
+                GateOp::Mul | GateOp::Square => mul_i[index] += weight(output),
+            }
+        }
+
+        (MultiLinearPoly::new(&add_i), MultiLinearPoly::new(&mul_i))
     }
 
     // returns exploded tuple of w_i(b, c) for points b and c
@@ -122,157 +408,143 @@ impl<F: PrimeField> Circuit<F> {
             panic!("INVALID Layer index for EXPLOSION");
         }
 
-        let eval_layers = self.evaluate();
-        let poly = &eval_layers[layer_i];
+        let mut eval_layers = self.evaluate();
+        eval_layers[0] = self.input_padding().pad(&self.inputs);
 
-        let n_bits = poly.len();
-        let total_combinations = 2usize.pow(n_bits as u32);
-        let mut w_i_b = Vec::with_capacity(total_combinations);
-        let mut w_i_c = Vec::with_capacity(total_combinations);
+        Circuit::explode_layer(&eval_layers[layer_i])
+    }
 
-        // adding non-existent values of c e.g. 2b becomes 2b + 0c
-        for val in poly {
-            for _i in 0..n_bits {
-                w_i_b.push(val.clone());
-            }
+    // exploded arity-tuple of w_i(x₁, ..., x_arity), for fan-in-arity gates
+    pub fn explode_w_i_tuple(&self, layer_i: usize, arity: usize) -> Vec<Vec<F>> {
+        if layer_i > self.layers.len() {
+            panic!("INVALID Layer index for EXPLOSION");
         }
 
-        // adding non-existent values of b e.g. 2c becomes 0b + 2c
-        for _i in 0..n_bits {
-            for val in poly {
-                w_i_c.push(val.clone());
-            }
-        }
+        let mut eval_layers = self.evaluate();
+        eval_layers[0] = self.input_padding().pad(&self.inputs);
+
+        Circuit::explode_layer_tuple(&eval_layers[layer_i], arity)
+    }
+
+    // same as explode_w_i but works on an already evaluated layer so the trace isn't recomputed
+    pub fn explode_layer(poly: &[F]) -> (Vec<F>, Vec<F>) {
+        let mut exploded = Circuit::explode_layer_tuple(poly, 2);
+        let w_i_c = exploded.pop().unwrap();
+        let w_i_b = exploded.pop().unwrap();
 
         (w_i_b, w_i_c)
     }
 
-    // this function computes the addition or multiplication of w_i(b, c) for all points b and c
-    pub fn element_wise_op(poly_a: &[F], poly_b: &[F], op: GateOp) -> Vec<F> {
-        if poly_a.len() != poly_b.len() {
-            panic!("The polynomials must be of the same size");
+    // the tensor expansion of a layer over arity copies of its variables
+    // index (x₁, ..., x_arity) reads x₁ as the most significant digit in base poly.len(),
+    // the j-th table holds w(xⱼ) there, e.g. for 2: w(b) repeats each value n times, w(c) the layer n times
+    pub fn explode_layer_tuple(poly: &[F], arity: usize) -> Vec<Vec<F>> {
+        if arity == 0 {
+            panic!("A gate reads at least one wire");
         }
 
-        let mut result = vec![F::zero(); poly_a.len()];
+        let n = poly.len();
+        let total_combinations = n
+            .checked_pow(arity as u32)
+            .expect("The exploded layer does not fit in memory");
+
+        (0..arity)
+            .map(|j| {
+                let stride = n.pow((arity - 1 - j) as u32);
+                (0..total_combinations)
+                    .map(|index| poly[(index / stride) % n])
+                    .collect()
+            })
+            .collect()
+    }
 
-        for i in 0..poly_a.len() {
-            result[i] = match op {
-                GateOp::Add => poly_a[i] + poly_b[i],
-                GateOp::Mul => poly_a[i] * poly_b[i],
-            };
+    // this function computes the addition or multiplication of w_i(b, c) for all points b and c
+    pub fn element_wise_op(poly_a: &[F], poly_b: &[F], op: GateOp) -> Vec<F> {
+        Circuit::element_wise_op_tuple(&[poly_a, poly_b], op)
+    }
+
+    // the gate applied across a tuple of exploded tables: the sum or product of all of them,
+    // Square only reads the first
+    pub fn element_wise_op_tuple(polys: &[&[F]], op: GateOp) -> Vec<F> {
+        let len = match polys.first() {
+            Some(poly) => poly.len(),
+            None => panic!("A gate reads at least one wire"),
+        };
+        if polys.iter().any(|poly| poly.len() != len) {
+            panic!("The polynomials must be of the same size");
         }
 
-        result
+        (0..len)
+            .map(|i| match op {
+                GateOp::Add => polys.iter().map(|poly| poly[i]).sum(),
+                GateOp::Mul => polys.iter().map(|poly| poly[i]).product(),
+                GateOp::Square => polys[0][i] * polys[0][i],
+            })
+            .collect()
     }
 }
 
-#[cfg(test)]
+// χ_x(r) = Π (xⱼ * rⱼ + (1 - xⱼ) * (1 - rⱼ)) with x read as r.len() bits, most significant first
+// (the same variable order as MultiLinearPoly)
+pub fn eq_at<F: PrimeField>(x: usize, r: &[F]) -> F {
+    r.iter()
+        .enumerate()
+        .map(|(j, r_j)| {
+            if x & var_mask(j, r.len(), BitOrder::MsbFirst) != 0 {
+                *r_j
+            } else {
+                F::one() - r_j
+            }
+        })
+        .product()
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
 pub mod test {
     use super::*;
+    use crate::gkr::test_circuits;
     use ark_bn254::Fq;
 
     pub fn setup_test_circuit8() -> Circuit<Fq> {
-        let inputs = vec![
-            Fq::from(1),
-            Fq::from(2),
-            Fq::from(3),
-            Fq::from(4),
-            Fq::from(5),
-            Fq::from(6),
-            Fq::from(7),
-            Fq::from(8),
-        ];
-        let mut circuit = Circuit::new(inputs);
-
-        let layer_1 = Layer {
-            gates: vec![
-                Gate {
-                    left: 0,
-                    right: 1,
-                    op: GateOp::Add,
-                    output: 0,
-                },
-                Gate {
-                    left: 2,
-                    right: 3,
-                    op: GateOp::Mul,
-                    output: 1,
-                },
-                Gate {
-                    left: 4,
-                    right: 5,
-                    op: GateOp::Mul,
-                    output: 2,
-                },
-                Gate {
-                    left: 6,
-                    right: 7,
-                    op: GateOp::Mul,
-                    output: 3,
-                },
-            ],
-        };
-
-        let layer_2 = Layer {
-            gates: vec![
-                Gate {
-                    left: 0,
-                    right: 1,
-                    op: GateOp::Add,
-                    output: 0,
-                },
-                Gate {
-                    left: 2,
-                    right: 3,
-                    op: GateOp::Mul,
-                    output: 1,
-                },
-            ],
-        };
-
-        let layer_3 = Layer {
-            gates: vec![Gate {
-                left: 0,
-                right: 1,
-                op: GateOp::Add,
-                output: 0,
-            }],
-        };
-
-        circuit.add_layer(layer_1);
-        circuit.add_layer(layer_2);
-        circuit.add_layer(layer_3);
+        test_circuits::circuit8()
+    }
 
-        circuit
+    #[test]
+    fn test_eq_at_matches_evaluation_table() {
+        let point = vec![Fq::from(3), Fq::from(-2), Fq::from(7), Fq::from(11)];
+        let table = EvaluationTable::new(&point);
+        for x in [0, 5, 15] {
+            assert_eq!(table.weights()[x], eq_at(x, &point));
+        }
     }
 
     #[test]
     fn test_gate_creation() {
         let gate = Gate {
-            left: 0,
-            right: 1,
+            left: WireId(0),
+            right: WireId(1),
             op: GateOp::Add,
-            output: 0,
+            output: WireId(0),
         };
-        assert_eq!(gate.left, 0);
-        assert_eq!(gate.right, 1);
+        assert_eq!(gate.left, WireId(0));
+        assert_eq!(gate.right.index(), 1);
         assert_eq!(gate.op, GateOp::Add);
-        assert_eq!(gate.output, 0);
+        assert_eq!(gate.output, WireId::from(0));
     }
 
     #[test]
     fn test_layer_creation() {
         let gate_1 = Gate {
-            left: 0,
-            right: 1,
+            left: WireId(0),
+            right: WireId(1),
             op: GateOp::Add,
-            output: 0,
+            output: WireId(0),
         };
         let gate_2 = Gate {
-            left: 0,
-            right: 1,
+            left: WireId(0),
+            right: WireId(1),
             op: GateOp::Mul,
-            output: 1,
+            output: WireId(1),
         };
         let layer = Layer {
             gates: vec![gate_1, gate_2],
@@ -291,16 +563,16 @@ pub mod test {
         let layer = Layer {
             gates: vec![
                 Gate {
-                    left: 0,
-                    right: 1,
+                    left: WireId(0),
+                    right: WireId(1),
                     op: GateOp::Add,
-                    output: 0,
+                    output: WireId(0),
                 },
                 Gate {
-                    left: 0,
-                    right: 1,
+                    left: WireId(0),
+                    right: WireId(1),
                     op: GateOp::Mul,
-                    output: 1,
+                    output: WireId(1),
                 },
             ],
         };
@@ -325,26 +597,26 @@ pub mod test {
         let layer_1 = Layer {
             gates: vec![
                 Gate {
-                    left: 0,
-                    right: 1,
+                    left: WireId(0),
+                    right: WireId(1),
                     op: GateOp::Add,
-                    output: 0,
+                    output: WireId(0),
                 },
                 Gate {
-                    left: 2,
-                    right: 3,
+                    left: WireId(2),
+                    right: WireId(3),
                     op: GateOp::Mul,
-                    output: 1,
+                    output: WireId(1),
                 },
             ],
         };
 
         let layer_2 = Layer {
             gates: vec![Gate {
-                left: 0,
-                right: 1,
+                left: WireId(0),
+                right: WireId(1),
                 op: GateOp::Add,
-                output: 0,
+                output: WireId(0),
             }],
         };
 
@@ -375,28 +647,28 @@ pub mod test {
         let layer_1 = Layer {
             gates: vec![
                 Gate {
-                    left: 0,
-                    right: 1,
+                    left: WireId(0),
+                    right: WireId(1),
                     op: GateOp::Add,
-                    output: 0,
+                    output: WireId(0),
                 },
                 Gate {
-                    left: 2,
-                    right: 3,
+                    left: WireId(2),
+                    right: WireId(3),
                     op: GateOp::Mul,
-                    output: 1,
+                    output: WireId(1),
                 },
                 Gate {
-                    left: 4,
-                    right: 5,
+                    left: WireId(4),
+                    right: WireId(5),
                     op: GateOp::Mul,
-                    output: 2,
+                    output: WireId(2),
                 },
                 Gate {
-                    left: 6,
-                    right: 7,
+                    left: WireId(6),
+                    right: WireId(7),
                     op: GateOp::Mul,
-                    output: 3,
+                    output: WireId(3),
                 },
             ],
         };
@@ -404,16 +676,16 @@ pub mod test {
         let layer_2 = Layer {
             gates: vec![
                 Gate {
-                    left: 0,
-                    right: 1,
+                    left: WireId(0),
+                    right: WireId(1),
                     op: GateOp::Add,
-                    output: 0,
+                    output: WireId(0),
                 },
                 Gate {
-                    left: 2,
-                    right: 3,
+                    left: WireId(2),
+                    right: WireId(3),
                     op: GateOp::Mul,
-                    output: 1,
+                    output: WireId(1),
                 },
             ],
         };
@@ -436,28 +708,28 @@ pub mod test {
         let layer_1 = Layer {
             gates: vec![
                 Gate {
-                    left: 0,
-                    right: 0,
+                    left: WireId(0),
+                    right: WireId(0),
                     op: GateOp::Mul,
-                    output: 0,
+                    output: WireId(0),
                 },
                 Gate {
-                    left: 1,
-                    right: 1,
+                    left: WireId(1),
+                    right: WireId(1),
                     op: GateOp::Mul,
-                    output: 1,
+                    output: WireId(1),
                 },
                 Gate {
-                    left: 1,
-                    right: 2,
+                    left: WireId(1),
+                    right: WireId(2),
                     op: GateOp::Mul,
-                    output: 2,
+                    output: WireId(2),
                 },
                 Gate {
-                    left: 3,
-                    right: 3,
+                    left: WireId(3),
+                    right: WireId(3),
                     op: GateOp::Mul,
-                    output: 3,
+                    output: WireId(3),
                 },
             ],
         };
@@ -465,16 +737,16 @@ pub mod test {
         let layer_2 = Layer {
             gates: vec![
                 Gate {
-                    left: 0,
-                    right: 1,
+                    left: WireId(0),
+                    right: WireId(1),
                     op: GateOp::Mul,
-                    output: 0,
+                    output: WireId(0),
                 },
                 Gate {
-                    left: 2,
-                    right: 3,
+                    left: WireId(2),
+                    right: WireId(3),
                     op: GateOp::Mul,
-                    output: 1,
+                    output: WireId(1),
                 },
             ],
         };
@@ -489,6 +761,90 @@ pub mod test {
         assert_eq!(result, vec![inputs, eval_layer_1, eval_layer_2]);
     }
 
+    #[test]
+    fn test_square_gate_wiring() {
+        // (1², 3 * 4, 6², 7 + 8)
+        let mut circuit = Circuit::new((1..=8).map(Fq::from).collect());
+        circuit.add_layer(Layer {
+            gates: vec![
+                Gate {
+                    left: WireId(0),
+                    right: WireId(0),
+                    op: GateOp::Square,
+                    output: WireId(0),
+                },
+                Gate {
+                    left: WireId(2),
+                    right: WireId(3),
+                    op: GateOp::Mul,
+                    output: WireId(1),
+                },
+                Gate {
+                    left: WireId(5),
+                    right: WireId(5),
+                    op: GateOp::Square,
+                    output: WireId(2),
+                },
+                Gate {
+                    left: WireId(6),
+                    right: WireId(7),
+                    op: GateOp::Add,
+                    output: WireId(3),
+                },
+            ],
+        });
+
+        assert_eq!(
+            circuit.evaluate()[1],
+            vec![Fq::from(1), Fq::from(12), Fq::from(36), Fq::from(15)]
+        );
+
+        // 2 output bits and 3 input bits: sqᵢ has 5 variables, mulᵢ has 8
+        let (_, mul_i, square_i) = circuit.layer_i_wiring(1);
+        assert_eq!(square_i.len(), 32);
+        assert_eq!(mul_i.len(), 256);
+
+        let r_a = [Fq::from(3), Fq::from(5)];
+        let r_b = [Fq::from(7), Fq::from(11), Fq::from(13)];
+        let r_c = [Fq::from(17), Fq::from(19), Fq::from(23)];
+        let (_, merged_mul) = circuit.layer_i_add_mul(1);
+        assert_eq!(
+            circuit.layer_i_mul_eval(1, &r_a, &r_b, &r_c),
+            MultiLinearPoly::new(&merged_mul)
+                .evaluate(&[r_a.as_slice(), &r_b, &r_c].concat())
+                .computation[0]
+        );
+    }
+
+    #[test]
+    fn test_add_mul_at_matches_tables() {
+        let circuit = setup_test_circuit8();
+
+        for layer_i in 1..=circuit.layers.len() {
+            let (add_i, mul_i) = circuit.layer_i_add_mul(layer_i);
+            let num_vars = add_i.len().ilog2() as u64;
+            let point: Vec<Fq> = (0..num_vars).map(|j| Fq::from(3 * j + 2)).collect();
+
+            assert_eq!(
+                circuit.add_i_at(layer_i, &point),
+                MultiLinearPoly::new(&add_i).evaluate(&point).computation[0]
+            );
+            assert_eq!(
+                circuit.mul_i_at(layer_i, &point),
+                MultiLinearPoly::new(&mul_i).evaluate(&point).computation[0]
+            );
+        }
+    }
+
+    #[test]
+    fn test_eq_at_on_the_hypercube() {
+        // χ_x is 1 at x and 0 at every other corner
+        let corner = |bits: [u64; 3]| bits.map(Fq::from);
+        assert_eq!(eq_at(5, &corner([1, 0, 1])), Fq::from(1));
+        assert_eq!(eq_at(5, &corner([1, 1, 1])), Fq::from(0));
+        assert_eq!(eq_at(0, &corner([0, 0, 0])), Fq::from(1));
+    }
+
     #[test]
     fn test_compute_mul_add() {
         let circuit = setup_test_circuit8();
@@ -528,4 +884,79 @@ pub mod test {
             vec![Fq::from(1), Fq::from(4), Fq::from(9), Fq::from(16)]
         );
     }
+
+    #[test]
+    fn test_add_layer_in_order() {
+        // circuit8 with every layer written LSB-first
+        let msb = test_circuits::circuit8::<Fq>();
+        let mut lsb = Circuit::new_in_order(
+            reorder(&msb.inputs, BitOrder::MsbFirst, BitOrder::LsbFirst),
+            BitOrder::LsbFirst,
+        );
+        let mut below_len = msb.inputs.len();
+        for layer in msb.layers.iter() {
+            let own_len = layer.gates.len();
+            let to_lsb = |wire: WireId, len: usize| {
+                WireId(reorder_index(
+                    wire.index(),
+                    len.ilog2() as usize,
+                    BitOrder::MsbFirst,
+                    BitOrder::LsbFirst,
+                ))
+            };
+            let gates = layer
+                .gates
+                .iter()
+                .map(|gate| Gate {
+                    left: to_lsb(gate.left, below_len),
+                    right: to_lsb(gate.right, below_len),
+                    op: gate.op,
+                    output: to_lsb(gate.output, own_len),
+                })
+                .collect();
+            lsb.add_layer_in_order(Layer { gates }, BitOrder::LsbFirst);
+            below_len = own_len;
+        }
+
+        assert_eq!(lsb.inputs, msb.inputs);
+        assert_eq!(lsb.digest(), msb.digest());
+        assert_eq!(lsb.evaluate(), msb.evaluate());
+    }
+
+    #[test]
+    fn test_explode_tuple() {
+        let poly = vec![Fq::from(2), Fq::from(3)];
+
+        // the pair is the old (w_i_b, w_i_c)
+        let (w_i_b, w_i_c) = Circuit::explode_layer(&poly);
+        assert_eq!(
+            Circuit::explode_layer_tuple(&poly, 2),
+            vec![w_i_b.clone(), w_i_c.clone()]
+        );
+        assert_eq!(w_i_b, [2, 2, 3, 3].map(Fq::from).to_vec());
+        assert_eq!(w_i_c, [2, 3, 2, 3].map(Fq::from).to_vec());
+
+        // fan-in 3, index 0b110 is (3, 3, 2)
+        let exploded = Circuit::explode_layer_tuple(&poly, 3);
+        assert_eq!(exploded.len(), 3);
+        assert!(exploded.iter().all(|table| table.len() == 8));
+        assert_eq!(
+            [exploded[0][6], exploded[1][6], exploded[2][6]],
+            [3, 3, 2].map(Fq::from)
+        );
+
+        let tables: Vec<&[Fq]> = exploded.iter().map(|table| table.as_slice()).collect();
+        let sums = Circuit::element_wise_op_tuple(&tables, GateOp::Add);
+        let products = Circuit::element_wise_op_tuple(&tables, GateOp::Mul);
+        assert_eq!(sums[6], Fq::from(8));
+        assert_eq!(products[6], Fq::from(18));
+        assert_eq!(products[7], Fq::from(27));
+
+        // for a power of 2 layer, the j-th table is the layer extended by the other copies
+        let layer = MultiLinearPoly::new(&[2, 3, 5, 7].map(Fq::from));
+        let exploded = Circuit::explode_layer_tuple(&layer.computation, 3);
+        for (j, table) in exploded.iter().enumerate() {
+            assert_eq!(table, &layer.extend_vars(2 * j, 2 * (2 - j)).computation);
+        }
+    }
 }
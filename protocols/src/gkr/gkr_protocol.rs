@@ -1,263 +1,1090 @@
-use std::vec;
-
 use crate::{
-    gkr::gkr_circuit::{Circuit, GateOp},
-    gkr::partial_sum_check::{self, Proof},
-    gkr::product_poly::ProductPoly,
-    multi_linear::MultiLinearPoly,
-    transcript::Transcript,
+    eval_store::EvalStorage,
+    explain::Explain,
+    gkr::chunked_sum_check::ProverConfig,
+    gkr::core,
+    gkr::gkr_2_to_1_trick::{line_challenge, squeeze_alpha_beta, LayerReduction},
+    gkr::gkr_circuit::Circuit,
+    gkr::lookup::{self, FoldedLookup, LayerLookup, LayerLookups, LookupProof},
+    gkr::padded_layer::PaddedLayer,
+    gkr::partial_sum_check::Proof,
+    gkr::proof_view::GKRProofSource,
+    gkr::prover_state::{LayerClaim, ProverState},
+    gkr::public_inputs::PublicInputs,
+    gkr::trace::{EvaluatedTrace, TraceError},
+    lagrange::LagrangeWeights,
+    progress::{never_cancelled, Cancelled, Progress, ProgressEvent},
+    proof_format::{
+        ensure_consumed, read_header, read_nested_vec, read_vec, read_vec_with, write_header,
+        DecodeLimits, ProofFormatError, ProtocolId,
+    },
+    transcript::{Transcript, TranscriptDomain},
+    transcript_schedule::Step,
 };
 use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use std::io;
 
 pub struct GKRProof<F: PrimeField> {
     pub output_layer: Vec<F>,    // an array of wᵢ
     pub w_i_evals: Vec<(F, F)>,  // array of wᵢ evaluated at r_b and r_c
     pub p_proofs: Vec<Proof<F>>, // array of sum-check proofs
+    pub line_polys: Vec<Vec<F>>, // q(t) = Wᵢ(ℓ(t)) per inner layer, empty for the alpha/beta reduction
 }
 
-impl<F: PrimeField> Circuit<F> {
-    pub fn proof(&self) -> GKRProof<F> {
-        let mut transcript = Transcript::new();
-        let evaluated_circuit = self.evaluate();
+impl<F: PrimeField> GKRProof<F> {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofFormatError> {
+        let mut bytes = Vec::new();
+        write_header(ProtocolId::Gkr, &mut bytes);
 
-        let mut sum_poly_array = Vec::new();
-        let mut w_i_evals = Vec::new();
-        let mut p_proofs = Vec::new();
-        let mut r_a_challenges = Vec::new();
+        self.output_layer.serialize_compressed(&mut bytes)?;
+        self.w_i_evals.serialize_compressed(&mut bytes)?;
+        (self.p_proofs.len() as u64).serialize_compressed(&mut bytes)?;
+        for p_proof in self.p_proofs.iter() {
+            p_proof.serialize_into(&mut bytes)?;
+        }
+        self.line_polys.serialize_compressed(&mut bytes)?;
 
-        let circuit_len = evaluated_circuit.len() - 1;
+        Ok(bytes)
+    }
 
-        // Get the output layer evaluations (W₀)
-        let w_0 = evaluated_circuit[circuit_len].clone();
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
 
-        // Pad W₀ to power of 2 if needed
-        let w_0_arr = if w_0.len() == 1 {
-            vec![w_0[0], F::zero()]
-        } else if w_0.len().is_power_of_two() {
-            w_0
-        } else {
-            let target_length = w_0.len().next_power_of_two();
-            let mut padded = w_0.clone();
-            padded.resize(target_length, F::zero());
-            padded
-        };
-        let output_layer = w_0_arr.clone();
+    pub fn from_bytes_with_limits(
+        mut bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        limits.check_size(bytes)?;
+        read_header(&mut bytes, ProtocolId::Gkr)?;
 
-        let w_0_len = w_0_arr.len().ilog2();
+        let output_layer = read_vec::<F>(&mut bytes, limits)?;
+        let w_i_evals = read_vec::<(F, F)>(&mut bytes, limits)?;
+        let p_proofs = read_vec_with(&mut bytes, limits, |bytes| {
+            Proof::deserialize_from(bytes, limits)
+        })?;
+        let line_polys = read_nested_vec::<F>(&mut bytes, limits)?;
+        ensure_consumed(bytes)?;
 
-        // Get random point r₀
-        transcript.absorb(&MultiLinearPoly::to_bytes(&w_0_arr));
-        for _ in 0..w_0_len {
-            let r_a = F::from_be_bytes_mod_order(&transcript.squeeze());
-            r_a_challenges.push(r_a);
+        // one (Wᵢ(r_b), Wᵢ(r_c)) per layer sum-check
+        if w_i_evals.len() != p_proofs.len() {
+            return Err(ProofFormatError::InconsistentLengths {
+                expected: p_proofs.len(),
+                found: w_i_evals.len(),
+            });
         }
 
-        let w_0_eval = MultiLinearPoly::new(&w_0_arr).evaluate(&r_a_challenges); // claimed sum = w_0(r)
-        let init_claimed_sum = w_0_eval.computation[0];
+        Ok(GKRProof {
+            output_layer,
+            w_i_evals,
+            p_proofs,
+            line_polys,
+        })
+    }
+}
 
-        // f_ri_b_c = [add_i_ri_b_c * (w_i+1_b + w_i+1_c)] + [mul_i_ri_b_c * (w_i+1_b * w_i+1_c)]
-        let next_layer_idx = circuit_len - 1;
-        let (w_i_b_exploded, w_i_c_exploded) = self.explode_w_i(next_layer_idx);
+// everything the prover sends for one layer, in the order GKRProof stores it
+#[derive(Debug, Clone)]
+pub struct LayerProof<F: PrimeField> {
+    pub sum_check: Proof<F>,
+    pub w_evals: (F, F),   // Wᵢ₊₁(r_b), Wᵢ₊₁(r_c) of the layer below
+    pub line_poly: Vec<F>, // q(t) with LayerReduction::Line, empty otherwise and for the input layer
+}
 
-        let sum_term = Circuit::<F>::element_wise_op(&w_i_b_exploded, &w_i_c_exploded, GateOp::Add);
-        let mul_term = Circuit::<F>::element_wise_op(&w_i_b_exploded, &w_i_c_exploded, GateOp::Mul);
+// collects the LayerProofs into a GKRProof, every part is moved in as it comes
+// the vectors are sized up front: one sum-check and one pair of w evals per layer and a line
+// poly for every layer but the input one when the line reduction is used
+#[derive(Clone)]
+pub(crate) struct ProofBuilder<F: PrimeField> {
+    w_i_evals: Vec<(F, F)>,
+    p_proofs: Vec<Proof<F>>,
+    line_polys: Vec<Vec<F>>,
+}
 
-        let (add_i, mul_i) = self.layer_i_add_mul(circuit_len);
-        let mut add_i_mle = MultiLinearPoly::new(&add_i);
-        let mut mul_i_mle = MultiLinearPoly::new(&mul_i);
+impl<F: PrimeField> ProofBuilder<F> {
+    pub(crate) fn with_capacity(circuit_len: usize, reduction: LayerReduction) -> Self {
+        let line_polys = match reduction {
+            LayerReduction::AlphaBeta => 0,
+            LayerReduction::Line => circuit_len.saturating_sub(1),
+        };
 
-        for r_a in r_a_challenges.iter() {
-            add_i_mle = add_i_mle.partial_evaluate(*r_a, 0);
-            mul_i_mle = mul_i_mle.partial_evaluate(*r_a, 0);
+        ProofBuilder {
+            w_i_evals: Vec::with_capacity(circuit_len),
+            p_proofs: Vec::with_capacity(circuit_len),
+            line_polys: Vec::with_capacity(line_polys),
         }
+    }
 
-        let p_poly_1 = ProductPoly::new(vec![
-            add_i_mle,
-            MultiLinearPoly {
-                computation: sum_term,
-            },
-        ]);
-        let p_poly_2 = ProductPoly::new(vec![
-            mul_i_mle,
-            MultiLinearPoly {
-                computation: mul_term,
-            },
-        ]);
-
-        let p_poly = vec![p_poly_1, p_poly_2];
-        sum_poly_array.push(p_poly.clone());
-
-        let p_proof = partial_sum_check::proof::<F>(p_poly, init_claimed_sum);
-        p_proofs.push(p_proof.clone());
-        let mut challenges = p_proof.challenges.clone();
-
-        // For each layer i (going backwards from output to input)
-        // since last layer has been done, we start with next layer
-        // [0, 1, 2, 3] => would start at 2 and end at 1 as w will go down to 0
-        for layer_idx in (1..circuit_len).rev() {
-            let next_layer_idx = layer_idx - 1; // this is because w is 1 layer ahead
-            let current_layer_w = evaluated_circuit[layer_idx].clone();
-
-            // claimed_sum = (alpha * Wᵢ(*b)) + (beta * Wᵢ(*c))
-            let claimed_sum = self.new_claimed_sum(current_layer_w, &challenges);
-
-            // Get the add and mul vectors for current layer
-            let (new_add, new_mul) = self.gkr_trick(&challenges, layer_idx);
-
-            // Get the next layer evaluations (Wᵢ₊₁)
-            let (w_i_b_exploded, w_i_c_exploded) = self.explode_w_i(next_layer_idx);
-
-            // Compute f_rᵢ(b, c) = addᵢ(rᵢ,b,c)(Wᵢ₊₁(b) + Wᵢ₊₁(c)) + mulᵢ(rᵢ,b,c)(Wᵢ₊₁(b) * Wᵢ₊₁(c))
-            let sum_term =
-                Circuit::<F>::element_wise_op(&w_i_b_exploded, &w_i_c_exploded, GateOp::Add);
-            let mul_term =
-                Circuit::<F>::element_wise_op(&w_i_b_exploded, &w_i_c_exploded, GateOp::Mul);
-
-            // Create the polynomials for sum-check
-            let p_poly_1 = ProductPoly::new(vec![
-                new_add,
-                MultiLinearPoly {
-                    computation: sum_term,
-                },
-            ]);
-            let p_poly_2 = ProductPoly::new(vec![
-                new_mul,
-                MultiLinearPoly {
-                    computation: mul_term,
-                },
-            ]);
-
-            let p_poly = vec![p_poly_1, p_poly_2];
-            sum_poly_array.push(p_poly.clone());
-
-            // Run sum-check protocol
-            let p_proof = partial_sum_check::proof::<F>(p_poly, claimed_sum);
-            p_proofs.push(p_proof.clone());
-
-            challenges = p_proof.challenges.clone();
+    pub(crate) fn push_layer(&mut self, layer: LayerProof<F>) {
+        self.w_i_evals.push(layer.w_evals);
+        self.p_proofs.push(layer.sum_check);
+        if !layer.line_poly.is_empty() {
+            self.line_polys.push(layer.line_poly);
         }
+    }
 
-        // this section is to get the evaluations of wᵢ at r_b and r_c to be used by the verifier
-        for layer_idx in (0..circuit_len).rev() {
-            let current_layer_w = evaluated_circuit[layer_idx].clone();
-            let challenges = p_proofs[circuit_len - layer_idx - 1].challenges.clone();
+    pub(crate) fn finish(self, output_layer: Vec<F>) -> GKRProof<F> {
+        GKRProof {
+            output_layer,
+            w_i_evals: self.w_i_evals,
+            p_proofs: self.p_proofs,
+            line_polys: self.line_polys,
+        }
+    }
+}
 
-            let mid = challenges.len() / 2;
-            let (r_b_challenges, r_c_challenges) = challenges.split_at(mid);
+//=========================================================================================
+// LayerProver is the prover loop with its state between two layers spelled out
+// (transcript, claim on the next layer, how to build that layer's wiring), so the loop can stop
+// after any layer and pick up again, see proving_session
+// the wiring is only built when its layer is proven: replaying a layer that was proven before
+// redoes the cheap part (Wᵢ₊₁(r_b), Wᵢ₊₁(r_c), the reduction) and skips the sum-check and tables
+//=========================================================================================
+#[derive(Clone)]
+enum NextWiring<F: PrimeField> {
+    Output(Vec<F>),                   // r_a, addᵢ/mulᵢ of the output layer at r_a
+    AlphaBeta(Vec<F>, usize, (F, F)), // r_b ‖ r_c, layer, (alpha, beta)
+    Line(Vec<F>, F, usize),           // r_b ‖ r_c, r*, layer
+    Done,
+}
 
-            let w_i_b = MultiLinearPoly::new(&current_layer_w)
-                .evaluate(&r_b_challenges)
-                .computation[0];
-            let w_i_c = MultiLinearPoly::new(&current_layer_w)
-                .evaluate(&r_c_challenges)
-                .computation[0];
+#[derive(Clone)]
+pub(crate) struct LayerProver<F: PrimeField> {
+    pub(crate) transcript: Transcript,
+    pub(crate) output_layer: Vec<F>, // the W₀ that is sent, empty for a single output
+    claimed_sum: F,
+    next: NextWiring<F>,
+    lookups: LayerLookups<F>,
+    folded: Option<FoldedLookup<F>>, // the lookup weighed into the next layer's claim, see lookup
+    remaining: usize,                // layers left to prove
+}
 
-            transcript.absorb(&MultiLinearPoly::to_bytes(&[w_i_b, w_i_c]));
+impl<F: PrimeField> LayerProver<F> {
+    pub(crate) fn start(state: &ProverState<F>, transcript_domain: &TranscriptDomain) -> Self {
+        let transcript = Self::statement(state, transcript_domain);
 
-            w_i_evals.push((w_i_b, w_i_c));
-        }
+        // a single output is the whole statement, the verifier builds W₀ from it so nothing is sent
+        Self::from_statement(state, transcript, state.circuit.output_len() != 1)
+    }
 
-        GKRProof {
+    // the GKR transcript with the statement in, the verifier absorbs the PublicInputs it was handed
+    pub(crate) fn statement(
+        state: &ProverState<F>,
+        transcript_domain: &TranscriptDomain,
+    ) -> Transcript {
+        let mut transcript = transcript_domain
+            .transcript()
+            .with_schedule(&core::GKR_SCHEDULE);
+        PublicInputs::new(state.circuit.inputs.clone(), state.output_layer().to_vec())
+            .absorb_into(transcript.follow(Step::Absorb("public inputs")));
+
+        transcript
+    }
+
+    // the layers of a proof whose statement is already in the transcript, see gkr::core
+    pub(crate) fn from_statement(
+        state: &ProverState<F>,
+        transcript: Transcript,
+        send_w_0: bool,
+    ) -> Self {
+        Self::from_statement_with_lookups(state, transcript, send_w_0, LayerLookups::none())
+    }
+
+    // the same with the lookup claims folded into the layers they are on
+    pub(crate) fn from_statement_with_lookups(
+        state: &ProverState<F>,
+        mut transcript: Transcript,
+        send_w_0: bool,
+        lookups: LayerLookups<F>,
+    ) -> Self {
+        // Get the output layer evaluations (W₀) padded to power of 2 if needed
+        let w_0 = state.padded_output_layer();
+        let output_layer = if send_w_0 {
+            w_0.padded().to_vec()
+        } else {
+            Vec::new()
+        };
+
+        // Get random point r₀, claimed sum = w_0(r)
+        let (r_a_challenges, w_0_eval) = core::output_claim(&mut transcript, &w_0, send_w_0);
+        let folded = lookups.squeeze(&mut transcript, state.circuit_len());
+
+        LayerProver {
+            transcript,
             output_layer,
-            w_i_evals,
-            p_proofs,
+            claimed_sum: folded
+                .as_ref()
+                .map_or(w_0_eval, |folded| folded.claim(w_0_eval)),
+            next: NextWiring::Output(r_a_challenges),
+            lookups,
+            folded,
+            remaining: state.circuit_len(),
         }
     }
 
-    pub fn verify(&self, proof: &GKRProof<F>) -> bool {
-        // recall that f(a, b, c) has already been evaluated by r_a to get f(b, c)
-        // NOTE that the prover called evaluate meaning he has the Wᵢ values for every step while the verifier only has the input
-        let mut transcript = Transcript::new();
-        let mut last_challenges = Vec::new();
-        let mut curr_challenges = Vec::new();
-        let mut current_claimed_sum = F::zero();
-        let circuit_len = self.layers.len(); // actual number of layers
-        let mut last_idx = 0;
+    pub(crate) fn remaining(&self) -> usize {
+        self.remaining
+    }
 
-        let w_0_arr = proof.output_layer.clone();
-        transcript.absorb(&MultiLinearPoly::to_bytes(&w_0_arr));
-        let r_a = F::from_be_bytes_mod_order(&transcript.squeeze());
+    // proves the next layer down, None once the input layer is reached
+    // a layer cancelled in its sum-check leaves the prover where it was, it can be proven again
+    pub(crate) fn prove_layer(
+        &mut self,
+        state: &ProverState<F>,
+        config: &ProverConfig,
+        progress: &mut Progress,
+    ) -> Result<Option<LayerProof<F>>, Cancelled> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let circuit = state.circuit;
+        let next_layer_idx = self.remaining - 1; // this is because w is 1 layer ahead
 
-        let (add_i, mul_i) = self.layer_i_add_mul(circuit_len);
-        let mut new_add = MultiLinearPoly::new(&add_i).partial_evaluate(r_a, 0);
-        let mut new_mul = MultiLinearPoly::new(&mul_i).partial_evaluate(r_a, 0);
+        // Compute f_rᵢ(b, c) = addᵢ(rᵢ,b,c)(Wᵢ₊₁(b) + Wᵢ₊₁(c)) + mulᵢ(rᵢ,b,c)(Wᵢ₊₁(b) * Wᵢ₊₁(c))
+        let (new_add, new_mul) = match &self.next {
+            NextWiring::Output(r_a) => circuit.layer_i_add_mul_at(state.circuit_len(), r_a),
+            NextWiring::AlphaBeta(challenges, index, alpha_beta) => {
+                circuit.gkr_trick(challenges, *index, *alpha_beta)
+            }
+            NextWiring::Line(challenges, r_star, index) => {
+                circuit.line_add_mul(challenges, *r_star, *index)
+            }
+            NextWiring::Done => unreachable!("the wiring is set for every layer left"),
+        };
+        let (new_add, new_mul) = match &self.folded {
+            Some(folded) => folded.wiring(circuit, self.remaining, (new_add, new_mul)),
+            None => (new_add, new_mul),
+        };
+        let claim = LayerClaim {
+            next_layer_idx,
+            add_i: new_add,
+            mul_i: new_mul,
+            claimed_sum: self.claimed_sum,
+        };
+        let p_proof = if config.debug_checks {
+            // the check needs addᵢ and mulᵢ after the sum-check consumed them
+            let p_proof = state.layer_proof_with_progress(
+                &self.transcript,
+                claim.clone(),
+                config,
+                progress,
+            )?;
+            state.check_layer_proof(&claim, &p_proof);
+            p_proof
+        } else {
+            state.layer_proof_with_progress(&self.transcript, claim, config, progress)?
+        };
 
-        for (i, p_proof) in proof.p_proofs.iter().enumerate() {
-            let sub_claim = partial_sum_check::verify(p_proof.clone());
-            let challenges = sub_claim.challenges.clone();
+        Ok(Some(self.advance(state, config, p_proof)))
+    }
 
-            curr_challenges = challenges.clone();
-            last_idx = i;
+    // reported by the caller once the layer is stored, so a cancel on it doesn't lose the layer
+    pub(crate) fn layer_done(&self, state: &ProverState<F>) -> ProgressEvent {
+        ProgressEvent::Layer {
+            done: state.circuit_len() - self.remaining,
+            total: state.circuit_len(),
+        }
+    }
 
-            // For all but the last proof, check against w_i_evals
-            if i < proof.p_proofs.len() - 1 {
-                let new_add_eval = new_add.evaluate(&challenges);
-                let new_mul_eval = new_mul.evaluate(&challenges);
+    // takes a layer proven earlier instead of running its sum-check, false (and nothing
+    // changes) if it isn't the layer this prover would have produced next
+    pub(crate) fn replay_layer(
+        &mut self,
+        state: &ProverState<F>,
+        config: &ProverConfig,
+        layer: &LayerProof<F>,
+    ) -> bool {
+        if self.remaining == 0 || layer.sum_check.init_claimed_sum != self.claimed_sum {
+            return false;
+        }
 
-                let (w_i_rb, w_i_rc) = proof.w_i_evals[i];
-                transcript.absorb(&MultiLinearPoly::to_bytes(&[w_i_rb, w_i_rc]));
+        let mut replayed = self.clone();
+        let expected = replayed.advance(state, config, layer.sum_check.clone());
+        if expected.w_evals != layer.w_evals || expected.line_poly != layer.line_poly {
+            return false;
+        }
+
+        *self = replayed;
+        true
+    }
+
+    // everything after the layer sum-check: Wᵢ₊₁(r_b), Wᵢ₊₁(r_c) and the claim on the layer below
+    fn advance(
+        &mut self,
+        state: &ProverState<F>,
+        config: &ProverConfig,
+        p_proof: Proof<F>,
+    ) -> LayerProof<F> {
+        let circuit = state.circuit;
+        let next_layer_idx = self.remaining - 1;
+        let challenges = p_proof.challenges.clone();
 
-                let w_sum = w_i_rb + w_i_rc;
-                let w_mul = w_i_rb * w_i_rc;
+        // the evaluations of wᵢ at r_b and r_c used by the verifier
+        let (w_i_b, w_i_c) = state.w_i_evals(next_layer_idx, &challenges);
+        core::absorb_evals(&mut self.transcript, (w_i_b, w_i_c));
 
-                let check =
-                    (new_add_eval.computation[0] * w_sum) + (new_mul_eval.computation[0] * w_mul);
+        let mut line_poly = Vec::new();
+        self.next = NextWiring::Done;
+        self.folded = None;
+        if next_layer_idx > 0 {
+            let current_layer_w = &state.layer(next_layer_idx);
 
-                if check != sub_claim.last_claimed_sum {
-                    return false;
+            match config.reduction {
+                LayerReduction::AlphaBeta => {
+                    // claimed_sum = (alpha * Wᵢ(*b)) + (beta * Wᵢ(*c))
+                    let alpha_beta = squeeze_alpha_beta(&mut self.transcript);
+                    self.claimed_sum =
+                        circuit.new_claimed_sum(current_layer_w, &challenges, alpha_beta);
+                    self.next = NextWiring::AlphaBeta(challenges, next_layer_idx, alpha_beta);
                 }
+                LayerReduction::Line => {
+                    // claimed_sum = q(r*) = Wᵢ(ℓ(r*))
+                    let q = circuit.line_poly(current_layer_w, &challenges);
+                    let r_star = line_challenge(&mut self.transcript, &challenges, &q.coefficient);
 
-                (new_add, new_mul) = self.gkr_trick(&challenges, circuit_len - i - 1);
+                    self.claimed_sum = q.evaluate(r_star);
+                    self.next = NextWiring::Line(challenges, r_star, next_layer_idx);
+                    line_poly = q.coefficient;
+                }
+            }
 
-                last_challenges = challenges.clone();
+            self.folded = self.lookups.squeeze(&mut self.transcript, next_layer_idx);
+            if let Some(folded) = &self.folded {
+                self.claimed_sum = folded.claim(self.claimed_sum);
             }
+        }
+        self.remaining -= 1;
 
-            current_claimed_sum = sub_claim.last_claimed_sum;
+        LayerProof {
+            sum_check: p_proof,
+            w_evals: (w_i_b, w_i_c),
+            line_poly,
         }
+    }
+}
+
+// f(b, c) = [add_i(b, c) * (w_i+1(b) + w_i+1(c))] + [mul_i(b,c) * (w_i+1(b) * w_i+1(c))]
+pub fn layer_f_eval<F: PrimeField>(add_eval: F, mul_eval: F, w_b: F, w_c: F) -> F {
+    (add_eval * (w_b + w_c)) + (mul_eval * (w_b * w_c))
+}
+
+impl<F: PrimeField> Circuit<F> {
+    pub fn proof(&self, transcript_domain: &TranscriptDomain) -> GKRProof<F> {
+        self.proof_with_config(&ProverConfig::default(), transcript_domain)
+    }
+
+    // same proof, config.chunk_len bounds the memory used by each layer sum-check
+    pub fn proof_with_config(
+        &self,
+        config: &ProverConfig,
+        transcript_domain: &TranscriptDomain,
+    ) -> GKRProof<F> {
+        self.proof_with_state(&ProverState::new(self), config, transcript_domain)
+    }
 
-        // Finally, performs oracle check for each layer using the below
-        // f(b, c) = [add_i(b, c) * (w_i+1(b) + w_i+1(c))] + [mul_i(b,c) * (w_i+1(b) * w_i+1(c))]
-        let input_evaluations = self.inputs.clone();
-        let mut input_poly = MultiLinearPoly::new(&input_evaluations);
+    // same proof from a trace exported earlier, the circuit is not evaluated again
+    pub fn proof_from_trace(
+        &self,
+        trace: EvaluatedTrace<F>,
+        config: &ProverConfig,
+        transcript_domain: &TranscriptDomain,
+    ) -> Result<GKRProof<F>, TraceError> {
+        let state = ProverState::from_trace(self, trace)?;
 
-        let mid = curr_challenges.len() / 2;
-        let (r_b_challenges, r_c_challenges) = curr_challenges.split_at(mid);
+        Ok(self.proof_with_state(&state, config, transcript_domain))
+    }
 
-        let input_eval_b = input_poly.evaluate(&r_b_challenges).computation[0];
-        let input_eval_c = input_poly.evaluate(&r_c_challenges).computation[0];
+    // same proof with the evaluated layers kept where storage says, e.g. on disk for huge circuits
+    pub fn proof_with_storage(
+        &self,
+        config: &ProverConfig,
+        storage: &EvalStorage,
+        transcript_domain: &TranscriptDomain,
+    ) -> io::Result<GKRProof<F>> {
+        let state = ProverState::with_storage(self, storage)?;
 
-        transcript.absorb(&MultiLinearPoly::to_bytes(&[input_eval_b, input_eval_c]));
+        Ok(self.proof_with_state(&state, config, transcript_domain))
+    }
 
-        let input_w_sum = input_eval_b + input_eval_c;
-        let input_w_mul = input_eval_b * input_eval_c;
+    // same proof with every sum-check round and every finished layer reported, see progress
+    pub fn proof_with_progress(
+        &self,
+        config: &ProverConfig,
+        transcript_domain: &TranscriptDomain,
+        progress: &mut Progress,
+    ) -> Result<GKRProof<F>, Cancelled> {
+        self.proof_with_state_and_progress(
+            &ProverState::new(self),
+            config,
+            transcript_domain,
+            progress,
+        )
+    }
 
-        (new_add, new_mul) = self.gkr_trick(&last_challenges, circuit_len - last_idx);
-        let new_add_eval = new_add.evaluate(&curr_challenges).computation[0];
-        let new_mul_eval = new_mul.evaluate(&curr_challenges).computation[0];
+    fn proof_with_state(
+        &self,
+        state: &ProverState<F>,
+        config: &ProverConfig,
+        transcript_domain: &TranscriptDomain,
+    ) -> GKRProof<F> {
+        never_cancelled(self.proof_with_state_and_progress(
+            state,
+            config,
+            transcript_domain,
+            &mut Progress::none(),
+        ))
+    }
 
-        let oracle_check = (new_add_eval * input_w_sum) + (new_mul_eval * input_w_mul);
+    fn proof_with_state_and_progress(
+        &self,
+        state: &ProverState<F>,
+        config: &ProverConfig,
+        transcript_domain: &TranscriptDomain,
+        progress: &mut Progress,
+    ) -> Result<GKRProof<F>, Cancelled> {
+        let mut builder = ProofBuilder::with_capacity(state.circuit_len(), config.reduction);
+        let output_layer =
+            self.stream_with_state(state, config, transcript_domain, progress, |layer| {
+                builder.push_layer(layer)
+            })?;
 
-        oracle_check == current_claimed_sum
+        Ok(builder.finish(output_layer))
+    }
+
+    // runs the prover layer by layer from the output down, every LayerProof is handed to emit as
+    // soon as its sum-check is done (see streaming_gkr), returns the output layer that is sent
+    pub(crate) fn stream_with_state(
+        &self,
+        state: &ProverState<F>,
+        config: &ProverConfig,
+        transcript_domain: &TranscriptDomain,
+        progress: &mut Progress,
+        mut emit: impl FnMut(LayerProof<F>),
+    ) -> Result<Vec<F>, Cancelled> {
+        let mut prover = LayerProver::start(state, transcript_domain);
+        while let Some(layer) = prover.prove_layer(state, config, progress)? {
+            emit(layer);
+            progress.report(prover.layer_done(state))?;
+        }
+
+        Ok(prover.output_layer)
+    }
+
+    // public binds the proof to the statement, W₀ is only accepted if it is exactly public.outputs
+    // and the input layer is evaluated on public.inputs, never on the inputs baked into self
+    pub fn verify(
+        &self,
+        proof: &GKRProof<F>,
+        public: &PublicInputs<F>,
+        transcript_domain: &TranscriptDomain,
+    ) -> bool {
+        self.verify_explained(proof, public, transcript_domain, &mut Explain::off())
+    }
+
+    // same check with the claims, round polys, challenges and checks of every layer recorded
+    pub fn verify_explained(
+        &self,
+        proof: &GKRProof<F>,
+        public: &PublicInputs<F>,
+        transcript_domain: &TranscriptDomain,
+        explain: &mut Explain,
+    ) -> bool {
+        self.verify_from(transcript_domain.transcript(), proof, public, None, explain)
+    }
+
+    // transcript is the domain transcript, weights the sum-check weights if they were built ahead
+    // of time (see PreparedVerifier), otherwise each sum-check builds its own
+    pub(crate) fn verify_from(
+        &self,
+        transcript: Transcript,
+        proof: &impl GKRProofSource<F>,
+        public: &PublicInputs<F>,
+        weights: Option<&LagrangeWeights<F>>,
+        explain: &mut Explain,
+    ) -> bool {
+        self.verify_from_with_lookups(transcript, proof, public, weights, (&[], &[]), explain)
+    }
+
+    // the same with the lookups of a LookupGKRProof checked and folded into their layers
+    pub(crate) fn verify_from_with_lookups(
+        &self,
+        transcript: Transcript,
+        proof: &impl GKRProofSource<F>,
+        public: &PublicInputs<F>,
+        weights: Option<&LagrangeWeights<F>>,
+        lookups: (&[LayerLookup<F>], &[LookupProof<F>]),
+        explain: &mut Explain,
+    ) -> bool {
+        // recall that f(a, b, c) has already been evaluated by r_a to get f(b, c)
+        // NOTE that the prover called evaluate meaning he has the Wᵢ values for every step while the verifier only has the input
+        let circuit_len = self.layers.len(); // actual number of layers
+
+        explain.section("outputs");
+        if !explain.check(
+            "proof shape matches the circuit",
+            public.inputs.len() == self.inputs.len()
+                && proof.layer_count() == circuit_len
+                && proof.w_i_eval_count() == circuit_len
+                && (core::reduction_of(proof) == LayerReduction::AlphaBeta
+                    || proof.line_poly_count() == circuit_len - 1),
+        ) {
+            return false;
+        }
+        let output_layer = match proof.output_layer() {
+            Some(output_layer) => output_layer,
+            None => return false,
+        };
+
+        let mut transcript = transcript.with_schedule(&core::GKR_SCHEDULE);
+        public.absorb_into(transcript.follow(Step::Absorb("public inputs")));
+        let layer_lookups = match lookup::verify_lookups(self, &mut transcript, public, lookups) {
+            Some(layer_lookups) => layer_lookups,
+            None => return explain.check("every lookup holds", false),
+        };
+        let w_0 = if self.output_len() == 1 {
+            // W₀(x) = v * (1 - x), derived from the claimed output alone
+            if !explain.check(
+                "a single output",
+                output_layer.is_empty() && public.outputs.len() == 1,
+            ) {
+                return false;
+            }
+            PaddedLayer::new(&public.outputs)
+        } else {
+            // the padding must follow the circuit's output size and be all zeros
+            let w_0 = match PaddedLayer::from_padded(output_layer.into_owned(), self.output_len()) {
+                Some(w_0) => w_0,
+                None => return false,
+            };
+            if !explain.check("W₀ is the public outputs", w_0.values() == public.outputs) {
+                return false;
+            }
+            w_0
+        };
+
+        let walk = match core::verify_layers(
+            self,
+            &mut transcript,
+            proof,
+            (&w_0, self.output_len() != 1),
+            weights,
+            &layer_lookups,
+            explain,
+        ) {
+            Some(walk) => walk,
+            None => return false,
+        };
+
+        // the input layer is evaluated on the public inputs
+        let mut input_poly = self.input_poly(&public.inputs);
+
+        let mid = walk.point.len() / 2;
+        let (r_b_challenges, r_c_challenges) = walk.point.split_at(mid);
+
+        let input_eval_b = input_poly.evaluate(r_b_challenges).computation[0];
+        let input_eval_c = input_poly.evaluate(r_c_challenges).computation[0];
+
+        walk.check_inputs(&mut transcript, (input_eval_b, input_eval_c), explain)
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
 mod test {
-    use crate::gkr::gkr_circuit::test::setup_test_circuit8;
+    use super::{EvaluatedTrace, GKRProof, LayerProver, PublicInputs, TranscriptDomain};
+    use crate::{
+        eval_store::test::disk_storage,
+        explain::{Explain, ExplainStep},
+        gkr::{
+            chunked_sum_check::ProverConfig,
+            gkr_2_to_1_trick::LayerReduction,
+            gkr_circuit::{test::setup_test_circuit8, Circuit, Gate, GateOp, Layer, WireId},
+            partial_sum_check,
+            prover_state::ProverState,
+        },
+        progress::{CancelToken, Cancelled, Progress, ProgressEvent},
+        proof_format::{DecodeLimits, ProofFormatError},
+        transcript::{test::domain, TranscriptEvent, TranscriptRecorder},
+    };
+    use ark_bn254::Fq;
 
     #[test]
     fn test_gkr_protocol_proof() {
         let circuit = setup_test_circuit8();
 
-        circuit.proof();
+        circuit.proof(&domain());
     }
 
     #[test]
     fn test_gkr_protocol_verify() {
         let circuit = setup_test_circuit8();
 
-        let proof = circuit.proof();
-        let result = circuit.verify(&proof);
+        let proof = circuit.proof(&domain());
+        let result = circuit.verify(&proof, &circuit.public_inputs(), &domain());
         assert!(&result);
     }
+
+    #[test]
+    fn test_proof_bound_to_a_session() {
+        use crate::proof_format::{bind_session, open_session};
+
+        let circuit = setup_test_circuit8();
+        let proof = circuit.proof(&domain().with_session(b"session-1"));
+        let bytes = bind_session(b"session-1", &proof.to_bytes().unwrap());
+
+        let (session_id, proof_bytes) = open_session(&bytes, &DecodeLimits::default()).unwrap();
+        let decoded = GKRProof::<Fq>::from_bytes(proof_bytes).unwrap();
+        let public = circuit.public_inputs();
+        assert!(circuit.verify(&decoded, &public, &domain().with_session(&session_id)));
+
+        // the same proof under another session or none
+        assert!(!circuit.verify(&decoded, &public, &domain().with_session(b"session-2")));
+        assert!(!circuit.verify(&decoded, &public, &domain()));
+    }
+
+    #[test]
+    fn test_layer_sum_checks_bound_to_a_session() {
+        let circuit = setup_test_circuit8();
+        let state = ProverState::new(&circuit);
+        let session_1 = domain().with_session(b"session-1");
+        let proof = circuit.proof(&session_1);
+
+        // the GKR transcript when the output layer's sum-check starts, under either session
+        let at_layer_0 = |domain| LayerProver::<Fq>::start(&state, domain).transcript;
+        let p_proof = &proof.p_proofs[0];
+        let sub_claim = partial_sum_check::try_verify_in(&at_layer_0(&session_1), p_proof);
+        assert_eq!(sub_claim.unwrap().challenges, p_proof.challenges);
+
+        // another session draws other round challenges, the rounds stop adding up
+        let session_2 = domain().with_session(b"session-2");
+        assert!(partial_sum_check::try_verify_in(&at_layer_0(&session_2), p_proof).is_none());
+        assert!(partial_sum_check::try_verify(p_proof).is_none());
+    }
+
+    #[test]
+    fn test_gkr_verify_explained() {
+        let circuit = setup_test_circuit8();
+        let proof = circuit.proof(&domain());
+
+        let mut explain = Explain::new("gkr");
+        assert!(circuit.verify_explained(
+            &proof,
+            &circuit.public_inputs(),
+            &domain(),
+            &mut explain
+        ));
+        let text = explain.to_text();
+        for section in [
+            "== outputs ==",
+            "== layer 0 ==",
+            "== layer 2 ==",
+            "== inputs ==",
+        ] {
+            assert!(text.contains(section));
+        }
+        assert!(!text.contains("FAILED"));
+
+        // the first layer claim that doesn't add up is the last step
+        let mut tampered = proof;
+        tampered.w_i_evals[1].0 += Fq::from(1);
+        let mut explain = Explain::new("gkr");
+        assert!(!circuit.verify_explained(
+            &tampered,
+            &circuit.public_inputs(),
+            &domain(),
+            &mut explain
+        ));
+        assert!(matches!(
+            explain.steps().last(),
+            Some(ExplainStep::Check { passed: false, .. })
+        ));
+        assert!(explain.to_text().contains("== layer 1 =="));
+    }
+
+    #[test]
+    fn test_gkr_verify_binds_layer_evaluations() {
+        let circuit = setup_test_circuit8();
+        let proof = circuit.proof(&domain());
+
+        // alpha and beta come after Wᵢ(r_b), Wᵢ(r_c) in the transcript, other claims give other
+        // challenges and the next sum-check no longer starts where it has to
+        let mut tampered = proof;
+        tampered.w_i_evals[0].0 += Fq::from(1);
+        tampered.w_i_evals[0].1 -= Fq::from(1);
+        assert!(!circuit.verify(&tampered, &circuit.public_inputs(), &domain()));
+
+        let mut tampered = circuit.proof(&domain());
+        tampered.p_proofs[1].init_claimed_sum += Fq::from(1);
+        assert!(!circuit.verify(&tampered, &circuit.public_inputs(), &domain()));
+    }
+
+    #[test]
+    fn test_recorded_transcripts_diverge_at_the_tampered_evals() {
+        let circuit = setup_test_circuit8();
+        let prover_log = TranscriptRecorder::new();
+        let proof = circuit.proof(&domain().with_recorder(&prover_log));
+
+        let honest_log = TranscriptRecorder::new();
+        assert!(circuit.verify(
+            &proof,
+            &circuit.public_inputs(),
+            &domain().with_recorder(&honest_log)
+        ));
+        assert_eq!(prover_log.first_divergence(&honest_log), None);
+
+        let mut tampered = proof;
+        tampered.w_i_evals[0].0 += Fq::from(1);
+        tampered.w_i_evals[0].1 -= Fq::from(1);
+        let verifier_log = TranscriptRecorder::new();
+        assert!(!circuit.verify(
+            &tampered,
+            &circuit.public_inputs(),
+            &domain().with_recorder(&verifier_log)
+        ));
+
+        // everything up to the first layer's evals agrees, the evals are where they split
+        let divergence = prover_log.first_divergence(&verifier_log).unwrap();
+        assert!(matches!(
+            divergence.left,
+            Some(TranscriptEvent::Absorb { ref label, .. }) if label == "Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)"
+        ));
+        assert!(matches!(
+            divergence.right,
+            Some(TranscriptEvent::Absorb { ref label, .. }) if label == "Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)"
+        ));
+        assert!(prover_log.events()[..divergence.index].iter().any(
+            |event| matches!(event, TranscriptEvent::Squeeze { label, .. } if label == "r_a")
+        ));
+    }
+
+    #[test]
+    fn test_gkr_verify_rejects_other_outputs() {
+        let circuit = setup_test_circuit8();
+        let proof = circuit.proof(&domain());
+
+        let with_outputs = |outputs: Vec<Fq>| PublicInputs::new(circuit.inputs.clone(), outputs);
+
+        assert!(!circuit.verify(&proof, &with_outputs(vec![Fq::from(1696)]), &domain()));
+        assert!(!circuit.verify(
+            &proof,
+            &with_outputs(vec![Fq::from(1695), Fq::from(0)]),
+            &domain()
+        ));
+        assert!(!circuit.verify(&proof, &with_outputs(vec![]), &domain()));
+    }
+
+    #[test]
+    fn test_single_output_statement_is_the_output() {
+        let circuit = setup_test_circuit8();
+        let proof = circuit.proof(&domain());
+
+        // W₀ comes from the statement, a proof carrying its own output layer is not accepted
+        assert!(proof.output_layer.is_empty());
+        let mut padded = proof;
+        padded.output_layer = vec![Fq::from(1695), Fq::from(0)];
+        assert!(!circuit.verify(&padded, &circuit.public_inputs(), &domain()));
+    }
+
+    #[test]
+    fn test_gkr_verify_rejects_other_inputs() {
+        let circuit = setup_test_circuit8();
+        let proof = circuit.proof(&domain());
+
+        let mut public = circuit.public_inputs();
+        public.inputs[0] += Fq::from(1);
+        assert!(!circuit.verify(&proof, &public, &domain()));
+
+        let mut public = circuit.public_inputs();
+        public.inputs.pop();
+        assert!(!circuit.verify(&proof, &public, &domain()));
+    }
+
+    #[test]
+    fn test_chunked_proof_verifies() {
+        let circuit = setup_test_circuit8();
+
+        let proof = circuit.proof_with_config(&ProverConfig::chunked(2), &domain());
+        assert!(circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+        assert_eq!(
+            proof.to_bytes().unwrap(),
+            circuit.proof(&domain()).to_bytes().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_progress_reports_every_round_and_layer() {
+        let circuit = setup_test_circuit8();
+        let mut events = Vec::new();
+
+        let proof = circuit
+            .proof_with_progress(
+                &ProverConfig::default(),
+                &domain(),
+                &mut Progress::new(|event| events.push(event)),
+            )
+            .unwrap();
+        assert_eq!(
+            proof.to_bytes().unwrap(),
+            circuit.proof(&domain()).to_bytes().unwrap()
+        );
+
+        // a layer is reported after all of its rounds
+        let layers: Vec<usize> = events
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| matches!(event, ProgressEvent::Layer { .. }))
+            .map(|(i, _)| i)
+            .collect();
+        let rounds: usize = proof.p_proofs.iter().map(|p| p.round_polys.len()).sum();
+        assert_eq!(events.len(), rounds + 3);
+        assert_eq!(layers.len(), 3);
+        assert_eq!(
+            events[layers[0] - 1],
+            ProgressEvent::Round {
+                layer: Some(0),
+                done: proof.p_proofs[0].round_polys.len(),
+                total: proof.p_proofs[0].round_polys.len(),
+            }
+        );
+        assert_eq!(
+            events.last(),
+            Some(&ProgressEvent::Layer { done: 3, total: 3 })
+        );
+    }
+
+    #[test]
+    fn test_cancelled_proof() {
+        let circuit = setup_test_circuit8();
+        let token = CancelToken::new();
+        let mut progress = Progress::new(|event| {
+            if let ProgressEvent::Layer { done: 1, .. } = event {
+                token.cancel();
+            }
+        })
+        .with_cancel(&token);
+
+        let result =
+            circuit.proof_with_progress(&ProverConfig::chunked(2), &domain(), &mut progress);
+        assert_eq!(result.err(), Some(Cancelled));
+    }
+
+    #[test]
+    fn test_disk_backed_proof_verifies() {
+        let circuit = setup_test_circuit8();
+
+        let proof = circuit
+            .proof_with_storage(&ProverConfig::chunked(2), &disk_storage(), &domain())
+            .unwrap();
+        assert!(circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+        assert_eq!(
+            proof.to_bytes().unwrap(),
+            circuit.proof(&domain()).to_bytes().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_debug_checks_pass_on_honest_proof() {
+        let circuit = setup_test_circuit8();
+
+        for config in [
+            ProverConfig::default().with_debug_checks(),
+            ProverConfig::default()
+                .with_reduction(LayerReduction::Line)
+                .with_debug_checks(),
+        ] {
+            let proof = circuit.proof_with_config(&config, &domain());
+            assert!(circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+        }
+    }
+
+    #[test]
+    fn test_debug_checks_do_not_change_the_proof() {
+        // without the checks addᵢ and mulᵢ are moved into the sum-check instead of cloned
+        let circuit = setup_test_circuit8();
+
+        for reduction in [LayerReduction::AlphaBeta, LayerReduction::Line] {
+            let config = ProverConfig::default().with_reduction(reduction);
+            let checked = circuit.proof_with_config(&config.clone().with_debug_checks(), &domain());
+            let plain = circuit.proof_with_config(&config, &domain());
+
+            assert_eq!(checked.to_bytes().unwrap(), plain.to_bytes().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_proof_from_exported_trace() {
+        let circuit = setup_test_circuit8();
+        let bytes = circuit.export_trace().to_bytes().unwrap();
+
+        let trace = EvaluatedTrace::<Fq>::from_bytes(&bytes).unwrap();
+        let proof = circuit
+            .proof_from_trace(trace, &ProverConfig::default(), &domain())
+            .unwrap();
+        assert!(circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+        assert_eq!(
+            proof.to_bytes().unwrap(),
+            circuit.proof(&domain()).to_bytes().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_line_reduction_verifies() {
+        let circuit = setup_test_circuit8();
+        let config = ProverConfig::default().with_reduction(LayerReduction::Line);
+
+        let proof = circuit.proof_with_config(&config, &domain());
+        assert_eq!(proof.line_polys.len(), circuit.layers.len() - 1);
+        assert!(circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+
+        let decoded = GKRProof::<Fq>::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+        assert!(circuit.verify(&decoded, &circuit.public_inputs(), &domain()));
+
+        // same answer with the chunked layer sum-check
+        let chunked = circuit.proof_with_config(
+            &ProverConfig::chunked(2).with_reduction(LayerReduction::Line),
+            &domain(),
+        );
+        assert!(circuit.verify(&chunked, &circuit.public_inputs(), &domain()));
+    }
+
+    #[test]
+    fn test_line_reduction_rejects_tampered_line_poly() {
+        let circuit = setup_test_circuit8();
+        let config = ProverConfig::default().with_reduction(LayerReduction::Line);
+
+        let mut proof = circuit.proof_with_config(&config, &domain());
+        proof.line_polys[0][1] += Fq::from(1);
+        assert!(!circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+
+        let mut proof = circuit.proof_with_config(&config, &domain());
+        proof.line_polys.pop();
+        assert!(!circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+    }
+
+    #[test]
+    fn test_square_gates_verify() {
+        // (1² + 3 * 4, 6² * (7 + 8)) = (13, 540)
+        let mut circuit = Circuit::new((1..=8).map(Fq::from).collect());
+        let gate = |op, left, right, output| Gate {
+            left: WireId(left),
+            right: WireId(right),
+            op,
+            output: WireId(output),
+        };
+        circuit.add_layer(Layer {
+            gates: vec![
+                gate(GateOp::Square, 0, 0, 0),
+                gate(GateOp::Mul, 2, 3, 1),
+                gate(GateOp::Square, 5, 5, 2),
+                gate(GateOp::Add, 6, 7, 3),
+            ],
+        });
+        circuit.add_layer(Layer {
+            gates: vec![gate(GateOp::Add, 0, 1, 0), gate(GateOp::Mul, 2, 3, 1)],
+        });
+        assert_eq!(
+            circuit.public_inputs().outputs,
+            vec![Fq::from(13), Fq::from(540)]
+        );
+
+        let proof = circuit.proof(&domain());
+        assert!(circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+
+        let config = ProverConfig::chunked(2)
+            .with_reduction(LayerReduction::Line)
+            .with_debug_checks();
+        let proof = circuit.proof_with_config(&config, &domain());
+        assert!(circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+    }
+
+    #[test]
+    fn test_non_power_of_two_inputs() {
+        // 3 inputs under a 2 gate layer, which addresses 4 input wires
+        let mut circuit = Circuit::new((1..=3).map(Fq::from).collect());
+        let gate = |op, left, right, output| Gate {
+            left: WireId(left),
+            right: WireId(right),
+            op,
+            output: WireId(output),
+        };
+        circuit.add_layer(Layer {
+            gates: vec![gate(GateOp::Add, 0, 1, 0), gate(GateOp::Mul, 1, 2, 1)],
+        });
+        circuit.add_layer(Layer {
+            gates: vec![gate(GateOp::Add, 0, 1, 0)],
+        });
+        assert_eq!(circuit.input_padding().padding(), 3..4);
+        assert_eq!(circuit.input_num_vars(), 2);
+
+        let proof = circuit.proof(&domain());
+        assert!(circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+
+        let mut other = circuit.public_inputs();
+        other.inputs[2] += Fq::from(1);
+        assert!(!circuit.verify(&proof, &other, &domain()));
+    }
+
+    #[test]
+    fn test_gkr_verify_rejects_other_domain() {
+        let circuit = setup_test_circuit8();
+        let proof = circuit.proof(&domain());
+
+        let other_app = TranscriptDomain::new(b"other-app", domain().public_digest);
+        assert!(!circuit.verify(&proof, &circuit.public_inputs(), &other_app));
+    }
+
+    #[test]
+    fn test_gkr_proof_bytes_roundtrip() {
+        let circuit = setup_test_circuit8();
+
+        let proof = circuit.proof(&domain());
+        let bytes = proof.to_bytes().unwrap();
+
+        let decoded = GKRProof::<Fq>::from_bytes(&bytes).unwrap();
+        assert!(circuit.verify(&decoded, &circuit.public_inputs(), &domain()));
+
+        let mut future_bytes = bytes.clone();
+        future_bytes[0] += 1;
+        assert!(matches!(
+            GKRProof::<Fq>::from_bytes(&future_bytes),
+            Err(ProofFormatError::UnsupportedVersion { .. })
+        ));
+
+        let mut trailing_bytes = bytes;
+        trailing_bytes.push(0);
+        assert!(matches!(
+            GKRProof::<Fq>::from_bytes(&trailing_bytes),
+            Err(ProofFormatError::TrailingBytes(1))
+        ));
+    }
+
+    #[test]
+    fn test_gkr_proof_decode_limits() {
+        let circuit = setup_test_circuit8();
+        let bytes = circuit.proof(&domain()).to_bytes().unwrap();
+
+        let limits = DecodeLimits {
+            max_bytes: bytes.len() - 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            GKRProof::<Fq>::from_bytes_with_limits(&bytes, &limits),
+            Err(ProofFormatError::TooLarge { .. })
+        ));
+
+        // the sum-check over the 8 inputs has 2 * 3 challenges, more than a limit of 4 entries
+        let limits = DecodeLimits {
+            max_len: 4,
+            ..Default::default()
+        };
+        assert!(matches!(
+            GKRProof::<Fq>::from_bytes_with_limits(&bytes, &limits),
+            Err(ProofFormatError::TooLarge { max: 4, .. })
+        ));
+
+        // truncated bytes are an error, not a panic or a huge allocation
+        for len in 0..bytes.len() {
+            assert!(GKRProof::<Fq>::from_bytes(&bytes[..len]).is_err());
+        }
+    }
 }
@@ -0,0 +1,452 @@
+use crate::{
+    gkr::{
+        gkr_2_to_1_trick::squeeze_alpha_beta,
+        gkr_circuit::Circuit,
+        gkr_protocol::layer_f_eval,
+        padded_layer::{InputPadding, PaddedLayer},
+        partial_sum_check::{self, Proof},
+        prover_state::ProverState,
+    },
+    kzg::{
+        kzg_helper_functions::compute_commitment,
+        kzg_protocol::{self, KZGProof},
+        trusted_setup::{TrustedSetup, VerifierSetup},
+    },
+    multi_linear::MultiLinearPoly,
+    transcript::{Transcript, TranscriptDomain},
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// Holographic GKR: the wiring is committed once in a preprocessing step
+// addᵢ(a, b, c) and mulᵢ(a, b, c) of every layer are committed with KZG (trimmed to their size)
+// the verifier keeps only those commitments and gets addᵢ/mulᵢ at the sum-check point through
+// openings, so it never builds the 2^(a + 2b) wiring tables that layer_i_add_mul needs
+// output layer: addᵢ is opened at (r_a, r_b, r_c)
+// other layers (2 to 1 trick): addᵢ is opened at (r_b', r_b, r_c) and (r_c', r_b, r_c) and
+// combined with α, β, r_b' and r_c' being the challenges of the layer above
+// the inputs are still public and evaluated by the verifier itself
+//=========================================================================================
+pub struct WiringCommitments<P: Pairing> {
+    pub output_len: usize, // number of output gates, fixes how W₀ is padded
+    pub layer_bits: Vec<(u32, u32)>, // (output_bits, input_bits) per layer, layer 1 first
+    pub add_commitments: Vec<P::G1>,
+    pub mul_commitments: Vec<P::G1>,
+}
+
+pub struct WiringOpenings<F: PrimeField, P: Pairing> {
+    pub add_openings: Vec<KZGProof<F, P>>, // 1 for the output layer, 2 for every other layer
+    pub mul_openings: Vec<KZGProof<F, P>>,
+}
+
+pub struct HolographicGKRProof<F: PrimeField, P: Pairing> {
+    pub output_layer: Vec<F>,
+    pub w_i_evals: Vec<(F, F)>,
+    pub p_proofs: Vec<Proof<F>>,
+    pub wiring_openings: Vec<WiringOpenings<F, P>>, // output layer first like p_proofs
+}
+
+impl<P: Pairing> WiringCommitments<P> {
+    fn absorb_into(&self, transcript: &mut Transcript) {
+        for (add_commitment, mul_commitment) in
+            self.add_commitments.iter().zip(self.mul_commitments.iter())
+        {
+            transcript.absorb_commitment(add_commitment);
+            transcript.absorb_commitment(mul_commitment);
+        }
+    }
+
+    // number of variables of addᵢ/mulᵢ
+    fn num_vars(&self, layer_i: usize) -> usize {
+        let (output_bits, input_bits) = self.layer_bits[layer_i - 1];
+
+        (output_bits + 2 * input_bits) as usize
+    }
+}
+
+impl<F: PrimeField> Circuit<F> {
+    // preprocessing, done once per circuit and independent of the inputs
+    pub fn commit_wiring<P: Pairing>(&self, setup: &TrustedSetup<P>) -> WiringCommitments<P> {
+        let mut layer_bits = Vec::new();
+        let mut add_commitments = Vec::new();
+        let mut mul_commitments = Vec::new();
+
+        for layer_i in 1..=self.layers.len() {
+            let (output_bits, input_bits) = self.layer_bits(layer_i);
+            let wiring_setup = setup.trim((output_bits + 2 * input_bits) as usize);
+            let (add_i, mul_i) = self.layer_i_add_mul(layer_i);
+
+            layer_bits.push((output_bits, input_bits));
+            add_commitments.push(
+                compute_commitment::<F, P>(&MultiLinearPoly::new(&add_i), &wiring_setup.g1_arr)
+                    .expect("Wiring does not match the trimmed setup size"),
+            );
+            mul_commitments.push(
+                compute_commitment::<F, P>(&MultiLinearPoly::new(&mul_i), &wiring_setup.g1_arr)
+                    .expect("Wiring does not match the trimmed setup size"),
+            );
+        }
+
+        WiringCommitments {
+            output_len: self.output_len(),
+            layer_bits,
+            add_commitments,
+            mul_commitments,
+        }
+    }
+
+    pub fn holographic_proof<P: Pairing>(
+        &self,
+        setup: &TrustedSetup<P>,
+        wiring: &WiringCommitments<P>,
+        transcript_domain: &TranscriptDomain,
+    ) -> HolographicGKRProof<F, P> {
+        let mut transcript = transcript_domain.transcript();
+        let state = ProverState::new(self);
+        let circuit_len = state.circuit_len();
+
+        wiring.absorb_into(&mut transcript);
+
+        let w_0 = state.padded_output_layer();
+        w_0.absorb_into(&mut transcript);
+        let output_layer = w_0.into_padded();
+        let r_a_challenges: Vec<F> = (0..output_layer.len().ilog2())
+            .map(|_| transcript.squeeze_field::<F>())
+            .collect();
+
+        //=========================================================================================
+        // GKR layers, same sum-checks as the plain GKR prover
+        //=========================================================================================
+        let mut claimed_sum = MultiLinearPoly::new(&output_layer)
+            .evaluate(&r_a_challenges)
+            .computation[0];
+        let (add_i, mul_i) = self.layer_i_add_mul_at(circuit_len, &r_a_challenges);
+        let mut sum_poly = state.layer_sum_poly(circuit_len - 1, add_i, mul_i);
+
+        let mut w_i_evals = Vec::new();
+        let mut p_proofs = Vec::new();
+        let mut wiring_openings = Vec::new();
+        let mut wiring_prefixes = vec![r_a_challenges];
+
+        for layer_idx in (1..=circuit_len).rev() {
+            let p_proof =
+                partial_sum_check::proof_in::<F>(&transcript, sum_poly.clone(), claimed_sum);
+            let (w_i_b, w_i_c) = state.w_i_evals(layer_idx - 1, &p_proof.challenges);
+            transcript.absorb(&MultiLinearPoly::to_bytes(&[w_i_b, w_i_c]));
+
+            wiring_openings.push(self.open_wiring::<P>(
+                setup,
+                layer_idx,
+                &wiring_prefixes,
+                &p_proof.challenges,
+            ));
+
+            if layer_idx > 1 {
+                let (alpha, beta) = squeeze_alpha_beta(&mut transcript);
+                claimed_sum = (alpha * w_i_b) + (beta * w_i_c);
+                let (new_add, new_mul) =
+                    self.gkr_trick(&p_proof.challenges, layer_idx - 1, (alpha, beta));
+                sum_poly = state.layer_sum_poly(layer_idx - 2, new_add, new_mul);
+
+                let mid = p_proof.challenges.len() / 2;
+                let (r_b_challenges, r_c_challenges) = p_proof.challenges.split_at(mid);
+                wiring_prefixes = vec![r_b_challenges.to_vec(), r_c_challenges.to_vec()];
+            }
+
+            w_i_evals.push((w_i_b, w_i_c));
+            p_proofs.push(p_proof);
+        }
+
+        HolographicGKRProof {
+            output_layer,
+            w_i_evals,
+            p_proofs,
+            wiring_openings,
+        }
+    }
+
+    // opens addᵢ and mulᵢ at (prefix, r_b, r_c) for every prefix
+    fn open_wiring<P: Pairing>(
+        &self,
+        setup: &TrustedSetup<P>,
+        layer_i: usize,
+        prefixes: &[Vec<F>],
+        challenges: &[F],
+    ) -> WiringOpenings<F, P> {
+        let (output_bits, input_bits) = self.layer_bits(layer_i);
+        let wiring_setup = setup.trim((output_bits + 2 * input_bits) as usize);
+        let (add_i, mul_i) = self.layer_i_add_mul(layer_i);
+
+        let mut add_openings = Vec::new();
+        let mut mul_openings = Vec::new();
+        for prefix in prefixes.iter() {
+            let point = [prefix.as_slice(), challenges].concat();
+
+            add_openings.push(kzg_protocol::proof::<F, P>(
+                MultiLinearPoly::new(&add_i),
+                &wiring_setup.g1_arr,
+                &point,
+            ));
+            mul_openings.push(kzg_protocol::proof::<F, P>(
+                MultiLinearPoly::new(&mul_i),
+                &wiring_setup.g1_arr,
+                &point,
+            ));
+        }
+
+        WiringOpenings {
+            add_openings,
+            mul_openings,
+        }
+    }
+}
+
+// the verifier only needs the inputs, the wiring commitments and the verifier setup
+pub fn holographic_verify<F: PrimeField, P: Pairing>(
+    inputs: &[F],
+    wiring: &WiringCommitments<P>,
+    proof: &HolographicGKRProof<F, P>,
+    verifier_setup: &VerifierSetup<P>,
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    let circuit_len = wiring.layer_bits.len();
+    if circuit_len == 0
+        || proof.p_proofs.len() != circuit_len
+        || proof.w_i_evals.len() != circuit_len
+        || proof.wiring_openings.len() != circuit_len
+    {
+        return false;
+    }
+
+    let mut transcript = transcript_domain.transcript();
+    wiring.absorb_into(&mut transcript);
+
+    let w_0 = match PaddedLayer::from_padded(proof.output_layer.clone(), wiring.output_len) {
+        Some(w_0) => w_0,
+        None => return false,
+    };
+    w_0.absorb_into(&mut transcript);
+    let r_a_challenges: Vec<F> = (0..proof.output_layer.len().ilog2())
+        .map(|_| transcript.squeeze_field::<F>())
+        .collect();
+    if r_a_challenges.len() != wiring.layer_bits[circuit_len - 1].0 as usize {
+        return false;
+    }
+
+    //=========================================================================================
+    // GKR layers, addᵢ/mulᵢ come from openings of the wiring commitments
+    //=========================================================================================
+    let mut claimed_sum = MultiLinearPoly::new(&proof.output_layer)
+        .evaluate(&r_a_challenges)
+        .computation[0];
+    let mut wiring_prefixes = vec![r_a_challenges];
+    let mut combiners = vec![F::one()];
+
+    for (i, p_proof) in proof.p_proofs.iter().enumerate() {
+        let layer_i = circuit_len - i;
+        let (_, input_bits) = wiring.layer_bits[layer_i - 1];
+        if p_proof.init_claimed_sum != claimed_sum
+            || p_proof.round_polys.len() != 2 * input_bits as usize
+        {
+            return false;
+        }
+
+        let sub_claim = match partial_sum_check::try_verify_in(&transcript, p_proof) {
+            Some(sub_claim) => sub_claim,
+            None => return false,
+        };
+        let (add_eval, mul_eval) = match verify_wiring_openings(
+            wiring,
+            layer_i,
+            &proof.wiring_openings[i],
+            &wiring_prefixes,
+            &combiners,
+            &sub_claim.challenges,
+            verifier_setup,
+        ) {
+            Some(evals) => evals,
+            None => return false,
+        };
+
+        let (w_i_b, w_i_c) = proof.w_i_evals[i];
+        transcript.absorb(&MultiLinearPoly::to_bytes(&[w_i_b, w_i_c]));
+
+        if layer_f_eval(add_eval, mul_eval, w_i_b, w_i_c) != sub_claim.last_claimed_sum {
+            return false;
+        }
+
+        let mid = sub_claim.challenges.len() / 2;
+        let (r_b_challenges, r_c_challenges) = sub_claim.challenges.split_at(mid);
+
+        if layer_i == 1 {
+            let mut input_poly = match InputPadding::new(inputs.len(), input_bits) {
+                Some(padding) => padding.mle(inputs),
+                None => return false,
+            };
+            return input_poly.evaluate(r_b_challenges).computation[0] == w_i_b
+                && input_poly.evaluate(r_c_challenges).computation[0] == w_i_c;
+        }
+
+        let (alpha, beta) = squeeze_alpha_beta(&mut transcript);
+        claimed_sum = (alpha * w_i_b) + (beta * w_i_c);
+        wiring_prefixes = vec![r_b_challenges.to_vec(), r_c_challenges.to_vec()];
+        combiners = vec![alpha, beta];
+    }
+
+    false
+}
+
+// checks every opening against the preprocessed commitments and folds them with the combiners
+fn verify_wiring_openings<F: PrimeField, P: Pairing>(
+    wiring: &WiringCommitments<P>,
+    layer_i: usize,
+    openings: &WiringOpenings<F, P>,
+    prefixes: &[Vec<F>],
+    combiners: &[F],
+    challenges: &[F],
+    verifier_setup: &VerifierSetup<P>,
+) -> Option<(F, F)> {
+    let num_vars = wiring.num_vars(layer_i);
+    if num_vars > verifier_setup.max_num_vars()
+        || openings.add_openings.len() != prefixes.len()
+        || openings.mul_openings.len() != prefixes.len()
+    {
+        return None;
+    }
+
+    let wiring_setup = verifier_setup.trim(num_vars);
+    let mut add_eval = F::zero();
+    let mut mul_eval = F::zero();
+
+    for (j, prefix) in prefixes.iter().enumerate() {
+        let point = [prefix.as_slice(), challenges].concat();
+        let (add_opening, mul_opening) = (&openings.add_openings[j], &openings.mul_openings[j]);
+
+        if point.len() != num_vars
+            || add_opening.commitment != wiring.add_commitments[layer_i - 1]
+            || mul_opening.commitment != wiring.mul_commitments[layer_i - 1]
+            || add_opening.quotient_evals.len() != num_vars
+            || mul_opening.quotient_evals.len() != num_vars
+        {
+            return None;
+        }
+
+        for opening in [add_opening, mul_opening] {
+            if !kzg_protocol::verify::<F, P>(opening, &wiring_setup, &point) {
+                return None;
+            }
+        }
+
+        add_eval += combiners[j] * add_opening.poly_opened;
+        mul_eval += combiners[j] * mul_opening.poly_opened;
+    }
+
+    Some((add_eval, mul_eval))
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::gkr::{gkr_circuit::GateOp, test_circuits::binary_tree_circuit};
+    use crate::kzg::trusted_setup::tests::setup_of_size;
+    use crate::transcript::test::domain;
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    // ((1 + 2) * (3 * 4)) + ((5 * 6) * (7 * 8)) = 1716
+    fn setup_test_circuit() -> Circuit<BlsFr> {
+        binary_tree_circuit(
+            (1..=8).map(BlsFr::from).collect(),
+            &[
+                &[GateOp::Add, GateOp::Mul, GateOp::Mul, GateOp::Mul],
+                &[GateOp::Mul, GateOp::Mul],
+                &[GateOp::Add],
+            ],
+        )
+    }
+
+    // the widest wiring (layer 1) has 2 + 2 * 3 = 8 variables
+    fn setup() -> TrustedSetup<Bls12_381> {
+        setup_of_size(8)
+    }
+
+    #[test]
+    fn test_holographic_verify() {
+        let circuit = setup_test_circuit();
+        let setup = setup();
+        let wiring = circuit.commit_wiring::<Bls12_381>(&setup);
+
+        let proof = circuit.holographic_proof::<Bls12_381>(&setup, &wiring, &domain());
+        assert_eq!(proof.wiring_openings[0].add_openings.len(), 1);
+        assert_eq!(proof.wiring_openings[1].add_openings.len(), 2);
+
+        let result = holographic_verify::<BlsFr, Bls12_381>(
+            &circuit.inputs,
+            &wiring,
+            &proof,
+            &setup.verifier_setup,
+            &domain(),
+        );
+        assert!(result);
+    }
+
+    #[test]
+    fn test_holographic_rejects_other_wiring() {
+        let circuit = setup_test_circuit();
+        let setup = setup();
+        let wiring = circuit.commit_wiring::<Bls12_381>(&setup);
+        let proof = circuit.holographic_proof::<Bls12_381>(&setup, &wiring, &domain());
+
+        // same shape, but the top gate multiplies instead of adding
+        let mut other_circuit = setup_test_circuit();
+        other_circuit.layers[2].gates[0].op = GateOp::Mul;
+        let other_wiring = other_circuit.commit_wiring::<Bls12_381>(&setup);
+
+        let result = holographic_verify::<BlsFr, Bls12_381>(
+            &circuit.inputs,
+            &other_wiring,
+            &proof,
+            &setup.verifier_setup,
+            &domain(),
+        );
+        assert!(!result);
+    }
+
+    #[test]
+    fn test_holographic_rejects_tampered_round_poly() {
+        let circuit = setup_test_circuit();
+        let setup = setup();
+        let wiring = circuit.commit_wiring::<Bls12_381>(&setup);
+        let mut proof = circuit.holographic_proof::<Bls12_381>(&setup, &wiring, &domain());
+
+        proof.p_proofs[1].round_polys[1][0] += BlsFr::from(1);
+
+        let result = holographic_verify::<BlsFr, Bls12_381>(
+            &circuit.inputs,
+            &wiring,
+            &proof,
+            &setup.verifier_setup,
+            &domain(),
+        );
+        assert!(!result);
+    }
+
+    #[test]
+    fn test_holographic_rejects_wrong_wiring_eval() {
+        let circuit = setup_test_circuit();
+        let setup = setup();
+        let wiring = circuit.commit_wiring::<Bls12_381>(&setup);
+        let mut proof = circuit.holographic_proof::<Bls12_381>(&setup, &wiring, &domain());
+
+        proof.wiring_openings[1].mul_openings[0].poly_opened += BlsFr::from(1);
+
+        let result = holographic_verify::<BlsFr, Bls12_381>(
+            &circuit.inputs,
+            &wiring,
+            &proof,
+            &setup.verifier_setup,
+            &domain(),
+        );
+        assert!(!result);
+    }
+}
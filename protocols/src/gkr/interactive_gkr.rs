@@ -0,0 +1,348 @@
+use crate::{
+    fiat_shamir,
+    gkr::{
+        gkr_circuit::Circuit,
+        gkr_protocol::layer_f_eval,
+        padded_layer::PaddedLayer,
+        partial_sum_check::{fold_sum_poly, round_poly, verify_round},
+        product_poly::ProductPoly,
+        prover_state::ProverState,
+    },
+    iop::gkr_iop::{GKRProverMsg, GKRIOP},
+    lagrange::LagrangeWeights,
+    multi_linear::{EvaluationTable, MultiLinearPoly},
+    transcript::{Transcript, TranscriptDomain},
+};
+use ark_ff::PrimeField;
+#[cfg(feature = "prover")]
+use {crate::iop::iop_trait, rand::Rng};
+
+//=========================================================================================
+// Interactive GKR
+// Same layer logic as the Fiat-Shamir prover (ProverState, gkr_trick, partial sum-check rounds)
+// but every challenge is sampled by a real verifier instead of being squeezed from a transcript,
+// alpha and beta included: they are sampled after (Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)) are sent, a prover who
+// knew them before could pick the two evals so that only their combination is right
+//=========================================================================================
+pub struct GKRProverStruct<'a, F: PrimeField> {
+    state: ProverState<'a, F>,
+    layer_idx: usize, // the layer whose add/mul wiring is currently being sum-checked
+    sum_poly: Vec<ProductPoly<F>>,
+    challenges: Vec<F>,
+}
+
+impl<'a, F: PrimeField> GKRProverStruct<'a, F> {
+    pub fn new(circuit: &'a Circuit<F>) -> Self {
+        let state = ProverState::new(circuit);
+        let layer_idx = state.circuit_len();
+
+        GKRProverStruct {
+            state,
+            layer_idx,
+            sum_poly: Vec::new(),
+            challenges: Vec::new(),
+        }
+    }
+
+    pub fn output_layer(&self) -> Vec<F> {
+        self.state.padded_output_layer().into_padded()
+    }
+
+    pub fn receive_r_a(&mut self, r_a_challenges: &[F]) {
+        let (add_i, mul_i) = self
+            .state
+            .circuit
+            .layer_i_add_mul_at(self.layer_idx, r_a_challenges);
+
+        self.sum_poly = self.state.layer_sum_poly(self.layer_idx - 1, add_i, mul_i);
+    }
+
+    // number of sum-check rounds for the current layer i.e. number of (b, c) variables
+    pub fn num_rounds(&self) -> usize {
+        self.sum_poly[0].poly_array[0].computation.len().ilog2() as usize
+    }
+
+    pub fn round_poly(&self) -> Vec<F> {
+        round_poly(&self.sum_poly)
+    }
+
+    pub fn receive_challenge(&mut self, challenge: F) {
+        self.sum_poly = fold_sum_poly(&self.sum_poly, challenge);
+        self.challenges.push(challenge);
+    }
+
+    // ends the current layer by sending (Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)) and moves down to the next layer,
+    // whose sum-check only starts once the verifier's alpha and beta are in
+    pub fn w_evals(&mut self) -> (F, F) {
+        self.layer_idx -= 1;
+
+        self.state.w_i_evals(self.layer_idx, &self.challenges)
+    }
+
+    // the next layer's sum-check is over α * addᵢ(r_b, b, c) + β * addᵢ(r_c, b, c) (and mulᵢ)
+    pub fn receive_alpha_beta(&mut self, alpha: F, beta: F) {
+        let (new_add, new_mul) =
+            self.state
+                .circuit
+                .gkr_trick(&self.challenges, self.layer_idx, (alpha, beta));
+        self.sum_poly = self
+            .state
+            .layer_sum_poly(self.layer_idx - 1, new_add, new_mul);
+        self.challenges.clear();
+    }
+}
+
+pub struct GKRVerifierStruct<'a, F: PrimeField> {
+    pub circuit: &'a Circuit<F>,
+    layer_idx: usize,
+    claimed_sum: F,
+    r_a_challenges: Vec<F>,
+    prev_challenges: Vec<F>,
+    pub challenges: Vec<F>,
+    w_evals: (F, F), // the last (Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)), folded once alpha and beta are sampled
+    alpha_beta: (F, F), // what the current layer's addᵢ/mulᵢ were folded with
+    round_weights: Option<LagrangeWeights<F>>, // rebuilt only when the round poly degree changes
+}
+
+// the verifier never samples anything itself, every challenge is handed in by the caller
+// (an rng when run interactively, a transcript once Fiat-Shamir is applied)
+impl<'a, F: PrimeField> GKRVerifierStruct<'a, F> {
+    pub fn new(circuit: &'a Circuit<F>) -> Self {
+        GKRVerifierStruct {
+            circuit,
+            layer_idx: circuit.layers.len(),
+            claimed_sum: F::zero(),
+            r_a_challenges: Vec::new(),
+            prev_challenges: Vec::new(),
+            challenges: Vec::new(),
+            w_evals: (F::zero(), F::zero()),
+            alpha_beta: (F::zero(), F::zero()),
+            round_weights: None,
+        }
+    }
+
+    // length of the padded output layer the prover must send
+    pub fn output_layer_len(&self) -> usize {
+        PaddedLayer::<F>::padded_len(self.circuit.output_len())
+    }
+
+    // sets the initial claim to W₀(r_a), r_a has one challenge per output variable
+    pub fn receive_output_layer(&mut self, w_0_arr: &[F], r_a_challenges: Vec<F>) {
+        self.claimed_sum = MultiLinearPoly::new(w_0_arr)
+            .evaluate(&r_a_challenges)
+            .computation[0];
+
+        self.r_a_challenges = r_a_challenges;
+    }
+
+    pub fn received_output_layer(&self) -> bool {
+        !self.r_a_challenges.is_empty()
+    }
+
+    // number of sum-check rounds still expected for the current layer
+    pub fn num_rounds(&self) -> usize {
+        let (_, input_bits) = self.circuit.layer_bits(self.layer_idx);
+
+        2 * input_bits as usize - self.challenges.len()
+    }
+
+    // false if the round polynomial is inconsistent with the claim
+    pub fn receive_round_poly(&mut self, round_poly: &[F], challenge: F) -> bool {
+        if round_poly.len() < 2 {
+            return false;
+        }
+        if self
+            .round_weights
+            .as_ref()
+            .is_none_or(|weights| weights.len() != round_poly.len())
+        {
+            self.round_weights = Some(LagrangeWeights::for_degree(round_poly.len() - 1));
+        }
+        let weights = self.round_weights.as_ref().unwrap();
+
+        match verify_round(self.claimed_sum, round_poly, weights, challenge) {
+            Some(next_claimed_sum) => {
+                self.claimed_sum = next_claimed_sum;
+                self.challenges.push(challenge);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // oracle check for the current layer, (Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)) become the next claim once
+    // receive_alpha_beta has them, at the input layer the verifier evaluates the inputs itself
+    pub fn receive_w_evals(&mut self, w_b: F, w_c: F) -> bool {
+        let (add_i, mul_i) = if self.layer_idx == self.circuit.layers.len() {
+            self.circuit
+                .layer_i_add_mul_at(self.layer_idx, &self.r_a_challenges)
+        } else {
+            self.circuit
+                .gkr_trick(&self.prev_challenges, self.layer_idx, self.alpha_beta)
+        };
+
+        let table = EvaluationTable::new(&self.challenges);
+        let add_eval = table.evaluate(&add_i);
+        let mul_eval = table.evaluate(&mul_i);
+
+        if layer_f_eval(add_eval, mul_eval, w_b, w_c) != self.claimed_sum {
+            return false;
+        }
+
+        self.layer_idx -= 1;
+        self.prev_challenges = std::mem::take(&mut self.challenges);
+
+        if self.layer_idx == 0 {
+            let mid = self.prev_challenges.len() / 2;
+            let (r_b_challenges, r_c_challenges) = self.prev_challenges.split_at(mid);
+
+            let mut input_poly = self.circuit.input_poly(&self.circuit.inputs);
+            let input_eval_b = input_poly.evaluate(r_b_challenges).computation[0];
+            let input_eval_c = input_poly.evaluate(r_c_challenges).computation[0];
+
+            return input_eval_b == w_b && input_eval_c == w_c;
+        }
+
+        self.w_evals = (w_b, w_c);
+
+        true
+    }
+
+    // alpha and beta are sampled by the caller after receive_w_evals, never before
+    pub fn receive_alpha_beta(&mut self, alpha: F, beta: F) {
+        let (w_b, w_c) = self.w_evals;
+        self.claimed_sum = (alpha * w_b) + (beta * w_c);
+        self.alpha_beta = (alpha, beta);
+    }
+
+    pub fn reached_input_layer(&self) -> bool {
+        self.layer_idx == 0
+    }
+}
+
+/*
+   STEPS FOR RUNNING THE INTERACTIVE GKR PROTOCOL
+   1 => The prover sends the (padded) output layer and the verifier answers with r_a
+   2 => For every layer, run the sum-check rounds: prover sends round_poly, verifier answers with a challenge
+   3 => The prover sends (Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)), the verifier performs the layer oracle check and
+        answers with alpha and beta for the next layer's claim
+   4 => Repeat 2 & 3 until the input layer, which the verifier evaluates by itself
+   the message flow lives in iop::gkr_iop, this just runs it with coins from the rng
+*/
+#[cfg(feature = "prover")]
+pub fn run_interactive<F: PrimeField, R: Rng>(circuit: &Circuit<F>, mut rng: R) -> bool {
+    let mut prover = GKRProverStruct::new(circuit);
+    let mut verifier = GKRVerifierStruct::new(circuit);
+
+    iop_trait::run_interactive::<F, GKRIOP, _>(&mut prover, &mut verifier, &mut rng)
+}
+
+// the same message flow made non-interactive with fiat_shamir::compile
+// the circuit inputs are the statement, the output layer is the first prover message
+pub fn fiat_shamir_proof<F: PrimeField>(
+    circuit: &Circuit<F>,
+    transcript_domain: &TranscriptDomain,
+) -> Vec<GKRProverMsg<F>> {
+    let mut prover = GKRProverStruct::new(circuit);
+    let mut verifier = GKRVerifierStruct::new(circuit);
+
+    fiat_shamir::compile::<F, GKRIOP, _>(
+        &mut prover,
+        &mut verifier,
+        &mut statement_transcript(circuit, transcript_domain),
+    )
+}
+
+pub fn fiat_shamir_verify<F: PrimeField>(
+    circuit: &Circuit<F>,
+    proof: &[GKRProverMsg<F>],
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    let mut verifier = GKRVerifierStruct::new(circuit);
+
+    fiat_shamir::verify::<F, GKRIOP, _>(
+        &mut verifier,
+        proof,
+        &mut statement_transcript(circuit, transcript_domain),
+    )
+}
+
+fn statement_transcript<F: PrimeField>(
+    circuit: &Circuit<F>,
+    transcript_domain: &TranscriptDomain,
+) -> Transcript {
+    let mut transcript = transcript_domain.transcript();
+    transcript.absorb(&MultiLinearPoly::to_bytes(&circuit.inputs));
+
+    transcript
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{gkr::gkr_circuit::test::setup_test_circuit8, transcript::test::domain};
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_run_interactive() {
+        let circuit = setup_test_circuit8();
+
+        assert!(run_interactive(&circuit, rand::thread_rng()));
+    }
+
+    #[test]
+    fn test_fiat_shamir_gkr() {
+        let circuit = setup_test_circuit8();
+        let proof = fiat_shamir_proof(&circuit, &domain());
+        assert!(fiat_shamir_verify(&circuit, &proof, &domain()));
+
+        let mut tampered = proof.clone();
+        if let GKRProverMsg::WEvals(w_b, _) = tampered.last_mut().unwrap() {
+            *w_b += Fq::from(1);
+        }
+        assert!(!fiat_shamir_verify(&circuit, &tampered, &domain()));
+        assert!(!fiat_shamir_verify(
+            &circuit,
+            &proof[..proof.len() - 1],
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_interactive_rejects_bad_round_poly() {
+        let circuit = setup_test_circuit8();
+        let mut prover = GKRProverStruct::new(&circuit);
+        let mut verifier = GKRVerifierStruct::new(&circuit);
+
+        let r_a_challenges = vec![Fq::from(5)];
+        verifier.receive_output_layer(&prover.output_layer(), r_a_challenges.clone());
+        prover.receive_r_a(&r_a_challenges);
+
+        let mut round_poly = prover.round_poly();
+        round_poly[0] += Fq::from(1);
+
+        assert!(!verifier.receive_round_poly(&round_poly, Fq::from(7)));
+    }
+
+    #[test]
+    fn test_interactive_rejects_bad_w_evals() {
+        let circuit = setup_test_circuit8();
+        let mut prover = GKRProverStruct::new(&circuit);
+        let mut verifier = GKRVerifierStruct::new(&circuit);
+
+        let r_a_challenges = vec![Fq::from(5)];
+        verifier.receive_output_layer(&prover.output_layer(), r_a_challenges.clone());
+        prover.receive_r_a(&r_a_challenges);
+
+        assert_eq!(verifier.num_rounds(), prover.num_rounds());
+        for i in 0..prover.num_rounds() {
+            let challenge = Fq::from(i as u64 + 11);
+            assert!(verifier.receive_round_poly(&prover.round_poly(), challenge));
+            prover.receive_challenge(challenge);
+        }
+        assert_eq!(verifier.num_rounds(), 0);
+
+        let (w_b, w_c) = prover.w_evals();
+        assert!(!verifier.receive_w_evals(w_b + Fq::from(1), w_c));
+    }
+}
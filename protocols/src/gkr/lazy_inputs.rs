@@ -0,0 +1,502 @@
+use crate::{
+    commitment::{MerkleCommitment, VectorCommitment},
+    eval_claim::PolyId,
+    explain::Explain,
+    fri::merkle_tree::MerkleProof,
+    gkr::{
+        gkr_circuit::Circuit,
+        padded_layer::PaddedLayer,
+        partial_sum_check::Proof,
+        public_inputs::PublicInputs,
+        succinct_gkr::{statement_transcript, InputOpening},
+    },
+    kzg::split_kzg::{self, SplitCommitment, SplitKZGProof},
+    kzg::trusted_setup::VerifierSetup,
+    multi_linear::MultiLinearPoly,
+    progress::{never_cancelled, Progress},
+    transcript::{public_digest, Transcript, TranscriptDomain, DIGEST_LEN},
+    transcript_schedule::Step,
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use std::borrow::Cow;
+
+use super::{
+    chunked_sum_check::ProverConfig, core, gkr_2_to_1_trick::LayerReduction,
+    gkr_protocol::LayerProver, lookup::LayerLookups, proof_view::GKRProofSource,
+    prover_state::ProverState,
+};
+
+//=========================================================================================
+// GKR over an input layer the verifier only knows through a commitment
+// the layers are proven and checked as in the succinct GKR, the statement is the digests, the
+// outputs and the commitment (core::SUCCINCT_GKR_SCHEDULE), and the walk ends on two claims
+// about the inputs, W_in(r_b) and W_in(r_c)
+// what checks those claims is left to an InputOracle, which resolves W_in at a point on demand
+// from whatever backs the commitment: split KZG openings sent along with the proof
+// (KzgInputs, what succinct_verify uses) or Merkle queries for the leaves (MerkleInputs)
+// a new backend is one more InputOracle, the verifier in verify_committed_inputs is unchanged
+//=========================================================================================
+
+// how a commitment to the inputs goes into the transcript, one "segment commitment" per part
+pub trait InputCommitment {
+    fn absorb_into(&self, transcript: &mut Transcript);
+}
+
+// W_in at a point, None when the backend can't vouch for a value there
+// setup_digest names the parameters of the backend and is absorbed right after the circuit's
+pub trait InputOracle<F: PrimeField> {
+    type Commitment: InputCommitment;
+
+    fn commitment(&self) -> &Self::Commitment;
+    fn setup_digest(&self) -> [u8; DIGEST_LEN];
+    fn resolve(&mut self, point: &[F]) -> Option<F>;
+}
+
+// the GKR part of a proof over committed inputs, the openings are the backend's business
+#[derive(Debug, Clone)]
+pub struct CommittedInputProof<F: PrimeField> {
+    pub output_layer: Vec<F>,
+    pub w_i_evals: Vec<(F, F)>,
+    pub p_proofs: Vec<Proof<F>>,
+}
+
+impl<F: PrimeField> CommittedInputProof<F> {
+    // r_b and r_c, where the prover has to open the inputs for the verifier's oracle
+    pub fn input_points(&self) -> [Vec<F>; 2] {
+        let point = &self.p_proofs.last().unwrap().challenges;
+        let (r_b, r_c) = point.split_at(point.len() / 2);
+
+        [r_b.to_vec(), r_c.to_vec()]
+    }
+
+    // W_in(r_b) and W_in(r_c) as claimed by the last layer
+    pub fn input_evals(&self) -> (F, F) {
+        *self.w_i_evals.last().unwrap()
+    }
+}
+
+pub trait CommittedInputGKR<F: PrimeField> {
+    fn committed_input_proof(
+        &self,
+        commitment: &impl InputCommitment,
+        setup_digest: &[u8; DIGEST_LEN],
+        config: &ProverConfig,
+        transcript_domain: &TranscriptDomain,
+    ) -> CommittedInputProof<F>;
+
+    // public.inputs must be empty, the oracle stands for them
+    fn verify_committed_inputs(
+        &self,
+        proof: &impl GKRProofSource<F>,
+        public: &PublicInputs<F>,
+        oracle: &mut impl InputOracle<F>,
+        transcript_domain: &TranscriptDomain,
+    ) -> bool;
+}
+
+impl<F: PrimeField> CommittedInputGKR<F> for Circuit<F> {
+    fn committed_input_proof(
+        &self,
+        commitment: &impl InputCommitment,
+        setup_digest: &[u8; DIGEST_LEN],
+        config: &ProverConfig,
+        transcript_domain: &TranscriptDomain,
+    ) -> CommittedInputProof<F> {
+        if config.reduction != LayerReduction::AlphaBeta {
+            panic!("The committed input GKR only supports the alpha/beta reduction");
+        }
+        let state = ProverState::new(self);
+        let transcript = statement_transcript(
+            transcript_domain,
+            (&self.digest(), setup_digest),
+            &PublicInputs::outputs_only(state.output_layer().to_vec()),
+            commitment,
+        );
+
+        let mut w_i_evals = Vec::new();
+        let mut p_proofs = Vec::new();
+        let mut prover = LayerProver::from_statement(&state, transcript, true);
+        while let Some(layer) =
+            never_cancelled(prover.prove_layer(&state, config, &mut Progress::none()))
+        {
+            w_i_evals.push(layer.w_evals);
+            p_proofs.push(layer.sum_check);
+        }
+
+        CommittedInputProof {
+            output_layer: prover.output_layer,
+            w_i_evals,
+            p_proofs,
+        }
+    }
+
+    fn verify_committed_inputs(
+        &self,
+        proof: &impl GKRProofSource<F>,
+        public: &PublicInputs<F>,
+        oracle: &mut impl InputOracle<F>,
+        transcript_domain: &TranscriptDomain,
+    ) -> bool {
+        let circuit_len = self.layers.len();
+        if !public.inputs.is_empty()
+            || proof.layer_count() != circuit_len
+            || proof.w_i_eval_count() != circuit_len
+            || proof.line_poly_count() != 0
+        {
+            return false;
+        }
+        let output_layer = match proof.output_layer() {
+            Some(output_layer) => output_layer.into_owned(),
+            None => return false,
+        };
+        let mut transcript = statement_transcript(
+            transcript_domain,
+            (&self.digest(), &oracle.setup_digest()),
+            public,
+            oracle.commitment(),
+        );
+
+        //=========================================================================================
+        // The layer checks of gkr::core, W₀ is always sent
+        //=========================================================================================
+        let w_0 = match PaddedLayer::from_padded(output_layer, self.output_len()) {
+            Some(w_0) => w_0,
+            None => return false,
+        };
+        if w_0.values() != public.outputs {
+            return false;
+        }
+        let walk = match core::verify_layers(
+            self,
+            &mut transcript,
+            proof,
+            (&w_0, true),
+            None,
+            &LayerLookups::none(),
+            &mut Explain::off(),
+        ) {
+            Some(walk) => walk,
+            None => return false,
+        };
+
+        //=========================================================================================
+        // The claimed W_in(r_b) and W_in(r_c) against what the oracle resolves at r_b and r_c
+        //=========================================================================================
+        let input_claims = match proof.layer_claims(circuit_len - 1, &walk.point) {
+            Some(input_claims) => input_claims,
+            None => return false,
+        };
+        if !input_claims
+            .iter()
+            .all(|claim| oracle.resolve(&claim.point) == Some(claim.value))
+        {
+            return false;
+        }
+        let [input_eval_b, input_eval_c] = input_claims.map(|claim| claim.value);
+
+        walk.check_inputs(
+            &mut transcript,
+            (input_eval_b, input_eval_c),
+            &mut Explain::off(),
+        )
+    }
+}
+
+impl<F: PrimeField> GKRProofSource<F> for CommittedInputProof<F> {
+    fn layer_count(&self) -> usize {
+        self.p_proofs.len()
+    }
+
+    fn w_i_eval_count(&self) -> usize {
+        self.w_i_evals.len()
+    }
+
+    fn line_poly_count(&self) -> usize {
+        0
+    }
+
+    fn output_layer(&self) -> Option<Cow<'_, [F]>> {
+        Some(Cow::Borrowed(&self.output_layer))
+    }
+
+    fn w_i_evals(&self, i: usize) -> Option<(F, F)> {
+        self.w_i_evals.get(i).copied()
+    }
+
+    fn sum_check(&self, i: usize) -> Option<Cow<'_, Proof<F>>> {
+        self.p_proofs.get(i).map(Cow::Borrowed)
+    }
+
+    fn init_claimed_sum(&self, i: usize) -> Option<F> {
+        self.p_proofs.get(i).map(|p_proof| p_proof.init_claimed_sum)
+    }
+
+    fn line_poly(&self, _: usize) -> Option<Cow<'_, [F]>> {
+        None
+    }
+}
+
+//=========================================================================================
+// Split KZG backend, the openings come with the proof and are taken in order (r_b, then r_c)
+//=========================================================================================
+impl<P: Pairing> InputCommitment for SplitCommitment<P> {
+    fn absorb_into(&self, transcript: &mut Transcript) {
+        for segment in self.segment_commitments.iter() {
+            transcript
+                .follow(Step::Absorb("segment commitment"))
+                .absorb_commitment(segment);
+        }
+    }
+}
+
+pub struct KzgInputs<'a, F: PrimeField, P: Pairing> {
+    commitment: &'a SplitCommitment<P>,
+    openings: &'a [InputOpening<F, SplitKZGProof<F, P>>],
+    verifier_setup: &'a VerifierSetup<P>,
+    transcript_domain: &'a TranscriptDomain,
+    next: usize,
+}
+
+impl<'a, F: PrimeField, P: Pairing> KzgInputs<'a, F, P> {
+    pub fn new(
+        commitment: &'a SplitCommitment<P>,
+        openings: &'a [InputOpening<F, SplitKZGProof<F, P>>],
+        verifier_setup: &'a VerifierSetup<P>,
+        transcript_domain: &'a TranscriptDomain,
+    ) -> Self {
+        KzgInputs {
+            commitment,
+            openings,
+            verifier_setup,
+            transcript_domain,
+            next: 0,
+        }
+    }
+}
+
+impl<F: PrimeField, P: Pairing> InputOracle<F> for KzgInputs<'_, F, P> {
+    type Commitment = SplitCommitment<P>;
+
+    fn commitment(&self) -> &SplitCommitment<P> {
+        self.commitment
+    }
+
+    fn setup_digest(&self) -> [u8; DIGEST_LEN] {
+        self.verifier_setup.digest()
+    }
+
+    fn resolve(&mut self, point: &[F]) -> Option<F> {
+        let opening = self.openings.get(self.next)?;
+        self.next += 1;
+
+        (opening.point == point
+            && split_kzg::verify_claim(
+                self.commitment,
+                &opening.quotients,
+                self.verifier_setup,
+                &opening.claim(PolyId::Input),
+                self.transcript_domain,
+            ))
+        .then_some(opening.value)
+    }
+}
+
+//=========================================================================================
+// Merkle backend, the leaves are the padded input MLE's evaluations, compressed
+// query(i) fetches leaf i and its path (from the prover, a server, a file...) the first time
+// W_in is needed, every path is checked against the root and the leaves are kept for the
+// second point
+// NOTE: an evaluation at a random point needs every leaf, so this is linear in the inputs,
+// what it saves over public inputs is the proof size when the inputs are already committed
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleInputCommitment {
+    pub root: Vec<u8>,
+    pub num_vars: usize,
+}
+
+impl MerkleInputCommitment {
+    pub fn leaf<F: PrimeField>(value: &F) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        value
+            .serialize_compressed(&mut bytes)
+            .expect("Serializing into a Vec cannot fail");
+
+        bytes
+    }
+
+    // a Merkle tree has no setup, the depth is all both sides have to agree on
+    pub fn setup_digest(&self) -> [u8; DIGEST_LEN] {
+        public_digest(&(self.num_vars as u64).to_be_bytes())
+    }
+}
+
+impl InputCommitment for MerkleInputCommitment {
+    fn absorb_into(&self, transcript: &mut Transcript) {
+        transcript
+            .follow(Step::Absorb("segment commitment"))
+            .absorb_merkle_root(&self.root);
+    }
+}
+
+pub struct MerkleInputs<F: PrimeField, Q: FnMut(usize) -> Option<(F, MerkleProof)>> {
+    commitment: MerkleInputCommitment,
+    query: Q,
+    leaves: Option<Vec<F>>,
+}
+
+impl<F: PrimeField, Q: FnMut(usize) -> Option<(F, MerkleProof)>> MerkleInputs<F, Q> {
+    pub fn new(commitment: MerkleInputCommitment, query: Q) -> Self {
+        MerkleInputs {
+            commitment,
+            query,
+            leaves: None,
+        }
+    }
+
+    fn fetch_leaves(&mut self) -> Option<Vec<F>> {
+        let num_leaves = 1usize.checked_shl(self.commitment.num_vars as u32)?;
+        (0..num_leaves)
+            .map(|index| {
+                let (value, path) = (self.query)(index)?;
+                MerkleCommitment
+                    .verify(
+                        &self.commitment.root,
+                        &index,
+                        &MerkleInputCommitment::leaf(&value),
+                        &path,
+                    )
+                    .then_some(value)
+            })
+            .collect()
+    }
+}
+
+impl<F: PrimeField, Q: FnMut(usize) -> Option<(F, MerkleProof)>> InputOracle<F>
+    for MerkleInputs<F, Q>
+{
+    type Commitment = MerkleInputCommitment;
+
+    fn commitment(&self) -> &MerkleInputCommitment {
+        &self.commitment
+    }
+
+    fn setup_digest(&self) -> [u8; DIGEST_LEN] {
+        self.commitment.setup_digest()
+    }
+
+    fn resolve(&mut self, point: &[F]) -> Option<F> {
+        if point.len() != self.commitment.num_vars {
+            return None;
+        }
+        if self.leaves.is_none() {
+            self.leaves = Some(self.fetch_leaves()?);
+        }
+        let leaves = self.leaves.as_ref()?;
+
+        Some(MultiLinearPoly::new(leaves).evaluate(point).computation[0])
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::{
+        fri::merkle_tree::MerkleTree,
+        gkr::{gkr_circuit::GateOp, test_circuits::binary_tree_circuit},
+        kzg::trusted_setup::tests::setup,
+        transcript::test::domain,
+    };
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    // ((1 + 2) * (3 * 4)) + ((5 * 6) * (7 * 8)) = 1716
+    fn setup_test_circuit() -> Circuit<BlsFr> {
+        binary_tree_circuit(
+            (1..=8).map(BlsFr::from).collect(),
+            &[
+                &[GateOp::Add, GateOp::Mul, GateOp::Mul, GateOp::Mul],
+                &[GateOp::Mul, GateOp::Mul],
+                &[GateOp::Add],
+            ],
+        )
+    }
+
+    fn merkle_inputs(circuit: &Circuit<BlsFr>) -> (MerkleInputCommitment, Vec<BlsFr>, MerkleTree) {
+        let poly = circuit.input_poly(&circuit.inputs);
+        let leaves: Vec<Vec<u8>> = poly
+            .computation
+            .iter()
+            .map(MerkleInputCommitment::leaf)
+            .collect();
+        let (root, (_, tree)) = MerkleCommitment.commit(&leaves);
+        let commitment = MerkleInputCommitment {
+            root,
+            num_vars: circuit.input_num_vars(),
+        };
+
+        (commitment, poly.computation, tree)
+    }
+
+    #[test]
+    fn test_merkle_inputs() {
+        let circuit = setup_test_circuit();
+        let public = PublicInputs::outputs_only(circuit.public_inputs().outputs);
+        let (commitment, values, tree) = merkle_inputs(&circuit);
+
+        let proof = circuit.committed_input_proof(
+            &commitment,
+            &commitment.setup_digest(),
+            &ProverConfig::default(),
+            &domain(),
+        );
+
+        // every leaf is queried once, for r_b, and r_c reuses them
+        let mut queries = 0;
+        let mut oracle = MerkleInputs::new(commitment.clone(), |index| {
+            queries += 1;
+            Some((values[index], tree.generate_proof_at(index)?))
+        });
+        assert!(circuit.verify_committed_inputs(&proof, &public, &mut oracle, &domain()));
+        drop(oracle);
+        assert_eq!(queries, values.len());
+
+        // a leaf that isn't the committed one
+        let mut oracle = MerkleInputs::new(commitment.clone(), |index| {
+            let value = values[index] + BlsFr::from((index == 3) as u64);
+            Some((value, tree.generate_proof_at(index)?))
+        });
+        assert!(!circuit.verify_committed_inputs(&proof, &public, &mut oracle, &domain()));
+
+        // the right leaves against another root
+        let mut other = commitment.clone();
+        other.root = MerkleTree::hash(b"another root");
+        let mut oracle = MerkleInputs::new(other, |index| {
+            Some((values[index], tree.generate_proof_at(index)?))
+        });
+        assert!(!circuit.verify_committed_inputs(&proof, &public, &mut oracle, &domain()));
+    }
+
+    #[test]
+    fn test_kzg_inputs_match_succinct_verify() {
+        let circuit = setup_test_circuit();
+        let setup = setup();
+        let public = PublicInputs::outputs_only(circuit.public_inputs().outputs);
+        let domain = domain();
+        let proof = circuit.succinct_proof::<Bls12_381>(&setup, &domain);
+
+        let mut oracle = KzgInputs::new(
+            &proof.commitment,
+            &proof.input_openings,
+            &setup.verifier_setup,
+            &domain,
+        );
+        assert!(circuit.verify_committed_inputs(&proof, &public, &mut oracle, &domain));
+
+        // the openings swapped, r_c's opening where r_b's is expected
+        let swapped: Vec<_> = proof.input_openings.iter().rev().cloned().collect();
+        let mut oracle =
+            KzgInputs::new(&proof.commitment, &swapped, &setup.verifier_setup, &domain);
+        assert!(!circuit.verify_committed_inputs(&proof, &public, &mut oracle, &domain));
+    }
+}
@@ -0,0 +1,698 @@
+use crate::{
+    explain::Explain,
+    gkr::{
+        chunked_sum_check::ProverConfig,
+        gkr_circuit::{eq_at, Circuit},
+        gkr_protocol::{GKRProof, LayerProver, ProofBuilder},
+        padded_layer::PaddedLayer,
+        partial_sum_check::{fold_sum_poly, round_poly, verify_round},
+        product_poly::ProductPoly,
+        prover_state::ProverState,
+        public_inputs::PublicInputs,
+    },
+    lagrange::LagrangeWeights,
+    multi_linear::{EvaluationTable, MultiLinearPoly},
+    progress::{never_cancelled, Progress},
+    transcript::{Transcript, TranscriptDomain},
+    transcript_schedule::Step,
+};
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// Lookup of a GKR layer into a public table (logUp with a grand-sum GKR)
+// every value wᵢ of the layer is in the table t iff, for a random β,
+//   Σᵢ 1 / (β - wᵢ) == Σⱼ mⱼ / (β - tⱼ)       (mⱼ = how often tⱼ is looked up)
+// the table side is public, the verifier sums it itself from the multiplicities in the proof
+// the layer side is a binary tree of fractions p/q, leaves 1 / (β - wᵢ), every parent
+//   p = p_L·q_R + p_R·q_L, q = q_L·q_R
+// and the root p/q is checked against the table sum. Going down the tree is one sum-check per
+// level, a claim on (p, q) at r becomes a claim on the children at (μ, ρ):
+//   p(r) + λ·q(r) = Σₓ eq(r, x)·(p_L·q_R + p_R·q_L + λ·q_L·q_R)(x)
+// and at the leaves q = β - W, so the whole lookup ends in one claim W(point) = value on the
+// layer's MLE (padded to PaddedLayer::padded_len with t₀, so the padding is in the table too)
+// In the GKR (Circuit::proof_with_lookups) that claim is folded into the layer sum-checks:
+//   every lookup runs in a fork of the GKR transcript right after the public inputs and its claim
+//   goes into the GKR transcript, then once the claim the GKR has on layer j is settled
+//   (W₀(r_a) or the reduction of Wⱼ(r_b), Wⱼ(r_c)) γ is squeezed and the sum-check of layer j
+//   starts from claim + γ·v with addⱼ/mulⱼ + γ·addⱼ/mulⱼ(p, b, c)
+//   the t₀ padding is taken off v first (the GKR pads with zeros), the inputs are public so a
+//   lookup on them is checked on the spot
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct LookupTable<F: PrimeField> {
+    pub values: Vec<F>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrandSumLayer<F: PrimeField> {
+    pub round_polys: Vec<Vec<F>>,
+    pub children: [F; 4], // p_L(ρ), p_R(ρ), q_L(ρ), q_R(ρ)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LookupProof<F: PrimeField> {
+    pub multiplicities: Vec<F>,
+    pub root: (F, F),                  // (p, q) of the whole layer
+    pub layers: Vec<GrandSumLayer<F>>, // from the root down to the leaves
+}
+
+// what is left to check once the lookup verifies: W(point) == value
+#[derive(Debug, Clone, PartialEq)]
+pub struct LookupClaim<F: PrimeField> {
+    pub point: Vec<F>,
+    pub value: F,
+}
+
+// "every wire of layer (0 is the inputs, as in Circuit::evaluate) is in table", at most one per layer
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerLookup<F: PrimeField> {
+    pub layer: usize,
+    pub table: LookupTable<F>,
+}
+
+// a GKR proof whose layer sum-checks also discharge the lookups, one LookupProof per LayerLookup
+// in the same order
+pub struct LookupGKRProof<F: PrimeField> {
+    pub gkr: GKRProof<F>,
+    pub lookups: Vec<LookupProof<F>>,
+}
+
+impl<F: PrimeField> LookupTable<F> {
+    pub fn new(values: Vec<F>) -> Self {
+        if values.is_empty() {
+            panic!("A lookup table needs at least one value");
+        }
+
+        LookupTable { values }
+    }
+
+    // 0..2^bits, e.g. range(8) for bytes
+    pub fn range(bits: u32) -> Self {
+        LookupTable::new((0..1u64 << bits).map(F::from).collect())
+    }
+
+    // the values padded to PaddedLayer::padded_len with the first table value
+    pub fn pad(&self, values: &[F]) -> Vec<F> {
+        let mut padded = values.to_vec();
+        padded.resize(PaddedLayer::<F>::padded_len(values.len()), self.values[0]);
+        padded
+    }
+
+    fn absorb_into(&self, transcript: &mut Transcript) {
+        transcript.absorb(&(self.values.len() as u64).to_be_bytes());
+        transcript.absorb(&MultiLinearPoly::to_bytes(&self.values));
+    }
+}
+
+// the sum-check of one tree level, proving side, returns the children at (μ, ρ)
+fn prove_level<F: PrimeField>(
+    transcript: &mut Transcript,
+    point: &[F],
+    p: &[F],
+    q: &[F],
+) -> (GrandSumLayer<F>, Vec<F>) {
+    let lambda = transcript.squeeze_field::<F>();
+    let half = p.len() / 2;
+    let mle = |values: &[F]| MultiLinearPoly::new(values);
+    let eq = EvaluationTable::new(point).weights().to_vec();
+    let lambda_eq: Vec<F> = eq.iter().map(|e| *e * lambda).collect();
+
+    let (p_l, p_r) = p.split_at(half);
+    let (q_l, q_r) = q.split_at(half);
+    let mut sum_poly = vec![
+        ProductPoly::new(vec![mle(&eq), mle(p_l), mle(q_r)]),
+        ProductPoly::new(vec![mle(&eq), mle(p_r), mle(q_l)]),
+        ProductPoly::new(vec![mle(&lambda_eq), mle(q_l), mle(q_r)]),
+    ];
+
+    let mut round_polys = Vec::with_capacity(point.len());
+    let mut rho = Vec::with_capacity(point.len());
+    for _ in 0..point.len() {
+        let round_poly = round_poly(&sum_poly);
+        transcript.absorb(&MultiLinearPoly::to_bytes(&round_poly));
+        let challenge = transcript.squeeze_field::<F>();
+
+        round_polys.push(round_poly);
+        rho.push(challenge);
+        sum_poly = fold_sum_poly(&sum_poly, challenge);
+    }
+
+    let children = [
+        sum_poly[0].poly_array[1].computation[0],
+        sum_poly[1].poly_array[1].computation[0],
+        sum_poly[1].poly_array[2].computation[0],
+        sum_poly[0].poly_array[2].computation[0],
+    ];
+    transcript.absorb(&MultiLinearPoly::to_bytes(&children));
+    let mu = transcript.squeeze_field::<F>();
+
+    let next_point = [vec![mu], rho].concat();
+    (
+        GrandSumLayer {
+            round_polys,
+            children,
+        },
+        next_point,
+    )
+}
+
+fn tree_levels<F: PrimeField>(leaves_p: Vec<F>, leaves_q: Vec<F>) -> Vec<(Vec<F>, Vec<F>)> {
+    let mut levels = vec![(leaves_p, leaves_q)];
+
+    while levels.last().unwrap().0.len() > 1 {
+        let (p, q) = levels.last().unwrap();
+        let half = p.len() / 2;
+        let parent_p = (0..half)
+            .map(|i| p[i] * q[i + half] + p[i + half] * q[i])
+            .collect();
+        let parent_q = (0..half).map(|i| q[i] * q[i + half]).collect();
+        levels.push((parent_p, parent_q));
+    }
+
+    levels
+}
+
+// the values must already be padded to a power of two, panics if one is not in the table
+// the claim is the one verify ends on
+pub fn prove<F: PrimeField>(
+    transcript: &mut Transcript,
+    values: &[F],
+    table: &LookupTable<F>,
+) -> (LookupProof<F>, LookupClaim<F>) {
+    if !values.len().is_power_of_two() {
+        panic!("The looked up values must be padded to a power of two");
+    }
+
+    let mut multiplicities = vec![F::zero(); table.values.len()];
+    for value in values.iter() {
+        let j = table
+            .values
+            .iter()
+            .position(|t| t == value)
+            .expect("A looked up value is not in the table");
+        multiplicities[j] += F::one();
+    }
+
+    table.absorb_into(transcript);
+    transcript.absorb(&MultiLinearPoly::to_bytes(&multiplicities));
+    let beta = transcript.squeeze_field::<F>();
+
+    let leaves_q = values.iter().map(|w| beta - w).collect();
+    let levels = tree_levels(vec![F::one(); values.len()], leaves_q);
+    let (root_p, root_q) = {
+        let (p, q) = levels.last().unwrap();
+        (p[0], q[0])
+    };
+    transcript.absorb(&MultiLinearPoly::to_bytes(&[root_p, root_q]));
+
+    let mut point = vec![];
+    let mut layers = Vec::with_capacity(levels.len() - 1);
+    for (p, q) in levels.iter().rev().skip(1) {
+        let (layer, next_point) = prove_level(transcript, &point, p, q);
+        layers.push(layer);
+        point = next_point;
+    }
+
+    let value = EvaluationTable::new(&point).evaluate_slice(values);
+
+    (
+        LookupProof {
+            multiplicities,
+            root: (root_p, root_q),
+            layers,
+        },
+        LookupClaim { point, value },
+    )
+}
+
+// None if the lookup does not hold, otherwise the claim on the looked up values' MLE
+pub fn verify<F: PrimeField>(
+    transcript: &mut Transcript,
+    num_vars: usize,
+    table: &LookupTable<F>,
+    proof: &LookupProof<F>,
+) -> Option<LookupClaim<F>> {
+    if proof.multiplicities.len() != table.values.len() || proof.layers.len() != num_vars {
+        return None;
+    }
+
+    table.absorb_into(transcript);
+    transcript.absorb(&MultiLinearPoly::to_bytes(&proof.multiplicities));
+    let beta = transcript.squeeze_field::<F>();
+
+    // Σ mⱼ / (β - tⱼ), the table side in the clear
+    let mut table_sum = F::zero();
+    for (t, m) in table.values.iter().zip(proof.multiplicities.iter()) {
+        table_sum += *m * (beta - t).inverse()?;
+    }
+
+    let (mut p_claim, mut q_claim) = proof.root;
+    if q_claim.is_zero() || p_claim != table_sum * q_claim {
+        return None;
+    }
+    transcript.absorb(&MultiLinearPoly::to_bytes(&[p_claim, q_claim]));
+
+    let weights = LagrangeWeights::for_degree(3);
+    let mut point: Vec<F> = vec![];
+    for layer in proof.layers.iter() {
+        if layer.round_polys.len() != point.len() {
+            return None;
+        }
+
+        let lambda = transcript.squeeze_field::<F>();
+        let mut claim = p_claim + lambda * q_claim;
+        let mut rho = Vec::with_capacity(point.len());
+        for round_poly in layer.round_polys.iter() {
+            transcript.absorb(&MultiLinearPoly::to_bytes(round_poly));
+            let challenge = transcript.squeeze_field::<F>();
+            claim = verify_round(claim, round_poly, &weights, challenge)?;
+            rho.push(challenge);
+        }
+
+        let [p_l, p_r, q_l, q_r] = layer.children;
+        let eq: F = point
+            .iter()
+            .zip(rho.iter())
+            .map(|(r, x)| *r * x + (F::one() - r) * (F::one() - x))
+            .product();
+        if claim != eq * (p_l * q_r + p_r * q_l + lambda * q_l * q_r) {
+            return None;
+        }
+
+        transcript.absorb(&MultiLinearPoly::to_bytes(&layer.children));
+        let mu = transcript.squeeze_field::<F>();
+        p_claim = p_l + mu * (p_r - p_l);
+        q_claim = q_l + mu * (q_r - q_l);
+        point = [vec![mu], rho].concat();
+    }
+
+    // every leaf has p = 1 and q = β - W
+    if p_claim != F::one() {
+        return None;
+    }
+
+    Some(LookupClaim {
+        point,
+        value: beta - q_claim,
+    })
+}
+
+//=========================================================================================
+// The lookups inside the GKR, see the banner
+// LayerLookups holds the claims on the layers above the inputs, already moved onto the GKR's
+// zero padded MLEs, the GKR provers and verify_layers take a FoldedLookup from it at each layer
+//=========================================================================================
+#[derive(Clone)]
+pub(crate) struct LayerLookups<F: PrimeField> {
+    claims: Vec<Option<LookupClaim<F>>>, // by layer, never on the inputs
+}
+
+// a lookup claim W(point) = value weighed by gamma into the GKR's claim on its layer
+#[derive(Clone)]
+pub(crate) struct FoldedLookup<F: PrimeField> {
+    point: Vec<F>,
+    value: F,
+    gamma: F,
+}
+
+impl<F: PrimeField> LayerLookups<F> {
+    pub(crate) fn none() -> Self {
+        LayerLookups { claims: Vec::new() }
+    }
+
+    // gamma for the lookup on layer_i if it has one, squeezed once the GKR's own claim on the
+    // layer is in the transcript
+    pub(crate) fn squeeze(
+        &self,
+        transcript: &mut Transcript,
+        layer_i: usize,
+    ) -> Option<FoldedLookup<F>> {
+        let claim = self.claims.get(layer_i)?.as_ref()?;
+        let gamma = transcript
+            .follow(Step::Squeeze("gamma"))
+            .squeeze_field::<F>();
+
+        Some(FoldedLookup {
+            point: claim.point.clone(),
+            value: claim.value,
+            gamma,
+        })
+    }
+}
+
+impl<F: PrimeField> FoldedLookup<F> {
+    // claim + γ·W(point)
+    pub(crate) fn claim(&self, claim: F) -> F {
+        claim + self.gamma * self.value
+    }
+
+    // addᵢ + γ·addᵢ(point, b, c), the same for mulᵢ
+    pub(crate) fn wiring(
+        &self,
+        circuit: &Circuit<F>,
+        layer_i: usize,
+        (add_i, mul_i): (MultiLinearPoly<F>, MultiLinearPoly<F>),
+    ) -> (MultiLinearPoly<F>, MultiLinearPoly<F>) {
+        let table = EvaluationTable::new(&self.point);
+        let (lookup_add, lookup_mul) = circuit
+            .layer_i_add_mul_weighted(layer_i, |output| self.gamma * table.weights()[output]);
+        let sum = |a: MultiLinearPoly<F>, b: MultiLinearPoly<F>| MultiLinearPoly {
+            computation: a
+                .computation
+                .iter()
+                .zip(b.computation.iter())
+                .map(|(a, b)| *a + b)
+                .collect(),
+        };
+
+        (sum(add_i, lookup_add), sum(mul_i, lookup_mul))
+    }
+
+    pub(crate) fn explain(&self, explain: &mut Explain) {
+        explain.claim("lookup W(p)", self.value);
+        explain.challenge("gamma", self.gamma);
+    }
+}
+
+fn layer_len<F: PrimeField>(circuit: &Circuit<F>, layer: usize) -> usize {
+    match layer {
+        0 => circuit.inputs.len(),
+        _ => circuit.layers[layer - 1].gates.len(),
+    }
+}
+
+// every lookup on a layer of the circuit and no layer twice
+fn lookups_fit<F: PrimeField>(circuit: &Circuit<F>, lookups: &[LayerLookup<F>]) -> bool {
+    lookups.iter().enumerate().all(|(k, lookup)| {
+        lookup.layer <= circuit.layers.len()
+            && lookups[..k].iter().all(|other| other.layer != lookup.layer)
+    })
+}
+
+fn lookup_fork(transcript: &Transcript, layer: usize) -> Transcript {
+    transcript.fork(&[b"lookup".as_slice(), &(layer as u64).to_be_bytes()].concat())
+}
+
+fn absorb_claim<F: PrimeField>(transcript: &mut Transcript, claim: &LookupClaim<F>) {
+    transcript
+        .follow(Step::Absorb("lookup claim"))
+        .absorb(&MultiLinearPoly::to_bytes(
+            &[claim.point.as_slice(), &[claim.value]].concat(),
+        ));
+}
+
+// the claim on the zero padded MLE the GKR uses, t₀ times the weight of the padding taken off
+fn unpadded<F: PrimeField>(
+    claim: LookupClaim<F>,
+    len: usize,
+    table: &LookupTable<F>,
+) -> LookupClaim<F> {
+    let padding: F = (len..1 << claim.point.len())
+        .map(|k| eq_at(k, &claim.point))
+        .sum();
+
+    LookupClaim {
+        value: claim.value - table.values[0] * padding,
+        point: claim.point,
+    }
+}
+
+// the lookup proofs, each in a fork of transcript (the GKR transcript after the public inputs),
+// and the claims the layers fold in
+fn prove_lookups<F: PrimeField>(
+    state: &ProverState<F>,
+    transcript: &mut Transcript,
+    lookups: &[LayerLookup<F>],
+) -> (LayerLookups<F>, Vec<LookupProof<F>>) {
+    let circuit = state.circuit;
+    if !lookups_fit(circuit, lookups) {
+        panic!("The lookups must be on distinct layers of the circuit");
+    }
+
+    let mut claims = vec![None; circuit.layers.len() + 1];
+    let mut proofs = Vec::with_capacity(lookups.len());
+    for lookup in lookups.iter() {
+        // the state keeps the inputs padded with zeros to what layer 1 addresses, cut that off
+        let len = layer_len(circuit, lookup.layer);
+        let values = lookup.table.pad(&state.layer(lookup.layer)[..len]);
+
+        let (proof, claim) = prove(
+            &mut lookup_fork(transcript, lookup.layer),
+            &values,
+            &lookup.table,
+        );
+        absorb_claim(transcript, &claim);
+        if lookup.layer > 0 {
+            claims[lookup.layer] = Some(unpadded(claim, len, &lookup.table));
+        }
+        proofs.push(proof);
+    }
+
+    (LayerLookups { claims }, proofs)
+}
+
+// the verifying side of prove_lookups, a lookup on the inputs is checked here against the public
+// inputs, None as soon as a lookup fails
+pub(crate) fn verify_lookups<F: PrimeField>(
+    circuit: &Circuit<F>,
+    transcript: &mut Transcript,
+    public: &PublicInputs<F>,
+    (lookups, proofs): (&[LayerLookup<F>], &[LookupProof<F>]),
+) -> Option<LayerLookups<F>> {
+    if lookups.len() != proofs.len() || !lookups_fit(circuit, lookups) {
+        return None;
+    }
+
+    let mut claims = vec![None; circuit.layers.len() + 1];
+    for (lookup, proof) in lookups.iter().zip(proofs.iter()) {
+        let len = match lookup.layer {
+            0 => public.inputs.len(),
+            layer => layer_len(circuit, layer),
+        };
+        let num_vars = PaddedLayer::<F>::padded_len(len).ilog2() as usize;
+
+        let claim = verify(
+            &mut lookup_fork(transcript, lookup.layer),
+            num_vars,
+            &lookup.table,
+            proof,
+        )?;
+        absorb_claim(transcript, &claim);
+        if lookup.layer == 0 {
+            let inputs = lookup.table.pad(&public.inputs);
+            if EvaluationTable::new(&claim.point).evaluate_slice(&inputs) != claim.value {
+                return None;
+            }
+        } else {
+            claims[lookup.layer] = Some(unpadded(claim, len, &lookup.table));
+        }
+    }
+
+    Some(LayerLookups { claims })
+}
+
+impl<F: PrimeField> Circuit<F> {
+    pub fn proof_with_lookups(
+        &self,
+        lookups: &[LayerLookup<F>],
+        config: &ProverConfig,
+        transcript_domain: &TranscriptDomain,
+    ) -> LookupGKRProof<F> {
+        let state = ProverState::new(self);
+        let mut transcript = LayerProver::statement(&state, transcript_domain);
+        let (layer_lookups, lookup_proofs) = prove_lookups(&state, &mut transcript, lookups);
+
+        let mut prover = LayerProver::from_statement_with_lookups(
+            &state,
+            transcript,
+            self.output_len() != 1,
+            layer_lookups,
+        );
+        let mut builder = ProofBuilder::with_capacity(state.circuit_len(), config.reduction);
+        while let Some(layer) =
+            never_cancelled(prover.prove_layer(&state, config, &mut Progress::none()))
+        {
+            builder.push_layer(layer);
+        }
+
+        LookupGKRProof {
+            gkr: builder.finish(prover.output_layer),
+            lookups: lookup_proofs,
+        }
+    }
+
+    // lookups must be the ones the proof was made with, in the same order
+    pub fn verify_with_lookups(
+        &self,
+        proof: &LookupGKRProof<F>,
+        public: &PublicInputs<F>,
+        lookups: &[LayerLookup<F>],
+        transcript_domain: &TranscriptDomain,
+    ) -> bool {
+        self.verify_from_with_lookups(
+            transcript_domain.transcript(),
+            &proof.gkr,
+            public,
+            None,
+            (lookups, &proof.lookups),
+            &mut Explain::off(),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{
+        gkr::{gkr_2_to_1_trick::LayerReduction, gkr_circuit::test::setup_test_circuit8},
+        transcript::test::domain,
+    };
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_lookup_claim_matches_the_values() {
+        let table = LookupTable::<Fq>::range(4);
+        let values = [3, 3, 15, 0, 7, 1, 1, 9].map(Fq::from);
+
+        let (proof, claim) = prove(&mut domain().transcript(), &values, &table);
+        assert_eq!(proof.multiplicities[3], Fq::from(2));
+        assert_eq!(proof.layers.len(), 3);
+        assert_eq!(
+            EvaluationTable::new(&claim.point).evaluate_slice(&values),
+            claim.value
+        );
+        assert_eq!(
+            verify(&mut domain().transcript(), 3, &table, &proof),
+            Some(claim)
+        );
+
+        // multiplicities that don't add up to the leaves break the root check
+        let mut bad = proof.clone();
+        bad.multiplicities[3] = Fq::from(1);
+        bad.multiplicities[4] = Fq::from(1);
+        assert!(verify(&mut domain().transcript(), 3, &table, &bad).is_none());
+
+        let mut bad = proof.clone();
+        bad.layers[1].children[0] += Fq::from(1);
+        assert!(verify(&mut domain().transcript(), 3, &table, &bad).is_none());
+        assert!(verify(&mut domain().transcript(), 2, &table, &proof).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "A looked up value is not in the table")]
+    fn test_value_outside_the_table() {
+        let table = LookupTable::<Fq>::range(2);
+        prove(&mut domain().transcript(), &[1, 4].map(Fq::from), &table);
+    }
+
+    // every layer of circuit8 is below 2^12
+    fn circuit8_lookups() -> Vec<LayerLookup<Fq>> {
+        (0..=3)
+            .map(|layer| LayerLookup {
+                layer,
+                table: LookupTable::range(12),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_gkr_with_lookups_on_every_layer() {
+        let circuit = setup_test_circuit8();
+        let public = circuit.public_inputs();
+        let lookups = circuit8_lookups();
+
+        for reduction in [LayerReduction::AlphaBeta, LayerReduction::Line] {
+            let config = ProverConfig {
+                reduction,
+                ..ProverConfig::default()
+            };
+            let proof = circuit.proof_with_lookups(&lookups, &config, &domain());
+            assert!(circuit.verify_with_lookups(&proof, &public, &lookups, &domain()));
+        }
+
+        // no lookups is the plain GKR proof
+        let proof = circuit.proof_with_lookups(&[], &ProverConfig::default(), &domain());
+        assert_eq!(
+            proof.gkr.to_bytes().unwrap(),
+            circuit.proof(&domain()).to_bytes().unwrap()
+        );
+        assert!(circuit.verify(&proof.gkr, &public, &domain()));
+    }
+
+    #[test]
+    fn test_inner_layer_lookup_is_bound_to_the_gkr() {
+        let circuit = setup_test_circuit8();
+        let public = circuit.public_inputs();
+        let lookups = circuit8_lookups()[1..3].to_vec();
+        let proof = circuit.proof_with_lookups(&lookups, &ProverConfig::default(), &domain());
+        assert!(circuit.verify_with_lookups(&proof, &public, &lookups, &domain()));
+
+        // a lookup proof on layer 2 that verifies on its own, in the transcript the verifier
+        // forks it from, but for other values: its claim is not the layer's MLE and the layer
+        // sum-check that folds it in fails
+        let mut other = circuit.evaluate()[2].clone();
+        other.swap(0, 1);
+        let state = ProverState::new(&circuit);
+        let mut transcript = LayerProver::statement(&state, &domain());
+        let claim = verify(
+            &mut lookup_fork(&transcript, 1),
+            2,
+            &lookups[0].table,
+            &proof.lookups[0],
+        )
+        .unwrap();
+        absorb_claim(&mut transcript, &claim);
+        let (forged, forged_claim) = prove(
+            &mut lookup_fork(&transcript, 2),
+            &lookups[1].table.pad(&other),
+            &lookups[1].table,
+        );
+        assert!(verify(
+            &mut lookup_fork(&transcript, 2),
+            1,
+            &lookups[1].table,
+            &forged
+        )
+        .is_some_and(|claim| claim == forged_claim));
+
+        let tampered = LookupGKRProof {
+            gkr: circuit
+                .proof_with_lookups(&lookups, &ProverConfig::default(), &domain())
+                .gkr,
+            lookups: vec![proof.lookups[0].clone(), forged],
+        };
+        assert!(!circuit.verify_with_lookups(&tampered, &public, &lookups, &domain()));
+
+        // and the lookups are only accepted for the layers they were made for
+        let mut swapped = lookups.clone();
+        swapped.swap(0, 1);
+        assert!(!circuit.verify_with_lookups(&proof, &public, &swapped, &domain()));
+        assert!(!circuit.verify_with_lookups(&proof, &public, &lookups[..1], &domain()));
+    }
+
+    #[test]
+    fn test_input_lookup_is_checked_on_the_public_inputs() {
+        let circuit = setup_test_circuit8();
+        let lookups = [LayerLookup {
+            layer: 0,
+            table: LookupTable::range(4),
+        }];
+        let proof = circuit.proof_with_lookups(&lookups, &ProverConfig::default(), &domain());
+        assert!(circuit.verify_with_lookups(&proof, &circuit.public_inputs(), &lookups, &domain()));
+
+        let mut public = circuit.public_inputs();
+        public.inputs[0] += Fq::from(1);
+        assert!(!circuit.verify_with_lookups(&proof, &public, &lookups, &domain()));
+    }
+
+    #[test]
+    #[should_panic(expected = "A looked up value is not in the table")]
+    fn test_gkr_lookup_outside_the_table() {
+        let circuit = setup_test_circuit8();
+        let lookups = [LayerLookup {
+            layer: 3,
+            table: LookupTable::range(2),
+        }];
+        circuit.proof_with_lookups(&lookups, &ProverConfig::default(), &domain());
+    }
+}
@@ -0,0 +1,255 @@
+use crate::gkr::gkr_circuit::{Circuit, WireId};
+use ark_ff::PrimeField;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+//=========================================================================================
+// Named circuit inputs and outputs
+// a hand built circuit is a pile of wire indices, naming the ones that matter lets the witness
+// be assigned by name (circuit.assign(&[("amount", x)])) and lets errors say which value is off
+// inputs are wires of layer 0, outputs wires of the last layer, names are unique per side
+// names are not part of the wiring so they don't change the circuit digest or any proof
+//=========================================================================================
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WireNames {
+    inputs: HashMap<String, WireId>,
+    outputs: HashMap<String, WireId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    UnknownInput(String),
+    UnknownOutput(String),
+    DuplicateName(String),
+    WireOutOfRange {
+        name: String,
+        wire: WireId,
+        len: usize,
+    }, // the named wire is not in its layer
+    UnwrittenWire {
+        layer: usize,
+        wire: WireId,
+    }, // read from a layer that no gate writes it in, see densify
+    NoSuchWire {
+        layer: usize,
+        wire: WireId,
+    }, // layer 0 is the input layer, see epilogue
+    NoOutputLayer,
+    SparseOutputs, // some output wire no gate writes, see densify_outputs
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitError::UnknownInput(name) => write!(f, "the circuit has no input `{}`", name),
+            CircuitError::UnknownOutput(name) => {
+                write!(f, "the circuit has no output `{}`", name)
+            }
+            CircuitError::DuplicateName(name) => write!(f, "`{}` is named twice", name),
+            CircuitError::WireOutOfRange { name, wire, len } => write!(
+                f,
+                "`{}` is {} but its layer only has {} wires",
+                name, wire, len
+            ),
+            CircuitError::UnwrittenWire { layer, wire } => {
+                write!(f, "no gate of layer {} writes {}", layer, wire)
+            }
+            CircuitError::NoSuchWire { layer, wire } => {
+                write!(f, "layer {} of the circuit has no {}", layer, wire)
+            }
+            CircuitError::NoOutputLayer => write!(f, "the circuit has no output layer"),
+            CircuitError::SparseOutputs => {
+                write!(f, "the circuit outputs are not dense, see densify_outputs")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CircuitError {}
+
+impl WireNames {
+    // output names after the output layer (`layer`) is renumbered, nothing changes if one
+    // isn't in `map`
+    pub(crate) fn remap_outputs(
+        &mut self,
+        layer: usize,
+        map: &BTreeMap<WireId, WireId>,
+    ) -> Result<(), CircuitError> {
+        let outputs = self
+            .outputs
+            .iter()
+            .map(|(name, wire)| match map.get(wire) {
+                Some(new) => Ok((name.clone(), *new)),
+                None => Err(CircuitError::UnwrittenWire { layer, wire: *wire }),
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        self.outputs = outputs;
+        Ok(())
+    }
+}
+
+impl<F: PrimeField> Circuit<F> {
+    pub fn name_input(&mut self, name: &str, wire: WireId) -> Result<(), CircuitError> {
+        let len = self.inputs.len();
+        Self::insert_name(&mut self.names.inputs, name, wire, len)
+    }
+
+    // the output layer has to be added before its wires are named
+    pub fn name_output(&mut self, name: &str, wire: WireId) -> Result<(), CircuitError> {
+        let len = self.layers.last().map_or(0, |layer| layer.gates.len());
+        Self::insert_name(&mut self.names.outputs, name, wire, len)
+    }
+
+    fn insert_name(
+        names: &mut HashMap<String, WireId>,
+        name: &str,
+        wire: WireId,
+        len: usize,
+    ) -> Result<(), CircuitError> {
+        if wire.index() >= len {
+            return Err(CircuitError::WireOutOfRange {
+                name: name.to_string(),
+                wire,
+                len,
+            });
+        }
+        if names.contains_key(name) {
+            return Err(CircuitError::DuplicateName(name.to_string()));
+        }
+
+        names.insert(name.to_string(), wire);
+        Ok(())
+    }
+
+    pub fn input(&self, name: &str) -> Result<WireId, CircuitError> {
+        self.names
+            .inputs
+            .get(name)
+            .copied()
+            .ok_or_else(|| CircuitError::UnknownInput(name.to_string()))
+    }
+
+    pub fn output(&self, name: &str) -> Result<WireId, CircuitError> {
+        self.names
+            .outputs
+            .get(name)
+            .copied()
+            .ok_or_else(|| CircuitError::UnknownOutput(name.to_string()))
+    }
+
+    // sets the named inputs, nothing is written unless every name is known
+    pub fn assign(&mut self, assignment: &[(&str, F)]) -> Result<(), CircuitError> {
+        let wires = assignment
+            .iter()
+            .map(|(name, _)| self.input(name))
+            .collect::<Result<Vec<WireId>, _>>()?;
+
+        for (wire, (_, value)) in wires.iter().zip(assignment.iter()) {
+            self.inputs[wire.index()] = *value;
+        }
+
+        Ok(())
+    }
+
+    // the named output of the circuit evaluated on its current inputs
+    pub fn output_value(&self, name: &str) -> Result<F, CircuitError> {
+        let wire = self.output(name)?;
+        let output_layer = self.evaluate().pop().unwrap();
+
+        Ok(output_layer[wire.index()])
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::gkr::gkr_circuit::{Gate, GateOp, Layer};
+    use ark_bn254::Fq;
+
+    // total = amount * price + fee
+    fn invoice() -> Circuit<Fq> {
+        let mut circuit = Circuit::new(vec![Fq::from(0); 4]);
+        circuit.add_layer(Layer {
+            gates: vec![
+                Gate {
+                    left: WireId(0),
+                    right: WireId(1),
+                    op: GateOp::Mul,
+                    output: WireId(0),
+                },
+                Gate {
+                    left: WireId(2),
+                    right: WireId(3),
+                    op: GateOp::Add,
+                    output: WireId(1),
+                },
+            ],
+        });
+        circuit.add_layer(Layer {
+            gates: vec![Gate {
+                left: WireId(0),
+                right: WireId(1),
+                op: GateOp::Add,
+                output: WireId(0),
+            }],
+        });
+
+        for (name, wire) in [("amount", 0), ("price", 1), ("fee", 2)] {
+            circuit.name_input(name, WireId(wire)).unwrap();
+        }
+        circuit.name_output("total", WireId(0)).unwrap();
+
+        circuit
+    }
+
+    #[test]
+    fn test_assign_by_name() {
+        let mut circuit = invoice();
+        circuit
+            .assign(&[
+                ("amount", Fq::from(3)),
+                ("price", Fq::from(7)),
+                ("fee", Fq::from(2)),
+            ])
+            .unwrap();
+
+        assert_eq!(circuit.input("price"), Ok(WireId(1)));
+        assert_eq!(circuit.output_value("total"), Ok(Fq::from(23)));
+    }
+
+    #[test]
+    fn test_unknown_names_are_reported() {
+        let mut circuit = invoice();
+        let result = circuit.assign(&[("amount", Fq::from(3)), ("tax", Fq::from(1))]);
+
+        assert_eq!(result, Err(CircuitError::UnknownInput("tax".to_string())));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "the circuit has no input `tax`"
+        );
+        // nothing was assigned
+        assert_eq!(circuit.inputs[0], Fq::from(0));
+        assert_eq!(
+            circuit.output_value("subtotal"),
+            Err(CircuitError::UnknownOutput("subtotal".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_bad_names_are_rejected() {
+        let mut circuit = invoice();
+
+        assert_eq!(
+            circuit.name_input("amount", WireId(3)),
+            Err(CircuitError::DuplicateName("amount".to_string()))
+        );
+        let result = circuit.name_output("change", WireId(1));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "`change` is wire 1 but its layer only has 1 wires"
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{multi_linear::MultiLinearPoly, transcript::Transcript};
+use ark_ff::PrimeField;
+use std::ops::Range;
+
+//=========================================================================================
+// PaddedLayer is the one padding rule for a layer that has to be an MLE
+// the values are padded with zeros to the next power of 2, and a single value is padded to 2
+// so it still has 1 variable (same rule as layer_bits uses for the gate counts)
+// the true length is kept next to the table and is part of the absorbed bytes, so the prover
+// and the verifier hash exactly the same thing and a verifier can reject non-zero padding
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaddedLayer<F: PrimeField> {
+    true_len: usize,
+    padded: Vec<F>,
+}
+
+impl<F: PrimeField> PaddedLayer<F> {
+    pub fn new(values: &[F]) -> Self {
+        let mut padded = values.to_vec();
+        padded.resize(Self::padded_len(values.len()), F::zero());
+
+        PaddedLayer {
+            true_len: values.len(),
+            padded,
+        }
+    }
+
+    pub fn padded_len(true_len: usize) -> usize {
+        true_len.next_power_of_two().max(2)
+    }
+
+    // None if the table does not follow the padding rule for true_len
+    pub fn from_padded(padded: Vec<F>, true_len: usize) -> Option<Self> {
+        if true_len == 0 || padded.len() != Self::padded_len(true_len) {
+            return None;
+        }
+        if padded[true_len..].iter().any(|val| !val.is_zero()) {
+            return None;
+        }
+
+        Some(PaddedLayer { true_len, padded })
+    }
+
+    pub fn true_len(&self) -> usize {
+        self.true_len
+    }
+
+    pub fn values(&self) -> &[F] {
+        &self.padded[..self.true_len]
+    }
+
+    pub fn padded(&self) -> &[F] {
+        &self.padded
+    }
+
+    pub fn into_padded(self) -> Vec<F> {
+        self.padded
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.padded.len().ilog2() as usize
+    }
+
+    // true length as 8 big endian bytes followed by the padded table
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            (self.true_len as u64).to_be_bytes().to_vec(),
+            MultiLinearPoly::to_bytes(&self.padded),
+        ]
+        .concat()
+    }
+
+    pub fn absorb_into(&self, transcript: &mut Transcript) {
+        transcript.absorb(&self.to_bytes());
+    }
+}
+
+//=========================================================================================
+// InputPadding is the rule for the input layer, which is sized by the wiring reading it and not
+// by its own length: layer 1 addresses 2^input_bits wires (Circuit::layer_bits), so the inputs are
+// padded with zeros to exactly that many before they become an MLE
+// indices true_len..padded_len are padding, the prover's input layer, the verifier's input MLE
+// and the KZG setup that commits to the inputs all use padded_len
+//=========================================================================================
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputPadding {
+    pub true_len: usize,
+    pub padded_len: usize,
+}
+
+impl InputPadding {
+    // None if input_bits can't address every input
+    pub fn new(true_len: usize, input_bits: u32) -> Option<Self> {
+        let padded_len = 1usize.checked_shl(input_bits)?;
+        if true_len == 0 || true_len > padded_len {
+            return None;
+        }
+
+        Some(InputPadding {
+            true_len,
+            padded_len,
+        })
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.padded_len.ilog2() as usize
+    }
+
+    pub fn padding(&self) -> Range<usize> {
+        self.true_len..self.padded_len
+    }
+
+    pub fn is_padding(&self, index: usize) -> bool {
+        self.padding().contains(&index)
+    }
+
+    pub fn pad<F: PrimeField>(&self, inputs: &[F]) -> Vec<F> {
+        if inputs.len() != self.true_len {
+            panic!(
+                "The circuit takes {} inputs, got {}",
+                self.true_len,
+                inputs.len()
+            );
+        }
+
+        let mut padded = inputs.to_vec();
+        padded.resize(self.padded_len, F::zero());
+
+        padded
+    }
+
+    pub fn mle<F: PrimeField>(&self, inputs: &[F]) -> MultiLinearPoly<F> {
+        MultiLinearPoly::new(&self.pad(inputs))
+    }
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_padding_rule() {
+        let single = PaddedLayer::new(&[Fq::from(7)]);
+        assert_eq!(single.padded(), &[Fq::from(7), Fq::from(0)]);
+        assert_eq!(single.num_vars(), 1);
+
+        let three = PaddedLayer::new(&[1, 2, 3].map(Fq::from));
+        assert_eq!(three.padded().len(), 4);
+        assert_eq!(three.values(), &[1, 2, 3].map(Fq::from));
+
+        let four = PaddedLayer::new(&[1, 2, 3, 4].map(Fq::from));
+        assert_eq!(four.padded().len(), 4);
+    }
+
+    #[test]
+    fn test_from_padded() {
+        let layer = PaddedLayer::new(&[1, 2, 3].map(Fq::from));
+        let decoded = PaddedLayer::from_padded(layer.padded().to_vec(), 3).unwrap();
+        assert_eq!(decoded, layer);
+
+        // junk in the padding or a different true length is rejected
+        let junk = vec![Fq::from(1), Fq::from(2), Fq::from(3), Fq::from(4)];
+        assert_eq!(PaddedLayer::from_padded(junk, 3), None);
+        assert_eq!(PaddedLayer::from_padded(layer.padded().to_vec(), 5), None);
+    }
+
+    #[test]
+    fn test_bytes_include_true_len() {
+        // 3 values padded with a zero vs 4 values that happen to end in a zero
+        let three = PaddedLayer::new(&[1, 2, 3].map(Fq::from));
+        let four = PaddedLayer::new(&[1, 2, 3, 0].map(Fq::from));
+
+        assert_eq!(three.padded(), four.padded());
+        assert_ne!(three.to_bytes(), four.to_bytes());
+        assert_eq!(three.to_bytes()[8..], four.to_bytes()[8..]);
+    }
+
+    #[test]
+    fn test_input_padding() {
+        let padding = InputPadding::new(3, 3).unwrap();
+        assert_eq!(padding.padding(), 3..8);
+        assert!(!padding.is_padding(2) && padding.is_padding(3) && !padding.is_padding(8));
+        assert_eq!(
+            padding.pad(&[1, 2, 3].map(Fq::from)),
+            [1, 2, 3, 0, 0, 0, 0, 0].map(Fq::from)
+        );
+        assert_eq!(padding.num_vars(), 3);
+
+        // 2 bits can't reach a fifth input
+        assert_eq!(InputPadding::new(5, 2), None);
+    }
+}
@@ -1,19 +1,79 @@
-use core::panic;
-
 use crate::{
-    gkr::product_poly::ProductPoly, multi_linear::MultiLinearPoly, transcript::Transcript,
-    UnivariatePoly,
+    explain::Explain,
+    gkr::product_poly::ProductPoly,
+    lagrange::LagrangeWeights,
+    multi_linear::MultiLinearPoly,
+    progress::{never_cancelled, Cancelled, Progress, ProgressEvent},
+    proof_format::{read_nested_vec, read_vec, DecodeLimits, ProofFormatError},
+    transcript::Transcript,
+    transcript_schedule::{Schedule, Step},
+    virtual_polynomial::VirtualPolynomial,
 };
 use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 
 #[derive(Debug, Clone)]
 pub struct Proof<F: PrimeField> {
-    pub sum_poly: Vec<ProductPoly<F>>, // this is here so the verifier can compute the degree
+    pub degree: usize, // multilinears per product, every round poly is [g(0), ..., g(degree)]
+    pub num_products: usize, // products summed in the composed polynomial
     pub init_claimed_sum: F,
     pub challenges: Vec<F>,
     pub round_polys: Vec<Vec<F>>,
 }
 
+impl<F: PrimeField> Proof<F> {
+    // raw body used inside the GKR proof encoding, the header is written by the outer proof
+    pub fn serialize_into(&self, bytes: &mut Vec<u8>) -> Result<(), SerializationError> {
+        (self.degree as u64).serialize_compressed(&mut *bytes)?;
+        (self.num_products as u64).serialize_compressed(&mut *bytes)?;
+        self.init_claimed_sum.serialize_compressed(&mut *bytes)?;
+        self.challenges.serialize_compressed(&mut *bytes)?;
+        self.round_polys.serialize_compressed(&mut *bytes)?;
+
+        Ok(())
+    }
+
+    pub fn deserialize_from(
+        bytes: &mut &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        let degree = u64::deserialize_compressed(&mut *bytes)?;
+        if degree >= limits.max_len as u64 {
+            return Err(ProofFormatError::TooLarge {
+                len: degree as usize,
+                max: limits.max_len,
+            });
+        }
+        let degree = degree as usize;
+        let num_products = u64::deserialize_compressed(&mut *bytes)?;
+        if num_products >= limits.max_len as u64 {
+            return Err(ProofFormatError::TooLarge {
+                len: num_products as usize,
+                max: limits.max_len,
+            });
+        }
+        let num_products = num_products as usize;
+        let init_claimed_sum = F::deserialize_compressed(&mut *bytes)?;
+        let challenges = read_vec::<F>(bytes, limits)?;
+        let round_polys = read_nested_vec::<F>(bytes, limits)?;
+
+        if let Some(round_poly) = round_polys.iter().find(|p| p.len() != degree + 1) {
+            return Err(ProofFormatError::InconsistentLengths {
+                expected: degree + 1,
+                found: round_poly.len(),
+            });
+        }
+
+        Ok(Proof {
+            degree,
+            num_products,
+            init_claimed_sum,
+            challenges,
+            round_polys,
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct SubClaim<F: PrimeField> {
     pub challenges: Vec<F>,
@@ -33,95 +93,286 @@ pub fn reduce<F: PrimeField>(m_poly_array: Vec<Vec<F>>) -> Vec<F> {
     new_array
 }
 
-pub fn proof<F: PrimeField>(mut sum_poly: Vec<ProductPoly<F>>, init_claimed_sum: F) -> Proof<F> {
-    let mut initial_length = sum_poly[0].poly_array[0].computation.len().ilog2();
-    let mut transcript = Transcript::new();
-    let mut challenges: Vec<F> = vec![];
+// round polynomial in evaluation form i.e. [g(0), g(1), ..., g(d)] summed over the remaining hypercube
+pub fn round_poly<F: PrimeField>(sum_poly: &[ProductPoly<F>]) -> Vec<F> {
+    let eval_array: Vec<Vec<F>> = sum_poly
+        .iter()
+        .map(|p_poly| p_poly.univariate_to_evaluation())
+        .collect();
 
-    let mut round_polys = vec![];
+    reduce(eval_array)
+}
 
-    while initial_length > 0 {
-        let eval_array: Vec<Vec<F>> = sum_poly
-            .iter()
-            .map(|p_poly| p_poly.univariate_to_evaluation())
-            .collect();
-        let round_poly = reduce(eval_array);
+// binds the first variable of every product poly to the round challenge
+pub fn fold_sum_poly<F: PrimeField>(
+    sum_poly: &[ProductPoly<F>],
+    challenge: F,
+) -> Vec<ProductPoly<F>> {
+    sum_poly
+        .iter()
+        .map(|p_poly| p_poly.partial_evaluate(challenge, 0))
+        .collect()
+}
 
-        round_polys.push(round_poly.clone());
-        transcript.absorb(&MultiLinearPoly::to_bytes(&round_poly));
+// checks g(0) + g(1) against the claimed sum and returns g(challenge) as the next claimed sum
+// weights are over 0..=d (LagrangeWeights::for_degree), built once and shared by every round
+pub fn verify_round<F: PrimeField>(
+    claimed_sum: F,
+    round_poly: &[F],
+    weights: &LagrangeWeights<F>,
+    challenge: F,
+) -> Option<F> {
+    if round_poly.len() != weights.len() || round_poly.len() < 2 {
+        return None;
+    }
 
-        let challenge_bytes = transcript.squeeze();
-        let challenge = F::from_be_bytes_mod_order(&challenge_bytes);
-        challenges.push(challenge);
+    let verifier_sum = round_poly[0] + round_poly[1]; // This is doable because the round_poly is in its evaluation form
+    if claimed_sum != verifier_sum {
+        return None;
+    }
 
-        sum_poly = sum_poly
-            .iter()
-            .map(|p_poly| p_poly.partial_evaluate(challenge, 0))
-            .collect();
+    Some(weights.evaluate(round_poly, challenge))
+}
+
+// the transcript starts with the shape of the composed polynomial and the claimed sum, so
+// proofs of different compositions or claims never share a challenge sequence
+// [num_vars][degree][num_products] as u64 BE, then the claimed sum, the rounds follow
+// PARTIAL_SUM_CHECK_SCHEDULE on both sides
+// the transcript is a fork of the caller's (labelled with the protocol name), a GKR layer
+// sum-check forks the GKR transcript so its challenges are bound to the domain, the session and
+// every earlier layer, a standalone proof forks an empty transcript
+pub static PARTIAL_SUM_CHECK_SCHEDULE: Schedule = Schedule {
+    protocol: "partial_sum_check",
+    steps: &[
+        Step::Absorb("num_vars"),
+        Step::Absorb("degree"),
+        Step::Absorb("num_products"),
+        Step::Absorb("claimed sum"),
+        Step::Repeat("rounds", &[Step::Absorb("round poly"), Step::Squeeze("r")]),
+    ],
+};
 
-        initial_length -= 1;
+pub fn start_transcript<F: PrimeField>(
+    parent: &Transcript,
+    num_vars: usize,
+    degree: usize,
+    num_products: usize,
+    init_claimed_sum: F,
+) -> Transcript {
+    let mut transcript = parent
+        .fork(PARTIAL_SUM_CHECK_SCHEDULE.protocol.as_bytes())
+        .with_schedule(&PARTIAL_SUM_CHECK_SCHEDULE);
+    for (label, value) in [
+        ("num_vars", num_vars),
+        ("degree", degree),
+        ("num_products", num_products),
+    ] {
+        transcript
+            .follow(Step::Absorb(label))
+            .absorb(&(value as u64).to_be_bytes());
     }
+    transcript
+        .follow(Step::Absorb("claimed sum"))
+        .absorb(&MultiLinearPoly::to_bytes(&[init_claimed_sum]));
 
-    Proof {
+    transcript
+}
+
+pub fn proof<F: PrimeField>(sum_poly: Vec<ProductPoly<F>>, init_claimed_sum: F) -> Proof<F> {
+    proof_in(&Transcript::new(), sum_poly, init_claimed_sum)
+}
+
+// the sum-check of a protocol that runs it inside its own transcript, see start_transcript
+pub fn proof_in<F: PrimeField>(
+    transcript: &Transcript,
+    sum_poly: Vec<ProductPoly<F>>,
+    init_claimed_sum: F,
+) -> Proof<F> {
+    never_cancelled(proof_with_progress(
+        transcript,
         sum_poly,
         init_claimed_sum,
+        None,
+        &mut Progress::none(),
+    ))
+}
+
+// same proof with a Round reported after every round, `layer` is passed through to the events
+// the rounds run on the products as a VirtualPolynomial, see proof_virtual
+pub fn proof_with_progress<F: PrimeField>(
+    transcript: &Transcript,
+    sum_poly: Vec<ProductPoly<F>>,
+    init_claimed_sum: F,
+    layer: Option<usize>,
+    progress: &mut Progress,
+) -> Result<Proof<F>, Cancelled> {
+    proof_virtual_with_progress(
+        transcript,
+        VirtualPolynomial::from_products(sum_poly),
+        init_claimed_sum,
+        layer,
+        progress,
+    )
+}
+
+// degree is the largest product and num_products the number of products, so a composition of
+// products of different sizes verifies with the same try_verify
+pub fn proof_virtual<F: PrimeField>(poly: VirtualPolynomial<F>, init_claimed_sum: F) -> Proof<F> {
+    never_cancelled(proof_virtual_with_progress(
+        &Transcript::new(),
+        poly,
+        init_claimed_sum,
+        None,
+        &mut Progress::none(),
+    ))
+}
+
+pub fn proof_virtual_with_progress<F: PrimeField>(
+    transcript: &Transcript,
+    mut poly: VirtualPolynomial<F>,
+    init_claimed_sum: F,
+    layer: Option<usize>,
+    progress: &mut Progress,
+) -> Result<Proof<F>, Cancelled> {
+    let num_rounds = poly.num_vars();
+    let degree = poly.max_degree();
+    let num_products = poly.num_products();
+    let mut transcript = start_transcript(
+        transcript,
+        num_rounds,
+        degree,
+        num_products,
+        init_claimed_sum,
+    );
+    let mut challenges: Vec<F> = Vec::with_capacity(num_rounds);
+    let mut round_polys = Vec::with_capacity(num_rounds);
+
+    for round in 0..num_rounds {
+        let round_poly = poly.round_evaluations();
+        transcript
+            .follow(Step::Absorb("round poly"))
+            .absorb(&MultiLinearPoly::to_bytes(&round_poly));
+
+        let challenge = transcript.follow(Step::Squeeze("r")).squeeze_field::<F>();
+        challenges.push(challenge);
+        round_polys.push(round_poly);
+
+        poly.fix_variable(challenge);
+        progress.report(ProgressEvent::Round {
+            layer,
+            done: round + 1,
+            total: num_rounds,
+        })?;
+    }
+
+    Ok(Proof {
+        degree,
+        num_products,
+        init_claimed_sum,
         challenges,
         round_polys,
-    }
+    })
 }
 
 // returns a struct of an array of challenges and last claimed_sum
-pub fn verify<F: PrimeField>(proof: Proof<F>) -> SubClaim<F> {
-    let mut transcript = Transcript::new();
+pub fn verify<F: PrimeField>(proof: &Proof<F>) -> SubClaim<F> {
+    match try_verify(proof) {
+        Some(sub_claim) => sub_claim,
+        None => panic!("Claimed sum does not match verifier sum"),
+    }
+}
+
+// same as verify but a bad round is a None instead of a panic
+pub fn try_verify<F: PrimeField>(proof: &Proof<F>) -> Option<SubClaim<F>> {
+    try_verify_in(&Transcript::new(), proof)
+}
+
+// try_verify of a proof made with proof_in under the same transcript
+pub fn try_verify_in<F: PrimeField>(
+    transcript: &Transcript,
+    proof: &Proof<F>,
+) -> Option<SubClaim<F>> {
+    try_verify_with(
+        transcript,
+        proof,
+        &LagrangeWeights::for_degree(proof.degree),
+    )
+}
+
+// try_verify_in with weights built ahead of time, a proof of any other degree is rejected
+pub fn try_verify_with<F: PrimeField>(
+    transcript: &Transcript,
+    proof: &Proof<F>,
+    weights: &LagrangeWeights<F>,
+) -> Option<SubClaim<F>> {
+    try_verify_explained(transcript, proof, weights, &mut Explain::off())
+}
+
+// try_verify_with that records the rounds, see explain
+pub fn try_verify_explained<F: PrimeField>(
+    transcript: &Transcript,
+    proof: &Proof<F>,
+    weights: &LagrangeWeights<F>,
+    explain: &mut Explain,
+) -> Option<SubClaim<F>> {
+    let mut transcript = start_transcript(
+        transcript,
+        proof.round_polys.len(),
+        proof.degree,
+        proof.num_products,
+        proof.init_claimed_sum,
+    );
     let mut claimed_sum: F = proof.init_claimed_sum;
     let mut challenges: Vec<F> = vec![];
 
-    let degree = ProductPoly::get_degree(&proof.sum_poly[0]);
-    let mut xs = Vec::with_capacity(degree);
-    for i in 0..(degree + 1) {
-        xs.push(F::from(i as u32));
+    // the degree is only a number in the proof, it must agree with every round poly
+    if weights.len().checked_sub(1) != Some(proof.degree)
+        || proof
+            .round_polys
+            .iter()
+            .any(|round_poly| round_poly.len().checked_sub(1) != Some(proof.degree))
+    {
+        return None;
     }
 
-    for round_poly in proof.round_polys.iter() {
-        let verifier_sum = round_poly[0] + round_poly[1]; // This is doable because the round_poly is in its evaluation form
-        if claimed_sum != verifier_sum {
-            panic!("Claimed sum does not match verifier sum");
-        }
-
-        transcript.absorb(&MultiLinearPoly::to_bytes(&round_poly));
-        let challenge_bytes = transcript.squeeze();
-        let challenge = F::from_be_bytes_mod_order(&challenge_bytes);
+    explain.claim("claimed sum", proof.init_claimed_sum);
+    for (round, round_poly) in proof.round_polys.iter().enumerate() {
+        transcript
+            .follow(Step::Absorb("round poly"))
+            .absorb(&MultiLinearPoly::to_bytes(round_poly));
+        let challenge = transcript.follow(Step::Squeeze("r")).squeeze_field::<F>();
         challenges.push(challenge);
 
-        let equation = UnivariatePoly::interpolate(&xs, &round_poly);
-        /*
-         * This check below does the same as line 87 to 89
-         */
-        // let take1 = equation.evaluate(F::zero());
-        // let take2 = equation.evaluate(F::one());
-        // let sum = take1 + take2;
-        // dbg!(sum);
-        // if claimed_sum != sum {
-        //     panic!("Claimed sum does not match sum");
-        // }
-        claimed_sum = equation.evaluate(challenge);
-    }
-
-    SubClaim {
+        explain.round_poly(round, round_poly);
+        let next = verify_round(claimed_sum, round_poly, weights, challenge);
+        if !explain.check("g(0) + g(1) = claim", next.is_some()) {
+            return None;
+        }
+        explain.challenge(&format!("r_{}", round), challenge);
+        claimed_sum = next?;
+    }
+
+    Some(SubClaim {
         challenges,
         last_claimed_sum: claimed_sum,
-    }
+    })
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
 mod tests {
     use super::*;
+    use crate::{
+        transcript::{test::domain, TranscriptDomain},
+        virtual_polynomial::VirtualPolynomial,
+    };
     use ark_bn254::Fq;
+    use ark_ff::UniformRand;
+    use std::sync::Arc;
 
     #[test]
     fn test_proof() {
         // for a quick test, use [0, 0, 0, 2] and [0, 0, 0, 3]
-        let poly_1 = MultiLinearPoly::new(&vec![
+        let poly_1 = MultiLinearPoly::new(&[
             Fq::from(1),
             Fq::from(2),
             Fq::from(3),
@@ -131,7 +382,7 @@ mod tests {
             Fq::from(7),
             Fq::from(8),
         ]);
-        let poly_2 = MultiLinearPoly::new(&vec![
+        let poly_2 = MultiLinearPoly::new(&[
             Fq::from(1),
             Fq::from(2),
             Fq::from(3),
@@ -146,8 +397,135 @@ mod tests {
 
         let proof = proof(vec![prod_poly.clone(), prod_poly], init_claimed_sum);
         // dbg!(&proof);
-        let verify = verify(proof);
+        let verify = verify(&proof);
         dbg!(&verify);
         assert_eq!(verify.challenges.len(), 3);
     }
+
+    #[test]
+    fn test_degree_must_match_round_polys() {
+        let poly = MultiLinearPoly::new(&[1, 2, 3, 4].map(Fq::from));
+        let prod_poly = ProductPoly::new(vec![poly.clone(), poly]);
+        let mut proof = proof(vec![prod_poly], Fq::from(30));
+
+        // 2 multilinears per product, so 3 evaluations per round
+        assert_eq!(proof.degree, 2);
+        assert!(try_verify(&proof).is_some());
+
+        proof.degree = 3;
+        assert!(try_verify(&proof).is_none());
+
+        let mut bytes = Vec::new();
+        proof.serialize_into(&mut bytes).unwrap();
+        assert!(matches!(
+            Proof::<Fq>::deserialize_from(&mut bytes.as_slice(), &DecodeLimits::default()),
+            Err(ProofFormatError::InconsistentLengths {
+                expected: 4,
+                found: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_transcript_binds_claim_and_composition() {
+        let poly = MultiLinearPoly::new(&[1, 2, 3, 4].map(Fq::from));
+        let prod_poly = ProductPoly::new(vec![poly.clone(), poly]);
+        let proof = proof(vec![prod_poly], Fq::from(30));
+        assert_eq!(proof.num_products, 1);
+        assert_eq!(try_verify(&proof).unwrap().challenges, proof.challenges);
+
+        // the same round polys read as another composition draw other challenges
+        let mut other = proof.clone();
+        other.num_products = 2;
+        assert!(try_verify(&other).is_none_or(|sub_claim| sub_claim.challenges != proof.challenges));
+
+        assert_ne!(
+            start_transcript(&Transcript::new(), 2, 2, 1, Fq::from(30)).squeeze_field::<Fq>(),
+            start_transcript(&Transcript::new(), 2, 2, 1, Fq::from(31)).squeeze_field::<Fq>()
+        );
+    }
+
+    #[test]
+    fn test_proof_bound_to_the_caller_transcript() {
+        let poly = MultiLinearPoly::new(&[1, 2, 3, 4, 5, 6, 7, 8].map(Fq::from));
+        let prod_poly = ProductPoly::new(vec![poly.clone(), poly]);
+        let app = domain().transcript();
+        let other_app = TranscriptDomain::new(b"other-app", domain().public_digest).transcript();
+
+        let proof = proof_in(&app, vec![prod_poly], Fq::from(204));
+        assert_eq!(
+            try_verify_in(&app, &proof).unwrap().challenges,
+            proof.challenges
+        );
+
+        // other challenges, so the later rounds no longer add up
+        assert!(try_verify_in(&other_app, &proof).is_none());
+        assert!(try_verify(&proof).is_none());
+    }
+
+    #[test]
+    fn test_virtual_proof() {
+        // the old fold-and-copy rounds give the same round polys as the virtual ones
+        let poly_1 = MultiLinearPoly::new(&[1, 2, 3, 4, 5, 6, 7, 8].map(Fq::from));
+        let poly_2 = MultiLinearPoly::new(&[8, 1, 7, 2, 6, 3, 5, 4].map(Fq::from));
+        let mut sum_poly = vec![
+            ProductPoly::new(vec![poly_1.clone(), poly_2.clone()]),
+            ProductPoly::new(vec![poly_2.clone(), poly_2.clone()]),
+        ];
+        let claimed_sum = VirtualPolynomial::from_products(sum_poly.clone()).sum();
+        let p_proof = proof(sum_poly.clone(), claimed_sum);
+        for (round, challenge) in p_proof.round_polys.iter().zip(p_proof.challenges.iter()) {
+            assert_eq!(*round, round_poly(&sum_poly));
+            sum_poly = fold_sum_poly(&sum_poly, *challenge);
+        }
+
+        // products of different sizes, 3·a·b·b + a
+        let (a, b) = (Arc::new(poly_1), Arc::new(poly_2));
+        let mut poly = VirtualPolynomial::new(3);
+        poly.add_product([a.clone(), b.clone(), b.clone()], Fq::from(3));
+        poly.add_product([a.clone()], Fq::from(1));
+        let claimed_sum = poly.sum();
+        let p_proof = proof_virtual(poly.clone(), claimed_sum);
+        assert_eq!((p_proof.degree, p_proof.num_products), (3, 2));
+
+        let sub_claim = try_verify(&p_proof).unwrap();
+        assert_eq!(
+            sub_claim.last_claimed_sum,
+            poly.evaluate(&sub_claim.challenges)
+        );
+        assert!(proof_virtual(poly, claimed_sum + Fq::from(1))
+            .round_polys
+            .first()
+            .is_some_and(|round| round[0] + round[1] != claimed_sum + Fq::from(1)));
+    }
+
+    fn random_mle(num_vars: usize) -> Arc<MultiLinearPoly<Fq>> {
+        let mut rng = rand::thread_rng();
+        let computation: Vec<Fq> = (0..1 << num_vars).map(|_| Fq::rand(&mut rng)).collect();
+        Arc::new(MultiLinearPoly::new(&computation))
+    }
+
+    #[test]
+    fn test_matches_product_polys() {
+        let (a, b, c) = (random_mle(3), random_mle(3), random_mle(3));
+        let mut sum_poly = vec![
+            ProductPoly::new(vec![(*a).clone(), (*b).clone()]),
+            ProductPoly::new(vec![(*b).clone(), (*c).clone()]),
+        ];
+        let mut poly = VirtualPolynomial::from_products(sum_poly.clone());
+        assert_eq!((poly.max_degree(), poly.num_products()), (2, 2));
+
+        for r in [3, 5, 7].map(Fq::from) {
+            assert_eq!(poly.round_evaluations(), round_poly(&sum_poly));
+            poly.fix_variable(r);
+            sum_poly = fold_sum_poly(&sum_poly, r);
+        }
+        assert_eq!(poly.num_vars(), 0);
+        assert_eq!(
+            poly.evaluate(&[]),
+            sum_poly[0].poly_array[0].computation[0] * sum_poly[0].poly_array[1].computation[0]
+                + sum_poly[1].poly_array[0].computation[0]
+                    * sum_poly[1].poly_array[1].computation[0]
+        );
+    }
 }
@@ -0,0 +1,112 @@
+use crate::{
+    explain::Explain,
+    gkr::{
+        chunked_sum_check::LAYER_DEGREE, gkr_circuit::Circuit, gkr_protocol::GKRProof,
+        public_inputs::PublicInputs,
+    },
+    lagrange::LagrangeWeights,
+    transcript::{Transcript, TranscriptDomain, DIGEST_LEN},
+};
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// A GKR verifier set up once per circuit and reused for every proof of it
+// what doesn't depend on the proof is done in new(): the transcript is seeded with the domain
+// and cloned per proof instead of rehashing the context, the sum-check weights over 0..=2 are
+// built once instead of once per layer, and the wiring digest is kept for callers that bind
+// proofs to the circuit
+// every layer of an honest proof has degree 2 (see chunked_sum_check), a proof claiming any
+// other degree is rejected here even though Circuit::verify would rebuild its weights for it
+//=========================================================================================
+pub struct PreparedVerifier<'a, F: PrimeField> {
+    circuit: &'a Circuit<F>,
+    transcript: Transcript, // domain already absorbed
+    weights: LagrangeWeights<F>,
+    digest: [u8; DIGEST_LEN],
+}
+
+impl<'a, F: PrimeField> PreparedVerifier<'a, F> {
+    pub fn new(circuit: &'a Circuit<F>, transcript_domain: &TranscriptDomain) -> Self {
+        PreparedVerifier {
+            circuit,
+            transcript: transcript_domain.transcript(),
+            weights: LagrangeWeights::for_degree(LAYER_DEGREE),
+            digest: circuit.digest(),
+        }
+    }
+
+    pub fn circuit_digest(&self) -> [u8; DIGEST_LEN] {
+        self.digest
+    }
+
+    // same answer as circuit.verify(proof, public, transcript_domain)
+    pub fn verify(&self, proof: &GKRProof<F>, public: &PublicInputs<F>) -> bool {
+        self.circuit.verify_from(
+            self.transcript.clone(),
+            proof,
+            public,
+            Some(&self.weights),
+            &mut Explain::off(),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{
+        gkr::{
+            chunked_sum_check::ProverConfig, gkr_2_to_1_trick::LayerReduction,
+            gkr_circuit::test::setup_test_circuit8,
+        },
+        transcript::test::domain,
+    };
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_prepared_verifier_matches_verify() {
+        let mut circuit = setup_test_circuit8();
+        let proofs: Vec<(GKRProof<Fq>, PublicInputs<Fq>)> = (0..3)
+            .map(|i| {
+                circuit.inputs[0] = Fq::from(i);
+                (circuit.proof(&domain()), circuit.public_inputs())
+            })
+            .collect();
+        let verifier = PreparedVerifier::new(&circuit, &domain());
+
+        for (proof, public) in proofs.iter() {
+            assert!(verifier.verify(proof, public));
+            assert!(circuit.verify(proof, public, &domain()));
+        }
+        assert!(!verifier.verify(&proofs[0].0, &proofs[1].1));
+        assert_eq!(verifier.circuit_digest(), circuit.digest());
+
+        let config = ProverConfig {
+            reduction: LayerReduction::Line,
+            ..Default::default()
+        };
+        let proof = circuit.proof_with_config(&config, &domain());
+        assert!(verifier.verify(&proof, &circuit.public_inputs()));
+    }
+
+    #[test]
+    fn test_prepared_verifier_rejects_bad_sum_checks() {
+        let circuit = setup_test_circuit8();
+        let public = circuit.public_inputs();
+        let verifier = PreparedVerifier::new(&circuit, &domain());
+
+        let mut proof = circuit.proof(&domain());
+        proof.p_proofs[1].round_polys[0][0] += Fq::from(1);
+        assert!(!verifier.verify(&proof, &public));
+        // the plain verifier rejects it too instead of panicking
+        assert!(!circuit.verify(&proof, &public, &domain()));
+
+        // a layer claiming another degree
+        let mut proof = circuit.proof(&domain());
+        for round_poly in proof.p_proofs[0].round_polys.iter_mut() {
+            round_poly.push(Fq::from(0));
+        }
+        proof.p_proofs[0].degree += 1;
+        assert!(!verifier.verify(&proof, &public));
+    }
+}
@@ -0,0 +1,411 @@
+use crate::{
+    eval_claim::{EvalClaim, PolyId},
+    explain::Explain,
+    gkr::{
+        gkr_circuit::Circuit, gkr_protocol::GKRProof, partial_sum_check::Proof,
+        public_inputs::PublicInputs,
+    },
+    proof_format::{
+        ensure_consumed, read_header, read_len, DecodeLimits, ProofFormatError, ProtocolId,
+    },
+    transcript::TranscriptDomain,
+};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalDeserialize;
+use std::{borrow::Cow, marker::PhantomData};
+
+//=========================================================================================
+// Borrowed views over a serialized GKR proof, for verifiers that check many proofs
+// parse only walks the length prefixes and checks every section is as long as its counts say,
+// no field element is decoded and the only allocation is one small entry per layer
+// elements are decoded (and checked to be canonical) when the verifier reads them, so a bad
+// element is a rejected proof instead of a parse error, and a sum-check is only turned into
+// an owned Proof while its own layer is being checked
+// same byte layout as GKRProof::to_bytes, Circuit::verify_view makes the same checks as verify
+//=========================================================================================
+
+// what the GKR verifier reads from a proof, implemented by the owned proof and by the view
+pub trait GKRProofSource<F: PrimeField> {
+    fn layer_count(&self) -> usize;
+    fn w_i_eval_count(&self) -> usize;
+    fn line_poly_count(&self) -> usize;
+    fn output_layer(&self) -> Option<Cow<'_, [F]>>;
+    fn w_i_evals(&self, i: usize) -> Option<(F, F)>;
+    fn sum_check(&self, i: usize) -> Option<Cow<'_, Proof<F>>>;
+    fn init_claimed_sum(&self, i: usize) -> Option<F>;
+    fn line_poly(&self, i: usize) -> Option<Cow<'_, [F]>>;
+
+    // the (Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)) pair after sum-check i as claims at its point r_b ‖ r_c
+    // the last pair is about the input layer
+    fn layer_claims(&self, i: usize, point: &[F]) -> Option<[EvalClaim<F>; 2]> {
+        let poly_id = if i + 1 == self.layer_count() {
+            PolyId::Input
+        } else {
+            PolyId::Layer(i + 1)
+        };
+
+        EvalClaim::pair(poly_id, point, self.w_i_evals(i)?)
+    }
+}
+
+impl<F: PrimeField> GKRProofSource<F> for GKRProof<F> {
+    fn layer_count(&self) -> usize {
+        self.p_proofs.len()
+    }
+
+    fn w_i_eval_count(&self) -> usize {
+        self.w_i_evals.len()
+    }
+
+    fn line_poly_count(&self) -> usize {
+        self.line_polys.len()
+    }
+
+    fn output_layer(&self) -> Option<Cow<'_, [F]>> {
+        Some(Cow::Borrowed(&self.output_layer))
+    }
+
+    fn w_i_evals(&self, i: usize) -> Option<(F, F)> {
+        self.w_i_evals.get(i).copied()
+    }
+
+    fn sum_check(&self, i: usize) -> Option<Cow<'_, Proof<F>>> {
+        self.p_proofs.get(i).map(Cow::Borrowed)
+    }
+
+    fn init_claimed_sum(&self, i: usize) -> Option<F> {
+        self.p_proofs.get(i).map(|p_proof| p_proof.init_claimed_sum)
+    }
+
+    fn line_poly(&self, i: usize) -> Option<Cow<'_, [F]>> {
+        self.line_polys
+            .get(i)
+            .map(|line_poly| Cow::Borrowed(line_poly.as_slice()))
+    }
+}
+
+// ark compressed prime field elements all take the same number of bytes
+fn element_len<F: PrimeField>() -> usize {
+    F::zero().compressed_size()
+}
+
+// count consecutive compressed field elements
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSlice<'a, F> {
+    bytes: &'a [u8],
+    _field: PhantomData<F>,
+}
+
+impl<'a, F: PrimeField> FieldSlice<'a, F> {
+    fn take(bytes: &mut &'a [u8], count: usize) -> Result<Self, ProofFormatError> {
+        let len = count
+            .checked_mul(element_len::<F>())
+            .filter(|len| *len <= bytes.len())
+            .ok_or(ProofFormatError::LengthMismatch {
+                len: count as u64,
+                remaining: bytes.len(),
+            })?;
+        let (head, rest) = bytes.split_at(len);
+        *bytes = rest;
+
+        Ok(FieldSlice {
+            bytes: head,
+            _field: PhantomData,
+        })
+    }
+
+    // a length prefixed Vec<F>
+    fn read(bytes: &mut &'a [u8], limits: &DecodeLimits) -> Result<Self, ProofFormatError> {
+        let len = read_len(bytes, limits)?;
+        FieldSlice::take(bytes, len)
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len() / element_len::<F>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    // None if i is out of range or the bytes are not a canonical element
+    pub fn get(&self, i: usize) -> Option<F> {
+        let len = element_len::<F>();
+        let chunk = self.bytes.get(i * len..(i + 1) * len)?;
+
+        F::deserialize_compressed(chunk).ok()
+    }
+
+    pub fn to_vec(&self) -> Option<Vec<F>> {
+        (0..self.len()).map(|i| self.get(i)).collect()
+    }
+}
+
+// one layer's sum-check, laid out as partial_sum_check::Proof::serialize_into
+#[derive(Debug, Clone)]
+pub struct SumCheckView<'a, F> {
+    pub degree: usize,
+    pub num_products: usize,
+    init_claimed_sum: FieldSlice<'a, F>,
+    challenges: FieldSlice<'a, F>,
+    round_polys: &'a [u8], // every round poly is its length prefix and degree + 1 elements
+    num_rounds: usize,
+}
+
+impl<'a, F: PrimeField> SumCheckView<'a, F> {
+    fn read(bytes: &mut &'a [u8], limits: &DecodeLimits) -> Result<Self, ProofFormatError> {
+        let read_count = |bytes: &mut &'a [u8]| {
+            let count = u64::deserialize_compressed(&mut *bytes)?;
+            if count >= limits.max_len as u64 {
+                return Err(ProofFormatError::TooLarge {
+                    len: count as usize,
+                    max: limits.max_len,
+                });
+            }
+
+            Ok(count as usize)
+        };
+        let degree = read_count(bytes)?;
+        let num_products = read_count(bytes)?;
+        let init_claimed_sum = FieldSlice::take(bytes, 1)?;
+        let challenges = FieldSlice::read(bytes, limits)?;
+
+        let num_rounds = read_len(bytes, limits)?;
+        let start = *bytes;
+        for _ in 0..num_rounds {
+            let len = read_len(bytes, limits)?;
+            if len != degree + 1 {
+                return Err(ProofFormatError::InconsistentLengths {
+                    expected: degree + 1,
+                    found: len,
+                });
+            }
+            FieldSlice::<F>::take(bytes, len)?;
+        }
+        let round_polys = &start[..start.len() - bytes.len()];
+
+        Ok(SumCheckView {
+            degree,
+            num_products,
+            init_claimed_sum,
+            challenges,
+            round_polys,
+            num_rounds,
+        })
+    }
+
+    pub fn init_claimed_sum(&self) -> Option<F> {
+        self.init_claimed_sum.get(0)
+    }
+
+    pub fn round_poly(&self, i: usize) -> Option<FieldSlice<'a, F>> {
+        if i >= self.num_rounds {
+            return None;
+        }
+
+        let round_len = 8 + (self.degree + 1) * element_len::<F>();
+        let mut bytes = &self.round_polys[i * round_len + 8..(i + 1) * round_len];
+        FieldSlice::take(&mut bytes, self.degree + 1).ok()
+    }
+
+    pub fn to_proof(&self) -> Option<Proof<F>> {
+        Some(Proof {
+            degree: self.degree,
+            num_products: self.num_products,
+            init_claimed_sum: self.init_claimed_sum()?,
+            challenges: self.challenges.to_vec()?,
+            round_polys: (0..self.num_rounds)
+                .map(|i| self.round_poly(i)?.to_vec())
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GKRProofView<'a, F> {
+    output_layer: FieldSlice<'a, F>,
+    w_i_evals: FieldSlice<'a, F>, // (Wᵢ(r_b), Wᵢ(r_c)) pairs, two elements per layer
+    p_proofs: Vec<SumCheckView<'a, F>>,
+    line_polys: Vec<FieldSlice<'a, F>>,
+}
+
+impl<'a, F: PrimeField> GKRProofView<'a, F> {
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, ProofFormatError> {
+        Self::parse_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    pub fn parse_with_limits(
+        mut bytes: &'a [u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        limits.check_size(bytes)?;
+        read_header(&mut bytes, ProtocolId::Gkr)?;
+
+        let output_layer = FieldSlice::read(&mut bytes, limits)?;
+        let num_w_i_evals = read_len(&mut bytes, limits)?;
+        let w_i_evals = FieldSlice::take(&mut bytes, 2 * num_w_i_evals)?;
+
+        let num_layers = read_len(&mut bytes, limits)?;
+        let p_proofs = (0..num_layers)
+            .map(|_| SumCheckView::read(&mut bytes, limits))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let num_line_polys = read_len(&mut bytes, limits)?;
+        let line_polys = (0..num_line_polys)
+            .map(|_| FieldSlice::read(&mut bytes, limits))
+            .collect::<Result<Vec<_>, _>>()?;
+        ensure_consumed(bytes)?;
+
+        // one (Wᵢ(r_b), Wᵢ(r_c)) per layer sum-check
+        if num_w_i_evals != p_proofs.len() {
+            return Err(ProofFormatError::InconsistentLengths {
+                expected: p_proofs.len(),
+                found: num_w_i_evals,
+            });
+        }
+
+        Ok(GKRProofView {
+            output_layer,
+            w_i_evals,
+            p_proofs,
+            line_polys,
+        })
+    }
+
+    // the owned proof, None if any element is not canonical
+    pub fn to_proof(&self) -> Option<GKRProof<F>> {
+        Some(GKRProof {
+            output_layer: self.output_layer.to_vec()?,
+            w_i_evals: (0..self.w_i_eval_count())
+                .map(|i| self.w_i_evals(i))
+                .collect::<Option<_>>()?,
+            p_proofs: self
+                .p_proofs
+                .iter()
+                .map(|p_proof| p_proof.to_proof())
+                .collect::<Option<_>>()?,
+            line_polys: self
+                .line_polys
+                .iter()
+                .map(|line_poly| line_poly.to_vec())
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+impl<F: PrimeField> GKRProofSource<F> for GKRProofView<'_, F> {
+    fn layer_count(&self) -> usize {
+        self.p_proofs.len()
+    }
+
+    fn w_i_eval_count(&self) -> usize {
+        self.w_i_evals.len() / 2
+    }
+
+    fn line_poly_count(&self) -> usize {
+        self.line_polys.len()
+    }
+
+    fn output_layer(&self) -> Option<Cow<'_, [F]>> {
+        self.output_layer.to_vec().map(Cow::Owned)
+    }
+
+    fn w_i_evals(&self, i: usize) -> Option<(F, F)> {
+        Some((self.w_i_evals.get(2 * i)?, self.w_i_evals.get(2 * i + 1)?))
+    }
+
+    fn sum_check(&self, i: usize) -> Option<Cow<'_, Proof<F>>> {
+        self.p_proofs.get(i)?.to_proof().map(Cow::Owned)
+    }
+
+    fn init_claimed_sum(&self, i: usize) -> Option<F> {
+        self.p_proofs.get(i)?.init_claimed_sum()
+    }
+
+    fn line_poly(&self, i: usize) -> Option<Cow<'_, [F]>> {
+        self.line_polys.get(i)?.to_vec().map(Cow::Owned)
+    }
+}
+
+impl<F: PrimeField> Circuit<F> {
+    // Circuit::verify on the serialized proof without decoding it up front
+    pub fn verify_view(
+        &self,
+        proof: &GKRProofView<F>,
+        public: &PublicInputs<F>,
+        transcript_domain: &TranscriptDomain,
+    ) -> bool {
+        self.verify_from(
+            transcript_domain.transcript(),
+            proof,
+            public,
+            None,
+            &mut Explain::off(),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{
+        gkr::{
+            chunked_sum_check::ProverConfig, gkr_2_to_1_trick::LayerReduction,
+            gkr_circuit::test::setup_test_circuit8,
+        },
+        transcript::test::domain,
+    };
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_view_verifies_like_the_proof() {
+        let circuit = setup_test_circuit8();
+        let public = circuit.public_inputs();
+
+        for reduction in [LayerReduction::AlphaBeta, LayerReduction::Line] {
+            let config = ProverConfig {
+                reduction,
+                ..Default::default()
+            };
+            let proof = circuit.proof_with_config(&config, &domain());
+            let bytes = proof.to_bytes().unwrap();
+
+            let view = GKRProofView::<Fq>::parse(&bytes).unwrap();
+            assert_eq!(view.layer_count(), proof.p_proofs.len());
+            assert!(circuit.verify_view(&view, &public, &domain()));
+            assert_eq!(view.to_proof().unwrap().to_bytes().unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_view_rejects_bad_bytes() {
+        let circuit = setup_test_circuit8();
+        let public = circuit.public_inputs();
+        let bytes = circuit.proof(&domain()).to_bytes().unwrap();
+
+        // parsing only checks the shape, a cut proof or trailing bytes fail right away
+        assert!(GKRProofView::<Fq>::parse(&bytes[..bytes.len() - 1]).is_err());
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert!(matches!(
+            GKRProofView::<Fq>::parse(&longer),
+            Err(ProofFormatError::TrailingBytes(1))
+        ));
+
+        // a changed element still parses, the verifier rejects it when it reads it
+        let mut tampered = bytes.clone();
+        // the last round poly element sits right before the empty line_polys prefix
+        let last = tampered.len() - 8 - 20;
+        tampered[last] ^= 1;
+        let view = GKRProofView::<Fq>::parse(&tampered).unwrap();
+        assert!(!circuit.verify_view(&view, &public, &domain()));
+
+        // an element that is not canonical (all ones is above the modulus) is a rejection too
+        let mut non_canonical = bytes.clone();
+        let end = non_canonical.len() - 8;
+        non_canonical[end - 32..end].fill(0xff);
+        let view = GKRProofView::<Fq>::parse(&non_canonical).unwrap();
+        assert!(view.to_proof().is_none());
+        assert!(!circuit.verify_view(&view, &public, &domain()));
+    }
+}
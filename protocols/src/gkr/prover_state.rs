@@ -0,0 +1,345 @@
+use crate::{
+    eval_store::{EvalStorage, EvalStore},
+    gkr::{
+        chunked_sum_check::{self, ChunkedLayerPoly, ProverConfig},
+        gkr_circuit::{Circuit, GateOp},
+        gkr_protocol::layer_f_eval,
+        padded_layer::PaddedLayer,
+        partial_sum_check::{self, Proof},
+        product_poly::ProductPoly,
+        trace::{EvaluatedTrace, TraceError},
+    },
+    lagrange::LagrangeWeights,
+    multi_linear::MultiLinearPoly,
+    progress::{never_cancelled, Cancelled, Progress},
+    transcript::Transcript,
+};
+use ark_ff::PrimeField;
+use std::{borrow::Cow, io};
+
+//=========================================================================================
+// ProverState evaluates the circuit exactly once and owns the resulting trace
+// layer 0 is the input layer and the last layer is the output layer (same as Circuit::evaluate)
+// the provers borrow slices from here instead of cloning whole layers every round
+// with EvalStorage::Disk each layer is spilled as soon as it is evaluated and read back per use
+// the input layer is kept padded to what layer 1 addresses (Circuit::input_padding)
+//=========================================================================================
+pub struct ProverState<'a, F: PrimeField> {
+    pub circuit: &'a Circuit<F>,
+    evaluated_layers: Vec<EvalStore<F>>,
+}
+
+// what a layer sum-check proves: f_rᵢ(b, c) over Wᵢ₊₁ sums to claimed_sum, with addᵢ and mulᵢ
+// already reduced to (b, c)
+#[derive(Debug, Clone)]
+pub struct LayerClaim<F: PrimeField> {
+    pub next_layer_idx: usize,
+    pub add_i: MultiLinearPoly<F>,
+    pub mul_i: MultiLinearPoly<F>,
+    pub claimed_sum: F,
+}
+
+impl<'a, F: PrimeField> ProverState<'a, F> {
+    pub fn new(circuit: &'a Circuit<F>) -> Self {
+        let mut layers = circuit.evaluate();
+        layers[0] = circuit.input_padding().pad(&circuit.inputs);
+
+        ProverState {
+            circuit,
+            evaluated_layers: layers.into_iter().map(EvalStore::Memory).collect(),
+        }
+    }
+
+    // evaluates one layer at a time so only two layers are ever in memory together
+    pub fn with_storage(circuit: &'a Circuit<F>, storage: &EvalStorage) -> io::Result<Self> {
+        let mut evaluated_layers = Vec::new();
+        let mut current_layer = circuit.inputs.clone();
+        let padded_inputs = circuit.input_padding().pad(&circuit.inputs);
+
+        for (layer_i, layer) in circuit.layers.iter().enumerate() {
+            let next_layer = Circuit::evaluate_layer(layer, &current_layer);
+            let stored = match layer_i {
+                0 => padded_inputs.clone(),
+                _ => current_layer,
+            };
+            evaluated_layers.push(storage.store(stored)?);
+            current_layer = next_layer;
+        }
+        evaluated_layers.push(storage.store(current_layer)?);
+
+        Ok(ProverState {
+            circuit,
+            evaluated_layers,
+        })
+    }
+
+    // reuses an exported evaluation instead of evaluating the circuit again
+    pub fn from_trace(
+        circuit: &'a Circuit<F>,
+        trace: EvaluatedTrace<F>,
+    ) -> Result<Self, TraceError> {
+        circuit.check_trace(&trace)?;
+        let mut layers = trace.layers;
+        layers[0] = circuit.input_padding().pad(&layers[0]);
+
+        Ok(ProverState {
+            circuit,
+            evaluated_layers: layers.into_iter().map(EvalStore::Memory).collect(),
+        })
+    }
+
+    // number of gate layers i.e. excluding the input layer
+    pub fn circuit_len(&self) -> usize {
+        self.evaluated_layers.len() - 1
+    }
+
+    // borrowed for an in-memory layer, read back from disk otherwise
+    pub fn layer(&self, layer_i: usize) -> Cow<'_, [F]> {
+        self.evaluated_layers[layer_i].values()
+    }
+
+    pub fn output_layer(&self) -> Cow<'_, [F]> {
+        self.layer(self.circuit_len())
+    }
+
+    // W₀ padded to a power of 2, a single output is padded to 2 so it still has 1 variable
+    pub fn padded_output_layer(&self) -> PaddedLayer<F> {
+        PaddedLayer::new(&self.output_layer())
+    }
+
+    // returns exploded tuple of w_i(b, c) for the already evaluated layer_i
+    pub fn explode_w_i(&self, layer_i: usize) -> (Vec<F>, Vec<F>) {
+        if layer_i > self.circuit_len() {
+            panic!("INVALID Layer index for EXPLOSION");
+        }
+
+        Circuit::explode_layer(&self.layer(layer_i))
+    }
+
+    // exploded arity-tuple of w_i(x₁, ..., x_arity) for the already evaluated layer_i
+    pub fn explode_w_i_tuple(&self, layer_i: usize, arity: usize) -> Vec<Vec<F>> {
+        if layer_i > self.circuit_len() {
+            panic!("INVALID Layer index for EXPLOSION");
+        }
+
+        Circuit::explode_layer_tuple(&self.layer(layer_i), arity)
+    }
+
+    // f_rᵢ(b, c) = addᵢ(rᵢ,b,c)(Wᵢ₊₁(b) + Wᵢ₊₁(c)) + mulᵢ(rᵢ,b,c)(Wᵢ₊₁(b) * Wᵢ₊₁(c))
+    // where add_i and mul_i have already been reduced to (b, c)
+    pub fn layer_sum_poly(
+        &self,
+        next_layer_idx: usize,
+        add_i: MultiLinearPoly<F>,
+        mul_i: MultiLinearPoly<F>,
+    ) -> Vec<ProductPoly<F>> {
+        let (w_i_b_exploded, w_i_c_exploded) = self.explode_w_i(next_layer_idx);
+
+        let sum_term = Circuit::<F>::element_wise_op(&w_i_b_exploded, &w_i_c_exploded, GateOp::Add);
+        let mul_term = Circuit::<F>::element_wise_op(&w_i_b_exploded, &w_i_c_exploded, GateOp::Mul);
+
+        let p_poly_1 = ProductPoly::new(vec![
+            add_i,
+            MultiLinearPoly {
+                computation: sum_term,
+            },
+        ]);
+        let p_poly_2 = ProductPoly::new(vec![
+            mul_i,
+            MultiLinearPoly {
+                computation: mul_term,
+            },
+        ]);
+
+        vec![p_poly_1, p_poly_2]
+    }
+
+    // sum-check of f_rᵢ(b, c) in a fork of transcript (the GKR transcript at this layer), with a
+    // chunk length the exploded tables are never materialized
+    pub fn layer_proof(
+        &self,
+        transcript: &Transcript,
+        claim: LayerClaim<F>,
+        config: &ProverConfig,
+    ) -> Proof<F> {
+        never_cancelled(self.layer_proof_with_progress(
+            transcript,
+            claim,
+            config,
+            &mut Progress::none(),
+        ))
+    }
+
+    // same proof with its rounds reported, the events carry the index of the layer proof
+    pub fn layer_proof_with_progress(
+        &self,
+        transcript: &Transcript,
+        claim: LayerClaim<F>,
+        config: &ProverConfig,
+        progress: &mut Progress,
+    ) -> Result<Proof<F>, Cancelled> {
+        let LayerClaim {
+            next_layer_idx,
+            add_i,
+            mul_i,
+            claimed_sum,
+        } = claim;
+        let layer = Some(self.circuit_len() - next_layer_idx - 1);
+
+        match config.chunk_len {
+            Some(chunk_len) => {
+                let layer_poly =
+                    ChunkedLayerPoly::new(add_i, mul_i, &self.layer(next_layer_idx), chunk_len)
+                        .with_parallelism(config.parallelism.clone());
+                chunked_sum_check::proof_with_progress(
+                    transcript,
+                    layer_poly,
+                    claimed_sum,
+                    layer,
+                    progress,
+                )
+            }
+            None => {
+                let sum_poly = self.layer_sum_poly(next_layer_idx, add_i, mul_i);
+                partial_sum_check::proof_with_progress(
+                    transcript,
+                    sum_poly,
+                    claimed_sum,
+                    layer,
+                    progress,
+                )
+            }
+        }
+    }
+
+    //=========================================================================================
+    // Debug check for a layer sum-check, the prover replays the rounds and compares the final
+    // claim with f_rᵢ(b*, c*) computed straight from the trace
+    // a wrong claimed sum is caught at the round it breaks, a wrong wiring or trace at the end,
+    // either way the panic names the GKR layer (the circuit layer whose gates are being checked)
+    //=========================================================================================
+    pub fn check_layer_proof(&self, claim: &LayerClaim<F>, proof: &Proof<F>) {
+        let next_layer_idx = claim.next_layer_idx;
+        let layer = next_layer_idx + 1;
+
+        let degree = proof
+            .round_polys
+            .first()
+            .map_or(0, |round_poly| round_poly.len() - 1);
+        let weights = LagrangeWeights::for_degree(degree);
+
+        let mut claimed_sum = proof.init_claimed_sum;
+        for (round, (round_poly, challenge)) in proof
+            .round_polys
+            .iter()
+            .zip(proof.challenges.iter())
+            .enumerate()
+        {
+            claimed_sum =
+                match partial_sum_check::verify_round(claimed_sum, round_poly, &weights, *challenge) {
+                    Some(next_claimed_sum) => next_claimed_sum,
+                    None => panic!(
+                        "GKR layer {} diverges at sum-check round {}: g(0) + g(1) is not the claimed sum",
+                        layer, round
+                    ),
+                };
+        }
+
+        let (w_b, w_c) = self.w_i_evals(next_layer_idx, &proof.challenges);
+        let direct = layer_f_eval(
+            claim.add_i.clone().evaluate(&proof.challenges).computation[0],
+            claim.mul_i.clone().evaluate(&proof.challenges).computation[0],
+            w_b,
+            w_c,
+        );
+        if direct != claimed_sum {
+            panic!(
+                "GKR layer {} diverges: the sum-check ends at {} but f_r(b*, c*) = {} from the trace",
+                layer, claimed_sum, direct
+            );
+        }
+    }
+
+    // (Wᵢ(r_b), Wᵢ(r_c)) where the sum-check challenges are r_b || r_c
+    pub fn w_i_evals(&self, layer_i: usize, challenges: &[F]) -> (F, F) {
+        let mid = challenges.len() / 2;
+        let (r_b_challenges, r_c_challenges) = challenges.split_at(mid);
+
+        let layer = self.layer(layer_i);
+        let w_i_b = MultiLinearPoly::new(&layer)
+            .evaluate(r_b_challenges)
+            .computation[0];
+        let w_i_c = MultiLinearPoly::new(&layer)
+            .evaluate(r_c_challenges)
+            .computation[0];
+
+        (w_i_b, w_i_c)
+    }
+
+    pub fn into_layers(self) -> Vec<Vec<F>> {
+        self.evaluated_layers
+            .into_iter()
+            .map(EvalStore::into_vec)
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{eval_store::test::disk_storage, gkr::gkr_circuit::test::setup_test_circuit8};
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_prover_state_matches_evaluate() {
+        let circuit = setup_test_circuit8();
+        let state = ProverState::new(&circuit);
+
+        assert_eq!(state.circuit_len(), circuit.layers.len());
+        assert_eq!(
+            state.output_layer().as_ref(),
+            circuit.evaluate().last().unwrap().as_slice()
+        );
+        assert_eq!(state.into_layers(), circuit.evaluate());
+    }
+
+    #[test]
+    fn test_prover_state_on_disk() {
+        let circuit = setup_test_circuit8();
+        let state = ProverState::with_storage(&circuit, &disk_storage()).unwrap();
+
+        assert_eq!(state.explode_w_i(1), circuit.explode_w_i(1));
+        assert_eq!(state.into_layers(), circuit.evaluate());
+    }
+
+    #[test]
+    #[should_panic(expected = "GKR layer 3 diverges at sum-check round 0")]
+    fn test_check_layer_proof_names_the_layer() {
+        let circuit = setup_test_circuit8();
+        let state = ProverState::new(&circuit);
+
+        let (add_i, mul_i) = circuit.layer_i_add_mul_at(3, &[Fq::from(5)]);
+        let claim = LayerClaim {
+            next_layer_idx: 2,
+            add_i,
+            mul_i,
+            claimed_sum: Fq::from(1),
+        };
+        let proof = state.layer_proof(&Transcript::new(), claim.clone(), &ProverConfig::default());
+        state.check_layer_proof(&claim, &proof);
+    }
+
+    #[test]
+    fn test_prover_state_explode_w_i() {
+        let circuit = setup_test_circuit8();
+        let state = ProverState::new(&circuit);
+
+        for layer_i in 0..=circuit.layers.len() {
+            assert_eq!(state.explode_w_i(layer_i), circuit.explode_w_i(layer_i));
+            assert_eq!(
+                state.explode_w_i_tuple(layer_i, 3),
+                circuit.explode_w_i_tuple(layer_i, 3)
+            );
+        }
+    }
+}
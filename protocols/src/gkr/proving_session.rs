@@ -0,0 +1,427 @@
+use crate::{
+    gkr::{
+        chunked_sum_check::ProverConfig,
+        gkr_2_to_1_trick::LayerReduction,
+        gkr_circuit::Circuit,
+        gkr_protocol::{GKRProof, LayerProof, LayerProver, ProofBuilder},
+        prover_state::ProverState,
+    },
+    progress::{Cancelled, Progress},
+    proof_format::{read_header, write_header, DecodeLimits, ProofFormatError, ProtocolId},
+    transcript::{TranscriptDomain, DIGEST_LEN},
+};
+use ark_ff::PrimeField;
+use std::{fmt, fs, io, path::Path};
+
+//=========================================================================================
+// Proving sessions that can stop after any layer and resume later, e.g. on another machine
+// a session runs the GKR prover layer by layer (run stops on a cancelled Progress, see progress)
+// and checkpoint() captures what it has: the circuit digest, the reduction, the layers proven so
+// far as a partial GKRProof and a digest of the transcript state after them
+// resuming evaluates the circuit again, then replays the transcript over the saved layers
+// without their sum-checks, every replayed layer has to be the one the prover would produce
+// (same claim, same W evaluations) and the transcript has to end where the saved one did,
+// so a checkpoint from another circuit, statement or domain is an error, not a wrong proof
+// the finished proof is the same as circuit.proof_with_config(config, domain)
+// checkpoint bytes: header (version, GkrCheckpoint) || circuit digest || reduction (1 byte)
+//   || transcript digest || the partial GKRProof bytes
+//=========================================================================================
+#[derive(Debug)]
+pub enum CheckpointError {
+    CircuitMismatch,
+    ReductionMismatch,
+    LayerMismatch { layer: usize }, // not the layer this prover produces, e.g. other inputs
+    TranscriptMismatch,             // the replayed transcript doesn't end where the saved one did
+    Format(ProofFormatError),
+    Io(io::Error),
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::CircuitMismatch => {
+                write!(f, "the checkpoint was made for another circuit")
+            }
+            CheckpointError::ReductionMismatch => {
+                write!(f, "the checkpoint was made with another layer reduction")
+            }
+            CheckpointError::LayerMismatch { layer } => {
+                write!(f, "layer {} of the checkpoint doesn't replay", layer)
+            }
+            CheckpointError::TranscriptMismatch => {
+                write!(f, "the checkpoint's transcript doesn't replay")
+            }
+            CheckpointError::Format(err) => write!(f, "malformed checkpoint: {}", err),
+            CheckpointError::Io(err) => write!(f, "checkpoint file: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<ProofFormatError> for CheckpointError {
+    fn from(err: ProofFormatError) -> Self {
+        CheckpointError::Format(err)
+    }
+}
+
+impl From<io::Error> for CheckpointError {
+    fn from(err: io::Error) -> Self {
+        CheckpointError::Io(err)
+    }
+}
+
+pub struct ProverCheckpoint<F: PrimeField> {
+    pub circuit_digest: [u8; DIGEST_LEN],
+    pub reduction: LayerReduction,
+    pub transcript_digest: [u8; DIGEST_LEN],
+    pub partial: GKRProof<F>, // the output layer and the layers proven so far, from the output down
+}
+
+impl<F: PrimeField> ProverCheckpoint<F> {
+    pub fn layers_done(&self) -> usize {
+        self.partial.p_proofs.len()
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofFormatError> {
+        let mut bytes = Vec::new();
+        write_header(ProtocolId::GkrCheckpoint, &mut bytes);
+
+        bytes.extend_from_slice(&self.circuit_digest);
+        bytes.push(match self.reduction {
+            LayerReduction::AlphaBeta => 0,
+            LayerReduction::Line => 1,
+        });
+        bytes.extend_from_slice(&self.transcript_digest);
+        bytes.extend(self.partial.to_bytes()?);
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    pub fn from_bytes_with_limits(
+        mut bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        limits.check_size(bytes)?;
+        read_header(&mut bytes, ProtocolId::GkrCheckpoint)?;
+        if bytes.len() < 2 * DIGEST_LEN + 1 {
+            return Err(ProofFormatError::LengthMismatch {
+                len: 2 * DIGEST_LEN as u64 + 1,
+                remaining: bytes.len(),
+            });
+        }
+
+        let (circuit_digest, bytes) = bytes.split_at(DIGEST_LEN);
+        let reduction = match bytes[0] {
+            0 => LayerReduction::AlphaBeta,
+            1 => LayerReduction::Line,
+            id => return Err(ProofFormatError::UnknownReduction(id)),
+        };
+        let (transcript_digest, bytes) = bytes[1..].split_at(DIGEST_LEN);
+
+        Ok(ProverCheckpoint {
+            circuit_digest: circuit_digest.try_into().unwrap(),
+            reduction,
+            transcript_digest: transcript_digest.try_into().unwrap(),
+            partial: GKRProof::from_bytes_with_limits(bytes, limits)?,
+        })
+    }
+
+    // written next to path first and renamed, a machine going down mid-write keeps the old file
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let tmp = path.with_extension("partial");
+        fs::write(&tmp, self.to_bytes()?)?;
+        fs::rename(&tmp, path)?;
+
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CheckpointError> {
+        Ok(Self::from_bytes(&fs::read(path)?)?)
+    }
+}
+
+pub struct ProvingSession<'a, F: PrimeField> {
+    state: ProverState<'a, F>,
+    config: ProverConfig,
+    prover: LayerProver<F>,
+    builder: ProofBuilder<F>,
+}
+
+impl<F: PrimeField> Circuit<F> {
+    pub fn proving_session(
+        &self,
+        config: ProverConfig,
+        transcript_domain: &TranscriptDomain,
+    ) -> ProvingSession<'_, F> {
+        let state = ProverState::new(self);
+        let prover = LayerProver::start(&state, transcript_domain);
+        let builder = ProofBuilder::with_capacity(state.circuit_len(), config.reduction);
+
+        ProvingSession {
+            state,
+            config,
+            prover,
+            builder,
+        }
+    }
+
+    // config.reduction has to be the one the checkpoint was made with, the rest can change
+    pub fn resume_session(
+        &self,
+        config: ProverConfig,
+        transcript_domain: &TranscriptDomain,
+        checkpoint: &ProverCheckpoint<F>,
+    ) -> Result<ProvingSession<'_, F>, CheckpointError> {
+        if checkpoint.circuit_digest != self.digest() {
+            return Err(CheckpointError::CircuitMismatch);
+        }
+        if checkpoint.reduction != config.reduction {
+            return Err(CheckpointError::ReductionMismatch);
+        }
+
+        let reduction = config.reduction;
+        let mut session = self.proving_session(config, transcript_domain);
+        let partial = &checkpoint.partial;
+        if partial.output_layer != session.prover.output_layer
+            || partial.p_proofs.len() > session.prover.remaining()
+        {
+            return Err(CheckpointError::LayerMismatch { layer: 0 });
+        }
+
+        let mut line_polys = partial.line_polys.iter();
+        for (layer, (sum_check, w_evals)) in partial
+            .p_proofs
+            .iter()
+            .zip(partial.w_i_evals.iter())
+            .enumerate()
+        {
+            // every layer but the input one has a line poly with the line reduction
+            let line_poly = match reduction {
+                LayerReduction::Line if session.prover.remaining() > 1 => {
+                    line_polys.next().cloned().unwrap_or_default()
+                }
+                _ => Vec::new(),
+            };
+            let layer_proof = LayerProof {
+                sum_check: sum_check.clone(),
+                w_evals: *w_evals,
+                line_poly,
+            };
+
+            if !session
+                .prover
+                .replay_layer(&session.state, &session.config, &layer_proof)
+            {
+                return Err(CheckpointError::LayerMismatch { layer });
+            }
+            session.builder.push_layer(layer_proof);
+        }
+
+        if session.transcript_digest() != checkpoint.transcript_digest {
+            return Err(CheckpointError::TranscriptMismatch);
+        }
+
+        Ok(session)
+    }
+}
+
+impl<F: PrimeField> ProvingSession<'_, F> {
+    pub fn layers_done(&self) -> usize {
+        self.state.circuit_len() - self.prover.remaining()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.prover.remaining() == 0
+    }
+
+    // proves the layers left, on Err(Cancelled) the finished layers are kept and the session can
+    // be checkpointed or run again
+    pub fn run(&mut self, progress: &mut Progress) -> Result<(), Cancelled> {
+        while let Some(layer) = self
+            .prover
+            .prove_layer(&self.state, &self.config, progress)?
+        {
+            self.builder.push_layer(layer);
+            progress.report(self.prover.layer_done(&self.state))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn checkpoint(&self) -> ProverCheckpoint<F> {
+        ProverCheckpoint {
+            circuit_digest: self.state.circuit.digest(),
+            reduction: self.config.reduction,
+            transcript_digest: self.transcript_digest(),
+            partial: self
+                .builder
+                .clone()
+                .finish(self.prover.output_layer.clone()),
+        }
+    }
+
+    // None until every layer is proven
+    pub fn finish(self) -> Option<GKRProof<F>> {
+        if !self.is_done() {
+            return None;
+        }
+
+        Some(self.builder.finish(self.prover.output_layer))
+    }
+
+    // squeezed from a copy, the session's transcript is not touched
+    fn transcript_digest(&self) -> [u8; DIGEST_LEN] {
+        self.prover
+            .transcript
+            .clone()
+            .squeeze()
+            .try_into()
+            .expect("a squeeze is one digest")
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{
+        gkr::gkr_circuit::test::setup_test_circuit8,
+        progress::{CancelToken, ProgressEvent},
+        transcript::test::domain,
+    };
+    use ark_bn254::Fq;
+
+    // proves layers until `layers` are done, then cancels
+    fn stop_after(
+        circuit: &Circuit<Fq>,
+        config: ProverConfig,
+        layers: usize,
+    ) -> ProverCheckpoint<Fq> {
+        let token = CancelToken::new();
+        let mut progress = Progress::new(|event| {
+            if event
+                == (ProgressEvent::Layer {
+                    done: layers,
+                    total: 3,
+                })
+            {
+                token.cancel();
+            }
+        })
+        .with_cancel(&token);
+
+        let mut session = circuit.proving_session(config, &domain());
+        assert_eq!(session.run(&mut progress), Err(Cancelled));
+        assert_eq!(session.layers_done(), layers);
+
+        session.checkpoint()
+    }
+
+    #[test]
+    fn test_resumed_proof_is_the_same_proof() {
+        let circuit = setup_test_circuit8();
+
+        for config in [
+            ProverConfig::default(),
+            ProverConfig::default().with_reduction(LayerReduction::Line),
+        ] {
+            let expected = circuit.proof_with_config(&config, &domain());
+            let bytes = stop_after(&circuit, config.clone(), 2).to_bytes().unwrap();
+
+            let checkpoint = ProverCheckpoint::from_bytes(&bytes).unwrap();
+            assert_eq!(checkpoint.layers_done(), 2);
+            let mut session = circuit
+                .resume_session(config, &domain(), &checkpoint)
+                .unwrap();
+            session.run(&mut Progress::none()).unwrap();
+            let proof = session.finish().unwrap();
+
+            assert_eq!(proof.to_bytes().unwrap(), expected.to_bytes().unwrap());
+            assert!(circuit.verify(&proof, &circuit.public_inputs(), &domain()));
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_decode_limits() {
+        let circuit = setup_test_circuit8();
+        let bytes = stop_after(&circuit, ProverConfig::default(), 2)
+            .to_bytes()
+            .unwrap();
+
+        let limits = DecodeLimits {
+            max_bytes: bytes.len() - 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            ProverCheckpoint::<Fq>::from_bytes_with_limits(&bytes, &limits),
+            Err(ProofFormatError::TooLarge { .. })
+        ));
+
+        // the limits reach the partial proof, its second sum-check has 2 * 2 challenges
+        let limits = DecodeLimits {
+            max_len: 3,
+            ..Default::default()
+        };
+        assert!(matches!(
+            ProverCheckpoint::<Fq>::from_bytes_with_limits(&bytes, &limits),
+            Err(ProofFormatError::TooLarge { max: 3, .. })
+        ));
+
+        for len in 0..bytes.len() {
+            assert!(ProverCheckpoint::<Fq>::from_bytes(&bytes[..len]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_file_roundtrip() {
+        let circuit = setup_test_circuit8();
+        let path = std::env::temp_dir().join(format!("gkr-checkpoint-{}", std::process::id()));
+
+        stop_after(&circuit, ProverConfig::default(), 1)
+            .save(&path)
+            .unwrap();
+        let checkpoint = ProverCheckpoint::<Fq>::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let session = circuit.resume_session(ProverConfig::chunked(2), &domain(), &checkpoint);
+        assert_eq!(session.unwrap().layers_done(), 1);
+    }
+
+    #[test]
+    fn test_checkpoint_for_another_statement_is_rejected() {
+        let circuit = setup_test_circuit8();
+        let config = ProverConfig::default();
+        let checkpoint = stop_after(&circuit, config.clone(), 1);
+
+        // the first claim already depends on the statement and the domain
+        let other_domain = TranscriptDomain::new(b"other", domain().public_digest);
+        assert!(matches!(
+            circuit.resume_session(config.clone(), &other_domain, &checkpoint),
+            Err(CheckpointError::LayerMismatch { layer: 0 })
+        ));
+
+        let mut other_inputs = circuit.clone();
+        other_inputs.inputs[0] += Fq::from(1);
+        assert!(matches!(
+            other_inputs.resume_session(config.clone(), &domain(), &checkpoint),
+            Err(CheckpointError::LayerMismatch { layer: 0 })
+        ));
+
+        let mut tampered = stop_after(&circuit, config.clone(), 1);
+        tampered.transcript_digest[0] ^= 1;
+        assert!(matches!(
+            circuit.resume_session(config.clone(), &domain(), &tampered),
+            Err(CheckpointError::TranscriptMismatch)
+        ));
+
+        let line = config.with_reduction(LayerReduction::Line);
+        assert!(matches!(
+            circuit.resume_session(line, &domain(), &checkpoint),
+            Err(CheckpointError::ReductionMismatch)
+        ));
+    }
+}
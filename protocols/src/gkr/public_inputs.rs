@@ -0,0 +1,93 @@
+use crate::{
+    gkr::gkr_circuit::Circuit,
+    proof_format::{ensure_consumed, read_vec, DecodeLimits, ProofFormatError},
+    transcript::Transcript,
+};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+
+//=========================================================================================
+// The public part of a GKR statement, handed to the verifier next to the proof
+// inputs: the values the verifier evaluates the input layer MLE on (empty when the inputs are committed)
+// outputs: the claimed circuit outputs, W₀ without its padding
+// both sides absorb the canonical bytes before anything else so the challenges depend on them
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicInputs<F: PrimeField> {
+    pub inputs: Vec<F>,
+    pub outputs: Vec<F>,
+}
+
+impl<F: PrimeField> PublicInputs<F> {
+    pub fn new(inputs: Vec<F>, outputs: Vec<F>) -> Self {
+        PublicInputs { inputs, outputs }
+    }
+
+    // for protocols where the inputs are behind a commitment
+    pub fn outputs_only(outputs: Vec<F>) -> Self {
+        PublicInputs {
+            inputs: Vec::new(),
+            outputs,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.inputs.serialize_compressed(&mut bytes).unwrap();
+        self.outputs.serialize_compressed(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    pub fn from_bytes_with_limits(
+        mut bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        limits.check_size(bytes)?;
+        let inputs = read_vec::<F>(&mut bytes, limits)?;
+        let outputs = read_vec::<F>(&mut bytes, limits)?;
+        ensure_consumed(bytes)?;
+
+        Ok(PublicInputs { inputs, outputs })
+    }
+
+    pub fn absorb_into(&self, transcript: &mut Transcript) {
+        transcript.absorb(&self.to_bytes());
+    }
+}
+
+impl<F: PrimeField> Circuit<F> {
+    // the statement an honest prover of this circuit claims: its inputs and what they evaluate to
+    pub fn public_inputs(&self) -> PublicInputs<F> {
+        let outputs = self.evaluate().last().cloned().unwrap_or_default();
+
+        PublicInputs::new(self.inputs.clone(), outputs)
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::gkr::gkr_circuit::test::setup_test_circuit8;
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_public_inputs_bytes_roundtrip() {
+        let public = setup_test_circuit8().public_inputs();
+        assert_eq!(public.outputs, vec![Fq::from(1695)]);
+
+        let bytes = public.to_bytes();
+        assert_eq!(PublicInputs::<Fq>::from_bytes(&bytes).unwrap(), public);
+
+        let mut trailing_bytes = bytes;
+        trailing_bytes.push(0);
+        assert!(matches!(
+            PublicInputs::<Fq>::from_bytes(&trailing_bytes),
+            Err(ProofFormatError::TrailingBytes(1))
+        ));
+    }
+}
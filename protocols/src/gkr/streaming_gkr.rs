@@ -0,0 +1,275 @@
+use crate::{
+    gkr::{
+        chunked_sum_check::ProverConfig,
+        gkr_2_to_1_trick::{line_challenge, squeeze_alpha_beta, LayerReduction},
+        gkr_circuit::Circuit,
+        gkr_protocol::{layer_f_eval, LayerProof},
+        padded_layer::PaddedLayer,
+        partial_sum_check,
+        prover_state::ProverState,
+        public_inputs::PublicInputs,
+    },
+    multi_linear::{EvaluationTable, MultiLinearPoly},
+    progress::{never_cancelled, Progress},
+    transcript::{Transcript, TranscriptDomain},
+    UnivariatePoly,
+};
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// Layer-wise streaming of a GKR proof
+// the prover hands out every LayerProof (sum-check, Wᵢ₊₁(r_b), Wᵢ₊₁(r_c), q(t)) as soon as the
+// layer is done, from the output layer down to the inputs, so a verifier on the other side of a
+// connection checks layer i while the prover is still working on layer i + 1
+// the messages are exactly the ones of GKRProof and the transcript is the same, collecting every
+// LayerProof gives back circuit.proof()
+// W₀ is never streamed, the verifier builds it from the public outputs it already holds
+//=========================================================================================
+impl<F: PrimeField> Circuit<F> {
+    pub fn prove_streaming(
+        &self,
+        config: &ProverConfig,
+        transcript_domain: &TranscriptDomain,
+        on_layer: impl FnMut(LayerProof<F>),
+    ) {
+        let state = ProverState::new(self);
+        never_cancelled(self.stream_with_state(
+            &state,
+            config,
+            transcript_domain,
+            &mut Progress::none(),
+            on_layer,
+        ));
+    }
+}
+
+pub struct StreamingVerifier<'a, F: PrimeField> {
+    circuit: &'a Circuit<F>,
+    inputs: Vec<F>,
+    transcript: Transcript,
+    reduction: Option<LayerReduction>, // read off the first layer
+    layers_checked: usize,
+    claimed_sum: F,
+    add_i: MultiLinearPoly<F>,
+    mul_i: MultiLinearPoly<F>,
+    rejected: bool,
+}
+
+impl<'a, F: PrimeField> StreamingVerifier<'a, F> {
+    // None if the statement does not even fit the circuit
+    pub fn new(
+        circuit: &'a Circuit<F>,
+        public: &PublicInputs<F>,
+        transcript_domain: &TranscriptDomain,
+    ) -> Option<Self> {
+        if public.inputs.len() != circuit.inputs.len()
+            || public.outputs.len() != circuit.output_len()
+        {
+            return None;
+        }
+
+        let mut transcript = transcript_domain.transcript();
+        public.absorb_into(&mut transcript);
+
+        // same W₀ the prover sends, or derives from the single output
+        let w_0 = PaddedLayer::new(&public.outputs);
+        if circuit.output_len() > 1 {
+            w_0.absorb_into(&mut transcript);
+        }
+
+        let r_a_challenges: Vec<F> = (0..w_0.num_vars())
+            .map(|_| transcript.squeeze_field::<F>())
+            .collect();
+        let claimed_sum = MultiLinearPoly::new(w_0.padded())
+            .evaluate(&r_a_challenges)
+            .computation[0];
+        let (add_i, mul_i) = circuit.layer_i_add_mul_at(circuit.layers.len(), &r_a_challenges);
+
+        Some(StreamingVerifier {
+            circuit,
+            inputs: public.inputs.clone(),
+            transcript,
+            reduction: None,
+            layers_checked: 0,
+            claimed_sum,
+            add_i,
+            mul_i,
+            rejected: false,
+        })
+    }
+
+    // checks the next layer, false once any layer has been rejected
+    pub fn receive_layer(&mut self, layer: &LayerProof<F>) -> bool {
+        if !self.rejected && !self.check_layer(layer) {
+            self.rejected = true;
+        }
+
+        !self.rejected
+    }
+
+    // true only once every layer down to the inputs has been received and accepted
+    pub fn is_accepted(&self) -> bool {
+        !self.rejected && self.layers_checked == self.circuit.layers.len()
+    }
+
+    fn check_layer(&mut self, layer: &LayerProof<F>) -> bool {
+        let circuit_len = self.circuit.layers.len();
+        if self.layers_checked == circuit_len
+            || layer.sum_check.init_claimed_sum != self.claimed_sum
+        {
+            return false;
+        }
+
+        let sub_claim = match partial_sum_check::try_verify_in(&self.transcript, &layer.sum_check) {
+            Some(sub_claim) => sub_claim,
+            None => return false,
+        };
+        let challenges = sub_claim.challenges;
+
+        let (w_b, w_c) = layer.w_evals;
+        self.transcript
+            .absorb(&MultiLinearPoly::to_bytes(&[w_b, w_c]));
+
+        let table = EvaluationTable::new(&challenges);
+        let check = layer_f_eval(
+            table.evaluate(&self.add_i),
+            table.evaluate(&self.mul_i),
+            w_b,
+            w_c,
+        );
+        if check != sub_claim.last_claimed_sum {
+            return false;
+        }
+
+        // the layer below this one, index 0 is the input layer
+        let next_layer_idx = circuit_len - self.layers_checked - 1;
+        self.layers_checked += 1;
+
+        if next_layer_idx == 0 {
+            // Wᵢ₊₁ is the input layer, the verifier evaluates it itself
+            let mid = challenges.len() / 2;
+            let (r_b_challenges, r_c_challenges) = challenges.split_at(mid);
+            let mut input_poly = self.circuit.input_poly(&self.inputs);
+
+            return layer.line_poly.is_empty()
+                && input_poly.evaluate(r_b_challenges).computation[0] == w_b
+                && input_poly.evaluate(r_c_challenges).computation[0] == w_c;
+        }
+
+        let reduction = *self.reduction.get_or_insert(if layer.line_poly.is_empty() {
+            LayerReduction::AlphaBeta
+        } else {
+            LayerReduction::Line
+        });
+
+        (self.claimed_sum, self.add_i, self.mul_i) = match reduction {
+            LayerReduction::AlphaBeta => {
+                if !layer.line_poly.is_empty() {
+                    return false;
+                }
+
+                let (alpha, beta) = squeeze_alpha_beta(&mut self.transcript);
+                let (add_i, mul_i) =
+                    self.circuit
+                        .gkr_trick(&challenges, next_layer_idx, (alpha, beta));
+
+                (alpha * w_b + beta * w_c, add_i, mul_i)
+            }
+            LayerReduction::Line => {
+                // q(0) and q(1) are the two claims, the next sum-check must start at q(r*)
+                if layer.line_poly.is_empty() || layer.line_poly.len() > challenges.len() / 2 + 1 {
+                    return false;
+                }
+                let q = UnivariatePoly {
+                    coefficient: layer.line_poly.clone(),
+                };
+                if q.evaluate(F::zero()) != w_b || q.evaluate(F::one()) != w_c {
+                    return false;
+                }
+
+                let r_star = line_challenge(&mut self.transcript, &challenges, &layer.line_poly);
+                let (add_i, mul_i) = self
+                    .circuit
+                    .line_add_mul(&challenges, r_star, next_layer_idx);
+
+                (q.evaluate(r_star), add_i, mul_i)
+            }
+        };
+
+        true
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{
+        gkr::{gkr_circuit::test::setup_test_circuit8, public_inputs::PublicInputs},
+        transcript::test::domain,
+    };
+    use ark_bn254::Fq;
+
+    fn streamed(circuit: &Circuit<Fq>, config: &ProverConfig) -> Vec<LayerProof<Fq>> {
+        let mut layers = Vec::new();
+        circuit.prove_streaming(config, &domain(), |layer| layers.push(layer));
+
+        layers
+    }
+
+    #[test]
+    fn test_streamed_layers_match_the_proof() {
+        let circuit = setup_test_circuit8();
+        let layers = streamed(&circuit, &ProverConfig::default());
+        let proof = circuit.proof(&domain());
+
+        assert_eq!(layers.len(), circuit.layers.len());
+        for (layer, (w_evals, p_proof)) in layers
+            .iter()
+            .zip(proof.w_i_evals.iter().zip(proof.p_proofs.iter()))
+        {
+            assert_eq!(layer.w_evals, *w_evals);
+            assert_eq!(layer.sum_check.round_polys, p_proof.round_polys);
+        }
+    }
+
+    #[test]
+    fn test_streaming_verifier_accepts_layer_by_layer() {
+        let circuit = setup_test_circuit8();
+
+        for reduction in [LayerReduction::AlphaBeta, LayerReduction::Line] {
+            let config = ProverConfig {
+                reduction,
+                ..Default::default()
+            };
+            let mut verifier =
+                StreamingVerifier::new(&circuit, &circuit.public_inputs(), &domain()).unwrap();
+
+            circuit.prove_streaming(&config, &domain(), |layer| {
+                assert!(!verifier.is_accepted());
+                assert!(verifier.receive_layer(&layer));
+            });
+            assert!(verifier.is_accepted());
+        }
+    }
+
+    #[test]
+    fn test_streaming_verifier_rejects_early() {
+        let circuit = setup_test_circuit8();
+        let mut layers = streamed(&circuit, &ProverConfig::default());
+        layers[1].w_evals.0 += Fq::from(1);
+
+        let mut verifier =
+            StreamingVerifier::new(&circuit, &circuit.public_inputs(), &domain()).unwrap();
+        assert!(verifier.receive_layer(&layers[0]));
+        assert!(!verifier.receive_layer(&layers[1]));
+        // once rejected it stays rejected
+        assert!(!verifier.receive_layer(&layers[2]));
+        assert!(!verifier.is_accepted());
+
+        // a wrong output is caught at the very first layer
+        let other = PublicInputs::new(circuit.inputs.clone(), vec![Fq::from(1696)]);
+        let layers = streamed(&circuit, &ProverConfig::default());
+        let mut verifier = StreamingVerifier::new(&circuit, &other, &domain()).unwrap();
+        assert!(!verifier.receive_layer(&layers[0]));
+    }
+}
@@ -1,387 +1,445 @@
 use crate::{
-    gkr::{gkr_circuit::Circuit, partial_sum_check::Proof},
-    kzg::kzg_helper_functions::{
-        blow_up, compute_commitment, compute_poly_minus_v, compute_quotient, compute_remainder,
-    },
-    multi_linear::MultiLinearPoly,
-    transcript::Transcript,
+    calldata::{abi_encode, uint_word, AbiValue},
+    eval_claim::{EvalClaim, PolyId},
+    gkr::{gkr_circuit::Circuit, partial_sum_check::Proof, public_inputs::PublicInputs},
+    kzg::split_kzg::{self, SplitCommitment, SplitKZGProof},
+    kzg::trusted_setup::{TrustedSetup, VerifierSetup},
+    transcript::{DigestTag, Transcript, TranscriptDomain, DIGEST_LEN},
+    transcript_schedule::Step,
 };
-use ark_ec::{
-    pairing::{Pairing, PairingOutput},
-    PrimeGroup,
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::PrimeField;
+use std::{borrow::Cow, fmt};
+
+use super::{
+    chunked_sum_check::ProverConfig,
+    core,
+    gkr_2_to_1_trick::LayerReduction,
+    lazy_inputs::{CommittedInputGKR, InputCommitment, KzgInputs},
+    proof_view::GKRProofSource,
 };
-use ark_ff::{AdditiveGroup, PrimeField, Zero};
-
-use super::{gkr_circuit::GateOp, partial_sum_check, product_poly::ProductPoly};
 
-#[derive(Debug)]
-pub struct SuccinctGKRProof<F: PrimeField, P: Pairing> {
-    pub output_layer: Vec<F>,    // an array of wᵢ
-    pub w_i_evals: Vec<(F, F)>,  // array of wᵢ evaluated at r_b and r_c
-    pub p_proofs: Vec<Proof<F>>, // array of sum-check proofs
-    pub commitment: P::G1,
-    pub quotient_evals_rb: Vec<P::G1>,
-    pub quotient_evals_rc: Vec<P::G1>,
+// one opening of the committed input layer, Q is whatever the commitment scheme opens with
+// the point travels with it, the verifier only checks the points of all the openings put
+// together are the last sum-check point (r_b ‖ r_c) instead of splitting that point itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputOpening<F: PrimeField, Q> {
+    pub point: Vec<F>,
+    pub value: F,
+    pub quotients: Q,
 }
 
-impl<F: PrimeField> Circuit<F> {
-    pub fn succinct_proof<P: Pairing>(&self, encrypted_basis: &[P::G1]) -> SuccinctGKRProof<F, P> {
-        let mut transcript = Transcript::new();
-        let evaluated_circuit = self.evaluate();
-
-        let mut sum_poly_array = Vec::new();
-        let mut w_i_evals = Vec::new();
-        let mut p_proofs = Vec::new();
-        let mut r_a_challenges = Vec::new();
+impl<F: PrimeField, Q> InputOpening<F, Q> {
+    // what the opening claims about the committed polynomial, without the quotients
+    pub fn claim(&self, poly_id: PolyId) -> EvalClaim<F> {
+        EvalClaim::new(poly_id, self.point.clone(), self.value)
+    }
+}
 
-        //=========================================================================================
-        // First step to push the commitment to the transcript
-        //=========================================================================================
-        let input_poly = MultiLinearPoly::new(&self.inputs);
-        let commitment = compute_commitment::<F, P>(&input_poly, encrypted_basis);
+// true if the openings are at the consecutive slices of point, in order
+pub fn openings_cover_point<F: PrimeField, Q>(
+    openings: &[InputOpening<F, Q>],
+    point: &[F],
+) -> bool {
+    openings
+        .iter()
+        .flat_map(|opening| opening.point.iter())
+        .eq(point.iter())
+}
 
-        transcript.absorb(commitment.to_string().as_bytes());
+// circuit_digest and setup_digest name the circuit and the setup the proof was made for, the
+// verifier compares them with its own before anything else and both are in the transcript
+#[derive(Debug, Clone)]
+pub struct SuccinctGKRProof<F: PrimeField, P: Pairing> {
+    pub circuit_digest: [u8; DIGEST_LEN], // Circuit::digest of the wiring
+    pub setup_digest: [u8; DIGEST_LEN],   // VerifierSetup::digest
+    pub output_layer: Vec<F>,             // an array of wᵢ
+    pub w_i_evals: Vec<(F, F)>,           // array of wᵢ evaluated at r_b and r_c
+    pub p_proofs: Vec<Proof<F>>,          // array of sum-check proofs
+    pub commitment: SplitCommitment<P>,   // one commitment per setup sized segment of the inputs
+    pub input_openings: Vec<InputOpening<F, SplitKZGProof<F, P>>>, // at r_b, then at r_c
+}
 
-        //=========================================================================================
-        // GKR Proving Process
-        //=========================================================================================
-        let circuit_len = evaluated_circuit.len() - 1;
-
-        // Get the output layer evaluations (W₀)
-        let w_0 = evaluated_circuit[circuit_len].clone();
-
-        // Pad W₀ to power of 2 if needed
-        let w_0_arr = if w_0.len() == 1 {
-            vec![w_0[0], F::zero()]
-        } else if w_0.len().is_power_of_two() {
-            w_0
-        } else {
-            let target_length = w_0.len().next_power_of_two();
-            let mut padded = w_0.clone();
-            padded.resize(target_length, F::zero());
-            padded
-        };
-        let output_layer = w_0_arr.clone();
+impl<F: PrimeField, P: Pairing> SuccinctGKRProof<F, P> {
+    // the proof as the arguments of a contract verifier, see crate::calldata, in order:
+    //   bytes32 circuit_digest, bytes32 setup_digest, uint256[] output_layer,
+    //   uint256[2][] w_i_evals, uint256[3][] sum-check (degree, num_products, rounds) per layer,
+    //   uint256[] init_claimed_sums, uint256[] round_polys (every layer's rounds, each degree + 1
+    //   evaluations), uint256[] challenges (every layer's, in order),
+    //   uint256[2][] segment_commitments (G1 on bn254, uint256[4][] on BLS12-381),
+    //   uint256[] opening_points (r_b then r_c), uint256[] opening_values,
+    //   uint256[] segment_evals (both openings), uint256[2][] opening_quotients (both openings)
+    // the openings' arrays are their two openings end to end, equal halves for a valid proof
+    pub fn to_calldata(&self) -> Vec<u8>
+    where
+        <P::G1 as CurveGroup>::BaseField: PrimeField,
+    {
+        let openings = &self.input_openings;
+        abi_encode(&[
+            AbiValue::digest(&self.circuit_digest),
+            AbiValue::digest(&self.setup_digest),
+            AbiValue::fields(self.output_layer.iter(), 1),
+            AbiValue::fields(self.w_i_evals.iter().flat_map(|(b, c)| [b, c]), 2),
+            AbiValue::Array {
+                element_words: 3,
+                words: self
+                    .p_proofs
+                    .iter()
+                    .flat_map(|p_proof| {
+                        [
+                            p_proof.degree,
+                            p_proof.num_products,
+                            p_proof.round_polys.len(),
+                        ]
+                        .map(|value| uint_word(value as u64))
+                    })
+                    .collect(),
+            },
+            AbiValue::fields(
+                self.p_proofs
+                    .iter()
+                    .map(|p_proof| &p_proof.init_claimed_sum),
+                1,
+            ),
+            AbiValue::fields(
+                self.p_proofs
+                    .iter()
+                    .flat_map(|p_proof| p_proof.round_polys.iter().flatten()),
+                1,
+            ),
+            AbiValue::fields(
+                self.p_proofs
+                    .iter()
+                    .flat_map(|p_proof| p_proof.challenges.iter()),
+                1,
+            ),
+            AbiValue::points(self.commitment.segment_commitments.iter()),
+            AbiValue::fields(openings.iter().flat_map(|opening| opening.point.iter()), 1),
+            AbiValue::fields(openings.iter().map(|opening| &opening.value), 1),
+            AbiValue::fields(
+                openings
+                    .iter()
+                    .flat_map(|opening| opening.quotients.segment_evals.iter()),
+                1,
+            ),
+            AbiValue::points(
+                openings
+                    .iter()
+                    .flat_map(|opening| opening.quotients.quotient_evals.iter()),
+            ),
+        ])
+    }
+}
 
-        let w_0_len = w_0_arr.len().ilog2();
+// what check_succinct_shape found wrong with a proof before any of it is evaluated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofShapeError {
+    // the proof was made for another circuit or another setup
+    CircuitMismatch,
+    SetupMismatch,
+    // one sum-check (and one (Wᵢ(r_b), Wᵢ(r_c)) pair) per layer of the circuit
+    LayerCount {
+        expected: usize,
+        found: usize,
+    },
+    EvalCount {
+        expected: usize,
+        found: usize,
+    },
+    OutputNotPowerOfTwo {
+        found: usize,
+    },
+    // the sum-check of proof `index` must have one round per variable of (b, c)
+    RoundCount {
+        index: usize,
+        expected: usize,
+        found: usize,
+    },
+    OpeningCount {
+        found: usize,
+    },
+    // the point must be half the last sum-check point, the quotients one per setup variable
+    OpeningShape {
+        index: usize,
+    },
+}
 
-        // Get random point r₀
-        transcript.absorb(&MultiLinearPoly::to_bytes(&w_0_arr));
-        for _ in 0..w_0_len {
-            let r_a = F::from_be_bytes_mod_order(&transcript.squeeze());
-            r_a_challenges.push(r_a);
+impl fmt::Display for ProofShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofShapeError::CircuitMismatch => {
+                write!(f, "the proof was made for another circuit")
+            }
+            ProofShapeError::SetupMismatch => {
+                write!(f, "the proof was made with another trusted setup")
+            }
+            ProofShapeError::LayerCount { expected, found } => write!(
+                f,
+                "expected {} sum-check proofs, one per layer, found {}",
+                expected, found
+            ),
+            ProofShapeError::EvalCount { expected, found } => write!(
+                f,
+                "expected {} pairs of layer evaluations, found {}",
+                expected, found
+            ),
+            ProofShapeError::OutputNotPowerOfTwo { found } => write!(
+                f,
+                "the output layer has {} values, not a power of two",
+                found
+            ),
+            ProofShapeError::RoundCount {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "sum-check proof {} has {} rounds instead of {}",
+                index, found, expected
+            ),
+            ProofShapeError::OpeningCount { found } => {
+                write!(
+                    f,
+                    "expected 2 input openings (r_b and r_c), found {}",
+                    found
+                )
+            }
+            ProofShapeError::OpeningShape { index } => write!(
+                f,
+                "input opening {} does not match the sum-check point or the setup",
+                index
+            ),
         }
+    }
+}
 
-        let w_0_eval = MultiLinearPoly::new(&w_0_arr).evaluate(&r_a_challenges); // claimed sum = w_0(r)
-        let init_claimed_sum = w_0_eval.computation[0];
-
-        //=========================================================================================
-        // f_ri_b_c = [add_i_ri_b_c * (w_i+1_b + w_i+1_c)] + [mul_i_ri_b_c * (w_i+1_b * w_i+1_c)]
-        //=========================================================================================
-        let next_layer_idx = circuit_len - 1;
-        let (w_i_b_exploded, w_i_c_exploded) = self.explode_w_i(next_layer_idx);
-
-        let sum_term = Circuit::<F>::element_wise_op(&w_i_b_exploded, &w_i_c_exploded, GateOp::Add);
-        let mul_term = Circuit::<F>::element_wise_op(&w_i_b_exploded, &w_i_c_exploded, GateOp::Mul);
-
-        let (add_i, mul_i) = self.layer_i_add_mul(circuit_len);
-        let mut add_i_mle = MultiLinearPoly::new(&add_i);
-        let mut mul_i_mle = MultiLinearPoly::new(&mul_i);
+impl std::error::Error for ProofShapeError {}
 
-        for r_a in r_a_challenges.iter() {
-            add_i_mle = add_i_mle.partial_evaluate(*r_a, 0);
-            mul_i_mle = mul_i_mle.partial_evaluate(*r_a, 0);
+impl<F: PrimeField> Circuit<F> {
+    // every length in the proof against the circuit and the setup, so the verifier never
+    // indexes past the end of a vector that came in with the proof
+    pub fn check_succinct_shape<P: Pairing>(
+        &self,
+        proof: &SuccinctGKRProof<F, P>,
+        verifier_setup: &VerifierSetup<P>,
+    ) -> Result<(), ProofShapeError> {
+        if proof.circuit_digest != self.digest() {
+            return Err(ProofShapeError::CircuitMismatch);
+        }
+        if proof.setup_digest != verifier_setup.digest() {
+            return Err(ProofShapeError::SetupMismatch);
         }
 
-        let p_poly_1 = ProductPoly::new(vec![
-            add_i_mle,
-            MultiLinearPoly {
-                computation: sum_term,
-            },
-        ]);
-        let p_poly_2 = ProductPoly::new(vec![
-            mul_i_mle,
-            MultiLinearPoly {
-                computation: mul_term,
-            },
-        ]);
-
-        let p_poly = vec![p_poly_1, p_poly_2];
-        sum_poly_array.push(p_poly.clone());
-
-        let p_proof = partial_sum_check::proof::<F>(p_poly, init_claimed_sum);
-        p_proofs.push(p_proof.clone());
-        let mut challenges = p_proof.challenges.clone();
-
-        //=========================================================================================
-        // For each layer i (going backwards from output to input)
-        // since last layer has been done, we start with next layer
-        // [0, 1, 2, 3] => would start at 2 and end at 1 as w will go down to 0
-        //=========================================================================================
-        for layer_idx in (1..circuit_len).rev() {
-            let next_layer_idx = layer_idx - 1; // this is because w is 1 layer ahead
-            let current_layer_w = evaluated_circuit[layer_idx].clone();
-
-            // claimed_sum = (alpha * Wᵢ(*b)) + (beta * Wᵢ(*c))
-            let claimed_sum = self.new_claimed_sum(current_layer_w, &challenges);
-
-            // Get the add and mul vectors for current layer
-            let (new_add, new_mul) = self.gkr_trick(&challenges, layer_idx);
-
-            // Get the next layer evaluations (Wᵢ₊₁)
-            let (w_i_b_exploded, w_i_c_exploded) = self.explode_w_i(next_layer_idx);
-
-            //=========================================================================================
-            // Compute f_rᵢ(b, c) = addᵢ(rᵢ,b,c)(Wᵢ₊₁(b) + Wᵢ₊₁(c)) + mulᵢ(rᵢ,b,c)(Wᵢ₊₁(b) * Wᵢ₊₁(c))
-            //=========================================================================================
-            let sum_term =
-                Circuit::<F>::element_wise_op(&w_i_b_exploded, &w_i_c_exploded, GateOp::Add);
-            let mul_term =
-                Circuit::<F>::element_wise_op(&w_i_b_exploded, &w_i_c_exploded, GateOp::Mul);
-
-            // Create the polynomials for sum-check
-            let p_poly_1 = ProductPoly::new(vec![
-                new_add,
-                MultiLinearPoly {
-                    computation: sum_term,
-                },
-            ]);
-            let p_poly_2 = ProductPoly::new(vec![
-                new_mul,
-                MultiLinearPoly {
-                    computation: mul_term,
-                },
-            ]);
-
-            let p_poly = vec![p_poly_1, p_poly_2];
-            sum_poly_array.push(p_poly.clone());
-
-            // Run sum-check protocol
-            let p_proof = partial_sum_check::proof::<F>(p_poly, claimed_sum);
-            p_proofs.push(p_proof.clone());
-
-            challenges = p_proof.challenges.clone();
+        let circuit_len = self.layers.len();
+        if proof.p_proofs.len() != circuit_len {
+            return Err(ProofShapeError::LayerCount {
+                expected: circuit_len,
+                found: proof.p_proofs.len(),
+            });
+        }
+        if proof.w_i_evals.len() != circuit_len {
+            return Err(ProofShapeError::EvalCount {
+                expected: circuit_len,
+                found: proof.w_i_evals.len(),
+            });
+        }
+        if !proof.output_layer.len().is_power_of_two() {
+            return Err(ProofShapeError::OutputNotPowerOfTwo {
+                found: proof.output_layer.len(),
+            });
         }
 
-        //=========================================================================================
-        // GKR evaluations of wᵢ at r_b and r_c to be used by the verifier
-        //=========================================================================================
-        for layer_idx in (0..circuit_len).rev() {
-            let current_layer_w = evaluated_circuit[layer_idx].clone();
-            let challenges = p_proofs[circuit_len - layer_idx - 1].challenges.clone();
+        // proof i reduces layer circuit_len - i onto the (b, c) variables of the layer below,
+        // input_bits of them for b and as many for c
+        for (index, p_proof) in proof.p_proofs.iter().enumerate() {
+            let (_, input_bits) = self.layer_bits(circuit_len - index);
+            let expected = 2 * input_bits as usize;
+            if p_proof.round_polys.len() != expected {
+                return Err(ProofShapeError::RoundCount {
+                    index,
+                    expected,
+                    found: p_proof.round_polys.len(),
+                });
+            }
+        }
 
-            let mid = challenges.len() / 2;
-            let (r_b_challenges, r_c_challenges) = challenges.split_at(mid);
+        if proof.input_openings.len() != 2 {
+            return Err(ProofShapeError::OpeningCount {
+                found: proof.input_openings.len(),
+            });
+        }
+        let (_, input_bits) = self.layer_bits(1);
+        for (index, opening) in proof.input_openings.iter().enumerate() {
+            if opening.point.len() != input_bits as usize
+                || opening.quotients.quotient_evals.len() != verifier_setup.max_num_vars()
+            {
+                return Err(ProofShapeError::OpeningShape { index });
+            }
+        }
 
-            let w_i_b = MultiLinearPoly::new(&current_layer_w)
-                .evaluate(&r_b_challenges)
-                .computation[0];
-            let w_i_c = MultiLinearPoly::new(&current_layer_w)
-                .evaluate(&r_c_challenges)
-                .computation[0];
+        Ok(())
+    }
 
-            transcript.absorb(&MultiLinearPoly::to_bytes(&[w_i_b, w_i_c]));
+    pub fn succinct_proof<P: Pairing>(
+        &self,
+        setup: &TrustedSetup<P>,
+        transcript_domain: &TranscriptDomain,
+    ) -> SuccinctGKRProof<F, P> {
+        self.succinct_proof_with_config(setup, &ProverConfig::default(), transcript_domain)
+    }
 
-            w_i_evals.push((w_i_b, w_i_c));
+    // the layers come from the same LayerProver as the plain GKR, config.chunk_len bounds the
+    // memory of each layer sum-check, the reduction has to be LayerReduction::AlphaBeta
+    pub fn succinct_proof_with_config<P: Pairing>(
+        &self,
+        setup: &TrustedSetup<P>,
+        config: &ProverConfig,
+        transcript_domain: &TranscriptDomain,
+    ) -> SuccinctGKRProof<F, P> {
+        if config.reduction != LayerReduction::AlphaBeta {
+            panic!("The succinct GKR only supports the alpha/beta reduction");
         }
+        let encrypted_basis = &setup.g1_arr;
+        let circuit_digest = self.digest();
+        let setup_digest = setup.verifier_setup.digest();
 
         //=========================================================================================
-        // KZG Proof
+        // The inputs are only known through the commitment so the statement is just the outputs,
+        // the layers are the committed input GKR of gkr::lazy_inputs
         //=========================================================================================
-        let mut quotient_evals_rb = Vec::new();
-        let mut quotient_evals_rc = Vec::new();
-
-        // Get last challenges for the quotient evaluations
-        let final_challenges = p_proofs.last().unwrap().challenges.clone();
-        let mid = final_challenges.len() / 2;
-        let (r_b_challenges, r_c_challenges) = final_challenges.split_at(mid);
+        let input_poly = self.input_poly(&self.inputs);
+        let commitment = split_kzg::commit::<F, P>(&input_poly, encrypted_basis)
+            .expect("Circuit inputs are smaller than the trusted setup");
+        let layers =
+            self.committed_input_proof(&commitment, &setup_digest, config, transcript_domain);
 
         //=========================================================================================
-        // Generating Quotients Q(τ) for r_b
-        //=========================================================================================
-        let mut poly_minus_v_b = compute_poly_minus_v(input_poly.clone(), &r_b_challenges);
-        for i in 0..(r_b_challenges.len()) {
-            let quotient = compute_quotient(&poly_minus_v_b);
-            let blown_quotient = blow_up(quotient, i + 1);
-
-            let mut quotient_eval = P::G1::zero();
-            for (j, e_basis) in encrypted_basis.iter().enumerate() {
-                quotient_eval += e_basis.mul_bigint(blown_quotient.computation[j].into_bigint());
-            }
-            quotient_evals_rb.push(quotient_eval);
-
-            let remainder = compute_remainder(poly_minus_v_b, r_b_challenges[i]);
-            poly_minus_v_b = remainder;
-        }
-
-        //=========================================================================================
-        // Generating Quotients Q(τ) for r_c
+        // KZG Proof
+        // the input MLE is opened at r_b and r_c, split into setup sized segments if it is larger
         //=========================================================================================
-        let mut poly_minus_v_c = compute_poly_minus_v(input_poly, &r_c_challenges);
-        for i in 0..(r_c_challenges.len()) {
-            let quotient = compute_quotient(&poly_minus_v_c);
-            let blown_quotient = blow_up(quotient, i + 1);
-
-            let mut quotient_eval = P::G1::zero();
-            for (j, e_basis) in encrypted_basis.iter().enumerate() {
-                quotient_eval += e_basis.mul_bigint(blown_quotient.computation[j].into_bigint());
-            }
-            quotient_evals_rc.push(quotient_eval);
-
-            let remainder = compute_remainder(poly_minus_v_c, r_c_challenges[i]);
-            poly_minus_v_c = remainder;
-        }
+        let (input_eval_b, input_eval_c) = layers.input_evals();
+        let input_openings = layers
+            .input_points()
+            .into_iter()
+            .zip([input_eval_b, input_eval_c])
+            .map(|(point, value)| InputOpening {
+                quotients: split_kzg::open(
+                    &input_poly,
+                    &commitment,
+                    encrypted_basis,
+                    &point,
+                    transcript_domain,
+                ),
+                point,
+                value,
+            })
+            .collect();
 
         SuccinctGKRProof {
-            output_layer,
-            w_i_evals,
-            p_proofs,
+            circuit_digest,
+            setup_digest,
+            output_layer: layers.output_layer,
+            w_i_evals: layers.w_i_evals,
+            p_proofs: layers.p_proofs,
             commitment,
-            quotient_evals_rb,
-            quotient_evals_rc,
+            input_openings,
         }
     }
 
+    // public.inputs must be empty, the inputs are bound by proof.commitment instead
+    // past the shape and the digests it is verify_committed_inputs with the openings as oracle
     pub fn succinct_verify<P: Pairing>(
         &self,
         proof: &SuccinctGKRProof<F, P>,
-        encrypted_basis_g2: &[P::G2],
+        public: &PublicInputs<F>,
+        verifier_setup: &VerifierSetup<P>,
+        transcript_domain: &TranscriptDomain,
     ) -> bool {
-        let g1_generator = P::G1::generator();
-        let g2_generator = P::G2::generator();
-
-        let mut transcript = Transcript::new();
-        let mut last_challenges = Vec::new();
-        let mut curr_challenges = Vec::new();
-        let mut current_claimed_sum = F::zero();
-        let circuit_len = self.layers.len(); // actual number of layers
-        let mut last_idx = 0;
-
-        //=========================================================================================
-        // First step to push the commitment to the transcript
-        //=========================================================================================
-        transcript.absorb(proof.commitment.to_string().as_bytes());
-
-        //=========================================================================================
-        // GKR Verification Process
-        //=========================================================================================
-        let w_0_arr = proof.output_layer.clone();
-        transcript.absorb(&MultiLinearPoly::to_bytes(&w_0_arr));
-        let r_a = F::from_be_bytes_mod_order(&transcript.squeeze());
-
-        let (add_i, mul_i) = self.layer_i_add_mul(circuit_len);
-        let mut new_add = MultiLinearPoly::new(&add_i).partial_evaluate(r_a, 0);
-        let mut new_mul = MultiLinearPoly::new(&mul_i).partial_evaluate(r_a, 0);
-
-        for (i, p_proof) in proof.p_proofs.iter().enumerate() {
-            let sub_claim = partial_sum_check::verify(p_proof.clone());
-            let challenges = sub_claim.challenges.clone();
-
-            curr_challenges = challenges.clone();
-            last_idx = i;
-
-            // For all but the last proof, check against w_i_evals
-            if i < proof.p_proofs.len() - 1 {
-                let new_add_eval = new_add.evaluate(&challenges);
-                let new_mul_eval = new_mul.evaluate(&challenges);
-
-                let (w_i_rb, w_i_rc) = proof.w_i_evals[i];
-                transcript.absorb(&MultiLinearPoly::to_bytes(&[w_i_rb, w_i_rc]));
-
-                let w_sum = w_i_rb + w_i_rc;
-                let w_mul = w_i_rb * w_i_rc;
-
-                let check =
-                    (new_add_eval.computation[0] * w_sum) + (new_mul_eval.computation[0] * w_mul);
-
-                if check != sub_claim.last_claimed_sum {
-                    return false;
-                }
-
-                (new_add, new_mul) = self.gkr_trick(&challenges, circuit_len - i - 1);
-
-                last_challenges = challenges.clone();
-            }
-
-            current_claimed_sum = sub_claim.last_claimed_sum;
+        if self.check_succinct_shape(proof, verifier_setup).is_err() {
+            return false;
         }
-
-        let mid = curr_challenges.len() / 2;
-        let (r_b_challenges, r_c_challenges) = curr_challenges.split_at(mid);
-
-        let input_eval_b = proof.w_i_evals.last().unwrap().0;
-        let input_eval_c = proof.w_i_evals.last().unwrap().1;
-
-        //=========================================================================================
-        // KZG Verification Process
-        // Verify opening at r_b
-        // pairing(g1_(f(τ) - v), g2_1) == pairing(Σ(g1_Q(τ), g2_(τ - a)))
-        //=========================================================================================
-        let b_lhs = P::pairing(
-            proof.commitment - g1_generator.mul_bigint(input_eval_b.into_bigint()),
-            g2_generator.mul_bigint(F::one().into_bigint()),
+        let mut oracle = KzgInputs::new(
+            &proof.commitment,
+            &proof.input_openings,
+            verifier_setup,
+            transcript_domain,
         );
 
-        let mut b_rhs = PairingOutput::ZERO;
-        for (i, tau) in encrypted_basis_g2.iter().enumerate() {
-            b_rhs += P::pairing(
-                proof.quotient_evals_rb[i],
-                *tau - g2_generator.mul_bigint(r_b_challenges[i].into_bigint()),
-            );
-        }
-
-        // r_b check
-        if b_lhs != b_rhs {
-            return false;
-        }
+        self.verify_committed_inputs(proof, public, &mut oracle, transcript_domain)
+    }
+}
 
-        //=========================================================================================
-        // KZG Verification Process
-        // Verify opening at r_c
-        // pairing(g1_(f(τ) - v), g2_1) == pairing(Σ(g1_Q(τ), g2_(τ - a)))
-        //=========================================================================================
-        let c_lhs = P::pairing(
-            proof.commitment - g1_generator.mul_bigint(input_eval_c.into_bigint()),
-            g2_generator.mul_bigint(F::one().into_bigint()),
-        );
+// the layer checks of gkr::core read a succinct proof like a plain one without line polys
+impl<F: PrimeField, P: Pairing> GKRProofSource<F> for SuccinctGKRProof<F, P> {
+    fn layer_count(&self) -> usize {
+        self.p_proofs.len()
+    }
 
-        let mut c_rhs = PairingOutput::ZERO;
-        for (i, tau) in encrypted_basis_g2.iter().enumerate() {
-            c_rhs += P::pairing(
-                proof.quotient_evals_rc[i],
-                *tau - g2_generator.mul_bigint(r_c_challenges[i].into_bigint()),
-            );
-        }
+    fn w_i_eval_count(&self) -> usize {
+        self.w_i_evals.len()
+    }
 
-        // r_c check
-        if c_lhs != c_rhs {
-            return false;
-        }
+    fn line_poly_count(&self) -> usize {
+        0
+    }
 
-        //=========================================================================================
-        // Input layer is verified, now perform the GKR oracle check
-        // f(b, c) = [add_i(b, c) * (w_i+1(b) + w_i+1(c))] + [mul_i(b,c) * (w_i+1(b) * w_i+1(c))]
-        //=========================================================================================
-        transcript.absorb(&MultiLinearPoly::to_bytes(&[input_eval_b, input_eval_c]));
+    fn output_layer(&self) -> Option<Cow<'_, [F]>> {
+        Some(Cow::Borrowed(&self.output_layer))
+    }
 
-        let input_w_sum = input_eval_b + input_eval_c;
-        let input_w_mul = input_eval_b * input_eval_c;
+    fn w_i_evals(&self, i: usize) -> Option<(F, F)> {
+        self.w_i_evals.get(i).copied()
+    }
 
-        (new_add, new_mul) = self.gkr_trick(&last_challenges, circuit_len - last_idx);
-        let new_add_eval = new_add.evaluate(&curr_challenges).computation[0];
-        let new_mul_eval = new_mul.evaluate(&curr_challenges).computation[0];
+    fn sum_check(&self, i: usize) -> Option<Cow<'_, Proof<F>>> {
+        self.p_proofs.get(i).map(Cow::Borrowed)
+    }
 
-        let oracle_check = (new_add_eval * input_w_sum) + (new_mul_eval * input_w_mul);
+    fn init_claimed_sum(&self, i: usize) -> Option<F> {
+        self.p_proofs.get(i).map(|p_proof| p_proof.init_claimed_sum)
+    }
 
-        oracle_check == current_claimed_sum
+    fn line_poly(&self, _: usize) -> Option<Cow<'_, [F]>> {
+        None
     }
 }
 
-#[cfg(test)]
+// the circuit and the setup go in before the statement, then the commitment, same on both sides
+pub(crate) fn statement_transcript<F: PrimeField>(
+    transcript_domain: &TranscriptDomain,
+    (circuit_digest, setup_digest): (&[u8; DIGEST_LEN], &[u8; DIGEST_LEN]),
+    public: &PublicInputs<F>,
+    commitment: &impl InputCommitment,
+) -> Transcript {
+    let mut transcript = transcript_domain
+        .transcript()
+        .with_schedule(&core::SUCCINCT_GKR_SCHEDULE);
+    transcript
+        .follow(Step::Absorb("circuit digest"))
+        .absorb_digest(DigestTag::Circuit, circuit_digest);
+    transcript
+        .follow(Step::Absorb("setup digest"))
+        .absorb_digest(DigestTag::Setup, setup_digest);
+    public.absorb_into(transcript.follow(Step::Absorb("outputs")));
+    commitment.absorb_into(&mut transcript);
+
+    transcript
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
 mod test {
+    use super::{ProofShapeError, SuccinctGKRProof};
     use crate::{
-        gkr::gkr_circuit::{Circuit, Gate, GateOp, Layer},
-        kzg::trusted_setup::tests::setup,
+        gkr::{
+            gkr_circuit::{Circuit, GateOp},
+            public_inputs::PublicInputs,
+            test_circuits,
+        },
+        kzg::trusted_setup::{
+            tests::{setup, TEST_SEED},
+            TrustedSetup,
+        },
+        transcript::test::domain,
     };
 
     use field_tracker::{print_summary, Ft};
@@ -390,78 +448,11 @@ mod test {
     use ark_bls12_381::Bls12_381;
 
     pub fn setup_test_circuit_s() -> Circuit<BlsFr> {
-        let inputs = vec![
-            BlsFr::from(1),
-            BlsFr::from(2),
-            BlsFr::from(3),
-            BlsFr::from(4),
-            BlsFr::from(5),
-            BlsFr::from(6),
-            BlsFr::from(7),
-            BlsFr::from(8),
-        ];
-        let mut circuit = Circuit::new(inputs);
-
-        let layer_1 = Layer {
-            gates: vec![
-                Gate {
-                    left: 0,
-                    right: 1,
-                    op: GateOp::Add,
-                    output: 0,
-                },
-                Gate {
-                    left: 2,
-                    right: 3,
-                    op: GateOp::Mul,
-                    output: 1,
-                },
-                Gate {
-                    left: 4,
-                    right: 5,
-                    op: GateOp::Mul,
-                    output: 2,
-                },
-                Gate {
-                    left: 6,
-                    right: 7,
-                    op: GateOp::Mul,
-                    output: 3,
-                },
-            ],
-        };
-
-        let layer_2 = Layer {
-            gates: vec![
-                Gate {
-                    left: 0,
-                    right: 1,
-                    op: GateOp::Add,
-                    output: 0,
-                },
-                Gate {
-                    left: 2,
-                    right: 3,
-                    op: GateOp::Mul,
-                    output: 1,
-                },
-            ],
-        };
-
-        let layer_3 = Layer {
-            gates: vec![Gate {
-                left: 0,
-                right: 1,
-                op: GateOp::Add,
-                output: 0,
-            }],
-        };
-
-        circuit.add_layer(layer_1);
-        circuit.add_layer(layer_2);
-        circuit.add_layer(layer_3);
+        test_circuits::circuit8()
+    }
 
-        circuit
+    fn outputs(circuit: &Circuit<BlsFr>) -> PublicInputs<BlsFr> {
+        PublicInputs::outputs_only(circuit.public_inputs().outputs)
     }
 
     #[test]
@@ -469,10 +460,10 @@ mod test {
         let circuit = setup_test_circuit_s();
         let setup = setup();
 
-        // let result = circuit.succinct_proof::<Bls12_381>(&setup.g1_arr);
+        // let result = circuit.succinct_proof::<Bls12_381>(&setup);
         // dbg!(&result);
 
-        circuit.succinct_proof::<Bls12_381>(&setup.g1_arr);
+        circuit.succinct_proof::<Bls12_381>(&setup, &domain());
         print_summary!();
     }
 
@@ -481,10 +472,227 @@ mod test {
         let circuit = setup_test_circuit_s();
         let setup = setup();
 
-        let proof = circuit.succinct_proof::<Bls12_381>(&setup.g1_arr);
-        let result = circuit.succinct_verify::<Bls12_381>(&proof, &setup.g2_arr);
+        let proof = circuit.succinct_proof::<Bls12_381>(&setup, &domain());
+        let result = circuit.succinct_verify::<Bls12_381>(
+            &proof,
+            &outputs(&circuit),
+            &setup.verifier_setup,
+            &domain(),
+        );
 
         assert!(&result);
         print_summary!();
     }
+
+    #[test]
+    fn test_succinct_calldata() {
+        use crate::calldata::{point_word_count, uint_word, WORD_LEN};
+        type G1 = <Bls12_381 as ark_ec::pairing::Pairing>::G1;
+
+        let circuit = setup_test_circuit_s();
+        let setup = setup();
+        let proof = circuit.succinct_proof::<Bls12_381>(&setup, &domain());
+        let calldata = proof.to_calldata();
+
+        // 2 digests and 11 array offsets, then every array's length and elements
+        let head_words = 13;
+        assert_eq!(calldata[..WORD_LEN], proof.circuit_digest);
+        assert_eq!(calldata[WORD_LEN..2 * WORD_LEN], proof.setup_digest);
+        assert_eq!(
+            calldata[2 * WORD_LEN..3 * WORD_LEN],
+            uint_word((head_words * WORD_LEN) as u64)
+        );
+
+        let sum_check_words: usize = proof
+            .p_proofs
+            .iter()
+            .map(|p_proof| {
+                4 + p_proof.round_polys.iter().map(Vec::len).sum::<usize>()
+                    + p_proof.challenges.len()
+            })
+            .sum();
+        let opening_words: usize = proof
+            .input_openings
+            .iter()
+            .map(|opening| {
+                opening.point.len()
+                    + 1
+                    + opening.quotients.segment_evals.len()
+                    + opening.quotients.quotient_evals.len() * point_word_count::<G1>()
+            })
+            .sum();
+        let words = head_words
+            + 11
+            + proof.output_layer.len()
+            + 2 * proof.w_i_evals.len()
+            + sum_check_words
+            + proof.commitment.segment_commitments.len() * point_word_count::<G1>()
+            + opening_words;
+        assert_eq!(calldata.len(), words * WORD_LEN);
+    }
+
+    #[test]
+    fn test_succinct_verify_rejects_other_statement() {
+        let circuit = setup_test_circuit_s();
+        let setup = setup();
+        let proof = circuit.succinct_proof::<Bls12_381>(&setup, &domain());
+
+        let mut public = outputs(&circuit);
+        public.outputs[0] += BlsFr::from(1);
+        assert!(!circuit.succinct_verify::<Bls12_381>(
+            &proof,
+            &public,
+            &setup.verifier_setup,
+            &domain()
+        ));
+
+        // the inputs are committed, revealing them in the statement is not accepted
+        assert!(!circuit.succinct_verify::<Bls12_381>(
+            &proof,
+            &circuit.public_inputs(),
+            &setup.verifier_setup,
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_succinct_verify_rejects_other_circuit_and_setup() {
+        let circuit = setup_test_circuit_s();
+        let setup = setup();
+        let proof = circuit.succinct_proof::<Bls12_381>(&setup, &domain());
+
+        // same dimensions, one gate swapped
+        let other_circuit = test_circuits::binary_tree_circuit(
+            (1..=8u64).map(BlsFr::from).collect(),
+            &[
+                &[GateOp::Mul, GateOp::Mul, GateOp::Mul, GateOp::Mul],
+                &[GateOp::Add, GateOp::Mul],
+                &[GateOp::Add],
+            ],
+        );
+        assert_eq!(
+            other_circuit.check_succinct_shape(&proof, &setup.verifier_setup),
+            Err(ProofShapeError::CircuitMismatch)
+        );
+        assert!(!other_circuit.succinct_verify::<Bls12_381>(
+            &proof,
+            &outputs(&other_circuit),
+            &setup.verifier_setup,
+            &domain()
+        ));
+
+        // same size, another tau
+        let other_setup = TrustedSetup::<Bls12_381>::insecure_from_seed(TEST_SEED + 1, 3);
+        assert_eq!(
+            circuit.check_succinct_shape(&proof, &other_setup.verifier_setup),
+            Err(ProofShapeError::SetupMismatch)
+        );
+        assert!(!circuit.succinct_verify::<Bls12_381>(
+            &proof,
+            &outputs(&circuit),
+            &other_setup.verifier_setup,
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_succinct_verify_checks_opening_points() {
+        let circuit = setup_test_circuit_s();
+        let setup = setup();
+        let proof = circuit.succinct_proof::<Bls12_381>(&setup, &domain());
+
+        // the openings carry r_b and r_c, together they are the last sum-check point
+        let last_point = &proof.p_proofs.last().unwrap().challenges;
+        assert_eq!(proof.input_openings.len(), 2);
+        assert_eq!(
+            [
+                proof.input_openings[0].point.clone(),
+                proof.input_openings[1].point.clone()
+            ]
+            .concat(),
+            *last_point
+        );
+
+        // an opening at a point of the prover's choosing is rejected
+        let mut tampered = proof;
+        tampered.input_openings[0].point[0] += BlsFr::from(1);
+        assert!(!circuit.succinct_verify::<Bls12_381>(
+            &tampered,
+            &outputs(&circuit),
+            &setup.verifier_setup,
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_succinct_shape_errors() {
+        let circuit = setup_test_circuit_s();
+        let setup = setup();
+        let proof = circuit.succinct_proof::<Bls12_381>(&setup, &domain());
+        let vk = &setup.verifier_setup;
+        assert_eq!(circuit.check_succinct_shape(&proof, vk), Ok(()));
+
+        let reject = |proof: &SuccinctGKRProof<BlsFr, Bls12_381>| {
+            !circuit.succinct_verify::<Bls12_381>(proof, &outputs(&circuit), vk, &domain())
+        };
+
+        let mut tampered = proof.clone();
+        tampered.w_i_evals.pop();
+        assert_eq!(
+            circuit.check_succinct_shape(&tampered, vk),
+            Err(ProofShapeError::EvalCount {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert!(reject(&tampered));
+
+        let mut tampered = proof.clone();
+        tampered.p_proofs[1].round_polys.pop();
+        assert!(matches!(
+            circuit.check_succinct_shape(&tampered, vk),
+            Err(ProofShapeError::RoundCount { index: 1, .. })
+        ));
+        assert!(reject(&tampered));
+
+        let mut tampered = proof.clone();
+        tampered.input_openings[1].quotients.quotient_evals.pop();
+        assert_eq!(
+            circuit.check_succinct_shape(&tampered, vk),
+            Err(ProofShapeError::OpeningShape { index: 1 })
+        );
+        assert!(reject(&tampered));
+
+        let mut tampered = proof;
+        tampered.output_layer.push(BlsFr::from(0));
+        assert_eq!(
+            circuit.check_succinct_shape(&tampered, vk),
+            Err(ProofShapeError::OutputNotPowerOfTwo { found: 3 })
+        );
+        assert!(reject(&tampered));
+    }
+
+    #[test]
+    fn test_gkr_inputs_larger_than_setup() {
+        let circuit = setup_test_circuit_s();
+        let setup = setup().trim(1); // 8 inputs against a 2 point setup => 4 segments
+
+        let proof = circuit.succinct_proof::<Bls12_381>(&setup, &domain());
+        assert_eq!(proof.commitment.num_segments(), 4);
+        assert!(circuit.succinct_verify::<Bls12_381>(
+            &proof,
+            &outputs(&circuit),
+            &setup.verifier_setup,
+            &domain()
+        ));
+
+        let mut tampered = proof;
+        tampered.input_openings[0].quotients.segment_evals[0] += BlsFr::from(1);
+        assert!(!circuit.succinct_verify::<Bls12_381>(
+            &tampered,
+            &outputs(&circuit),
+            &setup.verifier_setup,
+            &domain()
+        ));
+    }
 }
@@ -0,0 +1,157 @@
+use crate::gkr::gkr_circuit::{Circuit, Gate, GateOp, Layer, WireId};
+use ark_ff::PrimeField;
+use rand::Rng;
+
+//=========================================================================================
+// Circuit generators for tests and benchmarks
+// every layer has half the gates of the layer below (the shape the GKR provers expect),
+// so width is the number of inputs and depth can be at most log2(width)
+// GateMix::Random            => random wiring, add and mul equally likely
+// GateMix::MultiplicationHeavy => random wiring, 3 out of 4 gates are mul
+// GateMix::BinaryTree        => gate g reads wires 2g and 2g + 1 and adds them (a sum reduction)
+//=========================================================================================
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GateMix {
+    Random,
+    MultiplicationHeavy,
+    BinaryTree,
+}
+
+pub fn generate_circuit<F: PrimeField, R: Rng>(
+    width: usize,
+    depth: usize,
+    mix: GateMix,
+    rng: &mut R,
+) -> Circuit<F> {
+    if !width.is_power_of_two() || width < 2 {
+        panic!("The circuit width must be a power of 2 greater than 1");
+    }
+    if depth == 0 || depth > width.ilog2() as usize {
+        panic!("The circuit depth must be between 1 and log2(width)");
+    }
+
+    let inputs = (0..width).map(|_| F::rand(rng)).collect();
+    let mut circuit = Circuit::new(inputs);
+
+    let mut layer_width = width;
+    for _ in 0..depth {
+        let gates = (0..layer_width / 2)
+            .map(|output| match mix {
+                GateMix::BinaryTree => Gate {
+                    left: WireId(2 * output),
+                    right: WireId(2 * output + 1),
+                    op: GateOp::Add,
+                    output: WireId(output),
+                },
+                GateMix::Random | GateMix::MultiplicationHeavy => {
+                    let mul_weight = if mix == GateMix::Random { 2 } else { 3 };
+                    let op = if rng.gen_range(0..4) < mul_weight {
+                        GateOp::Mul
+                    } else {
+                        GateOp::Add
+                    };
+
+                    Gate {
+                        left: WireId(rng.gen_range(0..layer_width)),
+                        right: WireId(rng.gen_range(0..layer_width)),
+                        op,
+                        output: WireId(output),
+                    }
+                }
+            })
+            .collect();
+
+        circuit.add_layer(Layer { gates });
+        layer_width /= 2;
+    }
+
+    circuit
+}
+
+// gate g of every layer reads wires 2g and 2g + 1 with the given op
+pub fn binary_tree_circuit<F: PrimeField>(inputs: Vec<F>, layer_ops: &[&[GateOp]]) -> Circuit<F> {
+    let mut circuit = Circuit::new(inputs);
+
+    for ops in layer_ops.iter() {
+        let gates = ops
+            .iter()
+            .enumerate()
+            .map(|(output, op)| Gate {
+                left: WireId(2 * output),
+                right: WireId(2 * output + 1),
+                op: *op,
+                output: WireId(output),
+            })
+            .collect();
+
+        circuit.add_layer(Layer { gates });
+    }
+
+    circuit
+}
+
+// the 8 input fixture used across the GKR tests
+// ((1 + 2) + (3 * 4)) + ((5 * 6) * (7 * 8)) = 1695
+pub fn circuit8<F: PrimeField>() -> Circuit<F> {
+    binary_tree_circuit(
+        (1..=8u64).map(F::from).collect(),
+        &[
+            &[GateOp::Add, GateOp::Mul, GateOp::Mul, GateOp::Mul],
+            &[GateOp::Add, GateOp::Mul],
+            &[GateOp::Add],
+        ],
+    )
+}
+
+#[cfg(all(test, feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::gkr::interactive_gkr::{fiat_shamir_proof, fiat_shamir_verify, run_interactive};
+    use crate::transcript::test::domain;
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_circuit8_output() {
+        let circuit = circuit8::<Fq>();
+
+        assert_eq!(circuit.evaluate().last().unwrap(), &vec![Fq::from(1695)]);
+    }
+
+    #[test]
+    fn test_binary_tree_sums_inputs() {
+        let circuit =
+            generate_circuit::<Fq, _>(16, 4, GateMix::BinaryTree, &mut rand::thread_rng());
+        let sum: Fq = circuit.inputs.iter().sum();
+
+        assert_eq!(circuit.evaluate().last().unwrap(), &vec![sum]);
+    }
+
+    #[test]
+    fn test_generated_circuits_prove() {
+        let mut rng = rand::thread_rng();
+
+        for mix in [
+            GateMix::Random,
+            GateMix::MultiplicationHeavy,
+            GateMix::BinaryTree,
+        ] {
+            for (width, depth) in [(8, 3), (16, 2), (16, 4)] {
+                let circuit = generate_circuit::<Fq, _>(width, depth, mix, &mut rng);
+                assert_eq!(circuit.layers.len(), depth);
+
+                assert!(run_interactive(&circuit, rand::thread_rng()));
+                assert!(fiat_shamir_verify(
+                    &circuit,
+                    &fiat_shamir_proof(&circuit, &domain()),
+                    &domain()
+                ));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The circuit depth must be between 1 and log2(width)")]
+    fn test_generate_circuit_too_deep() {
+        generate_circuit::<Fq, _>(8, 4, GateMix::Random, &mut rand::thread_rng());
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    gkr::gkr_circuit::Circuit,
+    proof_format::{ensure_consumed, read_nested_vec, DecodeLimits, ProofFormatError},
+    transcript::{public_digest, DIGEST_LEN},
+};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use std::fmt;
+
+//=========================================================================================
+// An evaluated circuit saved to bytes so the evaluation is done once and proved many times
+// the trace is tied to the circuit by a digest of its wiring (every gate of every layer),
+// the inputs are layer 0 of the trace so they are compared separately when it is loaded
+// byte layout: circuit digest (32 bytes) || layers (ark compressed Vec<Vec<F>>)
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvaluatedTrace<F: PrimeField> {
+    pub circuit_digest: [u8; DIGEST_LEN],
+    pub layers: Vec<Vec<F>>, // same as Circuit::evaluate, layers[0] are the inputs
+}
+
+#[derive(Debug)]
+pub enum TraceError {
+    CircuitMismatch,
+    InputMismatch,
+    ShapeMismatch { layer: usize },
+    Format(ProofFormatError),
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceError::CircuitMismatch => write!(f, "the trace was evaluated for another circuit"),
+            TraceError::InputMismatch => {
+                write!(f, "the trace was evaluated on other inputs")
+            }
+            TraceError::ShapeMismatch { layer } => {
+                write!(
+                    f,
+                    "layer {} of the trace has the wrong number of values",
+                    layer
+                )
+            }
+            TraceError::Format(err) => write!(f, "malformed trace: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+impl From<ProofFormatError> for TraceError {
+    fn from(err: ProofFormatError) -> Self {
+        TraceError::Format(err)
+    }
+}
+
+impl<F: PrimeField> Circuit<F> {
+    // digest of the wiring only, two circuits with the same gates but other inputs share it
+    pub fn digest(&self) -> [u8; DIGEST_LEN] {
+        public_digest(&self.wiring_bytes())
+    }
+
+    // input count, layer count, then per layer its gate count and every gate as
+    // op (1 byte) || left || right || output, all counts and wires u64 big-endian
+    pub fn wiring_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(self.inputs.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(&(self.layers.len() as u64).to_be_bytes());
+
+        for layer in self.layers.iter() {
+            bytes.extend_from_slice(&(layer.gates.len() as u64).to_be_bytes());
+            for gate in layer.gates.iter() {
+                bytes.push(gate.op.to_byte());
+                for wire in [gate.left, gate.right, gate.output] {
+                    bytes.extend_from_slice(&(wire.index() as u64).to_be_bytes());
+                }
+            }
+        }
+
+        bytes
+    }
+
+    pub fn export_trace(&self) -> EvaluatedTrace<F> {
+        EvaluatedTrace {
+            circuit_digest: self.digest(),
+            layers: self.evaluate(),
+        }
+    }
+
+    // Ok if the trace is this circuit evaluated on self.inputs (the values themselves are trusted)
+    pub fn check_trace(&self, trace: &EvaluatedTrace<F>) -> Result<(), TraceError> {
+        if trace.circuit_digest != self.digest() {
+            return Err(TraceError::CircuitMismatch);
+        }
+        if trace.layers.first() != Some(&self.inputs) {
+            return Err(TraceError::InputMismatch);
+        }
+        if trace.layers.len() != self.layers.len() + 1 {
+            return Err(TraceError::ShapeMismatch {
+                layer: trace.layers.len().min(self.layers.len() + 1),
+            });
+        }
+        for (i, layer) in self.layers.iter().enumerate() {
+            if trace.layers[i + 1].len() != layer.gates.len() {
+                return Err(TraceError::ShapeMismatch { layer: i + 1 });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: PrimeField> EvaluatedTrace<F> {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofFormatError> {
+        let mut bytes = self.circuit_digest.to_vec();
+        self.layers.serialize_compressed(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    pub fn from_bytes_with_limits(
+        bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        limits.check_size(bytes)?;
+        if bytes.len() < DIGEST_LEN {
+            return Err(ProofFormatError::MissingHeader);
+        }
+        let (digest, mut bytes) = bytes.split_at(DIGEST_LEN);
+
+        let layers = read_nested_vec::<F>(&mut bytes, limits)?;
+        ensure_consumed(bytes)?;
+
+        Ok(EvaluatedTrace {
+            circuit_digest: digest.try_into().unwrap(),
+            layers,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::gkr::{
+        gkr_circuit::{test::setup_test_circuit8, GateOp},
+        test_circuits::binary_tree_circuit,
+    };
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_trace_roundtrip() {
+        let circuit = setup_test_circuit8();
+        let trace = circuit.export_trace();
+
+        let decoded = EvaluatedTrace::<Fq>::from_bytes(&trace.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded, trace);
+        assert!(circuit.check_trace(&decoded).is_ok());
+    }
+
+    #[test]
+    fn test_trace_rejects_other_circuit() {
+        let circuit = setup_test_circuit8();
+        let trace = circuit.export_trace();
+
+        let mut other_circuit = setup_test_circuit8();
+        other_circuit.layers[0].gates[0].op = GateOp::Mul;
+        assert!(matches!(
+            other_circuit.check_trace(&trace),
+            Err(TraceError::CircuitMismatch)
+        ));
+
+        let other_inputs = binary_tree_circuit(
+            (2..=9u64).map(Fq::from).collect(),
+            &[
+                &[GateOp::Add, GateOp::Mul, GateOp::Mul, GateOp::Mul],
+                &[GateOp::Add, GateOp::Mul],
+                &[GateOp::Add],
+            ],
+        );
+        assert_eq!(other_inputs.digest(), circuit.digest());
+        assert!(matches!(
+            other_inputs.check_trace(&trace),
+            Err(TraceError::InputMismatch)
+        ));
+
+        let mut short_trace = trace;
+        short_trace.layers[2].pop();
+        assert!(matches!(
+            circuit.check_trace(&short_trace),
+            Err(TraceError::ShapeMismatch { layer: 2 })
+        ));
+    }
+}
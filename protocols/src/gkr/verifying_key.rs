@@ -0,0 +1,243 @@
+use crate::{
+    gkr::{
+        gkr_circuit::{Circuit, Gate, GateOp, Layer, WireId},
+        gkr_protocol::GKRProof,
+        public_inputs::PublicInputs,
+    },
+    proof_format::{ensure_consumed, read_vec, DecodeLimits, ProofFormatError},
+    transcript::{TranscriptDomain, DIGEST_LEN},
+};
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+
+//=========================================================================================
+// Everything a GKR verifier needs next to the proof, as one blob of bytes
+// a verifier outside of Rust (see ffi) can't build a Circuit, so the wiring travels as bytes
+// together with the statement and the transcript domain the proof was made under
+// byte layout: wiring (Circuit::wiring_bytes) || public inputs (PublicInputs::to_bytes)
+//              || domain context (ark Vec<u8>) || domain public digest (32 bytes)
+// decoding checks every wire against the layer it points into, so a decoded key never makes
+// the verifier index out of bounds
+//=========================================================================================
+pub struct VerifyingKey<F: PrimeField> {
+    pub circuit: Circuit<F>, // inputs are set to public.inputs
+    pub public: PublicInputs<F>,
+    pub domain: TranscriptDomain,
+}
+
+impl<F: PrimeField> VerifyingKey<F> {
+    pub fn new(circuit: &Circuit<F>, public: PublicInputs<F>, domain: TranscriptDomain) -> Self {
+        let mut circuit = circuit.clone();
+        circuit.inputs = public.inputs.clone();
+
+        VerifyingKey {
+            circuit,
+            public,
+            domain,
+        }
+    }
+
+    pub fn verify(&self, proof: &GKRProof<F>) -> bool {
+        self.circuit.verify(proof, &self.public, &self.domain)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.circuit.wiring_bytes();
+        bytes.extend_from_slice(&self.public.to_bytes());
+        self.domain
+            .context
+            .serialize_compressed(&mut bytes)
+            .unwrap();
+        bytes.extend_from_slice(&self.domain.public_digest);
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    pub fn from_bytes_with_limits(
+        mut bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        limits.check_size(bytes)?;
+        let mut circuit = read_wiring::<F>(&mut bytes, limits)?;
+
+        let inputs = read_vec::<F>(&mut bytes, limits)?;
+        let outputs = read_vec::<F>(&mut bytes, limits)?;
+        if inputs.len() != circuit.inputs.len() {
+            return Err(ProofFormatError::InconsistentLengths {
+                expected: circuit.inputs.len(),
+                found: inputs.len(),
+            });
+        }
+        if outputs.len() != circuit.output_len() {
+            return Err(ProofFormatError::InconsistentLengths {
+                expected: circuit.output_len(),
+                found: outputs.len(),
+            });
+        }
+
+        let context = read_vec::<u8>(&mut bytes, limits)?;
+        if bytes.len() < DIGEST_LEN {
+            return Err(ProofFormatError::LengthMismatch {
+                len: DIGEST_LEN as u64,
+                remaining: bytes.len(),
+            });
+        }
+        let (digest, rest) = bytes.split_at(DIGEST_LEN);
+        ensure_consumed(rest)?;
+
+        circuit.inputs = inputs.clone();
+        Ok(VerifyingKey {
+            circuit,
+            public: PublicInputs::new(inputs, outputs),
+            domain: TranscriptDomain::new(&context, digest.try_into().unwrap()),
+        })
+    }
+}
+
+fn read_u64(bytes: &mut &[u8]) -> Result<u64, ProofFormatError> {
+    if bytes.len() < 8 {
+        return Err(ProofFormatError::LengthMismatch {
+            len: 8,
+            remaining: bytes.len(),
+        });
+    }
+    let (value, rest) = bytes.split_at(8);
+    *bytes = rest;
+
+    Ok(u64::from_be_bytes(value.try_into().unwrap()))
+}
+
+// a count that is checked against the limits and against the bytes left, every counted item
+// takes at least item_len bytes
+fn read_count(
+    bytes: &mut &[u8],
+    limits: &DecodeLimits,
+    item_len: usize,
+) -> Result<usize, ProofFormatError> {
+    let count = read_u64(bytes)?;
+    if count > limits.max_len as u64 {
+        return Err(ProofFormatError::TooLarge {
+            len: count as usize,
+            max: limits.max_len,
+        });
+    }
+    if count.saturating_mul(item_len as u64) > bytes.len() as u64 {
+        return Err(ProofFormatError::LengthMismatch {
+            len: count,
+            remaining: bytes.len(),
+        });
+    }
+
+    Ok(count as usize)
+}
+
+// the inverse of Circuit::wiring_bytes, the inputs come back as zeros and without names
+fn read_wiring<F: PrimeField>(
+    bytes: &mut &[u8],
+    limits: &DecodeLimits,
+) -> Result<Circuit<F>, ProofFormatError> {
+    const GATE_LEN: usize = 1 + 3 * 8;
+
+    let num_inputs = read_count(bytes, limits, 0)?;
+    let num_layers = read_count(bytes, limits, 8)?;
+    if num_layers == 0 {
+        return Err(ProofFormatError::EmptyCircuit);
+    }
+
+    let mut circuit = Circuit::new(vec![F::zero(); num_inputs]);
+    let mut below = num_inputs; // wires of the layer the gates read from
+    for _ in 0..num_layers {
+        let num_gates = read_count(bytes, limits, GATE_LEN)?;
+        if num_gates == 0 {
+            return Err(ProofFormatError::EmptyCircuit);
+        }
+
+        let mut gates = Vec::with_capacity(num_gates);
+        for _ in 0..num_gates {
+            let op =
+                GateOp::from_byte(bytes[0]).ok_or(ProofFormatError::UnknownGateOp(bytes[0]))?;
+            *bytes = &bytes[1..];
+
+            let mut wire = |bound: usize| -> Result<WireId, ProofFormatError> {
+                let index = read_u64(bytes)?;
+                if index >= bound as u64 {
+                    return Err(ProofFormatError::WireOutOfRange {
+                        wire: index,
+                        len: bound,
+                    });
+                }
+                Ok(WireId(index as usize))
+            };
+            let left = wire(below)?;
+            let right = wire(below)?;
+            let output = wire(num_gates)?;
+
+            gates.push(Gate {
+                left,
+                right,
+                op,
+                output,
+            });
+        }
+
+        circuit.add_layer(Layer { gates });
+        below = num_gates;
+    }
+
+    Ok(circuit)
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{gkr::gkr_circuit::test::setup_test_circuit8, transcript::test::domain};
+    use ark_bn254::Fq;
+
+    fn verifying_key() -> VerifyingKey<Fq> {
+        let circuit = setup_test_circuit8();
+        VerifyingKey::new(&circuit, circuit.public_inputs(), domain())
+    }
+
+    #[test]
+    fn test_verifying_key_roundtrip() {
+        let circuit = setup_test_circuit8();
+        let proof = circuit.proof(&domain());
+
+        let key = VerifyingKey::<Fq>::from_bytes(&verifying_key().to_bytes()).unwrap();
+        assert_eq!(key.circuit.digest(), circuit.digest());
+        assert_eq!(key.public, circuit.public_inputs());
+        assert_eq!(key.domain, domain());
+        assert!(key.verify(&proof));
+    }
+
+    #[test]
+    fn test_verifying_key_rejects_bad_wiring() {
+        let bytes = verifying_key().to_bytes();
+
+        // the first gate of layer 1 starts after the input, layer and gate counts
+        let gate = 3 * 8;
+        let mut bad_op = bytes.clone();
+        bad_op[gate] = 7;
+        assert!(matches!(
+            VerifyingKey::<Fq>::from_bytes(&bad_op),
+            Err(ProofFormatError::UnknownGateOp(7))
+        ));
+
+        // its left wire pointing past the 8 inputs
+        let mut bad_wire = bytes.clone();
+        bad_wire[gate + 8] = 8;
+        assert!(matches!(
+            VerifyingKey::<Fq>::from_bytes(&bad_wire),
+            Err(ProofFormatError::WireOutOfRange { wire: 8, len: 8 })
+        ));
+
+        assert!(matches!(
+            VerifyingKey::<Fq>::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ProofFormatError::LengthMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod hyperplonk_protocol;
+pub mod plonkish;
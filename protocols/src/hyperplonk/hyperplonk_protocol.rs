@@ -0,0 +1,653 @@
+use crate::{
+    gkr::partial_sum_check::verify_round,
+    hyperplonk::plonkish::{PlonkishCircuit, NUM_WIRES},
+    kzg::{
+        batch_kzg::{batch_verify, BatchKZGProof},
+        kzg_helper_functions::compute_commitment,
+        trusted_setup::{TrustedSetup, VerifierSetup},
+    },
+    lagrange::LagrangeWeights,
+    multi_linear::MultiLinearPoly,
+    transcript::{Transcript, TranscriptDomain, DIGEST_LEN},
+    transcript_schedule::{Schedule, Step},
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+#[cfg(feature = "prover")]
+use {
+    crate::{
+        kzg::batch_kzg::batch_open_committed, multi_linear::EvaluationTable,
+        virtual_polynomial::VirtualPolynomial,
+    },
+    ark_ff::{batch_inversion, Zero},
+    std::sync::Arc,
+};
+
+//=========================================================================================
+// HyperPlonk: a plonkish circuit (hyperplonk::plonkish) proven over the boolean hypercube with
+// multilinear KZG, one sum-check and one batched opening
+//   1. commit the wires a, b, c and the lookup multiplicities m, squeeze β, γ, δ
+//   2. commit the inverses the two multiset checks need (logUp with committed inverses)
+//        permutation: h_id,k = 1/(w_k + β·id_k + γ), h_σ,k = 1/(w_k + β·σ_k + γ),
+//                     Σₓ Σₖ h_id,k - h_σ,k = 0 iff {(w, id)} = {(w, σ)} i.e. the copies hold
+//        lookup:      h_f = q_lookup/(δ - a), h_t = m/(δ - t), Σₓ h_f - h_t = 0 iff every looked
+//                     up a is in t
+//   3. squeeze r and α, every row constraint Zⱼ (the gate and the six h definitions) has to
+//      vanish on the whole cube, and the two sums have to be 0, all in one claim
+//        Σₓ eq(r, x)·Σⱼ αʲ·Zⱼ(x) + α⁹·S(x) = 0
+//      proven with a sum-check over a VirtualPolynomial of degree 4 (eq·q_M·a·b)
+//   4. at the sum-check point ρ the proof gives the 22 committed polys' values, the verifier adds
+//      eq(r, ρ), id_k(ρ) and pi(ρ) itself (each is O(num_vars) or O(num_public)), recomputes the
+//      composition and checks it against the last claim, then one batched KZG opening at ρ
+// every term of the composition is listed once in composition() and used by both sides
+// NOTE: this is the univariate-free counterpart of a PLONK, there is no univariate PLONK in the
+// crate to share the frontend with yet, plonkish is the frontend both would use
+// NOTE: nothing is hidden, the commitments and openings are binding only
+//=========================================================================================
+
+// the 22 committed polys in the order of the evaluations in the proof, then the 5 the verifier
+// computes itself
+const Q_L: usize = 0;
+const Q_R: usize = 1;
+const Q_M: usize = 2;
+const Q_O: usize = 3;
+const Q_C: usize = 4;
+const Q_LOOKUP: usize = 5;
+const SIGMA: usize = 6; // 6, 7, 8
+const TABLE: usize = 9;
+const NUM_PREPROCESSED: usize = 10;
+const WIRE: usize = 10; // 10, 11, 12
+const MULTIPLICITY: usize = 13;
+const H_ID: usize = 14; // 14, 15, 16
+const H_SIGMA: usize = 17; // 17, 18, 19
+const H_F: usize = 20;
+const H_T: usize = 21;
+const NUM_COMMITTED: usize = 22;
+const EQ: usize = 22;
+const ID: usize = 23; // 23, 24, 25
+const PI: usize = 26;
+#[cfg(feature = "prover")]
+const NUM_COLUMNS: usize = 27;
+
+const NUM_WITNESS: usize = 4; // a, b, c, m
+const NUM_HELPERS: usize = NUM_COMMITTED - NUM_PREPROCESSED - NUM_WITNESS;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyingKey<P: Pairing> {
+    pub num_vars: usize,
+    pub num_public: usize,
+    pub commitments: Vec<P::G1>, // the selectors, σ and the table
+    pub verifier_setup: VerifierSetup<P>,
+}
+
+#[derive(Debug)]
+pub struct ProvingKey<P: Pairing> {
+    pub circuit: PlonkishCircuit<P::ScalarField>,
+    pub preprocessed: Vec<MultiLinearPoly<P::ScalarField>>,
+    pub setup: TrustedSetup<P>,
+    pub vk: VerifyingKey<P>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HyperPlonkProof<P: Pairing> {
+    pub witness_commitments: Vec<P::G1>, // a, b, c, m
+    pub helper_commitments: Vec<P::G1>,  // h_id, h_σ, h_f, h_t
+    pub round_polys: Vec<Vec<P::ScalarField>>,
+    pub evaluations: Vec<P::ScalarField>, // the committed polys at the sum-check point
+    pub quotients: Vec<P::G1>,            // the batched opening
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Challenges<F: PrimeField> {
+    beta: F,
+    gamma: F,
+    delta: F,
+    alpha: F,
+}
+
+// Σⱼ αʲ·eq·Zⱼ + α⁹·S as (coefficient, columns) products
+fn composition<F: PrimeField>(ch: &Challenges<F>) -> Vec<(F, Vec<usize>)> {
+    let alpha: Vec<F> = std::iter::successors(Some(F::one()), |power| Some(*power * ch.alpha))
+        .take(10)
+        .collect();
+    let mut terms = vec![
+        (F::one(), vec![EQ, Q_L, WIRE]),
+        (F::one(), vec![EQ, Q_R, WIRE + 1]),
+        (F::one(), vec![EQ, Q_O, WIRE + 2]),
+        (F::one(), vec![EQ, Q_M, WIRE, WIRE + 1]),
+        (F::one(), vec![EQ, Q_C]),
+        (-F::one(), vec![EQ, PI]),
+    ];
+
+    // h·(w + β·s + γ) - 1 for s = id and s = σ
+    for k in 0..NUM_WIRES {
+        for (j, h, s) in [(1 + k, H_ID + k, ID + k), (4 + k, H_SIGMA + k, SIGMA + k)] {
+            terms.extend([
+                (alpha[j], vec![EQ, h, WIRE + k]),
+                (alpha[j] * ch.beta, vec![EQ, h, s]),
+                (alpha[j] * ch.gamma, vec![EQ, h]),
+                (-alpha[j], vec![EQ]),
+            ]);
+        }
+    }
+
+    // h_f·(δ - a) - q_lookup and h_t·(δ - t) - m
+    terms.extend([
+        (alpha[7] * ch.delta, vec![EQ, H_F]),
+        (-alpha[7], vec![EQ, H_F, WIRE]),
+        (-alpha[7], vec![EQ, Q_LOOKUP]),
+        (alpha[8] * ch.delta, vec![EQ, H_T]),
+        (-alpha[8], vec![EQ, H_T, TABLE]),
+        (-alpha[8], vec![EQ, MULTIPLICITY]),
+    ]);
+
+    // S = Σₖ h_id,k - h_σ,k + h_f - h_t
+    for k in 0..NUM_WIRES {
+        terms.push((alpha[9], vec![H_ID + k]));
+        terms.push((-alpha[9], vec![H_SIGMA + k]));
+    }
+    terms.push((alpha[9], vec![H_F]));
+    terms.push((-alpha[9], vec![H_T]));
+
+    terms
+}
+
+fn degree<F: PrimeField>(terms: &[(F, Vec<usize>)]) -> usize {
+    terms
+        .iter()
+        .map(|(_, columns)| columns.len())
+        .max()
+        .unwrap()
+}
+
+pub static HYPERPLONK_SCHEDULE: Schedule = Schedule {
+    protocol: "hyperplonk",
+    steps: &[
+        Step::Absorb("num_vars"),
+        Step::Absorb("num_public"),
+        Step::Repeat(
+            "preprocessed commitments",
+            &[Step::Absorb("preprocessed commitment")],
+        ),
+        Step::Absorb("public inputs"),
+        Step::Repeat("witness commitments", &[Step::Absorb("witness commitment")]),
+        Step::Squeeze("beta"),
+        Step::Squeeze("gamma"),
+        Step::Squeeze("delta"),
+        Step::Repeat("helper commitments", &[Step::Absorb("helper commitment")]),
+        Step::Repeat("eq point", &[Step::Squeeze("r")]),
+        Step::Squeeze("alpha"),
+        Step::Repeat("rounds", &[Step::Absorb("round poly"), Step::Squeeze("ρ")]),
+        Step::Squeeze("opening domain"),
+    ],
+};
+
+fn start_transcript<P: Pairing>(
+    vk: &VerifyingKey<P>,
+    public: &[P::ScalarField],
+    transcript_domain: &TranscriptDomain,
+) -> Transcript {
+    let mut transcript = transcript_domain
+        .transcript()
+        .with_schedule(&HYPERPLONK_SCHEDULE);
+    transcript
+        .follow(Step::Absorb("num_vars"))
+        .absorb(&(vk.num_vars as u64).to_be_bytes());
+    transcript
+        .follow(Step::Absorb("num_public"))
+        .absorb(&(vk.num_public as u64).to_be_bytes());
+    absorb_commitments::<P>(&mut transcript, "preprocessed commitment", &vk.commitments);
+    transcript
+        .follow(Step::Absorb("public inputs"))
+        .absorb(&MultiLinearPoly::to_bytes(public));
+
+    transcript
+}
+
+fn absorb_commitments<P: Pairing>(
+    transcript: &mut Transcript,
+    label: &'static str,
+    commitments: &[P::G1],
+) {
+    commitments.iter().for_each(|commitment| {
+        transcript
+            .follow(Step::Absorb(label))
+            .absorb_commitment(commitment)
+    });
+}
+
+fn squeeze_challenges<F: PrimeField>(transcript: &mut Transcript) -> (F, F, F) {
+    (
+        transcript.follow(Step::Squeeze("beta")).squeeze_field(),
+        transcript.follow(Step::Squeeze("gamma")).squeeze_field(),
+        transcript.follow(Step::Squeeze("delta")).squeeze_field(),
+    )
+}
+
+// r for eq(r, x), then α
+fn squeeze_batching<F: PrimeField>(transcript: &mut Transcript, num_vars: usize) -> (Vec<F>, F) {
+    let r = (0..num_vars)
+        .map(|_| transcript.follow(Step::Squeeze("r")).squeeze_field())
+        .collect();
+
+    (r, transcript.follow(Step::Squeeze("alpha")).squeeze_field())
+}
+
+fn round_challenge<F: PrimeField>(transcript: &mut Transcript, round_poly: &[F]) -> F {
+    transcript
+        .follow(Step::Absorb("round poly"))
+        .absorb(&MultiLinearPoly::to_bytes(round_poly));
+
+    transcript.follow(Step::Squeeze("ρ")).squeeze_field()
+}
+
+// the column as an MLE, σ and id positions as field elements
+fn positions<F: PrimeField>(positions: &[usize]) -> Vec<F> {
+    positions
+        .iter()
+        .map(|position| F::from(*position as u64))
+        .collect()
+}
+
+impl<P: Pairing> ProvingKey<P> {
+    // setup must have at least circuit.num_vars variables, it is trimmed to fit
+    pub fn new(circuit: PlonkishCircuit<P::ScalarField>, setup: &TrustedSetup<P>) -> Self {
+        let setup = setup.trim(circuit.num_vars);
+        let mut columns = vec![
+            circuit.q_l.clone(),
+            circuit.q_r.clone(),
+            circuit.q_m.clone(),
+            circuit.q_o.clone(),
+            circuit.q_c.clone(),
+            circuit.q_lookup.clone(),
+        ];
+        columns.extend(circuit.sigma.iter().map(|sigma| positions(sigma)));
+        columns.push(circuit.table.clone());
+
+        let preprocessed: Vec<MultiLinearPoly<P::ScalarField>> = columns
+            .iter()
+            .map(|column| MultiLinearPoly::new(column))
+            .collect();
+        let commitments = preprocessed
+            .iter()
+            .map(|poly| {
+                compute_commitment::<P::ScalarField, P>(poly, &setup.g1_arr)
+                    .expect("The setup is trimmed to the circuit")
+            })
+            .collect();
+
+        let vk = VerifyingKey {
+            num_vars: circuit.num_vars,
+            num_public: circuit.num_public,
+            commitments,
+            verifier_setup: setup.verifier_setup.clone(),
+        };
+
+        ProvingKey {
+            circuit,
+            preprocessed,
+            setup,
+            vk,
+        }
+    }
+
+    #[cfg(feature = "prover")]
+    fn commit(&self, columns: &[Vec<P::ScalarField>]) -> Vec<P::G1> {
+        columns
+            .iter()
+            .map(|column| {
+                compute_commitment::<P::ScalarField, P>(
+                    &MultiLinearPoly::new(column),
+                    &self.setup.g1_arr,
+                )
+                .expect("The setup is trimmed to the circuit")
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "prover")]
+pub fn prove<P: Pairing>(
+    pk: &ProvingKey<P>,
+    public: &[P::ScalarField],
+    private: &[P::ScalarField],
+    transcript_domain: &TranscriptDomain,
+) -> HyperPlonkProof<P> {
+    let circuit = &pk.circuit;
+    let witness = circuit.assign(public, private);
+    if !circuit.is_satisfied(&witness, public) {
+        panic!("The witness does not satisfy the circuit");
+    }
+    let n = circuit.num_rows();
+    let mut transcript = start_transcript(&pk.vk, public, transcript_domain);
+
+    // 1. wires and multiplicities
+    let mut multiplicities = vec![P::ScalarField::from(0u64); n];
+    for (value, selected) in witness.wires[0].iter().zip(circuit.q_lookup.iter()) {
+        if !selected.is_zero() {
+            let j = circuit.table.iter().position(|t| t == value).unwrap();
+            multiplicities[j] += P::ScalarField::from(1u64);
+        }
+    }
+    let mut witness_columns = witness.wires.to_vec();
+    witness_columns.push(multiplicities);
+    let witness_commitments = pk.commit(&witness_columns);
+    absorb_commitments::<P>(&mut transcript, "witness commitment", &witness_commitments);
+    let (beta, gamma, delta) = squeeze_challenges::<P::ScalarField>(&mut transcript);
+
+    // 2. the inverses
+    let ids: Vec<Vec<P::ScalarField>> = (0..NUM_WIRES)
+        .map(|k| positions(&(k * n..(k + 1) * n).collect::<Vec<usize>>()))
+        .collect();
+    let sigmas: Vec<Vec<P::ScalarField>> = circuit.sigma.iter().map(|s| positions(s)).collect();
+    let inverse = |mut values: Vec<P::ScalarField>, numerators: &[P::ScalarField]| {
+        batch_inversion(&mut values);
+        values
+            .iter()
+            .zip(numerators)
+            .map(|(value, numerator)| *value * numerator)
+            .collect::<Vec<P::ScalarField>>()
+    };
+    let ones = vec![P::ScalarField::from(1u64); n];
+    let mut helper_columns = Vec::with_capacity(NUM_HELPERS);
+    for s in [&ids, &sigmas] {
+        for (wire, s) in witness.wires.iter().zip(s.iter()) {
+            let denominators = wire
+                .iter()
+                .zip(s)
+                .map(|(w, s)| *w + beta * s + gamma)
+                .collect();
+            helper_columns.push(inverse(denominators, &ones));
+        }
+    }
+    let lookup_side = witness.wires[0].iter().map(|a| delta - a).collect();
+    helper_columns.push(inverse(lookup_side, &circuit.q_lookup));
+    let table_side = circuit.table.iter().map(|t| delta - t).collect();
+    helper_columns.push(inverse(table_side, &witness_columns[MULTIPLICITY - WIRE]));
+    let helper_commitments = pk.commit(&helper_columns);
+    absorb_commitments::<P>(&mut transcript, "helper commitment", &helper_commitments);
+
+    // 3. the one sum-check
+    let (r, alpha) = squeeze_batching::<P::ScalarField>(&mut transcript, circuit.num_vars);
+    let terms = composition(&Challenges {
+        beta,
+        gamma,
+        delta,
+        alpha,
+    });
+
+    let mut columns: Vec<Vec<P::ScalarField>> = pk
+        .preprocessed
+        .iter()
+        .map(|poly| poly.computation.clone())
+        .collect();
+    columns.extend(witness_columns);
+    columns.extend(helper_columns);
+    columns.push(EvaluationTable::new(&r).weights().to_vec());
+    columns.extend(ids);
+    columns.push(circuit.public_column(public));
+    debug_assert_eq!(columns.len(), NUM_COLUMNS);
+
+    let mles: Vec<Arc<MultiLinearPoly<P::ScalarField>>> = columns
+        .into_iter()
+        .map(|column| {
+            Arc::new(MultiLinearPoly {
+                computation: column,
+            })
+        })
+        .collect();
+    let committed: Vec<MultiLinearPoly<P::ScalarField>> = mles[..NUM_COMMITTED]
+        .iter()
+        .map(|mle| (**mle).clone())
+        .collect();
+    let mut poly = VirtualPolynomial::new(circuit.num_vars);
+    for (coefficient, product) in terms {
+        poly.add_product(
+            product.iter().map(|column| mles[*column].clone()),
+            coefficient,
+        );
+    }
+    drop(mles);
+
+    let mut round_polys = Vec::with_capacity(circuit.num_vars);
+    let mut point = Vec::with_capacity(circuit.num_vars);
+    for _ in 0..circuit.num_vars {
+        let round_poly = poly.round_evaluations();
+        let challenge = round_challenge(&mut transcript, &round_poly);
+        round_polys.push(round_poly);
+        point.push(challenge);
+        poly.fix_variable(challenge);
+    }
+
+    // 4. one batched opening at the sum-check point
+    let commitments = [
+        pk.vk.commitments.clone(),
+        witness_commitments.clone(),
+        helper_commitments.clone(),
+    ]
+    .concat();
+    let opening = batch_open_committed::<P::ScalarField, P>(
+        &committed,
+        commitments,
+        &pk.setup.g1_arr,
+        &point,
+        &opening_domain(&mut transcript),
+    );
+
+    HyperPlonkProof {
+        witness_commitments,
+        helper_commitments,
+        round_polys,
+        evaluations: opening.polys_opened,
+        quotients: opening.quotient_evals,
+    }
+}
+
+// the batched opening runs on its own transcript, started from a squeeze of the protocol's so
+// it is bound to everything before it
+fn opening_domain(transcript: &mut Transcript) -> TranscriptDomain {
+    let mut digest = [0u8; DIGEST_LEN];
+    digest.copy_from_slice(&transcript.follow(Step::Squeeze("opening domain")).squeeze());
+
+    TranscriptDomain::new(b"hyperplonk-opening", digest)
+}
+
+// eq(r, ρ) = ∏ rᵢρᵢ + (1 - rᵢ)(1 - ρᵢ)
+fn eq_eval<F: PrimeField>(r: &[F], point: &[F]) -> F {
+    r.iter()
+        .zip(point)
+        .map(|(r, x)| *r * x + (F::one() - r) * (F::one() - x))
+        .product()
+}
+
+// Σ 2^(num_vars - 1 - i)·ρᵢ, the MLE of the row index (most significant variable first)
+fn index_eval<F: PrimeField>(point: &[F]) -> F {
+    point.iter().fold(F::zero(), |acc, x| acc + acc + x)
+}
+
+// pi(ρ) from the first num_public rows only
+fn public_eval<F: PrimeField>(public: &[F], point: &[F]) -> F {
+    let num_vars = point.len();
+    public
+        .iter()
+        .enumerate()
+        .map(|(row, value)| {
+            let bits: Vec<F> = (0..num_vars)
+                .map(|i| F::from(((row >> (num_vars - 1 - i)) & 1) as u64))
+                .collect();
+            eq_eval(&bits, point) * value
+        })
+        .sum()
+}
+
+pub fn verify<P: Pairing>(
+    vk: &VerifyingKey<P>,
+    public: &[P::ScalarField],
+    proof: &HyperPlonkProof<P>,
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    let num_vars = vk.num_vars;
+    if public.len() != vk.num_public
+        || vk.commitments.len() != NUM_PREPROCESSED
+        || proof.witness_commitments.len() != NUM_WITNESS
+        || proof.helper_commitments.len() != NUM_HELPERS
+        || proof.round_polys.len() != num_vars
+        || proof.evaluations.len() != NUM_COMMITTED
+        || proof.quotients.len() != num_vars
+        || vk.verifier_setup.max_num_vars() != num_vars
+    {
+        return false;
+    }
+
+    let mut transcript = start_transcript(vk, public, transcript_domain);
+    absorb_commitments::<P>(
+        &mut transcript,
+        "witness commitment",
+        &proof.witness_commitments,
+    );
+    let (beta, gamma, delta) = squeeze_challenges::<P::ScalarField>(&mut transcript);
+    absorb_commitments::<P>(
+        &mut transcript,
+        "helper commitment",
+        &proof.helper_commitments,
+    );
+    let (r, alpha) = squeeze_batching::<P::ScalarField>(&mut transcript, num_vars);
+    let terms = composition(&Challenges {
+        beta,
+        gamma,
+        delta,
+        alpha,
+    });
+
+    // the sum-check of a claim of 0
+    let weights = LagrangeWeights::for_degree(degree(&terms));
+    let mut claim = P::ScalarField::from(0u64);
+    let mut point = Vec::with_capacity(num_vars);
+    for round_poly in proof.round_polys.iter() {
+        let challenge = round_challenge(&mut transcript, round_poly);
+        claim = match verify_round(claim, round_poly, &weights, challenge) {
+            Some(next) => next,
+            None => return false,
+        };
+        point.push(challenge);
+    }
+
+    // the composition at ρ from the opened values and the ones computed here
+    let n = P::ScalarField::from(1u64 << num_vars);
+    let index = index_eval(&point);
+    let mut values = proof.evaluations.clone();
+    values.push(eq_eval(&r, &point));
+    values.extend((0..NUM_WIRES).map(|k| P::ScalarField::from(k as u64) * n + index));
+    values.push(public_eval(public, &point));
+    let expected: P::ScalarField = terms
+        .iter()
+        .map(|(coefficient, product)| {
+            product
+                .iter()
+                .fold(*coefficient, |acc, column| acc * values[*column])
+        })
+        .sum();
+    if expected != claim {
+        return false;
+    }
+
+    let opening = BatchKZGProof::<P::ScalarField, P> {
+        commitments: [
+            vk.commitments.clone(),
+            proof.witness_commitments.clone(),
+            proof.helper_commitments.clone(),
+        ]
+        .concat(),
+        polys_opened: proof.evaluations.clone(),
+        quotient_evals: proof.quotients.clone(),
+    };
+
+    batch_verify(
+        &opening,
+        &vk.verifier_setup,
+        &point,
+        &opening_domain(&mut transcript),
+    )
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::{
+        hyperplonk::plonkish::{test::cubic, PlonkishBuilder},
+        kzg::trusted_setup::tests::setup_of_size,
+        transcript::test::domain,
+    };
+    use ark_bls12_381::{Bls12_381, Fr};
+
+    fn proving_key(circuit: PlonkishCircuit<Fr>) -> ProvingKey<Bls12_381> {
+        ProvingKey::new(circuit, &setup_of_size(4))
+    }
+
+    #[test]
+    fn test_hyperplonk() {
+        let pk = proving_key(cubic());
+        let public = [Fr::from(35)];
+        let proof = prove(&pk, &public, &[Fr::from(3)], &domain());
+        assert_eq!(proof.round_polys.len(), 3);
+        assert!(proof.round_polys.iter().all(|round| round.len() == 5));
+        assert!(verify(&pk.vk, &public, &proof, &domain()));
+
+        // another statement, another domain
+        assert!(!verify(&pk.vk, &[Fr::from(36)], &proof, &domain()));
+        let other = TranscriptDomain::new(b"other-app", [0u8; 32]);
+        assert!(!verify(&pk.vk, &public, &proof, &other));
+    }
+
+    #[test]
+    fn test_hyperplonk_rejects_tampering() {
+        let pk = proving_key(cubic());
+        let public = [Fr::from(15)];
+        let proof = prove(&pk, &public, &[Fr::from(2)], &domain());
+
+        let mut tampered = proof.clone();
+        tampered.evaluations[WIRE] += Fr::from(1);
+        assert!(!verify(&pk.vk, &public, &tampered, &domain()));
+
+        let mut tampered = proof.clone();
+        tampered.round_polys[1][0] += Fr::from(1);
+        assert!(!verify(&pk.vk, &public, &tampered, &domain()));
+
+        let mut tampered = proof.clone();
+        tampered.helper_commitments.swap(0, 1);
+        assert!(!verify(&pk.vk, &public, &tampered, &domain()));
+
+        let mut tampered = proof;
+        tampered.quotients.pop();
+        assert!(!verify(&pk.vk, &public, &tampered, &domain()));
+    }
+
+    #[test]
+    fn test_hyperplonk_other_circuit() {
+        // (x + y)·y = out, the same public output from another circuit doesn't verify
+        let mut builder = PlonkishBuilder::new();
+        let out = builder.public_input();
+        let (x, y) = (builder.private_input(), builder.private_input());
+        let sum = builder.add(x, y);
+        let product = builder.mul(sum, y);
+        builder.assert_equal(product, out);
+        let pk = proving_key(builder.build());
+
+        let public = [Fr::from(35)];
+        let proof = prove(&pk, &public, &[Fr::from(2), Fr::from(5)], &domain());
+        assert!(verify(&pk.vk, &public, &proof, &domain()));
+        assert!(!verify(
+            &proving_key(cubic()).vk,
+            &public,
+            &proof,
+            &domain()
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "The witness does not satisfy the circuit")]
+    fn test_value_outside_the_table() {
+        let pk = proving_key(cubic());
+        prove(&pk, &[Fr::from(135)], &[Fr::from(5)], &domain());
+    }
+}
@@ -0,0 +1,331 @@
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// Plonkish constraint system: 2^num_vars rows of three wires a, b, c and one gate per row
+//   q_L·a + q_R·b + q_O·c + q_M·a·b + q_C - pi = 0
+// pi is the public input of the row (only the first num_public rows have one, each is a
+// public input gate a = pi), copy constraints tie wire positions of the same variable together
+// through a permutation σ over the 3·2^num_vars positions (position k·n + i is wire k of row i),
+// and a row with q_lookup = 1 asks that its a is an entry of the table
+// PlonkishBuilder is the frontend: variables instead of positions, gates appended in order, and
+// the witness is filled in by replaying the gates (assign), unused rows are all zero
+//=========================================================================================
+pub const NUM_WIRES: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlonkishCircuit<F: PrimeField> {
+    pub num_vars: usize,
+    pub num_public: usize,
+    pub q_l: Vec<F>,
+    pub q_r: Vec<F>,
+    pub q_m: Vec<F>,
+    pub q_o: Vec<F>,
+    pub q_c: Vec<F>,
+    pub q_lookup: Vec<F>,
+    pub sigma: [Vec<usize>; NUM_WIRES], // σ(k·n + i) for the wire k of every row i
+    pub table: Vec<F>,                  // padded to n with its first entry
+    wires: [Vec<usize>; NUM_WIRES],     // the variable on every wire, for assign
+    gates: Vec<Gate<F>>,
+    num_variables: usize,
+    private: Vec<usize>,
+}
+
+// a, b, c of every row
+#[derive(Debug, Clone, PartialEq)]
+pub struct Witness<F: PrimeField> {
+    pub wires: [Vec<F>; NUM_WIRES],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Var(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Gate<F: PrimeField> {
+    PublicInput,
+    Add,
+    Mul,
+    Constant(F),
+    AssertEqual,
+    Lookup,
+}
+
+#[derive(Debug, Clone)]
+struct Row<F: PrimeField> {
+    gate: Gate<F>,
+    wires: [Var; NUM_WIRES],
+}
+
+#[derive(Debug, Clone)]
+pub struct PlonkishBuilder<F: PrimeField> {
+    rows: Vec<Row<F>>,
+    num_variables: usize,
+    num_public: usize,
+    private: Vec<Var>,
+    table: Vec<F>,
+}
+
+impl<F: PrimeField> Default for PlonkishBuilder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: PrimeField> PlonkishBuilder<F> {
+    pub fn new() -> Self {
+        // variable 0 fills the wires a gate does not read, its positions are not tied together
+        PlonkishBuilder {
+            rows: Vec::new(),
+            num_variables: 1,
+            num_public: 0,
+            private: Vec::new(),
+            table: Vec::new(),
+        }
+    }
+
+    fn var(&mut self) -> Var {
+        self.num_variables += 1;
+        Var(self.num_variables - 1)
+    }
+
+    fn row(&mut self, gate: Gate<F>, wires: [Var; NUM_WIRES]) {
+        self.rows.push(Row { gate, wires });
+    }
+
+    // public inputs come first, they are the first rows of the circuit
+    pub fn public_input(&mut self) -> Var {
+        if self.rows.len() != self.num_public {
+            panic!("The public inputs must be declared before any gate");
+        }
+
+        let var = self.var();
+        self.row(Gate::PublicInput, [var, Var(0), Var(0)]);
+        self.num_public += 1;
+        var
+    }
+
+    pub fn private_input(&mut self) -> Var {
+        let var = self.var();
+        self.private.push(var);
+        var
+    }
+
+    pub fn add(&mut self, a: Var, b: Var) -> Var {
+        let c = self.var();
+        self.row(Gate::Add, [a, b, c]);
+        c
+    }
+
+    pub fn mul(&mut self, a: Var, b: Var) -> Var {
+        let c = self.var();
+        self.row(Gate::Mul, [a, b, c]);
+        c
+    }
+
+    pub fn constant(&mut self, value: F) -> Var {
+        let a = self.var();
+        self.row(Gate::Constant(value), [a, Var(0), Var(0)]);
+        a
+    }
+
+    pub fn assert_equal(&mut self, a: Var, b: Var) {
+        self.row(Gate::AssertEqual, [a, b, Var(0)]);
+    }
+
+    // a must be one of the table entries
+    pub fn lookup(&mut self, a: Var) {
+        self.row(Gate::Lookup, [a, Var(0), Var(0)]);
+    }
+
+    pub fn set_table(&mut self, table: Vec<F>) {
+        self.table = table;
+    }
+
+    pub fn build(self) -> PlonkishCircuit<F> {
+        // the table is a column too, so it can make the circuit taller than its gates
+        let n = self
+            .rows
+            .len()
+            .max(self.table.len())
+            .max(2)
+            .next_power_of_two();
+        let selector = |f: &dyn Fn(&Gate<F>) -> F| -> Vec<F> {
+            let mut column: Vec<F> = self.rows.iter().map(|row| f(&row.gate)).collect();
+            column.resize(n, F::zero());
+            column
+        };
+
+        let q_l = selector(&|gate| match gate {
+            Gate::PublicInput | Gate::Add | Gate::Constant(_) | Gate::AssertEqual => F::one(),
+            _ => F::zero(),
+        });
+        let q_r = selector(&|gate| match gate {
+            Gate::Add => F::one(),
+            Gate::AssertEqual => -F::one(),
+            _ => F::zero(),
+        });
+        let q_m = selector(&|gate| match gate {
+            Gate::Mul => F::one(),
+            _ => F::zero(),
+        });
+        let q_o = selector(&|gate| match gate {
+            Gate::Add | Gate::Mul => -F::one(),
+            _ => F::zero(),
+        });
+        let q_c = selector(&|gate| match gate {
+            Gate::Constant(value) => -*value,
+            _ => F::zero(),
+        });
+        let q_lookup = selector(&|gate| match gate {
+            Gate::Lookup => F::one(),
+            _ => F::zero(),
+        });
+
+        // padding rows read variable 0 on every wire
+        let wires: [Vec<usize>; NUM_WIRES] = std::array::from_fn(|k| {
+            let mut column: Vec<usize> = self.rows.iter().map(|row| row.wires[k].0).collect();
+            column.resize(n, 0);
+            column
+        });
+
+        // every variable's positions form one cycle of σ
+        let mut positions: Vec<Vec<usize>> = vec![Vec::new(); self.num_variables];
+        for (k, column) in wires.iter().enumerate() {
+            for (i, var) in column.iter().enumerate() {
+                positions[*var].push(k * n + i);
+            }
+        }
+        let mut sigma_flat: Vec<usize> = (0..NUM_WIRES * n).collect();
+        for cycle in positions.iter().skip(1).filter(|cycle| cycle.len() > 1) {
+            for (j, position) in cycle.iter().enumerate() {
+                sigma_flat[*position] = cycle[(j + 1) % cycle.len()];
+            }
+        }
+        let sigma = std::array::from_fn(|k| sigma_flat[k * n..(k + 1) * n].to_vec());
+
+        let mut table = self.table.clone();
+        table.resize(n, table.first().copied().unwrap_or(F::zero()));
+
+        PlonkishCircuit {
+            num_vars: n.ilog2() as usize,
+            num_public: self.num_public,
+            q_l,
+            q_r,
+            q_m,
+            q_o,
+            q_c,
+            q_lookup,
+            sigma,
+            table,
+            wires,
+            gates: self.rows.iter().map(|row| row.gate).collect(),
+            num_variables: self.num_variables,
+            private: self.private.iter().map(|var| var.0).collect(),
+        }
+    }
+}
+
+impl<F: PrimeField> PlonkishCircuit<F> {
+    pub fn num_rows(&self) -> usize {
+        1 << self.num_vars
+    }
+
+    // public and private in the order they were declared, replaying the gates fills in the rest
+    pub fn assign(&self, public: &[F], private: &[F]) -> Witness<F> {
+        if public.len() != self.num_public || private.len() != self.private.len() {
+            panic!("The inputs don't match the circuit's public and private inputs");
+        }
+
+        let mut values = vec![F::zero(); self.num_variables];
+        for (var, value) in self.private.iter().zip(private) {
+            values[*var] = *value;
+        }
+
+        for (i, gate) in self.gates.iter().enumerate() {
+            let [a, b, c] = std::array::from_fn(|k| self.wires[k][i]);
+            match gate {
+                Gate::PublicInput => values[a] = public[i],
+                Gate::Constant(value) => values[a] = *value,
+                Gate::Add => values[c] = values[a] + values[b],
+                Gate::Mul => values[c] = values[a] * values[b],
+                Gate::AssertEqual | Gate::Lookup => {}
+            }
+        }
+
+        Witness {
+            wires: std::array::from_fn(|k| self.wires[k].iter().map(|var| values[*var]).collect()),
+        }
+    }
+
+    // pi as a column, the public inputs on the first rows and zero below
+    pub fn public_column(&self, public: &[F]) -> Vec<F> {
+        let mut column = public.to_vec();
+        column.resize(self.num_rows(), F::zero());
+        column
+    }
+
+    // gates, copy constraints and lookups checked row by row
+    pub fn is_satisfied(&self, witness: &Witness<F>, public: &[F]) -> bool {
+        let pi = self.public_column(public);
+        let [a, b, c] = &witness.wires;
+        let gates = (0..self.num_rows()).all(|i| {
+            self.q_l[i] * a[i]
+                + self.q_r[i] * b[i]
+                + self.q_o[i] * c[i]
+                + self.q_m[i] * a[i] * b[i]
+                + self.q_c[i]
+                - pi[i]
+                == F::zero()
+        });
+
+        let n = self.num_rows();
+        let wire = |position: usize| witness.wires[position / n][position % n];
+        let copies = (0..NUM_WIRES * n)
+            .all(|position| wire(position) == wire(self.sigma[position / n][position % n]));
+
+        let lookups = (0..n).all(|i| self.q_lookup[i].is_zero() || self.table.contains(&a[i]));
+
+        public.len() == self.num_public && gates && copies && lookups
+    }
+}
+
+#[cfg(all(test, feature = "bls12-381"))]
+pub(crate) mod test {
+    use super::*;
+    use ark_bls12_381::Fr;
+
+    // x³ + x + 5 = out with x private, out public, and x in {0, 1, 2, 3}
+    pub fn cubic() -> PlonkishCircuit<Fr> {
+        let mut builder = PlonkishBuilder::new();
+        let out = builder.public_input();
+        let x = builder.private_input();
+        let x_2 = builder.mul(x, x);
+        let x_3 = builder.mul(x_2, x);
+        let sum = builder.add(x_3, x);
+        let five = builder.constant(Fr::from(5));
+        let result = builder.add(sum, five);
+        builder.assert_equal(result, out);
+        builder.lookup(x);
+        builder.set_table([0, 1, 2, 3].map(Fr::from).to_vec());
+
+        builder.build()
+    }
+
+    #[test]
+    fn test_cubic_is_satisfied() {
+        let circuit = cubic();
+        assert_eq!(circuit.num_rows(), 8);
+
+        let witness = circuit.assign(&[Fr::from(35)], &[Fr::from(3)]);
+        assert!(circuit.is_satisfied(&witness, &[Fr::from(35)]));
+        assert!(!circuit.is_satisfied(&witness, &[Fr::from(36)]));
+
+        // satisfies the gates but x = 5 is not in the table
+        let witness = circuit.assign(&[Fr::from(135)], &[Fr::from(5)]);
+        assert!(!circuit.is_satisfied(&witness, &[Fr::from(135)]));
+
+        // a wire that breaks a copy constraint
+        let mut witness = circuit.assign(&[Fr::from(35)], &[Fr::from(3)]);
+        witness.wires[1][1] += Fr::from(1);
+        assert!(!circuit.is_satisfied(&witness, &[Fr::from(35)]));
+    }
+}
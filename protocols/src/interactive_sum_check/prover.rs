@@ -35,7 +35,7 @@ impl<F: PrimeField> ProverStruct<F> {
         let left_sum: F = left.iter().sum();
         let right_sum = right.iter().sum();
 
-        let sum_poly = MultiLinearPoly::new(&vec![left_sum, right_sum]);
+        let sum_poly = MultiLinearPoly::new(&[left_sum, right_sum]);
         self.proof.claimed_sums.push(claimed_sum);
         self.proof.sum_polys.push(sum_poly.clone());
 
@@ -1,20 +1,58 @@
-use crate::{interactive_sum_check::transcript::Transcript, multi_linear::MultiLinearPoly};
+use crate::{
+    fiat_shamir::PROVER_MSG_LABEL,
+    multi_linear::MultiLinearPoly,
+    sum_check,
+    transcript::{Transcript, TranscriptHasher},
+};
 use ark_ff::PrimeField;
+use sha3::Keccak256;
 
-pub struct VerifierStruct<F: PrimeField> {
-    pub bh_computation: MultiLinearPoly<F>,
+// the challenges come from the same labelled schedule as sum_check and
+// fiat_shamir_non_interactive, so a run here can be replayed against either of them
+// the verifier only needs f(r) at the end, the oracle answers it: new / with_hasher wrap the
+// hypercube values in one, with_oracle takes any closure (e.g. a PCS opening of a committed f)
+// together with the statement transcript that binds what the oracle stands for
+pub type Oracle<F> = Box<dyn Fn(&[F]) -> F>;
+
+pub struct VerifierStruct<F: PrimeField, H: TranscriptHasher = Keccak256> {
+    pub num_vars: usize,
+    pub oracle: Oracle<F>,
+    pub statement: Transcript<H>, // where initial_transcript_push starts over from
     pub challenges: Vec<F>,
     pub final_eval_poly: Vec<F>,
-    pub transcript: Transcript,
+    pub transcript: Transcript<H>,
 }
 
 impl<F: PrimeField> VerifierStruct<F> {
     pub fn new(bh_computation: Vec<F>) -> Self {
+        VerifierStruct::with_hasher(bh_computation)
+    }
+}
+
+impl<F: PrimeField, H: TranscriptHasher> VerifierStruct<F, H> {
+    pub fn with_hasher(bh_computation: Vec<F>) -> Self {
+        let poly = MultiLinearPoly::new(&bh_computation);
+        let claimed_sum: F = bh_computation.iter().sum();
+
+        VerifierStruct::with_oracle(
+            bh_computation.len().ilog2() as usize,
+            sum_check::statement_transcript(&bh_computation, claimed_sum),
+            move |point| poly.clone().evaluate(point).computation[0],
+        )
+    }
+
+    pub fn with_oracle(
+        num_vars: usize,
+        statement: Transcript<H>,
+        oracle: impl Fn(&[F]) -> F + 'static,
+    ) -> Self {
         VerifierStruct {
-            bh_computation: MultiLinearPoly::new(&bh_computation),
+            num_vars,
+            oracle: Box::new(oracle),
+            statement,
             challenges: Vec::new(),
             final_eval_poly: Vec::with_capacity(1),
-            transcript: Transcript::new(),
+            transcript: Transcript::with_hasher(),
         }
     }
 
@@ -28,7 +66,7 @@ impl<F: PrimeField> VerifierStruct<F> {
     // }
 
     pub fn variable_count(&self) -> u32 {
-        self.bh_computation.computation.len().ilog2()
+        self.num_vars as u32
     }
 
     pub fn check_proof(&mut self, proof_vec: Vec<(F, MultiLinearPoly<F>)>) -> bool {
@@ -38,25 +76,23 @@ impl<F: PrimeField> VerifierStruct<F> {
             }
             self.final_eval_poly = sum_poly.computation.clone();
 
-            self.transcript
-                .append(&VerifierStruct::convert_to_bytes(vec![claimed_sum]));
-            self.transcript.append(&VerifierStruct::convert_to_bytes(
-                sum_poly.computation.clone(),
-            ));
+            // the claimed sum is g(0) + g(1), only the round poly goes into the transcript
+            self.transcript.absorb_labeled(
+                PROVER_MSG_LABEL,
+                &MultiLinearPoly::to_bytes(&sum_poly.computation),
+            );
         }
 
         true
     }
 
+    // starts the transcript over from the statement (the hypercube values and their sum for new)
     pub fn initial_transcript_push(&mut self) {
-        self.transcript.append(&VerifierStruct::convert_to_bytes(
-            self.bh_computation.computation.clone(),
-        ));
+        self.transcript = self.statement.clone();
     }
 
     pub fn generate_challenge(&mut self) -> F {
-        let challenge_bytes = self.transcript.challenge();
-        let challenge = F::from_be_bytes_mod_order(&challenge_bytes);
+        let challenge = self.transcript.squeeze_field::<F>();
 
         self.challenges.push(challenge);
 
@@ -69,10 +105,11 @@ impl<F: PrimeField> VerifierStruct<F> {
         let final_eval_at_challenge =
             final_eval.partial_evaluate(self.challenges[self.challenges.len() - 1], 0);
 
-        let mut this_computation = self.bh_computation.clone();
-        let final_output = this_computation.evaluate(&self.challenges);
+        if self.challenges.len() != self.num_vars {
+            return false;
+        }
 
-        final_output.computation[0] == final_eval_at_challenge.computation[0]
+        (self.oracle)(&self.challenges) == final_eval_at_challenge.computation[0]
     }
 }
 
@@ -87,7 +124,7 @@ impl<F: PrimeField> VerifierStruct<F> {
    7 => Finally call the verify_proof method on the VerifierStruct instance
 */
 
-#[cfg(test)]
+#[cfg(all(test, feature = "bn254"))]
 mod test {
     use crate::interactive_sum_check::prover::ProverStruct;
 
@@ -150,4 +187,95 @@ mod test {
 
         assert!(verifier.verify_proof(), "Final verification failed");
     }
+
+    #[test]
+    fn test_verify_against_an_oracle() {
+        // the verifier never sees the values, only the statement and f at the final point
+        let statement: Transcript =
+            sum_check::statement_transcript(&bh_computation(), bh_computation().iter().sum());
+        let run = |oracle: Oracle<Fq>| {
+            let mut verifier = VerifierStruct::with_oracle(4, statement.clone(), oracle);
+            let mut prover = ProverStruct::new(bh_computation());
+
+            verifier.initial_transcript_push();
+            for _ in 0..verifier.variable_count() {
+                assert!(verifier.check_proof(prover.generate_proof()));
+                let challenge = verifier.generate_challenge();
+                prover.next_poly(challenge);
+            }
+            verifier.verify_proof()
+        };
+
+        let committed = MultiLinearPoly::new(&bh_computation());
+        assert!(run(Box::new(move |point| {
+            committed.clone().evaluate(point).computation[0]
+        })));
+
+        let mut other_values = bh_computation();
+        other_values[3] += Fq::from(1);
+        let other = MultiLinearPoly::new(&other_values);
+        assert!(!run(Box::new(move |point| {
+            other.clone().evaluate(point).computation[0]
+        })));
+    }
+
+    // runs the interactive protocol to the end and returns the verifier's challenges
+    fn interactive_challenges<H: TranscriptHasher>(computation: Vec<Fq>) -> Vec<Fq> {
+        let mut verifier = VerifierStruct::<Fq, H>::with_hasher(computation.clone());
+        let mut prover = ProverStruct::new(computation);
+
+        verifier.initial_transcript_push();
+        for _ in 0..verifier.variable_count() {
+            assert!(verifier.check_proof(prover.generate_proof()));
+            let challenge = verifier.generate_challenge();
+            prover.next_poly(challenge);
+        }
+        assert!(verifier.verify_proof());
+
+        verifier.challenges
+    }
+
+    #[test]
+    fn test_frontends_share_challenges() {
+        use crate::{
+            fiat_shamir_non_interactive::{
+                prover::ProverStruct as NonInteractiveProver,
+                verifier::VerifierStruct as NonInteractiveVerifier,
+            },
+            sum_check,
+        };
+        use sha2::Sha256;
+
+        let challenges = interactive_challenges::<Keccak256>(bh_computation());
+
+        let mut non_interactive = NonInteractiveProver::new(bh_computation());
+        non_interactive.generate_proof();
+        assert_eq!(non_interactive.final_state.challenges, challenges);
+
+        let poly = MultiLinearPoly::new(&bh_computation());
+        let claimed_sum = bh_computation().iter().sum();
+        let proof = sum_check::proof(poly, claimed_sum);
+        assert!(sum_check::verify(&proof));
+        let mut transcript: Transcript =
+            sum_check::statement_transcript(&proof.init_poly.computation, claimed_sum);
+        let sum_check_challenges: Vec<Fq> = proof
+            .sum_polys
+            .iter()
+            .map(|sum_poly| sum_check::round_challenge(&mut transcript, &sum_poly.computation))
+            .collect();
+        assert_eq!(sum_check_challenges, challenges);
+
+        // another hasher gives other challenges, but the frontends still agree with each other
+        let sha_challenges = interactive_challenges::<Sha256>(bh_computation());
+        assert_ne!(sha_challenges, challenges);
+
+        let mut non_interactive = NonInteractiveProver::new(bh_computation());
+        non_interactive.generate_proof_with_hasher::<Sha256>();
+        assert_eq!(non_interactive.final_state.challenges, sha_challenges);
+
+        let proof = non_interactive.get_proof();
+        assert!(NonInteractiveVerifier::new(bh_computation())
+            .verify_proof_with_hasher::<Sha256>(&proof));
+        assert!(!NonInteractiveVerifier::new(bh_computation()).verify_proof(&proof));
+    }
 }
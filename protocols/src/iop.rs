@@ -0,0 +1,5 @@
+#[cfg(feature = "prover")]
+pub mod fri_iop;
+pub mod gkr_iop;
+pub mod iop_trait;
+pub mod sum_check_iop;
@@ -0,0 +1,200 @@
+use crate::{
+    fri::{
+        domain_cache::{Domain, DomainCache},
+        fri_helper_functions::fold_poly,
+        lde::lde,
+    },
+    iop::iop_trait::InteractiveOracleProof,
+    multi_linear::MultiLinearPoly,
+    transcript::index_from_challenge,
+};
+use ark_ff::PrimeField;
+use std::sync::Arc;
+
+//=========================================================================================
+// FRI folding rounds as an IOP (commit phase + query phase, no Merkle trees)
+// the prover sends fᵢ evaluated over a domain of size domain_size / 2ⁱ and folds on r
+// fᵢ₊₁(x²) = (fᵢ(x) + fᵢ(-x)) / 2 + r * (fᵢ(x) - fᵢ(-x)) / 2x
+// the verifier checks that equation at random query points and that the last oracle is constant
+// evals[j] = f(ωʲ) (same order as fri::lde with no shift) so -ωʲ sits at j + n/2
+//=========================================================================================
+pub struct FRIFoldingIOP;
+
+pub struct FRIFoldingProver<F: PrimeField> {
+    coeffs: Vec<F>,
+    domain_size: usize,
+}
+
+impl<F: PrimeField> FRIFoldingProver<F> {
+    pub fn new(coeffs: Vec<F>, blowup_factor: usize) -> Self {
+        let mut coeffs = coeffs;
+        coeffs.resize(coeffs.len().next_power_of_two(), F::zero());
+        let domain_size = coeffs.len() * blowup_factor;
+
+        FRIFoldingProver {
+            coeffs,
+            domain_size,
+        }
+    }
+
+    fn oracle(&self) -> Vec<F> {
+        lde(&self.coeffs, self.domain_size / self.coeffs.len(), F::one())
+    }
+}
+
+pub struct FRIFoldingVerifier<F: PrimeField> {
+    num_rounds: usize,
+    domain_size: usize,
+    num_queries: usize,
+    pub oracles: Vec<Vec<F>>,
+    challenges: Vec<F>,
+    domains: Vec<Arc<Domain<F>>>, // domain of every oracle that gets folded
+}
+
+impl<F: PrimeField> FRIFoldingVerifier<F> {
+    // num_coeffs is the degree bound being tested (degree < num_coeffs)
+    pub fn new(num_coeffs: usize, blowup_factor: usize, num_queries: usize) -> Self {
+        if !blowup_factor.is_power_of_two() || blowup_factor < 2 {
+            panic!("The blowup factor must be a power of 2 greater than 1");
+        }
+
+        let num_coeffs = num_coeffs.next_power_of_two();
+        let num_rounds = num_coeffs.ilog2() as usize;
+        let domain_size = num_coeffs * blowup_factor;
+
+        let mut cache = DomainCache::new();
+        let domains = (0..num_rounds)
+            .map(|i| cache.get(domain_size >> i))
+            .collect();
+
+        FRIFoldingVerifier {
+            num_rounds,
+            domain_size,
+            num_queries,
+            oracles: Vec::new(),
+            challenges: Vec::new(),
+            domains,
+        }
+    }
+
+    fn check_query(&self, query: usize) -> bool {
+        for (i, challenge) in self.challenges.iter().enumerate() {
+            let (oracle, next_oracle) = (&self.oracles[i], &self.oracles[i + 1]);
+            let n = oracle.len();
+            let half = n / 2;
+            let j = query % n;
+
+            let x = self.domains[i].element(j);
+            let (f_x, f_neg_x) = (oracle[j], oracle[(j + half) % n]);
+
+            let two = F::from(2u64);
+            let even = (f_x + f_neg_x) / two;
+            let odd = (f_x - f_neg_x) / (two * x);
+
+            if next_oracle[j % half] != even + (*challenge * odd) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl<F: PrimeField> InteractiveOracleProof<F> for FRIFoldingIOP {
+    type Prover = FRIFoldingProver<F>;
+    type Verifier = FRIFoldingVerifier<F>;
+    type ProverMsg = Vec<F>;
+    type VerifierMsg = F;
+
+    fn prover_round(prover: &mut FRIFoldingProver<F>, verifier_msg: Option<&F>) -> Vec<F> {
+        if let Some(r_challenge) = verifier_msg {
+            prover.coeffs = fold_poly(&prover.coeffs, *r_challenge);
+            prover.domain_size /= 2;
+        }
+
+        prover.oracle()
+    }
+
+    fn verifier_round(
+        verifier: &mut FRIFoldingVerifier<F>,
+        prover_msg: &Vec<F>,
+        coins: &mut dyn FnMut() -> F,
+    ) -> Option<F> {
+        if prover_msg.len() != verifier.domain_size >> verifier.oracles.len() {
+            return None;
+        }
+        verifier.oracles.push(prover_msg.clone());
+
+        // the last oracle needs no challenge, it is only checked for being constant
+        if verifier.oracles.len() > verifier.num_rounds {
+            return Some(F::zero());
+        }
+
+        let r_challenge = coins();
+        verifier.challenges.push(r_challenge);
+
+        Some(r_challenge)
+    }
+
+    fn is_finished(verifier: &FRIFoldingVerifier<F>) -> bool {
+        verifier.oracles.len() == verifier.num_rounds + 1
+    }
+
+    fn verify_final(verifier: &mut FRIFoldingVerifier<F>, coins: &mut dyn FnMut() -> F) -> bool {
+        let final_oracle = verifier.oracles.last().unwrap();
+        if final_oracle.iter().any(|eval| *eval != final_oracle[0]) {
+            return false;
+        }
+
+        (0..verifier.num_queries).all(|_| {
+            let query = index_from_challenge(coins(), verifier.domain_size);
+            verifier.check_query(query)
+        })
+    }
+
+    fn prover_msg_bytes(msg: &Vec<F>) -> Vec<u8> {
+        MultiLinearPoly::to_bytes(msg)
+    }
+}
+
+#[cfg(all(test, feature = "bls12-377"))]
+mod test {
+    use super::*;
+    use crate::iop::iop_trait::run_interactive;
+    use ark_bls12_377::Fr;
+
+    fn poly_1() -> Vec<Fr> {
+        // 5 + 3x + 2x^3 + x^4 + 7x^5
+        [5, 3, 0, 2, 1, 7].map(Fr::from).to_vec()
+    }
+
+    #[test]
+    fn test_fri_iop() {
+        let mut prover = FRIFoldingProver::new(poly_1(), 2);
+        let mut verifier = FRIFoldingVerifier::new(6, 2, 4);
+
+        let result = run_interactive::<Fr, FRIFoldingIOP, _>(
+            &mut prover,
+            &mut verifier,
+            &mut rand::thread_rng(),
+        );
+        assert!(result);
+        assert_eq!(verifier.oracles.len(), 4);
+        assert_eq!(verifier.oracles.last().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_fri_iop_degree_too_high() {
+        // same domain size (32) but the verifier only allows degree < 4 while the poly has degree 5
+        // so the prover still holds a linear poly when the final oracle must be constant
+        let mut prover = FRIFoldingProver::new(poly_1(), 4);
+        let mut verifier = FRIFoldingVerifier::new(4, 8, 4);
+
+        let result = run_interactive::<Fr, FRIFoldingIOP, _>(
+            &mut prover,
+            &mut verifier,
+            &mut rand::thread_rng(),
+        );
+        assert!(!result);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{
+    gkr::{
+        interactive_gkr::{GKRProverStruct, GKRVerifierStruct},
+        padded_layer::PaddedLayer,
+    },
+    iop::iop_trait::InteractiveOracleProof,
+    multi_linear::MultiLinearPoly,
+};
+use ark_ff::PrimeField;
+use std::marker::PhantomData;
+
+//=========================================================================================
+// GKR as an IOP, one message per sum-check round
+// output layer -> r_a, then per layer: round polys -> challenges, (Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)) -> α, β
+// the verifier finishes once it has evaluated the inputs itself, so there is nothing left to query
+//=========================================================================================
+pub struct GKRIOP<'a>(PhantomData<&'a ()>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GKRProverMsg<F: PrimeField> {
+    OutputLayer(Vec<F>),
+    RoundPoly(Vec<F>),
+    WEvals(F, F),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GKRVerifierMsg<F: PrimeField> {
+    RA(Vec<F>),
+    Challenge(F),
+    AlphaBeta(F, F),
+    InputLayer, // the inputs were checked, nothing more to ask
+}
+
+impl<'a, F: PrimeField> InteractiveOracleProof<F> for GKRIOP<'a> {
+    type Prover = GKRProverStruct<'a, F>;
+    type Verifier = GKRVerifierStruct<'a, F>;
+    type ProverMsg = GKRProverMsg<F>;
+    type VerifierMsg = GKRVerifierMsg<F>;
+
+    fn prover_round(
+        prover: &mut GKRProverStruct<'a, F>,
+        verifier_msg: Option<&GKRVerifierMsg<F>>,
+    ) -> GKRProverMsg<F> {
+        match verifier_msg {
+            None => GKRProverMsg::OutputLayer(prover.output_layer()),
+            Some(GKRVerifierMsg::RA(r_a_challenges)) => {
+                prover.receive_r_a(r_a_challenges);
+                GKRProverMsg::RoundPoly(prover.round_poly())
+            }
+            Some(GKRVerifierMsg::Challenge(challenge)) => {
+                prover.receive_challenge(*challenge);
+
+                if prover.num_rounds() > 0 {
+                    GKRProverMsg::RoundPoly(prover.round_poly())
+                } else {
+                    let (w_b, w_c) = prover.w_evals();
+                    GKRProverMsg::WEvals(w_b, w_c)
+                }
+            }
+            Some(GKRVerifierMsg::AlphaBeta(alpha, beta)) => {
+                prover.receive_alpha_beta(*alpha, *beta);
+                GKRProverMsg::RoundPoly(prover.round_poly())
+            }
+            Some(GKRVerifierMsg::InputLayer) => {
+                panic!("The prover has nothing to send once the verifier reached the input layer")
+            }
+        }
+    }
+
+    fn verifier_round(
+        verifier: &mut GKRVerifierStruct<'a, F>,
+        prover_msg: &GKRProverMsg<F>,
+        coins: &mut dyn FnMut() -> F,
+    ) -> Option<GKRVerifierMsg<F>> {
+        match prover_msg {
+            GKRProverMsg::OutputLayer(w_0_arr) => {
+                if verifier.received_output_layer() {
+                    return None;
+                }
+                // padded the same way the circuit's outputs would be, with zeros only
+                PaddedLayer::from_padded(w_0_arr.clone(), verifier.circuit.output_len())?;
+
+                let r_a_challenges: Vec<F> = (0..w_0_arr.len().ilog2()).map(|_| coins()).collect();
+                verifier.receive_output_layer(w_0_arr, r_a_challenges.clone());
+
+                Some(GKRVerifierMsg::RA(r_a_challenges))
+            }
+            GKRProverMsg::RoundPoly(poly) => {
+                if !verifier.received_output_layer() || verifier.num_rounds() == 0 {
+                    return None;
+                }
+
+                let challenge = coins();
+                if !verifier.receive_round_poly(poly, challenge) {
+                    return None;
+                }
+
+                Some(GKRVerifierMsg::Challenge(challenge))
+            }
+            GKRProverMsg::WEvals(w_b, w_c) => {
+                if !verifier.received_output_layer() || verifier.num_rounds() != 0 {
+                    return None;
+                }
+                if !verifier.receive_w_evals(*w_b, *w_c) {
+                    return None;
+                }
+                if verifier.reached_input_layer() {
+                    return Some(GKRVerifierMsg::InputLayer);
+                }
+
+                // only now, the evals can't depend on them
+                let (alpha, beta) = (coins(), coins());
+                verifier.receive_alpha_beta(alpha, beta);
+
+                Some(GKRVerifierMsg::AlphaBeta(alpha, beta))
+            }
+        }
+    }
+
+    fn is_finished(verifier: &GKRVerifierStruct<'a, F>) -> bool {
+        verifier.reached_input_layer()
+    }
+
+    // the input layer check already happened in the last receive_w_evals
+    fn verify_final(
+        verifier: &mut GKRVerifierStruct<'a, F>,
+        _coins: &mut dyn FnMut() -> F,
+    ) -> bool {
+        verifier.reached_input_layer()
+    }
+
+    // a tag byte so an output layer can't be replayed as a round poly
+    fn prover_msg_bytes(msg: &GKRProverMsg<F>) -> Vec<u8> {
+        let (tag, values) = match msg {
+            GKRProverMsg::OutputLayer(w_0_arr) => (0u8, w_0_arr.clone()),
+            GKRProverMsg::RoundPoly(poly) => (1u8, poly.clone()),
+            GKRProverMsg::WEvals(w_b, w_c) => (2u8, vec![*w_b, *w_c]),
+        };
+
+        [vec![tag], MultiLinearPoly::to_bytes(&values)].concat()
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{gkr::gkr_circuit::test::setup_test_circuit8, iop::iop_trait::run_interactive};
+    use ark_bn254::Fq;
+
+    #[test]
+    fn test_gkr_iop_rejects_out_of_order_msg() {
+        let circuit = setup_test_circuit8();
+        let mut prover = GKRProverStruct::new(&circuit);
+        let mut verifier = GKRVerifierStruct::new(&circuit);
+        let mut coins = || Fq::from(3);
+
+        // round polys before the output layer
+        let msg = GKRProverMsg::RoundPoly(vec![Fq::from(0), Fq::from(0)]);
+        assert!(GKRIOP::verifier_round(&mut verifier, &msg, &mut coins).is_none());
+
+        let msg = GKRIOP::prover_round(&mut prover, None);
+        let reply = GKRIOP::verifier_round(&mut verifier, &msg, &mut coins).unwrap();
+
+        // w evals before the layer's sum-check is done
+        let msg = GKRProverMsg::WEvals(Fq::from(1), Fq::from(2));
+        assert!(GKRIOP::verifier_round(&mut verifier, &msg, &mut coins).is_none());
+
+        let msg = GKRIOP::prover_round(&mut prover, Some(&reply));
+        assert!(matches!(msg, GKRProverMsg::RoundPoly(_)));
+        assert!(GKRIOP::verifier_round(&mut verifier, &msg, &mut coins).is_some());
+    }
+
+    #[test]
+    fn test_gkr_iop_wrong_inputs() {
+        let circuit = setup_test_circuit8();
+        let mut wrong_circuit = setup_test_circuit8();
+        wrong_circuit.inputs[0] += Fq::from(1);
+
+        // the prover runs on different inputs than the verifier checks against
+        let mut prover = GKRProverStruct::new(&wrong_circuit);
+        let mut verifier = GKRVerifierStruct::new(&circuit);
+
+        let result =
+            run_interactive::<Fq, GKRIOP, _>(&mut prover, &mut verifier, &mut rand::thread_rng());
+        assert!(!result);
+    }
+
+    #[test]
+    fn test_gkr_iop_alpha_beta_come_from_the_verifier() {
+        let circuit = setup_test_circuit8();
+        let mut prover = GKRProverStruct::new(&circuit);
+        let mut verifier = GKRVerifierStruct::new(&circuit);
+        let counter = std::cell::Cell::new(0u64);
+        let mut coins = || {
+            counter.set(counter.get() + 1);
+            Fq::from(counter.get() + 100)
+        };
+
+        let mut msg = GKRIOP::prover_round(&mut prover, None);
+        let reply = loop {
+            let reply = GKRIOP::verifier_round(&mut verifier, &msg, &mut coins).unwrap();
+            if matches!(msg, GKRProverMsg::WEvals(..)) {
+                break reply;
+            }
+            msg = GKRIOP::prover_round(&mut prover, Some(&reply));
+        };
+        // the two coins drawn right after the evals
+        let GKRVerifierMsg::AlphaBeta(alpha, beta) = reply else {
+            panic!("The verifier must answer the evals with alpha and beta");
+        };
+        let drawn = counter.get();
+        assert_eq!((alpha, beta), (Fq::from(drawn + 99), Fq::from(drawn + 100)));
+
+        // a prover folding with any other alpha and beta fails the next round
+        prover.receive_alpha_beta(alpha + Fq::from(1), beta);
+        let msg = GKRProverMsg::RoundPoly(prover.round_poly());
+        assert!(GKRIOP::verifier_round(&mut verifier, &msg, &mut coins).is_none());
+    }
+}
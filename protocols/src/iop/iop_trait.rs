@@ -0,0 +1,59 @@
+use ark_ff::PrimeField;
+#[cfg(feature = "prover")]
+use rand::Rng;
+
+//=========================================================================================
+// Interactive Oracle Proof (public coin)
+// the prover and verifier alternate messages until the verifier has heard enough,
+// then the verifier queries its oracles once in verify_final
+// every verifier message is derived from fresh coins only, so the same rounds can be run
+// with an rng (interactive) or with a transcript (Fiat-Shamir) without changing the protocol
+//=========================================================================================
+pub trait InteractiveOracleProof<F: PrimeField> {
+    type Prover;
+    type Verifier;
+    type ProverMsg;
+    type VerifierMsg;
+
+    // None on the very first round, otherwise the verifier's last message
+    fn prover_round(
+        prover: &mut Self::Prover,
+        verifier_msg: Option<&Self::VerifierMsg>,
+    ) -> Self::ProverMsg;
+
+    // None means the verifier rejects the prover message
+    fn verifier_round(
+        verifier: &mut Self::Verifier,
+        prover_msg: &Self::ProverMsg,
+        coins: &mut dyn FnMut() -> F,
+    ) -> Option<Self::VerifierMsg>;
+
+    fn is_finished(verifier: &Self::Verifier) -> bool;
+
+    // the oracle queries, coins are there for protocols that sample query points
+    fn verify_final(verifier: &mut Self::Verifier, coins: &mut dyn FnMut() -> F) -> bool;
+
+    // canonical encoding of a prover message, this is what gets absorbed into a transcript
+    fn prover_msg_bytes(msg: &Self::ProverMsg) -> Vec<u8>;
+}
+
+#[cfg(feature = "prover")]
+pub fn run_interactive<F: PrimeField, I: InteractiveOracleProof<F>, R: Rng>(
+    prover: &mut I::Prover,
+    verifier: &mut I::Verifier,
+    rng: &mut R,
+) -> bool {
+    let mut coins = || F::rand(rng);
+    let mut verifier_msg: Option<I::VerifierMsg> = None;
+
+    while !I::is_finished(verifier) {
+        let prover_msg = I::prover_round(prover, verifier_msg.as_ref());
+
+        match I::verifier_round(verifier, &prover_msg, &mut coins) {
+            Some(msg) => verifier_msg = Some(msg),
+            None => return false,
+        }
+    }
+
+    I::verify_final(verifier, &mut coins)
+}
@@ -0,0 +1,151 @@
+use crate::{
+    fiat_shamir_non_interactive::prover::{hypercube_values, oracle_eval},
+    gkr::{
+        partial_sum_check::{fold_sum_poly, round_poly, verify_round},
+        product_poly::ProductPoly,
+    },
+    iop::iop_trait::InteractiveOracleProof,
+    lagrange::LagrangeWeights,
+    multi_linear::MultiLinearPoly,
+};
+use ark_ff::PrimeField;
+
+//=========================================================================================
+// Sum-check over Σ ∏ of multilinear polys as an IOP
+// prover: round poly [g(0), ..., g(d)] for the first free variable, folds it on the challenge
+// verifier: g(0) + g(1) == claim, next claim = g(r), one oracle query at the end
+//=========================================================================================
+pub struct SumCheckIOP;
+
+pub struct SumCheckProver<F: PrimeField> {
+    sum_poly: Vec<ProductPoly<F>>,
+}
+
+impl<F: PrimeField> SumCheckProver<F> {
+    pub fn new(sum_poly: Vec<ProductPoly<F>>) -> Self {
+        SumCheckProver { sum_poly }
+    }
+
+    pub fn claimed_sum(&self) -> F {
+        hypercube_values(&self.sum_poly).iter().sum()
+    }
+}
+
+pub struct SumCheckVerifier<F: PrimeField> {
+    oracle: Vec<ProductPoly<F>>,
+    num_vars: usize,
+    claimed_sum: F,
+    weights: LagrangeWeights<F>, // over 0..=d, the same for every round
+    pub challenges: Vec<F>,
+}
+
+impl<F: PrimeField> SumCheckVerifier<F> {
+    pub fn new(oracle: Vec<ProductPoly<F>>, claimed_sum: F) -> Self {
+        let num_vars = oracle[0].poly_array[0].computation.len().ilog2() as usize;
+        let weights = LagrangeWeights::for_degree(oracle[0].get_degree());
+
+        SumCheckVerifier {
+            oracle,
+            num_vars,
+            claimed_sum,
+            weights,
+            challenges: Vec::new(),
+        }
+    }
+}
+
+impl<F: PrimeField> InteractiveOracleProof<F> for SumCheckIOP {
+    type Prover = SumCheckProver<F>;
+    type Verifier = SumCheckVerifier<F>;
+    type ProverMsg = Vec<F>;
+    type VerifierMsg = F;
+
+    fn prover_round(prover: &mut SumCheckProver<F>, verifier_msg: Option<&F>) -> Vec<F> {
+        if let Some(challenge) = verifier_msg {
+            prover.sum_poly = fold_sum_poly(&prover.sum_poly, *challenge);
+        }
+
+        round_poly(&prover.sum_poly)
+    }
+
+    fn verifier_round(
+        verifier: &mut SumCheckVerifier<F>,
+        prover_msg: &Vec<F>,
+        coins: &mut dyn FnMut() -> F,
+    ) -> Option<F> {
+        // degree d round poly is d + 1 evaluations
+        if prover_msg.len() != verifier.oracle[0].get_degree() + 1 {
+            return None;
+        }
+
+        let challenge = coins();
+        verifier.claimed_sum = verify_round(
+            verifier.claimed_sum,
+            prover_msg,
+            &verifier.weights,
+            challenge,
+        )?;
+        verifier.challenges.push(challenge);
+
+        Some(challenge)
+    }
+
+    fn is_finished(verifier: &SumCheckVerifier<F>) -> bool {
+        verifier.challenges.len() == verifier.num_vars
+    }
+
+    fn verify_final(verifier: &mut SumCheckVerifier<F>, _coins: &mut dyn FnMut() -> F) -> bool {
+        verifier.claimed_sum == oracle_eval(&verifier.oracle, &verifier.challenges)
+    }
+
+    fn prover_msg_bytes(msg: &Vec<F>) -> Vec<u8> {
+        MultiLinearPoly::to_bytes(msg)
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::iop::iop_trait::run_interactive;
+    use ark_bn254::Fq;
+
+    fn product_polys() -> Vec<ProductPoly<Fq>> {
+        let poly_1 = MultiLinearPoly::new(&[1, 2, 3, 4, 5, 6, 7, 8].map(Fq::from));
+        let poly_2 = MultiLinearPoly::new(&[0, 0, 1, 1, 2, 2, 3, 3].map(Fq::from));
+        let poly_3 = MultiLinearPoly::new(&[0, 0, 0, 3, 0, 0, 0, 5].map(Fq::from));
+
+        vec![
+            ProductPoly::new(vec![poly_1, poly_2]),
+            ProductPoly::new(vec![poly_3.clone(), poly_3]),
+        ]
+    }
+
+    #[test]
+    fn test_sum_check_iop() {
+        let mut prover = SumCheckProver::new(product_polys());
+        let claimed_sum = prover.claimed_sum();
+        let mut verifier = SumCheckVerifier::new(product_polys(), claimed_sum);
+
+        let result = run_interactive::<Fq, SumCheckIOP, _>(
+            &mut prover,
+            &mut verifier,
+            &mut rand::thread_rng(),
+        );
+        assert!(result);
+        assert_eq!(verifier.challenges.len(), 3);
+    }
+
+    #[test]
+    fn test_sum_check_iop_wrong_sum() {
+        let mut prover = SumCheckProver::new(product_polys());
+        let claimed_sum = prover.claimed_sum() + Fq::from(1);
+        let mut verifier = SumCheckVerifier::new(product_polys(), claimed_sum);
+
+        let result = run_interactive::<Fq, SumCheckIOP, _>(
+            &mut prover,
+            &mut verifier,
+            &mut rand::thread_rng(),
+        );
+        assert!(!result);
+    }
+}
@@ -1,3 +1,16 @@
+pub mod batch_kzg;
+pub mod ceremony;
+pub mod g2_kzg;
+pub mod homomorphic;
+pub mod kzg_error;
 pub mod kzg_helper_functions;
 pub mod kzg_protocol;
+pub mod prepared_verifier;
+pub mod split_kzg;
+pub mod subcube_zero;
 pub mod trusted_setup;
+pub mod univariate_kzg;
+pub mod vector_lookup;
+pub mod vertex_opening;
+
+pub use batch_kzg::{batch_commit_open, batch_verify};
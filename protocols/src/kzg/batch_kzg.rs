@@ -0,0 +1,272 @@
+use ark_ec::{pairing::Pairing, PrimeGroup};
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::CanonicalSerialize;
+
+use crate::{
+    kzg::{
+        kzg_helper_functions::compute_commitment,
+        kzg_protocol::{self, KZGProof},
+        trusted_setup::VerifierSetup,
+    },
+    multi_linear::MultiLinearPoly,
+    proof_format::{
+        ensure_consumed, read_header, read_points, read_vec, write_header, DecodeLimits,
+        ProofFormatError, ProtocolId,
+    },
+    transcript::{Transcript, TranscriptDomain},
+};
+
+//=============================================================================
+// Batch KZG opening (many polys, one point)
+// g(x) = Σ γʲ * fⱼ(x)  ==>  g(τ) = Σ γʲ * fⱼ(τ) and g(a) = Σ γʲ * fⱼ(a)
+// γ is squeezed after absorbing every commitment and every opened value
+// so a single set of quotients for g(x) proves all openings at once
+// the transcript starts from the caller's TranscriptDomain
+//=============================================================================
+pub struct BatchKZGProof<F: PrimeField, P: Pairing> {
+    pub commitments: Vec<P::G1>,
+    pub polys_opened: Vec<F>,
+    pub quotient_evals: Vec<P::G1>,
+}
+
+impl<F: PrimeField, P: Pairing> BatchKZGProof<F, P> {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofFormatError> {
+        let mut bytes = Vec::new();
+        write_header(ProtocolId::BatchKzg, &mut bytes);
+
+        self.commitments.serialize_compressed(&mut bytes)?;
+        self.polys_opened.serialize_compressed(&mut bytes)?;
+        self.quotient_evals.serialize_compressed(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    pub fn from_bytes_with_limits(
+        mut bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        limits.check_size(bytes)?;
+        read_header(&mut bytes, ProtocolId::BatchKzg)?;
+
+        let commitments = read_points::<P::G1>(&mut bytes, limits)?;
+        let polys_opened = read_vec::<F>(&mut bytes, limits)?;
+        let quotient_evals = read_points::<P::G1>(&mut bytes, limits)?;
+        ensure_consumed(bytes)?;
+
+        // one opened value per commitment
+        if polys_opened.len() != commitments.len() {
+            return Err(ProofFormatError::InconsistentLengths {
+                expected: commitments.len(),
+                found: polys_opened.len(),
+            });
+        }
+
+        Ok(BatchKZGProof {
+            commitments,
+            polys_opened,
+            quotient_evals,
+        })
+    }
+}
+
+pub fn batch_commit_open<F: PrimeField, P: Pairing>(
+    polys: &[MultiLinearPoly<F>],
+    encrypted_basis: &[P::G1],
+    vars_to_open: &[F],
+    transcript_domain: &TranscriptDomain,
+) -> BatchKZGProof<F, P> {
+    if polys.is_empty() {
+        panic!("Cannot batch open an empty set of polynomials");
+    }
+
+    let commitments: Vec<P::G1> = polys
+        .iter()
+        .map(|poly| {
+            compute_commitment::<F, P>(poly, encrypted_basis)
+                .expect("Polynomial size does not match the trusted setup")
+        })
+        .collect();
+
+    batch_open_committed(
+        polys,
+        commitments,
+        encrypted_basis,
+        vars_to_open,
+        transcript_domain,
+    )
+}
+
+// same as batch_commit_open when the commitments are already known (e.g. reused for several points)
+pub(crate) fn batch_open_committed<F: PrimeField, P: Pairing>(
+    polys: &[MultiLinearPoly<F>],
+    commitments: Vec<P::G1>,
+    encrypted_basis: &[P::G1],
+    vars_to_open: &[F],
+    transcript_domain: &TranscriptDomain,
+) -> BatchKZGProof<F, P> {
+    let mut transcript = transcript_domain.transcript();
+
+    let polys_opened: Vec<F> = polys
+        .iter()
+        .map(|poly| poly.clone().evaluate(vars_to_open).computation[0])
+        .collect();
+
+    let gamma = batch_challenge::<F, P>(&mut transcript, &commitments, &polys_opened);
+    let combined_poly = combine_polys(polys, gamma);
+
+    let combined_proof = kzg_protocol::proof::<F, P>(combined_poly, encrypted_basis, vars_to_open);
+
+    BatchKZGProof {
+        commitments,
+        polys_opened,
+        quotient_evals: combined_proof.quotient_evals,
+    }
+}
+
+pub fn batch_verify<F: PrimeField, P: Pairing>(
+    proof: &BatchKZGProof<F, P>,
+    verifier_setup: &VerifierSetup<P>,
+    vars_to_open: &[F],
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    if proof.commitments.is_empty() || proof.commitments.len() != proof.polys_opened.len() {
+        return false;
+    }
+
+    let mut transcript = transcript_domain.transcript();
+    let gamma = batch_challenge::<F, P>(&mut transcript, &proof.commitments, &proof.polys_opened);
+
+    // the verifier folds the commitments and the opened values with the same γ
+    let mut combined_commitment = P::G1::zero();
+    let mut combined_opened = F::zero();
+    let mut gamma_pow = F::one();
+    for (commitment, v) in proof.commitments.iter().zip(proof.polys_opened.iter()) {
+        combined_commitment += commitment.mul_bigint(gamma_pow.into_bigint());
+        combined_opened += gamma_pow * v;
+        gamma_pow *= gamma;
+    }
+
+    let combined_proof = KZGProof::<F, P> {
+        commitment: combined_commitment,
+        quotient_evals: proof.quotient_evals.clone(),
+        poly_opened: combined_opened,
+    };
+
+    kzg_protocol::verify::<F, P>(&combined_proof, verifier_setup, vars_to_open)
+}
+
+fn batch_challenge<F: PrimeField, P: Pairing>(
+    transcript: &mut Transcript,
+    commitments: &[P::G1],
+    polys_opened: &[F],
+) -> F {
+    for commitment in commitments.iter() {
+        transcript.absorb_commitment(commitment);
+    }
+    transcript.absorb(&MultiLinearPoly::to_bytes(polys_opened));
+
+    transcript.squeeze_field::<F>()
+}
+
+fn combine_polys<F: PrimeField>(polys: &[MultiLinearPoly<F>], gamma: F) -> MultiLinearPoly<F> {
+    let poly_len = polys[0].computation.len();
+    let mut combined = vec![F::zero(); poly_len];
+    let mut gamma_pow = F::one();
+
+    for poly in polys.iter() {
+        if poly.computation.len() != poly_len {
+            panic!("All polynomials in a batch must have the same number of variables");
+        }
+
+        for (acc, val) in combined.iter_mut().zip(poly.computation.iter()) {
+            *acc += gamma_pow * val;
+        }
+        gamma_pow *= gamma;
+    }
+
+    MultiLinearPoly::new(&combined)
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::{
+        kzg::{kzg_helper_functions::test::poly_1, trusted_setup::tests::setup},
+        transcript::test::domain,
+    };
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    fn poly_2() -> MultiLinearPoly<BlsFr> {
+        MultiLinearPoly::new(&[1, 2, 3, 4, 5, 6, 7, 8].map(BlsFr::from))
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let setup = setup();
+        let polys = vec![poly_1(), poly_2()];
+        let vars_to_open = vec![BlsFr::from(6), BlsFr::from(4), BlsFr::from(0)];
+
+        let proof =
+            batch_commit_open::<BlsFr, Bls12_381>(&polys, &setup.g1_arr, &vars_to_open, &domain());
+        assert_eq!(proof.quotient_evals.len(), 3);
+        assert_eq!(proof.polys_opened[0], BlsFr::from(72));
+
+        let result = batch_verify::<BlsFr, Bls12_381>(
+            &proof,
+            &setup.verifier_setup,
+            &vars_to_open,
+            &domain(),
+        );
+        assert!(result);
+    }
+
+    #[test]
+    fn test_batch_proof_bytes_roundtrip() {
+        let setup = setup();
+        let polys = vec![poly_1(), poly_2()];
+        let vars_to_open = vec![BlsFr::from(6), BlsFr::from(4), BlsFr::from(0)];
+
+        let proof =
+            batch_commit_open::<BlsFr, Bls12_381>(&polys, &setup.g1_arr, &vars_to_open, &domain());
+        let bytes = proof.to_bytes().unwrap();
+
+        let decoded = BatchKZGProof::<BlsFr, Bls12_381>::from_bytes(&bytes).unwrap();
+        let result = batch_verify::<BlsFr, Bls12_381>(
+            &decoded,
+            &setup.verifier_setup,
+            &vars_to_open,
+            &domain(),
+        );
+        assert!(result);
+
+        // a batch proof must not be accepted where a single KZG proof is expected
+        let result = KZGProof::<BlsFr, Bls12_381>::from_bytes(&bytes);
+        assert!(matches!(
+            result,
+            Err(ProofFormatError::ProtocolMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_batch_verify_wrong_opening() {
+        let setup = setup();
+        let polys = vec![poly_1(), poly_2()];
+        let vars_to_open = vec![BlsFr::from(6), BlsFr::from(4), BlsFr::from(0)];
+
+        let mut proof =
+            batch_commit_open::<BlsFr, Bls12_381>(&polys, &setup.g1_arr, &vars_to_open, &domain());
+        proof.polys_opened[1] += BlsFr::from(1);
+
+        let result = batch_verify::<BlsFr, Bls12_381>(
+            &proof,
+            &setup.verifier_setup,
+            &vars_to_open,
+            &domain(),
+        );
+        assert!(!result);
+    }
+}
@@ -0,0 +1,399 @@
+use ark_ec::{pairing::Pairing, PrimeGroup};
+use ark_ff::{PrimeField, Zero};
+#[cfg(feature = "prover")]
+use {ark_ff::UniformRand, rand::Rng};
+
+use crate::{
+    hypercube::{var_mask, BitOrder},
+    kzg::trusted_setup::{TrustedSetup, VerifierSetup},
+    transcript::{public_digest, Transcript},
+};
+
+//=========================================================================================
+// Multi-party setup ceremony for the multilinear KZG setup
+// the SRS is kept in monomial form: monomials[S] = [∏_{i∈S} τᵢ]G1 for every subset S of the variables
+// (MSB-first like the lagrange basis, bit n-1-i of S picks τᵢ) and g2_taus[i] = [τᵢ]G2
+// a contributor with secrets s₁..sₙ multiplies monomials[S] by ∏_{i∈S} sᵢ, so τᵢ becomes τᵢ * sᵢ
+// and the final τ stays unknown as long as a single contributor threw their secrets away
+// every contribution publishes [sᵢ]G2 so the update can be checked with pairings
+// the last contribution takes its secrets from a public random beacon so anyone can redo it:
+// the beacon value (e.g. a future block hash) goes through BEACON_ITERATIONS rounds of Keccak
+// before the secrets are drawn, so whoever publishes the beacon can't try many values
+// and pick the one that gives a convenient τ, see finalize_with_beacon
+// the lagrange basis is a signed sum of monomials: L_b(τ) = Σ_{S ⊇ b} (-1)^{|S| - |b|} τ^S
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct CeremonySrs<P: Pairing> {
+    pub monomials: Vec<P::G1>,
+    pub g2_taus: Vec<P::G2>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contribution<P: Pairing> {
+    pub srs: CeremonySrs<P>,
+    pub g2_secrets: Vec<P::G2>, // [sᵢ]G2, what the update is checked against
+}
+
+#[derive(Debug, Clone)]
+pub struct Ceremony<P: Pairing> {
+    pub num_vars: usize,
+    pub contributions: Vec<Contribution<P>>,
+    pub beacon: Option<Vec<u8>>, // set once the ceremony is finalized, the beacon step is the last contribution
+}
+
+impl<P: Pairing> CeremonySrs<P> {
+    // τ = (1, ..., 1), every monomial is the generator
+    pub fn new(num_vars: usize) -> Self {
+        CeremonySrs {
+            monomials: vec![P::G1::generator(); 1 << num_vars],
+            g2_taus: vec![P::G2::generator(); num_vars],
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.g2_taus.len()
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn contribute<R: Rng>(&self, rng: &mut R) -> Contribution<P> {
+        let secrets: Vec<P::ScalarField> = (0..self.num_vars())
+            .map(|_| P::ScalarField::rand(rng))
+            .collect();
+
+        self.contribute_with(&secrets)
+    }
+
+    pub fn contribute_with(&self, secrets: &[P::ScalarField]) -> Contribution<P> {
+        let num_vars = self.num_vars();
+        if secrets.len() != num_vars {
+            panic!("A contribution needs one secret per variable");
+        }
+        if secrets.iter().any(|secret| secret.is_zero()) {
+            panic!("A zero secret would erase the setup");
+        }
+
+        let monomials = self
+            .monomials
+            .iter()
+            .enumerate()
+            .map(|(subset, monomial)| {
+                let scale: P::ScalarField = (0..num_vars)
+                    .filter(|i| subset & var_bit(*i, num_vars) != 0)
+                    .map(|i| secrets[i])
+                    .product();
+
+                monomial.mul_bigint(scale.into_bigint())
+            })
+            .collect();
+        let g2_taus = self
+            .g2_taus
+            .iter()
+            .zip(secrets.iter())
+            .map(|(tau, secret)| tau.mul_bigint(secret.into_bigint()))
+            .collect();
+        let g2_secrets = secrets
+            .iter()
+            .map(|secret| P::G2::generator().mul_bigint(secret.into_bigint()))
+            .collect();
+
+        Contribution {
+            srs: CeremonySrs { monomials, g2_taus },
+            g2_secrets,
+        }
+    }
+
+    // the multilinear KZG setup for the τ this SRS encodes
+    pub fn to_trusted_setup(&self) -> TrustedSetup<P> {
+        let num_vars = self.num_vars();
+        let mut g1_arr = self.monomials.clone();
+
+        // L_b = Σ_{S ⊇ b} (-1)^{|S| - |b|} τ^S, one variable at a time
+        for i in 0..num_vars {
+            let bit = var_bit(i, num_vars);
+            for subset in 0..g1_arr.len() {
+                if subset & bit == 0 {
+                    let with_var = g1_arr[subset | bit];
+                    g1_arr[subset] -= with_var;
+                }
+            }
+        }
+
+        TrustedSetup {
+            max_input: num_vars,
+            g1_arr,
+            verifier_setup: VerifierSetup {
+                g1_generator: P::G1::generator(),
+                g2_generator: P::G2::generator(),
+                g2_arr: self.g2_taus.clone(),
+            },
+        }
+    }
+}
+
+// true if contribution is prev with every τᵢ multiplied by the sᵢ behind g2_secrets[i]
+// and its monomials are still the products of its own τs
+pub fn verify_contribution<P: Pairing>(
+    prev: &CeremonySrs<P>,
+    contribution: &Contribution<P>,
+) -> bool {
+    let num_vars = prev.num_vars();
+    let next = &contribution.srs;
+    let g1 = P::G1::generator();
+    let g2 = P::G2::generator();
+
+    if next.num_vars() != num_vars
+        || next.monomials.len() != 1 << num_vars
+        || contribution.g2_secrets.len() != num_vars
+        || next.monomials[0] != g1
+    {
+        return false;
+    }
+
+    for i in 0..num_vars {
+        let single = var_bit(i, num_vars);
+        let g2_secret = contribution.g2_secrets[i];
+
+        // sᵢ != 0 and τᵢ' = τᵢ * sᵢ in G1 and in G2
+        if g2_secret.is_zero()
+            || P::pairing(next.monomials[single], g2)
+                != P::pairing(prev.monomials[single], g2_secret)
+            || P::pairing(g1, next.g2_taus[i]) != P::pairing(next.monomials[single], g2)
+        {
+            return false;
+        }
+    }
+
+    // monomials[S] = monomials[S without its last variable] * τ of that variable
+    (1..next.monomials.len())
+        .filter(|subset| !subset.is_power_of_two())
+        .all(|subset| {
+            let bit = 1 << subset.trailing_zeros();
+            let var = num_vars - 1 - subset.trailing_zeros() as usize;
+
+            P::pairing(next.monomials[subset], g2)
+                == P::pairing(next.monomials[subset ^ bit], next.g2_taus[var])
+        })
+}
+
+pub const BEACON_ITERATIONS: usize = 1 << 16;
+
+// the beacon's secrets, anyone holding the beacon value recomputes them
+pub fn beacon_secrets<F: PrimeField>(beacon: &[u8], num_vars: usize) -> Vec<F> {
+    let mut digest = public_digest(beacon);
+    for _ in 1..BEACON_ITERATIONS {
+        digest = public_digest(&digest);
+    }
+
+    let mut transcript = Transcript::new();
+    transcript.absorb(&digest);
+
+    (0..num_vars)
+        .map(|_| {
+            let secret = transcript.squeeze_field::<F>();
+            if secret.is_zero() {
+                F::one()
+            } else {
+                secret
+            }
+        })
+        .collect()
+}
+
+// the last contribution to setup, made with the beacon's secrets instead of private ones
+pub fn finalize_with_beacon<P: Pairing>(setup: &CeremonySrs<P>, beacon: &[u8]) -> Contribution<P> {
+    let secrets = beacon_secrets::<P::ScalarField>(beacon, setup.num_vars());
+
+    setup.contribute_with(&secrets)
+}
+
+// true if contribution is exactly the beacon's step on top of prev, redone from the beacon
+pub fn verify_beacon_contribution<P: Pairing>(
+    prev: &CeremonySrs<P>,
+    contribution: &Contribution<P>,
+    beacon: &[u8],
+) -> bool {
+    finalize_with_beacon(prev, beacon) == *contribution
+}
+
+impl<P: Pairing> Ceremony<P> {
+    pub fn new(num_vars: usize) -> Self {
+        Ceremony {
+            num_vars,
+            contributions: Vec::new(),
+            beacon: None,
+        }
+    }
+
+    pub fn current(&self) -> CeremonySrs<P> {
+        match self.contributions.last() {
+            Some(contribution) => contribution.srs.clone(),
+            None => CeremonySrs::new(self.num_vars),
+        }
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) {
+        if self.beacon.is_some() {
+            panic!("The ceremony has already been finalized");
+        }
+
+        let contribution = self.current().contribute(rng);
+        self.contributions.push(contribution);
+    }
+
+    pub fn finalize(&mut self, beacon: &[u8]) {
+        if self.beacon.is_some() {
+            panic!("The ceremony has already been finalized");
+        }
+
+        let contribution = finalize_with_beacon(&self.current(), beacon);
+        self.contributions.push(contribution);
+        self.beacon = Some(beacon.to_vec());
+    }
+
+    // checks the whole chain from τ = 1, the last step must be exactly the beacon's
+    pub fn verify(&self) -> bool {
+        let beacon = match &self.beacon {
+            Some(beacon) => beacon,
+            None => return false,
+        };
+        // at least one contributor besides the beacon
+        if self.contributions.len() < 2 {
+            return false;
+        }
+
+        let mut prev = CeremonySrs::new(self.num_vars);
+        for contribution in self.contributions.iter() {
+            if !verify_contribution(&prev, contribution) {
+                return false;
+            }
+            prev = contribution.srs.clone();
+        }
+
+        let before_beacon = &self.contributions[self.contributions.len() - 2].srs;
+
+        verify_beacon_contribution(before_beacon, self.contributions.last().unwrap(), beacon)
+    }
+
+    // None until the ceremony is finalized and its chain checks out
+    pub fn trusted_setup(&self) -> Option<TrustedSetup<P>> {
+        if !self.verify() {
+            return None;
+        }
+
+        Some(self.current().to_trusted_setup())
+    }
+}
+
+// runs a whole ceremony in process: num_contributors random contributions then the beacon
+#[cfg(feature = "prover")]
+pub fn simulate<P: Pairing, R: Rng>(
+    num_vars: usize,
+    num_contributors: usize,
+    beacon: &[u8],
+    rng: &mut R,
+) -> Ceremony<P> {
+    let mut ceremony = Ceremony::new(num_vars);
+    for _ in 0..num_contributors {
+        ceremony.contribute(rng);
+    }
+    ceremony.finalize(beacon);
+
+    ceremony
+}
+
+// bit n-1-i of a subset picks τᵢ (MSB-first)
+fn var_bit(i: usize, num_vars: usize) -> usize {
+    var_mask(i, num_vars, BitOrder::MsbFirst)
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::kzg::{kzg_helper_functions::test::poly_1, kzg_protocol, trusted_setup::initialize};
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    #[test]
+    fn test_contributions_multiply_taus() {
+        let srs = CeremonySrs::<Bls12_381>::new(3);
+        let first = srs.contribute_with(&[5, 1, 3].map(BlsFr::from));
+        let second = first.srs.contribute_with(&[1, 2, 1].map(BlsFr::from));
+
+        assert!(verify_contribution(&srs, &first));
+        assert!(verify_contribution(&first.srs, &second));
+
+        // τ = (5, 2, 3) after both, same setup as initializing with it directly
+        let setup = second.srs.to_trusted_setup();
+        let expected = initialize::<BlsFr, Bls12_381>(&[5, 2, 3].map(BlsFr::from));
+        assert_eq!(setup.g1_arr, expected.g1_arr);
+        assert_eq!(setup.verifier_setup, expected.verifier_setup);
+    }
+
+    #[test]
+    fn test_verify_contribution_rejects_tampering() {
+        let srs = CeremonySrs::<Bls12_381>::new(3);
+        let contribution = srs.contribute_with(&[5, 2, 3].map(BlsFr::from));
+
+        let mut bad_monomial = contribution.clone();
+        let generator = bad_monomial.srs.monomials[0];
+        bad_monomial.srs.monomials[3] += generator;
+        assert!(!verify_contribution(&srs, &bad_monomial));
+
+        let mut bad_tau = contribution.clone();
+        let first_tau = bad_tau.srs.g2_taus[0];
+        bad_tau.srs.g2_taus[1] += first_tau;
+        assert!(!verify_contribution(&srs, &bad_tau));
+
+        // the update has to come from the published secrets
+        let mut other_secret = contribution;
+        other_secret.g2_secrets[0] = other_secret.g2_secrets[1];
+        assert!(!verify_contribution(&srs, &other_secret));
+    }
+
+    #[test]
+    fn test_simulated_ceremony() {
+        let ceremony = simulate::<Bls12_381, _>(3, 4, b"block 1234", &mut rand::thread_rng());
+
+        assert_eq!(ceremony.contributions.len(), 5);
+        assert!(ceremony.verify());
+
+        // the setup works for KZG like a directly initialized one
+        let setup = ceremony.trusted_setup().unwrap();
+        let vars_to_open = vec![BlsFr::from(6), BlsFr::from(4), BlsFr::from(0)];
+        let proof = kzg_protocol::proof::<BlsFr, Bls12_381>(poly_1(), &setup.g1_arr, &vars_to_open);
+        assert!(kzg_protocol::verify::<BlsFr, Bls12_381>(
+            &proof,
+            &setup.verifier_setup,
+            &vars_to_open
+        ));
+    }
+
+    #[test]
+    fn test_beacon_finalization() {
+        let srs = CeremonySrs::<Bls12_381>::new(2)
+            .contribute(&mut rand::thread_rng())
+            .srs;
+        let last = finalize_with_beacon(&srs, b"block 1234");
+
+        assert!(verify_contribution(&srs, &last));
+        assert!(verify_beacon_contribution(&srs, &last, b"block 1234"));
+        assert!(!verify_beacon_contribution(&srs, &last, b"block 1235"));
+
+        // a private contribution passes the pairing checks but isn't the beacon's
+        let private = srs.contribute(&mut rand::thread_rng());
+        assert!(verify_contribution(&srs, &private));
+        assert!(!verify_beacon_contribution(&srs, &private, b"block 1234"));
+    }
+
+    #[test]
+    fn test_ceremony_rejects_wrong_beacon() {
+        let mut ceremony = simulate::<Bls12_381, _>(2, 2, b"block 1234", &mut rand::thread_rng());
+        ceremony.beacon = Some(b"block 1235".to_vec());
+        assert!(!ceremony.verify());
+        assert!(ceremony.trusted_setup().is_none());
+
+        let mut unfinished = Ceremony::<Bls12_381>::new(2);
+        unfinished.contribute(&mut rand::thread_rng());
+        assert!(!unfinished.verify());
+    }
+}
@@ -0,0 +1,211 @@
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    PrimeGroup,
+};
+use ark_ff::{AdditiveGroup, PrimeField};
+
+use crate::{
+    kzg::kzg_error::KZGError,
+    kzg::kzg_helper_functions::{
+        compute_poly_minus_v, compute_quotient, compute_remainder, evaluate_in_basis, fold_basis,
+    },
+    kzg::trusted_setup::compute_lagrange_basis,
+    multi_linear::MultiLinearPoly,
+};
+
+//=========================================================================================
+// KZG with the pairing sides swapped: commitments and quotients live in G2, the taus in G1
+// (f(τ) - v) * 1 = Σ((τ - a) * Q(τ)) is checked as
+// pairing(g1_1, g2_(f(τ) - v)) == Σ pairing(g1_(τ - a), g2_Q(τ))
+// a G2 commitment can be paired directly with a G1 commitment from kzg_protocol, and the proofs
+// cost the verifier nothing in G1 bandwidth beyond the n taus
+// initialize_g2 uses the same taus as trusted_setup::initialize so commitments to the same poly
+// in both groups can be matched with commitments_match
+//=========================================================================================
+#[derive(Debug)]
+pub struct G2TrustedSetup<P: Pairing> {
+    pub max_input: usize,
+    pub g2_arr: Vec<P::G2>, // encrypted lagrange basis in G2
+    pub verifier_setup: G2VerifierSetup<P>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct G2VerifierSetup<P: Pairing> {
+    pub g1_generator: P::G1,
+    pub g2_generator: P::G2,
+    pub g1_arr: Vec<P::G1>, // encrypted taus in G1
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct G2KZGProof<F: PrimeField, P: Pairing> {
+    pub commitment: P::G2,
+    pub quotient_evals: Vec<P::G2>,
+    pub poly_opened: F,
+}
+
+pub fn initialize_g2<F: PrimeField, P: Pairing>(tau_arr: &[F]) -> G2TrustedSetup<P> {
+    let g1_generator = P::G1::generator();
+    let g2_generator = P::G2::generator();
+
+    let encrypted_basis_poly = compute_lagrange_basis(tau_arr)
+        .iter()
+        .map(|val| g2_generator.mul_bigint(val.into_bigint()))
+        .collect();
+    let encrypted_taus = tau_arr
+        .iter()
+        .map(|tau| g1_generator.mul_bigint(tau.into_bigint()))
+        .collect();
+
+    G2TrustedSetup {
+        max_input: tau_arr.len(),
+        g2_arr: encrypted_basis_poly,
+        verifier_setup: G2VerifierSetup {
+            g1_generator,
+            g2_generator,
+            g1_arr: encrypted_taus,
+        },
+    }
+}
+
+pub fn commit_g2<F: PrimeField, P: Pairing>(
+    poly: &MultiLinearPoly<F>,
+    encrypted_basis: &[P::G2],
+) -> Result<P::G2, KZGError> {
+    if poly.computation.len() != encrypted_basis.len() {
+        return Err(KZGError::SizeMismatch {
+            expected: encrypted_basis.len(),
+            found: poly.computation.len(),
+        });
+    }
+
+    Ok(evaluate_in_basis(&poly.computation, encrypted_basis))
+}
+
+// same quotients as kzg_protocol::proof, evaluated against the G2 basis
+pub fn proof_g2<F: PrimeField, P: Pairing>(
+    mut poly: MultiLinearPoly<F>,
+    encrypted_basis: &[P::G2],
+    vars_to_open: &[F],
+) -> G2KZGProof<F, P> {
+    let mut quotient_evals = Vec::with_capacity(vars_to_open.len());
+    let v = poly.evaluate(vars_to_open).computation[0];
+
+    let commitment = commit_g2::<F, P>(&poly, encrypted_basis)
+        .expect("Polynomial size does not match the trusted setup");
+
+    let mut poly_minus_v = compute_poly_minus_v(poly, vars_to_open);
+    let mut folded_basis = encrypted_basis.to_vec();
+
+    for var in vars_to_open.iter() {
+        let quotient = compute_quotient(&poly_minus_v);
+        folded_basis = fold_basis(&folded_basis);
+
+        quotient_evals.push(evaluate_in_basis(&quotient.computation, &folded_basis));
+
+        poly_minus_v = compute_remainder(poly_minus_v, *var);
+    }
+    assert_eq!(poly_minus_v.computation[0], F::zero());
+
+    G2KZGProof {
+        commitment,
+        quotient_evals,
+        poly_opened: v,
+    }
+}
+
+pub fn verify_g2<F: PrimeField, P: Pairing>(
+    proof: &G2KZGProof<F, P>,
+    verifier_setup: &G2VerifierSetup<P>,
+    vars_to_open: &[F],
+) -> bool {
+    let g1_generator = verifier_setup.g1_generator;
+    let g2_generator = verifier_setup.g2_generator;
+
+    if proof.quotient_evals.len() != verifier_setup.g1_arr.len()
+        || vars_to_open.len() != verifier_setup.g1_arr.len()
+    {
+        return false;
+    }
+
+    let mut rhs = PairingOutput::ZERO;
+    for (i, tau) in verifier_setup.g1_arr.iter().enumerate() {
+        rhs += P::pairing(
+            *tau - g1_generator.mul_bigint(vars_to_open[i].into_bigint()),
+            proof.quotient_evals[i],
+        );
+    }
+    let lhs = P::pairing(
+        g1_generator,
+        proof.commitment - g2_generator.mul_bigint(proof.poly_opened.into_bigint()),
+    );
+
+    lhs == rhs
+}
+
+// true if a G1 commitment and a G2 commitment (same taus) are to the same poly
+// pairing(g1_f(τ), g2_1) == pairing(g1_1, g2_f(τ))
+pub fn commitments_match<P: Pairing>(
+    g1_commitment: &P::G1,
+    g2_commitment: &P::G2,
+    verifier_setup: &G2VerifierSetup<P>,
+) -> bool {
+    P::pairing(*g1_commitment, verifier_setup.g2_generator)
+        == P::pairing(verifier_setup.g1_generator, *g2_commitment)
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::kzg::{
+        kzg_helper_functions::{compute_commitment, test::poly_1},
+        trusted_setup::{
+            insecure_taus,
+            tests::{setup, TEST_SEED},
+        },
+    };
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    fn setup_g2() -> G2TrustedSetup<Bls12_381> {
+        // same taus as trusted_setup::tests::setup
+        initialize_g2::<BlsFr, Bls12_381>(&insecure_taus(TEST_SEED, 3))
+    }
+
+    #[test]
+    fn test_g2_proof_verifies() {
+        let setup = setup_g2();
+        let point = [BlsFr::from(6), BlsFr::from(4), BlsFr::from(0)];
+
+        let proof = proof_g2::<BlsFr, Bls12_381>(poly_1(), &setup.g2_arr, &point);
+        // 3ab + 4c at (6, 4, 0)
+        assert_eq!(proof.poly_opened, BlsFr::from(72));
+        assert!(verify_g2(&proof, &setup.verifier_setup, &point));
+
+        let mut wrong_value = proof.clone();
+        wrong_value.poly_opened += BlsFr::from(1);
+        assert!(!verify_g2(&wrong_value, &setup.verifier_setup, &point));
+        assert!(!verify_g2(
+            &proof,
+            &setup.verifier_setup,
+            &[BlsFr::from(6), BlsFr::from(4), BlsFr::from(1)]
+        ));
+    }
+
+    #[test]
+    fn test_commitments_match_across_groups() {
+        let g2_setup = setup_g2();
+        let g1_commitment =
+            compute_commitment::<BlsFr, Bls12_381>(&poly_1(), &setup().g1_arr).unwrap();
+        let g2_commitment = commit_g2::<BlsFr, Bls12_381>(&poly_1(), &g2_setup.g2_arr).unwrap();
+
+        assert!(commitments_match(
+            &g1_commitment,
+            &g2_commitment,
+            &g2_setup.verifier_setup
+        ));
+        assert!(!commitments_match(
+            &(g1_commitment + g1_commitment),
+            &g2_commitment,
+            &g2_setup.verifier_setup
+        ));
+    }
+}
@@ -0,0 +1,170 @@
+use ark_ec::{pairing::Pairing, PrimeGroup};
+use ark_ff::{PrimeField, Zero};
+
+use crate::kzg::{kzg_error::KZGError, kzg_protocol::KZGProof};
+
+//=========================================================================================
+// KZG is additively homomorphic: C(f) = Σ fᵢ * g1_Lᵢ(τ) is linear in the evaluations of f
+// so C(f) + C(g) = C(f + g) and c * C(f) = C(c * f) without knowing f or g
+// a linear relation Σ cᵢ * fᵢ = g among committed polys is then checked on the commitments alone,
+// nothing is opened, and openings of several polys at the same point combine the same way
+// (the quotients of Σ cᵢ * fᵢ are Σ cᵢ * Qᵢ)
+//=========================================================================================
+pub fn commitments_equal<P: Pairing>(a: &P::G1, b: &P::G1) -> bool {
+    a == b
+}
+
+pub fn add_commitments<P: Pairing>(a: &P::G1, b: &P::G1) -> P::G1 {
+    *a + *b
+}
+
+pub fn scale_commitment<F: PrimeField, P: Pairing>(commitment: &P::G1, scalar: F) -> P::G1 {
+    commitment.mul_bigint(scalar.into_bigint())
+}
+
+// Σ cᵢ * C(fᵢ) = C(Σ cᵢ * fᵢ)
+pub fn linear_combination<F: PrimeField, P: Pairing>(
+    commitments: &[P::G1],
+    coeffs: &[F],
+) -> Result<P::G1, KZGError> {
+    if commitments.len() != coeffs.len() {
+        return Err(KZGError::SizeMismatch {
+            expected: commitments.len(),
+            found: coeffs.len(),
+        });
+    }
+
+    let mut combined = P::G1::zero();
+    for (commitment, coeff) in commitments.iter().zip(coeffs.iter()) {
+        combined += scale_commitment::<F, P>(commitment, *coeff);
+    }
+
+    Ok(combined)
+}
+
+// true if Σ cᵢ * fᵢ = g where target is C(g), pass the zero point to check Σ cᵢ * fᵢ = 0
+pub fn verify_linear_relation<F: PrimeField, P: Pairing>(
+    commitments: &[P::G1],
+    coeffs: &[F],
+    target: &P::G1,
+) -> bool {
+    match linear_combination::<F, P>(commitments, coeffs) {
+        Ok(combined) => commitments_equal::<P>(&combined, target),
+        Err(_) => false,
+    }
+}
+
+// an opening of Σ cᵢ * fᵢ from openings of every fᵢ at the same point
+pub fn combine_proofs<F: PrimeField, P: Pairing>(
+    proofs: &[KZGProof<F, P>],
+    coeffs: &[F],
+) -> Result<KZGProof<F, P>, KZGError> {
+    if proofs.len() != coeffs.len() || proofs.is_empty() {
+        return Err(KZGError::SizeMismatch {
+            expected: proofs.len(),
+            found: coeffs.len(),
+        });
+    }
+
+    let num_vars = proofs[0].quotient_evals.len();
+    if let Some(proof) = proofs.iter().find(|p| p.quotient_evals.len() != num_vars) {
+        return Err(KZGError::SizeMismatch {
+            expected: num_vars,
+            found: proof.quotient_evals.len(),
+        });
+    }
+
+    let commitments: Vec<P::G1> = proofs.iter().map(|proof| proof.commitment).collect();
+    let quotient_evals = (0..num_vars)
+        .map(|i| {
+            let quotients: Vec<P::G1> = proofs.iter().map(|p| p.quotient_evals[i]).collect();
+            linear_combination::<F, P>(&quotients, coeffs)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let poly_opened = proofs
+        .iter()
+        .zip(coeffs.iter())
+        .map(|(proof, coeff)| proof.poly_opened * coeff)
+        .sum();
+
+    Ok(KZGProof {
+        commitment: linear_combination::<F, P>(&commitments, coeffs)?,
+        quotient_evals,
+        poly_opened,
+    })
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::{
+        kzg::{
+            kzg_helper_functions::{compute_commitment, test::poly_1},
+            kzg_protocol,
+            trusted_setup::tests::setup,
+        },
+        multi_linear::MultiLinearPoly,
+    };
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    fn poly_2() -> MultiLinearPoly<BlsFr> {
+        MultiLinearPoly::new(&[1, 5, 2, 0, 7, 3, 3, 9].map(BlsFr::from))
+    }
+
+    fn commit(poly: &MultiLinearPoly<BlsFr>) -> <Bls12_381 as Pairing>::G1 {
+        compute_commitment::<BlsFr, Bls12_381>(poly, &setup().g1_arr).unwrap()
+    }
+
+    #[test]
+    fn test_linear_relation() {
+        let (f, g) = (poly_1(), poly_2());
+        let coeffs = [BlsFr::from(3), BlsFr::from(5)];
+
+        // h = 3f + 5g
+        let h = MultiLinearPoly::new(
+            &f.computation
+                .iter()
+                .zip(g.computation.iter())
+                .map(|(f_i, g_i)| coeffs[0] * f_i + coeffs[1] * g_i)
+                .collect::<Vec<_>>(),
+        );
+
+        let commitments = [commit(&f), commit(&g)];
+        assert!(verify_linear_relation::<BlsFr, Bls12_381>(
+            &commitments,
+            &coeffs,
+            &commit(&h)
+        ));
+        assert!(!verify_linear_relation::<BlsFr, Bls12_381>(
+            &commitments,
+            &[BlsFr::from(3), BlsFr::from(4)],
+            &commit(&h)
+        ));
+        assert_eq!(
+            add_commitments::<Bls12_381>(
+                &scale_commitment::<BlsFr, Bls12_381>(&commitments[0], coeffs[0]),
+                &scale_commitment::<BlsFr, Bls12_381>(&commitments[1], coeffs[1])
+            ),
+            commit(&h)
+        );
+    }
+
+    #[test]
+    fn test_combined_opening_verifies() {
+        let setup = setup();
+        let point = [BlsFr::from(6), BlsFr::from(4), BlsFr::from(2)];
+        let coeffs = [BlsFr::from(7), BlsFr::from(2)];
+
+        let proofs = [poly_1(), poly_2()]
+            .map(|poly| kzg_protocol::proof::<BlsFr, Bls12_381>(poly, &setup.g1_arr, &point));
+        let expected = coeffs[0] * proofs[0].poly_opened + coeffs[1] * proofs[1].poly_opened;
+
+        let combined = combine_proofs(&proofs, &coeffs).unwrap();
+        assert_eq!(combined.poly_opened, expected);
+        assert!(kzg_protocol::verify(
+            &combined,
+            &setup.verifier_setup,
+            &point
+        ));
+    }
+}
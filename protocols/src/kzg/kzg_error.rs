@@ -0,0 +1,26 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KZGError {
+    // the polynomial evaluations and the encrypted lagrange basis must have the same length
+    SizeMismatch { expected: usize, found: usize },
+    // the polynomial is not zero on every point of the sub-cube it was claimed to vanish on
+    NotZeroOnSubCube,
+}
+
+impl fmt::Display for KZGError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KZGError::SizeMismatch { expected, found } => write!(
+                f,
+                "size mismatch: setup supports {} evaluations but polynomial has {}",
+                expected, found
+            ),
+            KZGError::NotZeroOnSubCube => {
+                write!(f, "the polynomial does not vanish on the sub-cube")
+            }
+        }
+    }
+}
+
+impl std::error::Error for KZGError {}
@@ -1,4 +1,4 @@
-use crate::multi_linear::MultiLinearPoly;
+use crate::{kzg::kzg_error::KZGError, multi_linear::MultiLinearPoly};
 use ark_ec::{pairing::Pairing, PrimeGroup};
 use ark_ff::{PrimeField, Zero};
 
@@ -11,14 +11,22 @@ pub enum Operator {
 pub fn compute_commitment<F: PrimeField, P: Pairing>(
     poly: &MultiLinearPoly<F>,
     encrypted_basis: &[P::G1],
-) -> P::G1 {
+) -> Result<P::G1, KZGError> {
+    // a shorter poly would index out of bounds and a longer one would be silently truncated
+    if poly.computation.len() != encrypted_basis.len() {
+        return Err(KZGError::SizeMismatch {
+            expected: encrypted_basis.len(),
+            found: poly.computation.len(),
+        });
+    }
+
     let mut commitment = P::G1::zero();
 
     for (i, e_basis) in encrypted_basis.iter().enumerate() {
         commitment += e_basis.mul_bigint(poly.computation[i].into_bigint());
     }
 
-    commitment
+    Ok(commitment)
 }
 
 pub fn compute_poly_minus_v<F: PrimeField>(
@@ -29,9 +37,8 @@ pub fn compute_poly_minus_v<F: PrimeField>(
     let v = eval_poly.computation[0];
 
     let sub_poly: Vec<F> = poly.computation.iter().map(|val| *val - v).collect();
-    let result = MultiLinearPoly::new(&sub_poly);
 
-    result
+    MultiLinearPoly::new(&sub_poly)
 }
 
 pub fn element_wise_op<F: PrimeField>(poly_a: &[F], poly_b: &[F], op: Operator) -> Vec<F> {
@@ -68,53 +75,90 @@ pub fn compute_remainder<F: PrimeField>(
     eval_point: F,
 ) -> MultiLinearPoly<F> {
     let mut this_poly = poly;
-    let remainder = this_poly.partial_evaluate(eval_point, 0);
 
-    remainder
+    this_poly.partial_evaluate(eval_point, 0)
 }
 
-pub fn blow_up<F: PrimeField>(
-    poly: MultiLinearPoly<F>,
-    blow_up_times: usize,
-) -> MultiLinearPoly<F> {
-    if !poly.computation.len().is_power_of_two() {
-        panic!("The polynomial must have a power of 2 length");
+// Summing the lagrange basis over the first variable gives the basis over the remaining ones
+// i.e. L'(k) = L(0, k) + L(1, k), so Σ(L(j) * blow_up(Q)(j)) == Σ(L'(k) * Q(k))
+// this lets the prover open Q(τ) against the folded basis without re-expanding Q
+pub fn fold_encrypted_basis<P: Pairing>(encrypted_basis: &[P::G1]) -> Vec<P::G1> {
+    fold_basis(encrypted_basis)
+}
+
+// same fold for a basis in any group, a G2 basis (kzg::g2_kzg) is folded this way too
+pub fn fold_basis<G: PrimeGroup>(encrypted_basis: &[G]) -> Vec<G> {
+    let mid = encrypted_basis.len() / 2;
+    let (first_half, second_half) = encrypted_basis.split_at(mid);
+
+    first_half
+        .iter()
+        .zip(second_half.iter())
+        .map(|(a, b)| *a + b)
+        .collect()
+}
+
+// Σ(values[i] * basis[i]), i.e. a poly given by its evaluations evaluated at τ in the basis group
+pub fn evaluate_in_basis<F: PrimeField, G: PrimeGroup>(values: &[F], encrypted_basis: &[G]) -> G {
+    if values.len() != encrypted_basis.len() {
+        panic!("The evaluations and the encrypted basis must have the same length");
     }
-    let mut poly = poly;
 
-    for _i in 0..blow_up_times {
-        poly = MultiLinearPoly {
-            computation: explode(poly),
-        };
+    let mut result = G::zero();
+    for (value, e_basis) in values.iter().zip(encrypted_basis.iter()) {
+        result += e_basis.mul_bigint(value.into_bigint());
     }
 
-    poly
+    result
 }
 
-fn explode<F: PrimeField>(poly: MultiLinearPoly<F>) -> Vec<F> {
-    let n_bits = poly.computation.len();
-    let total_combinations = 2 * n_bits;
-    let mut blown_array = Vec::with_capacity(total_combinations);
+pub fn compute_quotient_eval<F: PrimeField, P: Pairing>(
+    quotient: &MultiLinearPoly<F>,
+    folded_basis: &[P::G1],
+) -> P::G1 {
+    if quotient.computation.len() != folded_basis.len() {
+        panic!("The quotient and the folded basis must have the same length");
+    }
+
+    let mut quotient_eval = P::G1::zero();
+    for (q, e_basis) in quotient.computation.iter().zip(folded_basis.iter()) {
+        quotient_eval += e_basis.mul_bigint(q.into_bigint());
+    }
+
+    quotient_eval
+}
 
-    for _i in 0..2 {
-        for val in poly.computation.iter() {
-            blown_array.push(*val);
-        }
+// the polynomial over blow_up_times new leading variables it does not depend on
+pub fn blow_up<F: PrimeField>(
+    poly: MultiLinearPoly<F>,
+    blow_up_times: usize,
+) -> MultiLinearPoly<F> {
+    if !poly.computation.len().is_power_of_two() {
+        panic!("The polynomial must have a power of 2 length");
     }
 
-    blown_array
+    poly.extend_vars(blow_up_times, 0)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
 pub mod test {
-    use crate::{kzg::trusted_setup::tests::setup, multi_linear::MultiLinearPoly};
-    use ark_bls12_381::{Bls12_381, Fr as BlsFr, G1Affine};
-    use ark_ec::AffineRepr;
-    use ark_ff::PrimeField;
+    use crate::{
+        kzg::{
+            kzg_error::KZGError,
+            trusted_setup::{
+                insecure_taus,
+                tests::{setup, TEST_SEED},
+            },
+        },
+        multi_linear::MultiLinearPoly,
+    };
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr, G1Affine, G1Projective as G1};
+    use ark_ec::{AffineRepr, PrimeGroup};
+    use ark_ff::{PrimeField, Zero};
 
     pub fn poly_1() -> MultiLinearPoly<BlsFr> {
         // f(x) = 3ab + 4c
-        MultiLinearPoly::new(&vec![
+        MultiLinearPoly::new(&[
             BlsFr::from(0),
             BlsFr::from(4),
             BlsFr::from(0),
@@ -130,7 +174,7 @@ pub mod test {
     fn test_compute_quotient() {
         let poly = poly_1();
         let result = super::compute_quotient(&poly);
-        let expected = MultiLinearPoly::new(&vec![
+        let expected = MultiLinearPoly::new(&[
             BlsFr::from(0),
             BlsFr::from(0),
             BlsFr::from(3),
@@ -145,13 +189,35 @@ pub mod test {
         let g1_generator = G1Affine::generator();
         let poly = poly_1();
 
-        let result = super::compute_commitment::<BlsFr, Bls12_381>(&poly, &trusted_setup.g1_arr);
+        let result =
+            super::compute_commitment::<BlsFr, Bls12_381>(&poly, &trusted_setup.g1_arr).unwrap();
 
-        let commitment = g1_generator.mul_bigint(BlsFr::from(42).into_bigint());
+        // the commitment is g1_f(τ)
+        let f_tau = poly
+            .clone()
+            .evaluate(&insecure_taus(TEST_SEED, 3))
+            .computation[0];
+        let commitment = g1_generator.mul_bigint(f_tau.into_bigint());
 
         assert_eq!(result, commitment);
     }
 
+    #[test]
+    fn test_compute_commitment_size_mismatch() {
+        let trusted_setup = setup();
+        let poly = MultiLinearPoly::new(&[BlsFr::from(3), BlsFr::from(4)]);
+
+        let result = super::compute_commitment::<BlsFr, Bls12_381>(&poly, &trusted_setup.g1_arr);
+
+        assert_eq!(
+            result,
+            Err(KZGError::SizeMismatch {
+                expected: 8,
+                found: 2
+            })
+        );
+    }
+
     #[test]
     fn test_test_compute_poly_minus_v() {
         let poly = poly_1();
@@ -188,9 +254,28 @@ pub mod test {
         assert_eq!(remainder, result.computation);
     }
 
+    #[test]
+    fn test_quotient_eval_matches_blow_up() {
+        let trusted_setup = setup();
+        let poly = poly_1();
+        let quotient = super::compute_quotient(&poly);
+
+        let blown_quotient = super::blow_up(quotient.clone(), 1);
+        let mut expected = G1::zero();
+        for (j, e_basis) in trusted_setup.g1_arr.iter().enumerate() {
+            expected += e_basis.mul_bigint(blown_quotient.computation[j].into_bigint());
+        }
+
+        let folded_basis = super::fold_encrypted_basis::<Bls12_381>(&trusted_setup.g1_arr);
+        let result = super::compute_quotient_eval::<BlsFr, Bls12_381>(&quotient, &folded_basis);
+
+        assert_eq!(folded_basis.len(), 4);
+        assert_eq!(result, expected);
+    }
+
     #[test]
     fn test_blow_up() {
-        let poly = MultiLinearPoly::new(&vec![BlsFr::from(3), BlsFr::from(4)]);
+        let poly = MultiLinearPoly::new(&[BlsFr::from(3), BlsFr::from(4)]);
         let result = super::blow_up(poly, 1);
         let new_poly = vec![
             BlsFr::from(3),
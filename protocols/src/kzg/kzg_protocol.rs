@@ -1,14 +1,22 @@
 use ark_ec::{
     pairing::{Pairing, PairingOutput},
-    PrimeGroup,
+    CurveGroup, PrimeGroup,
 };
-use ark_ff::{AdditiveGroup, PrimeField, Zero};
+use ark_ff::{AdditiveGroup, PrimeField};
+use ark_serialize::CanonicalSerialize;
 
 use crate::{
+    calldata::{abi_encode, AbiValue},
     kzg::kzg_helper_functions::{
-        blow_up, compute_commitment, compute_poly_minus_v, compute_quotient, compute_remainder,
+        compute_commitment, compute_poly_minus_v, compute_quotient, compute_quotient_eval,
+        compute_remainder, fold_encrypted_basis,
     },
+    kzg::trusted_setup::VerifierSetup,
     multi_linear::MultiLinearPoly,
+    proof_format::{
+        ensure_consumed, read_header, read_point, read_points, write_header, DecodeLimits,
+        ProofFormatError, ProtocolId,
+    },
 };
 
 pub struct KZGProof<F: PrimeField, P: Pairing> {
@@ -17,33 +25,80 @@ pub struct KZGProof<F: PrimeField, P: Pairing> {
     pub poly_opened: F,
 }
 
+impl<F: PrimeField, P: Pairing> KZGProof<F, P> {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofFormatError> {
+        let mut bytes = Vec::new();
+        write_header(ProtocolId::Kzg, &mut bytes);
+
+        self.commitment.serialize_compressed(&mut bytes)?;
+        self.quotient_evals.serialize_compressed(&mut bytes)?;
+        self.poly_opened.serialize_compressed(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    pub fn from_bytes_with_limits(
+        mut bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        limits.check_size(bytes)?;
+        read_header(&mut bytes, ProtocolId::Kzg)?;
+
+        let commitment = read_point::<P::G1>(&mut bytes)?;
+        let quotient_evals = read_points::<P::G1>(&mut bytes, limits)?;
+        let poly_opened = F::deserialize_compressed(&mut bytes)?;
+        ensure_consumed(bytes)?;
+
+        Ok(KZGProof {
+            commitment,
+            quotient_evals,
+            poly_opened,
+        })
+    }
+
+    // abi.encode(commitment, quotient_evals, poly_opened) as
+    // (uint256[2] commitment, uint256[2][] quotients, uint256 value), see crate::calldata
+    pub fn to_calldata(&self) -> Vec<u8>
+    where
+        <P::G1 as CurveGroup>::BaseField: PrimeField,
+    {
+        abi_encode(&[
+            AbiValue::point(&self.commitment),
+            AbiValue::points(self.quotient_evals.iter()),
+            AbiValue::field(&self.poly_opened),
+        ])
+    }
+}
+
 pub fn proof<F: PrimeField, P: Pairing>(
     mut poly: MultiLinearPoly<F>,
     encrypted_basis: &[P::G1],
     vars_to_open: &[F],
 ) -> KZGProof<F, P> {
     // Since we can't evaluate Q(x) at tau as we don't know tau,
-    // we need to first blow up Q_a(b, c) or Q_b(c) back to Q(a, b, c)
-    // Then we mul and add of Q(a, b, c) with the lagrange_basis
+    // we mul and add Q_a(b, c) or Q_b(c) with the lagrange basis folded down to the same variables
+    // (same result as blowing Q back up to Q(a, b, c) without the extra allocations)
 
-    let mut quotient_evals = Vec::new();
+    let mut quotient_evals = Vec::with_capacity(vars_to_open.len());
     let v = poly.evaluate(vars_to_open).computation[0];
 
-    let commitment = compute_commitment::<F, P>(&poly, encrypted_basis);
+    let commitment = compute_commitment::<F, P>(&poly, encrypted_basis)
+        .expect("Polynomial size does not match the trusted setup");
 
     let mut poly_minus_v = compute_poly_minus_v(poly, vars_to_open);
+    let mut folded_basis = encrypted_basis.to_vec();
 
-    for i in 0..(vars_to_open.len()) {
+    for var in vars_to_open {
         let quotient = compute_quotient(&poly_minus_v);
-        let blown_quotient = blow_up(quotient, i + 1);
+        folded_basis = fold_encrypted_basis::<P>(&folded_basis);
 
-        let mut quotient_eval = P::G1::zero();
-        for (j, e_basis) in encrypted_basis.iter().enumerate() {
-            quotient_eval += e_basis.mul_bigint(blown_quotient.computation[j].into_bigint());
-        }
-        quotient_evals.push(quotient_eval);
+        quotient_evals.push(compute_quotient_eval::<F, P>(&quotient, &folded_basis));
 
-        let remainder = compute_remainder(poly_minus_v, vars_to_open[i]);
+        let remainder = compute_remainder(poly_minus_v, *var);
         poly_minus_v = remainder;
     }
     assert_eq!(poly_minus_v.computation[0], F::zero());
@@ -62,19 +117,19 @@ pub fn proof<F: PrimeField, P: Pairing>(
 // f(τ) ==> commitment; v ==> poly opened at (a, b, c, ... n)
 //=============================================================================
 pub fn verify<F: PrimeField, P: Pairing>(
-    proof: KZGProof<F, P>,
-    encrypted_taus: Vec<P::G2>,
+    proof: &KZGProof<F, P>,
+    verifier_setup: &VerifierSetup<P>,
     vars_to_open: &[F],
 ) -> bool {
-    let g1_generator = P::G1::generator();
-    let g2_generator = P::G2::generator();
+    let g1_generator = verifier_setup.g1_generator;
+    let g2_generator = verifier_setup.g2_generator;
 
-    let quotient_evals = proof.quotient_evals;
+    let quotient_evals = &proof.quotient_evals;
     let commitment = proof.commitment;
     let v = proof.poly_opened;
 
     let mut rhs = PairingOutput::ZERO;
-    for (i, tau) in encrypted_taus.iter().enumerate() {
+    for (i, tau) in verifier_setup.g2_arr.iter().enumerate() {
         rhs += P::pairing(
             quotient_evals[i],
             *tau - g2_generator.mul_bigint(vars_to_open[i].into_bigint()),
@@ -89,7 +144,7 @@ pub fn verify<F: PrimeField, P: Pairing>(
     lhs == rhs
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
 mod test {
     use crate::kzg::{kzg_helper_functions::test::poly_1, trusted_setup::tests::setup};
 
@@ -113,8 +168,81 @@ mod test {
         let vars_to_open = vec![BlsFr::from(6), BlsFr::from(4), BlsFr::from(0)];
 
         let proof = proof::<BlsFr, Bls12_381>(poly, &setup.g1_arr, &vars_to_open);
-        let result = verify::<BlsFr, Bls12_381>(proof, setup.g2_arr, &vars_to_open);
-        assert_eq!(result, true);
+        let result = verify::<BlsFr, Bls12_381>(&proof, &setup.verifier_setup, &vars_to_open);
+        assert!(result);
+    }
+
+    #[test]
+    fn test_proof_bytes_roundtrip() {
+        let setup = setup();
+        let poly = poly_1();
+        let vars_to_open = vec![BlsFr::from(6), BlsFr::from(4), BlsFr::from(0)];
+
+        let proof = proof::<BlsFr, Bls12_381>(poly, &setup.g1_arr, &vars_to_open);
+        let bytes = proof.to_bytes().unwrap();
+        assert_eq!(bytes[0], crate::proof_format::PROOF_FORMAT_VERSION);
+
+        let decoded = KZGProof::<BlsFr, Bls12_381>::from_bytes(&bytes).unwrap();
+        let result = verify::<BlsFr, Bls12_381>(&decoded, &setup.verifier_setup, &vars_to_open);
+        assert!(result);
+    }
+
+    #[test]
+    fn test_proof_bytes_rejects_unknown_version() {
+        let setup = setup();
+        let vars_to_open = vec![BlsFr::from(6), BlsFr::from(4), BlsFr::from(0)];
+
+        let proof = proof::<BlsFr, Bls12_381>(poly_1(), &setup.g1_arr, &vars_to_open);
+        let mut bytes = proof.to_bytes().unwrap();
+        bytes[0] += 1;
+
+        let result = KZGProof::<BlsFr, Bls12_381>::from_bytes(&bytes);
+        assert!(matches!(
+            result,
+            Err(ProofFormatError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_proof_bytes_rejects_a_commitment_off_the_curve() {
+        let setup = setup();
+        let vars_to_open = vec![BlsFr::from(6), BlsFr::from(4), BlsFr::from(0)];
+
+        let proof = proof::<BlsFr, Bls12_381>(poly_1(), &setup.g1_arr, &vars_to_open);
+        let mut bytes = proof.to_bytes().unwrap();
+        let point_len = proof.commitment.compressed_size();
+        bytes[2..2 + point_len].fill(0xff);
+
+        assert!(KZGProof::<BlsFr, Bls12_381>::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_proof_calldata() {
+        use crate::calldata::{field_words, point_word_count, point_words, uint_word, WORD_LEN};
+        type G1 = <Bls12_381 as Pairing>::G1;
+
+        let setup = setup();
+        let vars_to_open = vec![BlsFr::from(6), BlsFr::from(4), BlsFr::from(0)];
+        let proof = proof::<BlsFr, Bls12_381>(poly_1(), &setup.g1_arr, &vars_to_open);
+        let calldata = proof.to_calldata();
+
+        // head: the commitment, the quotients' offset, the value; tail: 3 quotients
+        let point_len = point_word_count::<G1>() * WORD_LEN;
+        let head_len = point_len + 2 * WORD_LEN;
+        assert_eq!(calldata.len(), head_len + WORD_LEN + 3 * point_len);
+        assert_eq!(
+            calldata[..point_len],
+            point_words(&proof.commitment).concat()
+        );
+        assert_eq!(
+            calldata[point_len..point_len + WORD_LEN],
+            uint_word(head_len as u64)
+        );
+        assert_eq!(
+            calldata[point_len + WORD_LEN..head_len],
+            field_words(&proof.poly_opened).concat()
+        );
+        assert_eq!(calldata[head_len..head_len + WORD_LEN], uint_word(3));
     }
 
     #[test]
@@ -136,7 +264,7 @@ mod test {
         let vars_to_open = vec![BlsFr::from(6), BlsFr::from(4), BlsFr::from(0)];
 
         let proof = proof::<BlsFr, Bls12_381>(poly, &setup.g1_arr, &vars_to_open);
-        let result = verify::<BlsFr, Bls12_381>(proof, setup.g2_arr, &vars_to_open);
-        assert_eq!(result, true);
+        let result = verify::<BlsFr, Bls12_381>(&proof, &setup.verifier_setup, &vars_to_open);
+        assert!(result);
     }
 }
@@ -0,0 +1,103 @@
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    PrimeGroup,
+};
+use ark_ff::{AdditiveGroup, PrimeField};
+
+use crate::kzg::{kzg_protocol::KZGProof, trusted_setup::VerifierSetup};
+
+//=============================================================================
+// KZG verifier with the SRS prepared once and reused for every opening
+// the check of kzg_protocol::verify is rearranged so every G2 point is fixed:
+// e(f(τ) - v + Σ aᵢQᵢ(τ), g2) == Σ e(Qᵢ(τ), g2_τᵢ)
+// so g2 and every g2_τᵢ go through the pairing preparation (line coefficients of the
+// Miller loop) in new(), and each proof costs one multi pairing on already prepared points
+// instead of n + 1 pairings with fresh G2 arithmetic
+//=============================================================================
+pub struct PreparedVerifier<P: Pairing> {
+    g1_generator: P::G1,
+    g2_generator: P::G2Prepared,
+    g2_arr: Vec<P::G2Prepared>,
+}
+
+impl<P: Pairing> PreparedVerifier<P> {
+    pub fn new(verifier_setup: &VerifierSetup<P>) -> Self {
+        PreparedVerifier {
+            g1_generator: verifier_setup.g1_generator,
+            g2_generator: P::G2Prepared::from(verifier_setup.g2_generator),
+            g2_arr: verifier_setup
+                .g2_arr
+                .iter()
+                .map(|tau| P::G2Prepared::from(*tau))
+                .collect(),
+        }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.g2_arr.len()
+    }
+
+    // same answer as kzg_protocol::verify, but a proof or point of the wrong size is a false
+    pub fn verify<F: PrimeField>(&self, proof: &KZGProof<F, P>, vars_to_open: &[F]) -> bool {
+        if proof.quotient_evals.len() != self.num_vars() || vars_to_open.len() != self.num_vars() {
+            return false;
+        }
+
+        let mut lhs = proof.commitment
+            - self
+                .g1_generator
+                .mul_bigint(proof.poly_opened.into_bigint());
+        for (quotient, a) in proof.quotient_evals.iter().zip(vars_to_open.iter()) {
+            lhs += quotient.mul_bigint(a.into_bigint());
+        }
+
+        // e(lhs, g2) - Σ e(Qᵢ(τ), g2_τᵢ) == 0 as a single multi pairing
+        let g1_points = std::iter::once(lhs).chain(proof.quotient_evals.iter().map(|q| -*q));
+        let g2_points = std::iter::once(&self.g2_generator)
+            .chain(self.g2_arr.iter())
+            .cloned();
+
+        P::multi_pairing(g1_points, g2_points) == PairingOutput::ZERO
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::kzg::{
+        kzg_helper_functions::test::poly_1, kzg_protocol, trusted_setup::tests::setup,
+    };
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    #[test]
+    fn test_prepared_verifier_matches_verify() {
+        let setup = setup();
+        let verifier = PreparedVerifier::new(&setup.verifier_setup);
+
+        for point in [[6, 4, 0], [1, 2, 3], [0, 0, 0]] {
+            let point = point.map(BlsFr::from);
+            let proof = kzg_protocol::proof::<BlsFr, Bls12_381>(poly_1(), &setup.g1_arr, &point);
+
+            assert!(verifier.verify(&proof, &point));
+            assert!(!verifier.verify(&proof, &[point[0], point[1], point[2] + BlsFr::from(1)]));
+            assert!(kzg_protocol::verify(&proof, &setup.verifier_setup, &point));
+        }
+    }
+
+    #[test]
+    fn test_prepared_verifier_rejects_bad_proofs() {
+        let setup = setup();
+        let verifier = PreparedVerifier::new(&setup.verifier_setup);
+        let point = [6, 4, 0].map(BlsFr::from);
+
+        let mut proof = kzg_protocol::proof::<BlsFr, Bls12_381>(poly_1(), &setup.g1_arr, &point);
+        proof.poly_opened += BlsFr::from(1);
+        assert!(!verifier.verify(&proof, &point));
+
+        // one quotient short would make kzg_protocol::verify index out of bounds
+        let mut proof = kzg_protocol::proof::<BlsFr, Bls12_381>(poly_1(), &setup.g1_arr, &point);
+        proof.quotient_evals.pop();
+        assert!(!verifier.verify(&proof, &point));
+        assert!(!verifier.verify(&proof, &point[..2]));
+    }
+}
@@ -0,0 +1,248 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+
+use crate::{
+    eval_claim::EvalClaim,
+    kzg::{
+        batch_kzg::{self, BatchKZGProof},
+        kzg_error::KZGError,
+        kzg_helper_functions::compute_commitment,
+        trusted_setup::VerifierSetup,
+    },
+    multi_linear::MultiLinearPoly,
+    transcript::{Transcript, TranscriptDomain},
+};
+
+//=============================================================================
+// Two-level KZG for polys with more variables than the trusted setup
+// f(x_hi, x_lo) is cut into segments fⱼ(x_lo) = f(j, x_lo), each the size of the setup
+// (MSB-first, so segment j is just the j-th chunk of the evaluations)
+// f(r_hi, r_lo) = Σ eq(j, r_hi) * fⱼ(r_lo) = MLE of [f₀(r_lo), f₁(r_lo), ...] at r_hi
+// the prover sends every fⱼ(r_lo) and batch opens the segments at r_lo,
+// the verifier recombines the segment values at r_hi by itself
+// a single segment is the plain KZG commitment and opening
+//=============================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitCommitment<P: Pairing> {
+    pub segment_commitments: Vec<P::G1>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SplitKZGProof<F: PrimeField, P: Pairing> {
+    pub segment_evals: Vec<F>, // fⱼ(r_lo) for every segment
+    pub quotient_evals: Vec<P::G1>,
+}
+
+impl<P: Pairing> SplitCommitment<P> {
+    pub fn num_segments(&self) -> usize {
+        self.segment_commitments.len()
+    }
+
+    pub fn absorb_into(&self, transcript: &mut Transcript) {
+        for commitment in self.segment_commitments.iter() {
+            transcript.absorb_commitment(commitment);
+        }
+    }
+}
+
+// the poly must be at least as large as the setup, both are powers of 2
+pub fn commit<F: PrimeField, P: Pairing>(
+    poly: &MultiLinearPoly<F>,
+    encrypted_basis: &[P::G1],
+) -> Result<SplitCommitment<P>, KZGError> {
+    if poly.computation.len() < encrypted_basis.len() {
+        return Err(KZGError::SizeMismatch {
+            expected: encrypted_basis.len(),
+            found: poly.computation.len(),
+        });
+    }
+
+    let segment_commitments = segments(poly, encrypted_basis.len())
+        .iter()
+        .map(|segment| compute_commitment::<F, P>(segment, encrypted_basis))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(SplitCommitment {
+        segment_commitments,
+    })
+}
+
+pub fn open<F: PrimeField, P: Pairing>(
+    poly: &MultiLinearPoly<F>,
+    commitment: &SplitCommitment<P>,
+    encrypted_basis: &[P::G1],
+    vars_to_open: &[F],
+    transcript_domain: &TranscriptDomain,
+) -> SplitKZGProof<F, P> {
+    let segment_polys = segments(poly, encrypted_basis.len());
+    if segment_polys.len() != commitment.num_segments() {
+        panic!("The commitment does not have one segment per setup sized chunk of the poly");
+    }
+
+    let (_, r_lo) = split_point(vars_to_open, segment_polys.len());
+    let batch_proof = batch_kzg::batch_open_committed::<F, P>(
+        &segment_polys,
+        commitment.segment_commitments.clone(),
+        encrypted_basis,
+        r_lo,
+        transcript_domain,
+    );
+
+    SplitKZGProof {
+        segment_evals: batch_proof.polys_opened,
+        quotient_evals: batch_proof.quotient_evals,
+    }
+}
+
+// true if f(vars_to_open) == poly_opened for the committed f
+pub fn verify<F: PrimeField, P: Pairing>(
+    commitment: &SplitCommitment<P>,
+    proof: &SplitKZGProof<F, P>,
+    verifier_setup: &VerifierSetup<P>,
+    vars_to_open: &[F],
+    poly_opened: F,
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    let num_segments = commitment.num_segments();
+    if !num_segments.is_power_of_two()
+        || proof.segment_evals.len() != num_segments
+        || proof.quotient_evals.len() != verifier_setup.max_num_vars()
+        || vars_to_open.len() != num_segments.ilog2() as usize + verifier_setup.max_num_vars()
+    {
+        return false;
+    }
+
+    let (r_hi, r_lo) = split_point(vars_to_open, num_segments);
+    let recombined = MultiLinearPoly::new(&proof.segment_evals)
+        .evaluate(r_hi)
+        .computation[0];
+    if recombined != poly_opened {
+        return false;
+    }
+
+    let batch_proof = BatchKZGProof::<F, P> {
+        commitments: commitment.segment_commitments.clone(),
+        polys_opened: proof.segment_evals.clone(),
+        quotient_evals: proof.quotient_evals.clone(),
+    };
+
+    batch_kzg::batch_verify::<F, P>(&batch_proof, verifier_setup, r_lo, transcript_domain)
+}
+
+// verify for an evaluation claim, e.g. one a GKR verifier ended on for the committed inputs
+pub fn verify_claim<F: PrimeField, P: Pairing>(
+    commitment: &SplitCommitment<P>,
+    proof: &SplitKZGProof<F, P>,
+    verifier_setup: &VerifierSetup<P>,
+    claim: &EvalClaim<F>,
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    verify(
+        commitment,
+        proof,
+        verifier_setup,
+        &claim.point,
+        claim.value,
+        transcript_domain,
+    )
+}
+
+fn segments<F: PrimeField>(
+    poly: &MultiLinearPoly<F>,
+    segment_len: usize,
+) -> Vec<MultiLinearPoly<F>> {
+    poly.computation
+        .chunks(segment_len)
+        .map(MultiLinearPoly::new)
+        .collect()
+}
+
+// the first log2(num_segments) variables pick the segment, the rest are opened inside it
+fn split_point<F: PrimeField>(vars_to_open: &[F], num_segments: usize) -> (&[F], &[F]) {
+    vars_to_open.split_at(num_segments.ilog2() as usize)
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::{kzg::trusted_setup::tests::setup, transcript::test::domain};
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    fn poly_32() -> MultiLinearPoly<BlsFr> {
+        let evals: Vec<BlsFr> = (0..32u64).map(|i| BlsFr::from(i * i + 3)).collect();
+        MultiLinearPoly::new(&evals)
+    }
+
+    #[test]
+    fn test_split_open_larger_than_setup() {
+        let setup = setup(); // 3 variables
+        let mut poly = poly_32(); // 5 variables => 4 segments
+        let vars_to_open = [2, 7, 6, 4, 9].map(BlsFr::from);
+        let v = poly.evaluate(&vars_to_open).computation[0];
+
+        let commitment = commit::<BlsFr, Bls12_381>(&poly, &setup.g1_arr).unwrap();
+        assert_eq!(commitment.num_segments(), 4);
+
+        let proof = open(&poly, &commitment, &setup.g1_arr, &vars_to_open, &domain());
+        assert!(verify(
+            &commitment,
+            &proof,
+            &setup.verifier_setup,
+            &vars_to_open,
+            v,
+            &domain()
+        ));
+
+        let proof = open(&poly, &commitment, &setup.g1_arr, &vars_to_open, &domain());
+        assert!(!verify(
+            &commitment,
+            &proof,
+            &setup.verifier_setup,
+            &vars_to_open,
+            v + BlsFr::from(1),
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_split_rejects_tampered_segment() {
+        let setup = setup();
+        let mut poly = poly_32();
+        let vars_to_open = [2, 7, 6, 4, 9].map(BlsFr::from);
+        let v = poly.evaluate(&vars_to_open).computation[0];
+
+        let commitment = commit::<BlsFr, Bls12_381>(&poly, &setup.g1_arr).unwrap();
+        let mut proof = open(&poly, &commitment, &setup.g1_arr, &vars_to_open, &domain());
+        assert_eq!(proof.segment_evals.len(), 4);
+
+        // a tampered segment value recombined honestly must still fail the batch opening
+        proof.segment_evals[1] += BlsFr::from(1);
+        let tampered_v = MultiLinearPoly::new(&proof.segment_evals)
+            .evaluate(&vars_to_open[..2])
+            .computation[0];
+        assert_ne!(tampered_v, v);
+        assert!(!verify(
+            &commitment,
+            &proof,
+            &setup.verifier_setup,
+            &vars_to_open,
+            tampered_v,
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_single_segment_is_plain_kzg() {
+        let setup = setup();
+        let poly = MultiLinearPoly::new(&[1, 2, 3, 4, 5, 6, 7, 8].map(BlsFr::from));
+
+        let commitment = commit::<BlsFr, Bls12_381>(&poly, &setup.g1_arr).unwrap();
+        assert_eq!(
+            commitment.segment_commitments,
+            vec![compute_commitment::<BlsFr, Bls12_381>(&poly, &setup.g1_arr).unwrap()]
+        );
+
+        let small = MultiLinearPoly::new(&[1, 2].map(BlsFr::from));
+        assert!(commit::<BlsFr, Bls12_381>(&small, &setup.g1_arr).is_err());
+    }
+}
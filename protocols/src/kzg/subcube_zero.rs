@@ -0,0 +1,165 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+
+use crate::{
+    kzg::kzg_error::KZGError,
+    kzg::kzg_helper_functions::compute_commitment,
+    kzg::kzg_protocol::{self, KZGProof},
+    kzg::trusted_setup::VerifierSetup,
+    multi_linear::MultiLinearPoly,
+    transcript::TranscriptDomain,
+};
+
+//=========================================================================================
+// Zero on a sub-cube with a single opening
+// the sub-cube fixes some variables to bits and leaves the others free, f vanishes on all of it
+// iff g(y) = f(fixed, y) is the zero multilinear polynomial, and a nonzero g is 0 at a random
+// point with probability at most (#free vars) / |F|
+// so the prover opens f at (fixed, r) with r from the transcript (commitment + sub-cube) and
+// the verifier checks that the opening is a valid KZG opening of 0
+// e.g. padding: every padded row is 0, selectors: a column is 0 wherever the selector is off
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubCube {
+    pub fixed: Vec<Option<bool>>, // one entry per variable (MSB-first), None is a free variable
+}
+
+impl SubCube {
+    pub fn new(fixed: Vec<Option<bool>>) -> Self {
+        SubCube { fixed }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.fixed.len()
+    }
+
+    // 0 free, 1 fixed to 0, 2 fixed to 1
+    fn to_bytes(&self) -> Vec<u8> {
+        self.fixed
+            .iter()
+            .map(|bit| match bit {
+                None => 0,
+                Some(false) => 1,
+                Some(true) => 2,
+            })
+            .collect()
+    }
+
+    // (fixed, r) with the free variables drawn after the commitment is absorbed
+    fn opening_point<F: PrimeField, P: Pairing>(
+        &self,
+        commitment: &P::G1,
+        transcript_domain: &TranscriptDomain,
+    ) -> Vec<F> {
+        let mut transcript = transcript_domain.transcript();
+        transcript.absorb_commitment(commitment);
+        transcript.absorb(&self.to_bytes());
+
+        self.fixed
+            .iter()
+            .map(|bit| match bit {
+                Some(bit) => F::from(*bit),
+                None => transcript.squeeze_field::<F>(),
+            })
+            .collect()
+    }
+}
+
+pub fn prove_zero_on_subcube<F: PrimeField, P: Pairing>(
+    poly: &MultiLinearPoly<F>,
+    encrypted_basis: &[P::G1],
+    subcube: &SubCube,
+    transcript_domain: &TranscriptDomain,
+) -> Result<KZGProof<F, P>, KZGError> {
+    let commitment = compute_commitment::<F, P>(poly, encrypted_basis)?;
+    let point = subcube.opening_point::<F, P>(&commitment, transcript_domain);
+
+    let proof = kzg_protocol::proof::<F, P>(poly.clone(), encrypted_basis, &point);
+    if !proof.poly_opened.is_zero() {
+        return Err(KZGError::NotZeroOnSubCube);
+    }
+
+    Ok(proof)
+}
+
+pub fn verify_zero_on_subcube<F: PrimeField, P: Pairing>(
+    proof: &KZGProof<F, P>,
+    verifier_setup: &VerifierSetup<P>,
+    subcube: &SubCube,
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    if subcube.num_vars() != verifier_setup.g2_arr.len()
+        || proof.quotient_evals.len() != subcube.num_vars()
+        || !proof.poly_opened.is_zero()
+    {
+        return false;
+    }
+
+    let point = subcube.opening_point::<F, P>(&proof.commitment, transcript_domain);
+
+    kzg_protocol::verify(proof, verifier_setup, &point)
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::{
+        kzg::{kzg_helper_functions::test::poly_1, trusted_setup::tests::setup},
+        transcript::test::domain,
+    };
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    #[test]
+    fn test_zero_on_subcube() {
+        let setup = setup();
+        // 3ab + 4c vanishes wherever a = 0 and c = 0
+        let subcube = SubCube::new(vec![Some(false), None, Some(false)]);
+
+        let proof = prove_zero_on_subcube::<BlsFr, Bls12_381>(
+            &poly_1(),
+            &setup.g1_arr,
+            &subcube,
+            &domain(),
+        )
+        .unwrap();
+        assert!(verify_zero_on_subcube(
+            &proof,
+            &setup.verifier_setup,
+            &subcube,
+            &domain()
+        ));
+    }
+
+    #[test]
+    fn test_not_zero_on_subcube() {
+        let setup = setup();
+        // 3ab + 4c is 3 at (1, 1, 0)
+        let subcube = SubCube::new(vec![Some(true), None, Some(false)]);
+
+        assert_eq!(
+            prove_zero_on_subcube::<BlsFr, Bls12_381>(
+                &poly_1(),
+                &setup.g1_arr,
+                &subcube,
+                &domain()
+            )
+            .err(),
+            Some(KZGError::NotZeroOnSubCube)
+        );
+
+        // a proof for one sub-cube says nothing about another
+        let proof = prove_zero_on_subcube::<BlsFr, Bls12_381>(
+            &poly_1(),
+            &setup.g1_arr,
+            &SubCube::new(vec![Some(false), None, Some(false)]),
+            &domain(),
+        )
+        .unwrap();
+        assert!(!verify_zero_on_subcube(
+            &proof,
+            &setup.verifier_setup,
+            &SubCube::new(vec![Some(false), None, None]),
+            &domain()
+        ));
+    }
+}
@@ -1,16 +1,36 @@
 use ark_ec::{pairing::Pairing, PrimeGroup};
 use ark_ff::PrimeField;
+use ark_serialize::{CanonicalSerialize, SerializationError};
+#[cfg(feature = "prover")]
+use {ark_ff::UniformRand, rand::Rng};
 
+use crate::{
+    gkr::gkr_circuit::Circuit,
+    hypercube::{index_to_point, BitOrder},
+    kzg::kzg_helper_functions::fold_encrypted_basis,
+    proof_format::{ensure_consumed, read_point, read_points, DecodeLimits, ProofFormatError},
+    transcript::{public_digest, Transcript, DIGEST_LEN},
+};
+
+// The prover needs the full encrypted lagrange basis (2^n G1 points)
+// the verifier only needs the n encrypted taus and the generators, so that part lives on its own
 #[derive(Debug)]
 pub struct TrustedSetup<P: Pairing> {
     pub max_input: usize,
     pub g1_arr: Vec<P::G1>,
+    pub verifier_setup: VerifierSetup<P>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifierSetup<P: Pairing> {
+    pub g1_generator: P::G1,
+    pub g2_generator: P::G2,
     pub g2_arr: Vec<P::G2>,
 }
 
 pub fn initialize<F: PrimeField, P: Pairing>(tau_arr: &[F]) -> TrustedSetup<P> {
     let max_arr_size = tau_arr.len();
-    let lagrange_basis_arr = compute_lagrange_basis(&tau_arr);
+    let lagrange_basis_arr = compute_lagrange_basis(tau_arr);
 
     let g1_generator = P::G1::generator();
     let g2_generator = P::G2::generator();
@@ -27,40 +47,157 @@ pub fn initialize<F: PrimeField, P: Pairing>(tau_arr: &[F]) -> TrustedSetup<P> {
     TrustedSetup {
         max_input: max_arr_size,
         g1_arr: encrypted_basis_poly,
-        g2_arr: encrypted_taus,
+        verifier_setup: VerifierSetup {
+            g1_generator,
+            g2_generator,
+            g2_arr: encrypted_taus,
+        },
+    }
+}
+
+//=========================================================================================
+// INSECURE deterministic setup for tests and benchmarks
+// the taus are squeezed from a transcript seeded with `seed`, so anyone who knows the seed
+// knows τ and can open a commitment to anything, never use it for real proofs
+// (a real setup comes out of kzg::ceremony)
+//=========================================================================================
+pub fn insecure_taus<F: PrimeField>(seed: u64, num_vars: usize) -> Vec<F> {
+    let mut transcript = Transcript::new();
+    transcript.absorb(b"INSECURE kzg test setup");
+    transcript.absorb(&seed.to_be_bytes());
+
+    (0..num_vars)
+        .map(|_| transcript.squeeze_field::<F>())
+        .collect()
+}
+
+impl<P: Pairing> TrustedSetup<P> {
+    // INSECURE, see insecure_taus
+    pub fn insecure_from_seed(seed: u64, num_vars: usize) -> Self {
+        initialize::<P::ScalarField, P>(&insecure_taus(seed, num_vars))
+    }
+
+    // fresh taus from rng, exactly as many variables as the circuit's input layer
+    // the taus are dropped when this returns, anyone who kept them could forge openings
+    #[cfg(feature = "prover")]
+    pub fn for_circuit<R: Rng>(circuit: &Circuit<P::ScalarField>, rng: &mut R) -> Self {
+        let taus: Vec<P::ScalarField> = (0..circuit.input_num_vars())
+            .map(|_| P::ScalarField::rand(rng))
+            .collect();
+
+        initialize::<P::ScalarField, P>(&taus)
+    }
+
+    // the trim of a bigger (universal) setup that fits the circuit's input layer
+    pub fn trim_for_circuit(&self, circuit: &Circuit<P::ScalarField>) -> TrustedSetup<P> {
+        self.trim(circuit.input_num_vars())
+    }
+
+    // number of variables the setup was generated for i.e. it commits to polys of 2^max_num_vars evaluations
+    pub fn max_num_vars(&self) -> usize {
+        self.max_input
+    }
+
+    // setup for polys over fewer variables, keeps the last num_vars taus
+    // folding the basis halves drops the first variable: (1 - τ₁)L'(τ') + τ₁L'(τ') = L'(τ')
+    pub fn trim(&self, num_vars: usize) -> TrustedSetup<P> {
+        if num_vars > self.max_input {
+            panic!("Cannot trim a setup to more variables than it was generated for");
+        }
+
+        let mut g1_arr = self.g1_arr.clone();
+        for _ in num_vars..self.max_input {
+            g1_arr = fold_encrypted_basis::<P>(&g1_arr);
+        }
+
+        TrustedSetup {
+            max_input: num_vars,
+            g1_arr,
+            verifier_setup: self.verifier_setup.trim(num_vars),
+        }
+    }
+}
+
+impl<P: Pairing> VerifierSetup<P> {
+    pub fn max_num_vars(&self) -> usize {
+        self.g2_arr.len()
+    }
+
+    pub fn trim(&self, num_vars: usize) -> VerifierSetup<P> {
+        if num_vars > self.g2_arr.len() {
+            panic!("Cannot trim a setup to more variables than it was generated for");
+        }
+
+        VerifierSetup {
+            g1_generator: self.g1_generator,
+            g2_generator: self.g2_generator,
+            g2_arr: self.g2_arr[self.g2_arr.len() - num_vars..].to_vec(),
+        }
+    }
+
+    // identifies the setup, proofs carry it so they can't be checked against another setup
+    // of the same size
+    pub fn digest(&self) -> [u8; DIGEST_LEN] {
+        public_digest(&self.to_bytes().expect("Serializing into a Vec cannot fail"))
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        let mut bytes = Vec::new();
+        self.g1_generator.serialize_compressed(&mut bytes)?;
+        self.g2_generator.serialize_compressed(&mut bytes)?;
+        self.g2_arr.serialize_compressed(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    pub fn from_bytes_with_limits(
+        mut bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        limits.check_size(bytes)?;
+        let g1_generator = read_point::<P::G1>(&mut bytes)?;
+        let g2_generator = read_point::<P::G2>(&mut bytes)?;
+        let g2_arr = read_points::<P::G2>(&mut bytes, limits)?;
+        ensure_consumed(bytes)?;
+
+        Ok(VerifierSetup {
+            g1_generator,
+            g2_generator,
+            g2_arr,
+        })
     }
 }
 
-// SHOULD IMPLEMENT THE CONTRIBUTE FUNCTION IN THE FUTURE
+// contributions to the setup live in kzg::ceremony
 // SHOULD CHECK THAT THE INCOMING ARR IS SAME LEN AS THE MAX_INPUT
 
 pub fn compute_lagrange_basis<F: PrimeField>(tau_arr: &[F]) -> Vec<F> {
     let poly_size = 2u32.pow(tau_arr.len() as u32) as usize;
 
-    let num_bits = poly_size.trailing_zeros();
+    let num_bits = poly_size.trailing_zeros() as usize;
 
     let mut results = Vec::with_capacity(poly_size);
 
     for i in 0..poly_size {
         let mut product = F::one();
 
-        // (0 & (1 << 5)) => 0 & (1 * 2^5) => 0 & 32 => 0
-        for bit_position in 0..num_bits {
-            // Calculate the bit position in Most-Significant-Bit-first order
-            // normal order would go from LSB to MSB so we reverse it here
-            // i.e. normal order: 000, 100, 010, 110, 001, 101, 011, 111
-            // MSB-first order: 000, 001, 010, 011, 100, 101, 110, 111
-            let msb_position = num_bits - 1 - bit_position;
-
-            let bit_is_one = (i & (1 << msb_position)) != 0; // Check if the bit at position bit_position is 1
-
+        // MSB-first order: 000, 001, 010, 011, 100, 101, 110, 111
+        // so τ₀ is read from the most significant bit of i
+        for (bit_position, bit_is_one) in index_to_point(i, num_bits, BitOrder::MsbFirst)
+            .into_iter()
+            .enumerate()
+        {
             let val = if bit_is_one {
-                tau_arr[bit_position as usize] // If bit is 1, use the variable directly
+                tau_arr[bit_position] // If bit is 1, use the variable directly
             } else {
-                F::one() - tau_arr[bit_position as usize] // If bit is 0, use (1 - variable)
+                F::one() - tau_arr[bit_position] // If bit is 0, use (1 - variable)
             };
 
-            product = product * val;
+            product *= val;
         }
 
         results.push(product);
@@ -69,15 +206,22 @@ pub fn compute_lagrange_basis<F: PrimeField>(tau_arr: &[F]) -> Vec<F> {
     results
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
 pub mod tests {
     use super::*;
     use ark_bls12_381::{Bls12_381, Fr as BlsFr, G1Affine};
     use ark_ec::AffineRepr;
 
+    // seed every test setup is derived from
+    pub const TEST_SEED: u64 = 5;
+
+    // 3 variables, the size of kzg_helper_functions::test::poly_1
     pub fn setup() -> TrustedSetup<Bls12_381> {
-        let tau_arr = vec![BlsFr::from(5), BlsFr::from(2), BlsFr::from(3)];
-        initialize::<BlsFr, Bls12_381>(&tau_arr)
+        setup_of_size(3)
+    }
+
+    pub fn setup_of_size(num_vars: usize) -> TrustedSetup<Bls12_381> {
+        TrustedSetup::insecure_from_seed(TEST_SEED, num_vars)
     }
 
     #[test]
@@ -105,6 +249,72 @@ pub mod tests {
         dbg!(&result);
     }
 
+    #[test]
+    fn test_verifier_setup_roundtrip() {
+        let setup = setup();
+        let bytes = setup.verifier_setup.to_bytes().unwrap();
+
+        let result = VerifierSetup::<Bls12_381>::from_bytes(&bytes).unwrap();
+        assert_eq!(result, setup.verifier_setup);
+        assert_eq!(result.max_num_vars(), setup.max_num_vars());
+    }
+
+    #[test]
+    fn test_verifier_setup_truncated_bytes() {
+        let setup = setup();
+        let bytes = setup.verifier_setup.to_bytes().unwrap();
+
+        let result = VerifierSetup::<Bls12_381>::from_bytes(&bytes[..bytes.len() - 1]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_max_num_vars() {
+        let setup = setup();
+        assert_eq!(setup.max_num_vars(), 3);
+        assert_eq!(setup.g1_arr.len(), 1 << setup.max_num_vars());
+    }
+
+    #[test]
+    fn test_trim_matches_smaller_setup() {
+        let setup = setup();
+        let trimmed = setup.trim(2);
+
+        // trim keeps the last taus
+        let taus = insecure_taus::<BlsFr>(TEST_SEED, 3);
+        let expected = initialize::<BlsFr, Bls12_381>(&taus[1..]);
+        assert_eq!(trimmed.max_num_vars(), 2);
+        assert_eq!(trimmed.g1_arr, expected.g1_arr);
+        assert_eq!(trimmed.verifier_setup, expected.verifier_setup);
+    }
+
+    #[test]
+    fn test_setup_for_circuit() {
+        let circuit = crate::gkr::test_circuits::circuit8::<BlsFr>();
+        let setup = TrustedSetup::<Bls12_381>::for_circuit(&circuit, &mut rand::thread_rng());
+        assert_eq!(setup.max_num_vars(), 3);
+        assert_eq!(setup.g1_arr.len(), circuit.inputs.len());
+
+        let universal = setup_of_size(5);
+        let trimmed = universal.trim_for_circuit(&circuit);
+        assert_eq!(trimmed.max_num_vars(), 3);
+        assert_eq!(trimmed.g1_arr, universal.trim(3).g1_arr);
+    }
+
+    #[test]
+    fn test_insecure_setup_is_deterministic() {
+        let setup = setup_of_size(4);
+        assert_eq!(setup.max_num_vars(), 4);
+        assert_eq!(setup.g1_arr, setup_of_size(4).g1_arr);
+        assert_eq!(
+            setup.verifier_setup,
+            initialize::<BlsFr, Bls12_381>(&insecure_taus(TEST_SEED, 4)).verifier_setup
+        );
+
+        let other = TrustedSetup::<Bls12_381>::insecure_from_seed(TEST_SEED + 1, 4);
+        assert_ne!(other.verifier_setup, setup.verifier_setup);
+    }
+
     #[test]
     fn test_negative() {
         let g1_generator = G1Affine::generator();
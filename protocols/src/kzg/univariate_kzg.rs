@@ -0,0 +1,181 @@
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    PrimeGroup,
+};
+use ark_ff::{AdditiveGroup, PrimeField};
+
+use crate::{
+    kzg::{
+        kzg_error::KZGError, kzg_helper_functions::evaluate_in_basis, trusted_setup::insecure_taus,
+    },
+    UnivariatePoly,
+};
+
+//=========================================================================================
+// Univariate KZG over the powers of a single τ
+// commit(f) = f(τ)·g1 = Σ aⱼ·[τʲ]₁ and f(x) = y iff X - x divides f(X) - y, so the opening is
+// the commitment to q(X) = (f(X) - y) / (X - x) (synthetic division) and the verifier checks
+//   e(C - y·g1, g2) == e(π, [τ]₂ - x·g2)
+// one G1 point per opening whatever the degree and two pairings to check it
+// the rest of the crate commits to multilinear polys (kzg_protocol), this is for the places
+// that have a univariate poly in coefficient form, e.g. the dealer's in shamir_secret_sharing
+//=========================================================================================
+#[derive(Debug)]
+pub struct UnivariateSetup<P: Pairing> {
+    pub powers: Vec<P::G1>, // [τ⁰]₁, ..., [τᵈ]₁
+    pub verifier_key: UnivariateVerifierKey<P>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnivariateVerifierKey<P: Pairing> {
+    pub g1_generator: P::G1,
+    pub g2_generator: P::G2,
+    pub g2_tau: P::G2,
+}
+
+impl<P: Pairing> UnivariateSetup<P> {
+    // commits to polys of degree up to max_degree
+    pub fn initialize(tau: P::ScalarField, max_degree: usize) -> Self {
+        let g1_generator = P::G1::generator();
+        let g2_generator = P::G2::generator();
+
+        let powers =
+            std::iter::successors(Some(P::ScalarField::from(1u64)), |power| Some(*power * tau))
+                .take(max_degree + 1)
+                .map(|power| g1_generator.mul_bigint(power.into_bigint()))
+                .collect();
+
+        UnivariateSetup {
+            powers,
+            verifier_key: UnivariateVerifierKey {
+                g1_generator,
+                g2_generator,
+                g2_tau: g2_generator.mul_bigint(tau.into_bigint()),
+            },
+        }
+    }
+
+    // INSECURE, τ is squeezed from the seed, see trusted_setup::insecure_taus
+    pub fn insecure_from_seed(seed: u64, max_degree: usize) -> Self {
+        Self::initialize(insecure_taus(seed, 1)[0], max_degree)
+    }
+
+    pub fn max_degree(&self) -> usize {
+        self.powers.len() - 1
+    }
+}
+
+// (f(X) - f(x)) / (X - x) and f(x), from the top coefficient down
+pub fn divide_by_linear<F: PrimeField>(poly: &UnivariatePoly<F>, x: F) -> (UnivariatePoly<F>, F) {
+    let mut quotient = vec![F::zero(); poly.coefficient.len().saturating_sub(1)];
+    let mut carry = F::zero();
+    for (i, coefficient) in poly.coefficient.iter().enumerate().rev() {
+        carry = carry * x + coefficient;
+        if i > 0 {
+            quotient[i - 1] = carry;
+        }
+    }
+
+    (UnivariatePoly::new(quotient), carry)
+}
+
+pub fn commit<P: Pairing>(
+    poly: &UnivariatePoly<P::ScalarField>,
+    setup: &UnivariateSetup<P>,
+) -> Result<P::G1, KZGError> {
+    if poly.coefficient.len() > setup.powers.len() {
+        return Err(KZGError::SizeMismatch {
+            expected: setup.powers.len(),
+            found: poly.coefficient.len(),
+        });
+    }
+
+    Ok(evaluate_in_basis(
+        &poly.coefficient,
+        &setup.powers[..poly.coefficient.len()],
+    ))
+}
+
+// (f(x), π)
+pub fn open<P: Pairing>(
+    poly: &UnivariatePoly<P::ScalarField>,
+    x: P::ScalarField,
+    setup: &UnivariateSetup<P>,
+) -> Result<(P::ScalarField, P::G1), KZGError> {
+    let (quotient, value) = divide_by_linear(poly, x);
+
+    Ok((value, commit(&quotient, setup)?))
+}
+
+pub fn verify<P: Pairing>(
+    commitment: &P::G1,
+    x: P::ScalarField,
+    value: P::ScalarField,
+    proof: &P::G1,
+    verifier_key: &UnivariateVerifierKey<P>,
+) -> bool {
+    let lhs = *commitment - verifier_key.g1_generator.mul_bigint(value.into_bigint());
+    let tau_minus_x = verifier_key.g2_tau - verifier_key.g2_generator.mul_bigint(x.into_bigint());
+
+    // e(C - y·g1, g2) - e(π, [τ - x]₂) == 0 as a single multi pairing
+    P::multi_pairing([lhs, -*proof], [verifier_key.g2_generator, tau_minus_x])
+        == PairingOutput::ZERO
+}
+
+#[cfg(all(test, feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use ark_bls12_381::{Bls12_381, Fr};
+
+    // 5 + 3x + 2x³
+    fn poly() -> UnivariatePoly<Fr> {
+        UnivariatePoly::new([5, 3, 0, 2].map(Fr::from).to_vec())
+    }
+
+    #[test]
+    fn test_divide_by_linear() {
+        let (quotient, value) = divide_by_linear(&poly(), Fr::from(2));
+        assert_eq!(value, Fr::from(27));
+
+        // q(X)·(X - 2) + 27 is the poly again
+        let linear = UnivariatePoly::new(vec![-Fr::from(2), Fr::from(1)]);
+        let back = &(&quotient * &linear) + &UnivariatePoly::new(vec![value]);
+        assert_eq!(back.coefficient, poly().coefficient);
+    }
+
+    #[test]
+    fn test_univariate_kzg() {
+        let setup = UnivariateSetup::<Bls12_381>::insecure_from_seed(7, 3);
+        let commitment = commit(&poly(), &setup).unwrap();
+
+        for x in [0, 1, 2, 100].map(Fr::from) {
+            let (value, proof) = open(&poly(), x, &setup).unwrap();
+            assert_eq!(value, poly().evaluate(x));
+            assert!(verify(&commitment, x, value, &proof, &setup.verifier_key));
+            assert!(!verify(
+                &commitment,
+                x,
+                value + Fr::from(1),
+                &proof,
+                &setup.verifier_key
+            ));
+            assert!(!verify(
+                &commitment,
+                x + Fr::from(1),
+                value,
+                &proof,
+                &setup.verifier_key
+            ));
+        }
+
+        // degree 3 doesn't fit a setup of degree 2
+        let small = UnivariateSetup::<Bls12_381>::insecure_from_seed(7, 2);
+        assert_eq!(
+            commit(&poly(), &small),
+            Err(KZGError::SizeMismatch {
+                expected: 3,
+                found: 4
+            })
+        );
+    }
+}
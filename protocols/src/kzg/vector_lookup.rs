@@ -0,0 +1,443 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+#[cfg(feature = "prover")]
+use {
+    crate::{
+        kzg::batch_kzg::batch_open_committed, multi_linear::EvaluationTable,
+        virtual_polynomial::VirtualPolynomial,
+    },
+    ark_ff::UniformRand,
+    rand::Rng,
+    std::sync::Arc,
+};
+
+use crate::{
+    gkr::partial_sum_check::verify_round,
+    kzg::{
+        batch_kzg::{batch_verify, BatchKZGProof},
+        kzg_helper_functions::compute_commitment,
+        trusted_setup::{TrustedSetup, VerifierSetup},
+    },
+    lagrange::LagrangeWeights,
+    multi_linear::MultiLinearPoly,
+    transcript::{Transcript, TranscriptDomain, DIGEST_LEN},
+    transcript_schedule::{Schedule, Step},
+};
+
+//=========================================================================================
+// Vector lookup: "v is an entry of my committed vector t"
+// t has 2ⁿ entries and is committed with the setup trimmed to n variables (commit_vector), the
+// prover's witness is the one-hot vector e (eᵢ = 1 at the index i of v), and
+//   e is boolean, Σₓ e(x) = 1 and Σₓ e(x)·t(x) = v
+// says exactly that v = tᵢ for some i. The prover commits ê over one more variable y,
+// ê(0, x) = e(x) and ê(1, x) = s(x) with s random, and proves with one sum-check
+//   Σ_{y, x} (1 - y)·[ê·t + α·ê + β·eq(z, x)·ê·(ê - 1)](y, x) = v + α
+// (z, α, β from the transcript, the boolean check is a zero-check at z). The y = 1 half is cut
+// off by 1 - y, y is the first variable of the sum-check. t is opened at ρ through the (n + 1)
+// variable setup, where its commitment is the commitment of t blown up over y
+// NOTE: this is a membership proof, not a private one. s masks the commitment to e and ê(ρ), but
+// the quotients and the first round poly are not blinded and there is no simulator, so the proof
+// may well give away i. t(ρ) is in the proof as well
+// NOTE: KZG only, a Merkle committed vector would need an opening at ρ the crate doesn't have
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorLookupProof<P: Pairing> {
+    pub blinded_commitment: P::G1, // ê = e | s
+    pub round_polys: Vec<Vec<P::ScalarField>>,
+    pub evaluations: Vec<P::ScalarField>, // ê(ρ), t(ρ)
+    pub quotients: Vec<P::G1>,            // the batched opening of ê and t at ρ
+}
+
+// (1 - y)·ê·t, (1 - y)·ê, (1 - y)·eq·ê·ê, (1 - y)·eq·ê as columns of the sum-check
+const SELECTOR: usize = 0;
+const BLINDED: usize = 1;
+const VECTOR: usize = 2;
+const EQ: usize = 3;
+const DEGREE: usize = 4;
+
+fn composition<F: PrimeField>(alpha: F, beta: F) -> [(F, Vec<usize>); 4] {
+    [
+        (F::one(), vec![SELECTOR, BLINDED, VECTOR]),
+        (alpha, vec![SELECTOR, BLINDED]),
+        (beta, vec![SELECTOR, EQ, BLINDED, BLINDED]),
+        (-beta, vec![SELECTOR, EQ, BLINDED]),
+    ]
+}
+
+pub static VECTOR_LOOKUP_SCHEDULE: Schedule = Schedule {
+    protocol: "vector_lookup",
+    steps: &[
+        Step::Absorb("num_vars"),
+        Step::Absorb("vector commitment"),
+        Step::Absorb("value"),
+        Step::Absorb("blinded commitment"),
+        Step::Repeat("zero-check point", &[Step::Squeeze("z")]),
+        Step::Squeeze("alpha"),
+        Step::Squeeze("beta"),
+        Step::Repeat("rounds", &[Step::Absorb("round poly"), Step::Squeeze("ρ")]),
+        Step::Squeeze("opening domain"),
+    ],
+};
+
+// t over n variables, setup is the lookup's setup over n + 1
+pub fn commit_vector<P: Pairing>(vector: &[P::ScalarField], setup: &TrustedSetup<P>) -> P::G1 {
+    let trimmed = setup.trim(setup.max_num_vars() - 1);
+
+    compute_commitment::<P::ScalarField, P>(&MultiLinearPoly::new(vector), &trimmed.g1_arr)
+        .expect("The vector must have one entry per point of the setup without its first variable")
+}
+
+// z, α and β after the statement and the blinded commitment
+fn start_transcript<P: Pairing>(
+    num_vars: usize,
+    vector_commitment: &P::G1,
+    value: P::ScalarField,
+    blinded_commitment: &P::G1,
+    transcript_domain: &TranscriptDomain,
+) -> (
+    Transcript,
+    Vec<P::ScalarField>,
+    P::ScalarField,
+    P::ScalarField,
+) {
+    let mut transcript = transcript_domain
+        .transcript()
+        .with_schedule(&VECTOR_LOOKUP_SCHEDULE);
+    transcript
+        .follow(Step::Absorb("num_vars"))
+        .absorb(&(num_vars as u64).to_be_bytes());
+    transcript
+        .follow(Step::Absorb("vector commitment"))
+        .absorb_commitment(vector_commitment);
+    transcript
+        .follow(Step::Absorb("value"))
+        .absorb(&MultiLinearPoly::to_bytes(&[value]));
+    transcript
+        .follow(Step::Absorb("blinded commitment"))
+        .absorb_commitment(blinded_commitment);
+
+    let z = (0..num_vars)
+        .map(|_| transcript.follow(Step::Squeeze("z")).squeeze_field())
+        .collect();
+    let alpha = transcript.follow(Step::Squeeze("alpha")).squeeze_field();
+    let beta = transcript.follow(Step::Squeeze("beta")).squeeze_field();
+
+    (transcript, z, alpha, beta)
+}
+
+fn round_challenge<F: PrimeField>(transcript: &mut Transcript, round_poly: &[F]) -> F {
+    transcript
+        .follow(Step::Absorb("round poly"))
+        .absorb(&MultiLinearPoly::to_bytes(round_poly));
+
+    transcript.follow(Step::Squeeze("ρ")).squeeze_field()
+}
+
+fn opening_domain(transcript: &mut Transcript) -> TranscriptDomain {
+    let mut digest = [0u8; DIGEST_LEN];
+    digest.copy_from_slice(&transcript.follow(Step::Squeeze("opening domain")).squeeze());
+
+    TranscriptDomain::new(b"vector-lookup-opening", digest)
+}
+
+// a column over n variables repeated on both halves of y
+#[cfg(feature = "prover")]
+fn blow_up_column<F: PrimeField>(column: &[F]) -> Vec<F> {
+    [column, column].concat()
+}
+
+// setup has one more variable than the vector, value must be one of its entries
+#[cfg(feature = "prover")]
+pub fn prove<P: Pairing, R: Rng>(
+    setup: &TrustedSetup<P>,
+    vector: &[P::ScalarField],
+    value: P::ScalarField,
+    transcript_domain: &TranscriptDomain,
+    rng: &mut R,
+) -> VectorLookupProof<P> {
+    if vector.len() << 1 != setup.g1_arr.len() {
+        panic!("The setup must have one more variable than the vector");
+    }
+    let index = vector
+        .iter()
+        .position(|entry| *entry == value)
+        .expect("The value is not an entry of the vector");
+    let num_vars = setup.max_num_vars();
+    let n = vector.len();
+
+    // ê = e | s
+    let mut blinded = vec![P::ScalarField::from(0u64); 2 * n];
+    blinded[index] = P::ScalarField::from(1u64);
+    for entry in blinded[n..].iter_mut() {
+        *entry = P::ScalarField::rand(rng);
+    }
+    let blinded = MultiLinearPoly::new(&blinded);
+    let blinded_commitment = compute_commitment::<P::ScalarField, P>(&blinded, &setup.g1_arr)
+        .expect("The blinded vector has one entry per point of the setup");
+    let vector_commitment = commit_vector(vector, setup);
+
+    let (mut transcript, z, alpha, beta) = start_transcript::<P>(
+        num_vars - 1,
+        &vector_commitment,
+        value,
+        &blinded_commitment,
+        transcript_domain,
+    );
+
+    let mut selector = vec![P::ScalarField::from(1u64); n];
+    selector.resize(2 * n, P::ScalarField::from(0u64));
+    let blown_up = MultiLinearPoly::new(&blow_up_column(vector));
+    let mles = [
+        MultiLinearPoly::new(&selector),
+        blinded.clone(),
+        blown_up.clone(),
+        MultiLinearPoly::new(&blow_up_column(EvaluationTable::new(&z).weights())),
+    ]
+    .map(Arc::new);
+    let mut poly = VirtualPolynomial::new(num_vars);
+    for (coefficient, product) in composition(alpha, beta) {
+        poly.add_product(
+            product.iter().map(|column| mles[*column].clone()),
+            coefficient,
+        );
+    }
+    drop(mles);
+
+    let mut round_polys = Vec::with_capacity(num_vars);
+    let mut point = Vec::with_capacity(num_vars);
+    for _ in 0..num_vars {
+        let round_poly = poly.round_evaluations();
+        let challenge = round_challenge(&mut transcript, &round_poly);
+        round_polys.push(round_poly);
+        point.push(challenge);
+        poly.fix_variable(challenge);
+    }
+
+    let opening = batch_open_committed::<P::ScalarField, P>(
+        &[blinded, blown_up],
+        vec![blinded_commitment, vector_commitment],
+        &setup.g1_arr,
+        &point,
+        &opening_domain(&mut transcript),
+    );
+
+    VectorLookupProof {
+        blinded_commitment,
+        round_polys,
+        evaluations: opening.polys_opened,
+        quotients: opening.quotient_evals,
+    }
+}
+
+pub fn verify<P: Pairing>(
+    verifier_setup: &VerifierSetup<P>,
+    vector_commitment: &P::G1,
+    value: P::ScalarField,
+    proof: &VectorLookupProof<P>,
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    let num_vars = verifier_setup.max_num_vars();
+    if num_vars == 0
+        || proof.round_polys.len() != num_vars
+        || proof.evaluations.len() != 2
+        || proof.quotients.len() != num_vars
+    {
+        return false;
+    }
+
+    let (mut transcript, z, alpha, beta) = start_transcript::<P>(
+        num_vars - 1,
+        vector_commitment,
+        value,
+        &proof.blinded_commitment,
+        transcript_domain,
+    );
+
+    let weights = LagrangeWeights::for_degree(DEGREE);
+    let mut claim = value + alpha;
+    let mut point = Vec::with_capacity(num_vars);
+    for round_poly in proof.round_polys.iter() {
+        let challenge = round_challenge(&mut transcript, round_poly);
+        claim = match verify_round(claim, round_poly, &weights, challenge) {
+            Some(next) => next,
+            None => return false,
+        };
+        point.push(challenge);
+    }
+
+    // 1 - ρ_y and eq(z, ρ_x) are computed here, ê(ρ) and t(ρ) are opened
+    let eq = z
+        .iter()
+        .zip(&point[1..])
+        .map(|(z, x)| *z * x + (P::ScalarField::from(1u64) - z) * (P::ScalarField::from(1u64) - x))
+        .product();
+    let mut values = vec![P::ScalarField::from(1u64) - point[0]];
+    values.extend(proof.evaluations.iter().copied());
+    values.push(eq);
+    let expected: P::ScalarField = composition(alpha, beta)
+        .iter()
+        .map(|(coefficient, product)| {
+            product
+                .iter()
+                .fold(*coefficient, |acc, column| acc * values[*column])
+        })
+        .sum();
+    if expected != claim {
+        return false;
+    }
+
+    let opening = BatchKZGProof::<P::ScalarField, P> {
+        commitments: vec![proof.blinded_commitment, *vector_commitment],
+        polys_opened: proof.evaluations.clone(),
+        quotient_evals: proof.quotients.clone(),
+    };
+
+    batch_verify(
+        &opening,
+        verifier_setup,
+        &point,
+        &opening_domain(&mut transcript),
+    )
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::{kzg::trusted_setup::tests::setup_of_size, transcript::test::domain};
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::Zero;
+
+    fn vector() -> Vec<Fr> {
+        [3, 1, 4, 1, 5, 9, 2, 6].map(Fr::from).to_vec()
+    }
+
+    #[test]
+    fn test_vector_lookup() {
+        let setup = setup_of_size(4);
+        let commitment = commit_vector(&vector(), &setup);
+        let mut rng = rand::thread_rng();
+
+        for value in [3, 9, 6].map(Fr::from) {
+            let proof = prove(&setup, &vector(), value, &domain(), &mut rng);
+            assert_eq!(proof.round_polys.len(), 4);
+            assert!(verify(
+                &setup.verifier_setup,
+                &commitment,
+                value,
+                &proof,
+                &domain()
+            ));
+
+            // the proof is for this value and this vector
+            assert!(!verify(
+                &setup.verifier_setup,
+                &commitment,
+                value + Fr::from(1),
+                &proof,
+                &domain()
+            ));
+            let other = commit_vector(&[Fr::from(7); 8], &setup);
+            assert!(!verify(
+                &setup.verifier_setup,
+                &other,
+                value,
+                &proof,
+                &domain()
+            ));
+        }
+    }
+
+    #[test]
+    fn test_blinded_commitment_is_fresh() {
+        let setup = setup_of_size(4);
+        let mut rng = rand::thread_rng();
+
+        // the same index twice, nothing repeats
+        let first = prove(&setup, &vector(), Fr::from(5), &domain(), &mut rng);
+        let second = prove(&setup, &vector(), Fr::from(5), &domain(), &mut rng);
+        assert_ne!(first.blinded_commitment, second.blinded_commitment);
+        assert_ne!(first.evaluations[0], second.evaluations[0]);
+    }
+
+    // e = 2·e₄ - e₂ sums to 1 and gives 2·5 - 4 = 6 against t, only the boolean check stops it
+    #[test]
+    fn test_rejects_a_vector_that_is_not_one_hot() {
+        let setup = setup_of_size(4);
+        let vector = vector();
+        let commitment = commit_vector(&vector, &setup);
+        let value = Fr::from(6);
+
+        let mut blinded = vec![Fr::zero(); 16];
+        blinded[4] = Fr::from(2);
+        blinded[2] = -Fr::from(1);
+        let blinded = MultiLinearPoly::new(&blinded);
+        let blinded_commitment =
+            compute_commitment::<Fr, Bls12_381>(&blinded, &setup.g1_arr).unwrap();
+        let (mut transcript, z, alpha, beta) =
+            start_transcript::<Bls12_381>(3, &commitment, value, &blinded_commitment, &domain());
+
+        let mut selector = vec![Fr::from(1); 8];
+        selector.resize(16, Fr::zero());
+        let blown_up = MultiLinearPoly::new(&blow_up_column(&vector));
+        let mles = [
+            MultiLinearPoly::new(&selector),
+            blinded.clone(),
+            blown_up.clone(),
+            MultiLinearPoly::new(&blow_up_column(EvaluationTable::new(&z).weights())),
+        ]
+        .map(Arc::new);
+        let mut poly = VirtualPolynomial::new(4);
+        for (coefficient, product) in composition(alpha, beta) {
+            poly.add_product(
+                product.iter().map(|column| mles[*column].clone()),
+                coefficient,
+            );
+        }
+        // the honest sum is off by β·Σ eq(z, x)·e(e - 1), the cheater claims v + α anyway
+        assert_ne!(poly.sum(), value + alpha);
+
+        let mut round_polys = Vec::new();
+        let mut point = Vec::new();
+        for _ in 0..4 {
+            let round_poly = poly.round_evaluations();
+            let challenge = round_challenge(&mut transcript, &round_poly);
+            round_polys.push(round_poly);
+            point.push(challenge);
+            poly.fix_variable(challenge);
+        }
+        let opening = batch_open_committed::<Fr, Bls12_381>(
+            &[blinded, blown_up],
+            vec![blinded_commitment, commitment],
+            &setup.g1_arr,
+            &point,
+            &opening_domain(&mut transcript),
+        );
+        let proof = VectorLookupProof {
+            blinded_commitment,
+            round_polys,
+            evaluations: opening.polys_opened,
+            quotients: opening.quotient_evals,
+        };
+
+        assert!(!verify(
+            &setup.verifier_setup,
+            &commitment,
+            value,
+            &proof,
+            &domain()
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "The value is not an entry of the vector")]
+    fn test_value_outside_the_vector() {
+        let setup = setup_of_size(4);
+        prove(
+            &setup,
+            &vector(),
+            Fr::from(7),
+            &domain(),
+            &mut rand::thread_rng(),
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    PrimeGroup,
+};
+use ark_ff::{AdditiveGroup, PrimeField};
+
+use crate::{
+    hypercube::{index_to_point, BitOrder},
+    kzg::kzg_error::KZGError,
+    kzg::kzg_helper_functions::{
+        compute_commitment, compute_quotient_eval, element_wise_op, fold_encrypted_basis, Operator,
+    },
+    kzg::kzg_protocol::KZGProof,
+    kzg::trusted_setup::VerifierSetup,
+    multi_linear::MultiLinearPoly,
+};
+
+//=========================================================================================
+// Opening at a vertex of the hypercube, i.e. reading entry i of a committed vector
+// at a point b in {0, 1}ⁿ f(b) is the table entry at index b (MSB-first), so the value is read
+// instead of evaluated, and the remainder of every step f(bᵢ, x) is one half of the table,
+// only the quotients f(1, x) - f(0, x) need field arithmetic
+// the proof is an ordinary KZGProof, kzg_protocol::verify accepts it at (b₀, ..., bₙ₋₁), but
+// the verifier here uses the check of kzg::prepared_verifier with aᵢ ∈ {0, 1}:
+// e(f(τ) - v + Σ_{bᵢ = 1} Qᵢ(τ), g2) == Σ e(Qᵢ(τ), g2_τᵢ)
+// a few G1 additions and one multi pairing, no G2 (or G1) scalar multiplication per variable
+//=========================================================================================
+pub fn open_vertex<F: PrimeField, P: Pairing>(
+    poly: &MultiLinearPoly<F>,
+    encrypted_basis: &[P::G1],
+    index: usize,
+) -> Result<KZGProof<F, P>, KZGError> {
+    let commitment = compute_commitment::<F, P>(poly, encrypted_basis)?;
+    let num_vars = encrypted_basis.len().ilog2() as usize;
+    let vertex = index_to_point(index, num_vars, BitOrder::MsbFirst);
+
+    let v = poly.computation[index];
+    let mut poly_minus_v: Vec<F> = poly.computation.iter().map(|value| *value - v).collect();
+    let mut folded_basis = encrypted_basis.to_vec();
+    let mut quotient_evals = Vec::with_capacity(num_vars);
+
+    for bit in vertex {
+        let (low, high) = poly_minus_v.split_at(poly_minus_v.len() / 2);
+        let quotient = MultiLinearPoly {
+            computation: element_wise_op(high, low, Operator::Sub),
+        };
+        folded_basis = fold_encrypted_basis::<P>(&folded_basis);
+        quotient_evals.push(compute_quotient_eval::<F, P>(&quotient, &folded_basis));
+
+        poly_minus_v = if bit { high.to_vec() } else { low.to_vec() };
+    }
+
+    Ok(KZGProof {
+        commitment,
+        quotient_evals,
+        poly_opened: v,
+    })
+}
+
+// false for a proof of the wrong size or an index outside the setup's hypercube
+pub fn verify_vertex<F: PrimeField, P: Pairing>(
+    proof: &KZGProof<F, P>,
+    verifier_setup: &VerifierSetup<P>,
+    index: usize,
+) -> bool {
+    let num_vars = verifier_setup.g2_arr.len();
+    if proof.quotient_evals.len() != num_vars
+        || (num_vars < usize::BITS as usize && index >> num_vars != 0)
+    {
+        return false;
+    }
+
+    let vertex = index_to_point(index, num_vars, BitOrder::MsbFirst);
+    let mut lhs = proof.commitment
+        - verifier_setup
+            .g1_generator
+            .mul_bigint(proof.poly_opened.into_bigint());
+    for (quotient, bit) in proof.quotient_evals.iter().zip(vertex) {
+        if bit {
+            lhs += quotient;
+        }
+    }
+
+    let g1_points = std::iter::once(lhs).chain(proof.quotient_evals.iter().map(|q| -*q));
+    let g2_points =
+        std::iter::once(verifier_setup.g2_generator).chain(verifier_setup.g2_arr.iter().copied());
+
+    P::multi_pairing(g1_points, g2_points) == PairingOutput::ZERO
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::kzg::{
+        kzg_helper_functions::test::poly_1, kzg_protocol, trusted_setup::tests::setup,
+    };
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+
+    #[test]
+    fn test_open_every_vertex() {
+        let setup = setup();
+        let poly = poly_1();
+
+        for index in 0..8 {
+            let proof = open_vertex::<BlsFr, Bls12_381>(&poly, &setup.g1_arr, index).unwrap();
+            assert_eq!(proof.poly_opened, poly.computation[index]);
+            assert!(verify_vertex(&proof, &setup.verifier_setup, index));
+
+            // the same proof as the general opening at the vertex
+            let vertex: Vec<BlsFr> = index_to_point(index, 3, BitOrder::MsbFirst)
+                .into_iter()
+                .map(BlsFr::from)
+                .collect();
+            let general =
+                kzg_protocol::proof::<BlsFr, Bls12_381>(poly.clone(), &setup.g1_arr, &vertex);
+            assert_eq!(proof.quotient_evals, general.quotient_evals);
+            assert!(kzg_protocol::verify(&proof, &setup.verifier_setup, &vertex));
+        }
+    }
+
+    #[test]
+    fn test_vertex_opening_rejects() {
+        let setup = setup();
+        // 3ab + 4c is 4 at index 1 and 0 at index 2
+        let proof = open_vertex::<BlsFr, Bls12_381>(&poly_1(), &setup.g1_arr, 1).unwrap();
+
+        assert!(!verify_vertex(&proof, &setup.verifier_setup, 2));
+        assert!(!verify_vertex(&proof, &setup.verifier_setup, 9));
+
+        let mut wrong_value = open_vertex::<BlsFr, Bls12_381>(&poly_1(), &setup.g1_arr, 1).unwrap();
+        wrong_value.poly_opened += BlsFr::from(1);
+        assert!(!verify_vertex(&wrong_value, &setup.verifier_setup, 1));
+
+        let mut short = proof;
+        short.quotient_evals.pop();
+        assert!(!verify_vertex(&short, &setup.verifier_setup, 1));
+    }
+}
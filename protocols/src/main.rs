@@ -1,6 +1,6 @@
 use ark_bn254::Fq;
-use polynomials::*;
 use shamir_secret_sharing::*;
+use zk_protocols::{field_hex::Hex, *};
 
 fn main() {
     let secret = Fq::from(4);
@@ -9,12 +9,14 @@ fn main() {
 
     // Split the secret into shares
     let shares = generate_shares(secret, threshold, num_shares);
-    println!("Shares: {:?}", &shares);
+    for share in shares.iter() {
+        println!("Share: ({}, {})", Hex(share.x), Hex(share.y));
+    }
 
     // Can reconstruct with any threshold or more shares
     let some_shares = &shares[2..7]; // Using shares 2, 3, 4, 5, and 6
     let reconstructed = reconstruct_secret(some_shares, threshold);
 
-    println!("Original secret: {}", secret);
-    println!("Reconstructed secret: {}", reconstructed);
+    println!("Original secret: {}", Hex(secret));
+    println!("Reconstructed secret: {}", Hex(reconstructed));
 }
@@ -0,0 +1,43 @@
+pub mod fri;
+pub mod ipa;
+pub mod kzg;
+pub mod pcs_error;
+
+pub use fri::{FriKey, FriOpening, FriPolynomial};
+pub use ipa::IpaKey;
+pub use pcs_error::PcsError;
+
+//=========================================================================================
+// One verifier call for every polynomial commitment scheme in the crate
+// check_evaluation(commitment, point, value, proof, vk) is Ok iff the proof shows the committed
+// polynomial takes value at point, the verifier key picks the backend:
+//   KZG (VerifierSetup): multilinear, the point is a vector, the proof is the quotient commitments
+//   IPA (IpaKey):        univariate, <coefficients, G> opened with the Bulletproofs argument
+//   FRI (FriKey):        univariate, Merkle root of the LDE, the proof is FRI on (f - v) / (x - z)
+// swapping backends changes the key and the types around the call, not the call itself
+// none of the backends hide the polynomial, commitments are only binding
+//=========================================================================================
+pub trait PolynomialCommitment {
+    type Commitment;
+    type Point;
+    type Value;
+    type Proof;
+
+    fn check_evaluation(
+        &self,
+        commitment: &Self::Commitment,
+        point: &Self::Point,
+        value: &Self::Value,
+        proof: &Self::Proof,
+    ) -> Result<(), PcsError>;
+}
+
+pub fn check_evaluation<K: PolynomialCommitment>(
+    commitment: &K::Commitment,
+    point: &K::Point,
+    value: &K::Value,
+    proof: &K::Proof,
+    vk: &K,
+) -> Result<(), PcsError> {
+    vk.check_evaluation(commitment, point, value, proof)
+}
@@ -0,0 +1,494 @@
+use ark_ff::{FftField, PrimeField};
+use std::marker::PhantomData;
+
+#[cfg(feature = "prover")]
+use crate::fri::{
+    lde::lde,
+    low_degree::{commit_layer, commit_rows, prove_low_degree},
+};
+use crate::{
+    fri::{
+        domain_cache::{CosetDomain, DomainCache},
+        low_degree::{
+            check_opening, check_row_opening, verify_low_degree, LowDegreeConfig, LowDegreeProof,
+        },
+        merkle_tree::{MerkleProof, MerkleTree},
+    },
+    multi_linear::MultiLinearPoly,
+    pcs::{PcsError, PolynomialCommitment},
+    transcript::{public_digest, TranscriptDomain},
+};
+
+pub const BATCH_COMBINER_LABEL: &[u8] = b"fri-batch-combiner";
+
+//=========================================================================================
+// Univariate PCS from FRI (the DEEP quotient trick)
+// the commitment is the Merkle root of f over the LDE domain (degree_bound * blowup points)
+// f(z) = v iff x - z divides f(x) - v, so the prover runs FRI on q(x) = (f(x) - v) / (x - z)
+// the first FRI layer is q on the same domain, every query position j also opens f(ωʲ) against
+// the commitment and the verifier checks q(ωʲ)·(ωʲ - z) = f(ωʲ) - v there
+// the positions are the leaf indices of the first layer openings, which verify_low_degree has
+// already tied to the transcript
+// the FRI transcript is the key's domain with (root, z, v) folded into its public digest
+// z must lie outside the domain, there x - z vanishes at one of the points
+// with config.blinding > 0 the domain is the coset g·ωʲ and ωʲ above reads g·ωʲ
+//
+// a batch f₀..fₖ shares one tree, leaf j is the row (j, f₀(ωʲ), ..., fₖ(ωʲ)) (see commit_rows)
+// opening at z gives vᵢ = fᵢ(z), α is squeezed from the opening domain with (root, z, v₀..vₖ)
+// and FRI runs once on q(x) = Σ αⁱ (fᵢ(x) - vᵢ) / (x - z), each query opens one row
+//=========================================================================================
+#[derive(Debug, Clone)]
+pub struct FriKey<F> {
+    pub degree_bound: usize,
+    pub config: LowDegreeConfig,
+    pub domain: TranscriptDomain,
+    _field: PhantomData<F>,
+}
+
+// what the committer keeps to open later
+#[derive(Debug)]
+pub struct FriPolynomial<F> {
+    pub coefficients: Vec<F>,
+    pub evals: Vec<F>,
+    pub tree: MerkleTree,
+}
+
+#[derive(Debug, Clone)]
+pub struct FriOpening<F: PrimeField> {
+    pub quotient: LowDegreeProof<F>,
+    pub values: Vec<(F, MerkleProof)>, // f at each query position, in query order
+}
+
+#[derive(Debug)]
+pub struct FriBatch<F> {
+    pub coefficients: Vec<Vec<F>>,
+    pub evals: Vec<Vec<F>>, // [polynomial][domain point]
+    pub tree: MerkleTree,
+}
+
+#[derive(Debug, Clone)]
+pub struct FriBatchOpening<F: PrimeField> {
+    pub quotient: LowDegreeProof<F>,
+    pub rows: Vec<(Vec<F>, MerkleProof)>, // every fᵢ at each query position, in query order
+}
+
+impl<F: FftField + PrimeField> FriKey<F> {
+    pub fn new(degree_bound: usize, config: LowDegreeConfig, domain: TranscriptDomain) -> Self {
+        // with a single coefficient FRI has no rounds and so no positions to open f at
+        if degree_bound < 2 {
+            panic!("The FRI commitment needs a degree bound of at least 2");
+        }
+
+        FriKey {
+            degree_bound,
+            config,
+            domain,
+            _field: PhantomData,
+        }
+    }
+
+    #[cfg(feature = "prover")]
+    fn domain_size(&self) -> usize {
+        self.config.domain_size(self.degree_bound)
+    }
+
+    fn opening_domain(&self, root: &[u8], point: F, values: &[F]) -> TranscriptDomain {
+        let digest = public_digest(
+            &[
+                &self.domain.public_digest[..],
+                root,
+                &MultiLinearPoly::to_bytes(&[point]),
+                &MultiLinearPoly::to_bytes(values),
+            ]
+            .concat(),
+        );
+
+        TranscriptDomain::new(&self.domain.context, digest)
+    }
+
+    // α for the batch, bound to everything the opening domain is
+    fn batch_combiner(opening_domain: &TranscriptDomain) -> F {
+        let mut transcript = opening_domain.transcript();
+        transcript.absorb_labeled(BATCH_COMBINER_LABEL, &[]);
+        transcript.squeeze_field()
+    }
+
+    #[cfg(feature = "prover")]
+    fn evaluate(coefficients: &[F], point: F) -> F {
+        coefficients
+            .iter()
+            .rev()
+            .fold(F::zero(), |acc, coeff| acc * point + coeff)
+    }
+
+    #[cfg(feature = "prover")]
+    fn low_degree_extension(&self, coefficients: &[F]) -> Vec<F> {
+        if coefficients.is_empty() || coefficients.len() > self.degree_bound {
+            panic!("The polynomial does not fit under the degree bound");
+        }
+
+        let mut padded = coefficients.to_vec();
+        padded.resize(self.degree_bound.next_power_of_two(), F::zero());
+        lde(
+            &padded,
+            self.config.blowup_factor,
+            self.config.coset_shift(),
+        )
+    }
+
+    // the subgroup, or the coset g·ωʲ when the config blinds
+    fn lde_domain(&self) -> CosetDomain<F> {
+        self.config
+            .coset_domain(self.degree_bound, &mut DomainCache::new())
+    }
+
+    fn in_domain(&self, point: F) -> bool {
+        self.lde_domain().contains(point)
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn commit(&self, coefficients: &[F]) -> (Vec<u8>, FriPolynomial<F>) {
+        let evals = self.low_degree_extension(coefficients);
+        let tree = commit_layer(&evals);
+
+        (
+            tree.root().unwrap(),
+            FriPolynomial {
+                coefficients: coefficients.to_vec(),
+                evals,
+                tree,
+            },
+        )
+    }
+
+    // (f(z), proof)
+    #[cfg(feature = "prover")]
+    pub fn open(&self, poly: &FriPolynomial<F>, point: F) -> (F, FriOpening<F>) {
+        if self.in_domain(point) {
+            panic!("The FRI commitment cannot open at a point of its domain");
+        }
+
+        let value = Self::evaluate(&poly.coefficients, point);
+        let domain = self.lde_domain();
+        let quotient_evals: Vec<F> = poly
+            .evals
+            .iter()
+            .enumerate()
+            .map(|(j, eval)| (*eval - value) * (domain.element(j) - point).inverse().unwrap())
+            .collect();
+
+        let root = poly.tree.root().unwrap();
+        let quotient = prove_low_degree(
+            &quotient_evals,
+            self.degree_bound,
+            &self.config,
+            &self.opening_domain(&root, point, &[value]),
+        );
+        let values = quotient
+            .query_openings
+            .iter()
+            .map(|openings| {
+                let j = openings[0].proof.leaf_index;
+                (poly.evals[j], poly.tree.generate_proof_at(j).unwrap())
+            })
+            .collect();
+
+        (value, FriOpening { quotient, values })
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn commit_batch(&self, polys: &[&[F]]) -> (Vec<u8>, FriBatch<F>) {
+        if polys.is_empty() {
+            panic!("The batch needs at least one polynomial");
+        }
+
+        let evals: Vec<Vec<F>> = polys
+            .iter()
+            .map(|coefficients| self.low_degree_extension(coefficients))
+            .collect();
+        let tree = commit_rows(&evals);
+
+        (
+            tree.root().unwrap(),
+            FriBatch {
+                coefficients: polys
+                    .iter()
+                    .map(|coefficients| coefficients.to_vec())
+                    .collect(),
+                evals,
+                tree,
+            },
+        )
+    }
+
+    // ([f₀(z), ..., fₖ(z)], proof)
+    #[cfg(feature = "prover")]
+    pub fn open_batch(&self, batch: &FriBatch<F>, point: F) -> (Vec<F>, FriBatchOpening<F>) {
+        if self.in_domain(point) {
+            panic!("The FRI commitment cannot open at a point of its domain");
+        }
+
+        let values: Vec<F> = batch
+            .coefficients
+            .iter()
+            .map(|coefficients| Self::evaluate(coefficients, point))
+            .collect();
+        let root = batch.tree.root().unwrap();
+        let opening_domain = self.opening_domain(&root, point, &values);
+        let alpha = Self::batch_combiner(&opening_domain);
+
+        let domain = self.lde_domain();
+        let quotient_evals: Vec<F> = (0..self.domain_size())
+            .map(|j| {
+                let row: Vec<F> = batch.evals.iter().map(|evals| evals[j]).collect();
+                combine(&row, &values, alpha) * (domain.element(j) - point).inverse().unwrap()
+            })
+            .collect();
+
+        let quotient = prove_low_degree(
+            &quotient_evals,
+            self.degree_bound,
+            &self.config,
+            &opening_domain,
+        );
+        let rows = quotient
+            .query_openings
+            .iter()
+            .map(|openings| {
+                let j = openings[0].proof.leaf_index;
+                (
+                    batch.evals.iter().map(|evals| evals[j]).collect(),
+                    batch.tree.generate_proof_at(j).unwrap(),
+                )
+            })
+            .collect();
+
+        (values, FriBatchOpening { quotient, rows })
+    }
+
+    pub fn check_batch_evaluation(
+        &self,
+        root: &[u8],
+        point: F,
+        values: &[F],
+        proof: &FriBatchOpening<F>,
+    ) -> Result<(), PcsError> {
+        if self.in_domain(point) {
+            return Err(PcsError::PointInDomain);
+        }
+        if values.is_empty()
+            || proof.rows.len() != self.config.num_queries
+            || proof.rows.iter().any(|(row, _)| row.len() != values.len())
+        {
+            return Err(PcsError::MalformedProof);
+        }
+
+        let opening_domain = self.opening_domain(root, point, values);
+        if !verify_low_degree(
+            &proof.quotient,
+            self.degree_bound,
+            &self.config,
+            &opening_domain,
+        ) {
+            return Err(PcsError::Rejected);
+        }
+
+        let alpha = Self::batch_combiner(&opening_domain);
+        let domain = self.lde_domain();
+        let consistent = proof
+            .quotient
+            .query_openings
+            .iter()
+            .zip(proof.rows.iter())
+            .all(|(openings, (row, path))| {
+                let q = &openings[0];
+                let j = q.proof.leaf_index;
+
+                check_row_opening(j, row, path, root)
+                    && q.value * (domain.element(j) - point) == combine(row, values, alpha)
+            });
+
+        if consistent {
+            Ok(())
+        } else {
+            Err(PcsError::Rejected)
+        }
+    }
+}
+
+// Σ αⁱ (rowᵢ - valueᵢ)
+fn combine<F: PrimeField>(row: &[F], values: &[F], alpha: F) -> F {
+    row.iter()
+        .zip(values.iter())
+        .rev()
+        .fold(F::zero(), |acc, (eval, value)| {
+            acc * alpha + (*eval - value)
+        })
+}
+
+impl<F: FftField + PrimeField> PolynomialCommitment for FriKey<F> {
+    type Commitment = Vec<u8>;
+    type Point = F;
+    type Value = F;
+    type Proof = FriOpening<F>;
+
+    fn check_evaluation(
+        &self,
+        root: &Vec<u8>,
+        point: &F,
+        value: &F,
+        proof: &FriOpening<F>,
+    ) -> Result<(), PcsError> {
+        if self.in_domain(*point) {
+            return Err(PcsError::PointInDomain);
+        }
+        if proof.values.len() != self.config.num_queries {
+            return Err(PcsError::MalformedProof);
+        }
+
+        let opening_domain = self.opening_domain(root, *point, &[*value]);
+        if !verify_low_degree(
+            &proof.quotient,
+            self.degree_bound,
+            &self.config,
+            &opening_domain,
+        ) {
+            return Err(PcsError::Rejected);
+        }
+
+        let domain = self.lde_domain();
+        let consistent = proof
+            .quotient
+            .query_openings
+            .iter()
+            .zip(proof.values.iter())
+            .all(|(openings, (f_x, path))| {
+                let q = &openings[0];
+                let j = q.proof.leaf_index;
+
+                check_opening(j, *f_x, path, root)
+                    && q.value * (domain.element(j) - point) == *f_x - value
+            });
+
+        if consistent {
+            Ok(())
+        } else {
+            Err(PcsError::Rejected)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-377"))]
+mod test {
+    use super::*;
+    use crate::{pcs::check_evaluation, transcript::test::domain};
+    use ark_bls12_377::Fr;
+
+    fn key() -> FriKey<Fr> {
+        FriKey::new(6, LowDegreeConfig::default(), domain())
+    }
+
+    #[test]
+    fn test_fri_check_evaluation() {
+        let key = key();
+        // f(x) = 5 + 3x + 2x^3 + x^4 + 7x^5
+        let coefficients = [5, 3, 0, 2, 1, 7].map(Fr::from);
+        let (root, poly) = key.commit(&coefficients);
+        let point = Fr::from(2);
+        let (value, proof) = key.open(&poly, point);
+        assert_eq!(value, Fr::from(5 + 6 + 16 + 16 + 224));
+
+        assert_eq!(
+            check_evaluation(&root, &point, &value, &proof, &key),
+            Ok(())
+        );
+        assert_eq!(
+            check_evaluation(&root, &point, &(value + Fr::from(1)), &proof, &key),
+            Err(PcsError::Rejected)
+        );
+        assert_eq!(
+            check_evaluation(&root, &Fr::from(1), &value, &proof, &key),
+            Err(PcsError::PointInDomain)
+        );
+    }
+
+    #[test]
+    fn test_fri_on_a_coset() {
+        let key = FriKey::new(6, LowDegreeConfig::default().with_blinding(1), domain());
+        let coefficients = [5, 3, 0, 2, 1, 7].map(Fr::from);
+        let (root, poly) = key.commit(&coefficients);
+        assert_ne!(root, self::key().commit(&coefficients).0);
+
+        // 1 is no longer a point of the domain, g is
+        let (value, proof) = key.open(&poly, Fr::from(1));
+        assert_eq!(
+            check_evaluation(&root, &Fr::from(1), &value, &proof, &key),
+            Ok(())
+        );
+        assert_eq!(
+            check_evaluation(&root, &Fr::GENERATOR, &value, &proof, &key),
+            Err(PcsError::PointInDomain)
+        );
+    }
+
+    #[test]
+    fn test_fri_rejects_wrong_polynomial() {
+        let key = key();
+        let (root, _) = key.commit(&[5, 3, 0, 2, 1, 7].map(Fr::from));
+        // an honest opening of another polynomial does not open this root
+        let (_, other) = key.commit(&[1, 1, 1].map(Fr::from));
+        let (value, proof) = key.open(&other, Fr::from(2));
+
+        assert_eq!(
+            check_evaluation(&root, &Fr::from(2), &value, &proof, &key),
+            Err(PcsError::Rejected)
+        );
+
+        let mut short = proof.clone();
+        short.values.pop();
+        assert_eq!(
+            check_evaluation(&root, &Fr::from(2), &value, &short, &key),
+            Err(PcsError::MalformedProof)
+        );
+    }
+
+    #[test]
+    fn test_fri_batch_shares_one_tree() {
+        let key = key();
+        let f = [5, 3, 0, 2, 1, 7].map(Fr::from);
+        let g = [1, 1, 1].map(Fr::from);
+        let (root, batch) = key.commit_batch(&[&f, &g]);
+        assert_eq!(batch.tree.layers[0].len(), key.domain_size());
+
+        let point = Fr::from(2);
+        let (values, proof) = key.open_batch(&batch, point);
+        assert_eq!(values, vec![Fr::from(267), Fr::from(7)]);
+        assert_eq!(
+            key.check_batch_evaluation(&root, point, &values, &proof),
+            Ok(())
+        );
+
+        // a wrong value for either polynomial, or the values swapped
+        let wrong = vec![values[0], values[1] + Fr::from(1)];
+        assert_eq!(
+            key.check_batch_evaluation(&root, point, &wrong, &proof),
+            Err(PcsError::Rejected)
+        );
+        let swapped = vec![values[1], values[0]];
+        assert_eq!(
+            key.check_batch_evaluation(&root, point, &swapped, &proof),
+            Err(PcsError::Rejected)
+        );
+        assert_eq!(
+            key.check_batch_evaluation(&root, point, &values[..1], &proof),
+            Err(PcsError::MalformedProof)
+        );
+
+        // a single polynomial root does not open as a batch
+        let (single_root, _) = key.commit(&f);
+        assert_eq!(
+            key.check_batch_evaluation(&single_root, point, &values, &proof),
+            Err(PcsError::Rejected)
+        );
+    }
+}
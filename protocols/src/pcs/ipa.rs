@@ -0,0 +1,323 @@
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+#[cfg(feature = "prover")]
+use {ark_ff::UniformRand, rand::Rng};
+
+use crate::{
+    bulletproofs::inner_product::{self, inner_product, msm, InnerProductProof},
+    hash::{
+        generators::{derive_generators, GeneratorSetId},
+        hash_to_curve::HashToCurveGroup,
+    },
+    multi_linear::MultiLinearPoly,
+    pcs::{PcsError, PolynomialCommitment},
+    proof_format::{
+        ensure_consumed, read_header, read_points, write_header, DecodeLimits, ProofFormatError,
+        ProtocolId,
+    },
+    transcript::{Transcript, TranscriptDomain, DIGEST_LEN},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+//=========================================================================================
+// Univariate PCS from the inner product argument (no pairing, no trusted setup)
+// C = <a, G> commits to the coefficients a, f(z) = <a, b> with b = (1, z, z², ..., zⁿ⁻¹)
+// b is public, so the verifier builds P = C + <b, H> + v·U itself and the argument shows the
+// prover knows a with P = <a, G> + <b, H> + <a, b>·U, i.e. <a, b> = v for the committed a
+// the transcript starts from the key's domain and a fixed label and absorbs C, z and v before the
+// rounds, the key carries the domain (as FriKey does) so check_evaluation keeps its signature
+// verification is O(n) group operations, the proof is 2·log₂(n) points and two scalars
+// derive builds the generators from a domain string (hash::generators), so prover and verifier
+// only have to agree on the string, and every proof names the generator set it was made with
+//=========================================================================================
+const LABEL: &[u8] = b"pcs-ipa";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpaKey<G: CurveGroup> {
+    pub g_vec: Vec<G>,
+    pub h_vec: Vec<G>,
+    pub u: G,
+    pub id: GeneratorSetId, // of g_vec, h_vec and u
+    pub domain: TranscriptDomain,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpaProof<G: CurveGroup> {
+    pub generators: GeneratorSetId,
+    pub ipa: InnerProductProof<G>,
+}
+
+fn powers<F: PrimeField>(z: F, n: usize) -> Vec<F> {
+    std::iter::successors(Some(F::one()), |power| Some(*power * z))
+        .take(n)
+        .collect()
+}
+
+fn opening_transcript<G: CurveGroup>(
+    transcript_domain: &TranscriptDomain,
+    commitment: &G,
+    point: G::ScalarField,
+    value: G::ScalarField,
+) -> Transcript {
+    let mut transcript = transcript_domain.transcript();
+    transcript.absorb(LABEL);
+    transcript.absorb_commitment(&commitment.into_affine());
+    transcript.absorb(&MultiLinearPoly::to_bytes(&[point, value]));
+
+    transcript
+}
+
+impl<G: CurveGroup> IpaKey<G> {
+    pub fn new(g_vec: Vec<G>, h_vec: Vec<G>, u: G, transcript_domain: &TranscriptDomain) -> Self {
+        if g_vec.len() != h_vec.len() || !g_vec.len().is_power_of_two() {
+            panic!("The generator vectors must have the same power of two length");
+        }
+        let id = GeneratorSetId::of(g_vec.iter().chain(h_vec.iter()).chain([&u]));
+
+        IpaKey {
+            g_vec,
+            h_vec,
+            u,
+            id,
+            domain: transcript_domain.clone(),
+        }
+    }
+
+    // polynomials of up to max_coefficients coefficients (rounded up to a power of two)
+    #[cfg(feature = "prover")]
+    pub fn setup<R: Rng>(
+        max_coefficients: usize,
+        transcript_domain: &TranscriptDomain,
+        rng: &mut R,
+    ) -> Self {
+        let n = max_coefficients.next_power_of_two();
+        let mut random_point =
+            || G::generator().mul_bigint(G::ScalarField::rand(rng).into_bigint());
+
+        let g_vec = (0..n).map(|_| random_point()).collect();
+        let h_vec = (0..n).map(|_| random_point()).collect();
+        IpaKey::new(g_vec, h_vec, random_point(), transcript_domain)
+    }
+
+    // the same generators on both sides from nothing but the domain string
+    pub fn derive(
+        domain: &[u8],
+        max_coefficients: usize,
+        transcript_domain: &TranscriptDomain,
+    ) -> Self
+    where
+        G: HashToCurveGroup,
+    {
+        let n = max_coefficients.next_power_of_two();
+
+        IpaKey::new(
+            derive_generators(domain, "g", n),
+            derive_generators(domain, "h", n),
+            derive_generators(domain, "u", 1)[0],
+            transcript_domain,
+        )
+    }
+
+    fn pad(&self, coefficients: &[G::ScalarField]) -> Vec<G::ScalarField> {
+        if coefficients.len() > self.g_vec.len() {
+            panic!("The polynomial has more coefficients than the key supports");
+        }
+
+        let mut padded = coefficients.to_vec();
+        padded.resize(self.g_vec.len(), G::ScalarField::from(0u64));
+        padded
+    }
+
+    pub fn commit(&self, coefficients: &[G::ScalarField]) -> G {
+        msm(&self.g_vec, &self.pad(coefficients))
+    }
+
+    // (f(z), proof)
+    pub fn open(
+        &self,
+        coefficients: &[G::ScalarField],
+        point: G::ScalarField,
+    ) -> (G::ScalarField, IpaProof<G>) {
+        let a = self.pad(coefficients);
+        let b = powers(point, a.len());
+        let value = inner_product(&a, &b);
+
+        let mut transcript = opening_transcript(&self.domain, &msm(&self.g_vec, &a), point, value);
+        let ipa = inner_product::prove(&mut transcript, &self.g_vec, &self.h_vec, &self.u, &a, &b);
+
+        (
+            value,
+            IpaProof {
+                generators: self.id,
+                ipa,
+            },
+        )
+    }
+}
+
+impl<G: CurveGroup> IpaProof<G> {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofFormatError> {
+        let mut bytes = Vec::new();
+        write_header(ProtocolId::Ipa, &mut bytes);
+
+        bytes.extend_from_slice(&self.generators.0);
+        self.ipa.l_vec.serialize_compressed(&mut bytes)?;
+        self.ipa.r_vec.serialize_compressed(&mut bytes)?;
+        self.ipa.a.serialize_compressed(&mut bytes)?;
+        self.ipa.b.serialize_compressed(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    pub fn from_bytes_with_limits(
+        mut bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, ProofFormatError> {
+        limits.check_size(bytes)?;
+        read_header(&mut bytes, ProtocolId::Ipa)?;
+
+        let generators = GeneratorSetId(<[u8; DIGEST_LEN]>::deserialize_compressed(&mut bytes)?);
+        let l_vec = read_points::<G>(&mut bytes, limits)?;
+        let r_vec = read_points::<G>(&mut bytes, limits)?;
+        if l_vec.len() != r_vec.len() {
+            return Err(ProofFormatError::InconsistentLengths {
+                expected: l_vec.len(),
+                found: r_vec.len(),
+            });
+        }
+        let a = G::ScalarField::deserialize_compressed(&mut bytes)?;
+        let b = G::ScalarField::deserialize_compressed(&mut bytes)?;
+        ensure_consumed(bytes)?;
+
+        Ok(IpaProof {
+            generators,
+            ipa: InnerProductProof { l_vec, r_vec, a, b },
+        })
+    }
+}
+
+impl<G: CurveGroup> PolynomialCommitment for IpaKey<G> {
+    type Commitment = G;
+    type Point = G::ScalarField;
+    type Value = G::ScalarField;
+    type Proof = IpaProof<G>;
+
+    fn check_evaluation(
+        &self,
+        commitment: &G,
+        point: &G::ScalarField,
+        value: &G::ScalarField,
+        proof: &IpaProof<G>,
+    ) -> Result<(), PcsError> {
+        if proof.generators != self.id {
+            return Err(PcsError::GeneratorMismatch);
+        }
+        let proof = &proof.ipa;
+        let n = self.g_vec.len();
+        if proof.l_vec.len() != n.ilog2() as usize || proof.r_vec.len() != proof.l_vec.len() {
+            return Err(PcsError::MalformedProof);
+        }
+
+        let b = powers(*point, n);
+        let p = *commitment + msm(&self.h_vec, &b) + self.u.mul_bigint(value.into_bigint());
+        let mut transcript = opening_transcript(&self.domain, commitment, *point, *value);
+
+        if inner_product::verify(
+            &mut transcript,
+            &self.g_vec,
+            &self.h_vec,
+            &self.u,
+            &p,
+            proof,
+        ) {
+            Ok(())
+        } else {
+            Err(PcsError::Rejected)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::{pcs::check_evaluation, transcript::test::domain};
+    use ark_bn254::{Fr, G1Projective};
+
+    #[test]
+    fn test_ipa_check_evaluation() {
+        let mut rng = rand::thread_rng();
+        let key = IpaKey::<G1Projective>::setup(6, &domain(), &mut rng);
+        assert_eq!(key.g_vec.len(), 8);
+
+        // f(x) = 5 + 3x + 2x^3 + x^4 + 7x^5
+        let coefficients = [5, 3, 0, 2, 1, 7].map(Fr::from);
+        let commitment = key.commit(&coefficients);
+        let point = Fr::from(2);
+        let (value, proof) = key.open(&coefficients, point);
+        assert_eq!(value, Fr::from(5 + 6 + 16 + 16 + 224));
+
+        assert_eq!(
+            check_evaluation(&commitment, &point, &value, &proof, &key),
+            Ok(())
+        );
+        assert_eq!(
+            check_evaluation(&commitment, &point, &(value + Fr::from(1)), &proof, &key),
+            Err(PcsError::Rejected)
+        );
+        assert_eq!(
+            check_evaluation(&commitment, &Fr::from(3), &value, &proof, &key),
+            Err(PcsError::Rejected)
+        );
+
+        let mut short = proof.clone();
+        short.ipa.l_vec.pop();
+        assert_eq!(
+            check_evaluation(&commitment, &point, &value, &short, &key),
+            Err(PcsError::MalformedProof)
+        );
+
+        // the same generators under another transcript domain
+        let other = IpaKey {
+            domain: TranscriptDomain::new(b"other-application", [0; DIGEST_LEN]),
+            ..key.clone()
+        };
+        assert_eq!(
+            check_evaluation(&commitment, &point, &value, &proof, &other),
+            Err(PcsError::Rejected)
+        );
+    }
+
+    #[test]
+    fn test_derived_key() {
+        let key = IpaKey::<G1Projective>::derive(b"ipa-test", 6, &domain());
+        assert_eq!(key, IpaKey::derive(b"ipa-test", 5, &domain()));
+        assert_eq!(key.g_vec.len(), 8);
+
+        let coefficients = [5, 3, 0, 2, 1, 7].map(Fr::from);
+        let commitment = key.commit(&coefficients);
+        let (value, proof) = key.open(&coefficients, Fr::from(2));
+        let proof = IpaProof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+        assert_eq!(proof.generators, key.id);
+        assert_eq!(
+            check_evaluation(&commitment, &Fr::from(2), &value, &proof, &key),
+            Ok(())
+        );
+
+        // a verifier on another domain or another size names other generators
+        for other in [
+            IpaKey::<G1Projective>::derive(b"ipa-other", 6, &domain()),
+            IpaKey::derive(b"ipa-test", 16, &domain()),
+            IpaKey::setup(6, &domain(), &mut rand::thread_rng()),
+        ] {
+            assert_ne!(other.id, key.id);
+            assert_eq!(
+                check_evaluation(&commitment, &Fr::from(2), &value, &proof, &other),
+                Err(PcsError::GeneratorMismatch)
+            );
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use ark_ec::pairing::Pairing;
+
+use crate::{
+    kzg::{
+        kzg_protocol::{self, KZGProof},
+        trusted_setup::VerifierSetup,
+    },
+    pcs::{PcsError, PolynomialCommitment},
+};
+
+// the opening of kzg_protocol::proof without the commitment and value it repeats
+impl<P: Pairing> PolynomialCommitment for VerifierSetup<P> {
+    type Commitment = P::G1;
+    type Point = Vec<P::ScalarField>;
+    type Value = P::ScalarField;
+    type Proof = Vec<P::G1>; // the quotient commitments
+
+    fn check_evaluation(
+        &self,
+        commitment: &P::G1,
+        point: &Vec<P::ScalarField>,
+        value: &P::ScalarField,
+        quotients: &Vec<P::G1>,
+    ) -> Result<(), PcsError> {
+        let num_vars = self.max_num_vars();
+        if point.len() != num_vars {
+            return Err(PcsError::PointLength {
+                expected: num_vars,
+                found: point.len(),
+            });
+        }
+        if quotients.len() != num_vars {
+            return Err(PcsError::MalformedProof);
+        }
+
+        let proof = KZGProof {
+            commitment: *commitment,
+            quotient_evals: quotients.clone(),
+            poly_opened: *value,
+        };
+
+        if kzg_protocol::verify(&proof, self, point) {
+            Ok(())
+        } else {
+            Err(PcsError::Rejected)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use crate::{
+        commitment::VectorCommitment,
+        kzg::{kzg_helper_functions::test::poly_1, trusted_setup::tests::setup},
+        pcs::{check_evaluation, PcsError},
+    };
+    use ark_bls12_381::Fr;
+
+    #[test]
+    fn test_kzg_check_evaluation() {
+        let setup = setup();
+        let (commitment, poly) = setup.commit(&poly_1().computation);
+        let point = vec![Fr::from(2), Fr::from(3), Fr::from(4)];
+        let (value, quotients) = setup.open(&poly, &point).unwrap();
+        let vk = &setup.verifier_setup;
+
+        assert_eq!(
+            check_evaluation(&commitment, &point, &value, &quotients, vk),
+            Ok(())
+        );
+        assert_eq!(
+            check_evaluation(&commitment, &point, &(value + Fr::from(1)), &quotients, vk),
+            Err(PcsError::Rejected)
+        );
+        assert_eq!(
+            check_evaluation(&commitment, &point[..2].to_vec(), &value, &quotients, vk),
+            Err(PcsError::PointLength {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            check_evaluation(&commitment, &point, &value, &quotients[..2].to_vec(), vk),
+            Err(PcsError::MalformedProof)
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PcsError {
+    // the point has another number of variables (or coefficients) than the key was made for
+    PointLength { expected: usize, found: usize },
+    // the proof has the wrong number of rounds / quotients / openings for the key
+    MalformedProof,
+    // FRI cannot open at a point of its own evaluation domain, x - z vanishes there
+    PointInDomain,
+    // the proof was made with other commitment generators than the key's (hash::generators)
+    GeneratorMismatch,
+    // well formed, but it does not open the commitment to the claimed value
+    Rejected,
+}
+
+impl fmt::Display for PcsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PcsError::PointLength { expected, found } => write!(
+                f,
+                "the key opens points of length {} but the point has {}",
+                expected, found
+            ),
+            PcsError::MalformedProof => {
+                write!(f, "the proof does not have the shape the key expects")
+            }
+            PcsError::PointInDomain => {
+                write!(f, "the point lies in the FRI evaluation domain")
+            }
+            PcsError::GeneratorMismatch => {
+                write!(f, "the proof was made with other generators than the key")
+            }
+            PcsError::Rejected => {
+                write!(
+                    f,
+                    "the proof does not open the commitment to the claimed value"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PcsError {}
@@ -0,0 +1,270 @@
+use crate::{
+    fri::low_degree::LowDegreeProof, gkr::gkr_protocol::GKRProof, kzg::kzg_protocol::KZGProof,
+};
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use ark_serialize::CanonicalSerialize;
+use std::fmt;
+
+#[cfg(feature = "compression")]
+use crate::proof_format::{read_header, write_header, DecodeLimits, ProofFormatError, ProtocolId};
+#[cfg(feature = "compression")]
+use ark_serialize::CanonicalDeserialize;
+
+//=========================================================================================
+// Where the bytes of a proof go
+// size_report() splits the serialized proof into its parts so the sum of the components is
+// exactly to_bytes().len(), printing the report gives one line per component with its share
+// LowDegreeProof has no byte format yet, its report counts the raw payload (hashes, compressed
+// field elements, 8 bytes per leaf index and for the nonce)
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeReport {
+    pub components: Vec<(&'static str, usize)>,
+}
+
+impl SizeReport {
+    fn new() -> Self {
+        SizeReport {
+            components: Vec::new(),
+        }
+    }
+
+    fn add(mut self, name: &'static str, bytes: usize) -> Self {
+        self.components.push((name, bytes));
+        self
+    }
+
+    pub fn total(&self) -> usize {
+        self.components.iter().map(|(_, bytes)| bytes).sum()
+    }
+
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.components
+            .iter()
+            .find(|(component, _)| *component == name)
+            .map(|(_, bytes)| *bytes)
+    }
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total();
+
+        for (name, bytes) in self.components.iter() {
+            let share = if total == 0 {
+                0.0
+            } else {
+                100.0 * *bytes as f64 / total as f64
+            };
+            writeln!(f, "{:<20} {:>10} bytes {:>5.1}%", name, bytes, share)?;
+        }
+        write!(f, "{:<20} {:>10} bytes", "total", total)
+    }
+}
+
+// header written by write_header
+const HEADER_LEN: usize = 2;
+// ark length prefix of a Vec
+const LEN_PREFIX: usize = 8;
+
+impl<F: PrimeField> GKRProof<F> {
+    pub fn size_report(&self) -> SizeReport {
+        let challenges: usize = self
+            .p_proofs
+            .iter()
+            .map(|p_proof| p_proof.challenges.compressed_size())
+            .sum();
+        let round_polys: usize = self
+            .p_proofs
+            .iter()
+            .map(|p_proof| p_proof.round_polys.compressed_size())
+            .sum();
+        // degree, product count and claimed sum of every sum-check, and the number of sum-checks
+        let claims = LEN_PREFIX + self.p_proofs.len() * (16 + F::zero().compressed_size());
+
+        SizeReport::new()
+            .add("header", HEADER_LEN)
+            .add("output layer", self.output_layer.compressed_size())
+            .add("layer evals", self.w_i_evals.compressed_size())
+            .add("sum-check claims", claims)
+            .add("challenges", challenges)
+            .add("round polys", round_polys)
+            .add("line polys", self.line_polys.compressed_size())
+    }
+}
+
+impl<F: PrimeField, P: Pairing> KZGProof<F, P> {
+    pub fn size_report(&self) -> SizeReport {
+        SizeReport::new()
+            .add("header", HEADER_LEN)
+            .add("commitment", self.commitment.compressed_size())
+            .add("quotients", self.quotient_evals.compressed_size())
+            .add("opening", self.poly_opened.compressed_size())
+    }
+}
+
+impl<F: PrimeField> LowDegreeProof<F> {
+    pub fn size_report(&self) -> SizeReport {
+        let openings = self.query_openings.iter().flatten();
+        let values: usize = openings
+            .clone()
+            .map(|opening| (opening.value, opening.neg_value).compressed_size())
+            .sum();
+        let paths: usize = openings
+            .flat_map(|opening| [&opening.proof, &opening.neg_proof])
+            .map(|path| path.encoded_len())
+            .sum();
+
+        SizeReport::new()
+            .add("merkle roots", self.root_hashes.iter().map(Vec::len).sum())
+            .add(
+                "final layer",
+                self.final_layer.compressed_size() - LEN_PREFIX,
+            )
+            .add("openings", values)
+            .add("merkle paths", paths)
+            .add("pow nonce", 8)
+    }
+}
+
+//=========================================================================================
+// Compressed container for any serialized proof, behind the compression feature
+// byte layout: header (version, Compressed) || u64 length of the proof || zstd frame
+// the inner bytes are an ordinary proof with their own header, decompress hands them back
+// the declared length is checked against the limits before anything is inflated and zstd is
+// never allowed to write more than that, so a small container can't blow up into gigabytes
+//=========================================================================================
+#[cfg(feature = "compression")]
+const COMPRESSION_LEVEL: i32 = 19;
+
+#[cfg(feature = "compression")]
+pub fn compress(proof_bytes: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_header(ProtocolId::Compressed, &mut bytes);
+    (proof_bytes.len() as u64)
+        .serialize_compressed(&mut bytes)
+        .unwrap();
+    bytes.extend(
+        zstd::bulk::compress(proof_bytes, COMPRESSION_LEVEL)
+            .expect("Compressing into memory can't fail"),
+    );
+
+    bytes
+}
+
+#[cfg(feature = "compression")]
+pub fn decompress(bytes: &[u8]) -> Result<Vec<u8>, ProofFormatError> {
+    decompress_with_limits(bytes, &DecodeLimits::default())
+}
+
+#[cfg(feature = "compression")]
+pub fn decompress_with_limits(
+    mut bytes: &[u8],
+    limits: &DecodeLimits,
+) -> Result<Vec<u8>, ProofFormatError> {
+    limits.check_size(bytes)?;
+    read_header(&mut bytes, ProtocolId::Compressed)?;
+
+    let len = u64::deserialize_compressed(&mut bytes)?;
+    if len > limits.max_bytes as u64 {
+        return Err(ProofFormatError::TooLarge {
+            len: len as usize,
+            max: limits.max_bytes,
+        });
+    }
+
+    let proof_bytes =
+        zstd::bulk::decompress(bytes, len as usize).map_err(ProofFormatError::Decompression)?;
+    if proof_bytes.len() as u64 != len {
+        return Err(ProofFormatError::InconsistentLengths {
+            expected: len as usize,
+            found: proof_bytes.len(),
+        });
+    }
+
+    Ok(proof_bytes)
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::{
+        fri::{
+            lde::lde,
+            low_degree::{prove_low_degree, LowDegreeConfig},
+        },
+        gkr::chunked_sum_check::ProverConfig,
+        gkr::{gkr_2_to_1_trick::LayerReduction, gkr_circuit::test::setup_test_circuit8},
+        kzg::{kzg_helper_functions::test::poly_1, kzg_protocol, trusted_setup::tests::setup},
+        transcript::test::domain,
+    };
+    use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+    use ark_bn254::Fr;
+
+    #[test]
+    fn test_report_adds_up_to_the_proof() {
+        let circuit = setup_test_circuit8();
+        for reduction in [LayerReduction::AlphaBeta, LayerReduction::Line] {
+            let config = ProverConfig {
+                reduction,
+                ..Default::default()
+            };
+            let proof = circuit.proof_with_config(&config, &domain());
+            let report = proof.size_report();
+
+            assert_eq!(report.total(), proof.to_bytes().unwrap().len());
+            assert_eq!(
+                report.get("line polys") == Some(LEN_PREFIX),
+                reduction == LayerReduction::AlphaBeta
+            );
+        }
+
+        let setup = setup();
+        let point = [6, 4, 0].map(BlsFr::from);
+        let proof = kzg_protocol::proof::<BlsFr, Bls12_381>(poly_1(), &setup.g1_arr, &point);
+        let report = proof.size_report();
+        assert_eq!(report.total(), proof.to_bytes().unwrap().len());
+        assert!(report
+            .to_string()
+            .ends_with(&format!("{} bytes", report.total())));
+    }
+
+    #[test]
+    fn test_low_degree_report() {
+        let evals = lde(&[5, 3, 0, 2, 1, 7].map(Fr::from), 2, Fr::from(1));
+        let proof = prove_low_degree(&evals, 6, &LowDegreeConfig::default(), &domain());
+        let report = proof.size_report();
+
+        assert_eq!(
+            report.get("merkle roots"),
+            Some(32 * proof.root_hashes.len())
+        );
+        assert_eq!(report.get("pow nonce"), Some(8));
+        assert!(report.get("merkle paths").unwrap() > report.get("openings").unwrap());
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_compressed_container_roundtrip() {
+        let proof = setup_test_circuit8().proof(&domain()).to_bytes().unwrap();
+        let container = compress(&proof);
+
+        assert_eq!(decompress(&container).unwrap(), proof);
+        // an ordinary proof is not a container
+        assert!(matches!(
+            decompress(&proof),
+            Err(ProofFormatError::ProtocolMismatch { .. })
+        ));
+
+        // a declared length past the limits is refused before inflating
+        let small = DecodeLimits {
+            max_bytes: proof.len() - 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            decompress_with_limits(&container, &small),
+            Err(ProofFormatError::TooLarge { .. })
+        ));
+    }
+}
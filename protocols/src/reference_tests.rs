@@ -0,0 +1,165 @@
+//=========================================================================================
+// Differential tests against the arkworks reference implementations (ark-poly, dev only)
+// every check runs on fresh random inputs so an algebra bug that the hand picked vectors in
+// the module tests happen to miss still shows up
+// FFT / IFFT          fri::fft           vs Radix2EvaluationDomain
+// multilinear eval    multi_linear       vs DenseMultilinearExtension
+// univariate ops      UnivariatePoly     vs DensePolynomial
+// KZG commitment      kzg_protocol       vs g1 * MLE(τ) through DenseMultilinearExtension
+// the KZG here is multilinear so there is no ark-poly-commit scheme with matching outputs,
+// the commitment is checked against the reference MLE at the (known) test taus instead
+// NOTE: ark-poly reads the evaluation index little endian (bit 0 is the first variable),
+// MultiLinearPoly is big endian (the first variable splits the array in halves)
+//=========================================================================================
+use crate::{
+    fri::fft::FastFourierTransform,
+    kzg::{kzg_helper_functions::compute_commitment, kzg_protocol, trusted_setup},
+    multi_linear::MultiLinearPoly,
+    UnivariatePoly,
+};
+use ark_bls12_381::{Bls12_381, Fr as BlsFr, G1Projective};
+use ark_bn254::Fr;
+use ark_ec::PrimeGroup;
+use ark_ff::PrimeField;
+use ark_poly::{
+    univariate::DensePolynomial, DenseMultilinearExtension, DenseUVPolynomial, EvaluationDomain,
+    MultilinearExtension, Polynomial, Radix2EvaluationDomain,
+};
+use rand::thread_rng;
+
+const RUNS: usize = 8;
+
+fn random_vec<F: PrimeField>(len: usize) -> Vec<F> {
+    let mut rng = thread_rng();
+    (0..len).map(|_| F::rand(&mut rng)).collect()
+}
+
+// the same poly as an ark-poly MLE
+fn reference_mle<F: PrimeField>(evals: &[F]) -> DenseMultilinearExtension<F> {
+    DenseMultilinearExtension::from_evaluations_slice(evals.len().ilog2() as usize, evals)
+}
+
+fn reference_eval<F: PrimeField>(evals: &[F], point: &[F]) -> F {
+    let little_endian: Vec<F> = point.iter().rev().cloned().collect();
+    reference_mle(evals).evaluate(&little_endian)
+}
+
+#[test]
+fn test_fft_matches_radix2_domain() {
+    for log_n in 1..=6 {
+        let n = 1 << log_n;
+        let domain = Radix2EvaluationDomain::<Fr>::new(n).unwrap();
+
+        for _ in 0..RUNS {
+            let coefficients = random_vec::<Fr>(n);
+            let evals = FastFourierTransform::new(coefficients.clone()).evaluate();
+            assert_eq!(evals.coefficients, domain.fft(&coefficients));
+
+            let values = random_vec::<Fr>(n);
+            let interpolated = FastFourierTransform::new(values.clone()).interpolate();
+            assert_eq!(interpolated.coefficients, domain.ifft(&values));
+        }
+    }
+}
+
+#[test]
+fn test_mle_evaluate_matches_dense_mle() {
+    for num_vars in 1..=6 {
+        for _ in 0..RUNS {
+            let evals = random_vec::<Fr>(1 << num_vars);
+            let point = random_vec::<Fr>(num_vars);
+
+            let result = MultiLinearPoly::new(&evals).evaluate(&point).computation[0];
+            assert_eq!(result, reference_eval(&evals, &point));
+        }
+    }
+}
+
+#[test]
+fn test_mle_partial_evaluate_matches_fix_variables() {
+    for num_vars in 2..=6 {
+        let evals = random_vec::<Fr>(1 << num_vars);
+        let r = random_vec::<Fr>(1)[0];
+
+        // our last variable is ark-poly's first, fixing it leaves the same layout on both sides
+        let fixed_last = MultiLinearPoly::new(&evals).partial_evaluate(r, num_vars - 1);
+        assert_eq!(
+            fixed_last.computation,
+            reference_mle(&evals).fix_variables(&[r]).to_evaluations()
+        );
+
+        // fixing the first variable is checked on a random point of the remaining ones
+        let rest = random_vec::<Fr>(num_vars - 1);
+        let fixed_first = MultiLinearPoly::new(&evals)
+            .partial_evaluate(r, 0)
+            .evaluate(&rest)
+            .computation[0];
+        assert_eq!(
+            fixed_first,
+            reference_eval(&evals, &[vec![r], rest].concat())
+        );
+    }
+}
+
+#[test]
+fn test_univariate_matches_dense_polynomial() {
+    for _ in 0..RUNS {
+        let a = random_vec::<Fr>(7);
+        let b = random_vec::<Fr>(4);
+        let x = random_vec::<Fr>(1)[0];
+
+        let ours_a = UnivariatePoly {
+            coefficient: a.clone(),
+        };
+        let ours_b = UnivariatePoly {
+            coefficient: b.clone(),
+        };
+        let ref_a = DensePolynomial::from_coefficients_vec(a);
+        let ref_b = DensePolynomial::from_coefficients_vec(b);
+
+        assert_eq!(ours_a.evaluate(x), ref_a.evaluate(&x));
+        assert_eq!((&ours_a * &ours_b).coefficient, (&ref_a * &ref_b).coeffs);
+        assert_eq!((&ours_a + &ours_b).coefficient, (&ref_a + &ref_b).coeffs);
+    }
+}
+
+#[test]
+fn test_interpolate_matches_ifft() {
+    // interpolating on the roots of unity is exactly the IFFT
+    let n = 8;
+    let domain = Radix2EvaluationDomain::<Fr>::new(n).unwrap();
+    let xs: Vec<Fr> = (0..n).map(|i| domain.element(i)).collect();
+
+    for _ in 0..RUNS {
+        let ys = random_vec::<Fr>(n);
+        let mut expected = domain.ifft(&ys);
+        // UnivariatePoly keeps the full n coefficients, ark-poly trims trailing zeros
+        expected.resize(n, Fr::from(0));
+
+        assert_eq!(UnivariatePoly::interpolate(&xs, &ys).coefficient, expected);
+    }
+}
+
+#[test]
+fn test_kzg_commitment_matches_reference_mle() {
+    let num_vars = 4;
+    let taus = trusted_setup::insecure_taus::<BlsFr>(7, num_vars);
+    let setup = trusted_setup::initialize::<BlsFr, Bls12_381>(&taus);
+
+    for _ in 0..RUNS {
+        let evals = random_vec::<BlsFr>(1 << num_vars);
+        let poly = MultiLinearPoly::new(&evals);
+
+        // C(f) = g1_f(τ)
+        let commitment = compute_commitment::<BlsFr, Bls12_381>(&poly, &setup.g1_arr).unwrap();
+        let expected =
+            G1Projective::generator().mul_bigint(reference_eval(&evals, &taus).into_bigint());
+        assert_eq!(commitment, expected);
+
+        // and the opening is the reference evaluation
+        let point = random_vec::<BlsFr>(num_vars);
+        let proof = kzg_protocol::proof::<BlsFr, Bls12_381>(poly, &setup.g1_arr, &point);
+        assert_eq!(proof.poly_opened, reference_eval(&evals, &point));
+        assert!(kzg_protocol::verify(&proof, &setup.verifier_setup, &point));
+    }
+}
@@ -0,0 +1,94 @@
+//=========================================================================================
+// The transcript schedules of the protocols, checked against the cursor in transcript_schedule
+// (the schedules live with their protocols, the cursor with the transcript)
+//=========================================================================================
+use crate::{
+    gkr::{core::GKR_SCHEDULE, partial_sum_check::PARTIAL_SUM_CHECK_SCHEDULE},
+    sum_check::SUM_CHECK_SCHEDULE,
+    transcript::Transcript,
+    transcript_schedule::{Schedule, ScheduleCursor, Step},
+};
+
+#[test]
+fn test_nested_repeats() {
+    let mut cursor = ScheduleCursor::new(&GKR_SCHEDULE);
+    assert!(cursor.advance(Step::Absorb("public inputs")));
+    assert!(cursor.advance(Step::Squeeze("r_a")));
+    assert!(cursor.advance(Step::Squeeze("r_a")));
+    for _ in 0..2 {
+        assert!(cursor.advance(Step::Absorb("Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)")));
+        assert!(cursor.advance(Step::Squeeze("alpha")));
+        assert!(!cursor.advance(Step::Absorb("Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)")));
+        assert!(cursor.advance(Step::Squeeze("beta")));
+    }
+    assert!(cursor.advance(Step::Absorb("Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)")));
+    assert!(cursor.finished());
+    assert!(!cursor.advance(Step::Squeeze("r_a")));
+}
+
+#[test]
+#[should_panic(expected = "The transcript left the sum_check schedule at squeeze r")]
+fn test_transcript_off_schedule() {
+    let mut transcript = Transcript::new().with_schedule(&SUM_CHECK_SCHEDULE);
+    transcript.follow(Step::Absorb("poly")).absorb(b"poly");
+    assert!(!transcript.schedule_finished());
+    transcript.follow(Step::Squeeze("r")).squeeze();
+}
+
+fn lines(schedule: &Schedule) -> Vec<String> {
+    schedule.to_string().lines().map(String::from).collect()
+}
+
+// the schedules of record, a change here is a change of the wire format
+#[test]
+fn test_schedules() {
+    assert_eq!(
+        lines(&SUM_CHECK_SCHEDULE),
+        [
+            "sum_check",
+            "  absorb poly",
+            "  absorb claimed sum",
+            "  repeat rounds",
+            "    absorb round poly",
+            "    squeeze r",
+        ]
+    );
+    assert_eq!(
+        lines(&PARTIAL_SUM_CHECK_SCHEDULE),
+        [
+            "partial_sum_check",
+            "  absorb num_vars",
+            "  absorb degree",
+            "  absorb num_products",
+            "  absorb claimed sum",
+            "  repeat rounds",
+            "    absorb round poly",
+            "    squeeze r",
+        ]
+    );
+    assert_eq!(
+        lines(&GKR_SCHEDULE),
+        [
+            "gkr",
+            "  absorb public inputs",
+            "  repeat lookups (each proven in a fork of the transcript)",
+            "    absorb lookup claim",
+            "  repeat output layer (more than one output)",
+            "    absorb W₀",
+            "  repeat output point",
+            "    squeeze r_a",
+            "  repeat lookup on the output layer",
+            "    squeeze gamma",
+            "  repeat layers",
+            "    absorb Wᵢ₊₁(r_b), Wᵢ₊₁(r_c)",
+            "    repeat alpha, beta (AlphaBeta reduction, not after the input layer)",
+            "      squeeze alpha",
+            "      squeeze beta",
+            "    repeat line, q, r* (Line reduction, not after the input layer)",
+            "      absorb r_b ‖ r_c, q",
+            "      squeeze r*",
+            "    repeat lookup on the layer below (not the input layer)",
+            "      squeeze gamma",
+        ]
+    );
+}
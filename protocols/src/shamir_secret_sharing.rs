@@ -1,8 +1,21 @@
 use core::panic;
 
-use crate::UnivariatePoly;
+use crate::{
+    kzg::univariate_kzg::{self, UnivariateVerifierKey},
+    lagrange::lagrange_coefficients_at_zero,
+    proof_format::DecodeLimits,
+};
+use ark_ec::{pairing::Pairing, PrimeGroup};
 use ark_ff::PrimeField;
-use rand;
+use ark_serialize::{CanonicalSerialize, SerializationError};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "prover")]
+use {
+    crate::{kzg::univariate_kzg::UnivariateSetup, UnivariatePoly},
+    rand::{self, Rng},
+};
+
+pub mod dealing;
 
 #[derive(Debug)]
 pub struct ShamirShare<F: PrimeField> {
@@ -14,8 +27,27 @@ impl<F: PrimeField> ShamirShare<F> {
     pub fn new(x: F, y: F) -> Self {
         ShamirShare { x, y }
     }
+
+    // Feldman check done by the shareholder on the share it received
+    // g^y == Π C_j^(x^j) where C_j = g^(a_j) are the dealer's commitments to the coefficients
+    // (written additively below i.e. y * G == Σ x^j * C_j)
+    pub fn verify<G: PrimeGroup<ScalarField = F>>(&self, commitments: &[G]) -> bool {
+        if commitments.is_empty() {
+            return false;
+        }
+
+        let mut expected = G::zero();
+        let mut x_pow = F::one();
+        for commitment in commitments.iter() {
+            expected += commitment.mul_bigint(x_pow.into_bigint());
+            x_pow *= self.x;
+        }
+
+        G::generator().mul_bigint(self.y.into_bigint()) == expected
+    }
 }
 
+#[cfg(feature = "prover")]
 pub fn generate_shares<F: PrimeField>(
     secret: F,
     threshold: u8,
@@ -36,11 +68,96 @@ pub fn generate_shares<F: PrimeField>(
         .collect()
 }
 
+// same as generate_shares but also returns the Feldman commitments C_j = g^(a_j)
+// these are broadcast so every shareholder can run ShamirShare::verify on its own share
+#[cfg(feature = "prover")]
+pub fn generate_verifiable_shares<F: PrimeField, G: PrimeGroup<ScalarField = F>>(
+    secret: F,
+    threshold: u8,
+    num_shares: u8,
+) -> (Vec<ShamirShare<F>>, Vec<G>) {
+    if threshold > num_shares {
+        panic!("Threshold must be less than or equal to number of shares")
+    }
+
+    let polynomial = generate_random_polynomial(secret, threshold);
+    let x_values = generate_x_values(num_shares);
+
+    let shares = x_values
+        .iter()
+        .map(|x| ShamirShare::new(*x, polynomial.evaluate(*x)))
+        .collect();
+    let commitments = polynomial
+        .coefficient
+        .iter()
+        .map(|coefficient| G::generator().mul_bigint(coefficient.into_bigint()))
+        .collect();
+
+    (shares, commitments)
+}
+
+//=========================================================================================
+// KZG committed dealing, the pairing based alternative to the Feldman commitments
+// the dealer publishes one commitment C = f(τ)·g1 to the sharing polynomial (kzg::univariate_kzg)
+// instead of threshold points, and every share ships with the KZG opening of f at its x, so the
+// broadcast is one G1 point and checking a share is one multi pairing whatever the threshold
+// (Feldman is threshold scalar multiplications against threshold commitments)
+// the setup has to commit to degree threshold - 1, a share is still reconstructed as usual
+//=========================================================================================
+#[derive(Debug)]
+pub struct KzgShare<P: Pairing> {
+    pub share: ShamirShare<P::ScalarField>,
+    pub proof: P::G1, // the opening of f at share.x
+}
+
+impl<P: Pairing> KzgShare<P> {
+    pub fn verify(&self, commitment: &P::G1, verifier_key: &UnivariateVerifierKey<P>) -> bool {
+        univariate_kzg::verify(
+            commitment,
+            self.share.x,
+            self.share.y,
+            &self.proof,
+            verifier_key,
+        )
+    }
+}
+
+#[cfg(feature = "prover")]
+pub fn generate_kzg_shares<P: Pairing>(
+    secret: P::ScalarField,
+    threshold: u8,
+    num_shares: u8,
+    setup: &UnivariateSetup<P>,
+) -> (Vec<KzgShare<P>>, P::G1) {
+    if threshold > num_shares {
+        panic!("Threshold must be less than or equal to number of shares")
+    }
+
+    let polynomial = generate_random_polynomial(secret, threshold);
+    let commitment = univariate_kzg::commit(&polynomial, setup)
+        .expect("The setup must commit to polynomials of degree threshold - 1");
+
+    let shares = generate_x_values(num_shares)
+        .into_iter()
+        .map(|x| {
+            let (y, proof) = univariate_kzg::open(&polynomial, x, setup)
+                .expect("The quotient is smaller than the polynomial");
+            KzgShare {
+                share: ShamirShare::new(x, y),
+                proof,
+            }
+        })
+        .collect();
+
+    (shares, commitment)
+}
+
 // generate x values i.e. 1, 2, 3, 4, 5, 6, 7
 pub fn generate_x_values<F: PrimeField>(num_shares: u8) -> Vec<F> {
     (1..=num_shares).map(|x| F::from(x)).collect()
 }
 
+#[cfg(feature = "prover")]
 fn generate_random_polynomial<F: PrimeField>(secret: F, threshold: u8) -> UnivariatePoly<F> {
     let mut rng = rand::thread_rng();
     let mut coefficients = vec![secret];
@@ -57,15 +174,511 @@ pub fn reconstruct_secret<F: PrimeField>(shares: &[ShamirShare<F>], threshold: u
         panic!("Not enough shares to reconstruct secret")
     }
 
-    let xs: Vec<F> = shares.iter().map(|share| F::from(share.x)).collect();
-    let ys: Vec<F> = shares.iter().map(|share| F::from(share.y)).collect();
+    // f(0) = Σ λᵢ * yᵢ, no need for the dense poly
+    let xs: Vec<F> = shares.iter().map(|share| share.x).collect();
+
+    lagrange_coefficients_at_zero(&xs)
+        .iter()
+        .zip(shares.iter())
+        .map(|(coefficient, share)| *coefficient * share.y)
+        .sum()
+}
+
+//=========================================================================================
+// Batched dealing: many secrets, each with its own random polynomial, over the same x values
+// every x is raised to the powers 1..threshold once and reused by all the polynomials, and on
+// the way back the λᵢ (one batch inversion) are computed once and applied to every secret
+// shares[k] are the shares of secrets[k], so shares[k][i] is what participant i holds of it
+//=========================================================================================
+#[cfg(feature = "prover")]
+pub fn generate_shares_batch<F: PrimeField>(
+    secrets: &[F],
+    threshold: u8,
+    num_shares: u8,
+) -> Vec<Vec<ShamirShare<F>>> {
+    if threshold > num_shares {
+        panic!("Threshold must be less than or equal to number of shares")
+    }
+
+    let polynomials: Vec<UnivariatePoly<F>> = secrets
+        .iter()
+        .map(|secret| generate_random_polynomial(*secret, threshold))
+        .collect();
+    let x_values: Vec<F> = generate_x_values(num_shares);
+
+    let mut shares: Vec<Vec<ShamirShare<F>>> = secrets
+        .iter()
+        .map(|_| Vec::with_capacity(num_shares as usize))
+        .collect();
+    for x in x_values.iter() {
+        let x_powers: Vec<F> = std::iter::successors(Some(F::one()), |power| Some(*power * x))
+            .take(threshold as usize)
+            .collect();
+
+        for (polynomial, secret_shares) in polynomials.iter().zip(shares.iter_mut()) {
+            let y = polynomial
+                .coefficient
+                .iter()
+                .zip(x_powers.iter())
+                .map(|(coefficient, power)| *coefficient * power)
+                .sum();
+            secret_shares.push(ShamirShare::new(*x, y));
+        }
+    }
+
+    shares
+}
+
+// every secret has to come back from shares at the same x values, in the same order
+pub fn reconstruct_secrets_batch<F: PrimeField>(
+    shares: &[Vec<ShamirShare<F>>],
+    threshold: u8,
+) -> Vec<F> {
+    let xs: Vec<F> = match shares.first() {
+        Some(first) => first.iter().map(|share| share.x).collect(),
+        None => return Vec::new(),
+    };
+    if xs.len() < threshold as usize {
+        panic!("Not enough shares to reconstruct secret")
+    }
+    if shares.iter().any(|secret_shares| {
+        secret_shares.len() != xs.len()
+            || secret_shares
+                .iter()
+                .zip(xs.iter())
+                .any(|(share, x)| share.x != *x)
+    }) {
+        panic!("The batched shares must all use the same x values")
+    }
+
+    let coefficients = lagrange_coefficients_at_zero(&xs);
+    shares
+        .iter()
+        .map(|secret_shares| {
+            coefficients
+                .iter()
+                .zip(secret_shares.iter())
+                .map(|(coefficient, share)| *coefficient * share.y)
+                .sum()
+        })
+        .collect()
+}
+
+//=========================================================================================
+// Serialized share format
+// [version | scheme_id (8 bytes LE) | threshold | total_shares | has_digest | digest? |
+//  has_release | epoch, expiry, policy_id (8 bytes LE each)? | x | y | checksum]
+// scheme_id is picked at random by the dealer for every dealing so shares of different secrets
+// can't be mixed, and the checksum is the first 4 bytes of sha256 over everything before it
+// the release policy is covered by the checksum like the rest of the metadata, a share carrying
+// one only reconstructs through reconstruct_released_from_bytes at an epoch inside
+// [epoch, expiry) and under the same policy_id
+// version 1 shares (no has_release byte) still decode, without a release policy
+//=========================================================================================
+pub const SHARE_FORMAT_VERSION: u8 = 2;
+const LEGACY_SHARE_FORMAT_VERSION: u8 = 1;
+const CHECKSUM_LEN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReleasePolicy {
+    pub epoch: u64,  // first epoch the share may be used in
+    pub expiry: u64, // first epoch it may no longer be used in
+    pub policy_id: u64,
+}
+
+impl ReleasePolicy {
+    pub fn check(&self, now: u64, policy_id: u64) -> Result<(), ShareFormatError> {
+        if self.policy_id != policy_id {
+            return Err(ShareFormatError::WrongPolicy {
+                expected: policy_id,
+                found: self.policy_id,
+            });
+        }
+        if now < self.epoch {
+            return Err(ShareFormatError::NotYetReleased {
+                epoch: self.epoch,
+                now,
+            });
+        }
+        if now >= self.expiry {
+            return Err(ShareFormatError::Expired {
+                expiry: self.expiry,
+                now,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShareMetadata {
+    pub scheme_id: u64,
+    pub threshold: u8,
+    pub total_shares: u8,
+    pub commitment_digest: Option<[u8; 32]>, // digest of the Feldman commitments if the dealing was verifiable
+    pub release: Option<ReleasePolicy>,
+}
+
+impl ShareMetadata {
+    #[cfg(feature = "prover")]
+    pub fn new(threshold: u8, total_shares: u8) -> Self {
+        ShareMetadata {
+            scheme_id: rand::thread_rng().gen::<u64>(),
+            threshold,
+            total_shares,
+            commitment_digest: None,
+            release: None,
+        }
+    }
+
+    pub fn with_release(mut self, release: ReleasePolicy) -> Self {
+        if release.expiry <= release.epoch {
+            panic!("The release policy must expire after its first epoch");
+        }
+        self.release = Some(release);
+
+        self
+    }
+
+    pub fn with_commitments<G: CanonicalSerialize>(mut self, commitments: &[G]) -> Self {
+        let mut hasher = Sha256::new();
+        for commitment in commitments.iter() {
+            let mut bytes = Vec::new();
+            commitment
+                .serialize_compressed(&mut bytes)
+                .expect("Serializing into a Vec can't fail");
+            hasher.update(&bytes);
+        }
+
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(&hasher.finalize());
+        self.commitment_digest = Some(digest);
+
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum ShareFormatError {
+    Truncated,
+    BadChecksum,
+    UnsupportedVersion(u8),
+    InvalidDigestFlag(u8),
+    InvalidReleaseFlag(u8),
+    Serialization(SerializationError),
+    MixedShares, // shares don't come from the same dealing
+    NotEnoughShares { threshold: u8, found: usize },
+    TooLarge { len: usize, max: usize },
+    MissingReleasePolicy, // reconstruct_released_from_bytes on shares that have none
+    ReleasePolicyNotChecked, // reconstruct_from_bytes on shares that have one
+    NotYetReleased { epoch: u64, now: u64 },
+    Expired { expiry: u64, now: u64 },
+    WrongPolicy { expected: u64, found: u64 },
+}
+
+impl From<SerializationError> for ShareFormatError {
+    fn from(err: SerializationError) -> Self {
+        ShareFormatError::Serialization(err)
+    }
+}
+
+impl<F: PrimeField> ShamirShare<F> {
+    pub fn to_bytes(&self, metadata: &ShareMetadata) -> Vec<u8> {
+        let mut bytes = vec![SHARE_FORMAT_VERSION];
+        bytes.extend_from_slice(&metadata.scheme_id.to_le_bytes());
+        bytes.push(metadata.threshold);
+        bytes.push(metadata.total_shares);
+
+        match metadata.commitment_digest {
+            Some(digest) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&digest);
+            }
+            None => bytes.push(0),
+        }
+
+        match metadata.release {
+            Some(release) => {
+                bytes.push(1);
+                for field in [release.epoch, release.expiry, release.policy_id] {
+                    bytes.extend_from_slice(&field.to_le_bytes());
+                }
+            }
+            None => bytes.push(0),
+        }
+
+        self.x
+            .serialize_compressed(&mut bytes)
+            .expect("Serializing into a Vec can't fail");
+        self.y
+            .serialize_compressed(&mut bytes)
+            .expect("Serializing into a Vec can't fail");
+
+        let checksum = Sha256::digest(&bytes);
+        bytes.extend_from_slice(&checksum[..CHECKSUM_LEN]);
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, ShareMetadata), ShareFormatError> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    // a share is a fixed handful of bytes so only the total size is limited, before it is hashed
+    pub fn from_bytes_with_limits(
+        bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<(Self, ShareMetadata), ShareFormatError> {
+        if bytes.len() > limits.max_bytes {
+            return Err(ShareFormatError::TooLarge {
+                len: bytes.len(),
+                max: limits.max_bytes,
+            });
+        }
+        if bytes.len() < CHECKSUM_LEN + 12 {
+            return Err(ShareFormatError::Truncated);
+        }
+
+        let (body, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if Sha256::digest(body)[..CHECKSUM_LEN] != *checksum {
+            return Err(ShareFormatError::BadChecksum);
+        }
+
+        let version = body[0];
+        if version != SHARE_FORMAT_VERSION && version != LEGACY_SHARE_FORMAT_VERSION {
+            return Err(ShareFormatError::UnsupportedVersion(version));
+        }
+
+        let mut scheme_id = [0u8; 8];
+        scheme_id.copy_from_slice(&body[1..9]);
+        let threshold = body[9];
+        let total_shares = body[10];
+
+        let mut rest = &body[12..];
+        let commitment_digest = match body[11] {
+            0 => None,
+            1 => {
+                if rest.len() < 32 {
+                    return Err(ShareFormatError::Truncated);
+                }
+                let mut digest = [0u8; 32];
+                digest.copy_from_slice(&rest[..32]);
+                rest = &rest[32..];
+                Some(digest)
+            }
+            flag => return Err(ShareFormatError::InvalidDigestFlag(flag)),
+        };
+
+        let release = if version == LEGACY_SHARE_FORMAT_VERSION {
+            None
+        } else {
+            let (&flag, after_flag) = rest.split_first().ok_or(ShareFormatError::Truncated)?;
+            rest = after_flag;
+            match flag {
+                0 => None,
+                1 => {
+                    if rest.len() < 24 {
+                        return Err(ShareFormatError::Truncated);
+                    }
+                    let field = |i: usize| {
+                        let mut bytes = [0u8; 8];
+                        bytes.copy_from_slice(&rest[8 * i..8 * (i + 1)]);
+                        u64::from_le_bytes(bytes)
+                    };
+                    let release = ReleasePolicy {
+                        epoch: field(0),
+                        expiry: field(1),
+                        policy_id: field(2),
+                    };
+                    rest = &rest[24..];
+                    Some(release)
+                }
+                flag => return Err(ShareFormatError::InvalidReleaseFlag(flag)),
+            }
+        };
+
+        let x = F::deserialize_compressed(&mut rest)?;
+        let y = F::deserialize_compressed(&mut rest)?;
+        if !rest.is_empty() {
+            return Err(ShareFormatError::Truncated);
+        }
+
+        let metadata = ShareMetadata {
+            scheme_id: u64::from_le_bytes(scheme_id),
+            threshold,
+            total_shares,
+            commitment_digest,
+            release,
+        };
+
+        Ok((ShamirShare::new(x, y), metadata))
+    }
+}
+
+// decodes every share, makes sure they all belong to the same dealing and reconstructs
+// shares under a release policy are refused here, see reconstruct_released_from_bytes
+pub fn reconstruct_from_bytes<F: PrimeField>(encoded: &[Vec<u8>]) -> Result<F, ShareFormatError> {
+    let (shares, metadata) = decode_dealing::<F>(encoded)?;
+    if metadata.release.is_some() {
+        return Err(ShareFormatError::ReleasePolicyNotChecked);
+    }
+
+    Ok(reconstruct_secret(&shares, metadata.threshold))
+}
+
+// same, for shares dealt with_release: now has to fall inside their window and policy_id
+// has to be the one they were dealt under
+pub fn reconstruct_released_from_bytes<F: PrimeField>(
+    encoded: &[Vec<u8>],
+    now: u64,
+    policy_id: u64,
+) -> Result<F, ShareFormatError> {
+    let (shares, metadata) = decode_dealing::<F>(encoded)?;
+    metadata
+        .release
+        .ok_or(ShareFormatError::MissingReleasePolicy)?
+        .check(now, policy_id)?;
+
+    Ok(reconstruct_secret(&shares, metadata.threshold))
+}
+
+// the decoded shares and their common metadata, with at least threshold of them
+fn decode_dealing<F: PrimeField>(
+    encoded: &[Vec<u8>],
+) -> Result<(Vec<ShamirShare<F>>, ShareMetadata), ShareFormatError> {
+    let mut shares = Vec::with_capacity(encoded.len());
+    let mut metadata: Option<ShareMetadata> = None;
+
+    for bytes in encoded.iter() {
+        let (share, share_metadata) = ShamirShare::<F>::from_bytes(bytes)?;
+
+        match &metadata {
+            Some(expected) if *expected != share_metadata => {
+                return Err(ShareFormatError::MixedShares)
+            }
+            Some(_) => {}
+            None => metadata = Some(share_metadata),
+        }
+        shares.push(share);
+    }
+
+    let metadata = match metadata {
+        Some(metadata) => metadata,
+        None => {
+            return Err(ShareFormatError::NotEnoughShares {
+                threshold: 1,
+                found: 0,
+            })
+        }
+    };
+    if shares.len() < metadata.threshold as usize {
+        return Err(ShareFormatError::NotEnoughShares {
+            threshold: metadata.threshold,
+            found: shares.len(),
+        });
+    }
+
+    Ok((shares, metadata))
+}
+
+//=========================================================================================
+// Weighted sharing: participant i holds weights[i] evaluation points of the same polynomial
+// so a participant with weight 2 counts twice towards the threshold
+//=========================================================================================
+#[derive(Debug)]
+pub struct WeightedShare<F: PrimeField> {
+    pub participant: usize,
+    pub shares: Vec<ShamirShare<F>>,
+}
+
+#[cfg(feature = "prover")]
+pub fn generate_weighted_shares<F: PrimeField>(
+    secret: F,
+    threshold: u8,
+    weights: &[u8],
+) -> Vec<WeightedShare<F>> {
+    let total_weight: usize = weights.iter().map(|weight| *weight as usize).sum();
+    if total_weight > u8::MAX as usize {
+        panic!("Total weight must fit in the number of shares")
+    }
+
+    let mut shares = generate_shares(secret, threshold, total_weight as u8).into_iter();
+
+    weights
+        .iter()
+        .enumerate()
+        .map(|(participant, weight)| WeightedShare {
+            participant,
+            shares: shares.by_ref().take(*weight as usize).collect(),
+        })
+        .collect()
+}
+
+pub fn reconstruct_weighted_secret<F: PrimeField>(
+    holders: &[WeightedShare<F>],
+    threshold: u8,
+) -> F {
+    let shares: Vec<ShamirShare<F>> = holders
+        .iter()
+        .flat_map(|holder| holder.shares.iter())
+        .map(|share| ShamirShare::new(share.x, share.y))
+        .collect();
 
-    UnivariatePoly::interpolate(&xs, &ys).evaluate(F::from(0))
+    reconstruct_secret(&shares, threshold)
 }
 
-#[cfg(test)]
+//=========================================================================================
+// Hierarchical (two-level) sharing: the secret is split into top level shares
+// and the y of every top level share is split again among the members of that group
+// a group can only act once sub_threshold of its members agree, and the secret needs
+// top_threshold groups
+//=========================================================================================
+#[derive(Debug)]
+pub struct HierarchicalShare<F: PrimeField> {
+    pub x: F, // x of the top level share this group is holding
+    pub sub_threshold: u8,
+    pub sub_shares: Vec<ShamirShare<F>>,
+}
+
+#[cfg(feature = "prover")]
+pub fn generate_hierarchical_shares<F: PrimeField>(
+    secret: F,
+    top_threshold: u8,
+    num_groups: u8,
+    sub_threshold: u8,
+    group_size: u8,
+) -> Vec<HierarchicalShare<F>> {
+    generate_shares(secret, top_threshold, num_groups)
+        .into_iter()
+        .map(|top_share| HierarchicalShare {
+            x: top_share.x,
+            sub_threshold,
+            sub_shares: generate_shares(top_share.y, sub_threshold, group_size),
+        })
+        .collect()
+}
+
+pub fn reconstruct_hierarchical_secret<F: PrimeField>(
+    groups: &[HierarchicalShare<F>],
+    top_threshold: u8,
+) -> F {
+    // every group first recovers its own top level share from its members
+    let top_shares: Vec<ShamirShare<F>> = groups
+        .iter()
+        .map(|group| {
+            let y = reconstruct_secret(&group.sub_shares, group.sub_threshold);
+            ShamirShare::new(group.x, y)
+        })
+        .collect();
+
+    reconstruct_secret(&top_shares, top_threshold)
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254", feature = "bls12-381"))]
 mod tests {
     use super::*;
+    use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1};
     use ark_bn254::Fq;
 
     #[test]
@@ -90,6 +703,283 @@ mod tests {
         assert_eq!(reconstructed, secret);
     }
 
+    #[test]
+    fn test_batch_sharing() {
+        let secrets: Vec<Fq> = (0..20u64).map(|i| Fq::from(1000 + i)).collect();
+        let shares = generate_shares_batch(&secrets, 3, 5);
+
+        assert_eq!(shares.len(), secrets.len());
+        assert!(shares.iter().all(|secret_shares| secret_shares.len() == 5));
+        // each batch is an ordinary dealing of its own secret
+        assert_eq!(reconstruct_secret(&shares[7][1..4], 3), secrets[7]);
+
+        let subset: Vec<Vec<ShamirShare<Fq>>> = shares
+            .iter()
+            .map(|secret_shares| {
+                [0, 2, 4]
+                    .map(|i| ShamirShare::new(secret_shares[i].x, secret_shares[i].y))
+                    .into()
+            })
+            .collect();
+        assert_eq!(reconstruct_secrets_batch(&subset, 3), secrets);
+    }
+
+    #[test]
+    #[should_panic(expected = "The batched shares must all use the same x values")]
+    fn test_batch_reconstruction_mismatched_x() {
+        let mut shares = generate_shares_batch(&[Fq::from(1), Fq::from(2)], 2, 3);
+        shares[1].swap(0, 1);
+
+        reconstruct_secrets_batch(&shares, 2);
+    }
+
+    #[test]
+    fn test_verifiable_shares() {
+        let secret = Fr::from(42);
+        let (shares, commitments) = generate_verifiable_shares::<Fr, G1>(secret, 3, 5);
+
+        assert_eq!(commitments.len(), 3);
+        for share in &shares {
+            assert!(share.verify(&commitments));
+        }
+        assert_eq!(reconstruct_secret(&shares[1..4], 3), secret);
+    }
+
+    #[test]
+    fn test_tampered_share_fails_verification() {
+        let (shares, commitments) = generate_verifiable_shares::<Fr, G1>(Fr::from(42), 3, 5);
+
+        let tampered = ShamirShare::new(shares[0].x, shares[0].y + Fr::from(1));
+        assert!(!tampered.verify(&commitments));
+
+        let moved = ShamirShare::new(shares[1].x, shares[0].y);
+        assert!(!moved.verify(&commitments));
+    }
+
+    #[test]
+    fn test_kzg_shares() {
+        let setup = UnivariateSetup::<Bls12_381>::insecure_from_seed(11, 2);
+        let secret = Fr::from(42);
+        let (shares, commitment) = generate_kzg_shares(secret, 3, 5, &setup);
+
+        for share in &shares {
+            assert!(share.verify(&commitment, &setup.verifier_key));
+        }
+        let plain: Vec<ShamirShare<Fr>> = shares[2..]
+            .iter()
+            .map(|kzg_share| ShamirShare::new(kzg_share.share.x, kzg_share.share.y))
+            .collect();
+        assert_eq!(reconstruct_secret(&plain, 3), secret);
+
+        // a changed share, or a proof moved to another share, fails
+        let tampered = KzgShare::<Bls12_381> {
+            share: ShamirShare::new(shares[0].share.x, shares[0].share.y + Fr::from(1)),
+            proof: shares[0].proof,
+        };
+        assert!(!tampered.verify(&commitment, &setup.verifier_key));
+        let moved = KzgShare::<Bls12_381> {
+            share: ShamirShare::new(shares[1].share.x, shares[1].share.y),
+            proof: shares[0].proof,
+        };
+        assert!(!moved.verify(&commitment, &setup.verifier_key));
+    }
+
+    #[test]
+    fn test_share_bytes_roundtrip() {
+        let (shares, commitments) = generate_verifiable_shares::<Fr, G1>(Fr::from(42), 3, 5);
+        let metadata = ShareMetadata::new(3, 5).with_commitments(&commitments);
+
+        let bytes = shares[2].to_bytes(&metadata);
+        let (share, decoded_metadata) = ShamirShare::<Fr>::from_bytes(&bytes).unwrap();
+
+        assert_eq!((share.x, share.y), (shares[2].x, shares[2].y));
+        assert_eq!(decoded_metadata, metadata);
+        assert!(share.verify(&commitments));
+    }
+
+    #[test]
+    fn test_share_bytes_bad_checksum() {
+        let shares = generate_shares(Fq::from(42), 3, 5);
+        let mut bytes = shares[0].to_bytes(&ShareMetadata::new(3, 5));
+        bytes[12] ^= 1;
+
+        let result = ShamirShare::<Fq>::from_bytes(&bytes);
+        assert!(matches!(result, Err(ShareFormatError::BadChecksum)));
+    }
+
+    #[test]
+    fn test_reconstruct_from_bytes() {
+        let secret = Fq::from(42);
+        let metadata = ShareMetadata::new(3, 5);
+        let encoded: Vec<Vec<u8>> = generate_shares(secret, 3, 5)
+            .iter()
+            .map(|share| share.to_bytes(&metadata))
+            .collect();
+
+        assert_eq!(
+            reconstruct_from_bytes::<Fq>(&encoded[1..4]).unwrap(),
+            secret
+        );
+
+        let result = reconstruct_from_bytes::<Fq>(&encoded[0..2]);
+        assert!(matches!(
+            result,
+            Err(ShareFormatError::NotEnoughShares {
+                threshold: 3,
+                found: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_released_shares() {
+        let secret = Fq::from(42);
+        let release = ReleasePolicy {
+            epoch: 10,
+            expiry: 20,
+            policy_id: 7,
+        };
+        let metadata = ShareMetadata::new(2, 3).with_release(release);
+        let encoded: Vec<Vec<u8>> = generate_shares(secret, 2, 3)
+            .iter()
+            .map(|share| share.to_bytes(&metadata))
+            .collect();
+
+        let (_, decoded) = ShamirShare::<Fq>::from_bytes(&encoded[0]).unwrap();
+        assert_eq!(decoded.release, Some(release));
+        assert_eq!(
+            reconstruct_released_from_bytes::<Fq>(&encoded[1..], 15, 7).unwrap(),
+            secret
+        );
+
+        let result =
+            |now, policy_id| reconstruct_released_from_bytes::<Fq>(&encoded, now, policy_id);
+        assert!(matches!(
+            result(9, 7),
+            Err(ShareFormatError::NotYetReleased { epoch: 10, now: 9 })
+        ));
+        assert!(matches!(
+            result(20, 7),
+            Err(ShareFormatError::Expired {
+                expiry: 20,
+                now: 20
+            })
+        ));
+        assert!(matches!(
+            result(15, 8),
+            Err(ShareFormatError::WrongPolicy {
+                expected: 8,
+                found: 7
+            })
+        ));
+        assert!(matches!(
+            reconstruct_from_bytes::<Fq>(&encoded),
+            Err(ShareFormatError::ReleasePolicyNotChecked)
+        ));
+    }
+
+    #[test]
+    fn test_release_policy_is_authenticated() {
+        let metadata = ShareMetadata::new(2, 3).with_release(ReleasePolicy {
+            epoch: 10,
+            expiry: 20,
+            policy_id: 7,
+        });
+        let shares = generate_shares(Fq::from(42), 2, 3);
+        let mut bytes = shares[0].to_bytes(&metadata);
+        // push the expiry out by flipping a bit of it (release flag at 12, epoch at 13..21)
+        bytes[21] ^= 0x80;
+        assert!(matches!(
+            ShamirShare::<Fq>::from_bytes(&bytes),
+            Err(ShareFormatError::BadChecksum)
+        ));
+
+        // a plain share has no policy to satisfy
+        let plain_metadata = ShareMetadata::new(2, 3);
+        let plain: Vec<Vec<u8>> = shares
+            .iter()
+            .map(|share| share.to_bytes(&plain_metadata))
+            .collect();
+        assert!(matches!(
+            reconstruct_released_from_bytes::<Fq>(&plain, 15, 7),
+            Err(ShareFormatError::MissingReleasePolicy)
+        ));
+    }
+
+    #[test]
+    fn test_reconstruct_from_bytes_rejects_mixed_shares() {
+        let metadata_1 = ShareMetadata::new(2, 3);
+        let metadata_2 = ShareMetadata::new(2, 3);
+        let shares_1 = generate_shares(Fq::from(1), 2, 3);
+        let shares_2 = generate_shares(Fq::from(2), 2, 3);
+
+        let encoded = vec![
+            shares_1[0].to_bytes(&metadata_1),
+            shares_2[1].to_bytes(&metadata_2),
+        ];
+
+        let result = reconstruct_from_bytes::<Fq>(&encoded);
+        assert!(matches!(result, Err(ShareFormatError::MixedShares)));
+    }
+
+    #[test]
+    fn test_weighted_shares() {
+        let secret = Fq::from(42);
+        let holders = generate_weighted_shares(secret, 4, &[2, 1, 1, 3]);
+
+        assert_eq!(holders.len(), 4);
+        assert_eq!(holders[3].shares.len(), 3);
+        assert_eq!(holders[3].shares[0].x, Fq::from(5));
+        assert_eq!(holders[3].participant, 3);
+    }
+
+    #[test]
+    fn test_weighted_reconstruction() {
+        let secret = Fq::from(42);
+        let holders = generate_weighted_shares(secret, 4, &[2, 1, 1, 3]);
+
+        // participant 0 (weight 2) and participant 2 (weight 1) and participant 1 (weight 1)
+        assert_eq!(reconstruct_weighted_secret(&holders[0..3], 4), secret);
+        // participant 0 (weight 2) with participant 3 (weight 3)
+        let holders: Vec<WeightedShare<Fq>> = holders
+            .into_iter()
+            .filter(|holder| holder.participant == 0 || holder.participant == 3)
+            .collect();
+        assert_eq!(reconstruct_weighted_secret(&holders, 4), secret);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough shares")]
+    fn test_weighted_insufficient_weight() {
+        let holders = generate_weighted_shares(Fq::from(42), 4, &[2, 1, 1, 3]);
+        reconstruct_weighted_secret(&holders[1..3], 4);
+    }
+
+    #[test]
+    fn test_hierarchical_shares() {
+        let secret = Fq::from(42);
+        let mut groups = generate_hierarchical_shares(secret, 2, 3, 2, 4);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].sub_shares.len(), 4);
+
+        // only 2 of the 3 groups take part, each with only 2 of its 4 members
+        groups.remove(1);
+        for group in groups.iter_mut() {
+            group.sub_shares.truncate(2);
+        }
+        assert_eq!(reconstruct_hierarchical_secret(&groups, 2), secret);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough shares")]
+    fn test_hierarchical_insufficient_group_members() {
+        let mut groups = generate_hierarchical_shares(Fq::from(42), 2, 3, 3, 4);
+        groups[0].sub_shares.truncate(2);
+
+        reconstruct_hierarchical_secret(&groups, 2);
+    }
+
     #[test]
     #[should_panic(expected = "Not enough shares")]
     fn test_insufficient_shares() {
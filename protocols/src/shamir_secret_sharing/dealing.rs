@@ -0,0 +1,585 @@
+#[cfg(feature = "prover")]
+use crate::shamir_secret_sharing::generate_verifiable_shares;
+use crate::{
+    proof_format::{ensure_consumed, read_points, DecodeLimits, ProofFormatError},
+    shamir_secret_sharing::ShamirShare,
+};
+use ark_ec::PrimeGroup;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::fmt;
+
+//=========================================================================================
+// Feldman dealing as message passing
+// generate_verifiable_shares assumes the dealer and every shareholder live in one process,
+// here each party is a state machine that only sees the bytes it was sent, so the same dealing
+// runs over any transport that gives an AuthenticatedChannel
+// parties are numbered by the x of their share: the dealer is 0, participants are 1..=n
+// 1. dealer broadcasts the commitments C_j = g^(a_j) and sends every participant its share
+//    privately (Deal)
+// 2. a participant whose share fails the Feldman check broadcasts a Complaint
+// 3. the dealer answers every complaint by broadcasting that share in the clear (Justification),
+//    everyone checks it against the commitments and the accuser adopts it
+// the dealer is disqualified if a justification is wrong or missing, or if threshold
+// participants complained (their shares are public by then, enough to rebuild the secret)
+//=========================================================================================
+pub const DEALER: u8 = 0;
+
+// the transport has to authenticate the sender of every message (the handle fns take it as
+// `from`) and keep send_private confidential, e.g. TLS or an encrypted and signed envelope
+pub trait AuthenticatedChannel {
+    fn send_private(&mut self, to: u8, message: Vec<u8>);
+    fn broadcast(&mut self, message: Vec<u8>);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DealingMessage<F: PrimeField, G: PrimeGroup<ScalarField = F>> {
+    Commitments(Vec<G>),          // broadcast by the dealer
+    Deal { x: F, y: F },          // private, dealer to the participant at x
+    Complaint { accuser: u8 },    // broadcast by a participant
+    Justification { x: F, y: F }, // broadcast by the dealer, the share of the accuser at x
+}
+
+#[derive(Debug)]
+pub enum DealingError {
+    Format(ProofFormatError),
+    UnknownMessage(u8),
+    UnexpectedSender { from: u8 }, // e.g. a Deal that doesn't come from the dealer
+    MissingCommitments,
+    MissingShare,
+    InvalidShare,
+    InvalidJustification(u8),
+    UnansweredComplaint(u8),
+    TooManyComplaints { complaints: usize, threshold: u8 },
+}
+
+impl fmt::Display for DealingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DealingError::Format(err) => write!(f, "malformed dealing message: {}", err),
+            DealingError::UnknownMessage(tag) => write!(f, "unknown dealing message {}", tag),
+            DealingError::UnexpectedSender { from } => {
+                write!(f, "party {} is not allowed to send this message", from)
+            }
+            DealingError::MissingCommitments => write!(f, "the dealer never sent commitments"),
+            DealingError::MissingShare => write!(f, "the dealer never sent a share"),
+            DealingError::InvalidShare => write!(f, "the share does not match the commitments"),
+            DealingError::InvalidJustification(accuser) => write!(
+                f,
+                "the justification for participant {} does not match the commitments",
+                accuser
+            ),
+            DealingError::UnansweredComplaint(accuser) => {
+                write!(
+                    f,
+                    "the complaint of participant {} was never answered",
+                    accuser
+                )
+            }
+            DealingError::TooManyComplaints {
+                complaints,
+                threshold,
+            } => write!(
+                f,
+                "{} complaints reveal at least the threshold of {} shares",
+                complaints, threshold
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DealingError {}
+
+impl From<ProofFormatError> for DealingError {
+    fn from(err: ProofFormatError) -> Self {
+        DealingError::Format(err)
+    }
+}
+
+impl From<ark_serialize::SerializationError> for DealingError {
+    fn from(err: ark_serialize::SerializationError) -> Self {
+        DealingError::Format(err.into())
+    }
+}
+
+// [tag | body], the body is ark compressed
+impl<F: PrimeField, G: PrimeGroup<ScalarField = F>> DealingMessage<F, G> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let result = match self {
+            DealingMessage::Commitments(commitments) => {
+                bytes.push(0);
+                commitments.serialize_compressed(&mut bytes)
+            }
+            DealingMessage::Deal { x, y } => {
+                bytes.push(1);
+                x.serialize_compressed(&mut bytes)
+                    .and_then(|_| y.serialize_compressed(&mut bytes))
+            }
+            DealingMessage::Complaint { accuser } => {
+                bytes.push(2);
+                accuser.serialize_compressed(&mut bytes)
+            }
+            DealingMessage::Justification { x, y } => {
+                bytes.push(3);
+                x.serialize_compressed(&mut bytes)
+                    .and_then(|_| y.serialize_compressed(&mut bytes))
+            }
+        };
+        result.expect("Serializing into a Vec can't fail");
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8], limits: &DecodeLimits) -> Result<Self, DealingError> {
+        limits.check_size(bytes)?;
+        let (tag, mut body) = bytes
+            .split_first()
+            .ok_or(DealingError::Format(ProofFormatError::MissingHeader))?;
+
+        let message = match tag {
+            0 => DealingMessage::Commitments(read_points::<G>(&mut body, limits)?),
+            1 => DealingMessage::Deal {
+                x: F::deserialize_compressed(&mut body)?,
+                y: F::deserialize_compressed(&mut body)?,
+            },
+            2 => DealingMessage::Complaint {
+                accuser: u8::deserialize_compressed(&mut body)?,
+            },
+            3 => DealingMessage::Justification {
+                x: F::deserialize_compressed(&mut body)?,
+                y: F::deserialize_compressed(&mut body)?,
+            },
+            tag => return Err(DealingError::UnknownMessage(*tag)),
+        };
+        ensure_consumed(body)?;
+
+        Ok(message)
+    }
+}
+
+#[cfg(feature = "prover")]
+pub struct Dealer<F: PrimeField, G: PrimeGroup<ScalarField = F>> {
+    shares: Vec<ShamirShare<F>>,
+    commitments: Vec<G>,
+    justified: Vec<u8>, // accusers that already got their justification
+    limits: DecodeLimits,
+}
+
+#[cfg(feature = "prover")]
+impl<F: PrimeField, G: PrimeGroup<ScalarField = F>> Dealer<F, G> {
+    pub fn new(secret: F, threshold: u8, num_participants: u8) -> Self {
+        let (shares, commitments) =
+            generate_verifiable_shares::<F, G>(secret, threshold, num_participants);
+
+        Dealer {
+            shares,
+            commitments,
+            justified: Vec::new(),
+            limits: DecodeLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: DecodeLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn commitments(&self) -> &[G] {
+        &self.commitments
+    }
+
+    // round 1, the commitments in the clear and every share over its private channel
+    pub fn deal(&self, channel: &mut impl AuthenticatedChannel) {
+        channel.broadcast(DealingMessage::<F, G>::Commitments(self.commitments.clone()).to_bytes());
+
+        for (i, share) in self.shares.iter().enumerate() {
+            let deal = DealingMessage::<F, G>::Deal {
+                x: share.x,
+                y: share.y,
+            };
+            channel.send_private(i as u8 + 1, deal.to_bytes());
+        }
+    }
+
+    // round 3, every complaint is answered once by publishing the accuser's share
+    pub fn handle(
+        &mut self,
+        from: u8,
+        message: &[u8],
+        channel: &mut impl AuthenticatedChannel,
+    ) -> Result<(), DealingError> {
+        match DealingMessage::<F, G>::from_bytes(message, &self.limits)? {
+            DealingMessage::Complaint { accuser } => {
+                let share = match (accuser as usize).checked_sub(1) {
+                    Some(i) if accuser == from && i < self.shares.len() => &self.shares[i],
+                    _ => return Err(DealingError::UnexpectedSender { from }),
+                };
+                if self.justified.contains(&accuser) {
+                    return Ok(());
+                }
+
+                let justification = DealingMessage::<F, G>::Justification {
+                    x: share.x,
+                    y: share.y,
+                };
+                channel.broadcast(justification.to_bytes());
+                self.justified.push(accuser);
+
+                Ok(())
+            }
+            _ => Err(DealingError::UnexpectedSender { from }),
+        }
+    }
+}
+
+pub struct Participant<F: PrimeField, G: PrimeGroup<ScalarField = F>> {
+    index: u8,
+    threshold: u8,
+    commitments: Option<Vec<G>>,
+    share: Option<ShamirShare<F>>,
+    complaints: Vec<u8>,
+    justifications: Vec<u8>, // accusers whose share was published and checked
+    invalid_justification: Option<u8>,
+    limits: DecodeLimits,
+}
+
+impl<F: PrimeField, G: PrimeGroup<ScalarField = F>> Participant<F, G> {
+    pub fn new(index: u8, threshold: u8) -> Self {
+        if index == DEALER {
+            panic!("Participants are numbered from 1, 0 is the dealer");
+        }
+
+        Participant {
+            index,
+            threshold,
+            commitments: None,
+            share: None,
+            complaints: Vec::new(),
+            justifications: Vec::new(),
+            invalid_justification: None,
+            limits: DecodeLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: DecodeLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    pub fn handle(
+        &mut self,
+        from: u8,
+        message: &[u8],
+        channel: &mut impl AuthenticatedChannel,
+    ) -> Result<(), DealingError> {
+        match DealingMessage::<F, G>::from_bytes(message, &self.limits)? {
+            DealingMessage::Commitments(commitments) if from == DEALER => {
+                // only the first set counts, a dealer can't swap them after the shares went out
+                if self.commitments.is_none() {
+                    self.commitments = Some(commitments);
+                }
+            }
+            DealingMessage::Deal { x, y } if from == DEALER => {
+                if x != F::from(self.index) {
+                    return Err(DealingError::InvalidShare);
+                }
+                if self.share.is_none() {
+                    self.share = Some(ShamirShare::new(x, y));
+                }
+            }
+            DealingMessage::Complaint { accuser } if accuser == from && from != DEALER => {
+                if !self.complaints.contains(&accuser) {
+                    self.complaints.push(accuser);
+                }
+            }
+            DealingMessage::Justification { x, y } if from == DEALER => {
+                let commitments = self
+                    .commitments
+                    .as_ref()
+                    .ok_or(DealingError::MissingCommitments)?;
+                let accuser = self
+                    .complaints
+                    .iter()
+                    .copied()
+                    .find(|accuser| F::from(*accuser) == x)
+                    .ok_or(DealingError::UnexpectedSender { from })?;
+
+                let share = ShamirShare::new(x, y);
+                if !share.verify(commitments) {
+                    self.invalid_justification.get_or_insert(accuser);
+                    return Ok(());
+                }
+                if !self.justifications.contains(&accuser) {
+                    self.justifications.push(accuser);
+                }
+                if accuser == self.index {
+                    self.share = Some(share);
+                }
+            }
+            _ => return Err(DealingError::UnexpectedSender { from }),
+        }
+
+        // round 2, complain as soon as both the share and the commitments are in
+        self.complain_if_invalid(channel);
+
+        Ok(())
+    }
+
+    fn complain_if_invalid(&mut self, channel: &mut impl AuthenticatedChannel) {
+        let valid = match (&self.share, &self.commitments) {
+            (Some(share), Some(commitments)) => share.verify(commitments),
+            _ => return,
+        };
+
+        if !valid && !self.complaints.contains(&self.index) {
+            self.complaints.push(self.index);
+            let complaint = DealingMessage::<F, G>::Complaint {
+                accuser: self.index,
+            };
+            channel.broadcast(complaint.to_bytes());
+        }
+    }
+
+    // once every message of the dealing has been handled, the share if the dealer is qualified
+    pub fn finish(self) -> Result<ShamirShare<F>, DealingError> {
+        let commitments = self.commitments.ok_or(DealingError::MissingCommitments)?;
+        if let Some(accuser) = self.invalid_justification {
+            return Err(DealingError::InvalidJustification(accuser));
+        }
+        if self.complaints.len() >= self.threshold as usize {
+            return Err(DealingError::TooManyComplaints {
+                complaints: self.complaints.len(),
+                threshold: self.threshold,
+            });
+        }
+        if let Some(accuser) = self
+            .complaints
+            .iter()
+            .find(|accuser| !self.justifications.contains(accuser))
+        {
+            return Err(DealingError::UnansweredComplaint(*accuser));
+        }
+
+        let share = self.share.ok_or(DealingError::MissingShare)?;
+        if !share.verify(&commitments) {
+            return Err(DealingError::InvalidShare);
+        }
+
+        Ok(share)
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-381"))]
+mod test {
+    use super::*;
+    use crate::shamir_secret_sharing::reconstruct_secret;
+    use ark_bls12_381::{Fr, G1Projective as G1};
+    use std::collections::VecDeque;
+
+    // every message waits here with its sender until the test delivers it
+    #[derive(Default)]
+    struct Network {
+        sender: u8,
+        queue: VecDeque<(u8, Option<u8>, Vec<u8>)>, // (from, to or None for broadcast, bytes)
+    }
+
+    impl AuthenticatedChannel for Network {
+        fn send_private(&mut self, to: u8, message: Vec<u8>) {
+            self.queue.push_back((self.sender, Some(to), message));
+        }
+
+        fn broadcast(&mut self, message: Vec<u8>) {
+            self.queue.push_back((self.sender, None, message));
+        }
+    }
+
+    // delivers until nothing is left, tamper can rewrite a message on its way to a participant
+    fn run(
+        dealer: &mut Dealer<Fr, G1>,
+        participants: &mut [Participant<Fr, G1>],
+        network: &mut Network,
+        answer_complaints: bool,
+        tamper: impl Fn(u8, &DealingMessage<Fr, G1>) -> Option<DealingMessage<Fr, G1>>,
+    ) {
+        while let Some((from, to, bytes)) = network.queue.pop_front() {
+            if from != DEALER && answer_complaints {
+                network.sender = DEALER;
+                dealer.handle(from, &bytes, network).unwrap();
+            }
+
+            for participant in participants.iter_mut() {
+                let index = participant.index();
+                if index == from || to.is_some_and(|to| to != index) {
+                    continue;
+                }
+
+                let message = DealingMessage::from_bytes(&bytes, &DecodeLimits::default()).unwrap();
+                let bytes = tamper(index, &message).map_or(bytes.clone(), |m| m.to_bytes());
+
+                network.sender = index;
+                participant.handle(from, &bytes, network).unwrap();
+            }
+        }
+    }
+
+    fn setup(threshold: u8, n: u8) -> (Dealer<Fr, G1>, Vec<Participant<Fr, G1>>, Network) {
+        let dealer = Dealer::new(Fr::from(42), threshold, n);
+        let participants = (1..=n).map(|i| Participant::new(i, threshold)).collect();
+        let mut network = Network::default();
+        dealer.deal(&mut network);
+
+        (dealer, participants, network)
+    }
+
+    #[test]
+    fn test_honest_dealing() {
+        let (mut dealer, mut participants, mut network) = setup(3, 5);
+        run(
+            &mut dealer,
+            &mut participants,
+            &mut network,
+            true,
+            |_, _| None,
+        );
+
+        let shares: Vec<ShamirShare<Fr>> = participants
+            .into_iter()
+            .map(|participant| participant.finish().unwrap())
+            .collect();
+        assert_eq!(reconstruct_secret(&shares[2..5], 3), Fr::from(42));
+    }
+
+    #[test]
+    fn test_complaint_is_justified() {
+        // participant 2 gets a bad share, complains and picks up the published one
+        let (mut dealer, mut participants, mut network) = setup(3, 5);
+        run(
+            &mut dealer,
+            &mut participants,
+            &mut network,
+            true,
+            |to, message| match message {
+                DealingMessage::Deal { x, y } if to == 2 => Some(DealingMessage::Deal {
+                    x: *x,
+                    y: *y + Fr::from(1),
+                }),
+                _ => None,
+            },
+        );
+
+        let shares: Vec<ShamirShare<Fr>> = participants
+            .into_iter()
+            .map(|participant| participant.finish().unwrap())
+            .collect();
+        assert!(shares[1].verify(dealer.commitments()));
+        assert_eq!(reconstruct_secret(&shares[..3], 3), Fr::from(42));
+    }
+
+    #[test]
+    fn test_unanswered_complaint_disqualifies_the_dealer() {
+        let (mut dealer, mut participants, mut network) = setup(3, 5);
+        run(
+            &mut dealer,
+            &mut participants,
+            &mut network,
+            false,
+            |to, message| match message {
+                DealingMessage::Deal { x, .. } if to == 4 => Some(DealingMessage::Deal {
+                    x: *x,
+                    y: Fr::from(0),
+                }),
+                _ => None,
+            },
+        );
+
+        for participant in participants {
+            assert!(matches!(
+                participant.finish(),
+                Err(DealingError::UnansweredComplaint(4))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_too_many_complaints_disqualify_the_dealer() {
+        let (mut dealer, mut participants, mut network) = setup(2, 4);
+        run(
+            &mut dealer,
+            &mut participants,
+            &mut network,
+            true,
+            |to, message| match message {
+                DealingMessage::Deal { x, y } if to <= 2 => Some(DealingMessage::Deal {
+                    x: *x,
+                    y: *y + Fr::from(1),
+                }),
+                _ => None,
+            },
+        );
+
+        assert!(matches!(
+            participants.pop().unwrap().finish(),
+            Err(DealingError::TooManyComplaints {
+                complaints: 2,
+                threshold: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_message_bytes_roundtrip() {
+        let limits = DecodeLimits::default();
+        let messages = [
+            DealingMessage::<Fr, G1>::Commitments(vec![
+                G1::generator(),
+                G1::generator().mul_bigint([2u64]),
+            ]),
+            DealingMessage::Deal {
+                x: Fr::from(3),
+                y: Fr::from(9),
+            },
+            DealingMessage::Complaint { accuser: 3 },
+            DealingMessage::Justification {
+                x: Fr::from(3),
+                y: Fr::from(9),
+            },
+        ];
+
+        for message in messages {
+            let decoded = DealingMessage::from_bytes(&message.to_bytes(), &limits).unwrap();
+            assert_eq!(decoded, message);
+        }
+
+        assert!(matches!(
+            DealingMessage::<Fr, G1>::from_bytes(&[7], &limits),
+            Err(DealingError::UnknownMessage(7))
+        ));
+        let mut bytes = DealingMessage::<Fr, G1>::Complaint { accuser: 1 }.to_bytes();
+        bytes.push(0);
+        assert!(DealingMessage::<Fr, G1>::from_bytes(&bytes, &limits).is_err());
+    }
+
+    #[test]
+    fn test_participant_rejects_forged_senders() {
+        let mut participant = Participant::<Fr, G1>::new(1, 2);
+        let mut network = Network::default();
+        let deal = DealingMessage::<Fr, G1>::Deal {
+            x: Fr::from(1),
+            y: Fr::from(5),
+        };
+
+        assert!(matches!(
+            participant.handle(3, &deal.to_bytes(), &mut network),
+            Err(DealingError::UnexpectedSender { from: 3 })
+        ));
+        let complaint = DealingMessage::<Fr, G1>::Complaint { accuser: 2 };
+        assert!(participant
+            .handle(3, &complaint.to_bytes(), &mut network)
+            .is_err());
+    }
+}
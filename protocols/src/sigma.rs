@@ -0,0 +1,451 @@
+use crate::transcript::{Transcript, TranscriptDomain};
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+#[cfg(feature = "prover")]
+use {ark_ff::UniformRand, rand::Rng};
+
+//=========================================================================================
+// Sigma protocols for statements about discrete logs, made non-interactive with the transcript
+// the leaf statement is "I know x with yᵢ = x·gᵢ for every i":
+//   one base is a Schnorr proof of knowledge, two bases is Chaum-Pedersen (log_g y == log_h z)
+// leaves combine with AND (every part holds, one challenge for all) and OR (at least one part
+// holds, CDS: the false branches are simulated with challenges of their own and the real branch
+// gets c minus the rest, the verifier only sees that the branch challenges add up to c)
+// three moves: commitment (kᵢ·gᵢ), challenge c, response s = k + c·x, check s·gᵢ == Tᵢ + c·yᵢ
+// the statement and the commitment are absorbed before c is squeezed, so c binds both
+//=========================================================================================
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement<G: CurveGroup> {
+    Dlog(Vec<(G, G)>), // (base, public) pairs sharing one secret
+    And(Vec<Statement<G>>),
+    Or(Vec<Statement<G>>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Witness<F: PrimeField> {
+    Dlog(F),
+    And(Vec<Witness<F>>),
+    Or {
+        index: usize,
+        witness: Box<Witness<F>>,
+    }, // only the true branch has a witness
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Commitment<G: CurveGroup> {
+    Dlog(Vec<G>),
+    And(Vec<Commitment<G>>),
+    Or(Vec<Commitment<G>>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Response<F: PrimeField> {
+    Dlog(F),
+    And(Vec<Response<F>>),
+    Or(Vec<(F, Response<F>)>), // each branch with its own challenge
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigmaProof<G: CurveGroup> {
+    pub commitment: Commitment<G>,
+    pub response: Response<G::ScalarField>,
+}
+
+impl<G: CurveGroup> Statement<G> {
+    // y = x·g
+    pub fn schnorr(base: G, public: G) -> Self {
+        Statement::Dlog(vec![(base, public)])
+    }
+
+    // y = x·g and z = x·h for the same x
+    pub fn dlog_equality(g: G, y: G, h: G, z: G) -> Self {
+        Statement::Dlog(vec![(g, y), (h, z)])
+    }
+
+    fn absorb_into(&self, transcript: &mut Transcript) {
+        match self {
+            Statement::Dlog(pairs) => {
+                transcript.absorb(&[0]);
+                for (base, public) in pairs.iter() {
+                    transcript.absorb_commitment(base);
+                    transcript.absorb_commitment(public);
+                }
+            }
+            Statement::And(parts) | Statement::Or(parts) => {
+                let tag = if matches!(self, Statement::And(_)) {
+                    1
+                } else {
+                    2
+                };
+                transcript.absorb(&[tag]);
+                transcript.absorb(&(parts.len() as u64).to_be_bytes());
+                parts.iter().for_each(|part| part.absorb_into(transcript));
+            }
+        }
+    }
+}
+
+impl<F: PrimeField> Witness<F> {
+    pub fn or(index: usize, witness: Witness<F>) -> Self {
+        Witness::Or {
+            index,
+            witness: Box::new(witness),
+        }
+    }
+}
+
+impl<G: CurveGroup> Commitment<G> {
+    fn absorb_into(&self, transcript: &mut Transcript) {
+        match self {
+            Commitment::Dlog(points) => points
+                .iter()
+                .for_each(|point| transcript.absorb_commitment(point)),
+            Commitment::And(parts) | Commitment::Or(parts) => {
+                parts.iter().for_each(|part| part.absorb_into(transcript))
+            }
+        }
+    }
+}
+
+// a simulated OR branch: its challenge and the response that goes with it
+#[cfg(feature = "prover")]
+type SimulatedBranch<F> = (F, Response<F>);
+
+// what the prover keeps between the commitment and the response
+#[cfg(feature = "prover")]
+enum ProverState<G: CurveGroup> {
+    Dlog(G::ScalarField), // the nonce k
+    And(Vec<ProverState<G>>),
+    Or {
+        index: usize,
+        real: Box<ProverState<G>>,
+        simulated: Vec<Option<SimulatedBranch<G::ScalarField>>>, // None at index
+    },
+}
+
+#[cfg(feature = "prover")]
+fn commit<G: CurveGroup, R: Rng>(
+    statement: &Statement<G>,
+    witness: &Witness<G::ScalarField>,
+    rng: &mut R,
+) -> (Commitment<G>, ProverState<G>) {
+    match (statement, witness) {
+        (Statement::Dlog(pairs), Witness::Dlog(_)) => {
+            let k = G::ScalarField::rand(rng);
+            let points = pairs
+                .iter()
+                .map(|(base, _)| base.mul_bigint(k.into_bigint()))
+                .collect();
+
+            (Commitment::Dlog(points), ProverState::Dlog(k))
+        }
+        (Statement::And(parts), Witness::And(witnesses)) if parts.len() == witnesses.len() => {
+            let (commitments, states) = parts
+                .iter()
+                .zip(witnesses.iter())
+                .map(|(part, witness)| commit(part, witness, rng))
+                .unzip();
+
+            (Commitment::And(commitments), ProverState::And(states))
+        }
+        (Statement::Or(parts), Witness::Or { index, witness }) if *index < parts.len() => {
+            let mut commitments = Vec::with_capacity(parts.len());
+            let mut simulated = Vec::with_capacity(parts.len());
+            let mut real = None;
+
+            for (i, part) in parts.iter().enumerate() {
+                if i == *index {
+                    let (commitment, state) = commit(part, witness, rng);
+                    commitments.push(commitment);
+                    simulated.push(None);
+                    real = Some(state);
+                } else {
+                    let challenge = G::ScalarField::rand(rng);
+                    let (commitment, response) = simulate(part, challenge, rng);
+                    commitments.push(commitment);
+                    simulated.push(Some((challenge, response)));
+                }
+            }
+
+            let state = ProverState::Or {
+                index: *index,
+                real: Box::new(real.unwrap()),
+                simulated,
+            };
+            (Commitment::Or(commitments), state)
+        }
+        _ => panic!("The witness does not match the statement"),
+    }
+}
+
+#[cfg(feature = "prover")]
+fn respond<G: CurveGroup>(
+    statement: &Statement<G>,
+    witness: &Witness<G::ScalarField>,
+    state: ProverState<G>,
+    challenge: G::ScalarField,
+) -> Response<G::ScalarField> {
+    match (statement, witness, state) {
+        (Statement::Dlog(_), Witness::Dlog(x), ProverState::Dlog(k)) => {
+            Response::Dlog(k + challenge * x)
+        }
+        (Statement::And(parts), Witness::And(witnesses), ProverState::And(states)) => {
+            Response::And(
+                parts
+                    .iter()
+                    .zip(witnesses.iter())
+                    .zip(states)
+                    .map(|((part, witness), state)| respond(part, witness, state, challenge))
+                    .collect(),
+            )
+        }
+        (
+            Statement::Or(parts),
+            Witness::Or { witness, .. },
+            ProverState::Or {
+                index,
+                real,
+                simulated,
+            },
+        ) => {
+            // the real branch takes whatever challenge is left
+            let simulated_sum: G::ScalarField = simulated
+                .iter()
+                .flatten()
+                .map(|(challenge, _)| *challenge)
+                .sum();
+            let real_challenge = challenge - simulated_sum;
+            let mut real_response = Some(respond(&parts[index], witness, *real, real_challenge));
+
+            Response::Or(
+                simulated
+                    .into_iter()
+                    .map(|branch| {
+                        branch.unwrap_or_else(|| (real_challenge, real_response.take().unwrap()))
+                    })
+                    .collect(),
+            )
+        }
+        _ => unreachable!("commit already matched the witness with the statement"),
+    }
+}
+
+// an accepting transcript for a given challenge without a witness, picks the response first
+// and solves for the commitment: T = s·g - c·y
+#[cfg(feature = "prover")]
+fn simulate<G: CurveGroup, R: Rng>(
+    statement: &Statement<G>,
+    challenge: G::ScalarField,
+    rng: &mut R,
+) -> (Commitment<G>, Response<G::ScalarField>) {
+    match statement {
+        Statement::Dlog(pairs) => {
+            let s = G::ScalarField::rand(rng);
+            let points = pairs
+                .iter()
+                .map(|(base, public)| {
+                    base.mul_bigint(s.into_bigint()) - public.mul_bigint(challenge.into_bigint())
+                })
+                .collect();
+
+            (Commitment::Dlog(points), Response::Dlog(s))
+        }
+        Statement::And(parts) => {
+            let (commitments, responses) = parts
+                .iter()
+                .map(|part| simulate(part, challenge, rng))
+                .unzip();
+
+            (Commitment::And(commitments), Response::And(responses))
+        }
+        Statement::Or(parts) => {
+            // random challenges for all but the last branch, the last one makes them add up
+            let mut challenges: Vec<G::ScalarField> = (1..parts.len())
+                .map(|_| G::ScalarField::rand(rng))
+                .collect();
+            challenges.push(challenge - challenges.iter().sum::<G::ScalarField>());
+
+            let (commitments, responses) = parts
+                .iter()
+                .zip(challenges)
+                .map(|(part, challenge)| {
+                    let (commitment, response) = simulate(part, challenge, rng);
+                    (commitment, (challenge, response))
+                })
+                .unzip();
+
+            (Commitment::Or(commitments), Response::Or(responses))
+        }
+    }
+}
+
+fn check<G: CurveGroup>(
+    statement: &Statement<G>,
+    commitment: &Commitment<G>,
+    response: &Response<G::ScalarField>,
+    challenge: G::ScalarField,
+) -> bool {
+    match (statement, commitment, response) {
+        (Statement::Dlog(pairs), Commitment::Dlog(points), Response::Dlog(s)) => {
+            pairs.len() == points.len()
+                && pairs.iter().zip(points.iter()).all(|((base, public), t)| {
+                    base.mul_bigint(s.into_bigint())
+                        == *t + public.mul_bigint(challenge.into_bigint())
+                })
+        }
+        (Statement::And(parts), Commitment::And(commitments), Response::And(responses)) => {
+            parts.len() == commitments.len()
+                && parts.len() == responses.len()
+                && parts
+                    .iter()
+                    .zip(commitments.iter().zip(responses.iter()))
+                    .all(|(part, (commitment, response))| {
+                        check(part, commitment, response, challenge)
+                    })
+        }
+        (Statement::Or(parts), Commitment::Or(commitments), Response::Or(responses)) => {
+            parts.len() == commitments.len()
+                && parts.len() == responses.len()
+                && responses.iter().map(|(c, _)| *c).sum::<G::ScalarField>() == challenge
+                && parts
+                    .iter()
+                    .zip(commitments.iter().zip(responses.iter()))
+                    .all(|(part, (commitment, (c, response)))| {
+                        check(part, commitment, response, *c)
+                    })
+        }
+        _ => false,
+    }
+}
+
+fn challenge<G: CurveGroup>(
+    statement: &Statement<G>,
+    commitment: &Commitment<G>,
+    transcript_domain: &TranscriptDomain,
+) -> G::ScalarField {
+    let mut transcript = transcript_domain.transcript();
+    statement.absorb_into(&mut transcript);
+    commitment.absorb_into(&mut transcript);
+
+    transcript.squeeze_field::<G::ScalarField>()
+}
+
+#[cfg(feature = "prover")]
+pub fn prove<G: CurveGroup, R: Rng>(
+    statement: &Statement<G>,
+    witness: &Witness<G::ScalarField>,
+    transcript_domain: &TranscriptDomain,
+    rng: &mut R,
+) -> SigmaProof<G> {
+    let (commitment, state) = commit(statement, witness, rng);
+    let c = challenge(statement, &commitment, transcript_domain);
+    let response = respond(statement, witness, state, c);
+
+    SigmaProof {
+        commitment,
+        response,
+    }
+}
+
+pub fn verify<G: CurveGroup>(
+    statement: &Statement<G>,
+    proof: &SigmaProof<G>,
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    let c = challenge(statement, &proof.commitment, transcript_domain);
+
+    check(statement, &proof.commitment, &proof.response, c)
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::transcript::{public_digest, test::domain};
+    use ark_bn254::{Fr, G1Projective};
+    use ark_ec::PrimeGroup;
+
+    fn point(scalar: u64) -> G1Projective {
+        G1Projective::generator().mul_bigint(Fr::from(scalar).into_bigint())
+    }
+
+    fn times(base: G1Projective, x: Fr) -> G1Projective {
+        base.mul_bigint(x.into_bigint())
+    }
+
+    #[test]
+    fn test_schnorr_and_chaum_pedersen() {
+        let mut rng = rand::thread_rng();
+        let (g, h, x) = (point(1), point(17), Fr::from(42));
+
+        let schnorr = Statement::schnorr(g, times(g, x));
+        let proof = prove(&schnorr, &Witness::Dlog(x), &domain(), &mut rng);
+        assert!(verify(&schnorr, &proof, &domain()));
+        let other = TranscriptDomain::new(b"other-app", public_digest(b""));
+        assert!(!verify(&schnorr, &proof, &other));
+
+        let equal = Statement::dlog_equality(g, times(g, x), h, times(h, x));
+        let proof = prove(&equal, &Witness::Dlog(x), &domain(), &mut rng);
+        assert!(verify(&equal, &proof, &domain()));
+
+        // log_g y != log_h z, the honest prover's proof for x does not carry over
+        let unequal = Statement::dlog_equality(g, times(g, x), h, times(h, x + Fr::from(1)));
+        let proof = prove(&unequal, &Witness::Dlog(x), &domain(), &mut rng);
+        assert!(!verify(&unequal, &proof, &domain()));
+    }
+
+    #[test]
+    fn test_and_or_composition() {
+        let mut rng = rand::thread_rng();
+        let g = point(1);
+        let (x_1, x_2) = (Fr::from(5), Fr::from(9));
+        let (y_1, y_2) = (times(g, x_1), times(g, x_2));
+        let unknown = point(1234567);
+
+        let both = Statement::And(vec![Statement::schnorr(g, y_1), Statement::schnorr(g, y_2)]);
+        let witness = Witness::And(vec![Witness::Dlog(x_1), Witness::Dlog(x_2)]);
+        let proof = prove(&both, &witness, &domain(), &mut rng);
+        assert!(verify(&both, &proof, &domain()));
+
+        // knows the log of y_2 but not of the middle point, and an AND nested in the last branch
+        let one_of = Statement::Or(vec![
+            Statement::schnorr(g, unknown),
+            Statement::schnorr(g, y_2),
+            Statement::And(vec![Statement::schnorr(g, unknown)]),
+        ]);
+        let proof = prove(
+            &one_of,
+            &Witness::or(1, Witness::Dlog(x_2)),
+            &domain(),
+            &mut rng,
+        );
+        assert!(verify(&one_of, &proof, &domain()));
+
+        // the branch challenges must add up to the transcript challenge
+        let mut tampered = proof.clone();
+        if let Response::Or(branches) = &mut tampered.response {
+            branches[0].0 += Fr::from(1);
+        }
+        assert!(!verify(&one_of, &tampered, &domain()));
+
+        // a proof for one statement is no proof for a reshaped one
+        let reshaped = Statement::Or(vec![
+            Statement::schnorr(g, y_2),
+            Statement::schnorr(g, unknown),
+            Statement::And(vec![Statement::schnorr(g, unknown)]),
+        ]);
+        assert!(!verify(&reshaped, &proof, &domain()));
+    }
+
+    #[test]
+    #[should_panic(expected = "The witness does not match the statement")]
+    fn test_witness_shape_is_checked() {
+        let g = point(1);
+        let statement = Statement::Or(vec![Statement::schnorr(g, g)]);
+        prove(
+            &statement,
+            &Witness::or(3, Witness::Dlog(Fr::from(1))),
+            &domain(),
+            &mut rand::thread_rng(),
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{
+    hash::hash_to_curve::HashToCurveGroup,
+    transcript::{Transcript, DIGEST_LEN},
+};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::CanonicalSerialize;
+#[cfg(feature = "prover")]
+use {ark_ff::UniformRand, rand::Rng};
+
+//=========================================================================================
+// ECVRF (the shape of RFC 9381 with the crate's Keccak transcript as the hash)
+// the output for an input α is a hash of Γ = sk·H(pk, α), which only the key holder can compute
+// and which is unique: the proof is a Chaum-Pedersen proof that log_G pk == log_H Γ
+//   H:      RFC 9380 hash_to_curve of pk || α (the encode_to_curve_salt of RFC 9381 is pk),
+//           see hash::hash_to_curve
+//   prove:  k = hash(sk, H) (deterministic, no rng to get wrong, squeezed uniform since a
+//           biased nonce leaks sk), c = hash(H, Γ, k·G, k·H),
+//           s = k + c·sk
+//   verify: U = s·G - c·pk, V = s·H - c·Γ, accept iff c == hash(H, Γ, U, V)
+//   output: hash(cofactor·Γ), so every valid proof for (pk, α) gives the same 32 bytes
+// keys are sk and sk·G, the same as the Schnorr ones in zk-frontends
+//=========================================================================================
+const SUITE: &[u8] = b"ECVRF-ark-keccak-SVDW";
+const H2C_DST: &[u8] = b"ECVRF_XMD:SHA-256_SVDW_RO_ECVRF-ark-keccak-SVDW";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VrfProof<G: CurveGroup> {
+    pub gamma: G::Affine,
+    pub c: G::ScalarField,
+    pub s: G::ScalarField,
+}
+
+#[cfg(feature = "prover")]
+pub fn keygen<G: CurveGroup, R: Rng>(rng: &mut R) -> (G::ScalarField, G::Affine) {
+    let secret_key = G::ScalarField::rand(rng);
+    let public_key = G::generator().mul_bigint(secret_key.into_bigint());
+
+    (secret_key, public_key.into_affine())
+}
+
+fn suite_transcript(tag: u8) -> Transcript {
+    let mut transcript = Transcript::new();
+    transcript.absorb(SUITE);
+    transcript.absorb(&[tag]);
+    transcript
+}
+
+pub fn hash_to_curve<G: HashToCurveGroup>(public_key: &G::Affine, input: &[u8]) -> G::Affine {
+    let mut msg = Vec::new();
+    public_key
+        .serialize_compressed(&mut msg)
+        .expect("The public key must serialize");
+    msg.extend_from_slice(input);
+
+    G::hash_to_curve(&msg, H2C_DST)
+}
+
+fn nonce<G: CurveGroup>(secret_key: G::ScalarField, h: &G::Affine) -> G::ScalarField {
+    let mut transcript = suite_transcript(0x04);
+    transcript.absorb(&secret_key.into_bigint().to_bytes_be());
+    transcript.absorb_commitment(h);
+
+    transcript.squeeze_uniform_field()
+}
+
+fn challenge<G: CurveGroup>(points: &[G::Affine]) -> G::ScalarField {
+    let mut transcript = suite_transcript(0x02);
+    points
+        .iter()
+        .for_each(|point| transcript.absorb_commitment(point));
+
+    transcript.squeeze_field()
+}
+
+// the point is in the subgroup of order r
+fn in_subgroup<G: CurveGroup>(point: &G::Affine) -> bool {
+    point.mul_bigint(G::ScalarField::MODULUS).is_zero()
+}
+
+pub fn prove<G: HashToCurveGroup>(secret_key: G::ScalarField, input: &[u8]) -> VrfProof<G> {
+    let public_key = G::generator()
+        .mul_bigint(secret_key.into_bigint())
+        .into_affine();
+    let h = hash_to_curve::<G>(&public_key, input);
+
+    let gamma = h.mul_bigint(secret_key.into_bigint()).into_affine();
+    let k = nonce::<G>(secret_key, &h);
+    let k_g = G::generator().mul_bigint(k.into_bigint()).into_affine();
+    let k_h = h.mul_bigint(k.into_bigint()).into_affine();
+    let c = challenge::<G>(&[h, gamma, k_g, k_h]);
+
+    VrfProof {
+        gamma,
+        c,
+        s: k + c * secret_key,
+    }
+}
+
+// the 32 byte VRF output a proof commits to, only meaningful once verify accepts the proof
+pub fn proof_to_output<G: CurveGroup>(proof: &VrfProof<G>) -> [u8; DIGEST_LEN] {
+    let mut transcript = suite_transcript(0x03);
+    transcript.absorb_commitment(&proof.gamma.mul_by_cofactor());
+
+    transcript.squeeze().try_into().unwrap()
+}
+
+// Some(output) for a valid proof of input under public_key
+pub fn verify<G: HashToCurveGroup>(
+    public_key: &G::Affine,
+    input: &[u8],
+    proof: &VrfProof<G>,
+) -> Option<[u8; DIGEST_LEN]> {
+    // a key of low order (or the identity) would make Γ predictable
+    if public_key.is_zero() || !in_subgroup::<G>(public_key) || !in_subgroup::<G>(&proof.gamma) {
+        return None;
+    }
+    let h = hash_to_curve::<G>(public_key, input);
+
+    let u = G::generator().mul_bigint(proof.s.into_bigint())
+        - public_key.mul_bigint(proof.c.into_bigint());
+    let v = h.mul_bigint(proof.s.into_bigint()) - proof.gamma.mul_bigint(proof.c.into_bigint());
+    let c = challenge::<G>(&[h, proof.gamma, u.into_affine(), v.into_affine()]);
+
+    (c == proof.c).then(|| proof_to_output(proof))
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use ark_bn254::G1Projective;
+    use ark_ec::PrimeGroup;
+
+    #[test]
+    fn test_prove_and_verify() {
+        let mut rng = rand::thread_rng();
+        let (secret_key, public_key) = keygen::<G1Projective, _>(&mut rng);
+
+        let proof = prove::<G1Projective>(secret_key, b"block 42");
+        let output = verify(&public_key, b"block 42", &proof).unwrap();
+        assert_eq!(output, proof_to_output(&proof));
+
+        // deterministic: proving again gives the same proof and output
+        assert_eq!(prove::<G1Projective>(secret_key, b"block 42"), proof);
+
+        // another input gives another output
+        let other = prove::<G1Projective>(secret_key, b"block 43");
+        assert_ne!(verify(&public_key, b"block 43", &other), Some(output));
+        assert_eq!(verify(&public_key, b"block 43", &proof), None);
+    }
+
+    #[test]
+    fn test_rejects_forged_proofs() {
+        let mut rng = rand::thread_rng();
+        let (secret_key, public_key) = keygen::<G1Projective, _>(&mut rng);
+        let (_, other_key) = keygen::<G1Projective, _>(&mut rng);
+        let proof = prove::<G1Projective>(secret_key, b"seed");
+
+        assert_eq!(verify(&other_key, b"seed", &proof), None);
+
+        // a different Γ would be a different output, it must not verify
+        let mut forged = proof.clone();
+        forged.gamma = (forged.gamma.into_group() + G1Projective::generator()).into_affine();
+        assert_eq!(verify(&public_key, b"seed", &forged), None);
+
+        let mut forged = proof.clone();
+        forged.s += forged.c;
+        assert_eq!(verify(&public_key, b"seed", &forged), None);
+
+        let identity = G1Projective::default().into_affine();
+        assert_eq!(verify(&identity, b"seed", &proof), None);
+    }
+}
@@ -0,0 +1,340 @@
+//=========================================================================================
+// Soundness harness: cheating provers run against the verifiers thousands of times and the
+// acceptance rate of every strategy is reported (cargo test soundness -- --nocapture prints them)
+// the strategies stay inside what a verifier checks locally (right lengths, g(0) + g(1) = claim
+// in every round, well formed points) so a rejection has to come from the check the strategy is
+// aimed at: the final evaluation of a sum-check, the pairing of an opening, the composition of
+// HyperPlonk at the sum-check point
+// every instance also runs the honest prover, a verifier that rejects everything would pass the
+// cheating strategies and fails there instead
+// with a 254 bit field the acceptance of any of these is about degree·num_vars/|F| (or 0 for the
+// openings), so a single accepted cheat is a wiring bug and the tests assert there is none
+// SOUNDNESS_TRIALS=<n> overrides every trial count, the pairing based ones default to fewer
+//=========================================================================================
+use crate::{
+    gkr::partial_sum_check::{self, start_transcript},
+    hyperplonk::{
+        hyperplonk_protocol::{self, HyperPlonkProof, ProvingKey},
+        plonkish::test::cubic,
+    },
+    kzg::{
+        batch_kzg::{batch_commit_open, batch_verify, BatchKZGProof},
+        kzg_protocol::{self, KZGProof},
+        trusted_setup::tests::setup_of_size,
+    },
+    lagrange::LagrangeWeights,
+    multi_linear::MultiLinearPoly,
+    sum_check::{self, round_challenge, statement_transcript},
+    transcript::{test::domain, Transcript},
+    virtual_polynomial::VirtualPolynomial,
+};
+use ark_bls12_381::{Bls12_381, Fr as BlsFr, G1Projective};
+use ark_bn254::Fr;
+use ark_ff::{PrimeField, UniformRand};
+use rand::{rngs::ThreadRng, Rng};
+use std::{env, fmt, sync::Arc};
+
+const SUM_CHECK_TRIALS: usize = 2000;
+const PAIRING_TRIALS: usize = 20;
+
+struct Report {
+    strategy: &'static str,
+    trials: usize,
+    accepted: usize,
+}
+
+impl Report {
+    fn rate(&self) -> f64 {
+        self.accepted as f64 / self.trials as f64
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<60} {:>6}/{:<6} accepted ({:.4})",
+            self.strategy,
+            self.accepted,
+            self.trials,
+            self.rate()
+        )
+    }
+}
+
+fn trials(default: usize) -> usize {
+    env::var("SOUNDNESS_TRIALS")
+        .ok()
+        .and_then(|trials| trials.parse().ok())
+        .unwrap_or(default)
+}
+
+// attempt returns whether the verifier accepted
+fn run(
+    strategy: &'static str,
+    trials: usize,
+    mut attempt: impl FnMut(&mut ThreadRng) -> bool,
+) -> Report {
+    let mut rng = rand::thread_rng();
+    let accepted = (0..trials).filter(|_| attempt(&mut rng)).count();
+    let report = Report {
+        strategy,
+        trials,
+        accepted,
+    };
+    println!("{}", report);
+
+    report
+}
+
+fn assert_sound(report: Report) {
+    assert_eq!(
+        report.accepted, 0,
+        "a cheating prover got through: {}",
+        report
+    );
+}
+
+fn assert_complete(report: Report) {
+    assert_eq!(
+        report.accepted, report.trials,
+        "an honest prover was rejected: {}",
+        report
+    );
+}
+
+// a random [g(0), ..., g(degree)] with g(0) + g(1) = claim, passes the local check of a round
+fn round_poly_for<F: PrimeField>(claim: F, degree: usize, rng: &mut ThreadRng) -> Vec<F> {
+    let mut round_poly: Vec<F> = (0..=degree).map(|_| F::rand(rng)).collect();
+    round_poly[1] = claim - round_poly[0];
+
+    round_poly
+}
+
+// g(0) + g(1) kept, everything else moved, for a proof that is honest up to that round
+fn perturb_round<F: PrimeField>(round_poly: &mut [F], rng: &mut ThreadRng) {
+    let shift = F::rand(rng);
+    round_poly[0] += shift;
+    round_poly[1] -= shift;
+    round_poly[2..]
+        .iter_mut()
+        .for_each(|value| *value += F::rand(rng));
+}
+
+fn random_mle(num_vars: usize, rng: &mut ThreadRng) -> MultiLinearPoly<Fr> {
+    let computation: Vec<Fr> = (0..1 << num_vars).map(|_| Fr::rand(rng)).collect();
+    MultiLinearPoly::new(&computation)
+}
+
+// a wrong sum with every round made up to pass g(0) + g(1) = claim against the real challenges
+fn cheating_sum_check(poly: &MultiLinearPoly<Fr>, rng: &mut ThreadRng) -> sum_check::Proof<Fr> {
+    let wrong_sum = poly.computation.iter().sum::<Fr>() + Fr::rand(rng);
+    let mut transcript: Transcript = statement_transcript(&poly.computation, wrong_sum);
+    let mut claim = wrong_sum;
+    let sum_polys = (0..poly.computation.len().ilog2())
+        .map(|_| {
+            let round_poly = round_poly_for(claim, 1, rng);
+            let challenge = round_challenge(&mut transcript, &round_poly);
+            claim = round_poly[0] + (round_poly[1] - round_poly[0]) * challenge;
+            MultiLinearPoly::new(&round_poly)
+        })
+        .collect();
+
+    sum_check::Proof {
+        init_poly: poly.clone(),
+        init_claimed_sum: wrong_sum,
+        sum_polys,
+    }
+}
+
+#[test]
+fn test_sum_check() {
+    let mut rng = rand::thread_rng();
+    let poly = random_mle(4, &mut rng);
+    let honest = sum_check::proof(poly.clone(), poly.computation.iter().sum());
+
+    assert_complete(run("sum_check: honest", trials(10), |_| {
+        sum_check::verify(&honest)
+    }));
+    assert_sound(run(
+        "sum_check: wrong sum, consistent random rounds",
+        trials(SUM_CHECK_TRIALS),
+        |rng| sum_check::verify(&cheating_sum_check(&poly, rng)),
+    ));
+    assert_sound(run(
+        "sum_check: true sum, last round moved within its check",
+        trials(SUM_CHECK_TRIALS),
+        |rng| {
+            let mut proof = honest.clone();
+            perturb_round(&mut proof.sum_polys.last_mut().unwrap().computation, rng);
+            sum_check::verify(&proof)
+        },
+    ));
+}
+
+// the GKR sum-check only hands back a subclaim, the oracle check is the caller's, so accepted is
+// both: every round passes and the last claim is the polynomial at the challenges
+fn partial_accepts(proof: &partial_sum_check::Proof<Fr>, poly: &VirtualPolynomial<Fr>) -> bool {
+    partial_sum_check::try_verify(proof)
+        .is_some_and(|sub_claim| sub_claim.last_claimed_sum == poly.evaluate(&sub_claim.challenges))
+}
+
+#[test]
+fn test_partial_sum_check() {
+    // a·b + b·c, the shape of a GKR layer
+    let mut rng = rand::thread_rng();
+    let (a, b, c) = (
+        Arc::new(random_mle(3, &mut rng)),
+        Arc::new(random_mle(3, &mut rng)),
+        Arc::new(random_mle(3, &mut rng)),
+    );
+    let mut poly = VirtualPolynomial::new(3);
+    poly.add_product([a, b.clone()], Fr::from(1));
+    poly.add_product([b, c], Fr::from(1));
+    let honest = partial_sum_check::proof_virtual(poly.clone(), poly.sum());
+
+    assert_complete(run("partial_sum_check: honest", trials(10), |_| {
+        partial_accepts(&honest, &poly)
+    }));
+    assert_sound(run(
+        "partial_sum_check: wrong sum, consistent random rounds",
+        trials(SUM_CHECK_TRIALS),
+        |rng| {
+            let wrong_sum = poly.sum() + Fr::rand(rng);
+            let mut transcript = start_transcript(&Transcript::new(), 3, 2, 2, wrong_sum);
+            let mut claim = wrong_sum;
+            let mut challenges = Vec::new();
+            let round_polys = (0..3)
+                .map(|_| {
+                    let round_poly = round_poly_for(claim, 2, rng);
+                    transcript.absorb(&MultiLinearPoly::to_bytes(&round_poly));
+                    let challenge = transcript.squeeze_field();
+                    claim = partial_sum_check::verify_round(
+                        claim,
+                        &round_poly,
+                        &LagrangeWeights::for_degree(2),
+                        challenge,
+                    )
+                    .unwrap();
+                    challenges.push(challenge);
+                    round_poly
+                })
+                .collect();
+
+            let proof = partial_sum_check::Proof {
+                degree: 2,
+                num_products: 2,
+                init_claimed_sum: wrong_sum,
+                challenges,
+                round_polys,
+            };
+            partial_accepts(&proof, &poly)
+        },
+    ));
+    assert_sound(run(
+        "partial_sum_check: true sum, last round moved within its check",
+        trials(SUM_CHECK_TRIALS),
+        |rng| {
+            let mut proof = honest.clone();
+            perturb_round(proof.round_polys.last_mut().unwrap(), rng);
+            partial_accepts(&proof, &poly)
+        },
+    ));
+}
+
+#[test]
+fn test_kzg_openings() {
+    let setup = setup_of_size(3);
+    let mut rng = rand::thread_rng();
+    let poly = MultiLinearPoly::new(&(0..8).map(|_| BlsFr::rand(&mut rng)).collect::<Vec<_>>());
+    let point = [BlsFr::from(3), BlsFr::from(5), BlsFr::from(7)];
+    let honest = kzg_protocol::proof::<BlsFr, Bls12_381>(poly.clone(), &setup.g1_arr, &point);
+    let verify = |proof: &KZGProof<BlsFr, Bls12_381>| {
+        kzg_protocol::verify(proof, &setup.verifier_setup, &point)
+    };
+
+    assert_complete(run("kzg: honest", trials(2), |_| verify(&honest)));
+    assert_sound(run(
+        "kzg: wrong value, honest quotients",
+        trials(PAIRING_TRIALS),
+        |rng| {
+            verify(&KZGProof {
+                commitment: honest.commitment,
+                quotient_evals: honest.quotient_evals.clone(),
+                poly_opened: honest.poly_opened + BlsFr::rand(rng),
+            })
+        },
+    ));
+    assert_sound(run(
+        "kzg: true value, random quotients",
+        trials(PAIRING_TRIALS),
+        |rng| {
+            verify(&KZGProof {
+                commitment: honest.commitment,
+                quotient_evals: (0..3).map(|_| G1Projective::rand(rng)).collect(),
+                poly_opened: honest.poly_opened,
+            })
+        },
+    ));
+
+    let other = MultiLinearPoly::new(&(0..8).map(|_| BlsFr::rand(&mut rng)).collect::<Vec<_>>());
+    let polys = [poly.clone(), other];
+    let batch = batch_commit_open::<BlsFr, Bls12_381>(&polys, &setup.g1_arr, &point, &domain());
+    assert!(batch_verify(
+        &batch,
+        &setup.verifier_setup,
+        &point,
+        &domain()
+    ));
+    assert_sound(run(
+        "batch_kzg: one of the values moved",
+        trials(PAIRING_TRIALS),
+        |rng| {
+            let mut forged = BatchKZGProof {
+                commitments: batch.commitments.clone(),
+                polys_opened: batch.polys_opened.clone(),
+                quotient_evals: batch.quotient_evals.clone(),
+            };
+            forged.polys_opened[1] += BlsFr::rand(rng);
+            batch_verify(&forged, &setup.verifier_setup, &point, &domain())
+        },
+    ));
+}
+
+#[test]
+fn test_hyperplonk() {
+    let pk = ProvingKey::<Bls12_381>::new(cubic(), &setup_of_size(3));
+    let public = [BlsFr::from(35)];
+    let honest = hyperplonk_protocol::prove(&pk, &public, &[BlsFr::from(3)], &domain());
+    let verify = |public: &[BlsFr], proof: &HyperPlonkProof<Bls12_381>| {
+        hyperplonk_protocol::verify(&pk.vk, public, proof, &domain())
+    };
+
+    assert_complete(run("hyperplonk: honest", trials(2), |_| {
+        verify(&public, &honest)
+    }));
+    assert_sound(run(
+        "hyperplonk: the proof replayed for another public input",
+        trials(PAIRING_TRIALS),
+        |rng| verify(&[BlsFr::rand(rng)], &honest),
+    ));
+    assert_sound(run(
+        "hyperplonk: last round moved within its check",
+        trials(PAIRING_TRIALS),
+        |rng| {
+            let mut proof = honest.clone();
+            perturb_round(proof.round_polys.last_mut().unwrap(), rng);
+            verify(&public, &proof)
+        },
+    ));
+    assert_sound(run(
+        "hyperplonk: a committed value moved at the sum-check point",
+        trials(PAIRING_TRIALS),
+        |rng| {
+            let mut proof = honest.clone();
+            let i = rng.gen_range(0..proof.evaluations.len());
+            proof.evaluations[i] += BlsFr::rand(rng);
+            verify(&public, &proof)
+        },
+    ));
+}
@@ -1,21 +1,127 @@
-use crate::{multi_linear::MultiLinearPoly, transcript::Transcript};
+use crate::{
+    explain::Explain,
+    fiat_shamir::PROVER_MSG_LABEL,
+    multi_linear::{MultiLinearPoly, SumCheckField},
+    progress::{never_cancelled, Cancelled, Progress, ProgressEvent},
+    security::SecurityConfig,
+    transcript::{Transcript, TranscriptHasher},
+    transcript_schedule::{Schedule, Step},
+};
 use ark_ff::PrimeField;
 
 #[derive(Debug, Clone)]
-pub struct Proof<F: PrimeField> {
+pub struct Proof<F: SumCheckField> {
     pub init_poly: MultiLinearPoly<F>,
     pub init_claimed_sum: F,
     pub sum_polys: Vec<MultiLinearPoly<F>>,
 }
 
 // The prover doesn't compute the claimed_sum in the proof fn but does it externally and passes it in to the proof fn
-pub fn proof<F: PrimeField>(mut poly: MultiLinearPoly<F>, init_claimed_sum: F) -> Proof<F> {
-    let init_poly = poly.computation.clone();
-    let mut transcript = Transcript::new();
-    transcript.absorb(&MultiLinearPoly::to_bytes(&poly.computation.clone()));
+pub fn proof<F: SumCheckField>(poly: MultiLinearPoly<F>, init_claimed_sum: F) -> Proof<F> {
+    never_cancelled(proof_with_progress(
+        poly,
+        init_claimed_sum,
+        &mut Progress::none(),
+    ))
+}
+
+// same proof with a Round reported after every round, see progress
+pub fn proof_with_progress<F: SumCheckField>(
+    poly: MultiLinearPoly<F>,
+    init_claimed_sum: F,
+    progress: &mut Progress,
+) -> Result<Proof<F>, Cancelled> {
+    let mut transcript: Transcript = statement_transcript(&poly.computation, init_claimed_sum);
 
     // let init_claimed_sum = poly.computation.iter().sum();
-    let mut sum_polys = vec![];
+    let sum_polys = prove_rounds(poly.clone(), &mut transcript, progress)?;
+
+    Ok(Proof {
+        init_poly: poly,
+        init_claimed_sum,
+        sum_polys,
+    })
+}
+
+pub fn verify<F: SumCheckField>(proof: &Proof<F>) -> bool {
+    verify_explained(proof, &mut Explain::off())
+}
+
+// same check with every claim, round poly, challenge and check recorded, see explain
+pub fn verify_explained<F: SumCheckField>(proof: &Proof<F>, explain: &mut Explain) -> bool {
+    // // check that the polynomial is correct
+    // let verifier_claimed_sum = proof.init_poly.computation.iter().sum();
+    // if proof.init_claimed_sum != verifier_claimed_sum {
+    //     return false;
+    // }
+
+    let mut transcript: Transcript =
+        statement_transcript(&proof.init_poly.computation, proof.init_claimed_sum);
+
+    verify_rounds(
+        &mut proof.init_poly.clone(),
+        proof.init_claimed_sum,
+        &proof.sum_polys,
+        &mut transcript,
+        explain,
+    )
+}
+
+//=========================================================================================
+// The transcript shared by the multilinear sum-check frontends
+// sum_check, fiat_shamir_non_interactive and interactive_sum_check all run the same schedule:
+// [STATEMENT_POLY_LABEL: the evaluations][STATEMENT_SUM_LABEL: the claimed sum], then each round
+// [PROVER_MSG_LABEL: the round poly] followed by one squeeze for the round challenge
+// so one poly and one claim get the same challenges whichever frontend runs it, and whichever
+// TranscriptHasher they are given as long as it is the same on both sides
+// SUM_CHECK_SCHEDULE is that order as a transcript_schedule, both functions follow it
+//=========================================================================================
+pub static SUM_CHECK_SCHEDULE: Schedule = Schedule {
+    protocol: "sum_check",
+    steps: &[
+        Step::Absorb("poly"),
+        Step::Absorb("claimed sum"),
+        Step::Repeat("rounds", &[Step::Absorb("round poly"), Step::Squeeze("r")]),
+    ],
+};
+
+pub const STATEMENT_POLY_LABEL: &[u8] = b"sum-check-poly";
+pub const STATEMENT_SUM_LABEL: &[u8] = b"sum-check-claimed-sum";
+
+pub fn statement_transcript<F: SumCheckField, H: TranscriptHasher>(
+    evaluations: &[F],
+    claimed_sum: F,
+) -> Transcript<H> {
+    let mut transcript = Transcript::with_hasher().with_schedule(&SUM_CHECK_SCHEDULE);
+    transcript
+        .follow(Step::Absorb("poly"))
+        .absorb_labeled(STATEMENT_POLY_LABEL, &F::transcript_bytes(evaluations));
+    transcript
+        .follow(Step::Absorb("claimed sum"))
+        .absorb_labeled(STATEMENT_SUM_LABEL, &F::transcript_bytes(&[claimed_sum]));
+
+    transcript
+}
+
+// the round poly is absorbed the way fiat_shamir::compile absorbs a prover message
+pub fn round_challenge<F: SumCheckField, H: TranscriptHasher>(
+    transcript: &mut Transcript<H>,
+    round_poly: &[F],
+) -> F {
+    transcript
+        .follow(Step::Absorb("round poly"))
+        .absorb_labeled(PROVER_MSG_LABEL, &F::transcript_bytes(round_poly));
+
+    F::squeeze_challenge(transcript.follow(Step::Squeeze("r")))
+}
+
+fn prove_rounds<F: SumCheckField>(
+    mut poly: MultiLinearPoly<F>,
+    transcript: &mut Transcript,
+    progress: &mut Progress,
+) -> Result<Vec<MultiLinearPoly<F>>, Cancelled> {
+    let num_rounds = poly.computation.len().ilog2() as usize;
+    let mut sum_polys = Vec::with_capacity(num_rounds);
 
     while poly.computation.len() > 1 {
         let half_len = poly.computation.len() / 2;
@@ -23,71 +129,160 @@ pub fn proof<F: PrimeField>(mut poly: MultiLinearPoly<F>, init_claimed_sum: F) -
         let left_sum: F = left.iter().sum();
         let right_sum = right.iter().sum();
 
-        let claimed_sum: F = poly.computation.iter().sum();
-        let sum_poly = MultiLinearPoly::new(&vec![left_sum, right_sum]);
+        let sum_poly = MultiLinearPoly::new(&[left_sum, right_sum]);
         // println!("Sum poly is {:?}", sum_poly);
-        sum_polys.push(MultiLinearPoly {
-            computation: sum_poly.computation.clone(),
-        });
 
-        transcript.absorb(&MultiLinearPoly::to_bytes(&vec![claimed_sum]));
-        transcript.absorb(&MultiLinearPoly::to_bytes(&sum_poly.computation.clone()));
-        let challenge_bytes = transcript.squeeze();
-        let challenge = F::from_be_bytes_mod_order(&challenge_bytes);
+        let challenge = round_challenge(transcript, &sum_poly.computation);
+        sum_polys.push(sum_poly);
 
         poly = poly.partial_evaluate(challenge, 0);
+        progress.report(ProgressEvent::Round {
+            layer: None,
+            done: sum_polys.len(),
+            total: num_rounds,
+        })?;
     }
 
-    Proof {
-        init_poly: MultiLinearPoly {
-            computation: init_poly,
-        },
-        init_claimed_sum,
-        sum_polys,
-    }
+    Ok(sum_polys)
 }
 
-pub fn verify<F: PrimeField>(mut proof: Proof<F>) -> bool {
-    // // check that the polynomial is correct
-    // let verifier_claimed_sum = proof.init_poly.computation.iter().sum();
-    // if proof.init_claimed_sum != verifier_claimed_sum {
-    //     return false;
-    // }
-
-    let mut transcript = Transcript::new();
-    transcript.absorb(&MultiLinearPoly::to_bytes(
-        &proof.init_poly.computation.clone(),
-    ));
-
-    let mut claimed_sum: F = proof.init_claimed_sum;
+fn verify_rounds<F: SumCheckField>(
+    init_poly: &mut MultiLinearPoly<F>,
+    init_claimed_sum: F,
+    sum_polys: &[MultiLinearPoly<F>],
+    transcript: &mut Transcript,
+    explain: &mut Explain,
+) -> bool {
+    let mut claimed_sum: F = init_claimed_sum;
     let mut challenges: Vec<F> = vec![];
 
-    for sum_poly in proof.sum_polys.iter() {
+    explain.section("statement");
+    explain.claim("claimed sum", init_claimed_sum);
+    if !explain.check(
+        "one round per variable",
+        sum_polys.len() == init_poly.computation.len().ilog2() as usize,
+    ) {
+        return false;
+    }
+
+    explain.section("rounds");
+    for (round, sum_poly) in sum_polys.iter().enumerate() {
+        if sum_poly.computation.len() != 2 {
+            return false;
+        }
+        explain.round_poly(round, &sum_poly.computation);
         let poly_sum: F = sum_poly.computation.iter().sum();
-        if claimed_sum != poly_sum {
+        if !explain.check("g(0) + g(1) = claim", claimed_sum == poly_sum) {
             return false;
         }
 
-        transcript.absorb(&MultiLinearPoly::to_bytes(&vec![claimed_sum]));
-        transcript.absorb(&MultiLinearPoly::to_bytes(&sum_poly.computation.clone()));
-        let challenge_bytes = transcript.squeeze();
-        let challenge = F::from_be_bytes_mod_order(&challenge_bytes);
+        let challenge = round_challenge(transcript, &sum_poly.computation);
+        explain.challenge(&format!("r_{}", round), challenge);
         challenges.push(challenge);
 
         // verifier uses the (y_1 + (y_2 - y_1) * challenge) to evaluate the polynomial
         claimed_sum = sum_poly.computation[0]
             + ((sum_poly.computation[1] - sum_poly.computation[0]) * challenge);
+        explain.claim(&format!("g(r_{})", round), claimed_sum);
+    }
+
+    explain.section("final evaluation");
+    let final_eval = init_poly.evaluate(&challenges);
+    explain.claim("f(r)", final_eval.computation[0]);
+
+    explain.check("f(r) = g(r_last)", final_eval.computation[0] == claimed_sum)
+}
+
+//=========================================================================================
+// Repetition mode for small fields
+// a cheating prover gets through one run w.p. about num_vars / |F|, over a 64 bit field that is
+// nowhere near 100 bits, k runs with independent challenges push it down to (num_vars / |F|)ᵏ
+// every run's transcript has its repetition index absorbed after the poly so the challenges differ
+// the runs share the poly and the claimed sum, the verifier accepts only if every run does
+// SecurityConfig::num_repetitions is how many runs a field needs for its target
+//=========================================================================================
+#[derive(Debug, Clone)]
+pub struct RepeatedProof<F: PrimeField> {
+    pub init_poly: MultiLinearPoly<F>,
+    pub init_claimed_sum: F,
+    pub repetitions: Vec<Vec<MultiLinearPoly<F>>>, // the sum polys of each run
+}
+
+pub fn repeated_proof<F: PrimeField>(
+    poly: MultiLinearPoly<F>,
+    init_claimed_sum: F,
+    num_repetitions: usize,
+) -> RepeatedProof<F> {
+    if num_repetitions == 0 {
+        panic!("At least one repetition is needed");
+    }
+
+    let repetitions = (0..num_repetitions)
+        .map(|repetition| {
+            let mut transcript = repetition_transcript(&poly, init_claimed_sum, repetition);
+            never_cancelled(prove_rounds(
+                poly.clone(),
+                &mut transcript,
+                &mut Progress::none(),
+            ))
+        })
+        .collect();
+
+    RepeatedProof {
+        init_poly: poly,
+        init_claimed_sum,
+        repetitions,
     }
+}
 
-    let final_eval = proof.init_poly.evaluate(&challenges);
+pub fn proof_for_security<F: PrimeField>(
+    poly: MultiLinearPoly<F>,
+    init_claimed_sum: F,
+    security: &SecurityConfig,
+) -> RepeatedProof<F> {
+    repeated_proof(poly, init_claimed_sum, security.num_repetitions)
+}
 
-    final_eval.computation[0] == claimed_sum
+// every run must verify and there must be as many runs as the security level asks for
+pub fn verify_repeated<F: PrimeField>(proof: &RepeatedProof<F>, security: &SecurityConfig) -> bool {
+    if proof.repetitions.len() < security.num_repetitions.max(1) {
+        return false;
+    }
+
+    proof
+        .repetitions
+        .iter()
+        .enumerate()
+        .all(|(repetition, sum_polys)| {
+            let mut transcript =
+                repetition_transcript(&proof.init_poly, proof.init_claimed_sum, repetition);
+            verify_rounds(
+                &mut proof.init_poly.clone(),
+                proof.init_claimed_sum,
+                sum_polys,
+                &mut transcript,
+                &mut Explain::off(),
+            )
+        })
 }
 
-#[cfg(test)]
+fn repetition_transcript<F: PrimeField>(
+    poly: &MultiLinearPoly<F>,
+    init_claimed_sum: F,
+    repetition: usize,
+) -> Transcript {
+    let mut transcript = statement_transcript(&poly.computation, init_claimed_sum);
+    transcript.absorb_labeled(b"repetition", &(repetition as u64).to_be_bytes());
+
+    transcript
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
 mod tests {
     use super::*;
-    use crate::multi_linear::MultiLinearPoly;
+    use crate::{
+        binary_tower::B128, explain::ExplainStep, multi_linear::MultiLinearPoly, sum_check,
+    };
     use ark_ff::UniformRand;
     use field_tracker::{print_summary, Ft};
     type Fq = Ft!(ark_bn254::Fq);
@@ -108,14 +303,14 @@ mod tests {
         let poly = generate_random_poly();
         let init_claimed_sum = poly.computation.iter().sum();
         let proof = proof(poly.clone(), init_claimed_sum);
-        let result = verify(proof);
+        let result = verify(&proof);
         assert!(result);
         print_summary!();
     }
 
     #[test]
     fn test_proof() {
-        let poly = vec![MultiLinearPoly::new(&vec![
+        let poly = [MultiLinearPoly::new(&vec![
             Fq::from(0),
             Fq::from(0),
             Fq::from(0),
@@ -144,7 +339,7 @@ mod tests {
 
     #[test]
     fn test_verify() {
-        let poly = vec![MultiLinearPoly::new(&vec![
+        let poly = [MultiLinearPoly::new(&vec![
             Fq::from(0),
             Fq::from(0),
             Fq::from(0),
@@ -167,8 +362,75 @@ mod tests {
 
         let init_claimed_sum = poly[0].computation.iter().sum();
         let proof = proof(poly[0].clone(), init_claimed_sum);
-        let result = verify(proof);
+        let result = verify(&proof);
         println!("Result is {:?}", result);
         assert!(result);
     }
+
+    #[test]
+    fn test_verify_explained() {
+        let poly = MultiLinearPoly::new(&[0, 0, 0, 3, 0, 0, 2, 5].map(Fq::from));
+        let proof = proof(poly, Fq::from(10));
+
+        let mut explain = Explain::new("sum-check");
+        assert!(verify_explained(&proof, &mut explain));
+        let round_polys = explain
+            .steps()
+            .iter()
+            .filter(|step| matches!(step, ExplainStep::RoundPoly { .. }))
+            .count();
+        assert_eq!(round_polys, 3);
+        assert!(explain.to_text().ends_with("f(r) = g(r_last): ok\n"));
+
+        // a wrong claim stops at the first round check
+        let mut wrong = proof;
+        wrong.init_claimed_sum = Fq::from(11);
+        let mut explain = Explain::new("sum-check");
+        assert!(!verify_explained(&wrong, &mut explain));
+        assert_eq!(
+            explain.steps().last(),
+            Some(&ExplainStep::Check {
+                label: "g(0) + g(1) = claim".to_string(),
+                passed: false
+            })
+        );
+    }
+
+    #[test]
+    fn test_repeated_proof() {
+        let poly = MultiLinearPoly::new(&[0, 0, 0, 3, 0, 0, 2, 5].map(Fq::from));
+        let security = SecurityConfig::for_target(100, 64, 2);
+        assert_eq!(security.num_repetitions, 2);
+
+        let proof = proof_for_security(poly, Fq::from(10), &security);
+        assert_eq!(proof.repetitions.len(), 2);
+        // independent challenges, the runs only agree on the first round
+        assert_ne!(proof.repetitions[0][1], proof.repetitions[1][1]);
+        assert!(verify_repeated(&proof, &security));
+
+        // too few runs for the security level
+        let mut short = proof.clone();
+        short.repetitions.pop();
+        assert!(!verify_repeated(&short, &security));
+
+        let mut wrong_sum = proof;
+        wrong_sum.init_claimed_sum = Fq::from(11);
+        assert!(!verify_repeated(&wrong_sum, &security));
+    }
+
+    #[test]
+    fn test_sum_check_over_b128() {
+        let mut rng = rand::thread_rng();
+        let values: Vec<B128> = (0..16).map(|_| B128::rand(&mut rng)).collect();
+        let sum: B128 = values.iter().sum();
+
+        let proof = sum_check::proof(MultiLinearPoly::new(&values), sum);
+        assert!(sum_check::verify(&proof));
+
+        let wrong = sum + B128::ONE;
+        assert!(!sum_check::verify(&sum_check::proof(
+            MultiLinearPoly::new(&values),
+            wrong
+        )));
+    }
 }
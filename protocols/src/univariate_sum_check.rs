@@ -0,0 +1,302 @@
+#[cfg(feature = "prover")]
+use crate::fri::{
+    lde::lde_with,
+    low_degree::{commit_layer, leaf_bytes, prove_low_degree},
+};
+use crate::{
+    fri::{
+        domain_cache::DomainCache,
+        low_degree::{
+            check_opening, query_indices, verify_low_degree, LowDegreeConfig, LowDegreeProof,
+        },
+        merkle_tree::MerkleProof,
+    },
+    multi_linear::MultiLinearPoly,
+    transcript::{Transcript, TranscriptDomain},
+};
+use ark_ff::{FftField, PrimeField};
+
+//=========================================================================================
+// Univariate sum-check (Aurora) over a multiplicative subgroup H of size m
+// Z_H(x) = xᵐ - 1 and Σ_{x∈H} xⁱ = 0 unless m | i, so dividing f by Z_H gives
+// f(x) = g(x) * Z_H(x) + x * h(x) + s / m   where s = Σ_{x∈H} f(x)
+// the prover commits f, g and h over the same FRI domain L, proves each is low degree
+// (deg f < d, deg g < d - m, deg h < m - 1) and opens all three at random points of L
+// the verifier checks the decomposition at those points with the claimed s
+// with config.blinding > 0, L is the coset g·ωʲ and never meets H
+// NOTE: only FRI commitments here, the KZG in this repo is multilinear
+//=========================================================================================
+#[derive(Debug, Clone)]
+pub struct OracleOpening<F: PrimeField> {
+    pub value: F,
+    pub proof: MerkleProof,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnivariateSumCheckProof<F: PrimeField> {
+    pub f_proof: LowDegreeProof<F>,
+    pub g_proof: LowDegreeProof<F>,
+    pub h_proof: LowDegreeProof<F>,
+    pub openings: Vec<[OracleOpening<F>; 3]>, // f, g and h at every consistency query
+}
+
+impl<F: PrimeField> UnivariateSumCheckProof<F> {
+    // Merkle root of f over L, the commitment the claim is about
+    pub fn f_commitment(&self) -> &[u8] {
+        self.f_proof.commitment()
+    }
+}
+
+// Σ_{x∈H} f(x) for f given by its coefficients, straight from the decomposition
+pub fn subgroup_sum<F: PrimeField>(coefficients: &[F], subgroup_size: usize) -> F {
+    let (_, remainder) = divide_by_vanishing(coefficients, subgroup_size);
+
+    remainder[0] * F::from(subgroup_size as u64)
+}
+
+// f = g * (xᵐ - 1) + remainder with deg remainder < m
+pub fn divide_by_vanishing<F: PrimeField>(
+    coefficients: &[F],
+    subgroup_size: usize,
+) -> (Vec<F>, Vec<F>) {
+    let mut remainder = coefficients.to_vec();
+    remainder.resize(remainder.len().max(subgroup_size), F::zero());
+
+    let mut quotient = vec![F::zero(); remainder.len() - subgroup_size];
+    for i in (subgroup_size..remainder.len()).rev() {
+        let coeff = remainder[i];
+        quotient[i - subgroup_size] = coeff;
+        remainder[i] = F::zero();
+        remainder[i - subgroup_size] += coeff;
+    }
+    remainder.truncate(subgroup_size);
+
+    (quotient, remainder)
+}
+
+#[cfg(feature = "prover")]
+pub fn proof<F: FftField + PrimeField>(
+    coefficients: &[F],
+    subgroup_size: usize,
+    config: &LowDegreeConfig,
+    transcript_domain: &TranscriptDomain,
+) -> UnivariateSumCheckProof<F> {
+    if !subgroup_size.is_power_of_two() || subgroup_size < 2 {
+        panic!("The subgroup size must be a power of 2 greater than 1");
+    }
+
+    let degree_bound = coefficients.len();
+    let (g_bound, h_bound) = degree_bounds(degree_bound, subgroup_size);
+    let domain_size = config.domain_size(degree_bound);
+
+    let (mut g, remainder) = divide_by_vanishing(coefficients, subgroup_size);
+    let claimed_sum = remainder[0] * F::from(subgroup_size as u64);
+    let mut h = remainder[1..].to_vec();
+    g.resize(g_bound, F::zero());
+    h.resize(h_bound, F::zero());
+
+    let mut cache = DomainCache::new();
+    let oracles: Vec<Vec<F>> = [(coefficients, degree_bound), (&g, g_bound), (&h, h_bound)]
+        .iter()
+        .map(|(coeffs, bound)| {
+            let blowup = domain_size / bound.next_power_of_two();
+            lde_with(coeffs, blowup, config.coset_shift(), &mut cache)
+        })
+        .collect();
+
+    let low_degree_proofs: Vec<LowDegreeProof<F>> = oracles
+        .iter()
+        .zip([degree_bound, g_bound, h_bound])
+        .map(|(evals, bound)| {
+            prove_low_degree(
+                evals,
+                bound,
+                &oracle_config(config, domain_size, bound),
+                transcript_domain,
+            )
+        })
+        .collect();
+    let m_trees: Vec<_> = oracles.iter().map(|evals| commit_layer(evals)).collect();
+
+    let mut transcript = statement_transcript(
+        &[
+            &low_degree_proofs[0],
+            &low_degree_proofs[1],
+            &low_degree_proofs[2],
+        ],
+        claimed_sum,
+        transcript_domain,
+    );
+    let openings = query_indices::<F>(&mut transcript, domain_size, config.num_queries)
+        .into_iter()
+        .map(|query| {
+            [0, 1, 2].map(|i| OracleOpening {
+                value: oracles[i][query],
+                proof: m_trees[i]
+                    .generate_proof(&leaf_bytes(query, oracles[i][query]))
+                    .unwrap(),
+            })
+        })
+        .collect();
+
+    let [f_proof, g_proof, h_proof]: [LowDegreeProof<F>; 3] = low_degree_proofs.try_into().unwrap();
+
+    UnivariateSumCheckProof {
+        f_proof,
+        g_proof,
+        h_proof,
+        openings,
+    }
+}
+
+// true if the committed f has degree < degree_bound and sums to claimed_sum over H
+pub fn verify<F: FftField + PrimeField>(
+    proof: &UnivariateSumCheckProof<F>,
+    subgroup_size: usize,
+    degree_bound: usize,
+    claimed_sum: F,
+    config: &LowDegreeConfig,
+    transcript_domain: &TranscriptDomain,
+) -> bool {
+    if !subgroup_size.is_power_of_two()
+        || subgroup_size < 2
+        || proof.openings.len() != config.num_queries
+    {
+        return false;
+    }
+
+    let (g_bound, h_bound) = degree_bounds(degree_bound, subgroup_size);
+    let domain_size = config.domain_size(degree_bound);
+    let low_degree_proofs = [&proof.f_proof, &proof.g_proof, &proof.h_proof];
+
+    for (ld_proof, bound) in low_degree_proofs
+        .iter()
+        .zip([degree_bound, g_bound, h_bound])
+    {
+        if !verify_low_degree(
+            *ld_proof,
+            bound,
+            &oracle_config(config, domain_size, bound),
+            transcript_domain,
+        ) {
+            return false;
+        }
+    }
+
+    let mut transcript = statement_transcript(&low_degree_proofs, claimed_sum, transcript_domain);
+    let queries = query_indices::<F>(&mut transcript, domain_size, config.num_queries);
+
+    let domain = config.coset_domain::<F>(degree_bound, &mut DomainCache::new());
+    let m = F::from(subgroup_size as u64);
+    let sum_term = claimed_sum * m.inverse().unwrap();
+
+    queries
+        .iter()
+        .zip(proof.openings.iter())
+        .all(|(query, openings)| {
+            let all_open =
+                openings
+                    .iter()
+                    .zip(low_degree_proofs.iter())
+                    .all(|(opening, ld_proof)| {
+                        check_opening(*query, opening.value, &opening.proof, ld_proof.commitment())
+                    });
+            if !all_open {
+                return false;
+            }
+
+            // f(x) = g(x) * (xᵐ - 1) + x * h(x) + s / m
+            let x = domain.element(*query);
+            let z_h = x.pow([subgroup_size as u64]) - F::one();
+            let [f_x, g_x, h_x] = [0, 1, 2].map(|i| openings[i].value);
+
+            f_x == g_x * z_h + x * h_x + sum_term
+        })
+}
+
+// (deg g bound, deg h bound), at least 1 each so they are still committed as (zero) polys
+fn degree_bounds(degree_bound: usize, subgroup_size: usize) -> (usize, usize) {
+    (
+        degree_bound.saturating_sub(subgroup_size).max(1),
+        (subgroup_size - 1).max(1),
+    )
+}
+
+// every oracle lives on the same domain so a lower degree bound gets a bigger blowup
+fn oracle_config(
+    config: &LowDegreeConfig,
+    domain_size: usize,
+    degree_bound: usize,
+) -> LowDegreeConfig {
+    LowDegreeConfig {
+        blowup_factor: domain_size / degree_bound.next_power_of_two(),
+        num_queries: config.num_queries,
+        grinding_bits: config.grinding_bits,
+        storage: config.storage.clone(),
+        blinding: config.blinding,
+    }
+}
+
+fn statement_transcript<F: PrimeField>(
+    low_degree_proofs: &[&LowDegreeProof<F>],
+    claimed_sum: F,
+    transcript_domain: &TranscriptDomain,
+) -> Transcript {
+    let mut transcript = transcript_domain.transcript();
+    for ld_proof in low_degree_proofs.iter() {
+        transcript.absorb_merkle_root(ld_proof.commitment());
+    }
+    transcript.absorb(&MultiLinearPoly::to_bytes(&[claimed_sum]));
+
+    transcript
+}
+
+#[cfg(all(test, feature = "prover", feature = "bls12-377"))]
+mod test {
+    use super::*;
+    use crate::transcript::test::domain;
+    use ark_bls12_377::Fr;
+
+    // 5 + 3x + 2x^3 + x^4 + 7x^5 + 4x^9 + x^12
+    fn poly_1() -> Vec<Fr> {
+        [5, 3, 0, 2, 1, 7, 0, 0, 0, 4, 0, 0, 1]
+            .map(Fr::from)
+            .to_vec()
+    }
+
+    #[test]
+    fn test_subgroup_sum() {
+        // over H of size 4 only x^0, x^4, x^12 survive: 4 * (5 + 1 + 1)
+        assert_eq!(subgroup_sum(&poly_1(), 4), Fr::from(28));
+
+        let domain = DomainCache::<Fr>::new().get(4);
+        let direct: Fr = (0..4)
+            .map(|j| {
+                let x = domain.element(j);
+                poly_1()
+                    .iter()
+                    .rev()
+                    .fold(Fr::from(0), |acc, c| acc * x + c)
+            })
+            .sum();
+        assert_eq!(direct, Fr::from(28));
+    }
+
+    #[test]
+    fn test_univariate_sum_check() {
+        let config = LowDegreeConfig::default();
+        let proof = proof(&poly_1(), 4, &config, &domain());
+
+        assert!(verify(&proof, 4, 13, Fr::from(28), &config, &domain()));
+        assert!(!verify(&proof, 4, 13, Fr::from(29), &config, &domain()));
+    }
+
+    #[test]
+    fn test_univariate_sum_check_rejects_tampered_opening() {
+        let config = LowDegreeConfig::default();
+        let mut proof = proof(&poly_1(), 4, &config, &domain());
+        proof.openings[0][2].value += Fr::from(1);
+
+        assert!(!verify(&proof, 4, 13, Fr::from(28), &config, &domain()));
+    }
+}
@@ -0,0 +1,379 @@
+use crate::{
+    fri::low_degree::LowDegreeConfig,
+    gkr::{
+        chunked_sum_check::LAYER_DEGREE, gkr_2_to_1_trick::LayerReduction, gkr_circuit::Circuit,
+        partial_sum_check::PARTIAL_SUM_CHECK_SCHEDULE,
+    },
+    multi_linear::MultiLinearPoly,
+    transcript::DIGEST_LEN,
+};
+use ark_ff::PrimeField;
+use std::{
+    fmt,
+    ops::{Add, AddAssign},
+};
+
+//=========================================================================================
+// What a verifier costs, worked out from the circuit and the parameters without running it
+// for picking a backend before targeting a chain or a recursive circuit:
+//   field_adds / field_muls / field_invs  (subtractions count as adds)
+//   hash_calls:    transcript squeezes, Merkle compressions and grinding checks
+//   hashed_bytes:  everything fed to a hasher, what a Keccak precompile is charged on
+//   group_muls:    scalar multiplications in G1 or G2
+//   pairings
+// the counts follow the verifiers in this crate step by step, e.g. the GKR wiring is the dense
+// addᵢ / mulᵢ tables every layer fixes its challenges in, which dominates for wide layers
+// the opening of the input layer is where KZG (pairings) and FRI (hashes) differ, see InputOpening
+// it is an estimate: the early exits of a rejected proof and the bookkeeping are not counted
+//=========================================================================================
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerifierCost {
+    pub field_adds: u64,
+    pub field_muls: u64,
+    pub field_invs: u64,
+    pub hash_calls: u64,
+    pub hashed_bytes: u64,
+    pub group_muls: u64,
+    pub pairings: u64,
+}
+
+impl VerifierCost {
+    fn field(adds: u64, muls: u64) -> Self {
+        VerifierCost {
+            field_adds: adds,
+            field_muls: muls,
+            ..Self::default()
+        }
+    }
+
+    // bytes absorbed into a transcript, no finalization
+    fn absorb(bytes: u64) -> Self {
+        VerifierCost {
+            hashed_bytes: bytes,
+            ..Self::default()
+        }
+    }
+
+    // a squeeze finalizes once and absorbs its output back
+    fn squeezes(count: u64) -> Self {
+        VerifierCost {
+            hash_calls: count,
+            hashed_bytes: count * DIGEST_LEN as u64,
+            ..Self::default()
+        }
+    }
+
+    fn times(self, n: u64) -> Self {
+        VerifierCost {
+            field_adds: self.field_adds * n,
+            field_muls: self.field_muls * n,
+            field_invs: self.field_invs * n,
+            hash_calls: self.hash_calls * n,
+            hashed_bytes: self.hashed_bytes * n,
+            group_muls: self.group_muls * n,
+            pairings: self.pairings * n,
+        }
+    }
+}
+
+impl Add for VerifierCost {
+    type Output = VerifierCost;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        VerifierCost {
+            field_adds: self.field_adds + rhs.field_adds,
+            field_muls: self.field_muls + rhs.field_muls,
+            field_invs: self.field_invs + rhs.field_invs,
+            hash_calls: self.hash_calls + rhs.hash_calls,
+            hashed_bytes: self.hashed_bytes + rhs.hashed_bytes,
+            group_muls: self.group_muls + rhs.group_muls,
+            pairings: self.pairings + rhs.pairings,
+        }
+    }
+}
+
+impl AddAssign for VerifierCost {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+// same shape as SizeReport, one named part of the verifier per component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostReport {
+    pub components: Vec<(&'static str, VerifierCost)>,
+}
+
+impl CostReport {
+    pub fn total(&self) -> VerifierCost {
+        self.components
+            .iter()
+            .fold(VerifierCost::default(), |total, (_, cost)| total + *cost)
+    }
+
+    pub fn get(&self, name: &str) -> Option<VerifierCost> {
+        self.components
+            .iter()
+            .find(|(component, _)| *component == name)
+            .map(|(_, cost)| *cost)
+    }
+}
+
+impl fmt::Display for CostReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<16} {:>12} {:>12} {:>6} {:>8} {:>12} {:>8} {:>8}",
+            "", "adds", "muls", "invs", "hashes", "hash bytes", "grp muls", "pairings"
+        )?;
+        let total = self.total();
+        for (name, cost) in self.components.iter().chain([("total", total)].iter()) {
+            writeln!(
+                f,
+                "{:<16} {:>12} {:>12} {:>6} {:>8} {:>12} {:>8} {:>8}",
+                name,
+                cost.field_adds,
+                cost.field_muls,
+                cost.field_invs,
+                cost.hash_calls,
+                cost.hashed_bytes,
+                cost.group_muls,
+                cost.pairings
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// how the verifier learns the input layer at the two points the last sum-check ends on
+#[derive(Debug, Clone)]
+pub enum InputOpening {
+    Public,               // the inputs are in the statement, their MLE is evaluated directly
+    Kzg,                  // two multilinear KZG openings (kzg_protocol::verify)
+    Fri(LowDegreeConfig), // two FRI openings of the input vector (pcs::fri)
+}
+
+// bytes one element takes in the transcript, MultiLinearPoly::to_bytes
+pub fn field_bytes<F: PrimeField>() -> u64 {
+    MultiLinearPoly::to_bytes(&[F::zero()]).len() as u64
+}
+
+// fixing k of the n variables of a dense table, a + r(b - a) per halved entry
+fn fix_vars(n: u32, k: u32) -> VerifierCost {
+    let entries = (1u64 << n) - (1u64 << (n - k));
+    VerifierCost::field(2 * entries, entries)
+}
+
+// χ table of n variables and two evaluations against it (EvaluationTable)
+fn evaluation_table(n: u32, evaluations: u64) -> VerifierCost {
+    let size = 1u64 << n;
+    VerifierCost::field(size - 1, size - 1) + VerifierCost::field(size, size).times(evaluations)
+}
+
+// the barycentric evaluation of LagrangeWeights over `points` points, one batched inversion
+fn lagrange_eval(points: u64) -> VerifierCost {
+    VerifierCost {
+        field_invs: 1,
+        ..VerifierCost::field(2 * points - 1, 6 * points - 3)
+    }
+}
+
+// partial_sum_check::try_verify: a fork of the caller's transcript, labelled with the protocol
+// name, that absorbs (num_vars, degree, num_products, claim), then per round the round poly
+// absorbed, one squeeze, g(0) + g(1) and g(r)
+pub fn sum_check_cost(num_vars: usize, degree: usize, field_bytes: u64) -> VerifierCost {
+    // the fork label goes in as absorb_labeled(b"fork", name)
+    let fork = 4 + 4 + 8 + PARTIAL_SUM_CHECK_SCHEDULE.protocol.len() as u64;
+    let start = VerifierCost::absorb(fork + 3 * 8 + field_bytes);
+    let round = VerifierCost::absorb((degree as u64 + 1) * field_bytes)
+        + VerifierCost::squeezes(1)
+        + VerifierCost::field(1, 0)
+        + lagrange_eval(degree as u64 + 1);
+
+    start + round.times(num_vars as u64)
+}
+
+// kzg_protocol::verify: a pairing per variable plus one, [τᵢ - aᵢ]₂ and [f(τ) - v]₁ on the way
+pub fn kzg_opening_cost(num_vars: usize) -> VerifierCost {
+    VerifierCost {
+        group_muls: num_vars as u64 + 2,
+        pairings: num_vars as u64 + 1,
+        ..VerifierCost::default()
+    }
+}
+
+// verify_low_degree plus the opening of f at every query position (pcs::fri)
+pub fn fri_opening_cost(
+    degree_bound: usize,
+    config: &LowDegreeConfig,
+    field_bytes: u64,
+) -> VerifierCost {
+    let domain_size = config.domain_size(degree_bound);
+    let rounds = degree_bound.next_power_of_two().ilog2();
+    let final_len = (domain_size >> rounds) as u64;
+    // a leaf is the index and the value, a node the prefix and two digests
+    let path = |depth: u32| {
+        let leaf = VerifierCost {
+            hash_calls: 1,
+            hashed_bytes: 1 + 8 + field_bytes,
+            ..VerifierCost::default()
+        };
+        let node = VerifierCost {
+            hash_calls: 1,
+            hashed_bytes: 1 + 2 * DIGEST_LEN as u64,
+            ..VerifierCost::default()
+        };
+        leaf + node.times(depth as u64)
+    };
+
+    let transcript = VerifierCost::absorb((1 + 4 + DIGEST_LEN as u64) * rounds as u64)
+        + VerifierCost::squeezes(rounds as u64)
+        + VerifierCost::absorb(final_len * field_bytes)
+        + VerifierCost {
+            hash_calls: 1,
+            hashed_bytes: 8 + 8,
+            ..VerifierCost::default()
+        } // the grinding check
+        + VerifierCost::squeezes(config.num_queries as u64);
+
+    let depth = domain_size.ilog2();
+    let query = (0..rounds)
+        .map(|round| path(depth - round).times(2) + VerifierCost::field(3, 4))
+        .fold(VerifierCost::default(), |total, cost| total + cost)
+        // f at the position and q(ωʲ)(ωʲ - z) = f(ωʲ) - v
+        + path(depth)
+        + VerifierCost::field(2, 1);
+
+    transcript + VerifierCost::field(final_len, 0) + query.times(config.num_queries as u64)
+}
+
+impl<F: PrimeField> Circuit<F> {
+    // the cost of Circuit::verify for this circuit, a component per step of the verifier
+    pub fn verifier_cost(&self, reduction: LayerReduction, inputs: &InputOpening) -> CostReport {
+        let fb = field_bytes::<F>();
+        let circuit_len = self.layers.len();
+        let (output_bits, _) = self.layer_bits(circuit_len);
+
+        // the statement, W₀ and r_a, then W₀(r_a)
+        let input_len = self.inputs.len() as u64;
+        let statement = match inputs {
+            InputOpening::Public => VerifierCost::absorb(8 + input_len * fb + 8),
+            _ => VerifierCost::absorb(8 + 8),
+        } + VerifierCost::absorb(self.output_len() as u64 * fb)
+            + VerifierCost::squeezes(output_bits as u64)
+            + fix_vars(output_bits, output_bits);
+
+        let mut sum_checks = VerifierCost::default();
+        let mut wiring = VerifierCost::default();
+        let mut layer_checks = VerifierCost::default();
+
+        for i in 0..circuit_len {
+            let (out_bits, in_bits) = self.layer_bits(circuit_len - i);
+            let vars = out_bits + 2 * in_bits;
+            sum_checks += sum_check_cost(2 * in_bits as usize, LAYER_DEGREE, fb);
+
+            // addᵢ and mulᵢ at the layer's point, fixed by the reduction of the layer above
+            wiring += match (i, reduction) {
+                (0, _) | (_, LayerReduction::Line) => fix_vars(vars, out_bits).times(2),
+                (_, LayerReduction::AlphaBeta) => {
+                    // r_b and r_c each fixed into both tables, then alpha·b + beta·c
+                    let combined = 1u64 << (2 * in_bits);
+                    fix_vars(vars, out_bits).times(4)
+                        + VerifierCost::field(2 * combined, 4 * combined)
+                }
+            } + evaluation_table(2 * in_bits, 2);
+
+            // add·(W(r_b) + W(r_c)) + mul·W(r_b)·W(r_c)
+            layer_checks += VerifierCost::absorb(2 * fb) + VerifierCost::field(2, 3);
+            if i + 1 < circuit_len {
+                let line_len = in_bits as u64 + 1;
+                layer_checks += match reduction {
+                    LayerReduction::AlphaBeta => {
+                        VerifierCost::squeezes(2) + VerifierCost::field(1, 2)
+                    }
+                    // q(0), q(1) and q(r*) by Horner, r* from the GKR transcript, the line point
+                    LayerReduction::Line => {
+                        VerifierCost::field(3 * line_len, 3 * line_len)
+                            + VerifierCost::absorb((2 * in_bits as u64 + line_len) * fb)
+                            + VerifierCost::squeezes(1)
+                            + VerifierCost::field(2 * in_bits as u64, in_bits as u64)
+                    }
+                };
+            }
+        }
+
+        let (_, input_bits) = self.layer_bits(1);
+        let input_layer = VerifierCost::absorb(2 * fb)
+            + match inputs {
+                InputOpening::Public => fix_vars(input_bits, input_bits).times(2),
+                InputOpening::Kzg => kzg_opening_cost(input_bits as usize).times(2),
+                InputOpening::Fri(config) => fri_opening_cost(1 << input_bits, config, fb).times(2),
+            };
+
+        CostReport {
+            components: vec![
+                ("statement", statement),
+                ("sum-checks", sum_checks),
+                ("wiring", wiring),
+                ("layer checks", layer_checks),
+                ("input layer", input_layer),
+            ],
+        }
+    }
+}
+
+#[cfg(all(test, feature = "prover", feature = "bn254"))]
+mod test {
+    use super::*;
+    use crate::gkr::test_circuits::circuit8;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn test_sum_check_cost_per_round() {
+        let one_round = sum_check_cost(1, 2, 32);
+        let ten_rounds = sum_check_cost(10, 2, 32);
+        assert_eq!(one_round.hash_calls, 1);
+        assert_eq!(ten_rounds.hash_calls, 10);
+        assert_eq!(ten_rounds.field_invs, 10);
+        assert_eq!(
+            ten_rounds.field_muls,
+            10 * one_round.field_muls,
+            "only the transcript start is shared"
+        );
+    }
+
+    #[test]
+    fn test_gkr_cost_backends() {
+        let circuit = circuit8::<Fr>();
+        let public = circuit.verifier_cost(LayerReduction::AlphaBeta, &InputOpening::Public);
+        let kzg = circuit.verifier_cost(LayerReduction::AlphaBeta, &InputOpening::Kzg);
+        let fri = circuit.verifier_cost(
+            LayerReduction::AlphaBeta,
+            &InputOpening::Fri(LowDegreeConfig::default()),
+        );
+
+        // one sum-check round per variable of every layer
+        let rounds: u32 = (1..=circuit.layers.len())
+            .map(|layer| 2 * circuit.layer_bits(layer).1)
+            .sum();
+        assert_eq!(public.get("sum-checks").unwrap().hash_calls, rounds as u64);
+
+        // only the input layer differs, KZG pays in pairings and FRI in hashes
+        assert_eq!(public.get("wiring"), kzg.get("wiring"));
+        assert_eq!(public.total().pairings, 0);
+        let (_, input_bits) = circuit.layer_bits(1);
+        assert_eq!(kzg.total().pairings, 2 * (input_bits as u64 + 1));
+        assert_eq!(fri.total().pairings, 0);
+        assert!(
+            fri.get("input layer").unwrap().hash_calls > kzg.get("input layer").unwrap().hash_calls
+        );
+
+        assert!(public
+            .to_string()
+            .lines()
+            .last()
+            .unwrap()
+            .starts_with("total"));
+    }
+}
@@ -1,2 +1,3 @@
 pub mod dense;
+pub mod field;
 pub mod sparse;
\ No newline at end of file
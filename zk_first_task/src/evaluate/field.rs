@@ -0,0 +1,21 @@
+use ark_ff::Field;
+
+// same representations as dense_repr and sparse_repr but over a finite field, where the values
+// wrap around the modulus instead of overflowing and nothing is rounded
+
+pub fn dense_repr<F: Field>(x: F, dense_array: &[F]) -> F {
+    // Horner's rule: a_0 + x(a_1 + x(a_2 + ... + x(a_n))), one multiplication per coefficient
+    // and no powers of x, starting from the highest coefficient
+    dense_array
+        .iter()
+        .rev()
+        .fold(F::zero(), |result, coefficient| result * x + coefficient)
+}
+
+// (coefficient, power) pairs, the powers don't have to be in order
+pub fn sparse_repr<F: Field>(x: F, sparse_array: &[(F, u64)]) -> F {
+    sparse_array
+        .iter()
+        .map(|(coefficient, power)| *coefficient * x.pow([*power]))
+        .sum()
+}
@@ -1,3 +1,4 @@
 pub mod get_dense;
 pub mod get_sparse;
-pub mod expansion;
\ No newline at end of file
+pub mod expansion;
+pub mod field;
\ No newline at end of file
@@ -0,0 +1,65 @@
+use ark_ff::Field;
+
+/*
+    Lagrange interpolation over a finite field, the same steps as interpolate_dense and
+    interpolate_sparse but every division is an exact multiplication by an inverse
+    so the coefficients come out exact: no rounding error and no 1e-10 cut-off for zeros,
+    which is what a cryptographic use (e.g. Shamir's secret sharing) needs
+*/
+
+// same expansion as expansion::multiply_poly with field coefficients
+pub fn multiply_poly<F: Field>(p1: &[F], p2: &[F]) -> Vec<F> {
+    let mut result = vec![F::zero(); p1.len() + p2.len() - 1];
+
+    for (i, a) in p1.iter().enumerate() {
+        for (j, b) in p2.iter().enumerate() {
+            result[i + j] += *a * b;
+        }
+    }
+    result
+}
+
+// coefficients from the lowest power up, one per point
+pub fn interpolate_dense<F: Field>(points: &[(F, F)]) -> Vec<F> {
+    let n = points.len();
+    let mut final_result = vec![F::zero(); n];
+
+    // For each point
+    for (i, (x_i, y_i)) in points.iter().enumerate() {
+        let mut numerator = vec![F::one()]; // Start with 1
+        let mut denominator = F::one();
+
+        // Build the Lagrange basis polynomial
+        for (j, (x_j, _)) in points.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+
+            // Create (x - x_j) term
+            numerator = multiply_poly(&numerator, &[-*x_j, F::one()]);
+            denominator *= *x_i - x_j;
+        }
+
+        // Scale each coefficient by y_i/denominator, a repeated x makes the denominator 0
+        let scale = *y_i
+            * denominator
+                .inverse()
+                .expect("The x values of the points must be distinct");
+        for (k, coefficient) in numerator.iter().enumerate() {
+            final_result[k] += *coefficient * scale;
+        }
+    }
+
+    final_result
+}
+
+// the non-zero (coefficient, power) pairs, highest power first like interpolate_sparse
+pub fn interpolate_sparse<F: Field>(points: &[(F, F)]) -> Vec<(F, u64)> {
+    interpolate_dense(points)
+        .into_iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, coefficient)| !coefficient.is_zero())
+        .map(|(power, coefficient)| (coefficient, power as u64))
+        .collect()
+}
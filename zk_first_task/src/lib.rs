@@ -47,4 +47,132 @@ mod tests {
         let result = get_sparse::interpolate::interpolate_sparse(points);
         assert_eq!(result, vec![(2.0, 3.0), (5.0, 0.0)])
     }
+
+    // the field versions against the f64/u32 ones on polynomials both can represent exactly:
+    // small integer coefficients, evaluated and interpolated at x = 0..n
+    mod field {
+        use crate::evaluate::{dense, field as field_eval, sparse};
+        use crate::interpolate::field as field_interpolate;
+        use crate::interpolate::get_dense::interpolate;
+        use crate::interpolate::get_sparse;
+        use ark_bn254::Fr;
+        use ark_ff::Field;
+
+        // deterministic pseudo random coefficients in 0..10, every third one zero for the sparse form
+        fn coefficients(seed: u64, len: usize) -> Vec<u32> {
+            let mut state = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (0..len)
+                .map(|i| {
+                    state = state
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    if i % 3 == 2 {
+                        0
+                    } else {
+                        ((state >> 33) % 10) as u32
+                    }
+                })
+                .collect()
+        }
+
+        fn to_field(values: &[u32]) -> Vec<Fr> {
+            values.iter().map(|value| Fr::from(*value)).collect()
+        }
+
+        #[test]
+        fn test_field_evaluate_matches_u32() {
+            for seed in 0..50 {
+                let poly = coefficients(seed, 1 + seed as usize % 6);
+                let sparse_poly: Vec<(u32, u32)> = poly
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| **c != 0)
+                    .map(|(power, c)| (*c, power as u32))
+                    .collect();
+                let field_sparse: Vec<(Fr, u64)> = sparse_poly
+                    .iter()
+                    .map(|(c, power)| (Fr::from(*c), *power as u64))
+                    .collect();
+
+                for x in 0..5 {
+                    let expected = Fr::from(dense::dense_repr(x, poly.clone()));
+                    assert_eq!(
+                        field_eval::dense_repr(Fr::from(x), &to_field(&poly)),
+                        expected
+                    );
+                    assert_eq!(
+                        sparse::sparse_repr(x, sparse_poly.clone()),
+                        dense::dense_repr(x, poly.clone())
+                    );
+                    assert_eq!(
+                        field_eval::sparse_repr(Fr::from(x), &field_sparse),
+                        expected
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn test_field_interpolate_matches_f64() {
+            for seed in 0..50 {
+                let poly = coefficients(seed, 1 + seed as usize % 6);
+                let points: Vec<(f64, f64)> = (0..poly.len() as u32)
+                    .map(|x| (x as f64, dense::dense_repr(x, poly.clone()) as f64))
+                    .collect();
+                let field_points: Vec<(Fr, Fr)> = points
+                    .iter()
+                    .map(|(x, y)| (Fr::from(*x as u64), Fr::from(*y as u64)))
+                    .collect();
+
+                // the f64 coefficients are only close, the field ones are exact
+                let dense_f64 = interpolate::interpolate_dense(points.clone());
+                let rounded: Vec<u32> = dense_f64.iter().map(|c| c.round() as u32).collect();
+                assert_eq!(rounded, poly);
+                assert_eq!(
+                    field_interpolate::interpolate_dense(&field_points),
+                    to_field(&poly)
+                );
+
+                let sparse_f64: Vec<(Fr, u64)> =
+                    get_sparse::interpolate::interpolate_sparse(points)
+                        .iter()
+                        .map(|(c, power)| (Fr::from(c.round() as u64), *power as u64))
+                        .collect();
+                assert_eq!(
+                    field_interpolate::interpolate_sparse(&field_points),
+                    sparse_f64
+                );
+            }
+        }
+
+        #[test]
+        fn test_field_interpolate_is_exact_where_f64_is_not() {
+            // 1/3 has no exact f64, in the field 3 * (1/3) is 1 again
+            let third = Fr::from(3u64).inverse().unwrap();
+            let points = [
+                (Fr::from(0u64), Fr::from(0u64)),
+                (Fr::from(3u64), Fr::from(1u64)),
+            ];
+            assert_eq!(
+                field_interpolate::interpolate_dense(&points),
+                vec![Fr::from(0u64), third]
+            );
+            assert_eq!(
+                field_interpolate::interpolate_sparse(&points),
+                vec![(third, 1)]
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "The x values of the points must be distinct")]
+        fn test_field_interpolate_repeated_x() {
+            let points = [
+                (Fr::from(1u64), Fr::from(2u64)),
+                (Fr::from(1u64), Fr::from(3u64)),
+            ];
+            field_interpolate::interpolate_dense(&points);
+        }
+    }
 }